@@ -149,7 +149,7 @@ func main() {
 		fmt.Println("Starting QUIC Bottom for real-time metrics visualization...")
 		go func() {
 			// Запускаем QUIC Bottom в фоновом режиме
-			cmd := exec.Command("./quic-bottom/target/release/quic-bottom-real")
+			cmd := exec.Command("./quic-bottom/target/release/quic-bottom", "live")
 			cmd.Dir = "."
 			if err := cmd.Run(); err != nil {
 				fmt.Printf("❌ Failed to start QUIC Bottom: %v\n", err)
@@ -0,0 +1,104 @@
+//! Per-frame cost of reading the time series by copy and in place
+//!
+//! Fills three sources to history capacity and reads every series once per
+//! frame, the way the latency overlay does, through the copying accessors and
+//! through the borrowing ones. Run with `cargo bench --bench time_series`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+use quic_bottom::metrics::{MetricsHandle, QUICMetrics, TimeSeriesData};
+
+const SOURCES: [&str; 3] = ["client-a", "client-b", "client-c"];
+const SAMPLES: i64 = 1000;
+const FRAMES: u32 = 2000;
+
+/// Counts allocations so the copies show up next to the timings
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn filled() -> MetricsHandle {
+    let handle = MetricsHandle::new();
+    for i in 0..SAMPLES {
+        for (n, source) in SOURCES.iter().enumerate() {
+            let sample = QUICMetrics {
+                latency: 10.0 + n as f64 + (i % 17) as f64,
+                throughput: 1e8,
+                rtt: 12.0,
+                timestamp: Utc.timestamp_millis_opt(i * 100).unwrap(),
+                source: Some(source.to_string()),
+                ..Default::default()
+            };
+            handle.update(sample).expect("valid sample");
+        }
+    }
+    handle
+}
+
+fn mean_latency(series: &TimeSeriesData) -> f64 {
+    series.latency.iter().sum::<f64>() / series.latency.len().max(1) as f64
+}
+
+/// Time `frame` over `FRAMES` calls; returns the time and allocations per frame
+fn measure(mut frame: impl FnMut() -> f64) -> (Duration, f64) {
+    black_box(frame());
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        black_box(frame());
+    }
+    let elapsed = start.elapsed() / FRAMES;
+    let allocated = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    (elapsed, allocated as f64 / f64::from(FRAMES))
+}
+
+fn main() {
+    let handle = filled();
+
+    let copied = measure(|| {
+        let mut total = mean_latency(&handle.time_series());
+        for source in SOURCES {
+            let series = handle.source_time_series(source).expect("source is tracked");
+            total += mean_latency(&series);
+        }
+        total
+    });
+    let borrowed = measure(|| {
+        let mut total = handle.with_time_series(mean_latency);
+        total += handle.with_source_time_series(|sources| {
+            sources.iter().map(|(_, series)| mean_latency(series)).sum::<f64>()
+        });
+        total
+    });
+
+    println!(
+        "{} sources x {} samples, {} frames",
+        SOURCES.len(),
+        SAMPLES,
+        FRAMES
+    );
+    println!("copied:   {:>10.2?} per frame, {:>6.1} allocations", copied.0, copied.1);
+    println!("borrowed: {:>10.2?} per frame, {:>6.1} allocations", borrowed.0, borrowed.1);
+    assert!(
+        borrowed.1 < copied.1,
+        "borrowing should allocate less than copying"
+    );
+}
@@ -0,0 +1,26 @@
+//! Generates include/quic_bottom.h for the C API in src/ffi.rs
+
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    let crate_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let generated = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .map_err(|e| e.to_string())
+        .and_then(|config| {
+            cbindgen::Builder::new()
+                .with_config(config)
+                .with_src(crate_dir.join("src/ffi.rs"))
+                .generate()
+                .map_err(|e| e.to_string())
+        });
+    match generated {
+        Ok(bindings) => {
+            bindings.write_to_file(crate_dir.join("include/quic_bottom.h"));
+        }
+        // A missing header should not break the Rust build
+        Err(e) => println!("cargo:warning=failed to generate include/quic_bottom.h: {}", e),
+    }
+}
@@ -0,0 +1,312 @@
+#ifndef QUIC_BOTTOM_H
+#define QUIC_BOTTOM_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct QUICMetrics QUICMetrics;
+
+/**
+ * Success
+ */
+#define QUIC_BOTTOM_OK 0
+
+/**
+ * A handle or pointer argument was null
+ */
+#define QUIC_BOTTOM_ERR_NULL -1
+
+/**
+ * The sample was refused: a negative, non-finite or implausibly large value,
+ * a count beyond `i32`, or a timestamp out of range
+ */
+#define QUIC_BOTTOM_ERR_INVALID -2
+
+/**
+ * quic-bottom failed internally; the handle should not be used further
+ */
+#define QUIC_BOTTOM_ERR_INTERNAL -3
+
+/**
+ * Opaque metrics state owned by the caller
+ */
+typedef struct QuicBottomHandle QuicBottomHandle;
+
+/**
+ * One sample as passed across the C API
+ */
+typedef struct CQuicMetrics {
+  double latency;
+  /**
+   * Bits per second
+   */
+  double throughput;
+  int64_t connections;
+  int64_t errors;
+  /**
+   * Fraction of packets lost, 0.0-1.0
+   */
+  double packet_loss;
+  int64_t retransmits;
+  /**
+   * Capture time in unix milliseconds; 0 stamps the sample on arrival
+   */
+  int64_t timestamp_ms;
+} CQuicMetrics;
+
+/**
+ * `CQuicMetrics` with the fields added in version 2, for the `_v2` functions
+ */
+typedef struct CQuicMetricsV2 {
+  double latency;
+  /**
+   * Bits per second
+   */
+  double throughput;
+  int64_t connections;
+  int64_t errors;
+  /**
+   * Fraction of packets lost, 0.0-1.0
+   */
+  double packet_loss;
+  int64_t retransmits;
+  /**
+   * Capture time in unix milliseconds; 0 stamps the sample on arrival
+   */
+  int64_t timestamp_ms;
+  /**
+   * Smoothed round-trip time in ms
+   */
+  double rtt;
+  /**
+   * RTT variation in ms
+   */
+  double jitter;
+  /**
+   * Congestion window in bytes
+   */
+  int64_t congestion_window;
+  int64_t bytes_sent;
+  int64_t bytes_received;
+  int64_t streams;
+  /**
+   * Handshake duration in ms
+   */
+  double handshake_time;
+} CQuicMetricsV2;
+
+/**
+ * Create a handle with empty metrics state; release it with `quic_bottom_free`
+ */
+struct QuicBottomHandle *quic_bottom_new(void);
+
+/**
+ * Release a handle; null is ignored
+ *
+ * # Safety
+ *
+ * `handle` must be null or come from `quic_bottom_new` and not have been freed.
+ */
+void quic_bottom_free(struct QuicBottomHandle *handle);
+
+/**
+ * Store a sample
+ *
+ * # Safety
+ *
+ * `handle` must be null or a live handle from `quic_bottom_new`; `metrics`
+ * must be null or point to a readable `CQuicMetrics`.
+ */
+int32_t quic_bottom_update(const struct QuicBottomHandle *handle,
+                           const struct CQuicMetrics *metrics);
+
+/**
+ * `quic_bottom_update` for a `CQuicMetricsV2`
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_update`.
+ */
+int32_t quic_bottom_update_v2(const struct QuicBottomHandle *handle,
+                              const struct CQuicMetricsV2 *metrics);
+
+/**
+ * Write the last sample to `out`; all zeros before the first one
+ *
+ * # Safety
+ *
+ * `handle` must be null or a live handle from `quic_bottom_new`; `out` must
+ * be null or point to writable memory for a `CQuicMetrics`.
+ */
+int32_t quic_bottom_get_current(const struct QuicBottomHandle *handle, struct CQuicMetrics *out);
+
+/**
+ * `quic_bottom_get_current` for a `CQuicMetricsV2`
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_get_current`.
+ */
+int32_t quic_bottom_get_current_v2(const struct QuicBottomHandle *handle,
+                                   struct CQuicMetricsV2 *out);
+
+/**
+ * Store `len` samples in order
+ *
+ * The whole batch is checked before anything is stored: if any sample is
+ * invalid, none is stored and `QUIC_BOTTOM_ERR_INVALID` is returned.
+ *
+ * # Safety
+ *
+ * `handle` must be null or a live handle from `quic_bottom_new`; `samples`
+ * must point to `len` readable `CQuicMetrics` (it may be null when `len` is 0).
+ */
+int32_t quic_bottom_update_batch(const struct QuicBottomHandle *handle,
+                                 const struct CQuicMetrics *samples,
+                                 size_t len);
+
+/**
+ * `quic_bottom_update_batch` for `CQuicMetricsV2` samples
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_update_batch`.
+ */
+int32_t quic_bottom_update_batch_v2(const struct QuicBottomHandle *handle,
+                                    const struct CQuicMetricsV2 *samples,
+                                    size_t len);
+
+/**
+ * Copy the stored history, oldest first, into `out_buf`
+ *
+ * Returns the number of samples available, or a negative `QUIC_BOTTOM_*`
+ * code. When more are available than `cap`, the newest `cap` are copied;
+ * `*written` says how many. Pass a null `out_buf` and a `cap` of 0 to
+ * only ask for the count.
+ *
+ * # Safety
+ *
+ * `handle` must be null or a live handle from `quic_bottom_new`; `out_buf`
+ * must point to writable memory for `cap` `CQuicMetrics` (it may be null
+ * when `cap` is 0); `written` must be null or writable.
+ */
+int32_t quic_bottom_get_time_series(const struct QuicBottomHandle *handle,
+                                    struct CQuicMetrics *out_buf,
+                                    size_t cap,
+                                    size_t *written);
+
+/**
+ * `quic_bottom_get_time_series` for `CQuicMetricsV2` samples
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_get_time_series`.
+ */
+int32_t quic_bottom_get_time_series_v2(const struct QuicBottomHandle *handle,
+                                       struct CQuicMetricsV2 *out_buf,
+                                       size_t cap,
+                                       size_t *written);
+
+/**
+ * `quic_bottom_update_batch` on the global metrics, as shown by `start_quic_bottom`
+ *
+ * # Safety
+ *
+ * `samples` must point to `len` readable `CQuicMetrics` (it may be null
+ * when `len` is 0).
+ */
+int32_t update_quic_metrics_batch(const struct CQuicMetrics *samples, size_t len);
+
+/**
+ * `update_quic_metrics_batch` for `CQuicMetricsV2` samples
+ *
+ * # Safety
+ *
+ * As for `update_quic_metrics_batch`.
+ */
+int32_t update_quic_metrics_batch_v2(const struct CQuicMetricsV2 *samples, size_t len);
+
+/**
+ * `quic_bottom_get_time_series` on the global metrics
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_get_time_series`.
+ */
+int32_t get_quic_time_series(struct CQuicMetrics *out_buf, size_t cap, size_t *written);
+
+/**
+ * `get_quic_time_series` for `CQuicMetricsV2` samples
+ *
+ * # Safety
+ *
+ * As for `quic_bottom_get_time_series`.
+ */
+int32_t get_quic_time_series_v2(struct CQuicMetricsV2 *out_buf, size_t cap, size_t *written);
+
+/**
+ * FFI function to update QUIC metrics from Go
+ *
+ * Updates the global metrics and does nothing before `init_metrics`.
+ * `throughput` is in bits per second and `packet_loss` a fraction, 0.0-1.0.
+ * Returns -1 for a negative or non-finite value.
+ */
+__attribute__((deprecated("use quic_bottom_update with a handle from quic_bottom_new")))
+int32_t update_quic_metrics(double latency,
+                            double throughput,
+                            int32_t connections,
+                            int32_t errors,
+                            double packet_loss,
+                            int32_t retransmits);
+
+/**
+ * FFI function to get current metrics
+ *
+ * The returned struct is not C-compatible; null before `init_metrics`.
+ */
+__attribute__((deprecated("use quic_bottom_get_current, which fills a CQuicMetrics")))
+QUICMetrics *get_quic_metrics(void);
+
+/**
+ * FFI function to free metrics memory
+ *
+ * # Safety
+ *
+ * `ptr` must be null or come from `get_quic_metrics` and not have been freed.
+ */
+__attribute__((deprecated("only needed for get_quic_metrics")))
+void free_quic_metrics(QUICMetrics *ptr);
+
+/**
+ * Same as `update_quic_metrics`
+ */
+__attribute__((deprecated("use quic_bottom_update with a handle from quic_bottom_new")))
+int32_t update_quic_metrics_ffi(double latency,
+                                double throughput,
+                                int32_t connections,
+                                int32_t errors,
+                                double packet_loss,
+                                int32_t retransmits);
+
+/**
+ * Same as `get_quic_metrics`
+ */
+__attribute__((deprecated("use quic_bottom_get_current, which fills a CQuicMetrics")))
+QUICMetrics *get_quic_metrics_ffi(void);
+
+/**
+ * Same as `free_quic_metrics`
+ *
+ * # Safety
+ *
+ * As for `free_quic_metrics`.
+ */
+__attribute__((deprecated("only needed for get_quic_metrics_ffi")))
+void free_quic_metrics_ffi(QUICMetrics *ptr);
+
+#endif /* QUIC_BOTTOM_H */
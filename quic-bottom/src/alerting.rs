@@ -0,0 +1,239 @@
+//! Alerting on detected anomalies
+//!
+//! Forwards anomalies at or above a configured severity to a webhook and/or
+//! a shell command, so nobody has to watch the terminal to notice them.
+
+use anyhow::{anyhow, Result};
+use hyper::{client::HttpConnector, Body, Client, Method, Request, Uri};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::anomaly_detection::{AnomalyResult, AnomalySeverity};
+use crate::status::record_lagged;
+
+/// Alerting configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertingConfig {
+    /// Lowest severity that triggers an alert
+    pub min_severity: AnomalySeverity,
+
+    /// URL the JSON alert payload is POSTed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+
+    /// Shell command run per alert, with the fields in `QUIC_ALERT_*` env vars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Minimum seconds between two alerts for the same metric
+    pub rate_limit_secs: u64,
+
+    /// Webhook attempts after the first one fails
+    pub webhook_retries: u32,
+
+    /// Delay before the first webhook retry, doubled on each further attempt
+    pub retry_backoff_ms: u64,
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            min_severity: AnomalySeverity::Critical,
+            webhook_url: None,
+            command: None,
+            rate_limit_secs: 300,
+            webhook_retries: 3,
+            retry_backoff_ms: 1000,
+        }
+    }
+}
+
+impl AlertingConfig {
+    /// Whether any alert target is configured
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.command.is_some()
+    }
+}
+
+/// Number of alerts sent and held back by rate limiting
+#[derive(Debug, Default)]
+pub struct AlertCounters {
+    fired: AtomicU64,
+    suppressed: AtomicU64,
+}
+
+impl AlertCounters {
+    pub fn fired(&self) -> u64 {
+        self.fired.load(Ordering::Relaxed)
+    }
+
+    pub fn suppressed(&self) -> u64 {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+}
+
+/// Sends alerts for anomalies received from an `AnomalyDetector`
+pub struct Alerter {
+    config: AlertingConfig,
+    webhook: Option<Uri>,
+    client: Client<HttpConnector>,
+    last_fired: HashMap<String, Instant>,
+    counters: Arc<AlertCounters>,
+}
+
+impl Alerter {
+    pub fn new(config: AlertingConfig) -> Result<Self> {
+        let webhook = config
+            .webhook_url
+            .as_deref()
+            .map(|url| {
+                url.parse::<Uri>()
+                    .map_err(|e| anyhow!("invalid alerting webhook_url '{}': {}", url, e))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            config,
+            webhook,
+            client: Client::new(),
+            last_fired: HashMap::new(),
+            counters: Arc::new(AlertCounters::default()),
+        })
+    }
+
+    /// Counters shared with whoever displays them
+    pub fn counters(&self) -> Arc<AlertCounters> {
+        Arc::clone(&self.counters)
+    }
+
+    /// Alert on every anomaly received from `anomalies` until the sender is dropped
+    pub fn spawn(mut self, mut anomalies: broadcast::Receiver<AnomalyResult>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match anomalies.recv().await {
+                    Ok(anomaly) => self.handle(anomaly),
+                    Err(RecvError::Lagged(skipped)) => {
+                        record_lagged(skipped);
+                        log::warn!("alerting fell behind, {} anomalies were not evaluated", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    /// Send an alert for `anomaly` unless it is below the threshold or rate limited
+    pub fn handle(&mut self, anomaly: AnomalyResult) {
+        if anomaly.severity < self.config.min_severity {
+            return;
+        }
+
+        let now = Instant::now();
+        let window = Duration::from_secs(self.config.rate_limit_secs);
+        if let Some(last) = self.last_fired.get(&anomaly.metric) {
+            if now.duration_since(*last) < window {
+                self.counters.suppressed.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        }
+        self.last_fired.insert(anomaly.metric.clone(), now);
+        self.counters.fired.fetch_add(1, Ordering::Relaxed);
+
+        if let Some(uri) = &self.webhook {
+            let client = self.client.clone();
+            let uri = uri.clone();
+            let anomaly = anomaly.clone();
+            let retries = self.config.webhook_retries;
+            let backoff = Duration::from_millis(self.config.retry_backoff_ms);
+            tokio::spawn(async move {
+                if let Err(e) = post_with_retries(&client, &uri, &anomaly, retries, backoff).await {
+                    log::error!("alert webhook for {} failed: {}", anomaly.metric, e);
+                }
+            });
+        }
+
+        if let Some(command) = &self.config.command {
+            let command = command.clone();
+            tokio::spawn(async move {
+                if let Err(e) = run_command(&command, &anomaly).await {
+                    log::error!("alert command for {} failed: {}", anomaly.metric, e);
+                }
+            });
+        }
+    }
+}
+
+async fn post_with_retries(
+    client: &Client<HttpConnector>,
+    uri: &Uri,
+    anomaly: &AnomalyResult,
+    retries: u32,
+    backoff: Duration,
+) -> Result<()> {
+    let body = serde_json::to_vec(anomaly)?;
+    let mut delay = backoff;
+    let mut attempt = 0;
+    loop {
+        match post_alert(client, uri, &body).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < retries => {
+                log::warn!("alert webhook attempt {} failed: {}, retrying in {:?}", attempt + 1, e, delay);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn post_alert(client: &Client<HttpConnector>, uri: &Uri, body: &[u8]) -> Result<()> {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(uri.clone())
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_vec()))?;
+
+    let response = client.request(request).await?;
+    let status = response.status();
+    let _ = hyper::body::to_bytes(response.into_body()).await?;
+
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!("webhook responded with {}", status))
+    }
+}
+
+async fn run_command(command: &str, anomaly: &AnomalyResult) -> Result<()> {
+    // Output is discarded so the command cannot draw over the TUI
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("QUIC_ALERT_METRIC", &anomaly.metric)
+        .env("QUIC_ALERT_VALUE", anomaly.value.to_string())
+        .env("QUIC_ALERT_EXPECTED_MIN", anomaly.expected_range.0.to_string())
+        .env("QUIC_ALERT_EXPECTED_MAX", anomaly.expected_range.1.to_string())
+        .env("QUIC_ALERT_SEVERITY", anomaly.severity.get_description())
+        .env("QUIC_ALERT_STRATEGY", anomaly.strategy.name())
+        .env("QUIC_ALERT_TIMESTAMP", anomaly.timestamp.to_rfc3339())
+        .env("QUIC_ALERT_DESCRIPTION", &anomaly.description)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("exited with {}", status))
+    }
+}
@@ -1,29 +1,597 @@
 //! Anomaly detection for QUIC metrics
 //! 
-//! Automatically detects anomalies in performance data
+//! Automatically detects anomalies in performance data. Each metric runs
+//! one or more detection strategies over its recent history: a z-score of
+//! every sample, an EWMA control chart for slow drifts, and a rate-of-change
+//! check for sudden steps.
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Rect, Size},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::alerting::AlertCounters;
+use crate::focus::{border_style, title_style};
+use crate::improved_layout::{MinSize, Presentation};
+use crate::metrics::{finite_values, format_value, StreamingStats};
+use crate::theme::Theme;
+
+/// Sensitivity used when neither the detector nor the config sets one
+pub const DEFAULT_SENSITIVITY: f64 = 0.7;
+
+/// Samples needed before a metric is checked, unless configured otherwise
+pub const DEFAULT_MIN_SAMPLES: usize = 10;
+
+/// Seconds of samples needed before a metric is checked, unless configured otherwise
+pub const DEFAULT_WARMUP_SECS: f64 = 30.0;
+
+/// Metrics fed to `QUICAnomalyWidget`, as shown in the anomaly list
+pub const QUIC_ANOMALY_METRICS: &[&str] = &["Latency", "Throughput", "Packet Loss", "Connections", "Errors"];
+
+/// Samples kept per metric
+const HISTORY_LEN: usize = 100;
+
+/// EWMA weight of the newest sample, unless configured otherwise
+pub const DEFAULT_EWMA_LAMBDA: f64 = 0.2;
+
+/// EWMA control limits in standard deviations of the EWMA statistic
+pub const DEFAULT_EWMA_LIMIT: f64 = 3.0;
+
+/// Rate-of-change threshold as a multiple of the median absolute deviation of recent deltas
+pub const DEFAULT_ROC_K: f64 = 5.0;
+
+/// Recent deltas the rate-of-change threshold is taken from
+pub const DEFAULT_ROC_WINDOW: usize = 20;
+
+/// Seconds a metric stays quiet after an anomaly unless a more severe one comes
+pub const DEFAULT_COOLDOWN_SECS: f64 = 5.0;
+
+/// Seconds after a reset or network simulation change during which anomalies are only counted
+pub const DEFAULT_SUPPRESS_SECS: f64 = 10.0;
+
+/// Which deviations from the expected range are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyDirection {
+    /// Both spikes and drops
+    #[default]
+    Both,
+    /// Only values above the range, e.g. latency
+    High,
+    /// Only values below the range, e.g. throughput
+    Low,
+}
+
+/// Which statistics of the history the expected range is built from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnomalyStatistics {
+    /// Mean and standard deviation
+    #[default]
+    Standard,
+    /// Median and MAD scaled by 1.4826, which one extreme outlier barely moves
+    Robust,
+}
+
+/// Scales the median absolute deviation to the standard deviation of normal data
+pub const MAD_TO_STD_DEV: f64 = 1.4826;
+
+/// How a metric's samples are judged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// A sample far from the mean of the history
+    ZScore,
+    /// The exponentially weighted mean leaving its control limits, for slow drifts
+    Ewma,
+    /// A jump between consecutive samples far larger than the recent ones, for steps
+    RateOfChange,
+}
+
+impl StrategyKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            StrategyKind::ZScore => "z-score",
+            StrategyKind::Ewma => "EWMA",
+            StrategyKind::RateOfChange => "rate of change",
+        }
+    }
+}
+
+/// Detection settings for one metric; unset fields use the detector defaults
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricAnomalyConfig {
+    /// Statistics of the expected range, the `[anomaly] statistics` setting unless set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<AnomalyStatistics>,
+
+    /// Strategies run on the metric, `["z_score"]` unless set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategies: Option<Vec<StrategyKind>>,
+
+    /// EWMA weight of the newest sample (0.0 to 1.0]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ewma_lambda: Option<f64>,
+
+    /// EWMA control limits, in standard deviations of the EWMA statistic
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ewma_limit: Option<f64>,
+
+    /// Rate-of-change threshold, in median absolute deviations of recent deltas
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roc_k: Option<f64>,
+
+    /// Recent deltas the rate-of-change threshold is taken from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roc_window: Option<usize>,
+
+    /// Detection sensitivity (0.0 to 1.0), higher flags smaller deviations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensitivity: Option<f64>,
+
+    /// Samples needed before the metric is checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_samples: Option<usize>,
+
+    /// Seconds since the first sample before the metric is checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup_secs: Option<f64>,
+
+    /// Cool-down after an anomaly, the `[anomaly] cooldown_secs` setting unless set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_secs: Option<f64>,
+
+    /// Which deviations are reported
+    pub direction: AnomalyDirection,
+}
+
+/// `[anomaly]` section: settings for every metric, and per-metric ones keyed by metric name
+///
+/// Keys are matched case-insensitively with spaces as underscores, so
+/// `packet_loss` configures the "Packet Loss" metric.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// Statistics of the expected range for metrics without their own setting
+    #[serde(default)]
+    pub statistics: AnomalyStatistics,
+
+    /// Samples of the baseline for metrics without their own setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_samples: Option<usize>,
+
+    /// Duration of the baseline for metrics without their own setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup_secs: Option<f64>,
+
+    /// Cool-down after an anomaly for metrics without their own setting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_secs: Option<f64>,
+
+    /// Suppression window after a reset or network simulation change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppress_secs: Option<f64>,
+
+    #[serde(flatten)]
+    pub metrics: BTreeMap<String, MetricAnomalyConfig>,
+}
+
+impl AnomalyConfig {
+    /// Settings for `metric`, if it has an entry
+    pub fn get(&self, metric: &str) -> Option<&MetricAnomalyConfig> {
+        let key = metric_key(metric);
+        self.metrics
+            .iter()
+            .find(|(name, _)| metric_key(name) == key)
+            .map(|(_, config)| config)
+    }
+
+    /// How long resets and network simulation changes silence the detector
+    pub fn suppression(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(self.suppress_secs.unwrap_or(DEFAULT_SUPPRESS_SECS))
+    }
+
+    /// Check that warm-ups, cool-downs and the suppression window are durations
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let metrics = self.metrics.iter().flat_map(|(name, config)| {
+            [
+                (format!("anomaly.{}.warmup_secs", name), config.warmup_secs),
+                (format!("anomaly.{}.cooldown_secs", name), config.cooldown_secs),
+            ]
+        });
+        let settings = [
+            ("anomaly.warmup_secs".to_string(), self.warmup_secs),
+            ("anomaly.cooldown_secs".to_string(), self.cooldown_secs),
+            ("anomaly.suppress_secs".to_string(), self.suppress_secs),
+        ];
+        for (key, secs) in settings.into_iter().chain(metrics) {
+            if let Some(secs) = secs {
+                if !(secs.is_finite() && secs >= 0.0) {
+                    anyhow::bail!("{} must be a number of seconds >= 0, got {}", key, secs);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Configured names that match none of `known`
+    pub fn unknown_metrics(&self, known: &[&str]) -> Vec<String> {
+        self.metrics
+            .keys()
+            .filter(|name| !known.iter().any(|k| metric_key(k) == metric_key(name)))
+            .cloned()
+            .collect()
+    }
+}
+
+fn metric_key(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Settings applied to one metric after defaults are filled in
+#[derive(Debug, Clone, PartialEq)]
+struct ResolvedSettings {
+    sensitivity: f64,
+    statistics: AnomalyStatistics,
+    min_samples: usize,
+    /// Seconds since the first sample before detection starts
+    warmup_secs: f64,
+    direction: AnomalyDirection,
+    strategies: Vec<StrategyKind>,
+    ewma_lambda: f64,
+    ewma_limit: f64,
+    roc_k: f64,
+    roc_window: usize,
+    /// Seconds after an anomaly during which only a more severe one is reported
+    cooldown_secs: f64,
+}
+
+impl ResolvedSettings {
+    /// Z-score a value must exceed to be flagged, 2.0 to 4.0 based on sensitivity
+    fn threshold(&self) -> f64 {
+        2.0 + (1.0 - self.sensitivity) * 2.0
+    }
+
+    /// A fresh instance of every configured strategy
+    fn build_strategies(&self) -> Vec<Box<dyn DetectionStrategy>> {
+        self.strategies
+            .iter()
+            .map(|kind| -> Box<dyn DetectionStrategy> {
+                match kind {
+                    StrategyKind::ZScore => Box::new(ZScore {
+                        threshold: self.threshold(),
+                        statistics: self.statistics,
+                    }),
+                    StrategyKind::Ewma => Box::new(Ewma::new(self.ewma_lambda, self.ewma_limit, self.statistics)),
+                    StrategyKind::RateOfChange => Box::new(RateOfChange {
+                        k: self.roc_k,
+                        window: self.roc_window,
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ResolvedSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let strategies: Vec<String> = self.build_strategies().iter().map(|s| s.describe()).collect();
+        write!(f, "{}, min {}", strategies.join(" + "), self.min_samples)?;
+        if self.warmup_secs > 0.0 {
+            write!(f, "/{}s", self.warmup_secs)?;
+        }
+        match self.direction {
+            AnomalyDirection::Both => Ok(()),
+            AnomalyDirection::High => write!(f, ", high only"),
+            AnomalyDirection::Low => write!(f, ", low only"),
+        }
+    }
+}
 
 /// Anomaly detection result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AnomalyResult {
+    /// Unique within the detector, in detection order
+    pub id: u64,
     pub metric: String,
     pub value: f64,
     pub expected_range: (f64, f64),
     pub severity: AnomalySeverity,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub description: String,
+    /// Strategy that flagged the sample
+    pub strategy: StrategyKind,
+    /// Whether the user has seen and acknowledged it
+    pub acknowledged: bool,
+}
+
+/// Recent samples of one metric, the newest last
+pub struct SampleWindow<'a> {
+    pub values: &'a VecDeque<f64>,
+    /// Mean and variance of `values`
+    pub stats: &'a StreamingStats,
+}
+
+impl SampleWindow<'_> {
+    /// Center and spread of `values`: mean and standard deviation, or median and scaled MAD
+    pub fn center_and_spread(&self, statistics: AnomalyStatistics) -> (f64, f64) {
+        match statistics {
+            AnomalyStatistics::Standard => (self.stats.mean(), self.stats.std_dev()),
+            AnomalyStatistics::Robust => {
+                let values: Vec<f64> = self.values.iter().copied().collect();
+                let center = median(values.clone()).unwrap_or(0.0);
+                let mad = median(values.iter().map(|v| (v - center).abs()).collect()).unwrap_or(0.0);
+                (center, mad * MAD_TO_STD_DEV)
+            }
+        }
+    }
+}
+
+/// What a strategy found wrong with a sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// Range the judged quantity was expected in
+    pub expected_range: (f64, f64),
+    /// Whether the sample is above the expected range rather than below
+    pub high: bool,
+    pub severity: AnomalySeverity,
+    pub description: String,
+}
+
+/// One way of deciding whether a sample is anomalous
+///
+/// Every metric gets its own instance of each configured strategy, which
+/// may keep state between samples. `check` sees the window with the new
+/// sample already added.
+pub trait DetectionStrategy: Send {
+    fn kind(&self) -> StrategyKind;
+
+    /// Judge `value`, the newest sample of `window`
+    fn check(&mut self, value: f64, window: &SampleWindow) -> Option<Detection>;
+
+    /// Short summary of the settings, e.g. `z>2.6`
+    fn describe(&self) -> String;
+}
+
+/// Flags samples more than `threshold` standard deviations from the mean
+///
+/// With robust statistics, more than `threshold` scaled MADs from the median.
+pub struct ZScore {
+    pub threshold: f64,
+    pub statistics: AnomalyStatistics,
+}
+
+impl DetectionStrategy for ZScore {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::ZScore
+    }
+
+    fn check(&mut self, value: f64, window: &SampleWindow) -> Option<Detection> {
+        let (center, spread) = window.center_and_spread(self.statistics);
+        if spread <= f64::EPSILON || !spread.is_finite() {
+            return None; // Flat history: z-score is undefined
+        }
+
+        let z_score = (value - center) / spread;
+        if z_score.abs() <= self.threshold {
+            return None;
+        }
+        let expected_range = (center - 2.0 * spread, center + 2.0 * spread);
+        let description = match self.statistics {
+            AnomalyStatistics::Standard => format!(
+                "Z-score: {:.2}, Expected: {:.1}-{:.1}, Actual: {:.1}",
+                z_score, expected_range.0, expected_range.1, value
+            ),
+            AnomalyStatistics::Robust => format!(
+                "Robust z: {:.2} (median {:.1}, MAD {:.2}), Expected: {:.1}-{:.1}, Actual: {:.1}",
+                z_score,
+                center,
+                spread / MAD_TO_STD_DEV,
+                expected_range.0,
+                expected_range.1,
+                value
+            ),
+        };
+        Some(Detection {
+            expected_range,
+            high: z_score > 0.0,
+            severity: severity_of_z_score(z_score.abs()),
+            description,
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self.statistics {
+            AnomalyStatistics::Standard => format!("z>{:.1}", self.threshold),
+            AnomalyStatistics::Robust => format!("robust z>{:.1}", self.threshold),
+        }
+    }
+}
+
+/// EWMA control chart: flags the weighted mean leaving `limit` standard deviations around the history mean
+///
+/// Averaging out the noise lets a slow drift cross the limits long before
+/// any single sample looks unusual.
+pub struct Ewma {
+    pub lambda: f64,
+    pub limit: f64,
+    /// Statistics of the center line and the spread the limits scale
+    pub statistics: AnomalyStatistics,
+    /// Current weighted mean, `None` before the first sample
+    average: Option<f64>,
+}
+
+impl Ewma {
+    pub fn new(lambda: f64, limit: f64, statistics: AnomalyStatistics) -> Self {
+        Self {
+            lambda: lambda.clamp(0.01, 1.0),
+            limit,
+            statistics,
+            average: None,
+        }
+    }
+}
+
+impl DetectionStrategy for Ewma {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Ewma
+    }
+
+    fn check(&mut self, value: f64, window: &SampleWindow) -> Option<Detection> {
+        let average = match self.average {
+            Some(average) => self.lambda * value + (1.0 - self.lambda) * average,
+            None => value,
+        };
+        self.average = Some(average);
+
+        let (center, std_dev) = window.center_and_spread(self.statistics);
+        if std_dev <= f64::EPSILON || !std_dev.is_finite() {
+            return None;
+        }
+        // Control limits from the asymptotic standard deviation of the EWMA statistic
+        let half_width = self.limit * std_dev * (self.lambda / (2.0 - self.lambda)).sqrt();
+        let expected_range = (center - half_width, center + half_width);
+        let excess = (average - center).abs() / half_width;
+        if excess <= 1.0 {
+            return None;
+        }
+        Some(Detection {
+            expected_range,
+            high: average > center,
+            severity: severity_of_excess(excess),
+            description: format!(
+                "EWMA: {:.1} outside {:.1}-{:.1} (λ {:.2}, L {:.1}{})",
+                average,
+                expected_range.0,
+                expected_range.1,
+                self.lambda,
+                self.limit,
+                if self.statistics == AnomalyStatistics::Robust { ", median/MAD" } else { "" }
+            ),
+        })
+    }
+
+    fn describe(&self) -> String {
+        let robust = if self.statistics == AnomalyStatistics::Robust { " robust" } else { "" };
+        format!("EWMA λ{:.2} L{:.1}{}", self.lambda, self.limit, robust)
+    }
+}
+
+/// Flags a jump from the previous sample larger than `k` median absolute deviations of the last `window` jumps
+///
+/// Catches a step that a noisy history would hide from the z-score.
+pub struct RateOfChange {
+    pub k: f64,
+    pub window: usize,
+}
+
+impl DetectionStrategy for RateOfChange {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::RateOfChange
+    }
+
+    fn check(&mut self, value: f64, window: &SampleWindow) -> Option<Detection> {
+        // The window's last value is `value`; the deltas before it set the threshold
+        let values = window.values;
+        if values.len() < 3 {
+            return None;
+        }
+        let previous = values[values.len() - 2];
+        let first = values.len().saturating_sub(self.window + 2);
+        let deltas: Vec<f64> = values
+            .iter()
+            .skip(first)
+            .take(values.len() - 1 - first)
+            .zip(values.iter().skip(first + 1))
+            .map(|(a, b)| b - a)
+            .collect();
+        // Mostly equal deltas, as integer counters have, leave a zero MAD; the mean deviation still moves
+        let tolerance = 1e-9 * previous.abs().max(1.0);
+        let mad = median_absolute_deviation(&deltas)
+            .filter(|&mad| mad > tolerance)
+            .or_else(|| mean_absolute_deviation(&deltas))?;
+        if mad <= tolerance {
+            return None; // Steady deltas: any jump would count, so none does
+        }
+
+        let delta = value - previous;
+        let limit = self.k * mad;
+        let excess = delta.abs() / limit;
+        if excess <= 1.0 {
+            return None;
+        }
+        Some(Detection {
+            expected_range: (previous - limit, previous + limit),
+            high: delta > 0.0,
+            severity: severity_of_excess(excess),
+            description: format!(
+                "Step: {:+.1} from {:.1}, limit {:.1} ({}×MAD {:.2})",
+                delta, previous, limit, self.k, mad
+            ),
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("Δ>{}×MAD", self.k)
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Median absolute deviation from the median, `None` for no values
+fn median_absolute_deviation(values: &[f64]) -> Option<f64> {
+    let center = median(values.to_vec())?;
+    median(values.iter().map(|v| (v - center).abs()).collect())
 }
 
-/// Anomaly severity levels
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Mean absolute deviation from the median, `None` for no values
+fn mean_absolute_deviation(values: &[f64]) -> Option<f64> {
+    let center = median(values.to_vec())?;
+    Some(values.iter().map(|v| (v - center).abs()).sum::<f64>() / values.len() as f64)
+}
+
+/// Severity by z-score
+fn severity_of_z_score(z_score: f64) -> AnomalySeverity {
+    match z_score {
+        x if x >= 4.0 => AnomalySeverity::Critical,
+        x if x >= 3.0 => AnomalySeverity::High,
+        x if x >= 2.5 => AnomalySeverity::Medium,
+        _ => AnomalySeverity::Low,
+    }
+}
+
+/// Severity by how far past its limit a strategy's quantity went, as a multiple of the limit
+fn severity_of_excess(excess: f64) -> AnomalySeverity {
+    match excess {
+        x if x >= 2.0 => AnomalySeverity::Critical,
+        x if x >= 1.5 => AnomalySeverity::High,
+        x if x >= 1.25 => AnomalySeverity::Medium,
+        _ => AnomalySeverity::Low,
+    }
+}
+
+/// Anomaly severity levels, ordered from least to most severe
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AnomalySeverity {
     Low,
     Medium,
@@ -32,12 +600,13 @@ pub enum AnomalySeverity {
 }
 
 impl AnomalySeverity {
-    pub fn get_color(&self) -> Color {
+    /// Warning for low, up the top of the heat scale, error for critical
+    pub fn get_color(&self, theme: &Theme) -> Color {
         match self {
-            AnomalySeverity::Low => Color::Yellow,
-            AnomalySeverity::Medium => Color::LightRed,
-            AnomalySeverity::High => Color::Red,
-            AnomalySeverity::Critical => Color::Magenta,
+            AnomalySeverity::Low => theme.warning,
+            AnomalySeverity::Medium => theme.heat[3],
+            AnomalySeverity::High => theme.heat[4],
+            AnomalySeverity::Critical => theme.error,
         }
     }
 
@@ -51,6 +620,34 @@ impl AnomalySeverity {
     }
 }
 
+/// Progress of a metric's baseline, while detection waits for it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Warmup {
+    pub samples: usize,
+    pub min_samples: usize,
+    pub elapsed: std::time::Duration,
+    pub min_elapsed: std::time::Duration,
+}
+
+impl std::fmt::Display for Warmup {
+    /// `learning baseline (42/100 samples, 12s left)`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let samples = self.samples.min(self.min_samples);
+        write!(f, "learning baseline ({}/{} samples", samples, self.min_samples)?;
+        if let Some(left) = self.min_elapsed.checked_sub(self.elapsed).filter(|left| !left.is_zero()) {
+            write!(f, ", {}s left", left.as_secs_f64().ceil())?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Start of a metric's baseline and the samples seen since
+#[derive(Debug, Clone, Copy)]
+struct Baseline {
+    started: chrono::DateTime<chrono::Utc>,
+    samples: usize,
+}
+
 /// Anomaly detector for QUIC metrics
 pub struct AnomalyDetector {
     /// Historical data for each metric
@@ -62,105 +659,277 @@ pub struct AnomalyDetector {
     /// Maximum number of anomalies to keep
     pub max_anomalies: usize,
     
-    /// Detection sensitivity (0.0 to 1.0)
+    /// Detection sensitivity (0.0 to 1.0) for metrics without their own setting
     pub sensitivity: f64,
+
+    /// Per-metric overrides
+    pub config: AnomalyConfig,
+
+    /// Running mean/variance over each metric's history window
+    stats: std::collections::HashMap<String, StreamingStats>,
+
+    /// Strategies of each metric, built from its settings on its first sample
+    strategies: std::collections::HashMap<String, Vec<Box<dyn DetectionStrategy>>>,
+
+    /// Baseline of each metric, started by its first sample
+    baselines: std::collections::HashMap<String, Baseline>,
+
+    /// Newly detected anomalies, for subscribers such as alerting
+    events: broadcast::Sender<AnomalyResult>,
+
+    /// Id of the next anomaly
+    next_id: u64,
+
+    /// Until when anomalies are only counted, after a reset or network change
+    suppressed_until: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Anomalies held back by a cool-down or a suppression window
+    suppressed: u64,
+
+    /// When each metric last reported an anomaly, and how severe it was
+    last_reported: std::collections::HashMap<String, (chrono::DateTime<chrono::Utc>, AnomalySeverity)>,
 }
 
 impl AnomalyDetector {
     pub fn new(sensitivity: f64) -> Self {
+        Self::with_config(sensitivity, AnomalyConfig::default())
+    }
+
+    pub fn with_config(sensitivity: f64, config: AnomalyConfig) -> Self {
         Self {
             metric_history: std::collections::HashMap::new(),
             anomalies: VecDeque::new(),
             max_anomalies: 100,
             sensitivity,
+            config,
+            stats: std::collections::HashMap::new(),
+            strategies: std::collections::HashMap::new(),
+            baselines: std::collections::HashMap::new(),
+            events: broadcast::channel(100).0,
+            next_id: 0,
+            suppressed_until: None,
+            suppressed: 0,
+            last_reported: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Receive every anomaly detected from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AnomalyResult> {
+        self.events.subscribe()
+    }
+
+    /// Drop all history and anomalies, keeping the configuration
+    pub fn clear(&mut self) {
+        self.metric_history.clear();
+        self.anomalies.clear();
+        self.stats.clear();
+        self.strategies.clear();
+        self.baselines.clear();
+        self.suppressed_until = None;
+        self.suppressed = 0;
+        self.last_reported.clear();
+    }
+
+    /// Switch to new per-metric settings, keeping the history and anomalies
+    ///
+    /// Strategies are rebuilt from the new settings on each metric's next sample.
+    pub fn set_config(&mut self, config: AnomalyConfig) {
+        self.config = config;
+        self.strategies.clear();
+    }
+
+    /// Drop the history and start learning every baseline again, keeping the anomalies
+    pub fn rebaseline(&mut self) {
+        self.metric_history.clear();
+        self.stats.clear();
+        self.strategies.clear();
+        self.baselines.clear();
+    }
+
+    /// Baseline progress of `metric` at `now`, or None once detection runs
+    fn warmup_at(&self, metric: &str, settings: &ResolvedSettings, now: chrono::DateTime<chrono::Utc>) -> Option<Warmup> {
+        let (samples, elapsed) = match self.baselines.get(metric) {
+            Some(baseline) => (baseline.samples, (now - baseline.started).to_std().unwrap_or_default()),
+            None => (0, std::time::Duration::ZERO),
+        };
+        let warmup = Warmup {
+            samples,
+            min_samples: settings.min_samples,
+            elapsed,
+            min_elapsed: std::time::Duration::from_secs_f64(settings.warmup_secs),
+        };
+        (warmup.samples < warmup.min_samples || warmup.elapsed < warmup.min_elapsed).then_some(warmup)
+    }
+
+    /// Baseline progress of `metric`, or None once detection runs
+    pub fn warmup(&self, metric: &str) -> Option<Warmup> {
+        self.warmup_at(metric, &self.settings_for(metric), chrono::Utc::now())
+    }
+
+    /// Only count anomalies for the next `duration`, e.g. while a network change settles
+    ///
+    /// A window still running is extended, never shortened.
+    pub fn suppress(&mut self, duration: std::time::Duration) {
+        let Ok(duration) = chrono::Duration::from_std(duration) else {
+            return;
+        };
+        let until = chrono::Utc::now() + duration;
+        self.suppressed_until = Some(self.suppressed_until.map_or(until, |current| current.max(until)));
+    }
+
+    /// Time left in the suppression window, if one is running
+    pub fn suppression_remaining(&self) -> Option<std::time::Duration> {
+        let remaining = self.suppressed_until? - chrono::Utc::now();
+        remaining.to_std().ok().filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Anomalies held back by a cool-down or a suppression window since the last clear
+    pub fn suppressed_count(&self) -> u64 {
+        self.suppressed
+    }
+
+    /// Whether `anomaly` falls in the suppression window or its metric's cool-down
+    ///
+    /// A more severe anomaly than the last reported one always gets through
+    /// the cool-down.
+    fn holds_back(&self, anomaly: &AnomalyResult, cooldown_secs: f64) -> bool {
+        if self.suppressed_until.is_some_and(|until| anomaly.timestamp < until) {
+            return true;
+        }
+        let Some((reported_at, severity)) = self.last_reported.get(&anomaly.metric) else {
+            return false;
+        };
+        let elapsed = (anomaly.timestamp - *reported_at).num_milliseconds() as f64 / 1000.0;
+        elapsed < cooldown_secs && anomaly.severity <= *severity
+    }
+
+    fn settings_for(&self, metric: &str) -> ResolvedSettings {
+        let config = self.config.get(metric);
+        ResolvedSettings {
+            sensitivity: config
+                .and_then(|c| c.sensitivity)
+                .unwrap_or(self.sensitivity)
+                .clamp(0.0, 1.0),
+            statistics: config.and_then(|c| c.statistics).unwrap_or(self.config.statistics),
+            min_samples: config
+                .and_then(|c| c.min_samples)
+                .or(self.config.min_samples)
+                .unwrap_or(DEFAULT_MIN_SAMPLES)
+                .max(2),
+            warmup_secs: config
+                .and_then(|c| c.warmup_secs)
+                .or(self.config.warmup_secs)
+                .unwrap_or(DEFAULT_WARMUP_SECS),
+            direction: config.map(|c| c.direction).unwrap_or_default(),
+            strategies: config
+                .and_then(|c| c.strategies.clone())
+                .filter(|strategies| !strategies.is_empty())
+                .unwrap_or_else(|| vec![StrategyKind::ZScore]),
+            ewma_lambda: config.and_then(|c| c.ewma_lambda).unwrap_or(DEFAULT_EWMA_LAMBDA),
+            ewma_limit: config.and_then(|c| c.ewma_limit).unwrap_or(DEFAULT_EWMA_LIMIT),
+            roc_k: config.and_then(|c| c.roc_k).unwrap_or(DEFAULT_ROC_K),
+            roc_window: config.and_then(|c| c.roc_window).unwrap_or(DEFAULT_ROC_WINDOW).max(2),
+            cooldown_secs: config
+                .and_then(|c| c.cooldown_secs)
+                .or(self.config.cooldown_secs)
+                .unwrap_or(DEFAULT_COOLDOWN_SECS),
         }
     }
 
     /// Add metric data point
     pub fn add_data_point(&mut self, metric: String, value: f64) {
+        self.add_data_point_at(metric, value, chrono::Utc::now());
+    }
+
+    /// Add a data point taken at `now`, which also times the cool-down and suppression
+    pub fn add_data_point_at(&mut self, metric: String, value: f64, now: chrono::DateTime<chrono::Utc>) {
+        // Undefined values never enter the history; finite_values counts the skip
+        if finite_values(&[value]).is_empty() {
+            return;
+        }
+
         // Add to history
-        self.metric_history
+        let history = self.metric_history.entry(metric.clone()).or_default();
+        let stats = self.stats.entry(metric.clone()).or_default();
+        history.push_back(value);
+        stats.push(value);
+        self.baselines
             .entry(metric.clone())
-            .or_insert_with(VecDeque::new)
-            .push_back(value);
+            .or_insert(Baseline { started: now, samples: 0 })
+            .samples += 1;
         
-        // Keep only recent data (last 100 points)
-        if let Some(history) = self.metric_history.get_mut(&metric) {
-            while history.len() > 100 {
-                history.pop_front();
+        // Keep only recent data
+        while history.len() > HISTORY_LEN {
+            if let Some(evicted) = history.pop_front() {
+                stats.remove(evicted);
             }
         }
 
-        // Check for anomalies
-        if let Some(anomaly) = self.detect_anomaly(&metric, value) {
-            self.anomalies.push_back(anomaly);
-            
-            // Keep only recent anomalies
-            while self.anomalies.len() > self.max_anomalies {
-                self.anomalies.pop_front();
+        // Check for anomalies, one per strategy that fires
+        let cooldown_secs = self.settings_for(&metric).cooldown_secs;
+        for mut anomaly in self.detect_anomalies(&metric, value, now) {
+            if self.holds_back(&anomaly, cooldown_secs) {
+                self.suppressed += 1;
+                continue;
             }
+            self.last_reported
+                .insert(metric.clone(), (anomaly.timestamp, anomaly.severity.clone()));
+            anomaly.id = self.next_id;
+            self.next_id += 1;
+            // No subscribers is fine
+            let _ = self.events.send(anomaly.clone());
+            self.anomalies.push_back(anomaly);
         }
-    }
-
-    /// Detect anomaly in metric value
-    fn detect_anomaly(&self, metric: &str, value: f64) -> Option<AnomalyResult> {
-        let history = self.metric_history.get(metric)?;
-        
-        if history.len() < 10 {
-            return None; // Need more data for detection
-        }
-
-        let data: Vec<f64> = history.iter().cloned().collect();
-        let (mean, std_dev) = self.calculate_statistics(&data);
-        
-        // Z-score based detection
-        let z_score = (value - mean) / std_dev;
-        let threshold = 2.0 + (1.0 - self.sensitivity) * 2.0; // 2.0 to 4.0 based on sensitivity
-        
-        if z_score.abs() > threshold {
-            let severity = self.determine_severity(z_score.abs());
-            let expected_range = (mean - 2.0 * std_dev, mean + 2.0 * std_dev);
-            let description = format!(
-                "Z-score: {:.2}, Expected: {:.1}-{:.1}, Actual: {:.1}",
-                z_score, expected_range.0, expected_range.1, value
-            );
-            
-            Some(AnomalyResult {
-                metric: metric.to_string(),
-                value,
-                expected_range,
-                severity,
-                timestamp: chrono::Utc::now(),
-                description,
-            })
-        } else {
-            None
+        // Keep only recent anomalies
+        while self.anomalies.len() > self.max_anomalies {
+            self.anomalies.pop_front();
         }
     }
 
-    /// Calculate mean and standard deviation
-    fn calculate_statistics(&self, data: &[f64]) -> (f64, f64) {
-        if data.is_empty() {
-            return (0.0, 0.0);
-        }
-
-        let mean = data.iter().sum::<f64>() / data.len() as f64;
-        let variance = data.iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / data.len() as f64;
-        let std_dev = variance.sqrt();
-
-        (mean, std_dev)
-    }
+    /// Run every strategy of `metric` over its window, which ends with `value`
+    ///
+    /// Strategies see every sample so their state stays current, but report
+    /// nothing while the baseline is learned or against the configured direction.
+    fn detect_anomalies(&mut self, metric: &str, value: f64, now: chrono::DateTime<chrono::Utc>) -> Vec<AnomalyResult> {
+        let settings = self.settings_for(metric);
+        let learning = self.warmup_at(metric, &settings, now).is_some();
+        let (Some(values), Some(stats)) = (self.metric_history.get(metric), self.stats.get(metric)) else {
+            return Vec::new();
+        };
+        let window = SampleWindow { values, stats };
+        let strategies = self
+            .strategies
+            .entry(metric.to_string())
+            .or_insert_with(|| settings.build_strategies());
 
-    /// Determine anomaly severity based on z-score
-    fn determine_severity(&self, z_score: f64) -> AnomalySeverity {
-        match z_score {
-            x if x >= 4.0 => AnomalySeverity::Critical,
-            x if x >= 3.0 => AnomalySeverity::High,
-            x if x >= 2.5 => AnomalySeverity::Medium,
-            _ => AnomalySeverity::Low,
+        let mut anomalies = Vec::new();
+        for strategy in strategies.iter_mut() {
+            let Some(detection) = strategy.check(value, &window) else {
+                continue;
+            };
+            if learning {
+                continue; // Need a baseline for detection
+            }
+            let wanted = match settings.direction {
+                AnomalyDirection::Both => true,
+                AnomalyDirection::High => detection.high,
+                AnomalyDirection::Low => !detection.high,
+            };
+            if wanted {
+                anomalies.push(AnomalyResult {
+                    id: 0, // Assigned once the anomaly is kept
+                    metric: metric.to_string(),
+                    value,
+                    expected_range: detection.expected_range,
+                    severity: detection.severity,
+                    timestamp: now,
+                    description: detection.description,
+                    strategy: strategy.kind(),
+                    acknowledged: false,
+                });
+            }
         }
+        anomalies
     }
 
     /// Get recent anomalies
@@ -173,6 +942,41 @@ impl AnomalyDetector {
             .collect()
     }
 
+    /// Anomalies still kept, oldest first
+    pub fn anomalies(&self) -> Vec<AnomalyResult> {
+        self.anomalies.iter().cloned().collect()
+    }
+
+    /// Toggle the acknowledgment of anomaly `id`; false if it is no longer kept
+    pub fn acknowledge(&mut self, id: u64) -> bool {
+        match self.anomalies.iter_mut().find(|anomaly| anomaly.id == id) {
+            Some(anomaly) => {
+                anomaly.acknowledged = !anomaly.acknowledged;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop every acknowledged anomaly
+    pub fn clear_acknowledged(&mut self) {
+        self.anomalies.retain(|anomaly| !anomaly.acknowledged);
+    }
+
+    /// Anomalies nobody has acknowledged yet
+    pub fn unacknowledged_count(&self) -> usize {
+        self.anomalies.iter().filter(|anomaly| !anomaly.acknowledged).count()
+    }
+
+    /// Anomalies detected at or after `since`, by severity
+    pub fn counts_since(&self, since: chrono::DateTime<chrono::Utc>) -> BTreeMap<AnomalySeverity, usize> {
+        let mut counts = BTreeMap::new();
+        for anomaly in self.anomalies.iter().filter(|anomaly| anomaly.timestamp >= since) {
+            *counts.entry(anomaly.severity.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
     /// Get anomaly count by severity
     pub fn get_anomaly_counts(&self) -> std::collections::HashMap<AnomalySeverity, usize> {
         let mut counts = std::collections::HashMap::new();
@@ -189,131 +993,952 @@ impl AnomalyDetector {
 pub struct AnomalyWidget {
     detector: AnomalyDetector,
     title: String,
+    /// Summary of the applied settings, one entry per metric
+    settings: Vec<String>,
+    /// Alert counters shown in the summary line, when alerting is on
+    alert_counters: Option<Arc<AlertCounters>>,
+    /// Whether the widget has the focus
+    pub is_selected: bool,
+    /// Metrics the metric filter steps through
+    metrics: Vec<String>,
+    /// List only critical anomalies
+    pub critical_only: bool,
+    /// List only anomalies of this metric
+    pub metric_filter: Option<String>,
+    /// Id of the highlighted anomaly, which stays put as new ones arrive
+    selected: Option<u64>,
+    /// First listed row, kept between frames so the list scrolls instead of jumping
+    offset: Cell<usize>,
+    /// Rows the list showed last frame, the step of PageUp/PageDown
+    page_rows: Cell<usize>,
 }
 
 impl AnomalyWidget {
     pub fn new(title: String, sensitivity: f64) -> Self {
+        Self::with_config(title, sensitivity, AnomalyConfig::default(), &[])
+    }
+
+    /// Widget for `metrics`, with per-metric overrides from `config`
+    pub fn with_config(title: String, sensitivity: f64, config: AnomalyConfig, metrics: &[&str]) -> Self {
+        let detector = AnomalyDetector::with_config(sensitivity, config);
+        let settings = metrics
+            .iter()
+            .map(|metric| format!("{}: {}", metric, detector.settings_for(metric)))
+            .collect();
         Self {
-            detector: AnomalyDetector::new(sensitivity),
+            detector,
             title,
+            settings,
+            alert_counters: None,
+            is_selected: false,
+            metrics: metrics.iter().map(|metric| metric.to_string()).collect(),
+            critical_only: false,
+            metric_filter: None,
+            selected: None,
+            offset: Cell::new(0),
+            page_rows: Cell::new(1),
         }
     }
 
+    /// Switch to new per-metric settings, keeping the data
+    pub fn set_config(&mut self, config: AnomalyConfig) {
+        self.detector.set_config(config);
+        self.settings = self
+            .metrics
+            .iter()
+            .map(|metric| format!("{}: {}", metric, self.detector.settings_for(metric)))
+            .collect();
+    }
+
+    /// Receive every anomaly detected from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AnomalyResult> {
+        self.detector.subscribe()
+    }
+
+    /// Only count anomalies for the next `duration`
+    pub fn suppress(&mut self, duration: std::time::Duration) {
+        self.detector.suppress(duration);
+    }
+
+    /// Start learning every baseline again, keeping the anomalies
+    pub fn rebaseline(&mut self) {
+        self.detector.rebaseline();
+    }
+
+    /// Anomalies of the last `window`, by severity
+    pub fn recent_counts(&self, window: std::time::Duration) -> BTreeMap<AnomalySeverity, usize> {
+        let since = chrono::Duration::from_std(window).map_or(chrono::DateTime::<chrono::Utc>::MIN_UTC, |window| {
+            chrono::Utc::now() - window
+        });
+        self.detector.counts_since(since)
+    }
+
+    /// The configured suppression window for resets and network changes
+    pub fn suppression(&self) -> std::time::Duration {
+        self.detector.config.suppression()
+    }
+
+    /// Show fired/suppressed alert counts in the summary line
+    pub fn set_alert_counters(&mut self, counters: Arc<AlertCounters>) {
+        self.alert_counters = Some(counters);
+    }
+
+    /// Drop all data, keeping the configuration and the filters
+    pub fn clear(&mut self) {
+        self.detector.clear();
+        self.selected = None;
+        self.offset.set(0);
+    }
+
     /// Add metric data
     pub fn add_metric_data(&mut self, metric: String, value: f64) {
         self.detector.add_data_point(metric, value);
     }
 
+    /// Add metric data taken at `at`, e.g. a sample replayed from a recording
+    pub fn add_metric_data_at(&mut self, metric: String, value: f64, at: chrono::DateTime<chrono::Utc>) {
+        self.detector.add_data_point_at(metric, value, at);
+    }
+
+    /// Anomalies still kept, oldest first
+    pub fn anomalies(&self) -> Vec<AnomalyResult> {
+        self.detector.anomalies()
+    }
+
+    /// Anomalies passing the filters, newest first, as the list shows them
+    fn listed(&self) -> Vec<&AnomalyResult> {
+        self.detector
+            .anomalies
+            .iter()
+            .rev()
+            .filter(|anomaly| !self.critical_only || anomaly.severity == AnomalySeverity::Critical)
+            .filter(|anomaly| self.metric_filter.as_ref().is_none_or(|metric| &anomaly.metric == metric))
+            .collect()
+    }
+
+    /// Row of the highlighted anomaly, if it is listed
+    fn selected_index(&self, listed: &[&AnomalyResult]) -> Option<usize> {
+        let id = self.selected?;
+        listed.iter().position(|anomaly| anomaly.id == id)
+    }
+
+    /// Move the highlight `delta` rows down, starting at the newest anomaly
+    pub fn move_selection(&mut self, delta: isize) {
+        let listed = self.listed();
+        let Some(last) = listed.len().checked_sub(1) else {
+            self.selected = None;
+            return;
+        };
+        let index = match self.selected_index(&listed) {
+            Some(index) => index.saturating_add_signed(delta).min(last),
+            None => 0,
+        };
+        self.selected = Some(listed[index].id);
+    }
+
+    /// Move the highlight by `pages` screens
+    pub fn page_selection(&mut self, pages: isize) {
+        self.move_selection(pages * self.page_rows.get().max(1) as isize);
+    }
+
+    /// Switch between all anomalies and critical ones only
+    pub fn toggle_critical_only(&mut self) {
+        self.critical_only = !self.critical_only;
+    }
+
+    /// Step the metric filter through every metric and back to all of them
+    pub fn cycle_metric_filter(&mut self) {
+        let next = match &self.metric_filter {
+            None => 0,
+            Some(metric) => match self.metrics.iter().position(|m| m == metric) {
+                Some(index) => index + 1,
+                None => self.metrics.len(),
+            },
+        };
+        self.metric_filter = self.metrics.get(next).cloned();
+    }
+
+    /// Acknowledge the highlighted anomaly, or take the acknowledgment back
+    pub fn acknowledge_selected(&mut self) {
+        let listed = self.listed();
+        if let Some(index) = self.selected_index(&listed) {
+            let id = listed[index].id;
+            self.detector.acknowledge(id);
+        }
+    }
+
+    /// Drop acknowledged anomalies, keeping the highlight on the same row
+    pub fn clear_acknowledged(&mut self) {
+        let index = self.selected_index(&self.listed());
+        self.detector.clear_acknowledged();
+        let listed = self.listed();
+        self.selected = index
+            .and_then(|index| listed.get(index.min(listed.len().saturating_sub(1))))
+            .map(|anomaly| anomaly.id);
+    }
+
+    /// `Anomalies 12/40 · critical · Latency`, naming the active filters
+    fn list_title(&self, listed: usize) -> String {
+        let total = self.detector.anomalies.len();
+        let mut title = if listed == total {
+            format!("Anomalies {}", total)
+        } else {
+            format!("Anomalies {}/{}", listed, total)
+        };
+        if self.critical_only {
+            title.push_str(" · critical");
+        }
+        if let Some(metric) = &self.metric_filter {
+            title.push_str(&format!(" · {}", metric));
+        }
+        title
+    }
+
     /// Render the anomaly widget
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_as(f, area, Presentation::fitting(self, area), theme);
+    }
+
+    /// Render at `presentation`: compact drops the summary, minimal is the summary alone
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        if presentation == Presentation::Minimal {
+            self.render_summary(f, area, theme);
+            return;
+        }
+
+        let summary_rows = if presentation == Presentation::Full { 3 } else { 0 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(0),    // Anomaly list
-                Constraint::Length(3), // Summary
+                Constraint::Length(3),            // Title
+                Constraint::Min(0),               // Anomaly list
+                Constraint::Length(summary_rows), // Summary
             ])
             .split(area);
 
         // Title
-        self.render_title(f, chunks[0]);
+        self.render_title(f, chunks[0], theme);
         
         // Anomaly list
-        self.render_anomalies(f, chunks[1]);
+        self.render_anomalies(f, chunks[1], theme);
         
         // Summary
-        self.render_summary(f, chunks[2]);
+        if summary_rows > 0 {
+            self.render_summary(f, chunks[2], theme);
+        }
     }
 
-    fn render_title(&self, f: &mut Frame, area: Rect) {
-        let title = Paragraph::new(self.title.clone())
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL));
+    fn render_title(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        // Metrics still learning their baseline say so instead of their settings
+        let entries: Vec<String> = self
+            .settings
+            .iter()
+            .enumerate()
+            .map(|(index, settings)| {
+                let metric = self.metrics.get(index);
+                match metric.and_then(|metric| self.detector.warmup(metric).map(|warmup| (metric, warmup))) {
+                    Some((metric, warmup)) => format!("{}: {}", metric, warmup),
+                    None => settings.clone(),
+                }
+            })
+            .collect();
+        let title = Paragraph::new(entries.join(" | "))
+            .style(Style::default().fg(theme.muted))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style(theme, self.is_selected))
+                    .title(self.title.clone())
+                    .title_style(title_style(theme, self.is_selected)),
+            );
         f.render_widget(title, area);
     }
 
-    fn render_anomalies(&self, f: &mut Frame, area: Rect) {
-        let recent_anomalies = self.detector.get_recent_anomalies(10);
-        
-        if recent_anomalies.is_empty() {
+    fn render_anomalies(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        if self.detector.anomalies.is_empty() {
             let no_anomalies = Paragraph::new("No anomalies detected")
-                .style(Style::default().fg(Color::Green))
+                .style(Style::default().fg(theme.success))
                 .block(Block::default().borders(Borders::ALL));
             f.render_widget(no_anomalies, area);
             return;
         }
 
-        let mut lines = Vec::new();
-        for anomaly in recent_anomalies {
-            let severity_color = anomaly.severity.get_color();
-            let severity_text = anomaly.severity.get_description();
-            
-            let line = Line::from(vec![
-                Span::styled(
-                    format!("[{}] ", severity_text),
-                    Style::default().fg(severity_color).add_modifier(Modifier::BOLD)
-                ),
-                Span::styled(
-                    format!("{}: {:.2} ", anomaly.metric, anomaly.value),
-                    Style::default().fg(Color::White)
-                ),
-                Span::styled(
-                    format!("({})", anomaly.description),
-                    Style::default().fg(Color::Gray)
-                ),
-            ]);
-            lines.push(line);
-        }
-
-        let anomalies_paragraph = Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(anomalies_paragraph, area);
-    }
-
-    fn render_summary(&self, f: &mut Frame, area: Rect) {
+        let listed = self.listed();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.list_title(listed.len()))
+            .title_style(Style::default().fg(theme.muted));
+        if listed.is_empty() {
+            let no_matches = Paragraph::new("No anomalies match the filter")
+                .style(Style::default().fg(theme.muted))
+                .block(block);
+            f.render_widget(no_matches, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = listed
+            .iter()
+            .map(|anomaly| {
+                let severity_text = anomaly.severity.get_description();
+                if anomaly.acknowledged {
+                    // Seen already: the whole row steps back
+                    return ListItem::new(Line::from(vec![
+                        Span::raw(format!("✓ [{}] ", severity_text)),
+                        Span::raw(format!("{}: {} ", anomaly.metric, format_value(anomaly.value, 2))),
+                        Span::raw(format!("({})", anomaly.description)),
+                    ]))
+                    .style(Style::default().fg(theme.muted));
+                }
+
+                let severity_color = anomaly.severity.get_color(theme);
+                // Critical stands out even where the theme has one color for both
+                let emphasis = if anomaly.severity == AnomalySeverity::Critical {
+                    Modifier::BOLD | Modifier::REVERSED
+                } else {
+                    Modifier::BOLD
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", severity_text),
+                        Style::default().fg(severity_color).add_modifier(emphasis)
+                    ),
+                    Span::styled(
+                        format!("{}: {} ", anomaly.metric, format_value(anomaly.value, 2)),
+                        Style::default().fg(theme.text)
+                    ),
+                    Span::styled(
+                        format!("({})", anomaly.description),
+                        Style::default().fg(theme.muted)
+                    ),
+                ]))
+            })
+            .collect();
+
+        // The highlight only shows while the keys can move it
+        let selected = self.selected_index(&listed).filter(|_| self.is_selected);
+        let mut state = ListState::default()
+            .with_offset(self.offset.get())
+            .with_selected(selected);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(list, area, &mut state);
+        self.offset.set(state.offset());
+        self.page_rows.set(area.height.saturating_sub(2) as usize);
+    }
+
+    fn render_summary(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let counts = self.detector.get_anomaly_counts();
         let total_anomalies = counts.values().sum::<usize>();
         
-        let summary_text = if total_anomalies == 0 {
-            "✅ No anomalies detected".to_string()
+        let suppressed = match self.detector.suppressed_count() {
+            0 => String::new(),
+            count => format!(" (+{} suppressed)", count),
+        };
+        let mut summary_text = if total_anomalies == 0 {
+            format!("✅ No anomalies detected{}", suppressed)
         } else {
             format!(
-                "⚠️  {} anomalies: Critical: {} | High: {} | Medium: {} | Low: {}",
+                "⚠️  {} anomalies, {} unacknowledged{}: Critical: {} | High: {} | Medium: {} | Low: {}",
                 total_anomalies,
+                self.detector.unacknowledged_count(),
+                suppressed,
                 counts.get(&AnomalySeverity::Critical).unwrap_or(&0),
                 counts.get(&AnomalySeverity::High).unwrap_or(&0),
                 counts.get(&AnomalySeverity::Medium).unwrap_or(&0),
                 counts.get(&AnomalySeverity::Low).unwrap_or(&0),
             )
         };
+        if let Some(remaining) = self.detector.suppression_remaining() {
+            summary_text.push_str(&format!(" | Quiet for {}s", remaining.as_secs() + 1));
+        }
+        if let Some(alerts) = &self.alert_counters {
+            summary_text.push_str(&format!(
+                " | Alerts: {} fired, {} suppressed",
+                alerts.fired(),
+                alerts.suppressed()
+            ));
+        }
         
         let summary = Paragraph::new(summary_text)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         
         f.render_widget(summary, area);
     }
 }
 
+impl MinSize for AnomalyWidget {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        // Title, a bordered list line and the summary
+        match presentation {
+            Presentation::Full => Size::new(30, 9),
+            Presentation::Compact => Size::new(30, 6),
+            Presentation::Minimal => Size::new(0, 1),
+        }
+    }
+}
+
+/// Highlighted anomaly and scroll position of the list, as `cursor` saves them
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AnomalyListCursor {
+    selected: Option<u64>,
+    offset: usize,
+}
+
 /// QUIC Anomaly Detection Widget
 pub struct QUICAnomalyWidget {
     anomaly: AnomalyWidget,
 }
 
+impl Default for QUICAnomalyWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QUICAnomalyWidget {
     pub fn new() -> Self {
-        Self {
-            anomaly: AnomalyWidget::new("QUIC Anomaly Detection".to_string(), 0.7),
+        Self::with_config(&AnomalyConfig::default())
+    }
+
+    /// Apply per-metric settings; entries for unknown metrics are logged and ignored
+    pub fn with_config(config: &AnomalyConfig) -> Self {
+        let mut widget = Self {
+            anomaly: AnomalyWidget::with_config(
+                "QUIC Anomaly Detection".to_string(),
+                DEFAULT_SENSITIVITY,
+                config.clone(),
+                QUIC_ANOMALY_METRICS,
+            ),
+        };
+        widget.note_unknown_metrics(config);
+        widget
+    }
+
+    /// Switch to new per-metric settings, keeping the data and anomalies
+    pub fn set_config(&mut self, config: &AnomalyConfig) {
+        self.anomaly.set_config(config.clone());
+        self.note_unknown_metrics(config);
+    }
+
+    /// Log and list the entries of `config` for metrics this widget does not track
+    fn note_unknown_metrics(&mut self, config: &AnomalyConfig) {
+        let unknown = config.unknown_metrics(QUIC_ANOMALY_METRICS);
+        for name in &unknown {
+            log::warn!(
+                "ignoring anomaly config for unknown metric '{}' (known: {})",
+                name,
+                QUIC_ANOMALY_METRICS.join(", ")
+            );
+        }
+        if !unknown.is_empty() {
+            self.anomaly.settings.push(format!("ignored: {}", unknown.join(", ")));
         }
     }
 
+    /// Drop all data, keeping the configuration
+    pub fn clear(&mut self) {
+        self.anomaly.clear();
+    }
+
+    /// Receive every anomaly detected from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AnomalyResult> {
+        self.anomaly.subscribe()
+    }
+
+    /// Show fired/suppressed alert counts in the summary line
+    pub fn set_alert_counters(&mut self, counters: Arc<AlertCounters>) {
+        self.anomaly.set_alert_counters(counters);
+    }
+
+    /// Only count anomalies for the next `duration`
+    pub fn suppress(&mut self, duration: std::time::Duration) {
+        self.anomaly.suppress(duration);
+    }
+
+    /// Start learning every baseline again, keeping the anomalies
+    pub fn rebaseline(&mut self) {
+        self.anomaly.rebaseline();
+    }
+
+    /// Anomalies of the last `window`, by severity
+    pub fn recent_counts(&self, window: std::time::Duration) -> BTreeMap<AnomalySeverity, usize> {
+        self.anomaly.recent_counts(window)
+    }
+
+    /// The configured suppression window for resets and network changes
+    pub fn suppression(&self) -> std::time::Duration {
+        self.anomaly.suppression()
+    }
+
     /// Add QUIC metric data
     pub fn add_quic_metric(&mut self, metric: String, value: f64) {
         self.anomaly.add_metric_data(metric, value);
     }
 
+    /// Add QUIC metric data taken at `at`, which times the warm-up and cool-down
+    pub fn add_quic_metric_at(&mut self, metric: String, value: f64, at: chrono::DateTime<chrono::Utc>) {
+        self.anomaly.add_metric_data_at(metric, value, at);
+    }
+
+    /// Anomalies still kept, oldest first
+    pub fn anomalies(&self) -> Vec<AnomalyResult> {
+        self.anomaly.anomalies()
+    }
+
+    /// Highlight the widget as focused
+    pub fn set_selected(&mut self, selected: bool) {
+        self.anomaly.is_selected = selected;
+    }
+
+    /// Highlighted anomaly and first listed row
+    pub fn cursor(&self) -> AnomalyListCursor {
+        AnomalyListCursor {
+            selected: self.anomaly.selected,
+            offset: self.anomaly.offset.get(),
+        }
+    }
+
+    /// Go back to a `cursor` taken earlier; an anomaly no longer listed leaves nothing highlighted
+    pub fn set_cursor(&mut self, cursor: AnomalyListCursor) {
+        self.anomaly.selected = cursor.selected;
+        self.anomaly.offset.set(cursor.offset);
+    }
+
+    /// Move the highlight `delta` rows down the list
+    pub fn move_selection(&mut self, delta: isize) {
+        self.anomaly.move_selection(delta);
+    }
+
+    /// Move the highlight by `pages` screens
+    pub fn page_selection(&mut self, pages: isize) {
+        self.anomaly.page_selection(pages);
+    }
+
+    /// Switch between all anomalies and critical ones only
+    pub fn toggle_critical_only(&mut self) {
+        self.anomaly.toggle_critical_only();
+    }
+
+    /// Step the metric filter through the QUIC metrics and back to all of them
+    pub fn cycle_metric_filter(&mut self) {
+        self.anomaly.cycle_metric_filter();
+    }
+
+    /// Acknowledge the highlighted anomaly, or take the acknowledgment back
+    pub fn acknowledge_selected(&mut self) {
+        self.anomaly.acknowledge_selected();
+    }
+
+    /// Drop acknowledged anomalies
+    pub fn clear_acknowledged(&mut self) {
+        self.anomaly.clear_acknowledged();
+    }
+
     /// Render the anomaly widget
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.anomaly.render(f, area);
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.anomaly.render(f, area, theme);
+    }
+
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        self.anomaly.render_as(f, area, presentation, theme);
+    }
+}
+
+impl MinSize for QUICAnomalyWidget {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        self.anomaly.min_size(presentation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Settings that check from the 10th sample on and report every detection
+    fn detector(latency: MetricAnomalyConfig) -> AnomalyDetector {
+        let config = AnomalyConfig {
+            min_samples: Some(10),
+            warmup_secs: Some(0.0),
+            cooldown_secs: Some(0.0),
+            metrics: BTreeMap::from([("latency".to_string(), latency)]),
+            ..AnomalyConfig::default()
+        };
+        AnomalyDetector::with_config(DEFAULT_SENSITIVITY, config)
+    }
+
+    fn strategies(kinds: &[StrategyKind]) -> MetricAnomalyConfig {
+        MetricAnomalyConfig {
+            strategies: Some(kinds.to_vec()),
+            ..MetricAnomalyConfig::default()
+        }
+    }
+
+    /// One latency sample a second, returning the index of each anomaly's sample and its strategy
+    fn feed(detector: &mut AnomalyDetector, values: &[f64]) -> Vec<(usize, StrategyKind)> {
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut found = Vec::new();
+        for (i, &value) in values.iter().enumerate() {
+            let before = detector.anomalies.len();
+            detector.add_data_point_at("Latency".to_string(), value, start + chrono::Duration::seconds(i as i64));
+            found.extend(detector.anomalies.iter().skip(before).map(|anomaly| (i, anomaly.strategy)));
+        }
+        found
+    }
+
+    /// 100 ± 1, alternating
+    fn steady(count: usize) -> Vec<f64> {
+        (0..count).map(|i| if i % 2 == 0 { 99.0 } else { 101.0 }).collect()
+    }
+
+    #[test]
+    fn rate_of_change_catches_a_step_the_z_score_misses() {
+        // A slow swing of ±20 hides a +25 step taken near its trough from the z-score
+        let trough = 87;
+        let mut values: Vec<f64> = (0..=trough)
+            .map(|i| 100.0 + 20.0 * (i as f64 * std::f64::consts::TAU / 50.0).sin())
+            .collect();
+        let stepped = values[trough] + 25.0;
+        values.extend([stepped; 10]);
+
+        let mut detector = detector(strategies(&[StrategyKind::ZScore, StrategyKind::RateOfChange]));
+        let found = feed(&mut detector, &values);
+        assert!(found.contains(&(trough + 1, StrategyKind::RateOfChange)), "{:?}", found);
+        assert!(found.iter().all(|&(_, kind)| kind != StrategyKind::ZScore), "{:?}", found);
+    }
+
+    #[test]
+    fn ewma_flags_a_slow_ramp_before_the_z_score() {
+        let mut values = steady(100);
+        values.extend((1..=100).map(|i| 100.0 + 0.1 * i as f64));
+
+        let mut detector = detector(strategies(&[StrategyKind::ZScore, StrategyKind::Ewma]));
+        let found = feed(&mut detector, &values);
+        let first = |kind| found.iter().find(|&&(_, k)| k == kind).map(|&(i, _)| i);
+        let ewma = first(StrategyKind::Ewma).expect("the EWMA flags the ramp");
+        assert!((100..140).contains(&ewma), "EWMA first at {}", ewma);
+        assert!(first(StrategyKind::ZScore).is_none_or(|z| z > ewma), "{:?}", found);
+    }
+
+    #[test]
+    fn single_spike_is_flagged_once() {
+        let mut values = steady(50);
+        values.push(150.0);
+        values.extend(steady(10));
+
+        let mut detector = detector(strategies(&[StrategyKind::ZScore]));
+        assert_eq!(feed(&mut detector, &values), [(50, StrategyKind::ZScore)]);
+        let spike = &detector.anomalies[0];
+        assert_eq!(spike.severity, AnomalySeverity::Critical);
+        assert!(spike.expected_range.1 < 150.0);
+    }
+
+    #[test]
+    fn nothing_is_reported_while_the_baseline_is_learned() {
+        let mut values = steady(5);
+        values.push(150.0);
+        let mut detector = detector(strategies(&[StrategyKind::ZScore, StrategyKind::RateOfChange]));
+        assert!(feed(&mut detector, &values).is_empty());
+    }
+
+    #[test]
+    fn one_huge_outlier_masks_later_anomalies_without_robust_statistics() {
+        let mut values = steady(50);
+        values.push(10_000.0);
+        values.extend(steady(20));
+        values.push(110.0);
+        let real = values.len() - 1;
+
+        let mut standard = detector(strategies(&[StrategyKind::ZScore]));
+        let found = feed(&mut standard, &values);
+        assert_eq!(found, [(50, StrategyKind::ZScore)], "the outlier inflates the standard deviation");
+
+        let mut robust = detector(MetricAnomalyConfig {
+            statistics: Some(AnomalyStatistics::Robust),
+            ..strategies(&[StrategyKind::ZScore])
+        });
+        let found = feed(&mut robust, &values);
+        assert!(found.contains(&(real, StrategyKind::ZScore)), "{:?}", found);
+
+        let anomaly = robust.anomalies.back().unwrap();
+        assert_eq!(anomaly.value, 110.0);
+        // Two scaled MADs around the median, which the outlier barely moved
+        let (low, high) = anomaly.expected_range;
+        assert!(low < 100.0 && high > 100.0 && high < 110.0, "{:?}", anomaly.expected_range);
+        assert!(anomaly.description.starts_with("Robust z:"), "{}", anomaly.description);
+    }
+
+    #[test]
+    fn robust_statistics_apply_globally_or_per_metric() {
+        let global = AnomalyConfig {
+            statistics: AnomalyStatistics::Robust,
+            ..AnomalyConfig::default()
+        };
+        let detector = AnomalyDetector::with_config(DEFAULT_SENSITIVITY, global);
+        assert_eq!(detector.settings_for("Latency").statistics, AnomalyStatistics::Robust);
+
+        let per_metric = self::detector(MetricAnomalyConfig {
+            statistics: Some(AnomalyStatistics::Robust),
+            ..MetricAnomalyConfig::default()
+        });
+        assert_eq!(per_metric.settings_for("Latency").statistics, AnomalyStatistics::Robust);
+        assert_eq!(per_metric.settings_for("Throughput").statistics, AnomalyStatistics::Standard);
+    }
+
+    /// Widget listing `anomalies` as (metric, severity), oldest first
+    fn listing(anomalies: &[(&str, AnomalySeverity)]) -> AnomalyWidget {
+        let mut widget = AnomalyWidget::with_config(
+            "Anomalies".to_string(),
+            DEFAULT_SENSITIVITY,
+            AnomalyConfig::default(),
+            &["Latency", "Throughput"],
+        );
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        for (id, (metric, severity)) in anomalies.iter().enumerate() {
+            widget.detector.anomalies.push_back(AnomalyResult {
+                id: id as u64,
+                metric: metric.to_string(),
+                value: 100.0,
+                expected_range: (90.0, 110.0),
+                severity: severity.clone(),
+                timestamp: start + chrono::Duration::seconds(id as i64),
+                description: format!("anomaly {}", id),
+                strategy: StrategyKind::ZScore,
+                acknowledged: false,
+            });
+        }
+        widget
+    }
+
+    fn listed_ids(widget: &AnomalyWidget) -> Vec<u64> {
+        widget.listed().iter().map(|anomaly| anomaly.id).collect()
+    }
+
+    fn rendered(widget: &AnomalyWidget, width: u16, height: u16) -> String {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| widget.render(f, f.area(), &Theme::default())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn acknowledging_toggles_the_highlighted_anomaly() {
+        let mut widget = listing(&[
+            ("Latency", AnomalySeverity::Low),
+            ("Latency", AnomalySeverity::High),
+            ("Throughput", AnomalySeverity::Critical),
+        ]);
+        // Nothing is highlighted until the keys move
+        widget.acknowledge_selected();
+        assert_eq!(widget.detector.unacknowledged_count(), 3);
+
+        // The highlight starts at the newest and walks down
+        widget.move_selection(1);
+        assert_eq!(widget.selected, Some(2));
+        widget.move_selection(1);
+        widget.acknowledge_selected();
+        assert!(widget.detector.anomalies[1].acknowledged);
+        assert_eq!(widget.detector.unacknowledged_count(), 2);
+
+        widget.acknowledge_selected();
+        assert!(!widget.detector.anomalies[1].acknowledged);
+        assert_eq!(widget.detector.unacknowledged_count(), 3);
+
+        // Past either end the highlight stays on the last row
+        widget.move_selection(10);
+        assert_eq!(widget.selected, Some(0));
+        widget.move_selection(-10);
+        assert_eq!(widget.selected, Some(2));
+    }
+
+    #[test]
+    fn filters_narrow_the_list_and_name_themselves() {
+        let mut widget = listing(&[
+            ("Latency", AnomalySeverity::Critical),
+            ("Throughput", AnomalySeverity::Medium),
+            ("Throughput", AnomalySeverity::Critical),
+            ("Latency", AnomalySeverity::Low),
+        ]);
+        assert_eq!(listed_ids(&widget), [3, 2, 1, 0]);
+
+        widget.toggle_critical_only();
+        assert_eq!(listed_ids(&widget), [2, 0]);
+        assert_eq!(widget.list_title(2), "Anomalies 2/4 · critical");
+        widget.toggle_critical_only();
+
+        widget.cycle_metric_filter();
+        assert_eq!(widget.metric_filter.as_deref(), Some("Latency"));
+        assert_eq!(listed_ids(&widget), [3, 0]);
+        widget.cycle_metric_filter();
+        assert_eq!(listed_ids(&widget), [2, 1]);
+        assert_eq!(widget.list_title(2), "Anomalies 2/4 · Throughput");
+        widget.cycle_metric_filter();
+        assert_eq!(widget.metric_filter, None);
+        assert_eq!(listed_ids(&widget), [3, 2, 1, 0]);
+
+        // Both filters at once, and a highlight the filter hides acknowledges nothing
+        widget.move_selection(0);
+        widget.toggle_critical_only();
+        widget.cycle_metric_filter();
+        assert_eq!(listed_ids(&widget), [0]);
+        widget.acknowledge_selected();
+        assert_eq!(widget.detector.unacknowledged_count(), 4);
+    }
+
+    #[test]
+    fn clearing_acknowledged_keeps_the_highlight_on_its_row() {
+        let mut widget = listing(&[
+            ("Latency", AnomalySeverity::Low),
+            ("Latency", AnomalySeverity::Medium),
+            ("Latency", AnomalySeverity::High),
+            ("Latency", AnomalySeverity::Critical),
+        ]);
+        // Acknowledge rows 0 and 1 (ids 3 and 2) and leave the highlight on row 1
+        widget.move_selection(0);
+        widget.acknowledge_selected();
+        widget.move_selection(1);
+        widget.acknowledge_selected();
+
+        widget.clear_acknowledged();
+        assert_eq!(listed_ids(&widget), [1, 0]);
+        assert_eq!(widget.selected, Some(0), "row 1 is now id 0");
+
+        // Clearing the last rows moves the highlight up to what is left
+        widget.acknowledge_selected();
+        widget.clear_acknowledged();
+        assert_eq!(widget.selected, Some(1));
+        widget.acknowledge_selected();
+        widget.clear_acknowledged();
+        assert_eq!(widget.selected, None);
+        assert!(widget.detector.anomalies.is_empty());
+    }
+
+    #[test]
+    fn acknowledgments_survive_a_render() {
+        let mut widget = listing(&[
+            ("Latency", AnomalySeverity::High),
+            ("Throughput", AnomalySeverity::Critical),
+        ]);
+        widget.is_selected = true;
+        widget.move_selection(0);
+        widget.move_selection(1);
+        widget.acknowledge_selected();
+
+        let screen = rendered(&widget, 120, 20);
+        assert!(screen.contains("✓ [High] Latency"), "{}", screen);
+        assert!(screen.contains("2 anomalies, 1 unacknowledged"), "{}", screen);
+
+        // Rendering again changes nothing and the row can still be taken back
+        assert_eq!(rendered(&widget, 120, 20), screen);
+        assert_eq!(widget.selected, Some(0));
+        widget.acknowledge_selected();
+        assert_eq!(widget.detector.unacknowledged_count(), 2);
+    }
+
+    /// Z-score only, with a `cooldown_secs` cool-down after each report
+    fn cooled(cooldown_secs: f64) -> AnomalyDetector {
+        let mut detector = detector(strategies(&[StrategyKind::ZScore]));
+        detector.config.cooldown_secs = Some(cooldown_secs);
+        detector
+    }
+
+    fn severities(detector: &AnomalyDetector) -> Vec<AnomalySeverity> {
+        detector.anomalies.iter().map(|anomaly| anomaly.severity.clone()).collect()
+    }
+
+    #[test]
+    fn burst_within_the_cooldown_is_counted_not_listed() {
+        // Five spikes a second apart, then one more after the 5s cool-down
+        let mut values = steady(50);
+        values.extend([150.0; 5]);
+        values.extend(steady(2));
+        values.push(150.0);
+
+        let mut detector = cooled(5.0);
+        let found = feed(&mut detector, &values);
+        assert_eq!(found, [(50, StrategyKind::ZScore), (57, StrategyKind::ZScore)]);
+        assert_eq!(detector.suppressed_count(), 4);
+
+        // Without a cool-down every spike is its own anomaly
+        let mut uncooled = cooled(0.0);
+        assert_eq!(feed(&mut uncooled, &values).len(), 6);
+        assert_eq!(uncooled.suppressed_count(), 0);
+    }
+
+    #[test]
+    fn more_severe_anomaly_gets_through_the_cooldown() {
+        let mut values = steady(50);
+        values.extend([103.2, 110.0, 110.0]);
+
+        let mut detector = cooled(5.0);
+        let found = feed(&mut detector, &values);
+        assert_eq!(found.iter().map(|&(i, _)| i).collect::<Vec<_>>(), [50, 51]);
+        let reported = severities(&detector);
+        assert!(reported[0] < AnomalySeverity::Critical, "{:?}", reported);
+        assert_eq!(reported[1], AnomalySeverity::Critical);
+        // The third is no worse than the second, so the cool-down holds it
+        assert_eq!(detector.suppressed_count(), 1);
+    }
+
+    #[test]
+    fn suppression_window_holds_back_every_anomaly() {
+        let now = chrono::Utc::now();
+        let mut detector = cooled(0.0);
+        for (i, value) in steady(50).into_iter().enumerate() {
+            detector.add_data_point_at("Latency".to_string(), value, now - chrono::Duration::seconds(60 - i as i64));
+        }
+
+        detector.suppress(std::time::Duration::from_secs(30));
+        assert!(detector.suppression_remaining().is_some());
+        for i in 0..3 {
+            detector.add_data_point_at("Latency".to_string(), 150.0 + i as f64, now + chrono::Duration::seconds(i));
+        }
+        assert!(detector.anomalies.is_empty());
+        assert_eq!(detector.suppressed_count(), 3);
+
+        // After the window anomalies are reported again
+        detector.add_data_point_at("Latency".to_string(), 400.0, now + chrono::Duration::seconds(31));
+        assert_eq!(detector.anomalies.len(), 1);
+        assert_eq!(detector.suppressed_count(), 3);
+    }
+
+    #[test]
+    fn summary_shows_the_suppressed_count() {
+        let mut widget = listing(&[]);
+        widget.detector = cooled(5.0);
+        assert!(!rendered(&widget, 120, 20).contains("suppressed"));
+
+        let mut values = steady(50);
+        values.extend([150.0; 3]);
+        feed(&mut widget.detector, &values);
+        let screen = rendered(&widget, 120, 20);
+        assert!(screen.contains("1 anomalies, 1 unacknowledged (+2 suppressed)"), "{}", screen);
+
+        // Clearing the data starts the count over
+        widget.clear();
+        assert!(rendered(&widget, 120, 20).contains("No anomalies detected"));
+        assert!(!rendered(&widget, 120, 20).contains("suppressed"));
+    }
+
+    #[test]
+    fn flat_history_and_undefined_samples_never_reach_a_description() {
+        let mut values = vec![100.0; 50];
+        values.extend([f64::NAN, f64::INFINITY, 150.0, f64::NEG_INFINITY, 100.0, 0.0]);
+
+        let kinds = [StrategyKind::ZScore, StrategyKind::Ewma, StrategyKind::RateOfChange];
+        let mut detector = detector(strategies(&kinds));
+        let found = feed(&mut detector, &values);
+        assert!(found.iter().any(|&(i, _)| i == 52), "{:?}", found);
+        assert!(found.iter().all(|&(i, _)| values[i].is_finite()), "{:?}", found);
+        for anomaly in &detector.anomalies {
+            let (low, high) = anomaly.expected_range;
+            assert!(low.is_finite() && high.is_finite(), "{:?}", anomaly.expected_range);
+            assert!(!anomaly.description.contains("NaN") && !anomaly.description.contains("inf"), "{}", anomaly.description);
+        }
     }
 }
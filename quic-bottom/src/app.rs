@@ -1,170 +1,1047 @@
 //! Simplified QUIC Bottom application
 //! 
-//! A minimal TUI application for QUIC monitoring
+//! A minimal TUI application for QUIC monitoring, plus the pieces every
+//! `quic-bottom` mode shares: terminal handling and the event loop
+//! (`AppCore`) and the quit keys.
+//!
+//! The event loop draws only when something changed: a key or mouse event,
+//! a resize, or a tick that reports a change, with ticks run on the mode's
+//! interval and as soon as new samples are stored. Frames are capped at
+//! `max_fps`, and input is read from an async stream, so neither a burst of
+//! samples nor a burst of keys holds up the other or the frames.
+//!
+//! The terminal is put back in cooked mode on every way out of a mode:
+//! normal quit, an early error return (`TerminalGuard`'s drop) and a panic
+//! (a hook that restores it before the panic message prints).
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent, KeyModifiers, MouseEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Sparkline},
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
     Frame, Terminal,
 };
 use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+use std::time::Instant;
 use tokio::time::Duration;
+use tokio_stream::{Stream, StreamExt};
 
 use crate::{
-    metrics::{get_current_metrics, init_metrics},
-    widgets::{QUICConnectionWidget, QUICLatencyWidget, QUICNetworkWidget, QUICThroughputWidget},
-    improved_layout::{create_improved_layout, render_spacer},
+    config::{NetworkWidgetConfig, QuicBottomConfig, WidgetConfig, DEFAULT_MAX_FPS},
+    config_watch::{failure_summary, ConfigUpdate, ConfigWatcher},
+    export::{export_session, ExportConfig},
+    exporters::influx::InfluxQueue,
+    metrics::{
+        format_span, Freshness, MetricsHandle, QUICMetrics, StalenessConfig,
+    },
+    redact::{RedactionConfig, Redactor},
+    replay::{QlogTrace, Replayer},
+    report::{write_report, ReportConfig, ReportPaths, SessionReport},
+    shutdown,
+    status::{self, StatusBar},
+    sync::catching_panic,
+    theme::{Theme, ThemeCycle},
+    widgets::{
+        last_update_span, render_header, sla_span, source_color, LatencyOverlayWidget, QUICConnectionWidget,
+        QUICLatencyWidget, QUICNetworkWidget, QUICStreamsWidget, QUICThroughputWidget,
+    },
+    improved_layout::{create_improved_layout, render_no_widgets, render_unavailable, LayoutTree, WidgetKind},
+    keybindings::{render_help_overlay, Action, AppKind, KeyMap},
 };
 
+/// A full-screen mode driven by `AppCore`
+pub trait TuiMode {
+    /// Pull in new data; returns whether the screen would differ from the last frame
+    ///
+    /// Called every `poll_interval` and when samples are stored. Input
+    /// always redraws, so only changes made here need to be reported.
+    fn tick(&mut self) -> bool;
+
+    /// Draw one frame
+    fn draw(&mut self, f: &mut Frame);
+
+    fn handle_key(&mut self, key: KeyEvent);
+
+    /// Clicks and scrolling; ignored unless the mode hit-tests its widgets
+    fn handle_mouse(&mut self, _mouse: MouseEvent) {}
+
+    fn should_quit(&self) -> bool;
+
+    /// Time between ticks, when it differs from `AppCore`'s
+    fn poll_interval(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Terminal setup/teardown and the event loop shared by every TUI mode
+pub struct AppCore {
+    /// Time between ticks
+    poll_interval: Duration,
+    /// Shortest time between two frames
+    frame_interval: Duration,
+}
+
+impl AppCore {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            frame_interval: frame_interval(DEFAULT_MAX_FPS),
+        }
+    }
+
+    /// Draw at most `max_fps` frames per second
+    pub fn with_max_fps(mut self, max_fps: u32) -> Self {
+        self.frame_interval = frame_interval(max_fps);
+        self
+    }
+
+    /// Run `mode` until it asks to quit or SIGINT/SIGTERM arrives
+    ///
+    /// The terminal is restored even when the loop fails or panics.
+    pub async fn run<M: TuiMode>(&self, mode: &mut M) -> Result<()> {
+        shutdown::watch();
+        let _guard = TerminalGuard::enter()?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        self.event_loop(&mut terminal, EventStream::new(), mode).await
+    }
+
+    /// Tick, draw and dispatch `input` to `mode` until it quits or `input` ends
+    async fn event_loop<B, I, M>(&self, terminal: &mut Terminal<B>, mut input: I, mode: &mut M) -> Result<()>
+    where
+        B: Backend,
+        B::Error: Send + Sync + 'static,
+        I: Stream<Item = io::Result<Event>> + Unpin,
+        M: TuiMode,
+    {
+        let mut samples = status::watch_samples();
+        let mut next_tick = Instant::now();
+        let mut last_tick = next_tick;
+        let mut last_frame: Option<Instant> = None;
+        // The first frame is drawn before anything happens
+        let mut dirty = true;
+
+        while !mode.should_quit() && shutdown::requested().is_none() {
+            let now = Instant::now();
+            if now >= next_tick {
+                dirty |= mode.tick();
+                last_tick = now;
+                next_tick = now + mode.poll_interval().unwrap_or(self.poll_interval);
+            }
+            let next_frame = last_frame.map_or(now, |at| at + self.frame_interval);
+            if dirty && now >= next_frame {
+                let started = Instant::now();
+                terminal.draw(|f| mode.draw(f))?;
+                status::record_frame(started.elapsed());
+                last_frame = Some(started);
+                dirty = false;
+            }
+
+            let wake = if dirty { next_tick.min(next_frame) } else { next_tick };
+            tokio::select! {
+                event = input.next() => match event {
+                    Some(Ok(Event::Key(key))) => {
+                        mode.handle_key(key);
+                        dirty = true;
+                    }
+                    Some(Ok(Event::Mouse(mouse))) => {
+                        mode.handle_mouse(mouse);
+                        dirty = true;
+                    }
+                    Some(Ok(Event::Resize(..))) => dirty = true,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => break,
+                },
+                // Pull the new samples in with a tick, no more often than frames are drawn
+                Ok(()) = samples.changed() => {
+                    next_tick = next_tick.min(last_tick + self.frame_interval);
+                }
+                _ = tokio::time::sleep_until(wake.into()) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Time between frames at `max_fps`
+fn frame_interval(max_fps: u32) -> Duration {
+    Duration::from_secs_f64(1.0 / f64::from(max_fps.max(1)))
+}
+
+/// Set while raw mode and the alternate screen are on
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Raw mode, the alternate screen and mouse capture, undone on drop
+pub struct TerminalGuard {
+    _private: (),
+}
+
+impl TerminalGuard {
+    /// Take over the terminal, installing the restoring panic hook first
+    pub fn enter() -> Result<Self> {
+        install_panic_hook();
+        enable_raw_mode()?;
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        // Built before the remaining setup so a failure there restores too
+        let guard = Self { _private: () };
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave raw mode, the alternate screen and mouse capture if a mode took them
+///
+/// Best effort and idempotent: it runs from drops and the panic hook, where
+/// there is nobody left to report an error to.
+pub fn restore_terminal() {
+    if TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show);
+    }
+}
+
+/// Chain a hook that restores the terminal before the previous hook prints
+///
+/// Panics that `sync::catch_panic` answers leave the terminal alone, since
+/// the UI keeps running after them.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !catching_panic() {
+                restore_terminal();
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Whether `key` quits every mode: q, Esc or Ctrl-C
+pub fn is_quit_key(key: &KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
 /// Main application state for QUIC Bottom
 pub struct QuicBottomApp {
     latency_widget: QUICLatencyWidget,
     throughput_widget: QUICThroughputWidget,
     connection_widget: QUICConnectionWidget,
     network_widget: QUICNetworkWidget,
+    streams_widget: QUICStreamsWidget,
+    /// Latency and handshake times as histograms instead of sparklines ('H')
+    show_histograms: bool,
+    overlay_widget: LatencyOverlayWidget,
+    /// Latency of every source on one chart instead of the latency widget ('o')
+    show_overlay: bool,
+    /// Sender the widgets follow (Tab); all samples, interleaved, when unset
+    source: Option<String>,
+    /// `[widgets.network] loss_disagreement_margin`
+    loss_margin: f64,
+    /// Which of the widgets are drawn
+    widget_config: WidgetConfig,
+    /// Grid of the dashboard, from `[layout]`
+    layout: LayoutTree,
     should_quit: bool,
+    paused: bool,
+    show_help: bool,
+    keymap: KeyMap,
+    /// Configured theme and the built-in ones the cycle key steps through
+    themes: ThemeCycle,
+    /// History position the widgets have caught up to; held while paused
+    seen_mark: u64,
+    /// What the last tick's screen showed besides the effects of input
+    screen: Option<ScreenState>,
     update_interval: Duration,
+    /// Frame rate cap, from `max_fps`
+    max_fps: u32,
+    export_config: ExportConfig,
+    report_config: ReportConfig,
+    redactor: Redactor,
+    /// Whether exports and reports are redacted; `[redaction] enabled`, then toggled with 'P'
+    redact: bool,
+    status: Option<(String, Instant)>,
+    staleness: StalenessConfig,
+    /// qlog trace played back instead of the bridge API's metrics
+    replay: Option<Replayer>,
+    /// Events of the replayed trace that were skipped
+    replay_skipped: usize,
+    /// InfluxDB exporter whose state the footer shows
+    exporter: Option<Arc<InfluxQueue>>,
+    /// Ingest rate, totals and refusals in the footer
+    status_bar: StatusBar,
+    /// Id of the last remote reset the widgets were cleared for
+    seen_reset: u64,
+    metrics: MetricsHandle,
+    /// Config file reloaded while running
+    config_watcher: Option<ConfigWatcher>,
 }
 
+/// What a frame shows besides the effects of input, compared between ticks to skip unchanged frames
+#[derive(Debug, Clone, PartialEq)]
+struct ScreenState {
+    /// History position the widgets have caught up to
+    seen_mark: u64,
+    /// When the footer notice was shown, while it is
+    notice: Option<Instant>,
+    /// Whole seconds since the last sample, as the header counts them
+    age_secs: Option<u64>,
+    freshness: Freshness,
+    /// Position of the replay, which moves while it plays
+    replay: Option<Duration>,
+}
+
+/// How far Left/Right seek a replay
+const REPLAY_SEEK_SECS: i64 = 10;
+
+/// How long a footer status message stays visible
+const STATUS_DURATION: Duration = Duration::from_secs(4);
+
+/// The dashboard when `[layout]` sets none
+fn default_layout() -> LayoutTree {
+    LayoutTree::columns(&[
+        &[WidgetKind::Latency, WidgetKind::Throughput],
+        &[WidgetKind::Connections, WidgetKind::Network, WidgetKind::Streams],
+    ])
+}
+
+/// Top-level config keys applied when the config file changes while running
+const RELOADABLE: &[&str] = &[
+    "update_interval",
+    "colors",
+    "keybindings",
+    "staleness",
+    "derived",
+    "sla",
+    "health",
+    "widgets",
+    "layout",
+];
+
 impl QuicBottomApp {
+    /// App over the global metrics handle, as used by the FFI functions
     pub async fn new(interval_ms: u64) -> Result<Self> {
-        // Initialize metrics system
-        init_metrics()?;
+        let metrics = MetricsHandle::global();
 
         Ok(Self {
             latency_widget: QUICLatencyWidget::new(1000),
             throughput_widget: QUICThroughputWidget::new(1000),
             connection_widget: QUICConnectionWidget::new(),
             network_widget: QUICNetworkWidget::new(),
+            streams_widget: QUICStreamsWidget::new(1000),
+            show_histograms: false,
+            overlay_widget: LatencyOverlayWidget::new(),
+            show_overlay: false,
+            source: None,
+            loss_margin: NetworkWidgetConfig::default().loss_disagreement_margin,
+            widget_config: WidgetConfig::default(),
+            layout: default_layout(),
             should_quit: false,
+            paused: false,
+            show_help: false,
+            keymap: KeyMap::default(),
+            themes: ThemeCycle::default(),
+            seen_mark: metrics.history_mark(),
+            screen: None,
             update_interval: Duration::from_millis(interval_ms),
+            max_fps: DEFAULT_MAX_FPS,
+            export_config: ExportConfig::default(),
+            report_config: ReportConfig::default(),
+            redactor: Redactor::new(&RedactionConfig::default()),
+            redact: false,
+            status: None,
+            staleness: StalenessConfig::default(),
+            replay: None,
+            replay_skipped: 0,
+            exporter: None,
+            status_bar: StatusBar::new(),
+            seen_reset: metrics.last_reset().map_or(0, |event| event.id),
+            metrics,
+            config_watcher: None,
         })
     }
 
+    /// Show the samples of `metrics` instead of the global handle's
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.seen_reset = metrics.last_reset().map_or(0, |event| event.id);
+        self.seen_mark = metrics.history_mark();
+        self.metrics = metrics;
+        self
+    }
+
+    /// Apply export, redaction, keybinding and color settings from the config file
+    pub fn with_config(mut self, config: &QuicBottomConfig) -> Self {
+        let (keymap, mut warnings) = KeyMap::from_config(&config.keybindings);
+        self.keymap = keymap;
+        match config.colors.theme() {
+            Ok(theme) => self.themes = ThemeCycle::new(theme),
+            Err(e) => warnings.push(format!("{:#}", e)),
+        }
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        if !warnings.is_empty() {
+            self.status = Some((warnings.join("; "), Instant::now()));
+        }
+        self.export_config = config.export.clone();
+        self.report_config = config.report.clone();
+        self.redactor = Redactor::new(&config.redaction);
+        self.redact = config.redaction.enabled;
+        self.staleness = config.staleness.clone();
+        self.loss_margin = config.widgets.network.loss_disagreement_margin;
+        self.network_widget = QUICNetworkWidget::new().with_loss_margin(self.loss_margin);
+        self.widget_config = config.widgets.clone();
+        self.layout = config.layout.tree().unwrap_or_else(default_layout);
+        self.max_fps = config.max_fps;
+        self
+    }
+
+    /// Apply changes to the config file without restarting; see `RELOADABLE`
+    pub fn with_config_watcher(mut self, watcher: ConfigWatcher) -> Self {
+        self.config_watcher = Some(watcher);
+        self
+    }
+
+    /// Show the state of an InfluxDB exporter in the footer
+    pub fn with_exporter(mut self, queue: Arc<InfluxQueue>) -> Self {
+        self.exporter = Some(queue);
+        self
+    }
+
+    /// Show a qlog replay instead of the metrics posted to the bridge API
+    pub fn with_replay(mut self, trace: QlogTrace, speed: f64) -> Self {
+        self.replay_skipped = trace.skipped_events;
+        self.replay = Some(Replayer::new(trace.samples, speed));
+        self
+    }
+
     pub async fn run(&mut self) -> Result<()> {
-        // Setup terminal
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+        AppCore::new(self.update_interval)
+            .with_max_fps(self.max_fps)
+            .run(self)
+            .await?;
+        if let Some(queue) = &self.exporter {
+            shutdown::flush_exporter(queue).await;
+        }
 
-        // Main event loop
-        loop {
-            if self.should_quit {
-                break;
+        // Written after the terminal is restored, so the path can be printed
+        if self.report_config.on_exit && !self.metrics.history(None, None, 1).is_empty() {
+            match self.write_report() {
+                Ok(paths) => println!("{}", paths),
+                Err(e) => eprintln!("Report failed: {}", e),
             }
+        }
+        Ok(())
+    }
 
-            // Update widgets with latest metrics
-            self.update_widgets();
+    /// Summarize the stored history; this app runs no anomaly or correlation analysis
+    fn write_report(&self) -> Result<ReportPaths> {
+        let samples = self.metrics.history(None, None, usize::MAX);
+        let report = SessionReport::build(&samples, &[], &[], chrono::Utc::now())
+            .with_health(self.metrics.health_score());
+        write_report(&self.report_config, &report, self.redactor())
+    }
 
-            // Render the UI
-            terminal.draw(|f| self.ui(f))?;
+    /// Reload the config file if it changed, keeping the running config when it fails to load
+    fn check_config(&mut self) {
+        let Some(result) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) else {
+            return;
+        };
+        let message = match result {
+            Ok(update) => {
+                let mut messages = vec![update.summary(RELOADABLE)];
+                messages.extend(self.apply_config_update(&update));
+                messages.join("; ")
+            }
+            Err(e) => {
+                log::warn!("{:#}", e);
+                failure_summary(&e)
+            }
+        };
+        log::info!("{}", message);
+        self.status = Some((message, Instant::now()));
+    }
 
-            // Handle events
-            if event::poll(self.update_interval)? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key_event(key);
-                }
+    /// Switch to the reloadable settings that changed, returning warnings
+    fn apply_config_update(&mut self, update: &ConfigUpdate) -> Vec<String> {
+        let config = &update.config;
+        let mut warnings = Vec::new();
+        if update.changed("update_interval") {
+            self.update_interval = Duration::from_millis(config.update_interval);
+        }
+        if update.changed("colors") {
+            match config.colors.theme() {
+                Ok(theme) => self.themes = ThemeCycle::new(theme),
+                Err(e) => warnings.push(format!("{:#}", e)),
+            }
+        }
+        if update.changed("keybindings") {
+            let (keymap, keymap_warnings) = KeyMap::from_config(&config.keybindings);
+            self.keymap = keymap;
+            warnings.extend(keymap_warnings);
+        }
+        if update.changed("staleness") {
+            self.staleness = config.staleness.clone();
+        }
+        if update.changed("derived") {
+            self.metrics.set_derived_config(config.derived.clone());
+        }
+        if update.changed("sla") {
+            self.metrics.set_sla_config(config.sla.clone());
+        }
+        if update.changed("health") {
+            self.metrics.set_health_config(config.health.clone());
+        }
+        if update.changed("widgets") {
+            self.loss_margin = config.widgets.network.loss_disagreement_margin;
+            self.network_widget.set_loss_margin(self.loss_margin);
+            self.widget_config = config.widgets.clone();
+        }
+        if update.changed("layout") {
+            self.layout = config.layout.tree().unwrap_or_else(default_layout);
+        }
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        warnings
+    }
+
+    fn update_widgets(&mut self) {
+        if let Some(mut replayer) = self.replay.take() {
+            for (_, metrics) in replayer.advance() {
+                self.apply_metrics(metrics);
             }
+            self.replay = Some(replayer);
+            return;
         }
 
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
+        self.apply_remote_reset();
 
-        Ok(())
+        let freshness = self.staleness.freshness(self.metrics.last_update_age());
+        self.connection_widget.set_stale(freshness == Freshness::Stale);
+
+        // Samples keep landing in the history while paused and are replayed on resume
+        if self.paused {
+            return;
+        }
+
+        if self.catch_up() == 0 {
+            return;
+        }
+        let deltas = match self.source.as_deref() {
+            Some(source) => self.metrics.source_packet_deltas(source),
+            None => self.metrics.packet_deltas(),
+        };
+        self.connection_widget
+            .set_handshake_stats(self.metrics.handshake_stats());
+        self.connection_widget
+            .set_lifecycle(self.metrics.connection_stats());
+        self.network_widget
+            .set_computed_loss(deltas.and_then(|deltas| deltas.loss_pct()));
     }
 
-    fn update_widgets(&mut self) {
-        if let Some(metrics) = get_current_metrics() {
-            // Update latency widget
-            self.latency_widget.update(metrics.latency);
+    /// Whether `metrics` belongs to the selected source
+    fn shows(&self, metrics: &QUICMetrics) -> bool {
+        self.source.is_none() || metrics.source == self.source
+    }
+
+    /// Follow the next labelled source, or all of them after the last one
+    fn cycle_source(&mut self) {
+        let names: Vec<String> = self.metrics.sources().into_iter().map(|s| s.source).collect();
+        if names.is_empty() {
+            self.status = Some(("No labelled sources yet".to_string(), Instant::now()));
+            return;
+        }
+        self.source = match &self.source {
+            None => names.first().cloned(),
+            Some(current) => names
+                .iter()
+                .position(|name| name == current)
+                .and_then(|i| names.get(i + 1))
+                .cloned(),
+        };
+
+        // Redraw the graphs from the stored samples of the new selection
+        self.reset_widgets();
+        let samples: Vec<QUICMetrics> = self
+            .metrics
+            .history(None, None, usize::MAX)
+            .into_iter()
+            .filter(|metrics| self.shows(metrics))
+            .collect();
+        for metrics in &samples {
+            self.apply_metrics(metrics);
+        }
+        self.seen_mark = self.metrics.history_mark();
+
+        let message = match &self.source {
+            Some(source) => format!("Showing source {}", source),
+            None => "Showing all sources".to_string(),
+        };
+        self.status = Some((message, Instant::now()));
+    }
 
-            // Update throughput widget
-            self.throughput_widget.update(metrics.throughput);
+    fn apply_metrics(&mut self, metrics: &QUICMetrics) {
+        // Update latency widget
+        self.latency_widget.update(metrics.latency);
 
-            // Update connection widget
-            self.connection_widget.update(
-                metrics.connections,
-                metrics.errors,
-                metrics.connections + metrics.errors,
-            );
+        // Update throughput widget
+        self.throughput_widget.update(metrics.throughput);
 
-            // Update network widget
-            self.network_widget.update(
-                metrics.packet_loss,
-                metrics.retransmits,
-                "BBRv2".to_string(), // TODO: Get actual CC algorithm
-            );
+        // Update connection widget
+        self.connection_widget.update(metrics.connections, metrics.errors);
+        if metrics.handshake_time > 0.0 {
+            self.connection_widget.add_handshake_time(metrics.handshake_time);
         }
+
+        // Update network widget
+        self.network_widget.update(
+            metrics.packet_loss,
+            metrics.retransmits,
+            "BBRv2".to_string(), // TODO: Get actual CC algorithm
+        );
+        self.network_widget.set_congestion_window(metrics.congestion_window);
+
+        self.streams_widget
+            .update(metrics.timestamp, metrics.streams, metrics.throughput);
     }
 
-    fn handle_key_event(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.should_quit = true;
-            }
-            KeyCode::Esc => {
-                self.should_quit = true;
+    /// Clear the widgets after an API reset of the stats, so empty graphs come with an explanation
+    fn apply_remote_reset(&mut self) {
+        let Some(event) = self.metrics.last_reset().filter(|event| event.id > self.seen_reset) else {
+            return;
+        };
+        self.seen_reset = event.id;
+        if event.outcome.scope.stats {
+            self.source = None;
+            self.reset_widgets();
+        }
+        self.seen_mark = self.metrics.history_mark();
+
+        let message = format!(
+            "Remote reset by {}: {} samples dropped, segment {}",
+            event.source, event.outcome.history_dropped, event.outcome.segment
+        );
+        log::info!("{}", message);
+        self.status = Some((message, Instant::now()));
+    }
+
+    /// Start the graphs over, for a replay that seeks backwards or a remote reset
+    fn reset_widgets(&mut self) {
+        self.latency_widget = QUICLatencyWidget::new(1000);
+        self.throughput_widget = QUICThroughputWidget::new(1000);
+        self.connection_widget = QUICConnectionWidget::new();
+        self.network_widget = QUICNetworkWidget::new().with_loss_margin(self.loss_margin);
+        self.streams_widget = QUICStreamsWidget::new(1000);
+        self.latency_widget.set_histogram(self.show_histograms);
+        self.connection_widget.set_histogram(self.show_histograms);
+    }
+
+    /// Replay controls; returns false for keys a replay does not use
+    fn handle_replay_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(replayer) = self.replay.as_mut() else {
+            return false;
+        };
+        let rewound = match self.keymap.action(AppKind::Basic, key) {
+            Some(Action::Pause) => {
+                replayer.toggle_pause();
+                false
             }
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Action::ScrollBack) => replayer.seek_by(-REPLAY_SEEK_SECS),
+            Some(Action::ScrollForward) => replayer.seek_by(REPLAY_SEEK_SECS),
+            Some(Action::Oldest) => replayer.seek(Duration::ZERO),
+            Some(Action::Newest) => replayer.seek(replayer.duration()),
+            _ => return false,
+        };
+        if rewound {
+            self.reset_widgets();
+        }
+        true
+    }
+
+    fn toggle_pause(&mut self) {
+        if !self.paused {
+            self.paused = true;
+            return;
+        }
+
+        let backfilled = self.catch_up();
+        self.paused = false;
+        self.status = Some((format!("Resumed, backfilled {} samples", backfilled), Instant::now()));
+    }
+
+    /// Feed the samples stored since the last call to the widgets, returning how many were shown
+    fn catch_up(&mut self) -> usize {
+        let (samples, mark) = self.metrics.history_catch_up(self.seen_mark);
+        self.seen_mark = mark;
+        let shown: Vec<&QUICMetrics> = samples.iter().filter(|metrics| self.shows(metrics)).collect();
+        for metrics in &shown {
+            self.apply_metrics(metrics);
+        }
+        shown.len()
+    }
+
+    /// What the screen shows now besides the effects of input
+    fn screen_state(&self) -> ScreenState {
+        let age = self.metrics.last_update_age();
+        ScreenState {
+            seen_mark: self.seen_mark,
+            notice: self
+                .status
+                .as_ref()
+                .map(|(_, shown_at)| *shown_at)
+                .filter(|shown_at| shown_at.elapsed() < STATUS_DURATION),
+            age_secs: age.map(|age| age.as_secs()),
+            freshness: self.staleness.freshness(age),
+            replay: self.replay.as_ref().map(Replayer::position),
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        if self.handle_replay_key(&key) {
+            return;
+        }
+
+        let Some(action) = self.keymap.action(AppKind::Basic, &key) else {
+            return;
+        };
+        match action {
+            Action::Quit => {
                 self.should_quit = true;
             }
-            KeyCode::Char('r') => {
+            Action::Reset => {
                 // Refresh/reset metrics
                 log::info!("Refreshing metrics");
             }
-            KeyCode::Char('h') => {
-                // Show help
-                log::info!("Help: q/ESC to quit, r to refresh, h for help");
+            Action::Help => {
+                self.show_help = true;
+            }
+            Action::Pause => {
+                self.toggle_pause();
+            }
+            Action::Export => {
+                self.export();
+            }
+            Action::Report => {
+                let message = match self.write_report() {
+                    Ok(paths) => paths.to_string(),
+                    Err(e) => format!("Report failed: {}", e),
+                };
+                self.status = Some((message, Instant::now()));
+            }
+            Action::ToggleRedaction => {
+                self.redact = !self.redact;
+                let message = if self.redact {
+                    "Redacting exports and reports"
+                } else {
+                    "Exports and reports are not redacted"
+                };
+                self.status = Some((message.to_string(), Instant::now()));
+            }
+            Action::ToggleHistograms => {
+                self.show_histograms = !self.show_histograms;
+                self.latency_widget.set_histogram(self.show_histograms);
+                self.connection_widget.set_histogram(self.show_histograms);
+            }
+            Action::CycleSource => {
+                self.cycle_source();
+            }
+            Action::ToggleOverlay => {
+                self.show_overlay = !self.show_overlay;
+            }
+            Action::CycleTheme => {
+                let message = format!("Theme: {}", self.themes.advance().label());
+                self.status = Some((message, Instant::now()));
             }
             _ => {}
         }
     }
 
+    /// The redactor, while exports and reports are redacted
+    fn redactor(&self) -> Option<&Redactor> {
+        self.redact.then_some(&self.redactor)
+    }
+
+    /// Export the stored metrics history to CSV and JSON
+    fn export(&mut self) {
+        let samples = self.metrics.history(None, None, usize::MAX);
+        let message = match export_session(&self.export_config, &samples, self.redactor()) {
+            Ok(summary) => summary.to_string(),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        log::info!("{}", message);
+        self.status = Some((message, Instant::now()));
+    }
+
     fn ui(&self, f: &mut Frame) {
-        let chunks = create_improved_layout(f.area());
+        // Disabled widgets leave their room to the others
+        let layout = create_improved_layout(f.area(), &self.layout, &|kind| self.widget_config.is_enabled(kind));
+        let theme = self.themes.current();
 
         // Header
-        self.render_header(f, chunks[0]);
+        self.render_header(f, layout.header, theme);
 
         // Render widgets with better spacing
-        self.latency_widget.render(f, chunks[1]);
-        self.throughput_widget.render(f, chunks[2]);
-        self.connection_widget.render(f, chunks[3]);
-        self.network_widget.render(f, chunks[4]);
+        if layout.widgets.is_empty() {
+            render_no_widgets(f, layout.main, theme);
+        }
+        for (kind, area) in layout.widgets {
+            match kind {
+                WidgetKind::Latency if self.show_overlay => self
+                    .metrics
+                    .with_source_time_series(|sources| self.overlay_widget.render(f, area, sources, theme)),
+                WidgetKind::Latency => self.latency_widget.render(f, area, theme),
+                WidgetKind::Throughput => self.throughput_widget.render(f, area, theme),
+                WidgetKind::Connections => self.connection_widget.render(f, area, theme),
+                WidgetKind::Network => self.network_widget.render(f, area, theme),
+                WidgetKind::Streams => self.streams_widget.render(f, area, theme),
+                _ => render_unavailable(f, area, kind, theme),
+            }
+        }
 
         // Footer
-        self.render_footer(f, chunks[5]);
+        self.render_footer(f, layout.footer, theme);
+
+        if self.show_help {
+            render_help_overlay(f, AppKind::Basic, &self.keymap, theme);
+        }
     }
 
-    fn render_header(&self, f: &mut Frame, area: Rect) {
+    fn render_header(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let header_text = "QUIC Bottom - Real-time QUIC Protocol Monitor";
-        let header = Paragraph::new(header_text)
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(header, area);
+        let mut spans = vec![Span::raw(header_text), Span::raw("  ")];
+        let paused = match &self.replay {
+            Some(replayer) => {
+                spans.push(Span::styled(
+                    format!(
+                        "Replay {} / {} ({}x)",
+                        format_span(replayer.position()),
+                        format_span(replayer.duration()),
+                        replayer.speed()
+                    ),
+                    Style::default().fg(theme.info),
+                ));
+                replayer.is_paused()
+            }
+            None => {
+                let age = self.metrics.last_update_age();
+                spans.push(last_update_span(age, self.staleness.freshness(age), theme));
+                self.paused
+            }
+        };
+        let sources = self.metrics.sources();
+        if !sources.is_empty() {
+            spans.push(Span::raw("  "));
+            let selected = self
+                .source
+                .as_ref()
+                .and_then(|source| sources.iter().position(|s| &s.source == source));
+            spans.push(match selected {
+                Some(i) => Span::styled(
+                    format!("Source: {} ({}/{})", sources[i].source, i + 1, sources.len()),
+                    Style::default().fg(source_color(theme, i)),
+                ),
+                None => Span::styled(
+                    format!("Source: all ({})", sources.len()),
+                    Style::default().fg(theme.info),
+                ),
+            });
+        }
+        if let Some(sla) = sla_span(&self.metrics.sla(), theme) {
+            spans.push(Span::raw("  "));
+            spans.push(sla);
+        }
+        if paused {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                " PAUSED ",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        render_header(f, area, Line::from(spans), &self.metrics.health_score(), theme);
     }
 
-    fn render_footer(&self, f: &mut Frame, area: Rect) {
-        let footer_text = "Press 'q' to quit, 'r' to refresh, 'h' for help";
-        let footer = Paragraph::new(footer_text)
-            .style(Style::default().fg(Color::Gray))
+    fn render_footer(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let mut footer = match (&self.status, &self.replay) {
+            (Some((message, shown_at)), _) if shown_at.elapsed() < STATUS_DURATION => Line::from(message.clone()),
+            (_, Some(replayer)) => Line::from(format!(
+                "{} unsupported qlog events skipped | '{}' pause, {}/{} seek {}s, {}/{}, '{}' quit{}",
+                self.replay_skipped,
+                self.keymap.key(Action::Pause),
+                self.keymap.key(Action::ScrollBack),
+                self.keymap.key(Action::ScrollForward),
+                REPLAY_SEEK_SECS,
+                self.keymap.key(Action::Oldest),
+                self.keymap.key(Action::Newest),
+                self.keymap.key(Action::Quit),
+                if replayer.is_finished() { " | finished" } else { "" }
+            )),
+            _ => self
+                .status_bar
+                .line(self.metrics.history_fill(), &self.keymap.key(Action::Help), theme),
+        };
+        if let Some(queue) = &self.exporter {
+            footer.spans.insert(0, Span::raw(format!("{} | ", queue.status_line())));
+        }
+        let footer = Paragraph::new(footer)
+            .style(Style::default().fg(theme.muted))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(footer, area);
     }
-}
\ No newline at end of file
+}
+
+impl TuiMode for QuicBottomApp {
+    fn tick(&mut self) -> bool {
+        self.check_config();
+        self.update_widgets();
+        self.status_bar.sample();
+        let screen = self.screen_state();
+        let changed = self.screen.as_ref() != Some(&screen);
+        self.screen = Some(screen);
+        changed
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        self.ui(f);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.handle_key_event(key);
+    }
+
+    fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    fn poll_interval(&self) -> Option<Duration> {
+        Some(self.update_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEventKind;
+    use ratatui::backend::TestBackend;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Counts frames, ticks and keys for `run_for`
+    struct Counting {
+        /// Whether every tick reports a change, as a mode fed data would
+        busy: bool,
+        until: Instant,
+        ticks: usize,
+        draws: usize,
+        keys: usize,
+    }
+
+    impl TuiMode for Counting {
+        fn tick(&mut self) -> bool {
+            self.ticks += 1;
+            self.busy
+        }
+
+        fn draw(&mut self, _f: &mut Frame) {
+            self.draws += 1;
+        }
+
+        fn handle_key(&mut self, _key: KeyEvent) {
+            self.keys += 1;
+        }
+
+        fn should_quit(&self) -> bool {
+            Instant::now() >= self.until
+        }
+    }
+
+    /// Run the event loop over `input` for `period` at 30 fps with 20ms ticks
+    async fn run_for<I>(period: Duration, busy: bool, input: I) -> Counting
+    where
+        I: Stream<Item = io::Result<Event>> + Unpin,
+    {
+        let mut mode = Counting { busy, until: Instant::now() + period, ticks: 0, draws: 0, keys: 0 };
+        let mut terminal = Terminal::new(TestBackend::new(20, 5)).unwrap();
+        let core = AppCore::new(Duration::from_millis(20)).with_max_fps(30);
+        core.event_loop(&mut terminal, input, &mut mode).await.unwrap();
+        mode
+    }
+
+    #[tokio::test]
+    async fn quiet_period_draws_only_the_first_frame() {
+        let mode = run_for(Duration::from_millis(600), false, tokio_stream::pending()).await;
+        assert_eq!(mode.draws, 1);
+        assert!(mode.ticks >= 10, "{} ticks", mode.ticks);
+    }
+
+    #[tokio::test]
+    async fn changes_and_key_floods_draw_at_most_max_fps() {
+        // Ticks every 20ms, each with news, against frames every 33ms
+        let busy = run_for(Duration::from_millis(600), true, tokio_stream::pending()).await;
+        assert!((8..=20).contains(&busy.draws), "{} frames in 600ms", busy.draws);
+
+        // Keys arriving faster than they can be drawn neither starve the frames nor add to them
+        let key = KeyEvent::new_with_kind(KeyCode::Char('j'), KeyModifiers::NONE, KeyEventKind::Press);
+        let flood = tokio_stream::iter(std::iter::repeat_with(move || Ok(Event::Key(key))));
+        let flooded = run_for(Duration::from_millis(600), false, flood).await;
+        assert!((8..=20).contains(&flooded.draws), "{} frames in 600ms", flooded.draws);
+        assert!(flooded.keys > 1000, "{} keys", flooded.keys);
+        assert!(flooded.ticks >= 10, "{} ticks", flooded.ticks);
+    }
+
+    // Both tests share the flag, so they run as one
+    #[test]
+    fn panics_and_early_returns_restore_the_terminal() {
+        install_panic_hook();
+        let mut terminal = Terminal::new(TestBackend::new(20, 5)).unwrap();
+
+        // As if `TerminalGuard::enter` had taken the terminal
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        let outcome = catch_unwind(AssertUnwindSafe(|| {
+            terminal.draw(|_| panic!("widget index out of bounds")).map(|_| ())
+        }));
+        assert!(outcome.is_err());
+        assert!(!TERMINAL_ACTIVE.load(Ordering::SeqCst), "the panic hook did not restore the terminal");
+
+        // A handler panic the API answers leaves the running UI alone
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        let response = crate::sync::catch_panic(|| -> &'static str { panic!("handler bug") });
+        assert_eq!(response.status(), warp::http::StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(TERMINAL_ACTIVE.load(Ordering::SeqCst));
+
+        // An early `?` return drops the guard
+        let guard = TerminalGuard { _private: () };
+        let early_return = || -> Result<()> {
+            let _guard = guard;
+            Err(anyhow::anyhow!("setup failed"))
+        };
+        assert!(early_return().is_err());
+        assert!(!TERMINAL_ACTIVE.load(Ordering::SeqCst));
+    }
+}
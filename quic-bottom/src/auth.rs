@@ -0,0 +1,186 @@
+//! Bearer-token authentication for the HTTP APIs
+//!
+//! With a token set, in `[api] auth_token` or the `QUIC_BOTTOM_API_TOKEN`
+//! environment variable (which wins), every POST must carry
+//! `Authorization: Bearer <token>`; with `auth_reads = true` GETs must too,
+//! except `/health`. OPTIONS never needs it, since browsers send CORS
+//! preflights without credentials. Other requests get 401 with a JSON error body.
+
+use std::sync::Arc;
+use warp::http::{Method, StatusCode};
+use warp::path::FullPath;
+use warp::{Filter, Rejection, Reply};
+
+use crate::bridge::ApiConfig;
+
+/// Environment variable overriding `[api] auth_token`
+pub const TOKEN_ENV: &str = "QUIC_BOTTOM_API_TOKEN";
+
+/// The token in effect: the environment first, then the config; empty means none
+pub fn resolve_token(config: &ApiConfig) -> Option<String> {
+    std::env::var(TOKEN_ENV)
+        .ok()
+        .or_else(|| config.auth_token.clone())
+        .filter(|token| !token.is_empty())
+}
+
+/// Why a request was refused
+#[derive(Debug)]
+pub struct Unauthorized {
+    reason: &'static str,
+}
+
+impl warp::reject::Reject for Unauthorized {}
+
+/// Filter that lets a request through only if it is exempt or carries the token
+///
+/// Put it in front of the routes and `recover_unauthorized` behind them.
+pub fn require_token(config: &ApiConfig) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    let token = resolve_token(config).map(Arc::<str>::from);
+    let protect_reads = config.auth_reads;
+    if token.is_some() {
+        log::info!(
+            "API authentication enabled: bearer token required for {}",
+            if protect_reads { "all requests but /health" } else { "POST requests" }
+        );
+    }
+
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |method: Method, path: FullPath, header: Option<String>| {
+            let token = token.clone();
+            async move {
+                let Some(token) = token else {
+                    return Ok(());
+                };
+                // A preflight carries no credentials; CORS answers it or the routes refuse it
+                let exempt = method == Method::OPTIONS
+                    || (method == Method::GET && (!protect_reads || path.as_str() == "/health"));
+                if exempt {
+                    return Ok(());
+                }
+                let presented = header.as_deref().and_then(|value| value.strip_prefix("Bearer "));
+                match presented {
+                    Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => Ok(()),
+                    Some(_) => Err(warp::reject::custom(Unauthorized { reason: "invalid token" })),
+                    None => Err(warp::reject::custom(Unauthorized { reason: "missing bearer token" })),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Turn an `Unauthorized` rejection into a 401 with a JSON body
+pub async fn recover_unauthorized(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<Unauthorized>() {
+        Some(unauthorized) => {
+            let body = serde_json::json!({"status": "error", "message": unauthorized.reason});
+            Ok(warp::reply::with_header(
+                warp::reply::with_status(warp::reply::json(&body), StatusCode::UNAUTHORIZED),
+                "www-authenticate",
+                "Bearer",
+            ))
+        }
+        None => Err(rejection),
+    }
+}
+
+/// Compare without an early exit, so timing does not reveal the matching prefix
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = a.len() ^ b.len();
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).copied().unwrap_or(0);
+        let y = b.get(i).copied().unwrap_or(0);
+        diff |= usize::from(x ^ y);
+    }
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cors::{with_cors, CorsConfig};
+    use warp::filters::BoxedFilter;
+    use warp::reply::Response;
+
+    /// `POST /api/metrics` and `GET /api/current` behind the token, as the APIs mount them
+    fn routes(token: Option<&str>, cors: &CorsConfig) -> BoxedFilter<(Response,)> {
+        let config = ApiConfig {
+            auth_token: token.map(str::to_string),
+            auth_reads: true,
+            ..ApiConfig::default()
+        };
+        let post = warp::path!("api" / "metrics").and(warp::post()).map(|| "posted");
+        let get = warp::path!("api" / "current").and(warp::get()).map(|| "current");
+        let routes = require_token(&config).and(post.or(get)).recover(recover_unauthorized);
+        with_cors(routes, cors).unwrap()
+    }
+
+    async fn post(routes: &BoxedFilter<(Response,)>, authorization: Option<&str>) -> StatusCode {
+        let mut request = warp::test::request().method("POST").path("/api/metrics");
+        if let Some(value) = authorization {
+            request = request.header("authorization", value);
+        }
+        request.reply(routes).await.status()
+    }
+
+    #[tokio::test]
+    async fn token_is_required_when_set() {
+        if std::env::var(TOKEN_ENV).is_ok() {
+            return;
+        }
+        let routes = routes(Some("secret"), &CorsConfig::default());
+        assert_eq!(post(&routes, Some("Bearer secret")).await, StatusCode::OK);
+        assert_eq!(post(&routes, Some("Bearer wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(post(&routes, Some("secret")).await, StatusCode::UNAUTHORIZED);
+        let response = warp::test::request().method("POST").path("/api/metrics").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()["www-authenticate"], "Bearer");
+        let response = warp::test::request().path("/api/current").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn everything_is_open_without_a_token() {
+        if std::env::var(TOKEN_ENV).is_ok() {
+            return;
+        }
+        let routes = routes(None, &CorsConfig::default());
+        assert_eq!(post(&routes, None).await, StatusCode::OK);
+        assert_eq!(post(&routes, Some("Bearer anything")).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn preflight_needs_no_token() {
+        if std::env::var(TOKEN_ENV).is_ok() {
+            return;
+        }
+        let cors = CorsConfig {
+            allowed_origins: vec!["https://dash.example.com".to_string()],
+            ..CorsConfig::default()
+        };
+        let routes = routes(Some("secret"), &cors);
+        let response = warp::test::request()
+            .method("OPTIONS")
+            .path("/api/metrics")
+            .header("origin", "https://dash.example.com")
+            .header("access-control-request-method", "POST")
+            .header("access-control-request-headers", "authorization, content-type")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "https://dash.example.com"
+        );
+
+        // Without CORS there is nothing to answer, but no 401 either
+        let routes = self::routes(Some("secret"), &CorsConfig::default());
+        let response = warp::test::request().method("OPTIONS").path("/api/metrics").reply(&routes).await;
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // The request after the preflight still needs the token
+        assert_eq!(post(&routes, None).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,69 @@
+//! Linear or logarithmic value axis for the time graphs
+//!
+//! A log axis keeps a 5ms baseline readable next to 500ms satellite
+//! latency. Only the plotted coordinates are transformed; analytics stay in
+//! linear units.
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::format_value;
+
+/// Smallest value plotted on a log axis; zero and negative values are clamped to it
+pub const LOG_FLOOR: f64 = 0.01;
+
+/// Value axis scale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AxisScale {
+    #[default]
+    Linear,
+    Log10,
+}
+
+impl AxisScale {
+    pub fn toggled(self) -> Self {
+        match self {
+            AxisScale::Linear => AxisScale::Log10,
+            AxisScale::Log10 => AxisScale::Linear,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AxisScale::Linear => "linear",
+            AxisScale::Log10 => "log",
+        }
+    }
+
+    /// Plot coordinate of a finite `value`
+    pub fn transform(self, value: f64) -> f64 {
+        match self {
+            AxisScale::Linear => value,
+            AxisScale::Log10 => value.max(LOG_FLOOR).log10(),
+        }
+    }
+
+    /// Axis bounds and labels in plot coordinates
+    ///
+    /// `padded` is the linear axis range; `data` is the unpadded min/max,
+    /// which the log axis rounds out to whole decades.
+    pub fn axis(self, padded: (f64, f64), data: (f64, f64)) -> ([f64; 2], Vec<String>) {
+        match self {
+            AxisScale::Linear => (
+                [padded.0, padded.1],
+                vec![format!("{:.1}", padded.0), format!("{:.1}", padded.1)],
+            ),
+            AxisScale::Log10 => {
+                let low = self.transform(data.0).floor();
+                let high = self.transform(data.1).ceil().max(low + 1.0);
+                let labels = (low as i32..=high as i32).map(decade_label).collect();
+                ([low, high], labels)
+            }
+        }
+    }
+}
+
+/// `10^exponent` written out, e.g. `0.01`, `1`, `1000`
+fn decade_label(exponent: i32) -> String {
+    format_value(10f64.powi(exponent), exponent.min(0).unsigned_abs() as usize)
+}
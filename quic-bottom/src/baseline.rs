@@ -0,0 +1,184 @@
+//! Saved runs to compare the live one against
+//!
+//! A baseline is read from any file quic-bottom writes about a run: the
+//! `.json` or `.csv` of an export ('e'), or a `--record` session. Other CSVs
+//! work too if they have `timestamp`, `latency` and `throughput` columns,
+//! with timestamps in unix milliseconds or RFC 3339. Throughput is read in
+//! the row's `unit` column when it has one, else in `[ingest] throughput_unit`.
+//!
+//! Samples are kept by their offset from the first one, so the graphs can
+//! line the baseline up with the live run by elapsed time.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::ingest::throughput_bps;
+use crate::units::Unit;
+
+/// A loaded baseline run
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    pub path: PathBuf,
+    /// Latency in ms by offset from the first sample, in order
+    pub latency: Vec<(Duration, f64)>,
+    /// Throughput in bits per second by offset from the first sample, in order
+    pub throughput: Vec<(Duration, f64)>,
+}
+
+impl Baseline {
+    /// Read a baseline, picking the format from the extension and content
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline {}", path.display()))?;
+        let rows = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv")) {
+            csv_rows(&text)?
+        } else {
+            json_rows(&text)?
+        };
+
+        let mut samples = Vec::with_capacity(rows.len());
+        for (index, row) in rows.iter().enumerate() {
+            let at = timestamp(row).ok_or_else(|| {
+                anyhow!("{}: sample {} has no valid timestamp", path.display(), index + 1)
+            })?;
+            let unit = match row.get("unit").and_then(Value::as_str) {
+                Some(unit) => Some(unit.parse::<Unit>().with_context(|| {
+                    format!("{}: sample {}", path.display(), index + 1)
+                })?),
+                None => None,
+            };
+            let rate = number(row, "throughput").map(|value| throughput_bps(value, unit));
+            samples.push((at, number(row, "latency"), rate));
+        }
+        samples.sort_by_key(|(at, _, _)| *at);
+        let first = samples
+            .first()
+            .ok_or_else(|| anyhow!("{} contains no samples", path.display()))?
+            .0;
+
+        let (mut latency, mut throughput) = (Vec::new(), Vec::new());
+        for (at, latency_ms, rate) in samples {
+            let offset = (at - first).to_std().unwrap_or_default();
+            latency.extend(latency_ms.filter(|v| v.is_finite()).map(|v| (offset, v)));
+            throughput.extend(rate.filter(|v| v.is_finite()).map(|v| (offset, v)));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            latency,
+            throughput,
+        })
+    }
+
+    /// Time between the first and last sample
+    pub fn duration(&self) -> Duration {
+        let last = |series: &[(Duration, f64)]| series.last().map_or(Duration::ZERO, |(at, _)| *at);
+        last(&self.latency).max(last(&self.throughput))
+    }
+}
+
+/// Newest `quic-metrics-*.json` export in `dir`, as written by the 'e' key
+pub fn latest_export(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+            name.starts_with("quic-metrics-") && name.ends_with(".json")
+        })
+        // The names embed the export time, so they sort chronologically
+        .max()
+}
+
+/// A JSON array of samples, or one JSON object per line as `--record` writes
+fn json_rows(text: &str) -> Result<Vec<Map<String, Value>>> {
+    if let Ok(Value::Array(rows)) = serde_json::from_str(text) {
+        return rows.into_iter().map(into_object).collect();
+    }
+    let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+    let mut rows = Vec::with_capacity(lines.len());
+    for (number, line) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(value) => rows.push(into_object(value)?),
+            // A recording cut short mid-write, as `load_session` allows
+            Err(e) if number + 1 == lines.len() && number > 0 => {
+                log::warn!("baseline: dropping truncated last line: {}", e);
+            }
+            Err(e) => return Err(anyhow!("line {} is not a JSON sample: {}", number + 1, e)),
+        }
+    }
+    Ok(rows)
+}
+
+/// A session record's sample, placed at its arrival time like a replay does
+fn into_object(value: Value) -> Result<Map<String, Value>> {
+    let Value::Object(mut object) = value else {
+        return Err(anyhow!("baseline samples must be JSON objects"));
+    };
+    if let (Some(received_at), Some(Value::Object(mut sample))) =
+        (object.get("received_at").cloned(), object.remove("sample"))
+    {
+        sample.insert("timestamp".to_string(), received_at);
+        return Ok(sample);
+    }
+    Ok(object)
+}
+
+/// Rows of a CSV with a header line, numbers parsed where possible
+fn csv_rows(text: &str) -> Result<Vec<Map<String, Value>>> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().ok_or_else(|| anyhow!("CSV baseline is empty"))?);
+    Ok(lines
+        .map(|line| {
+            header
+                .iter()
+                .zip(split_csv_line(line))
+                .map(|(key, cell)| {
+                    let value = match cell.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+                        Some(number) => Value::Number(number),
+                        None => Value::String(cell),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Split one CSV line, honouring quoted cells with `""` escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+fn number(row: &Map<String, Value>, key: &str) -> Option<f64> {
+    row.get(key)?.as_f64()
+}
+
+/// `timestamp` as unix milliseconds or an RFC 3339 string
+fn timestamp(row: &Map<String, Value>) -> Option<DateTime<Utc>> {
+    match row.get("timestamp")? {
+        Value::Number(ms) => DateTime::from_timestamp_millis(ms.as_f64()? as i64),
+        Value::String(s) => DateTime::parse_from_rfc3339(s).ok().map(|t| t.with_timezone(&Utc)),
+        _ => None,
+    }
+}
@@ -1,7 +1,12 @@
 //! Simple test for BBRv3 API endpoint
 //! This binary starts only the HTTP API server without TUI
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use quic_bottom::config::parse_bind_addr;
+use quic_bottom::metrics::{BBRv3Metrics, MetricsHistory};
+use quic_bottom::sync::MutexExt;
+use quic_bottom::QuicBottomConfig;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use warp::Filter;
@@ -24,83 +29,61 @@ pub struct RealQUICMetrics {
     pub streams: i32,
     pub handshake_time: f64,
 
-    // BBRv3 specific metrics
-    #[serde(default)]
-    pub bbrv3_phase: Option<String>,
-    #[serde(default)]
-    pub bbrv3_bw_fast: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_bw_slow: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_loss_rate_round: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_loss_rate_ema: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_loss_threshold: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_headroom_usage: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_inflight_target: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_pacing_quantum: Option<i64>,
-    #[serde(default)]
-    pub bbrv3_pacing_gain: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_cwnd_gain: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_probe_rtt_min_ms: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_bufferbloat_factor: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_stability_index: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_phase_duration_ms: Option<std::collections::HashMap<String, f64>>,
-    #[serde(default)]
-    pub bbrv3_recovery_time_ms: Option<f64>,
-    #[serde(default)]
-    pub bbrv3_loss_recovery_efficiency: Option<f64>,
+    /// BBRv3 state, only when the sender uses BBRv3
+    #[serde(flatten)]
+    pub bbrv3: BBRv3Metrics,
+}
+
+#[derive(Parser)]
+#[command(name = "bbrv3_test")]
+#[command(about = "BBRv3 API test server: prints every posted sample, no TUI")]
+struct Cli {
+    /// HTTP API port [default: 8080]
+    #[arg(long, visible_alias = "port")]
+    api_port: Option<u16>,
+
+    /// HTTP API bind address, `ip` or `ip:port` [default: 127.0.0.1]
+    #[arg(long)]
+    bind: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
     let current_metrics: Arc<Mutex<Option<RealQUICMetrics>>> = Arc::new(Mutex::new(None));
-    let metrics_history: Arc<Mutex<Vec<RealQUICMetrics>>> = Arc::new(Mutex::new(Vec::new()));
+    let metrics_history: Arc<Mutex<MetricsHistory<RealQUICMetrics>>> =
+        Arc::new(Mutex::new(MetricsHistory::new(1000)));
 
-    println!("Starting BBRv3 API Test Server...");
-    println!("HTTP API listening on http://127.0.0.1:8080");
-    println!("\nAvailable endpoints:");
-    println!("  POST http://127.0.0.1:8080/api/metrics - Send metrics");
-    println!("  GET  http://127.0.0.1:8080/health - Health check");
-    println!("  GET  http://127.0.0.1:8080/api/current - Get current metrics");
-    println!("\nTo test, run in another terminal:");
-    println!("  curl -X POST http://127.0.0.1:8080/health");
-    println!("  curl -X POST http://127.0.0.1:8080/api/metrics -H 'Content-Type: application/json' -d '{{...}}'");
-    println!("\nPress Ctrl+C to stop.\n");
+    let config = QuicBottomConfig::default();
+    let bind = cli.bind.as_deref().unwrap_or(&config.bind_address);
+    let addr = parse_bind_addr(bind, cli.api_port.unwrap_or(config.api_port))?;
 
     // HTTP API routes
     let current_metrics_post = Arc::clone(&current_metrics);
+    let history_post = Arc::clone(&metrics_history);
     let metrics_filter = warp::path("api")
         .and(warp::path("metrics"))
         .and(warp::post())
         .and(warp::body::json())
         .map(move |metrics: RealQUICMetrics| {
             println!("\n📊 Received metrics:");
-            println!("  Phase: {:?}", metrics.bbrv3_phase);
-            println!("  Bandwidth Fast: {:?} bps", metrics.bbrv3_bw_fast);
-            println!("  Bandwidth Slow: {:?} bps", metrics.bbrv3_bw_slow);
-            println!("  Loss Rate: {:?}%", metrics.bbrv3_loss_rate_ema.map(|x| x * 100.0));
-            println!("  Bufferbloat: {:?}", metrics.bbrv3_bufferbloat_factor);
-            println!("  Stability Index: {:?}", metrics.bbrv3_stability_index);
-            println!("  Pacing Gain: {:?}", metrics.bbrv3_pacing_gain);
-            println!("  CWND Gain: {:?}", metrics.bbrv3_cwnd_gain);
-            println!("  Recovery Time: {:?} ms", metrics.bbrv3_recovery_time_ms);
-            println!("  Recovery Efficiency: {:?}", metrics.bbrv3_loss_recovery_efficiency);
+            println!("  Phase: {:?}", metrics.bbrv3.phase);
+            println!("  Bandwidth Fast: {:?} bps", metrics.bbrv3.bw_fast);
+            println!("  Bandwidth Slow: {:?} bps", metrics.bbrv3.bw_slow);
+            println!("  Loss Rate: {:?}%", metrics.bbrv3.loss_rate_ema.map(|x| x * 100.0));
+            println!("  Bufferbloat: {:?}", metrics.bbrv3.bufferbloat_factor);
+            println!("  Stability Index: {:?}", metrics.bbrv3.stability_index);
+            println!("  Pacing Gain: {:?}", metrics.bbrv3.pacing_gain);
+            println!("  CWND Gain: {:?}", metrics.bbrv3.cwnd_gain);
+            println!("  Recovery Time: {:?} ms", metrics.bbrv3.recovery_time_ms);
+            println!("  Recovery Efficiency: {:?}", metrics.bbrv3.loss_recovery_efficiency);
 
             // Update current metrics
             {
-                let mut current = current_metrics_post.lock().unwrap();
+                let mut current = current_metrics_post.lock_or_recover();
                 *current = Some(metrics.clone());
             }
+            history_post.lock_or_recover().push(metrics);
 
             warp::reply::json(&serde_json::json!({"status": "ok", "message": "BBRv3 metrics received"}))
         });
@@ -116,7 +99,7 @@ async fn main() -> Result<()> {
         .and(warp::path("current"))
         .and(warp::get())
         .map(move || {
-            let current = current_metrics_get.lock().unwrap();
+            let current = current_metrics_get.lock_or_recover();
             println!("📋 Getting current metrics");
             warp::reply::json(&*current)
         });
@@ -125,9 +108,22 @@ async fn main() -> Result<()> {
         .or(health_filter)
         .or(current_filter);
 
-    warp::serve(routes)
-        .run(([127, 0, 0, 1], 8080))
-        .await;
+    let (_, server) = warp::serve(routes)
+        .try_bind_ephemeral(addr)
+        .map_err(|e| anyhow!("failed to bind HTTP API to {}: {}", addr, e))?;
+
+    println!("Starting BBRv3 API Test Server...");
+    println!("HTTP API listening on http://{}", addr);
+    println!("\nAvailable endpoints:");
+    println!("  POST http://{}/api/metrics - Send metrics", addr);
+    println!("  GET  http://{}/health - Health check", addr);
+    println!("  GET  http://{}/api/current - Get current metrics", addr);
+    println!("\nTo test, run in another terminal:");
+    println!("  curl -X POST http://{}/health", addr);
+    println!("  curl -X POST http://{}/api/metrics -H 'Content-Type: application/json' -d '{{...}}'", addr);
+    println!("\nPress Ctrl+C to stop.\n");
+
+    server.await;
 
     Ok(())
 }
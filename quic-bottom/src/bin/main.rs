@@ -2,34 +2,255 @@
 //! 
 //! A specialized version of bottom for monitoring QUIC protocol metrics
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use log::info;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-// Modules are defined in lib.rs
+mod modes;
 
 use quic_bottom::app::QuicBottomApp;
+use quic_bottom::config::expand_home;
+use quic_bottom::config_watch::ConfigWatcher;
+use quic_bottom::demo_data::{DemoDataGenerator, TRAFFIC_PROFILES};
+use quic_bottom::exporters::otel;
+use quic_bottom::headless::start_ingestion;
+use quic_bottom::metrics::MetricsHandle;
+use quic_bottom::replay::QlogTrace;
+use quic_bottom::theme::{ColorConfig, THEMES};
+use quic_bottom::QuicBottomConfig;
 
 #[derive(Parser)]
 #[command(name = "quic-bottom")]
 #[command(about = "QUIC Bottom - Real-time QUIC protocol monitor")]
 #[command(version)]
 struct Cli {
-    /// Configuration file path
-    #[arg(short, long, default_value = "~/.config/quic-bottom/config.toml")]
+    /// Configuration file path; `live` and the default mode reload it when it changes
+    #[arg(short, long, global = true, default_value = "~/.config/quic-bottom/config.toml")]
     config: Option<String>,
     
     /// Enable debug logging
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     debug: bool,
     
-    /// Update interval in milliseconds
-    #[arg(short, long, default_value = "100")]
-    interval: u64,
+    /// Update interval in milliseconds [default: from config, 100; console: 1000]
+    #[arg(short, long, global = true)]
+    interval: Option<u64>,
     
-    /// HTTP API port for Go integration
-    #[arg(long, default_value = "8080")]
-    api_port: u16,
+    /// HTTP API port for Go integration [default: from config, 8080]
+    #[arg(long, visible_alias = "port", global = true)]
+    api_port: Option<u16>,
+
+    /// HTTP API bind address, `ip` or `ip:port` [default: from config, 127.0.0.1]
+    #[arg(long, global = true)]
+    bind: Option<String>,
+
+    /// Also accept newline-delimited JSON samples on this Unix socket
+    #[arg(long, global = true)]
+    uds_path: Option<PathBuf>,
+
+    /// PEM certificate chain; serves the HTTP API over HTTPS [default: from config]
+    #[arg(long, global = true, value_name = "FILE", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// PEM private key for --tls-cert
+    #[arg(long, global = true, value_name = "FILE", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// Built-in color theme, replacing the config's [colors] [default: from config, default]
+    #[arg(long, global = true, value_name = "NAME", value_parser = PossibleValuesParser::new(THEMES))]
+    theme: Option<String>,
+
+    /// Serve the HTTP API and run the analytics without a TUI; needs no terminal
+    #[arg(long, conflicts_with = "replay")]
+    headless: bool,
+
+    /// Play back a qlog trace instead of serving the HTTP API
+    #[arg(long, value_name = "FILE")]
+    replay: Option<PathBuf>,
+
+    /// Playback speed multiplier for --replay
+    #[arg(long, default_value = "1.0", requires = "replay")]
+    speed: f64,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Dashboard over generated demo data, no Go sender needed
+    Demo(DemoArgs),
+    /// Full dashboard over metrics posted by the Go sender
+    Live(LiveArgs),
+    /// Analytics dashboard (heatmap, correlation, anomalies) over demo data
+    Analytics(AnalyticsArgs),
+    /// Analytics over demo data printed to the console, without a TUI
+    Console(ConsoleArgs),
+    /// Generate synthetic metrics load against a running instance
+    Loadgen(LoadgenArgs),
+    /// Write or check the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Write the documented default config file
+    Init(ConfigInitArgs),
+    /// Validate the config file and print the settings in effect with it and QUIC_BOTTOM_* variables
+    Check(ConfigCheckArgs),
+}
+
+#[derive(Args)]
+struct ConfigInitArgs {
+    /// File to write [default: --config]
+    #[arg(long, value_name = "FILE")]
+    path: Option<String>,
+
+    /// Replace an existing file
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct ConfigCheckArgs {
+    /// File to check [default: --config]
+    #[arg(long, value_name = "FILE")]
+    path: Option<String>,
+}
+
+#[derive(Args)]
+struct DemoArgs {
+    /// Graph style
+    #[arg(long, value_enum, default_value_t = DemoStyle::Widgets)]
+    style: DemoStyle,
+
+    #[command(flatten)]
+    data: DemoDataArgs,
+}
+
+/// Where the generated samples come from
+#[derive(Args)]
+struct DemoDataArgs {
+    /// Seed of the generated samples; the same seed repeats a run [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Shape of the generated traffic
+    #[arg(long, value_name = "PROFILE", default_value = "steady-stream", value_parser = PossibleValuesParser::new(TRAFFIC_PROFILES))]
+    traffic: String,
+}
+
+impl DemoDataArgs {
+    /// The generator, with faults lasting `[faults] duration_secs` at one sample per `interval_ms`
+    fn generator(&self, config: &QuicBottomConfig, interval_ms: u64) -> Result<DemoDataGenerator> {
+        let generator = match self.seed {
+            Some(seed) => DemoDataGenerator::with_seed(seed),
+            None => DemoDataGenerator::new(),
+        };
+        Ok(generator
+            .with_profile(self.traffic.parse()?)
+            .with_fault_length(config.faults.samples(Duration::from_millis(interval_ms))))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DemoStyle {
+    /// Sparkline widgets
+    Widgets,
+    /// Time graphs with analytics
+    Simple,
+    /// Time graphs with trend analysis and an adjustable window
+    Professional,
+}
+
+#[derive(Args)]
+struct LiveArgs {
+    /// Serve the HTTP API only, without the TUI
+    #[arg(long)]
+    headless: bool,
+
+    /// Directory session exports are written to [default: from config]
+    #[arg(long)]
+    export_dir: Option<PathBuf>,
+
+    /// Append every ingested sample, with its arrival time, to this file
+    #[arg(long, value_name = "FILE", conflicts_with = "replay_session")]
+    record: Option<PathBuf>,
+
+    /// Start with empty graphs even when the --record file holds an earlier run
+    #[arg(long, requires = "record")]
+    no_restore: bool,
+
+    /// Play back a session written by --record instead of serving the HTTP API
+    #[arg(long, value_name = "FILE", conflicts_with = "headless")]
+    replay_session: Option<PathBuf>,
+
+    /// Playback speed multiplier for --replay-session
+    #[arg(long, default_value = "1.0", requires = "replay_session")]
+    speed: f64,
+
+    /// Saved run to compare against: an export (.json/.csv) or a --record file
+    #[arg(long, value_name = "FILE", conflicts_with = "headless")]
+    baseline: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct AnalyticsArgs {
+    /// Heatmap, correlation and anomaly views without the simulation panels
+    #[arg(long)]
+    enhanced: bool,
+
+    /// Run the timed steps of this scenario file (TOML)
+    #[arg(long, value_name = "FILE", conflicts_with = "enhanced")]
+    scenario: Option<PathBuf>,
+
+    #[command(flatten)]
+    data: DemoDataArgs,
+}
+
+#[derive(Args)]
+struct ConsoleArgs {
+    /// Run the timed steps of this scenario file (TOML) instead of the built-in one
+    #[arg(long, value_name = "FILE")]
+    scenario: Option<PathBuf>,
+
+    #[command(flatten)]
+    data: DemoDataArgs,
+}
+
+#[derive(Args)]
+struct LoadgenArgs {
+    /// Where to send samples: http://host:port/metrics, unix:///path/to.sock (NDJSON) or udp://host:port (statsd)
+    #[arg(long, default_value = "http://127.0.0.1:8080/metrics")]
+    url: String,
+
+    /// Number of concurrent sender tasks
+    #[arg(long, default_value = "4")]
+    concurrency: usize,
+
+    /// Target aggregate rate in samples per second
+    #[arg(long, default_value = "1000")]
+    rate: f64,
+
+    /// Run duration in seconds
+    #[arg(long, default_value = "10")]
+    duration: u64,
+
+    /// Seconds to ramp linearly up to the target rate, counted within --duration
+    #[arg(long, default_value = "0")]
+    ramp: u64,
+
+    /// Seed of the first sender's samples, the others counting up from it [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Shape of the generated traffic; bbrv3-startup also sends the bbrv3_* fields
+    #[arg(long, value_name = "PROFILE", default_value = "steady-stream", value_parser = PossibleValuesParser::new(TRAFFIC_PROFILES))]
+    traffic: String,
 }
 
 #[tokio::main]
@@ -45,38 +266,235 @@ async fn main() -> Result<()> {
         env_logger::init();
     }
     
-    info!("Starting QUIC Bottom v{}", env!("CARGO_PKG_VERSION"));
-    info!("Debug mode: {}", cli.debug);
-    info!("Update interval: {}ms", cli.interval);
-    info!("API port: {}", cli.api_port);
-    
-    // Initialize metrics system
-    quic_bottom::metrics::init_metrics()?;
-    
-    // Start HTTP API server for Go integration
-    let api_port = cli.api_port;
-    tokio::spawn(async move {
-        if let Err(e) = start_api_server(api_port).await {
-            log::error!("API server error: {}", e);
+    if cli.replay.is_some() && cli.command.is_some() {
+        return Err(anyhow!("--replay cannot be combined with a subcommand"));
+    }
+    if cli.headless && cli.command.is_some() {
+        return Err(anyhow!("--headless applies to the default mode; use `live --headless` for live"));
+    }
+    if let Some(Command::Config(command)) = &cli.command {
+        return run_config_command(command, cli.config.as_deref());
+    }
+
+    // Defaults, then the file, then QUIC_BOTTOM_* variables, then flags
+    let config_path = cli.config.as_deref().map(expand_home);
+    // Loadgen and the demo modes don't read the config, bar the demo's keys
+    let reads_config = matches!(
+        &cli.command,
+        Some(Command::Live(_)) | Some(Command::Analytics(_)) | Some(Command::Console(_)) | None
+    );
+    let mut config = if reads_config {
+        load_config(config_path.as_deref())?
+    } else {
+        QuicBottomConfig::default()
+    };
+    // Reloads are compared with the file as loaded, before the overrides below
+    let config_watcher = match &cli.command {
+        Some(Command::Live(_)) | None => config_path.clone().map(|path| ConfigWatcher::new(path, config.clone())),
+        Some(_) => None,
+    };
+    if reads_config {
+        config.apply_env()?;
+    }
+    if let Some(interval) = cli.interval {
+        config.update_interval = interval;
+    }
+    if let Some(bind) = cli.bind {
+        config.bind_address = bind;
+    }
+    if let Some(port) = cli.api_port {
+        config.api_port = port;
+    }
+    if let Some(path) = cli.uds_path {
+        config.ingest.uds_path = Some(path);
+    }
+    if cli.tls_cert.is_some() {
+        config.api.tls_cert = cli.tls_cert;
+        config.api.tls_key = cli.tls_key;
+    }
+    if let Some(name) = cli.theme {
+        config.colors = ColorConfig::builtin(&name);
+    }
+    config.validate().with_context(|| match &config_path {
+        Some(path) if reads_config && path.exists() => format!("invalid configuration (file {})", path.display()),
+        _ => "invalid configuration".to_string(),
+    })?;
+    let interval = config.update_interval;
+
+    otel::init(&config.exporters.otel)?;
+    quic_bottom::ingest::set_throughput_unit(config.ingest.throughput_unit);
+    quic_bottom::ingest::set_loss_unit(config.ingest.loss_unit);
+    let result = match cli.command {
+        Some(Command::Demo(args)) => {
+            let theme = config.colors.theme()?;
+            let generator = args.data.generator(&config, interval)?;
+            // The demo styles read nothing else from the config file
+            let keybindings = load_config(config_path.as_deref())?.keybindings;
+            match args.style {
+                DemoStyle::Widgets => modes::demo::run(interval, theme, generator, &keybindings).await,
+                DemoStyle::Simple => {
+                    modes::simple_professional::run(interval, theme, generator, &keybindings).await
+                }
+                DemoStyle::Professional => modes::professional::run(interval, theme, generator, &keybindings).await,
+            }
         }
-    });
+        Some(Command::Live(args)) => {
+            if let Some(dir) = args.export_dir {
+                config.export.output_dir = dir;
+            }
+            let options = modes::real_quic_bottom::LiveOptions {
+                headless: args.headless,
+                record: args.record,
+                replay_session: args.replay_session,
+                speed: args.speed,
+                baseline: args.baseline,
+                restore: !args.no_restore,
+                config_watcher,
+            };
+            modes::real_quic_bottom::run(config, interval, options).await
+        }
+        Some(Command::Analytics(args)) if args.enhanced => {
+            let generator = args.data.generator(&config, interval)?;
+            modes::enhanced_analytics::run(config, interval, generator).await
+        }
+        Some(Command::Analytics(args)) => {
+            let generator = args.data.generator(&config, interval)?;
+            modes::ultimate_analytics::run(config, interval, generator, args.scenario.as_deref()).await
+        }
+        Some(Command::Console(args)) => {
+            let interval = cli.interval.unwrap_or(1000);
+            let generator = args.data.generator(&config, interval)?;
+            modes::ultimate_analytics_console::run(&config, interval, generator, args.scenario.as_deref()).await
+        }
+        Some(Command::Loadgen(args)) => {
+            let report = quic_bottom::loadgen::run_loadgen(quic_bottom::loadgen::LoadgenConfig {
+                url: args.url,
+                concurrency: args.concurrency,
+                rate: args.rate,
+                duration: Duration::from_secs(args.duration),
+                ramp: Duration::from_secs(args.ramp),
+                token: std::env::var(quic_bottom::auth::TOKEN_ENV).ok().filter(|t| !t.is_empty()),
+                seed: args.seed,
+                profile: args.traffic.parse()?,
+            })
+            .await?;
+            println!("{}", report);
+            Ok(())
+        }
+        Some(Command::Config(_)) => unreachable!("config commands return before the config is loaded"),
+        None if cli.headless => quic_bottom::headless::run(config, interval).await,
+        None => match cli.replay {
+            Some(path) => run_replay(&config, interval, &path, cli.speed).await,
+            None => run_monitor(config, interval, cli.debug, config_watcher).await,
+        },
+    };
+    // Push the last samples before exiting, also after a failed run
+    otel::shutdown();
+    result
+}
+
+/// The default mode's widgets over a qlog trace, without the bridge API
+async fn run_replay(config: &QuicBottomConfig, interval: u64, path: &Path, speed: f64) -> Result<()> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(anyhow!("--speed must be a positive number, got {}", speed));
+    }
+    let trace = QlogTrace::load(path)?;
+    info!(
+        "Replaying {} ({} samples, {} unsupported events skipped) at {}x",
+        path.display(),
+        trace.samples.len(),
+        trace.skipped_events,
+        speed
+    );
+
+    let mut app = QuicBottomApp::new(interval)
+        .await?
+        .with_config(config)
+        .with_replay(trace, speed);
+    app.run().await
+}
+
+/// `config init` and `config check`; failures exit non-zero for provisioning scripts
+fn run_config_command(command: &ConfigCommand, default_path: Option<&str>) -> Result<()> {
+    let path = |given: &Option<String>| {
+        given
+            .as_deref()
+            .or(default_path)
+            .map(expand_home)
+            .ok_or_else(|| anyhow!("no config path; pass --path"))
+    };
+    match command {
+        ConfigCommand::Init(args) => {
+            let path = path(&args.path)?;
+            if path.exists() && !args.force {
+                return Err(anyhow!("{} already exists; pass --force to replace it", path.display()));
+            }
+            QuicBottomConfig::create_default_config(&path)?;
+            println!("Wrote {}", path.display());
+        }
+        ConfigCommand::Check(args) => {
+            let path = path(&args.path)?;
+            if !path.exists() {
+                return Err(anyhow!("{} does not exist; `config init` writes one", path.display()));
+            }
+            let mut config = QuicBottomConfig::parse_file(&path)?;
+            config.apply_env()?;
+            config
+                .validate()
+                .with_context(|| format!("invalid configuration (file {})", path.display()))?;
+            println!("# {} is valid; values differing from the defaults name their source", path.display());
+            println!();
+            print!("{}", config.annotated_toml(&path.display().to_string())?);
+        }
+    }
+    Ok(())
+}
+
+/// The default mode: basic widgets over metrics received by the bridge API
+async fn run_monitor(
+    config: QuicBottomConfig,
+    interval: u64,
+    debug: bool,
+    config_watcher: Option<ConfigWatcher>,
+) -> Result<()> {
+    let api_addr = config.api_addr()?;
+
+    info!("Starting QUIC Bottom v{}", env!("CARGO_PKG_VERSION"));
+    info!("Debug mode: {}", debug);
+    info!("Update interval: {}ms", interval);
+    info!("API address: {}", api_addr);
     
+    // Metrics shared by the ingestion paths and the UI
+    let metrics = MetricsHandle::global();
+    let exporter = start_ingestion(&config, &metrics, Duration::from_millis(interval)).await?;
+
     // Create and run the application
-    let mut app = QuicBottomApp::new(cli.interval).await?;
+    let mut app = QuicBottomApp::new(interval)
+        .await?
+        .with_config(&config)
+        .with_metrics(metrics);
+    if let Some(queue) = exporter {
+        app = app.with_exporter(queue);
+    }
+    if let Some(watcher) = config_watcher {
+        app = app.with_config_watcher(watcher);
+    }
     app.run().await?;
     
     info!("QUIC Bottom stopped");
     Ok(())
 }
 
-async fn start_api_server(port: u16) -> Result<()> {
-    // Используем create_api_routes из bridge.rs для поддержки POST /metrics
-    let routes = quic_bottom::bridge::create_api_routes();
-    
-    info!("Starting API server on port {}", port);
-    warp::serve(routes)
-        .run(([127, 0, 0, 1], port))
-        .await;
-    
-    Ok(())
+/// Read the config file if it exists, falling back to defaults; validated once the overrides are in
+fn load_config(path: Option<&Path>) -> Result<QuicBottomConfig> {
+    let Some(path) = path else {
+        return Ok(QuicBottomConfig::default());
+    };
+
+    if path.exists() {
+        info!("Loading config from {}", path.display());
+        QuicBottomConfig::parse_file(path)
+    } else {
+        Ok(QuicBottomConfig::default())
+    }
 }
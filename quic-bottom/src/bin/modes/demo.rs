@@ -0,0 +1,277 @@
+//! QUIC Bottom Demo - Shows dynamic graphs with test data
+//! 
+//! This demo version generates realistic QUIC metrics to showcase
+//! the dynamic graphs and widgets
+
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::time::Duration;
+
+use quic_bottom::{
+    app::{AppCore, TuiMode},
+    demo_data::DemoDataGenerator,
+    keybindings::{render_help_overlay, Action, AppKind, KeyMap, KeybindingsConfig},
+    metrics::{ConnectionLifecycle, DerivedMetricsConfig, QUICMetrics},
+    theme::{Theme, ThemeCycle},
+    widgets::{QUICConnectionWidget, QUICLatencyWidget, QUICNetworkWidget, QUICThroughputWidget},
+};
+
+/// Demo application with test data
+pub struct QuicBottomDemo {
+    latency_widget: QUICLatencyWidget,
+    throughput_widget: QUICThroughputWidget,
+    connection_widget: QUICConnectionWidget,
+    network_widget: QUICNetworkWidget,
+    demo_generator: DemoDataGenerator,
+    /// Lifecycle counters as a sender would report them
+    connections: ConnectionLifecycle,
+    failed_total: u64,
+    themes: ThemeCycle,
+    show_help: bool,
+    keymap: KeyMap,
+    should_quit: bool,
+    update_interval: Duration,
+}
+
+impl QuicBottomDemo {
+    pub async fn new(interval_ms: u64, theme: Theme, demo_generator: DemoDataGenerator) -> Result<Self> {
+        Ok(Self {
+            latency_widget: QUICLatencyWidget::new(1000),
+            throughput_widget: QUICThroughputWidget::new(1000),
+            connection_widget: QUICConnectionWidget::new(),
+            network_widget: QUICNetworkWidget::new(),
+            demo_generator,
+            connections: ConnectionLifecycle::default(),
+            failed_total: 0,
+            themes: ThemeCycle::new(theme),
+            show_help: false,
+            keymap: KeyMap::default(),
+            should_quit: false,
+            update_interval: Duration::from_millis(interval_ms),
+        })
+    }
+
+    /// Use `keymap` instead of the default keys
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        AppCore::new(self.update_interval).run(self).await
+    }
+
+    fn update_widgets(&mut self) {
+        // Generate demo data
+        let (latency, throughput, handshake_time, packet_loss, retransmits) = 
+            self.demo_generator.generate_next();
+
+        // Update latency widget
+        self.latency_widget.update(latency);
+
+        // Update throughput widget
+        self.throughput_widget.update(throughput);
+
+        // Update connection widget
+        let connections = 2 + (self.demo_generator.counter / 10) as i32;
+        let failed = self.demo_generator.failed_connections() as i32;
+        let errors = if self.demo_generator.counter > 20 { 
+            (self.demo_generator.counter % 10) as i32 
+        } else { 
+            0 
+        } + self.demo_generator.injected_errors() as i32;
+        self.failed_total += u64::from(self.demo_generator.failed_connections());
+        let window = DerivedMetricsConfig::default().success_window();
+        self.connections.record(
+            &QUICMetrics {
+                timestamp: chrono::Utc::now(),
+                connections_opened: Some((connections - failed).max(0) as u64 + self.failed_total),
+                connections_failed: Some(self.failed_total),
+                connections_closed: Some(0),
+                ..QUICMetrics::default()
+            },
+            window,
+        );
+        self.connection_widget.update((connections - failed).max(0), errors);
+        self.connection_widget.set_lifecycle(self.connections.stats(window));
+        self.connection_widget.add_handshake_time(handshake_time);
+
+        // Update network widget
+        self.network_widget.update(packet_loss, retransmits, "BBRv2".to_string());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        let Some(action) = self.keymap.action(AppKind::Demo, &key) else {
+            return;
+        };
+        if let Some(fault) = action.fault() {
+            self.demo_generator.inject(fault);
+            return;
+        }
+        match action {
+            Action::Quit => {
+                self.should_quit = true;
+            }
+            Action::Reset => {
+                // Reset demo data
+                self.demo_generator.reset();
+                self.latency_widget = QUICLatencyWidget::new(1000);
+                self.throughput_widget = QUICThroughputWidget::new(1000);
+                self.connection_widget = QUICConnectionWidget::new();
+                self.connections = ConnectionLifecycle::default();
+                self.failed_total = 0;
+                self.network_widget = QUICNetworkWidget::new();
+            }
+            Action::CycleTheme => {
+                self.themes.advance();
+            }
+            Action::Help => {
+                self.show_help = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        let theme = self.themes.current();
+
+        // Header
+        self.render_header(f, chunks[0], theme);
+
+        // Main content
+        let main_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(50), // Left column
+                Constraint::Percentage(50), // Right column
+            ])
+            .split(chunks[1]);
+
+        // Left column
+        let left_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50), // Latency
+                Constraint::Percentage(50), // Throughput
+            ])
+            .split(main_chunks[0]);
+
+        // Right column
+        let right_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50), // Connections
+                Constraint::Percentage(50), // Network
+            ])
+            .split(main_chunks[1]);
+
+        // Render widgets
+        self.latency_widget.render(f, left_chunks[0], theme);
+        self.throughput_widget.render(f, left_chunks[1], theme);
+        self.connection_widget.render(f, right_chunks[0], theme);
+        self.network_widget.render(f, right_chunks[1], theme);
+
+        // Footer
+        self.render_footer(f, chunks[2], theme);
+
+        if self.show_help {
+            render_help_overlay(f, AppKind::Demo, &self.keymap, theme);
+        }
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let header_text = "QUIC Bottom DEMO - Dynamic Graphs with Test Data";
+        let header = Paragraph::new(header_text)
+            .style(theme.title(theme.text))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(header, area);
+    }
+
+    fn render_footer(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let keys = format!(
+            "Press '{}' to quit, '{}' to reset, '{}' for theme, '{}' for help",
+            self.keymap.key(Action::Quit),
+            self.keymap.key(Action::Reset),
+            self.keymap.key(Action::CycleTheme),
+            self.keymap.key(Action::Help)
+        );
+        let footer_text = match self.demo_generator.injection_status(self.update_interval) {
+            Some(status) => format!("{} | {}", status, keys),
+            None => format!("{}, F1-F5 to inject faults | DEMO MODE", keys),
+        };
+        let footer = Paragraph::new(footer_text)
+            .style(Style::default().fg(theme.accent))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, area);
+    }
+}
+
+impl TuiMode for QuicBottomDemo {
+    fn tick(&mut self) -> bool {
+        // New demo data every tick
+        self.update_widgets();
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        self.ui(f);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.handle_key_event(key);
+    }
+
+    fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+}
+
+/// Run `quic-bottom demo`
+pub async fn run(
+    interval_ms: u64,
+    theme: Theme,
+    demo_generator: DemoDataGenerator,
+    keybindings: &KeybindingsConfig,
+) -> Result<()> {
+    let (keymap, warnings) = KeyMap::from_config(keybindings);
+    println!("Starting QUIC Bottom DEMO with dynamic graphs...");
+    println!("This demo shows realistic QUIC metrics with live graphs!");
+    println!("Watch the sparkline graphs update in real-time!");
+    println!();
+    for warning in &warnings {
+        println!("Warning: {}", warning);
+    }
+
+    let mut demo = QuicBottomDemo::new(interval_ms, theme, demo_generator)
+        .await?
+        .with_keymap(keymap);
+    demo.run().await?;
+    println!("Demo data: {}", demo.demo_generator.summary());
+    for fault in demo.demo_generator.fault_log() {
+        println!("Injected {}", fault);
+    }
+    
+    println!("✅ QUIC Bottom DEMO completed!");
+    Ok(())
+}
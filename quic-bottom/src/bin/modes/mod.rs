@@ -0,0 +1,12 @@
+//! `quic-bottom` subcommands
+//!
+//! Each mode owns its widgets and key handling; terminal setup, the event
+//! loop and the network presets come from `quic_bottom::app`.
+
+pub mod demo;
+pub mod enhanced_analytics;
+pub mod professional;
+pub mod real_quic_bottom;
+pub mod simple_professional;
+pub mod ultimate_analytics;
+pub mod ultimate_analytics_console;
@@ -0,0 +1,3468 @@
+//! Real QUIC Bottom - Production Version
+//! 
+//! Features:
+//! - Real-time QUIC metrics from Go application
+//! - HTTP API for metrics collection
+//! - Professional visualizations
+//! - Network simulation integration
+//! - Security checks of the QUIC endpoint under test
+//! - Cloud deployment monitoring
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect, Size},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use warp::filters::BoxedFilter;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::Filter;
+
+#[cfg(unix)]
+use quic_bottom::ingest::uds::UdsListener;
+use quic_bottom::{
+    app::{AppCore, TuiMode},
+    config_watch::{failure_summary, ConfigUpdate, ConfigWatcher},
+    keybindings::{render_help_overlay, Action, AppKind, KeyMap},
+    config::{WidgetConfig, DEFAULT_MAX_FPS},
+    focus::{FocusState, Panel},
+    improved_layout::{
+        build_layout, render_no_widgets, render_unavailable, responsive_layout, AnySize, LayoutTree, MinSize,
+        Presentation, WidgetKind,
+    },
+    mouse::HitMap,
+    views::{render_view_picker, PresetContent, ViewRegistry},
+    network_sim::{NetworkSimulation, SimulationRequest},
+    simple_professional::{SimpleQuicLatencyGraph, SimpleQuicThroughputGraph},
+    time_view::{TimeNav, TimeView},
+    heatmap_widget::{HeatmapCursor, QUICPerformanceHeatmap},
+    correlation_widget::{CorrelationCursor, QUICCorrelationWidget},
+    anomaly_detection::{AnomalyListCursor, AnomalySeverity, QUICAnomalyWidget},
+    alerting::Alerter,
+    auth::{recover_unauthorized, require_token, resolve_token},
+    baseline::{latest_export, Baseline},
+    bridge::{check_source_label, reset_routes, resolve_source, ApiConfig, SourceQuery},
+    cors::with_cors,
+    ingest::{
+        check_batch_size, ensure_loss, ensure_non_negative, loss_fraction, order_batch, record_ingest_error, throughput_bps,
+        limit::{body_limit, http_limit_stats, rate_limit, recover_rate_limited},
+        BatchQuery, BatchReport,
+    },
+    metrics::{
+        ensure_source_room, format_span, BBRv3Metrics, ConnectionTracker, DerivedMetrics, DerivedMetricsConfig,
+        Freshness, HandshakeType, MetricsHistory, QUICMetrics, ResetOutcome, ResetScope, SegmentReset, SourceSummary,
+        StalenessConfig, Timestamped,
+    },
+    widgets::{
+        last_update_span, render_header, sla_span, BBRv3GraphsWidget, CongestionWindowWidget, HistogramWidget,
+        QUICConnectionTableWidget, QUICEfficiencyWidget, QUICStreamsWidget, TracePanel, HISTOGRAM_BINS,
+    },
+    export::{export_session, ExportConfig},
+    exporters::{
+        influx::{InfluxExporter, InfluxQueue},
+        otel,
+    },
+    redact::{RedactionConfig, Redactor},
+    security::{CheckKind, CheckStatus, SecurityConfig, SecurityScanner},
+    health::HealthReport,
+    health_score::{HealthConfig, HealthInputs, HealthScore},
+    replay::{load_session, Replayer, Session, SessionRecorder},
+    report::{write_report, ReportConfig, ReportPaths, SessionReport},
+    shutdown,
+    sla::{SlaConfig, SlaVerdict},
+    status::{record_samples, StatusBar},
+    sync::{catch_panic, MutexExt},
+    theme::{Theme, ThemeCycle},
+    tls::{self, ServerFuture, TlsIdentity},
+    units::{format_rate, LossUnit, Unit},
+    QuicBottomConfig,
+};
+
+/// Number of samples kept in the metrics history
+const HISTORY_CAPACITY: usize = 1000;
+
+/// Recorded time fast-forwarded through the analytics when `--record` resumes a recording
+const RESTORE_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Most samples fast-forwarded at startup; a longer tail is thinned to every n-th sample
+const MAX_RESTORED_SAMPLES: usize = 20_000;
+
+/// Reset done through the API, with the caller's address
+type ResetRequest = (ResetOutcome, String);
+
+/// How long footer notices stay visible
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// Width of the replay progress bar in the footer
+const PROGRESS_WIDTH: usize = 20;
+
+/// What a frame shows besides the effects of input, compared between ticks to skip unchanged frames
+#[derive(Debug, Clone, PartialEq)]
+struct ScreenState {
+    /// History position the widgets have caught up to
+    seen_mark: u64,
+    /// When the footer notice was shown, while it is
+    notice: Option<Instant>,
+    /// Whole seconds since the last sample, as the header counts them
+    age_secs: Option<u64>,
+    freshness: Freshness,
+    /// Position of the replay, which moves while it plays
+    replay: Option<Duration>,
+    /// Simulation changes made through the API
+    network_revision: u64,
+    /// Security check progress and results
+    security_revision: u64,
+    /// Tracked connections, which drop out once they expire
+    connections: usize,
+}
+
+/// Top-level config keys applied when the config file changes while running
+const RELOADABLE: &[&str] = &[
+    "update_interval",
+    "colors",
+    "keybindings",
+    "staleness",
+    "derived",
+    "sla",
+    "health",
+    "anomaly",
+    "widgets",
+    "layout",
+    "views",
+    "security",
+];
+
+/// Views of the mode by preset name, in the order of their keys
+const BUILTIN_VIEWS: &[(&str, ViewMode)] = &[
+    ("dashboard", ViewMode::Dashboard),
+    ("analytics", ViewMode::Analytics),
+    ("network", ViewMode::Network),
+    ("security", ViewMode::Security),
+    ("cloud", ViewMode::Cloud),
+    ("bbrv3", ViewMode::BBRv3),
+    ("all", ViewMode::All),
+];
+
+/// The dashboard view when `[layout]` sets none
+fn default_layout() -> LayoutTree {
+    LayoutTree::columns(&[
+        &[WidgetKind::CurrentMetrics, WidgetKind::Latency, WidgetKind::Throughput, WidgetKind::Streams],
+        &[WidgetKind::Heatmap, WidgetKind::Cwnd, WidgetKind::Anomaly],
+    ])
+}
+
+/// Where the user left a view: focus, selections, scroll positions and zoom
+///
+/// The widgets are shared by every view and hold the state of the one shown;
+/// switching views stores theirs here and puts back what the next view had.
+#[derive(Debug, Clone)]
+struct ViewState {
+    focus: FocusState,
+    correlation: CorrelationCursor,
+    anomalies: AnomalyListCursor,
+    heatmap: Option<HeatmapCursor>,
+    latency_view: TimeView,
+    throughput_view: TimeView,
+}
+
+/// Options of `quic-bottom live` beyond the config file
+#[derive(Debug, Clone)]
+pub struct LiveOptions {
+    /// Serve the HTTP API only, without the TUI
+    pub headless: bool,
+    /// Append every ingested sample to this session file
+    pub record: Option<PathBuf>,
+    /// Play back this recorded session instead of serving the API
+    pub replay_session: Option<PathBuf>,
+    /// Playback speed multiplier for `replay_session`
+    pub speed: f64,
+    /// Saved run drawn behind the latency and throughput graphs
+    pub baseline: Option<PathBuf>,
+    /// Fast-forward the tail of an existing `record` file before the first frame
+    pub restore: bool,
+    /// Config file whose changes the TUI applies while running
+    pub config_watcher: Option<ConfigWatcher>,
+}
+
+/// A recorded session being played back
+struct SessionReplay {
+    replayer: Replayer<RealQUICMetrics>,
+    /// Arrival time of the first recorded sample
+    started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Real-time QUIC metrics from Go application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealQUICMetrics {
+    /// Connection the sample belongs to; aggregate samples leave this unset
+    #[serde(default)]
+    pub connection_id: Option<String>,
+    pub timestamp: u64,
+    pub latency: f64,
+    pub throughput: f64,
+    /// Unit of `throughput`; `[ingest] throughput_unit` when unset, bps once stored
+    #[serde(default)]
+    pub unit: Option<Unit>,
+    pub connections: i32,
+    pub errors: i32,
+    /// Fraction of packets lost, 0.0-1.0, once stored
+    pub packet_loss: f64,
+    /// Unit of `packet_loss`; `[ingest] loss_unit` when unset, a fraction once stored
+    #[serde(default)]
+    pub loss_unit: Option<LossUnit>,
+    pub retransmits: i32,
+    pub jitter: f64,
+    pub congestion_window: i32,
+    pub rtt: f64,
+    pub bytes_received: i64,
+    pub bytes_sent: i64,
+    pub streams: i32,
+    pub handshake_time: f64,
+    /// `"0rtt"` or `"1rtt"`
+    #[serde(default)]
+    pub handshake_type: Option<HandshakeType>,
+    #[serde(default)]
+    pub session_resumed: bool,
+    /// Cumulative packet counters
+    #[serde(default)]
+    pub packets_sent: Option<u64>,
+    #[serde(default)]
+    pub packets_received: Option<u64>,
+    #[serde(default)]
+    pub packets_lost: Option<u64>,
+    #[serde(default)]
+    pub packets_acked: Option<u64>,
+    /// Cumulative connection counters
+    #[serde(default)]
+    pub connections_opened: Option<u64>,
+    #[serde(default)]
+    pub connections_failed: Option<u64>,
+    #[serde(default)]
+    pub connections_closed: Option<u64>,
+    /// Sender label; the `source` query parameter or the client address when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    
+    /// BBRv3 state, only when the sender uses BBRv3
+    #[serde(flatten)]
+    pub bbrv3: BBRv3Metrics,
+}
+
+impl RealQUICMetrics {
+    /// Reject negative or non-finite measurements and counts
+    fn validate(&self) -> Result<()> {
+        ensure_non_negative("latency", self.latency)?;
+        ensure_non_negative("throughput", self.throughput)?;
+        ensure_non_negative("connections", self.connections as f64)?;
+        ensure_non_negative("errors", self.errors as f64)?;
+        ensure_loss(self.packet_loss, self.loss_unit)?;
+        ensure_non_negative("retransmits", self.retransmits as f64)?;
+        ensure_non_negative("jitter", self.jitter)?;
+        ensure_non_negative("congestion_window", self.congestion_window as f64)?;
+        ensure_non_negative("rtt", self.rtt)?;
+        ensure_non_negative("bytes_received", self.bytes_received as f64)?;
+        ensure_non_negative("bytes_sent", self.bytes_sent as f64)?;
+        ensure_non_negative("streams", self.streams as f64)?;
+        ensure_non_negative("handshake_time", self.handshake_time)?;
+        self.bbrv3.ensure_finite()?;
+        if let Some(source) = &self.source {
+            check_source_label(source)?;
+        }
+        Ok(())
+    }
+
+    /// Convert throughput to bits per second and packet loss to a fraction
+    ///
+    /// The units are set afterwards, so recordings and exports say what
+    /// they hold and a replayed sample is not converted twice.
+    fn normalize_units(&mut self) {
+        self.throughput = throughput_bps(self.throughput, self.unit);
+        self.unit = Some(Unit::Bps);
+        self.packet_loss = loss_fraction(self.packet_loss, self.loss_unit);
+        self.loss_unit = Some(LossUnit::Fraction);
+    }
+
+    /// Convert to the library's metrics type, using RTT as the latency figure
+    fn to_quic_metrics(&self) -> QUICMetrics {
+        QUICMetrics {
+            latency: self.rtt,
+            throughput: self.throughput,
+            connections: self.connections,
+            errors: self.errors,
+            packet_loss: self.packet_loss,
+            retransmits: self.retransmits,
+            timestamp: chrono::DateTime::from_timestamp_millis(self.timestamp as i64)
+                .unwrap_or_else(chrono::Utc::now),
+            rtt: self.rtt,
+            jitter: self.jitter,
+            congestion_window: self.congestion_window,
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            streams: self.streams,
+            handshake_time: self.handshake_time,
+            handshake_type: self.handshake_type,
+            session_resumed: self.session_resumed,
+            packets_sent: self.packets_sent,
+            packets_received: self.packets_received,
+            packets_lost: self.packets_lost,
+            packets_acked: self.packets_acked,
+            connections_opened: self.connections_opened,
+            connections_failed: self.connections_failed,
+            connections_closed: self.connections_closed,
+            source: self.source.clone(),
+            bbrv3: self.bbrv3.is_reported().then(|| self.bbrv3.clone()),
+        }
+    }
+}
+
+/// History as the report reads it, keeping the latency the graphs show
+fn report_samples(history: &MetricsHistory<RealQUICMetrics>) -> Vec<QUICMetrics> {
+    history
+        .iter()
+        .map(|metrics| QUICMetrics {
+            latency: metrics.latency,
+            ..metrics.to_quic_metrics()
+        })
+        .collect()
+}
+
+/// `GET /api/current` body: the latest sample, values derived from it and how long ago it arrived
+#[derive(Serialize)]
+struct CurrentResponse<'a> {
+    #[serde(flatten)]
+    metrics: &'a RealQUICMetrics,
+    derived: DerivedMetrics,
+    age_ms: u64,
+}
+
+impl Timestamped for RealQUICMetrics {
+    fn timestamp_ms(&self) -> i64 {
+        self.timestamp as i64
+    }
+}
+
+/// Real QUIC Bottom application
+pub struct RealQUICBottom {
+    // Basic graphs
+    latency_graph: SimpleQuicLatencyGraph,
+    throughput_graph: SimpleQuicThroughputGraph,
+    
+    // Enhanced analytics
+    performance_heatmap: QUICPerformanceHeatmap,
+    correlation_widget: QUICCorrelationWidget,
+    anomaly_widget: QUICAnomalyWidget,
+    connection_table: QUICConnectionTableWidget,
+    bbrv3_graphs: BBRv3GraphsWidget,
+    cwnd_widget: CongestionWindowWidget,
+    efficiency_widget: QUICEfficiencyWidget,
+    streams_widget: QUICStreamsWidget,
+    
+    // Real-time data
+    api: ApiState,
+    api_addr: SocketAddr,
+    api_config: ApiConfig,
+    uds_path: Option<PathBuf>,
+    reset_rx: mpsc::UnboundedReceiver<ResetRequest>,
+    /// Recorded session shown instead of the API's samples
+    replay: Option<SessionReplay>,
+    
+    // App state
+    should_quit: bool,
+    paused: bool,
+    show_help: bool,
+    keymap: KeyMap,
+    /// Configured theme and the built-in ones 't' steps through
+    themes: ThemeCycle,
+    /// Where the last frame drew the widgets the mouse reaches
+    hits: HitMap<Panel>,
+    /// Focused widget of the view (Tab, clicks) and whether it is expanded ('e')
+    focus: FocusState,
+    /// Widgets turned on and off under `[widgets]`
+    widget_config: WidgetConfig,
+    /// Grid of the dashboard view, from `[layout]`
+    layout: LayoutTree,
+    /// History position the widgets have caught up to; held while paused
+    seen_mark: u64,
+    /// What the last tick's screen showed besides the effects of input
+    screen: Option<ScreenState>,
+    update_interval: Duration,
+    /// Frame rate cap, from `max_fps`
+    max_fps: u32,
+    /// Built-in views and `[[views]]` presets, and the one shown
+    views: ViewRegistry<ViewMode>,
+    /// Preset selected in the open view picker (':')
+    view_picker: Option<usize>,
+    /// State of the views not shown, by preset name
+    view_states: HashMap<String, ViewState>,
+    time_slot: usize,
+    toast: Option<(String, Instant)>,
+    /// Ingest rate, totals and refusals in the footer
+    status_bar: StatusBar,
+    export_config: ExportConfig,
+    report_config: ReportConfig,
+    redactor: Redactor,
+    /// Whether exports and reports are redacted; `[redaction] enabled`, then toggled with 'P'
+    redact: bool,
+    staleness: StalenessConfig,
+    /// Config file reloaded while running
+    config_watcher: Option<ConfigWatcher>,
+    
+    /// Revision of `api.network` last seen, to notice changes made through the API
+    network_revision: u64,
+    
+    // Cloud deployment state
+    cloud_deployment_active: bool,
+    cloud_provider: String,
+    cloud_instances: usize,
+    cloud_status: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ViewMode {
+    Dashboard,
+    Analytics,
+    Network,
+    Security,
+    Cloud,
+    BBRv3,
+    All,
+}
+
+impl RealQUICBottom {
+    pub async fn new(interval_ms: u64, api_addr: SocketAddr) -> Result<Self> {
+        let (reset_tx, reset_rx) = mpsc::unbounded_channel();
+        Ok(Self {
+            latency_graph: SimpleQuicLatencyGraph::new(),
+            throughput_graph: SimpleQuicThroughputGraph::new(),
+            performance_heatmap: QUICPerformanceHeatmap::new(),
+            correlation_widget: QUICCorrelationWidget::new()
+                .with_sample_interval(Duration::from_millis(interval_ms)),
+            anomaly_widget: QUICAnomalyWidget::new(),
+            connection_table: QUICConnectionTableWidget::new(),
+            bbrv3_graphs: BBRv3GraphsWidget::new(1000),
+            cwnd_widget: CongestionWindowWidget::new(1000),
+            efficiency_widget: QUICEfficiencyWidget::new(1000),
+            streams_widget: QUICStreamsWidget::new(1000),
+            api: ApiState::new(connection_tracker(&QuicBottomConfig::default()), reset_tx),
+            api_addr,
+            api_config: ApiConfig::default(),
+            uds_path: None,
+            reset_rx,
+            replay: None,
+            should_quit: false,
+            paused: false,
+            show_help: false,
+            keymap: KeyMap::default(),
+            themes: ThemeCycle::default(),
+            hits: HitMap::new(),
+            focus: FocusState::default(),
+            widget_config: WidgetConfig::default(),
+            layout: default_layout(),
+            seen_mark: 0,
+            screen: None,
+            update_interval: Duration::from_millis(interval_ms),
+            max_fps: DEFAULT_MAX_FPS,
+            views: ViewRegistry::new(BUILTIN_VIEWS, &[]),
+            view_picker: None,
+            view_states: HashMap::new(),
+            time_slot: 0,
+            toast: None,
+            status_bar: StatusBar::new(),
+            export_config: ExportConfig::default(),
+            report_config: ReportConfig::default(),
+            redactor: Redactor::new(&RedactionConfig::default()),
+            redact: false,
+            staleness: StalenessConfig::default(),
+            config_watcher: None,
+            network_revision: 0,
+            cloud_deployment_active: false,
+            cloud_provider: "aws".to_string(),
+            cloud_instances: 2,
+            cloud_status: "running".to_string(),
+        })
+    }
+
+    /// Apply export, redaction, keybinding and color settings from the config
+    pub fn with_config(mut self, config: &QuicBottomConfig) -> Result<Self> {
+        let (keymap, warnings) = KeyMap::from_config(&config.keybindings);
+        self.keymap = keymap;
+        self.themes = ThemeCycle::new(config.colors.theme()?);
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        if !warnings.is_empty() {
+            self.show_toast(warnings.join("; "));
+        }
+        self.export_config = config.export.clone();
+        self.report_config = config.report.clone();
+        self.redactor = Redactor::new(&config.redaction);
+        self.redact = config.redaction.enabled;
+        self.staleness = config.staleness.clone();
+        self.max_fps = config.max_fps;
+        self.widget_config = config.widgets.clone();
+        self.layout = config.layout.tree().unwrap_or_else(default_layout);
+        self.views = ViewRegistry::new(BUILTIN_VIEWS, &config.views);
+        self.api_config = config.api.clone();
+        self.uds_path = config.ingest.uds_path.clone();
+        *self.api.derived_config.lock_or_recover() = config.derived.clone();
+        *self.api.sla_config.lock_or_recover() = config.sla.clone();
+        *self.api.health_config.lock_or_recover() = config.health.clone();
+        *self.api.network.lock_or_recover() = NetworkSimulation::from_config(&config.network);
+        *self.api.connection_tracker.lock_or_recover() = connection_tracker(config);
+        self.api.security.set_config(config.security.clone());
+        self.latency_graph = SimpleQuicLatencyGraph::with_max_points(config.max_data_points)
+            .with_scale(config.widgets.latency.scale);
+        self.throughput_graph = SimpleQuicThroughputGraph::with_max_points(config.max_data_points)
+            .with_scale(config.widgets.throughput.scale);
+        self.bbrv3_graphs = BBRv3GraphsWidget::new(config.max_data_points);
+        self.cwnd_widget = CongestionWindowWidget::new(config.max_data_points);
+        self.efficiency_widget = QUICEfficiencyWidget::new(config.max_data_points);
+        self.streams_widget = QUICStreamsWidget::new(config.max_data_points);
+        self.performance_heatmap = QUICPerformanceHeatmap::with_config(config.heatmap.clone());
+        self.correlation_widget = QUICCorrelationWidget::new()
+            .with_sample_interval(self.update_interval)
+            .with_config(config.correlation.clone());
+        self.anomaly_widget = QUICAnomalyWidget::with_config(&config.anomaly);
+        if config.alerting.is_enabled() {
+            let alerter = Alerter::new(config.alerting.clone())?;
+            self.anomaly_widget.set_alert_counters(alerter.counters());
+            alerter.spawn(self.anomaly_widget.subscribe());
+        }
+        Ok(self)
+    }
+
+    /// Apply changes to the config file without restarting; see `RELOADABLE`
+    pub fn with_config_watcher(mut self, watcher: ConfigWatcher) -> Self {
+        self.config_watcher = Some(watcher);
+        self
+    }
+
+    /// Reload the config file if it changed, keeping the running config when it fails to load
+    fn check_config(&mut self) {
+        let Some(result) = self.config_watcher.as_mut().and_then(ConfigWatcher::poll) else {
+            return;
+        };
+        let message = match result {
+            Ok(update) => {
+                let mut messages = vec![update.summary(RELOADABLE)];
+                messages.extend(self.apply_config_update(&update));
+                messages.join("; ")
+            }
+            Err(e) => {
+                log::warn!("{:#}", e);
+                failure_summary(&e)
+            }
+        };
+        log::info!("{}", message);
+        self.show_toast(message);
+    }
+
+    /// Switch to the reloadable settings that changed, returning warnings
+    ///
+    /// Collected data is kept: the anomaly detector keeps its history and
+    /// the API picks up the new derivation, SLA and health settings.
+    fn apply_config_update(&mut self, update: &ConfigUpdate) -> Vec<String> {
+        let config = &update.config;
+        let mut warnings = Vec::new();
+        if update.changed("update_interval") {
+            self.update_interval = Duration::from_millis(config.update_interval);
+            self.correlation_widget.set_sample_interval(self.update_interval);
+        }
+        if update.changed("colors") {
+            match config.colors.theme() {
+                Ok(theme) => self.themes = ThemeCycle::new(theme),
+                Err(e) => warnings.push(format!("{:#}", e)),
+            }
+        }
+        if update.changed("keybindings") {
+            let (keymap, keymap_warnings) = KeyMap::from_config(&config.keybindings);
+            self.keymap = keymap;
+            warnings.extend(keymap_warnings);
+        }
+        if update.changed("staleness") {
+            self.staleness = config.staleness.clone();
+        }
+        if update.changed("derived") {
+            *self.api.derived_config.lock_or_recover() = config.derived.clone();
+        }
+        if update.changed("sla") {
+            *self.api.sla_config.lock_or_recover() = config.sla.clone();
+        }
+        if update.changed("health") {
+            *self.api.health_config.lock_or_recover() = config.health.clone();
+        }
+        if update.changed("anomaly") {
+            self.anomaly_widget.set_config(&config.anomaly);
+        }
+        if update.changed("security") {
+            self.api.security.set_config(config.security.clone());
+        }
+        if update.changed("widgets") {
+            self.widget_config = config.widgets.clone();
+        }
+        if update.changed("layout") {
+            self.layout = config.layout.tree().unwrap_or_else(default_layout);
+        }
+        if update.changed("views") {
+            self.switch_view(|views| views.reload(BUILTIN_VIEWS, &config.views));
+            let presets = self.views.presets();
+            self.view_states
+                .retain(|name, _| presets.iter().any(|preset| preset.name == *name));
+            self.view_picker = None;
+        }
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        warnings
+    }
+
+    /// Forward every ingested sample to an InfluxDB exporter
+    pub fn with_exporter(mut self, queue: Arc<InfluxQueue>) -> Self {
+        self.api.exporter = Some(queue);
+        self
+    }
+
+    /// Append every ingested sample to `recorder`
+    pub fn with_recorder(mut self, recorder: Arc<SessionRecorder>) -> Self {
+        self.api.recorder = Some(recorder);
+        self
+    }
+
+    /// Draw a saved run behind the latency and throughput graphs
+    pub fn with_baseline(mut self, baseline: Baseline) -> Self {
+        self.set_baseline(Some(baseline));
+        self
+    }
+
+    fn set_baseline(&mut self, baseline: Option<Baseline>) {
+        let (latency, throughput) = match baseline {
+            Some(baseline) => (Some(baseline.latency), Some(baseline.throughput)),
+            None => (None, None),
+        };
+        self.latency_graph.set_baseline(latency);
+        self.throughput_graph.set_baseline(throughput);
+    }
+
+    /// Compare against the newest export in the export directory
+    fn load_latest_baseline(&mut self) {
+        let message = match latest_export(&self.export_config.output_dir) {
+            None => format!("No export in {} to use as baseline", self.export_config.output_dir.display()),
+            Some(path) => match Baseline::load(&path) {
+                Ok(baseline) => {
+                    let message = format!(
+                        "Baseline: {} ({})",
+                        path.display(),
+                        format_span(baseline.duration())
+                    );
+                    self.set_baseline(Some(baseline));
+                    message
+                }
+                Err(e) => format!("Baseline not loaded: {}", e),
+            },
+        };
+        self.show_toast(message);
+    }
+
+    /// Play back a recorded session instead of serving the API
+    pub fn with_session_replay(mut self, session: Session<RealQUICMetrics>, speed: f64) -> Self {
+        self.replay = Some(SessionReplay {
+            replayer: Replayer::new(session.samples, speed),
+            started_at: session.started_at,
+        });
+        self
+    }
+
+    /// Fast-forward the tail of an earlier recording through the analytics before the first frame
+    ///
+    /// The samples take the path live ones do, so anomaly baselines,
+    /// correlation windows and the heatmap start where the earlier run left
+    /// them. They are not recorded or exported again.
+    pub fn with_restored_history(mut self, session: Session<RealQUICMetrics>) -> Self {
+        let samples = restore_tail(session, RESTORE_WINDOW, MAX_RESTORED_SAMPLES);
+        let (Some((first, _)), Some((last, _))) = (samples.first(), samples.last()) else {
+            return self;
+        };
+        let span = (*last - *first).to_std().unwrap_or_default();
+        let count = samples.len();
+        for (at, mut metrics) in samples {
+            metrics.normalize_units();
+            self.api.restore(metrics.clone());
+            self.ingest_sample(&metrics, at);
+        }
+        self.seen_mark = self.api.metrics_history.lock_or_recover().total_pushed();
+        self.show_toast(format!("Restored {} of history ({} samples)", format_span(span), count));
+        self
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        if self.replay.is_some() {
+            AppCore::new(self.update_interval)
+                .with_max_fps(self.max_fps)
+                .run(self)
+                .await?;
+            self.report_on_exit();
+            return Ok(());
+        }
+
+        // Binding up front surfaces errors before the TUI takes over
+        let ready = start_http_server(self.api_addr, self.api.clone(), &self.api_config)?;
+        let bound = ready.await.map_err(|_| anyhow!("HTTP API server stopped before it was ready"))?;
+        log::info!("HTTP API server listening on {}", bound);
+        #[cfg(unix)]
+        if let Some(path) = &self.uds_path {
+            spawn_uds_ingest(path, self.api.clone())?;
+        }
+
+        AppCore::new(self.update_interval)
+            .with_max_fps(self.max_fps)
+            .run(self)
+            .await?;
+        if let Some(queue) = &self.api.exporter {
+            shutdown::flush_exporter(queue).await;
+        }
+        self.report_on_exit();
+        Ok(())
+    }
+
+    /// Summarize the history with the anomalies and correlations found
+    fn write_report(&self) -> Result<ReportPaths> {
+        let samples = report_samples(&self.api.metrics_history.lock_or_recover());
+        let report = SessionReport::build(
+            &samples,
+            &self.anomaly_widget.anomalies(),
+            self.correlation_widget.correlations(),
+            chrono::Utc::now(),
+        )
+        .with_health(self.api.health_score());
+        write_report(&self.report_config, &report, self.redactor())
+    }
+
+    /// Write the report after the TUI has closed, if configured and there is anything to report
+    fn report_on_exit(&self) {
+        if !self.report_config.on_exit || self.api.metrics_history.lock_or_recover().is_empty() {
+            return;
+        }
+        match self.write_report() {
+            Ok(paths) => println!("{}", paths),
+            Err(e) => eprintln!("Report failed: {}", e),
+        }
+    }
+
+    fn update_all_widgets(&mut self) {
+        self.api.connection_tracker.lock_or_recover().evict_expired();
+
+        if let Some(mut replay) = self.replay.take() {
+            for (offset, metrics) in replay.replayer.advance() {
+                // Placed at the original arrival time, as the live run showed them
+                let at = replay.started_at + chrono::Duration::from_std(*offset).unwrap_or_default();
+                let mut metrics = metrics.clone();
+                metrics.normalize_units();
+                if let Err(e) = self.api.ingest(metrics.clone()) {
+                    log::warn!("Replayed sample skipped: {}", e);
+                    continue;
+                }
+                self.ingest_sample(&metrics, at);
+            }
+            self.seen_mark = self.api.metrics_history.lock_or_recover().total_pushed();
+            self.replay = Some(replay);
+            return;
+        }
+
+        // Samples keep landing in the history while paused and are replayed on resume
+        if !self.paused {
+            self.catch_up(false);
+        }
+    }
+
+    /// Feed the samples stored since the last call to the widgets, returning how many
+    ///
+    /// They are placed at the current time, or with `backfill` where they
+    /// were reported, for samples held back by a pause.
+    fn catch_up(&mut self, backfill: bool) -> usize {
+        let samples: Vec<RealQUICMetrics> = {
+            let history = self.api.metrics_history.lock_or_recover();
+            let samples = history.iter_since(self.seen_mark).cloned().collect();
+            self.seen_mark = history.total_pushed();
+            samples
+        };
+        let now = chrono::Utc::now();
+        for metrics in &samples {
+            let at = if backfill { metrics.to_quic_metrics().timestamp } else { now };
+            self.ingest_sample(metrics, at);
+        }
+        samples.len()
+    }
+
+    /// What the screen shows now besides the effects of input
+    fn screen_state(&self) -> ScreenState {
+        let age = self.api.last_update_age();
+        ScreenState {
+            seen_mark: self.seen_mark,
+            notice: self
+                .toast
+                .as_ref()
+                .map(|(_, shown_at)| *shown_at)
+                .filter(|shown_at| shown_at.elapsed() < TOAST_DURATION),
+            age_secs: age.map(|age| age.as_secs()),
+            freshness: self.staleness.freshness(age),
+            replay: self.replay.as_ref().map(|replay| replay.replayer.position()),
+            network_revision: self.network_revision,
+            security_revision: self.api.security.revision(),
+            connections: self.api.connection_tracker.lock_or_recover().len(),
+        }
+    }
+
+    /// Feed one sample into the graphs, heatmap, correlation and anomaly widgets
+    ///
+    /// `at` places the sample on the graphs' time axis.
+    fn ingest_sample(&mut self, metrics: &RealQUICMetrics, at: chrono::DateTime<chrono::Utc>) {
+        // Apply network simulation effects
+        let (adjusted_latency, adjusted_throughput, adjusted_loss) = self.api.network.lock_or_recover().apply(
+            metrics.latency, metrics.throughput, metrics.packet_loss
+        );
+
+        // Update basic graphs
+        self.latency_graph.add_latency_at(at, adjusted_latency);
+        self.throughput_graph.add_throughput_at(at, adjusted_throughput);
+        self.bbrv3_graphs.add(&metrics.bbrv3);
+        self.cwnd_widget
+            .update(metrics.congestion_window as f64, metrics.bbrv3.inflight_target);
+        self.efficiency_widget.update(self.api.derive(metrics));
+        // Rates need the sender's clock; `at` moves on while a sample is shown again
+        let captured_at = chrono::DateTime::from_timestamp_millis(metrics.timestamp as i64).unwrap_or(at);
+        self.streams_widget
+            .update(captured_at, metrics.streams, adjusted_throughput);
+
+        // The analytics show packet loss in percent
+        let loss_pct = adjusted_loss * 100.0;
+
+        // Update enhanced analytics
+        self.performance_heatmap.add_metric(at, "Latency", adjusted_latency);
+        self.performance_heatmap.add_metric(at, "Throughput", adjusted_throughput);
+        self.performance_heatmap.add_metric(at, "Packet Loss", loss_pct);
+        self.performance_heatmap.add_metric(at, "Connections", metrics.connections as f64);
+        self.performance_heatmap.add_metric(at, "Errors", metrics.errors as f64);
+        self.performance_heatmap.add_metric(at, "RTT", metrics.rtt);
+        self.performance_heatmap.add_metric(at, "Jitter", metrics.jitter);
+        self.performance_heatmap.add_metric(at, "CWND", metrics.congestion_window as f64);
+        self.performance_heatmap.add_metric(at, "Retransmits", metrics.retransmits as f64);
+
+        // Update correlation data - include more metrics that change
+        self.correlation_widget.add_metric_data("Latency".to_string(), adjusted_latency);
+        self.correlation_widget.add_metric_data("Throughput".to_string(), adjusted_throughput);
+        self.correlation_widget.add_metric_data("Packet Loss".to_string(), loss_pct);
+        self.correlation_widget.add_metric_data("RTT".to_string(), metrics.rtt);
+        self.correlation_widget.add_metric_data("Jitter".to_string(), metrics.jitter);
+        self.correlation_widget.add_metric_data("Retransmits".to_string(), metrics.retransmits as f64);
+        // Only add Connections and Errors if they change (to avoid constant values)
+        if metrics.connections > 0 {
+            self.correlation_widget.add_metric_data("Connections".to_string(), metrics.connections as f64);
+        }
+        if metrics.errors > 0 {
+            self.correlation_widget.add_metric_data("Errors".to_string(), metrics.errors as f64);
+        }
+        self.correlation_widget.update_correlations();
+
+        // Update anomaly detection; warm-up and cool-down follow `at`, so a fast-forward times them as live did
+        self.anomaly_widget.add_quic_metric_at("Latency".to_string(), adjusted_latency, at);
+        self.anomaly_widget.add_quic_metric_at("Throughput".to_string(), adjusted_throughput, at);
+        self.anomaly_widget.add_quic_metric_at("Packet Loss".to_string(), loss_pct, at);
+        self.anomaly_widget.add_quic_metric_at("Connections".to_string(), metrics.connections as f64, at);
+        self.anomaly_widget.add_quic_metric_at("Errors".to_string(), metrics.errors as f64, at);
+
+        // Update time slot
+        self.time_slot = (self.time_slot + 1) % 20;
+    }
+
+
+    fn toggle_pause(&mut self) {
+        if !self.paused {
+            self.paused = true;
+            return;
+        }
+
+        // Backfilled samples go where they were reported, not at the resume time
+        let backfilled = self.catch_up(true);
+        self.paused = false;
+        self.show_toast(format!("Resumed, backfilled {} samples", backfilled));
+    }
+
+    /// Whether the current view shows the correlation matrix
+    fn shows_correlation(&self) -> bool {
+        self.panels().contains(&Panel::Correlation)
+    }
+
+    /// Move through and pick from the view picker while it is open
+    fn handle_picker_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(selected) = self.view_picker else {
+            return false;
+        };
+        let count = self.views.presets().len();
+        match key.code {
+            KeyCode::Up => self.view_picker = Some((selected + count - 1) % count),
+            KeyCode::Down => self.view_picker = Some((selected + 1) % count),
+            KeyCode::Enter => {
+                self.switch_view(|views| {
+                    views.select(selected);
+                });
+                self.view_picker = None;
+            }
+            KeyCode::Esc | KeyCode::Char(':') => self.view_picker = None,
+            _ => {}
+        }
+        true
+    }
+
+    /// Whether the correlation widget has the focus, or is alone in the view
+    fn correlation_focused(&self) -> bool {
+        self.shows_correlation()
+            && (self.focus.focused() == Some(Panel::Correlation) || self.panels() == [Panel::Correlation])
+    }
+
+    /// Only count anomalies while a reset or network simulation change settles
+    fn suppress_anomalies(&mut self) {
+        let window = self.anomaly_widget.suppression();
+        self.anomaly_widget.suppress(window);
+    }
+
+    /// Suppress anomalies after a simulation change made through `PUT /api/simulation`
+    fn process_remote_simulation(&mut self) {
+        let revision = self.api.network.lock_or_recover().revision();
+        if revision != self.network_revision {
+            self.network_revision = revision;
+            self.suppress_anomalies();
+        }
+    }
+
+    /// Whether the anomaly widget has the focus, or is alone in the view
+    fn anomaly_focused(&self) -> bool {
+        self.panels().contains(&Panel::Anomaly)
+            && (self.focus.focused() == Some(Panel::Anomaly) || self.panels() == [Panel::Anomaly])
+    }
+
+    fn navigate_graphs(&mut self, nav: TimeNav) {
+        self.latency_graph.navigate(nav);
+        self.throughput_graph.navigate(nav);
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        if self.handle_picker_key(&key)
+            || self.handle_connection_key(&key)
+            || self.handle_inspect_key(&key)
+            || self.handle_anomaly_key(&key)
+            || self.handle_correlation_key(&key)
+        {
+            return;
+        }
+        let Some(action) = self.keymap.action(AppKind::Real, &key) else {
+            return;
+        };
+        match action {
+            Action::Quit => {
+                self.should_quit = true;
+            }
+            Action::Reset => {
+                // Reset all data
+                self.apply_reset(ResetScope::full());
+            }
+            Action::Help => {
+                self.show_help = true;
+            }
+            Action::CorrelationMethod if self.shows_correlation() => {
+                self.correlation_widget.toggle_method();
+            }
+            Action::CorrelationLayout if self.shows_correlation() => {
+                self.correlation_widget.cycle_layout();
+            }
+            Action::Rebaseline => {
+                self.anomaly_widget.rebaseline();
+            }
+            Action::Inspect if self.panels().contains(&Panel::Heatmap) => {
+                self.performance_heatmap.toggle_inspect();
+            }
+            Action::ToggleScale => {
+                self.latency_graph.toggle_scale();
+                self.throughput_graph.toggle_scale();
+            }
+            // Time graph zoom and scroll-back
+            Action::ZoomIn => self.navigate_graphs(TimeNav::ZoomIn),
+            Action::ZoomOut => self.navigate_graphs(TimeNav::ZoomOut),
+            Action::ScrollBack => self.navigate_graphs(TimeNav::PanBack),
+            Action::ScrollForward => self.navigate_graphs(TimeNav::PanForward),
+            Action::Oldest => self.navigate_graphs(TimeNav::Oldest),
+            Action::Newest => self.navigate_graphs(TimeNav::Live),
+            Action::Pause => match &mut self.replay {
+                Some(replay) => replay.replayer.toggle_pause(),
+                None => self.toggle_pause(),
+            },
+            Action::LoadBaseline => self.load_latest_baseline(),
+            Action::ClearBaseline => {
+                self.set_baseline(None);
+                self.show_toast("Baseline cleared".to_string());
+            }
+            Action::CycleTheme => {
+                let message = format!("Theme: {}", self.themes.advance().label());
+                self.show_toast(message);
+            }
+            Action::Export => {
+                self.export_history();
+            }
+            Action::Report => {
+                let message = match self.write_report() {
+                    Ok(paths) => paths.to_string(),
+                    Err(e) => format!("Report failed: {}", e),
+                };
+                self.show_toast(message);
+            }
+            Action::ToggleRedaction => {
+                self.redact = !self.redact;
+                let message = if self.redact {
+                    "Redacting exports and reports"
+                } else {
+                    "Exports and reports are not redacted"
+                };
+                self.show_toast(message.to_string());
+            }
+            // Connection table sorting
+            Action::SortConnections => {
+                self.connection_table.next_sort_column();
+            }
+            Action::ReverseSort => {
+                self.connection_table.toggle_sort_order();
+            }
+            Action::TraceConnection if self.shows_network() => self.toggle_trace(),
+            // View switching
+            Action::ViewDashboard => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::Dashboard);
+                });
+            }
+            Action::ViewAnalytics => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::Analytics);
+                });
+            }
+            Action::ViewNetwork => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::Network);
+                });
+            }
+            Action::ViewSecurity => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::Security);
+                });
+            }
+            Action::ViewCloud => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::Cloud);
+                });
+            }
+            Action::ViewBbrv3 => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::BBRv3);
+                });
+            }
+            Action::ViewAll => {
+                self.switch_view(|views| {
+                    views.select_builtin(ViewMode::All);
+                });
+            }
+            Action::NextView | Action::PrevView => {
+                let forward = action == Action::NextView;
+                self.switch_view(|views| views.cycle(forward));
+            }
+            Action::ViewPicker => {
+                self.view_picker = Some(self.views.active_index());
+            }
+            Action::FocusNext | Action::FocusPrev => {
+                self.focus.cycle(&self.panels(), action == Action::FocusNext);
+                self.apply_focus();
+            }
+            Action::Expand => {
+                self.focus.toggle_expanded(&self.panels());
+            }
+            // Network simulation controls
+            Action::ToggleNetwork => {
+                self.network_revision = {
+                    let mut network = self.api.network.lock_or_recover();
+                    network.toggle();
+                    network.revision()
+                };
+                self.suppress_anomalies();
+            }
+            Action::NextPreset => {
+                self.network_revision = {
+                    let mut network = self.api.network.lock_or_recover();
+                    network.next_preset();
+                    network.revision()
+                };
+                self.suppress_anomalies();
+            }
+            Action::PrevPreset => {
+                self.network_revision = {
+                    let mut network = self.api.network.lock_or_recover();
+                    network.prev_preset();
+                    network.revision()
+                };
+                self.suppress_anomalies();
+            }
+            Action::ToggleSecurity => {
+                self.run_security_checks();
+            }
+            // Cloud deployment controls
+            Action::ToggleCloud => {
+                self.toggle_cloud_deployment();
+            }
+            Action::ScaleInstances => {
+                self.scale_cloud_instances();
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the network view, with the connection table, is shown
+    fn shows_network(&self) -> bool {
+        matches!(self.views.active().content, PresetContent::Builtin(ViewMode::Network))
+            && self.focus.expanded(&self.panels()).is_none()
+    }
+
+    /// Keys selecting a row of the connection table in the network view
+    fn handle_connection_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.shows_network() {
+            return false;
+        }
+        let delta = match key.code {
+            KeyCode::Up => -1,
+            KeyCode::Down => 1,
+            _ => return false,
+        };
+        let tracker = self.api.connection_tracker.lock_or_recover();
+        self.connection_table.move_selection(&tracker, delta);
+        true
+    }
+
+    /// Pin the selected connection for tracing, or unpin the one pinned
+    fn toggle_trace(&mut self) {
+        let selected = {
+            let tracker = self.api.connection_tracker.lock_or_recover();
+            self.connection_table.selected(&tracker)
+        };
+        let mut tracker = self.api.connection_tracker.lock_or_recover();
+        let pinned = tracker.pinned().map(|trace| trace.connection_id().to_string());
+        // A row other than the pinned one is traced instead; otherwise the trace stops
+        let message = match selected.filter(|id| pinned.as_ref() != Some(id)) {
+            Some(selected) => match tracker.pin(&selected, &self.export_config.output_dir) {
+                Ok(trace) => format!("Tracing {} to {}", selected, trace.path().display()),
+                Err(e) => format!("Trace not started: {:#}", e),
+            },
+            None => match tracker.unpin() {
+                Some(trace) => format!(
+                    "Stopped tracing {}: {} samples in {}",
+                    trace.connection_id(),
+                    trace.written(),
+                    trace.path().display()
+                ),
+                None => "Select a connection with Up/Down to trace it".to_string(),
+            },
+        };
+        drop(tracker);
+        self.show_toast(message);
+    }
+
+    /// Keys of the heatmap's inspect mode, which take precedence while it is on
+    fn handle_inspect_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.performance_heatmap.is_inspecting() || !self.panels().contains(&Panel::Heatmap) {
+            return false;
+        }
+        match key.code {
+            KeyCode::Esc => self.performance_heatmap.toggle_inspect(),
+            KeyCode::Up => self.performance_heatmap.move_cursor(-1, 0),
+            KeyCode::Down => self.performance_heatmap.move_cursor(1, 0),
+            KeyCode::Left => self.performance_heatmap.move_cursor(0, -1),
+            KeyCode::Right => self.performance_heatmap.move_cursor(0, 1),
+            KeyCode::Char('[') => self.performance_heatmap.jump_to_spike(false),
+            KeyCode::Char(']') => self.performance_heatmap.jump_to_spike(true),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys of the correlation matrix and its detail view, which take precedence there
+    /// Keys of the focused anomaly list: select, filter, acknowledge
+    fn handle_anomaly_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.anomaly_focused() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Up => self.anomaly_widget.move_selection(-1),
+            KeyCode::Down => self.anomaly_widget.move_selection(1),
+            KeyCode::PageUp => self.anomaly_widget.page_selection(-1),
+            KeyCode::PageDown => self.anomaly_widget.page_selection(1),
+            KeyCode::Char('c') => self.anomaly_widget.toggle_critical_only(),
+            KeyCode::Char('m') => self.anomaly_widget.cycle_metric_filter(),
+            KeyCode::Enter => self.anomaly_widget.acknowledge_selected(),
+            KeyCode::Delete => self.anomaly_widget.clear_acknowledged(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_correlation_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.shows_correlation() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Esc if self.correlation_widget.is_detail_open() => self.correlation_widget.close_detail(),
+            KeyCode::Up => self.correlation_widget.move_selection(-1, 0),
+            KeyCode::Down => self.correlation_widget.move_selection(1, 0),
+            KeyCode::Left => self.correlation_widget.move_selection(0, -1),
+            KeyCode::Right => self.correlation_widget.move_selection(0, 1),
+            KeyCode::PageUp => self.correlation_widget.page_selection(-1),
+            KeyCode::PageDown => self.correlation_widget.page_selection(1),
+            KeyCode::Char('+') if self.correlation_focused() => self.correlation_widget.resize_window(1),
+            KeyCode::Char('-') if self.correlation_focused() => self.correlation_widget.resize_window(-1),
+            KeyCode::Enter => self.correlation_widget.open_detail(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Clicks focus a widget or pick a correlation pair; the wheel zooms the time graphs
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        let clicked = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        // A click closes the help overlay, like any key
+        if self.show_help {
+            if clicked {
+                self.show_help = false;
+            }
+            return;
+        }
+
+        let hit = self.hits.hit(mouse.column, mouse.row);
+        let over_graph = matches!(hit, Some((Panel::LatencyGraph | Panel::ThroughputGraph, _)));
+        match mouse.kind {
+            MouseEventKind::ScrollUp if over_graph => self.navigate_graphs(TimeNav::ZoomIn),
+            MouseEventKind::ScrollDown if over_graph => self.navigate_graphs(TimeNav::ZoomOut),
+            _ if clicked => {
+                self.focus.set(hit.map(|(panel, _)| panel));
+                self.apply_focus();
+                if let Some((Panel::Correlation, area)) = hit {
+                    if self.correlation_widget.select_at(area, Position::new(mouse.column, mouse.row)) {
+                        self.correlation_widget.open_detail();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reset the selected parts of the app state; shared by the 'r' key and the API
+    fn apply_reset(&mut self, scope: ResetScope) {
+        if scope.stats {
+            self.latency_graph.clear();
+            self.throughput_graph.clear();
+            self.performance_heatmap.clear();
+            self.correlation_widget.clear();
+            self.bbrv3_graphs.clear();
+            self.cwnd_widget.clear();
+            self.efficiency_widget.clear();
+            self.streams_widget.clear();
+            self.api.connection_tracker.lock_or_recover().clear();
+            self.time_slot = 0;
+            // Other views' zoom may be panned into history that is gone now
+            for state in self.view_states.values_mut() {
+                state.latency_view.go_live();
+                state.throughput_view.go_live();
+            }
+        }
+
+        if scope.anomalies {
+            self.anomaly_widget.clear();
+            self.suppress_anomalies();
+        }
+
+        if scope.history {
+            let mut history = self.api.metrics_history.lock_or_recover();
+            history.clear();
+        }
+    }
+
+    /// Hand the anomalies of the health window to the API's health score
+    fn publish_anomaly_counts(&self) {
+        let window = self.api.health_config.lock_or_recover().anomaly_window();
+        let counts = self.anomaly_widget.recent_counts(window);
+        *self.api.anomaly_counts.lock_or_recover() = Some(counts);
+    }
+
+    fn process_remote_resets(&mut self) {
+        while let Ok((outcome, caller)) = self.reset_rx.try_recv() {
+            self.apply_reset(outcome.scope);
+            // The samples before the reset no longer describe the connection
+            self.anomaly_widget.rebaseline();
+            let segment = match outcome.scope.segments {
+                SegmentReset::New => format!(", segment {} started", outcome.segment),
+                SegmentReset::Keep => String::new(),
+            };
+            self.show_toast(format!("Reset via API by {}{}", caller, segment));
+        }
+    }
+
+    /// The redactor, while exports and reports are redacted
+    fn redactor(&self) -> Option<&Redactor> {
+        self.redact.then_some(&self.redactor)
+    }
+
+    /// Dump the full metrics history to CSV and JSON
+    fn export_history(&mut self) {
+        let history = self.api.metrics_history.lock_or_recover().clone();
+        let message = match export_session(&self.export_config, history.iter(), self.redactor()) {
+            Ok(summary) => summary.to_string(),
+            Err(e) => format!("Export failed: {}", e),
+        };
+        self.show_toast(message);
+    }
+
+    fn show_toast(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+    }
+
+
+
+
+
+    /// Start the security checks that may run again, saying which
+    fn run_security_checks(&mut self) {
+        let message = match self.api.security.run() {
+            Ok(request) => {
+                let held: Vec<String> = request
+                    .held
+                    .iter()
+                    .map(|held| format!("{} for {}s", held.check.label(), held.retry_in_secs))
+                    .collect();
+                if request.started.is_empty() {
+                    format!("Security checks ran too recently: {}", held.join(", "))
+                } else if held.is_empty() {
+                    format!("Running {} security check(s)", request.started.len())
+                } else {
+                    format!("Running {} security check(s); held: {}", request.started.len(), held.join(", "))
+                }
+            }
+            Err(e) => format!("Security checks not run: {:#}", e),
+        };
+        self.show_toast(message);
+    }
+
+    fn toggle_cloud_deployment(&mut self) {
+        self.cloud_deployment_active = !self.cloud_deployment_active;
+        if self.cloud_deployment_active {
+            self.cloud_status = "running".to_string();
+        } else {
+            self.cloud_status = "stopped".to_string();
+        }
+    }
+
+    fn scale_cloud_instances(&mut self) {
+        if self.cloud_deployment_active {
+            self.cloud_instances = (self.cloud_instances % 5) + 1;
+        }
+    }
+
+    /// Focusable widgets of the current view, in Tab order
+    ///
+    /// Widgets turned off under `[widgets]` are left out.
+    fn panels(&self) -> Vec<Panel> {
+        let grid_panels = |tree: &LayoutTree| -> Vec<Panel> {
+            tree.rows
+                .iter()
+                .flat_map(|row| &row.columns)
+                .flat_map(|column| &column.widgets)
+                .filter_map(|&widget| Panel::for_widget(widget))
+                .collect()
+        };
+        let panels = match &self.views.active().content {
+            PresetContent::Layout(tree) => grid_panels(tree),
+            PresetContent::Builtin(ViewMode::Dashboard) => grid_panels(&self.layout),
+            PresetContent::Builtin(ViewMode::Analytics) => vec![Panel::Correlation, Panel::Anomaly],
+            PresetContent::Builtin(ViewMode::All) => vec![
+                Panel::LatencyGraph,
+                Panel::ThroughputGraph,
+                Panel::Heatmap,
+                Panel::Correlation,
+                Panel::Anomaly,
+            ],
+            PresetContent::Builtin(ViewMode::Network | ViewMode::Security | ViewMode::Cloud | ViewMode::BBRv3) => {
+                Vec::new()
+            }
+        };
+        panels
+            .into_iter()
+            .filter(|panel| self.widget_config.is_enabled(panel.widget()))
+            .collect()
+    }
+
+    /// Change the shown view with `select`, keeping each view's state
+    ///
+    /// The view being left stores its state and the one shown gets back what
+    /// it had, so selections and zoom survive a trip elsewhere. A view shown
+    /// for the first time starts from the state of the one before it.
+    fn switch_view(&mut self, select: impl FnOnce(&mut ViewRegistry<ViewMode>)) {
+        let before = self.views.active().name.clone();
+        select(&mut self.views);
+        let after = &self.views.active().name;
+        if *after == before {
+            return;
+        }
+        let state = self.view_state();
+        if let Some(saved) = self.view_states.remove(after) {
+            self.restore_view_state(saved);
+        }
+        self.view_states.insert(before, state);
+    }
+
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            focus: self.focus.clone(),
+            correlation: self.correlation_widget.cursor(),
+            anomalies: self.anomaly_widget.cursor(),
+            heatmap: self.performance_heatmap.cursor(),
+            latency_view: self.latency_graph.time_view(),
+            throughput_view: self.throughput_graph.time_view(),
+        }
+    }
+
+    fn restore_view_state(&mut self, state: ViewState) {
+        self.focus = state.focus;
+        self.correlation_widget.set_cursor(state.correlation);
+        self.anomaly_widget.set_cursor(state.anomalies);
+        self.performance_heatmap.set_cursor(state.heatmap);
+        self.latency_graph.set_time_view(state.latency_view);
+        self.throughput_graph.set_time_view(state.throughput_view);
+        self.apply_focus();
+    }
+
+    /// Show the focus on the widgets themselves
+    fn apply_focus(&mut self) {
+        let focused = self.focus.focused();
+        self.latency_graph.set_selected(focused == Some(Panel::LatencyGraph));
+        self.throughput_graph.set_selected(focused == Some(Panel::ThroughputGraph));
+        self.performance_heatmap.set_selected(focused == Some(Panel::Heatmap));
+        self.correlation_widget.set_selected(focused == Some(Panel::Correlation));
+        self.anomaly_widget.set_selected(focused == Some(Panel::Anomaly));
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        self.hits.clear();
+        if let Some(panel) = self.focus.expanded(&self.panels()) {
+            self.render_expanded(f, panel);
+        } else {
+            match &self.views.active().content {
+                PresetContent::Builtin(ViewMode::Dashboard) => {
+                    self.render_grid(f, "Real QUIC Bottom - Dashboard", &self.layout)
+                }
+                PresetContent::Builtin(ViewMode::Analytics) => self.render_analytics_view(f),
+                PresetContent::Builtin(ViewMode::Network) => self.render_network_view(f),
+                PresetContent::Builtin(ViewMode::Security) => self.render_security_view(f),
+                PresetContent::Builtin(ViewMode::Cloud) => self.render_cloud_view(f),
+                PresetContent::Builtin(ViewMode::BBRv3) => self.render_bbrv3_view(f),
+                PresetContent::Builtin(ViewMode::All) => self.render_all_view(f),
+                PresetContent::Layout(tree) => self.render_grid(f, "View preset", tree),
+            }
+        }
+
+        if self.show_help {
+            render_help_overlay(f, AppKind::Real, &self.keymap, self.themes.current());
+        }
+        if let Some(selected) = self.view_picker {
+            render_view_picker(f, &self.views, selected, self.themes.current());
+        }
+    }
+
+    /// Draw `panel` over `area` and remember where, for the mouse
+    fn render_panel(&self, f: &mut Frame, panel: Panel, area: Rect) {
+        self.hits.record(panel, area);
+        let theme = self.themes.current();
+        match panel {
+            Panel::LatencyGraph => self.latency_graph.render(f, area, theme),
+            Panel::ThroughputGraph => self.throughput_graph.render(f, area, theme),
+            Panel::Heatmap => self.performance_heatmap.render(f, area, theme),
+            Panel::Correlation => self.correlation_widget.render(f, area, theme),
+            Panel::Anomaly => self.anomaly_widget.render(f, area, theme),
+        }
+    }
+
+    /// Lay the view's enabled panels out over `area`, or a notice when `[widgets]` turned them all off
+    fn render_panels(&self, f: &mut Frame, area: Rect) {
+        let panels = self.panels();
+        if panels.is_empty() {
+            render_no_widgets(f, area, self.themes.current());
+            return;
+        }
+        let kinds: Vec<WidgetKind> = panels.iter().map(|panel| panel.widget()).collect();
+        for (panel, area) in panels.into_iter().zip(build_layout(&kinds, area)) {
+            self.render_panel(f, panel, area);
+        }
+    }
+
+    /// The focused widget alone between the header and footer
+    fn render_expanded(&self, f: &mut Frame, panel: Panel) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Expanded widget
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], &format!("{} (expanded)", panel.name()));
+        self.render_panel(f, panel, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    /// The header, `tree`'s widgets and the footer; the dashboard and the `[[views]]` presets
+    fn render_grid(&self, f: &mut Frame, title: &str, tree: &LayoutTree) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], title);
+
+        // Current metrics widget; a replay's gaps are the recording's, not staleness
+        let stale = self.replay.is_none()
+            && self.staleness.freshness(self.api.last_update_age()) == Freshness::Stale;
+        let metrics_opt = self.api.current_metrics.lock_or_recover();
+        let metrics_text = if let Some(metrics) = metrics_opt.as_ref() {
+            let connections = if stale {
+                "STALE (no recent updates)".to_string()
+            } else {
+                metrics.connections.to_string()
+            };
+            format!(
+                "Connections: {}\nLatency: {:.2} ms\nThroughput: {}\nRTT: {:.2} ms\nPacket Loss: {:.2}%\nRetransmits: {}\nErrors: {}\nStreams: {}",
+                connections,
+                metrics.latency,
+                format_rate(metrics.throughput, 2),
+                metrics.rtt,
+                metrics.packet_loss * 100.0,
+                metrics.retransmits,
+                metrics.errors,
+                metrics.streams
+            )
+        } else {
+            format!(
+                "Waiting for metrics...\n\nMake sure quic-test is running\nand sending data to:\n{}://{}/api/metrics",
+                self.api_config.scheme(),
+                self.api_addr
+            )
+        };
+        drop(metrics_opt);
+        let current_metrics = CurrentMetricsPanel {
+            text: metrics_text,
+            stale,
+        };
+
+        let theme = self.themes.current();
+
+        // Columns of the `[layout]` grid, without the widgets turned off under `[widgets]`;
+        // a small terminal gets fewer rows per widget, or one line each
+        let enabled = |kind| self.widget_config.is_enabled(kind);
+        let columns = tree.column_areas(chunks[1], &enabled, 0, 0);
+        let sized = |kind: &WidgetKind| -> &dyn MinSize {
+            match kind {
+                WidgetKind::CurrentMetrics => &current_metrics,
+                WidgetKind::Latency => &self.latency_graph,
+                WidgetKind::Throughput => &self.throughput_graph,
+                WidgetKind::Streams => &self.streams_widget,
+                WidgetKind::Heatmap => &self.performance_heatmap,
+                WidgetKind::Cwnd => &self.cwnd_widget,
+                WidgetKind::Anomaly => &self.anomaly_widget,
+                _ => &AnySize,
+            }
+        };
+        let widgets: Vec<(Rect, Vec<&dyn MinSize>)> = columns
+            .iter()
+            .map(|(area, kinds)| (*area, kinds.iter().map(sized).collect()))
+            .collect();
+        let layout = responsive_layout(chunks[1], &widgets);
+        if layout.areas.is_empty() {
+            render_no_widgets(f, chunks[1], theme);
+        }
+        let kinds = columns.iter().flat_map(|(_, kinds)| kinds);
+        for (&kind, area) in kinds.zip(layout.areas) {
+            let Some(area) = area else {
+                continue;
+            };
+            match kind {
+                WidgetKind::CurrentMetrics => current_metrics.render(f, area, theme),
+                WidgetKind::Streams => self.streams_widget.render(f, area, theme),
+                WidgetKind::Cwnd => self.cwnd_widget.render(f, area, theme),
+                WidgetKind::Bbrv3 => self.bbrv3_graphs.render_bandwidth(f, area, theme),
+                WidgetKind::LatencyHistogram => HistogramWidget::new("Latency Distribution", HISTOGRAM_BINS)
+                    .with_unit("ms")
+                    .with_color(theme.primary)
+                    .render(f, area, &self.latency_graph.values(), theme),
+                _ => match Panel::for_widget(kind) {
+                    Some(panel) => self.render_panel(f, panel, area),
+                    None => render_unavailable(f, area, kind, theme),
+                },
+            }
+        }
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_analytics_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Analytics
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Real QUIC Bottom - Analytics");
+        self.render_panels(f, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_network_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Network info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Real QUIC Bottom - Network Simulation");
+
+        // Get current metrics for real-time data
+        let metrics_opt = self.api.current_metrics.lock_or_recover();
+        let simulation = self.api.network.lock_or_recover().status_text();
+        let metrics_text = if let Some(metrics) = metrics_opt.as_ref() {
+            format!(
+                "{}\n\n--- Real Metrics ---\nActual Latency: {:.2} ms\nActual Throughput: {}\nActual RTT: {:.2} ms\nPacket Loss: {:.2}%\nRetransmits: {}\nConnections: {}",
+                simulation,
+                metrics.latency,
+                format_rate(metrics.throughput, 2),
+                metrics.rtt,
+                metrics.packet_loss * 100.0,
+                metrics.retransmits,
+                metrics.connections
+            )
+        } else {
+            format!("{}\n\n--- Real Metrics ---\nWaiting for data...", simulation)
+        };
+        drop(metrics_opt);
+
+        let body_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[1]);
+
+        let left_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Network status
+                Constraint::Length(8), // Efficiency
+            ])
+            .split(body_chunks[0]);
+
+        let theme = self.themes.current();
+        let network_paragraph = Paragraph::new(metrics_text)
+            .style(Style::default().fg(theme.info))
+            .block(Block::default().borders(Borders::ALL).title("Network Status"));
+        f.render_widget(network_paragraph, left_chunks[0]);
+        self.efficiency_widget.render(f, left_chunks[1], theme);
+
+        let tracker = self.api.connection_tracker.lock_or_recover();
+        match tracker.pinned() {
+            Some(trace) => {
+                let right_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Percentage(40), // Connection table
+                        Constraint::Percentage(60), // Trace of the pinned connection
+                    ])
+                    .split(body_chunks[1]);
+                self.connection_table.render(f, right_chunks[0], &tracker, theme);
+                TracePanel::render(f, right_chunks[1], trace, theme);
+            }
+            None => self.connection_table.render(f, body_chunks[1], &tracker, theme),
+        }
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_security_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Security info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Real QUIC Bottom - Security Checks");
+        let body_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(65), // Check results
+                Constraint::Percentage(35), // Connection security
+            ])
+            .split(chunks[1]);
+        let theme = self.themes.current();
+
+        let report = self.api.security.report();
+        let mut lines = Vec::new();
+        match &report.target {
+            Some(target) => {
+                let mut spans = vec![Span::raw(format!("Target: {}", target))];
+                if report.running {
+                    spans.push(Span::styled("  running...", Style::default().fg(theme.info)));
+                }
+                lines.push(Line::from(spans));
+            }
+            None => lines.push(Line::styled(
+                "No target: set target = \"host:port\" under [security] in the config",
+                Style::default().fg(theme.muted),
+            )),
+        }
+        lines.push(Line::raw(""));
+        for check in CheckKind::ALL {
+            let label = format!("{:<28}", check.label());
+            match report.result(check) {
+                Some(result) => {
+                    let color = match result.status {
+                        CheckStatus::Pass => theme.success,
+                        CheckStatus::Warn => theme.warning,
+                        CheckStatus::Fail | CheckStatus::Error => theme.error,
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("{:<6}", result.status.label()),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(label),
+                        Span::styled(result.summary.clone(), Style::default().fg(color)),
+                        Span::styled(
+                            format!(
+                                "  ({} UTC, {} ms)",
+                                result.finished_at.format("%H:%M:%S"),
+                                result.duration_ms
+                            ),
+                            Style::default().fg(theme.muted),
+                        ),
+                    ]));
+                    for detail in &result.details {
+                        lines.push(Line::styled(format!("{:6}- {}", "", detail), Style::default().fg(theme.muted)));
+                    }
+                }
+                None => {
+                    let state = if report.enabled.contains(&check) { "not run yet" } else { "disabled" };
+                    lines.push(Line::styled(format!("{:<6}{}{}", "-", label, state), Style::default().fg(theme.muted)));
+                }
+            }
+        }
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            format!("'{}' runs the checks", self.keymap.key(Action::ToggleSecurity)),
+            Style::default().fg(theme.muted),
+        ));
+        let checks_paragraph = Paragraph::new(lines)
+            .style(Style::default().fg(theme.text))
+            .block(Block::default().borders(Borders::ALL).title("Security Checks"));
+        f.render_widget(checks_paragraph, body_chunks[0]);
+
+        let metrics_opt = self.api.current_metrics.lock_or_recover();
+        let connection_text = if let Some(metrics) = metrics_opt.as_ref() {
+            let error_rate = if metrics.connections > 0 {
+                (metrics.errors as f64 / metrics.connections as f64) * 100.0
+            } else {
+                0.0
+            };
+            format!(
+                "Errors: {}\nError Rate: {:.2}%\nPacket Loss: {:.2}%\nRetransmits: {}\nHandshake Time: {:.2} ms\nJitter: {:.2} ms",
+                metrics.errors,
+                error_rate,
+                metrics.packet_loss * 100.0,
+                metrics.retransmits,
+                metrics.handshake_time,
+                metrics.jitter
+            )
+        } else {
+            "Waiting for data...".to_string()
+        };
+        drop(metrics_opt);
+
+        let connection_paragraph = Paragraph::new(connection_text)
+            .style(Style::default().fg(theme.warning))
+            .block(Block::default().borders(Borders::ALL).title("Connection Security"));
+        f.render_widget(connection_paragraph, body_chunks[1]);
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_cloud_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Cloud info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Real QUIC Bottom - Cloud Deployment");
+
+        // Cloud deployment status
+        let cloud_text = format!(
+            "Cloud Deployment: {}\nProvider: {}\nInstances: {}\nStatus: {}",
+            if self.cloud_deployment_active { "ACTIVE" } else { "INACTIVE" },
+            self.cloud_provider,
+            self.cloud_instances,
+            self.cloud_status
+        );
+
+        let cloud_paragraph = Paragraph::new(cloud_text)
+            .style(Style::default().fg(self.themes.current().success))
+            .block(Block::default().borders(Borders::ALL).title("Cloud Status"));
+        f.render_widget(cloud_paragraph, chunks[1]);
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_bbrv3_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "BBRv3 Congestion Control");
+        let theme = self.themes.current();
+
+        // Get current metrics
+        let metrics_opt = self.api.current_metrics.lock_or_recover();
+
+        if let Some(metrics) = metrics_opt.as_ref() {
+            if metrics.bbrv3.phase.is_some() {
+                // 2-column grid over the congestion window graph
+                let body_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Percentage(75), // Panels
+                        Constraint::Percentage(25), // Congestion window
+                    ])
+                    .split(chunks[1]);
+                self.cwnd_widget.render(f, body_chunks[1], theme);
+
+                let main_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Percentage(50), // Left column
+                        Constraint::Percentage(50), // Right column
+                    ])
+                    .split(body_chunks[0]);
+
+                // Left column - 3 rows
+                let left_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Percentage(33), // Phase Status
+                        Constraint::Percentage(33), // Bandwidth Estimates
+                        Constraint::Percentage(34), // Loss Metrics
+                    ])
+                    .split(main_chunks[0]);
+
+                // Right column - 3 rows
+                let right_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Percentage(33), // Bufferbloat & Stability
+                        Constraint::Percentage(33), // Pacing/CWND Gains
+                        Constraint::Percentage(34), // Recovery Metrics
+                    ])
+                    .split(main_chunks[1]);
+
+                // 1. Phase Status Widget
+                if let Some(phase) = &metrics.bbrv3.phase {
+                    let phase_color = match phase.as_str() {
+                        "Startup" => theme.error,
+                        "Drain" => theme.warning,
+                        "ProbeBW" => theme.success,
+                        "ProbeRTT" => theme.info,
+                        _ => theme.text,
+                    };
+
+                    let phase_text = format!(
+                        "Current Phase: {}\n\nDescription:\n- Manages network bottleneck\n- Optimizes bandwidth usage\n- Adapts to network conditions",
+                        phase
+                    );
+
+                    let phase_widget = Paragraph::new(phase_text)
+                        .style(Style::default().fg(phase_color).add_modifier(Modifier::BOLD))
+                        .block(Block::default().borders(Borders::ALL).title("Phase Status"));
+                    f.render_widget(phase_widget, left_chunks[0]);
+                }
+
+                // Charts once there is some history, text panels until then
+                let graphs = self.bbrv3_graphs.has_history();
+
+                // 2. Bandwidth Estimates Widget
+                if graphs {
+                    self.bbrv3_graphs.render_bandwidth(f, left_chunks[1], theme);
+                } else {
+                    let bw_text = if let (Some(bw_fast), Some(bw_slow)) =
+                        (&metrics.bbrv3.bw_fast, &metrics.bbrv3.bw_slow) {
+                        format!(
+                            "Fast Bandwidth: {}\nSlow Bandwidth: {}\n\nRatio: {:.2}x",
+                            format_rate(*bw_fast, 2),
+                            format_rate(*bw_slow, 2),
+                            bw_fast / bw_slow.max(10_000.0)
+                        )
+                    } else {
+                        "N/A".to_string()
+                    };
+
+                    let bw_widget = Paragraph::new(bw_text)
+                        .style(Style::default().fg(theme.primary))
+                        .block(Block::default().borders(Borders::ALL).title("Bandwidth Estimates"));
+                    f.render_widget(bw_widget, left_chunks[1]);
+                }
+
+                // 3. Loss Metrics Widget
+                let loss_text = if let Some(loss_rate) = metrics.bbrv3.loss_rate_ema {
+                    format!(
+                        "Loss Rate (EMA): {:.2}%\n\nStatus: {}\nThreshold: 2.0%",
+                        loss_rate * 100.0,
+                        if loss_rate < 0.02 { "HEALTHY" } else { "ELEVATED" }
+                    )
+                } else {
+                    "N/A".to_string()
+                };
+
+                let loss_widget = Paragraph::new(loss_text)
+                    .style(Style::default().fg(theme.warning))
+                    .block(Block::default().borders(Borders::ALL).title("Loss Metrics"));
+                f.render_widget(loss_widget, left_chunks[2]);
+
+                // 4. Bufferbloat & Stability Widget
+                if graphs {
+                    self.bbrv3_graphs.render_bufferbloat(f, right_chunks[0], metrics.bbrv3.stability_index, theme);
+                } else {
+                    let bufferbloat_text = if let Some(factor) = metrics.bbrv3.bufferbloat_factor {
+                        let status = if factor < 0.1 { "EXCELLENT" }
+                                    else if factor < 0.3 { "GOOD" }
+                                    else { "HIGH" };
+                        format!(
+                            "Bufferbloat: {:.3}\n\nStatus: {}\nTarget: < 0.1",
+                            factor,
+                            status
+                        )
+                    } else {
+                        "N/A".to_string()
+                    };
+
+                    let stability_text = format!(
+                        "{}\n\nStability Index: {:.2}",
+                        bufferbloat_text,
+                        metrics.bbrv3.stability_index.unwrap_or(0.0)
+                    );
+
+                    let bufferbloat_widget = Paragraph::new(stability_text)
+                        .style(Style::default().fg(theme.accent))
+                        .block(Block::default().borders(Borders::ALL).title("Bufferbloat & Stability"));
+                    f.render_widget(bufferbloat_widget, right_chunks[0]);
+                }
+
+                // 5. Pacing/CWND Gains Widget
+                if graphs {
+                    self.bbrv3_graphs.render_gains(f, right_chunks[1], theme);
+                } else {
+                    let gains_text = format!(
+                        "Pacing Gain: {:.2}x\nCWND Gain: {:.2}x\n\nTarget Inflight: {} KB",
+                        metrics.bbrv3.pacing_gain.unwrap_or(1.0),
+                        metrics.bbrv3.cwnd_gain.unwrap_or(2.0),
+                        (metrics.bbrv3.inflight_target.unwrap_or(0.0) / 1024.0) as i64
+                    );
+
+                    let gains_widget = Paragraph::new(gains_text)
+                        .style(Style::default().fg(theme.info))
+                        .block(Block::default().borders(Borders::ALL).title("Pacing/CWND Gains"));
+                    f.render_widget(gains_widget, right_chunks[1]);
+                }
+
+                // 6. Recovery Metrics Widget
+                let recovery_text = format!(
+                    "Recovery Time: {:.0} ms\nLoss Efficiency: {:.2}%\n\nHeadroom Usage: {:.1}%",
+                    metrics.bbrv3.recovery_time_ms.unwrap_or(0.0),
+                    metrics.bbrv3.loss_recovery_efficiency.unwrap_or(0.0) * 100.0,
+                    metrics.bbrv3.headroom_usage.unwrap_or(0.0) * 100.0
+                );
+
+                let recovery_widget = Paragraph::new(recovery_text)
+                    .style(Style::default().fg(theme.primary))
+                    .block(Block::default().borders(Borders::ALL).title("Recovery Metrics"));
+                f.render_widget(recovery_widget, right_chunks[2]);
+            } else {
+                // BBRv3 metrics not available
+                let no_data_text = "BBRv3 metrics not available.\n\nMake sure:\n1. quic-test is running with --congestion-control=bbrv3\n2. Connection is established\n3. Data is being transmitted";
+                let no_data_widget = Paragraph::new(no_data_text)
+                    .style(Style::default().fg(theme.error))
+                    .block(Block::default().borders(Borders::ALL).title("BBRv3 Status"));
+                f.render_widget(no_data_widget, chunks[1]);
+            }
+        } else {
+            // No metrics at all
+            let no_metrics_text = "No metrics received yet.\n\nWaiting for quic-test connection...";
+            let no_metrics_widget = Paragraph::new(no_metrics_text)
+                .style(Style::default().fg(theme.warning))
+                .block(Block::default().borders(Borders::ALL).title("Connection Status"));
+            f.render_widget(no_metrics_widget, chunks[1]);
+        }
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_all_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Real QUIC Bottom - All Views");
+        self.render_panels(f, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect, title: &str) {
+        let theme = self.themes.current();
+        let header_text = format!("Real QUIC Bottom - {}", title);
+        let mut spans = vec![
+            Span::raw(header_text),
+            Span::raw("  "),
+            Span::styled(format!("[{}]", self.views.active().name), Style::default().fg(theme.accent)),
+            Span::raw("  "),
+        ];
+        let paused = match &self.replay {
+            Some(replay) => {
+                spans.push(Span::styled("Session replay", Style::default().fg(theme.info)));
+                replay.replayer.is_paused()
+            }
+            None => {
+                let age = self.api.last_update_age();
+                spans.push(last_update_span(age, self.staleness.freshness(age), theme));
+                self.paused
+            }
+        };
+        if let Some(sla) = sla_span(&self.api.sla(), theme) {
+            spans.push(Span::raw("  "));
+            spans.push(sla);
+        }
+        if paused {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                " PAUSED ",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        if let Some(recorder) = &self.api.recorder {
+            let status = recorder.status();
+            let (label, color) = match &status.error {
+                Some(error) => (format!(" REC STOPPED: {} ", error), theme.warning),
+                None => (format!(" REC {} ", status.written), theme.error),
+            };
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                label,
+                Style::default().fg(color).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        render_header(f, area, Line::from(spans), &self.api.health_score(), theme);
+    }
+
+    fn render_footer(&self, f: &mut Frame, area: Rect) {
+        let theme = self.themes.current();
+        if let Some((message, shown_at)) = &self.toast {
+            if shown_at.elapsed() < TOAST_DURATION {
+                let toast = Paragraph::new(message.as_str())
+                    .style(Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED))
+                    .block(Block::default().borders(Borders::ALL));
+                f.render_widget(toast, area);
+                return;
+            }
+        }
+
+        let mut footer = match &self.replay {
+            Some(replay) => Line::from(replay_progress(&replay.replayer, &self.keymap)),
+            None => {
+                let fill = {
+                    let history = self.api.metrics_history.lock_or_recover();
+                    (history.len(), history.capacity())
+                };
+                self.status_bar.line(fill, &self.keymap.key(Action::Help), theme)
+            }
+        };
+        if let Some(queue) = &self.api.exporter {
+            footer.spans.insert(0, Span::raw(format!("{} | ", queue.status_line())));
+        }
+        let footer = Paragraph::new(footer)
+            .style(Style::default().fg(theme.accent))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, area);
+    }
+}
+
+/// Footer line for a session replay: position, duration and a progress bar
+fn replay_progress(replayer: &Replayer<RealQUICMetrics>, keymap: &KeyMap) -> String {
+    let progress = replayer.progress().clamp(0.0, 1.0);
+    let filled = (progress * PROGRESS_WIDTH as f64).round() as usize;
+    format!(
+        "Replay {} / {} [{}{}] {:.0}% at {}x{} | '{}' to pause, '{}' to quit, '{}' for help",
+        format_span(replayer.position()),
+        format_span(replayer.duration()),
+        "█".repeat(filled),
+        "░".repeat(PROGRESS_WIDTH - filled),
+        progress * 100.0,
+        replayer.speed(),
+        if replayer.is_finished() { ", finished" } else { "" },
+        keymap.key(Action::Pause),
+        keymap.key(Action::Quit),
+        keymap.key(Action::Help)
+    )
+}
+
+/// The dashboard's "Current Metrics" box, one value per line
+struct CurrentMetricsPanel {
+    text: String,
+    stale: bool,
+}
+
+impl CurrentMetricsPanel {
+    /// In a box, or without one when there is only room for the lines
+    fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let paragraph = Paragraph::new(self.text.as_str())
+            .style(Style::default().fg(if self.stale { theme.muted } else { theme.info }));
+        let paragraph = match Presentation::fitting(self, area) {
+            Presentation::Minimal => paragraph,
+            _ => paragraph.block(Block::default().borders(Borders::ALL).title("Current Metrics")),
+        };
+        f.render_widget(paragraph, area);
+    }
+}
+
+impl MinSize for CurrentMetricsPanel {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        let lines = self.text.lines().count() as u16;
+        match presentation {
+            Presentation::Full | Presentation::Compact => Size::new(20, lines + 2),
+            Presentation::Minimal => Size::new(0, lines),
+        }
+    }
+}
+
+/// State shared between the TUI and the HTTP API
+#[derive(Clone)]
+struct ApiState {
+    current_metrics: Arc<Mutex<Option<RealQUICMetrics>>>,
+    /// When the last sample was posted, by the local clock
+    last_update: Arc<Mutex<Option<Instant>>>,
+    metrics_history: Arc<Mutex<MetricsHistory<RealQUICMetrics>>>,
+    connection_tracker: Arc<Mutex<ConnectionTracker>>,
+    reset_tx: mpsc::UnboundedSender<ResetRequest>,
+    /// Bumped by an API reset with `"segments": "new"`
+    segment: Arc<AtomicU64>,
+    /// Session file every ingested sample is appended to
+    recorder: Option<Arc<SessionRecorder>>,
+    /// InfluxDB exporter queue every ingested sample is pushed to
+    exporter: Option<Arc<InfluxQueue>>,
+    /// Derivation, SLA and health settings, shared so a config reload reaches the API
+    derived_config: Arc<Mutex<DerivedMetricsConfig>>,
+    sla_config: Arc<Mutex<SlaConfig>>,
+    health_config: Arc<Mutex<HealthConfig>>,
+    /// Anomalies in the health window by severity, `None` without the TUI's detector
+    anomaly_counts: Arc<Mutex<Option<BTreeMap<AnomalySeverity, usize>>>>,
+    /// Network simulation the TUI applies, switched by keys and `PUT /api/simulation`
+    network: Arc<Mutex<NetworkSimulation>>,
+    /// Security checks of the endpoint, run by key and `POST /api/security`
+    security: Arc<SecurityScanner>,
+    /// Labelled senders, kept as the bridge keeps them
+    sources: Arc<Mutex<BTreeMap<String, LiveSource>>>,
+}
+
+/// Newest sample of one labelled sender and how many it sent
+struct LiveSource {
+    current: RealQUICMetrics,
+    samples: u64,
+    last_update: Instant,
+}
+
+impl ApiState {
+    fn new(tracker: ConnectionTracker, reset_tx: mpsc::UnboundedSender<ResetRequest>) -> Self {
+        Self {
+            current_metrics: Arc::new(Mutex::new(None)),
+            last_update: Arc::new(Mutex::new(None)),
+            metrics_history: Arc::new(Mutex::new(MetricsHistory::new(HISTORY_CAPACITY))),
+            connection_tracker: Arc::new(Mutex::new(tracker)),
+            reset_tx,
+            segment: Arc::new(AtomicU64::new(1)),
+            recorder: None,
+            exporter: None,
+            derived_config: Arc::new(Mutex::new(DerivedMetricsConfig::default())),
+            sla_config: Arc::new(Mutex::new(SlaConfig::default())),
+            health_config: Arc::new(Mutex::new(HealthConfig::default())),
+            anomaly_counts: Arc::new(Mutex::new(None)),
+            network: Arc::new(Mutex::new(NetworkSimulation::default())),
+            security: Arc::new(SecurityScanner::new(SecurityConfig::default())),
+            sources: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Clear `scope` for `POST /api/reset` and tell the TUI, if any, to clear the rest
+    fn reset(&self, scope: ResetScope, caller: &str) -> ResetOutcome {
+        // History lives in the server, so clear it here even when no TUI is attached
+        if scope.stats {
+            self.sources.lock_or_recover().clear();
+        }
+        let history_dropped = if scope.history {
+            let mut history = self.metrics_history.lock_or_recover();
+            let dropped = history.len();
+            history.clear();
+            dropped
+        } else {
+            0
+        };
+        let segment = match scope.segments {
+            SegmentReset::New => self.segment.fetch_add(1, Ordering::Relaxed) + 1,
+            SegmentReset::Keep => self.segment.load(Ordering::Relaxed),
+        };
+        let outcome = ResetOutcome {
+            scope,
+            history_dropped,
+            segment,
+        };
+        let _ = self.reset_tx.send((outcome, caller.to_string()));
+        outcome
+    }
+
+    /// Readiness for `GET /health`; initialized while the dashboard consumes resets
+    fn health_report(&self, started: Instant, max_sample_age: Option<Duration>) -> HealthReport {
+        let last_sample_age = self.last_update.lock_or_recover().map(|at| at.elapsed());
+        let samples_total = self.metrics_history.lock_or_recover().total_pushed();
+        HealthReport::build(!self.reset_tx.is_closed(), last_sample_age, samples_total, started, max_sample_age)
+    }
+
+    /// Goodput, efficiency and error rate of `metrics`
+    fn derive(&self, metrics: &RealQUICMetrics) -> DerivedMetrics {
+        DerivedMetrics::compute(&metrics.to_quic_metrics(), &self.derived_config.lock_or_recover())
+    }
+
+    /// Verdict of the stored history against the SLA, with the latency the graphs show
+    fn sla(&self) -> SlaVerdict {
+        let sla_config = self.sla_config.lock_or_recover().clone();
+        let history = self.metrics_history.lock_or_recover();
+        let from = history
+            .latest()
+            .and_then(|newest| sla_config.window_start_ms(newest.timestamp_ms()));
+        let samples: Vec<QUICMetrics> = history
+            .range(from, None)
+            .map(|metrics| QUICMetrics {
+                latency: metrics.latency,
+                ..metrics.to_quic_metrics()
+            })
+            .collect();
+        sla_config.evaluate(&samples)
+    }
+
+    /// Health score of the current sample, with the latency the graphs show
+    fn health_score(&self) -> HealthScore {
+        let current = self.current_metrics.lock_or_recover().clone();
+        let mut inputs = match &current {
+            Some(metrics) => HealthInputs::from_sample(
+                &QUICMetrics {
+                    latency: metrics.latency,
+                    ..metrics.to_quic_metrics()
+                },
+                &self.derive(metrics),
+                self.last_update_age(),
+            ),
+            None => HealthInputs::default(),
+        };
+        inputs.anomalies = self.anomaly_counts.lock_or_recover().clone();
+        self.health_config.lock_or_recover().score(&inputs)
+    }
+
+    /// Time since the last posted sample, `None` before the first one
+    fn last_update_age(&self) -> Option<Duration> {
+        self.last_update.lock_or_recover().map(|at| at.elapsed())
+    }
+
+    /// Store one posted sample as the current one and in the history
+    ///
+    /// Fails, storing nothing, for a sample from a new `source` once
+    /// `MAX_SOURCES` are tracked, as the bridge does.
+    fn ingest(&self, mut metrics: RealQUICMetrics) -> Result<()> {
+        metrics.normalize_units();
+        self.track_source(&metrics)?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record(chrono::Utc::now(), &metrics);
+        }
+        let quic_metrics = metrics.to_quic_metrics();
+        if let Some(exporter) = &self.exporter {
+            exporter.push(&quic_metrics);
+        }
+        otel::record(&quic_metrics);
+        otel::record_gauges(&metrics.bbrv3.gauges());
+        *self.current_metrics.lock_or_recover() = Some(metrics.clone());
+        *self.last_update.lock_or_recover() = Some(Instant::now());
+        record_samples(1);
+
+        // Track per-connection series when the sample names its connection
+        if let Some(connection_id) = &metrics.connection_id {
+            self.connection_tracker
+                .lock_or_recover()
+                .record(connection_id, &quic_metrics);
+        }
+
+        self.metrics_history.lock_or_recover().push(metrics);
+        Ok(())
+    }
+
+    /// Make `metrics` the newest sample of its source, if it names one
+    fn track_source(&self, metrics: &RealQUICMetrics) -> Result<()> {
+        let Some(source) = &metrics.source else {
+            return Ok(());
+        };
+        let mut sources = self.sources.lock_or_recover();
+        ensure_source_room(source, sources.contains_key(source), sources.len())?;
+        let tracked = sources.entry(source.clone()).or_insert_with(|| LiveSource {
+            current: metrics.clone(),
+            samples: 0,
+            last_update: Instant::now(),
+        });
+        tracked.current = metrics.clone();
+        tracked.samples += 1;
+        tracked.last_update = Instant::now();
+        Ok(())
+    }
+
+    /// Labelled senders for `GET /api/sources`, listed as the bridge lists them
+    fn source_summaries(&self) -> Vec<SourceSummary> {
+        self.sources
+            .lock_or_recover()
+            .iter()
+            .map(|(name, source)| SourceSummary {
+                source: name.clone(),
+                samples: source.samples,
+                last_sample: chrono::DateTime::from_timestamp_millis(source.current.timestamp_ms()).unwrap_or_default(),
+                age_ms: source.last_update.elapsed().as_millis() as u64,
+            })
+            .collect()
+    }
+
+    /// Store a sample restored from a recording, without recording or exporting it again
+    fn restore(&self, metrics: RealQUICMetrics) {
+        // Sources past the limit were refused when recorded, so none is refused here
+        let _ = self.track_source(&metrics);
+        if let Some(connection_id) = &metrics.connection_id {
+            self.connection_tracker
+                .lock_or_recover()
+                .record(connection_id, &metrics.to_quic_metrics());
+        }
+        *self.current_metrics.lock_or_recover() = Some(metrics.clone());
+        self.metrics_history.lock_or_recover().push(metrics);
+    }
+
+    /// Validate, label, order and store a batch of samples
+    fn ingest_batch(
+        &self,
+        batch: Vec<RealQUICMetrics>,
+        query: BatchQuery,
+        label: impl Fn(&mut RealQUICMetrics) -> Result<()>,
+    ) -> Result<BatchReport> {
+        let mut report = BatchReport::default();
+        let mut entries = Vec::with_capacity(batch.len());
+        for (index, mut metrics) in batch.into_iter().enumerate() {
+            match metrics.validate().and_then(|()| label(&mut metrics)) {
+                Ok(()) => entries.push((index, metrics)),
+                Err(e) => report.reject(index, e.to_string()),
+            }
+        }
+
+        let newest = self
+            .metrics_history
+            .lock_or_recover()
+            .latest()
+            .map(|metrics| metrics.timestamp_ms());
+        for metrics in order_batch(entries, query.order, newest, &mut report) {
+            self.ingest(metrics)?;
+        }
+        Ok(report)
+    }
+}
+
+// HTTP API server for receiving metrics from Go application
+fn bind_http_server(
+    addr: SocketAddr,
+    state: ApiState,
+    config: &ApiConfig,
+) -> Result<(SocketAddr, ServerFuture)> {
+    let routes = api_routes(state, config)?;
+    let identity = TlsIdentity::from_config(config)?;
+    tls::bind(routes, addr, identity.as_ref())
+}
+
+/// Run the HTTP API in the background
+///
+/// Binding errors are returned right away; the receiver gets the bound
+/// address once the server task is running, so the caller can wait for it
+/// instead of sleeping before it draws the TUI.
+fn start_http_server(addr: SocketAddr, state: ApiState, config: &ApiConfig) -> Result<oneshot::Receiver<SocketAddr>> {
+    let (bound, server) = bind_http_server(addr, state, config)?;
+    let (ready_tx, ready_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        // The listener is open, so connections made from now on queue until the server accepts them
+        let _ = ready_tx.send(bound);
+        server.await;
+    });
+    Ok(ready_rx)
+}
+
+/// Routes of the live dashboard's API over `state`
+fn api_routes(state: ApiState, config: &ApiConfig) -> Result<BoxedFilter<(Response,)>> {
+    let limiter = config.rate_limiter();
+    let source_from_addr = config.source_from_addr;
+
+    let state_post = state.clone();
+    let metrics_filter = warp::path("api")
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(rate_limit(limiter.clone()))
+        .and(warp::query::<SourceQuery>())
+        .and(warp::addr::remote())
+        .and(body_limit(config.max_body_bytes))
+        .and(warp::body::json())
+        .map(move |query: SourceQuery, addr: Option<SocketAddr>, mut metrics: RealQUICMetrics| {
+            catch_panic(|| {
+                let result = metrics
+                    .validate()
+                    .and_then(|()| resolve_source(&mut metrics.source, &query, addr, source_from_addr))
+                    .and_then(|()| state_post.ingest(metrics));
+                match result {
+                    Ok(()) => {
+                        warp::reply::with_status(warp::reply::json(&serde_json::json!({"status": "ok"})), StatusCode::OK)
+                    }
+                    Err(e) => {
+                        record_ingest_error();
+                        let body = serde_json::json!({"status": "error", "message": e.to_string()});
+                        warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST)
+                    }
+                }
+            })
+        });
+
+    let state_batch = state.clone();
+    let max_batch_size = config.max_batch_size;
+    let batch_filter = warp::path!("api" / "metrics" / "batch")
+        .and(warp::post())
+        .and(rate_limit(limiter))
+        .and(warp::query::<BatchQuery>())
+        .and(warp::query::<SourceQuery>())
+        .and(warp::addr::remote())
+        .and(body_limit(config.max_batch_body_bytes))
+        .and(warp::body::json())
+        .map(move |query: BatchQuery, source: SourceQuery, addr: Option<SocketAddr>, batch: Vec<RealQUICMetrics>| {
+            catch_panic(|| {
+                if let Some(message) = check_batch_size(batch.len(), max_batch_size) {
+                    let body = serde_json::json!({"status": "error", "message": message});
+                    return warp::reply::with_status(warp::reply::json(&body), StatusCode::PAYLOAD_TOO_LARGE);
+                }
+                let label = |metrics: &mut RealQUICMetrics| resolve_source(&mut metrics.source, &source, addr, source_from_addr);
+                match state_batch.ingest_batch(batch, query, label) {
+                    Ok(report) => warp::reply::with_status(warp::reply::json(&report), StatusCode::OK),
+                    Err(e) => {
+                        let body = serde_json::json!({
+                            "status": "error",
+                            "message": format!("Failed to update metrics: {}", e),
+                        });
+                        warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR)
+                    }
+                }
+            })
+        });
+
+    let state_ready = state.clone();
+    let started = Instant::now();
+    let max_sample_age = config.health_max_sample_age_ms.map(Duration::from_millis);
+    let health_filter = warp::path("health")
+        .and(warp::path::end())
+        .and(warp::get())
+        .map(move || {
+            catch_panic(|| {
+                let report = state_ready.health_report(started, max_sample_age);
+                let status = if report.is_ready() {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                };
+                warp::reply::with_status(warp::reply::json(&report), status)
+            })
+        });
+
+    let state_sla = state.clone();
+    let sla_filter = warp::path!("api" / "sla")
+        .and(warp::get())
+        .map(move || catch_panic(|| warp::reply::json(&state_sla.sla())));
+
+    let state_health = state.clone();
+    let health_score_filter = warp::path!("api" / "health-score")
+        .and(warp::get())
+        .map(move || catch_panic(|| warp::reply::json(&state_health.health_score())));
+
+    let state_simulation = state.clone();
+    let simulation_filter = warp::path!("api" / "simulation")
+        .and(warp::get())
+        .map(move || catch_panic(|| warp::reply::json(&state_simulation.network.lock_or_recover().state())));
+
+    let state_simulation = state.clone();
+    let simulation_put_filter = warp::path!("api" / "simulation")
+        .and(warp::put())
+        .and(body_limit(config.max_body_bytes))
+        .and(warp::body::json())
+        .map(move |request: SimulationRequest| {
+            catch_panic(|| {
+                let mut network = state_simulation.network.lock_or_recover();
+                match network.update(&request) {
+                    Ok(()) => warp::reply::with_status(warp::reply::json(&network.state()), StatusCode::OK),
+                    Err(e) => {
+                        let body = serde_json::json!({"status": "error", "message": e.to_string()});
+                        warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST)
+                    }
+                }
+            })
+        });
+
+    let state_security = state.clone();
+    let security_filter = warp::path!("api" / "security")
+        .and(warp::get())
+        .map(move || catch_panic(|| warp::reply::json(&state_security.security.report())));
+
+    // Starts the checks and answers at once; GET /api/security has the results
+    let state_security = state.clone();
+    let security_run_filter = warp::path!("api" / "security")
+        .and(warp::post())
+        .map(move || {
+            catch_panic(|| match state_security.security.run() {
+                Ok(request) => {
+                    // Every check held back for having run too recently
+                    let status = if request.started.is_empty() {
+                        StatusCode::TOO_MANY_REQUESTS
+                    } else {
+                        StatusCode::ACCEPTED
+                    };
+                    warp::reply::with_status(warp::reply::json(&request), status)
+                }
+                Err(e) => {
+                    let body = serde_json::json!({"status": "error", "message": e.to_string()});
+                    warp::reply::with_status(warp::reply::json(&body), StatusCode::CONFLICT)
+                }
+            })
+        });
+
+    let ingest_stats_filter = warp::path!("api" / "ingest" / "stats")
+        .and(warp::get())
+        .map(|| catch_panic(|| warp::reply::json(&serde_json::json!({"http": http_limit_stats()}))));
+
+    let state_get = state.clone();
+    let current_filter = warp::path("api")
+        .and(warp::path("current"))
+        .and(warp::get())
+        .and(warp::query::<SourceQuery>())
+        .map(move |query: SourceQuery| {
+            catch_panic(|| {
+                // One sender's latest sample, as the bridge answers `?source=`
+                if let Some(source) = &query.source {
+                    let sources = state_get.sources.lock_or_recover();
+                    return match sources.get(source) {
+                        Some(tracked) => {
+                            let response = CurrentResponse {
+                                metrics: &tracked.current,
+                                derived: state_get.derive(&tracked.current),
+                                age_ms: tracked.last_update.elapsed().as_millis() as u64,
+                            };
+                            warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)
+                        }
+                        None => {
+                            let body = serde_json::json!({
+                                "status": "error",
+                                "message": format!("unknown source '{}'", source),
+                            });
+                            warp::reply::with_status(warp::reply::json(&body), StatusCode::NOT_FOUND)
+                        }
+                    };
+                }
+
+                let current = state_get.current_metrics.lock_or_recover();
+                let age_ms = state_get
+                    .last_update
+                    .lock_or_recover()
+                    .map_or(0, |at| at.elapsed().as_millis() as u64);
+                let response = current.as_ref().map(|metrics| CurrentResponse {
+                    metrics,
+                    derived: state_get.derive(metrics),
+                    age_ms,
+                });
+                warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)
+            })
+        });
+
+    let state_sources = state.clone();
+    let sources_filter = warp::path!("api" / "sources")
+        .and(warp::get())
+        .map(move || catch_panic(|| warp::reply::json(&state_sources.source_summaries())));
+
+    let reset_filter = reset_routes(config, move |scope, caller| state.reset(scope, caller));
+
+    let routes = require_token(config)
+        .and(
+            metrics_filter
+                .or(batch_filter)
+                .or(health_filter)
+                .or(current_filter)
+                .or(sources_filter)
+                .or(sla_filter)
+                .or(health_score_filter)
+                .or(simulation_filter)
+                .or(simulation_put_filter)
+                .or(security_filter)
+                .or(security_run_filter)
+                .or(ingest_stats_filter)
+                .or(reset_filter),
+        )
+        .recover(recover_unauthorized)
+        .recover(recover_rate_limited);
+    with_cors(routes, &config.cors)
+}
+
+/// The valid samples of `session` within `window` of its last one, with their arrival times
+///
+/// More than `max` are thinned to every n-th sample, keeping the newest, so
+/// the fast-forward stays quick however dense the recording.
+fn restore_tail(
+    session: Session<RealQUICMetrics>,
+    window: Duration,
+    max: usize,
+) -> Vec<(chrono::DateTime<chrono::Utc>, RealQUICMetrics)> {
+    let end = session.samples.last().map_or(Duration::ZERO, |(offset, _)| *offset);
+    let start = end.saturating_sub(window);
+    let tail: Vec<(Duration, RealQUICMetrics)> = session
+        .samples
+        .into_iter()
+        .filter(|(offset, metrics)| *offset >= start && metrics.validate().is_ok())
+        .collect();
+
+    let step = tail.len().div_ceil(max.max(1)).max(1);
+    let mut kept: Vec<_> = tail
+        .into_iter()
+        .rev()
+        .step_by(step)
+        .map(|(offset, metrics)| {
+            let at = session.started_at + chrono::Duration::from_std(offset).unwrap_or_default();
+            (at, metrics)
+        })
+        .collect();
+    kept.reverse();
+    kept
+}
+
+/// Feed newline-delimited JSON samples from a Unix socket into `state`
+#[cfg(unix)]
+fn spawn_uds_ingest(path: &Path, state: ApiState) -> Result<()> {
+    let uds = UdsListener::bind(path)?;
+    tokio::spawn(uds.serve(move |metrics: RealQUICMetrics| {
+        metrics.validate()?;
+        state.ingest(metrics)
+    }));
+    Ok(())
+}
+
+impl TuiMode for RealQUICBottom {
+    fn tick(&mut self) -> bool {
+        self.check_config();
+        // Apply resets requested through the API
+        self.process_remote_resets();
+        self.process_remote_simulation();
+        self.update_all_widgets();
+        self.publish_anomaly_counts();
+        self.status_bar.sample();
+        let screen = self.screen_state();
+        let changed = self.screen.as_ref() != Some(&screen);
+        self.screen = Some(screen);
+        changed
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        self.ui(f);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.handle_key_event(key);
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        self.handle_mouse_event(mouse);
+    }
+
+    fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    fn poll_interval(&self) -> Option<Duration> {
+        Some(self.update_interval)
+    }
+}
+
+/// Connection tracker with the configured stale/evict timeouts
+fn connection_tracker(config: &QuicBottomConfig) -> ConnectionTracker {
+    let table = &config.widgets.connection_table;
+    ConnectionTracker::new(
+        config.max_data_points,
+        Duration::from_secs(table.stale_after_secs),
+        Duration::from_secs(table.evict_after_secs),
+    )
+}
+
+/// Run `quic-bottom live`: the TUI over metrics posted by the Go sender, or only its API when `headless`
+pub async fn run(config: QuicBottomConfig, interval_ms: u64, options: LiveOptions) -> Result<()> {
+    let api_addr = config.api_addr()?;
+    let baseline = options.baseline.as_ref().map(Baseline::load).transpose()?;
+
+    if let Some(path) = &options.replay_session {
+        if !(options.speed.is_finite() && options.speed > 0.0) {
+            return Err(anyhow!("--speed must be a positive number, got {}", options.speed));
+        }
+        let mut session = load_session::<RealQUICMetrics>(path)?;
+        // Recordings hold what was accepted, but may be edited or come from an older version
+        session.samples.retain(|(offset, metrics)| match metrics.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                record_ingest_error();
+                log::warn!("{}: skipping sample at {:?}: {}", path.display(), offset, e);
+                false
+            }
+        });
+        if session.samples.is_empty() {
+            return Err(anyhow!("{} contains no valid samples", path.display()));
+        }
+        println!(
+            "Replaying {} ({} samples) at {}x",
+            path.display(),
+            session.samples.len(),
+            options.speed
+        );
+        let mut app = RealQUICBottom::new(interval_ms, api_addr)
+            .await?
+            .with_config(&config)?
+            .with_session_replay(session, options.speed);
+        if let Some(baseline) = baseline {
+            app = app.with_baseline(baseline);
+        }
+        if let Some(watcher) = options.config_watcher {
+            app = app.with_config_watcher(watcher);
+        }
+        return app.run().await;
+    }
+
+    // Read before the recorder opens the file, which may create it
+    let restored = options
+        .record
+        .as_ref()
+        .filter(|path| options.restore && path.metadata().is_ok_and(|meta| meta.len() > 0))
+        .and_then(|path| match load_session::<RealQUICMetrics>(path) {
+            Ok(session) => Some(session),
+            Err(e) => {
+                eprintln!("Not restoring history: {:#}", e);
+                None
+            }
+        });
+
+    let recorder = options
+        .record
+        .as_ref()
+        .map(|path| SessionRecorder::create(path).map(Arc::new))
+        .transpose()?;
+
+    println!("Starting Real QUIC Bottom...");
+    println!("Real-time QUIC metrics from Go application!");
+    println!("Professional visualizations with live data!");
+    println!();
+    println!("Features:");
+    println!("  ✅ Real-time QUIC metrics from Go application");
+    println!("  ✅ HTTP API for metrics collection");
+    println!("  ✅ Professional visualizations");
+    println!("  ✅ Network simulation integration");
+    println!("  ✅ Security checks of the QUIC endpoint");
+    println!("  ✅ Cloud deployment monitoring");
+    println!("  ✅ Interactive controls");
+    println!();
+    let scheme = config.api.scheme();
+    println!("HTTP API endpoints ({}://{}):", scheme, api_addr);
+    println!("  POST /api/metrics - Receive metrics from Go app");
+    println!("  POST /api/metrics/batch[?order=sort|reject] - Receive a JSON array of metrics");
+    println!("  GET /health - Health check");
+    println!("  GET /api/current - Get current metrics and their age (age_ms)");
+    println!("  GET /api/sla - Verdict against the [sla] targets");
+    println!("  GET /api/health-score - Composite health score with its breakdown");
+    println!("  GET /api/simulation - Network simulation state and profiles");
+    println!("  PUT /api/simulation - Switch the simulation on/off or to another profile");
+    println!("  GET /api/security - Results of the security checks");
+    println!("  POST /api/security - Run the security checks against [security] target");
+    println!("  GET /api/ingest/stats - Requests refused by the rate and size limits");
+    println!("  POST /api/reset - Reset stats/history/anomalies (needs the API token)");
+    println!("  DELETE /api/metrics - Reset everything, as 'r' does");
+    if let Some(path) = &config.ingest.uds_path {
+        println!("Unix socket ingestion: {} (newline-delimited JSON)", path.display());
+    }
+    if resolve_token(&config.api).is_some() {
+        let scope = if config.api.auth_reads { "all requests but /health" } else { "POST requests" };
+        println!("Authentication: 'Authorization: Bearer <token>' required for {}", scope);
+    }
+    println!();
+
+    if let Some(recorder) = &recorder {
+        println!("Recording ingested samples to {}", recorder.status().path.display());
+        println!();
+    }
+
+    let exporter = config
+        .exporters
+        .influx
+        .is_enabled()
+        .then(|| InfluxExporter::new(config.exporters.influx.clone()))
+        .transpose()?
+        .map(|exporter| {
+            let queue = exporter.queue();
+            exporter.spawn();
+            queue
+        });
+
+    if options.headless {
+        println!("🚀 Starting in HEADLESS mode (HTTP API only, no TUI)");
+        let (reset_tx, _reset_rx) = mpsc::unbounded_channel();
+        let mut state = ApiState::new(connection_tracker(&config), reset_tx);
+        // Without the TUI only the history and current sample are restored
+        if let Some(session) = restored {
+            let samples = restore_tail(session, RESTORE_WINDOW, MAX_RESTORED_SAMPLES);
+            println!("Restored {} samples of history", samples.len());
+            for (_, mut metrics) in samples {
+                metrics.normalize_units();
+                state.restore(metrics);
+            }
+        }
+        state.recorder = recorder;
+        state.exporter = exporter;
+        *state.derived_config.lock_or_recover() = config.derived.clone();
+        *state.sla_config.lock_or_recover() = config.sla.clone();
+        *state.health_config.lock_or_recover() = config.health.clone();
+        *state.network.lock_or_recover() = NetworkSimulation::from_config(&config.network);
+        state.security.set_config(config.security.clone());
+        let (_, server) = bind_http_server(api_addr, state.clone(), &config.api)?;
+        #[cfg(unix)]
+        if let Some(path) = &config.ingest.uds_path {
+            spawn_uds_ingest(path, state.clone())?;
+        }
+
+        println!("HTTP API server listening on {}://{}", scheme, api_addr);
+        println!("\nTo test, run in another terminal:");
+        println!("  curl -X GET {}://{}/health", scheme, api_addr);
+        println!(
+            "  curl -X POST {}://{}/api/metrics -H 'Content-Type: application/json' -d '{{...}}'",
+            scheme, api_addr
+        );
+        println!("\nPress Ctrl+C to stop.\n");
+
+        // Return on a signal so exporters get flushed on the way out
+        tokio::select! {
+            _ = server => {}
+            name = shutdown::signal() => println!("\nshutting down ({})", name),
+        }
+        if let Some(queue) = &state.exporter {
+            shutdown::flush_exporter(queue).await;
+        }
+
+        // No analytics run without the TUI, so the report covers the samples only
+        let samples = report_samples(&state.metrics_history.lock_or_recover());
+        if config.report.on_exit && !samples.is_empty() {
+            let report =
+                SessionReport::build(&samples, &[], &[], chrono::Utc::now()).with_health(state.health_score());
+            let redactor = config.redaction.enabled.then(|| Redactor::new(&config.redaction));
+            match write_report(&config.report, &report, redactor.as_ref()) {
+                Ok(paths) => println!("{}", paths),
+                Err(e) => eprintln!("Report failed: {}", e),
+            }
+        }
+
+        // CI gates on the exit status
+        if config.sla.is_enabled() {
+            let verdict = state.sla();
+            println!("SLA: {}", verdict.summary());
+            if config.sla.exit_on_fail && verdict.is_failure() {
+                return Err(anyhow!("SLA not met: {}", verdict.summary()));
+            }
+        }
+    } else {
+        println!("Starting in TUI mode");
+        println!("Press '6' to switch to BBRv3 mode");
+        println!();
+
+        let mut app = RealQUICBottom::new(interval_ms, api_addr).await?.with_config(&config)?;
+        if let Some(baseline) = baseline {
+            println!(
+                "Comparing against {} ({})",
+                baseline.path.display(),
+                format_span(baseline.duration())
+            );
+            app = app.with_baseline(baseline);
+        }
+        if let Some(session) = restored {
+            app = app.with_restored_history(session);
+        }
+        if let Some(recorder) = recorder {
+            app = app.with_recorder(recorder);
+        }
+        if let Some(queue) = exporter {
+            app = app.with_exporter(queue);
+        }
+        if let Some(watcher) = options.config_watcher {
+            app = app.with_config_watcher(watcher);
+        }
+        app.run().await?;
+    }
+
+    println!("✅ Real QUIC Bottom completed!");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quic_bottom::metrics::MAX_SOURCES;
+
+    /// A valid `POST /api/metrics` body
+    fn sample(latency: f64) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp": chrono::Utc::now().timestamp_millis(),
+            "latency": latency, "throughput": 100.0, "connections": 1, "errors": 0,
+            "packet_loss": 0.0, "retransmits": 0, "jitter": 1.0, "congestion_window": 10,
+            "rtt": latency, "bytes_received": 0, "bytes_sent": 0, "streams": 1, "handshake_time": 5.0,
+        })
+    }
+
+    fn state() -> (ApiState, mpsc::UnboundedReceiver<ResetRequest>) {
+        let (reset_tx, reset_rx) = mpsc::unbounded_channel();
+        (ApiState::new(connection_tracker(&QuicBottomConfig::default()), reset_tx), reset_rx)
+    }
+
+    async fn health(routes: &BoxedFilter<(Response,)>) -> (StatusCode, serde_json::Value) {
+        let response = warp::test::request().path("/health").reply(routes).await;
+        (response.status(), serde_json::from_slice(response.body()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn health_reports_readiness() {
+        let (state, reset_rx) = state();
+        let config = ApiConfig {
+            health_max_sample_age_ms: Some(60_000),
+            ..ApiConfig::default()
+        };
+        let routes = api_routes(state, &config).unwrap();
+
+        let (status, body) = health(&routes).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["problems"], serde_json::json!(["no sample received yet"]));
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/metrics")
+            .json(&sample(12.0))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (status, body) = health(&routes).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["metrics_initialized"], true);
+        assert_eq!(body["samples_total"], 1);
+
+        // Nothing consumes the state once the dashboard is gone
+        drop(reset_rx);
+        let (status, body) = health(&routes).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["problems"], serde_json::json!(["metrics system is not initialized"]));
+    }
+
+    #[tokio::test]
+    async fn http_server_answers_once_it_reports_ready() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (api, _reset_rx) = state();
+        let ready = start_http_server(([127, 0, 0, 1], 0).into(), api, &ApiConfig::default()).unwrap();
+        let bound = ready.await.unwrap();
+
+        // Straight away, as the TUI would start drawing
+        let mut stream = tokio::net::TcpStream::connect(bound).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 "), "{}", response);
+        assert!(response.contains("\"metrics_initialized\":true"), "{}", response);
+
+        // An occupied port is an error before anything is spawned
+        let (api, _other_rx) = state();
+        assert!(start_http_server(bound, api, &ApiConfig::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn health_answers_get_only() {
+        let (state, _reset_rx) = state();
+        let routes = api_routes(state, &ApiConfig::default()).unwrap();
+        let response = warp::test::request().method("POST").path("/health").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        let response = warp::test::request().path("/health/extra").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    async fn get(routes: &BoxedFilter<(Response,)>, path: &str) -> (StatusCode, serde_json::Value) {
+        let response = warp::test::request().path(path).reply(routes).await;
+        (response.status(), serde_json::from_slice(response.body()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn sources_are_labelled_as_the_bridge_labels_them() {
+        let (state, _reset_rx) = state();
+        let config = ApiConfig {
+            source_from_addr: true,
+            ..ApiConfig::default()
+        };
+        let routes = api_routes(state, &config).unwrap();
+
+        let mut labelled = sample(10.0);
+        labelled["source"] = "server".into();
+        for (path, body) in [
+            ("/api/metrics", labelled),
+            // The body wins over the query, which wins over the address
+            ("/api/metrics?source=client", sample(20.0)),
+            ("/api/metrics", sample(30.0)),
+        ] {
+            let response = warp::test::request()
+                .method("POST")
+                .path(path)
+                .remote_addr("10.0.0.7:4000".parse().unwrap())
+                .json(&body)
+                .reply(&routes)
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/metrics/batch?source=client")
+            .json(&serde_json::json!([sample(40.0), sample(50.0)]))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let (_, sources) = get(&routes, "/api/sources").await;
+        let counts: Vec<(String, u64)> = sources
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| (s["source"].as_str().unwrap().to_string(), s["samples"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(
+            counts,
+            [("10.0.0.7".to_string(), 1), ("client".to_string(), 3), ("server".to_string(), 1)]
+        );
+
+        let (status, body) = get(&routes, "/api/current?source=server").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["latency"], 10.0);
+        let (_, body) = get(&routes, "/api/current?source=client").await;
+        assert_eq!(body["latency"], 50.0);
+        let (_, body) = get(&routes, "/api/current").await;
+        assert_eq!(body["latency"], 50.0);
+        let (status, body) = get(&routes, "/api/current?source=nobody").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["message"], "unknown source 'nobody'");
+    }
+
+    #[tokio::test]
+    async fn sources_past_the_limit_are_refused() {
+        let (state, _reset_rx) = state();
+        let history = Arc::clone(&state.metrics_history);
+        let routes = api_routes(state, &ApiConfig::default()).unwrap();
+        let post = |source: String| {
+            let routes = routes.clone();
+            async move {
+                warp::test::request()
+                    .method("POST")
+                    .path(&format!("/api/metrics?source={}", source))
+                    .json(&sample(10.0))
+                    .reply(&routes)
+                    .await
+                    .status()
+            }
+        };
+
+        for i in 0..MAX_SOURCES {
+            assert_eq!(post(format!("s{}", i)).await, StatusCode::OK);
+        }
+        assert_eq!(post("one-too-many".to_string()).await, StatusCode::BAD_REQUEST);
+        assert_eq!(post("s0".to_string()).await, StatusCode::OK);
+        assert_eq!(post("bad%0Alabel".to_string()).await, StatusCode::BAD_REQUEST);
+        assert_eq!(history.lock_or_recover().len(), MAX_SOURCES + 1);
+    }
+
+    #[tokio::test]
+    async fn reset_scope_reaches_the_dashboard() {
+        let (state, mut reset_rx) = state();
+        let history = Arc::clone(&state.metrics_history);
+        let config = ApiConfig {
+            auth_token: Some("reset-token".to_string()),
+            ..ApiConfig::default()
+        };
+        let routes = api_routes(state.clone(), &config).unwrap();
+        for latency in [10.0, 20.0] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/api/metrics")
+                .header("authorization", "Bearer reset-token")
+                .json(&sample(latency))
+                .reply(&routes)
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/reset")
+            .header("authorization", "Bearer reset-token")
+            .body(r#"{"stats": false, "anomalies": true, "segments": "new"}"#)
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["history_dropped"], 0);
+        assert_eq!(body["segment"], 2);
+        let (outcome, _caller) = reset_rx.try_recv().unwrap();
+        assert!(!outcome.scope.stats && outcome.scope.anomalies);
+        assert_eq!(history.lock_or_recover().len(), 2);
+
+        let response = warp::test::request()
+            .method("DELETE")
+            .path("/api/metrics")
+            .header("authorization", "Bearer reset-token")
+            .reply(&routes)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["history_dropped"], 2);
+        assert_eq!(body["segment"], 2);
+        assert_eq!(reset_rx.try_recv().unwrap().0.scope, ResetScope::full());
+        assert!(history.lock_or_recover().is_empty());
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/reset")
+            .header("authorization", "Bearer reset-token")
+            .body("not json")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(reset_rx.try_recv().is_err());
+
+        // Without a token to demand, nobody may reset
+        let routes = api_routes(state, &ApiConfig::default()).unwrap();
+        let response = warp::test::request().method("DELETE").path("/api/metrics").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(reset_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_resets_clear_what_each_scope_selects() {
+        let started_at = chrono::Utc::now() - chrono::Duration::minutes(5);
+        let samples = recorded(120);
+        for bits in 0..16u8 {
+            let scope = ResetScope {
+                stats: bits & 1 != 0,
+                history: bits & 2 != 0,
+                anomalies: bits & 4 != 0,
+                segments: if bits & 8 != 0 { SegmentReset::New } else { SegmentReset::Keep },
+            };
+            let mut app = RealQUICBottom::new(1000, ([127, 0, 0, 1], 0).into()).await.unwrap();
+            feed(&mut app, started_at, &samples);
+            assert!(!app.latency_graph.values().is_empty());
+            assert!(!app.anomaly_widget.anomalies().is_empty());
+
+            // What `POST /api/reset` calls, then what the TUI does with it
+            app.api.reset(scope, "10.0.0.9:5000");
+            app.process_remote_resets();
+            assert_eq!(app.latency_graph.values().is_empty(), scope.stats, "{:?}", scope);
+            assert_eq!(app.anomaly_widget.anomalies().is_empty(), scope.anomalies, "{:?}", scope);
+            assert_eq!(app.api.metrics_history.lock_or_recover().is_empty(), scope.history, "{:?}", scope);
+            let (toast, _) = app.toast.as_ref().unwrap();
+            assert!(toast.starts_with("Reset via API by 10.0.0.9:5000"), "{}", toast);
+            assert_eq!(toast.contains("segment 2 started"), scope.segments == SegmentReset::New);
+        }
+    }
+
+    /// A second apart, with a latency spike and throughput following latency
+    fn recorded(count: usize) -> Vec<(Duration, RealQUICMetrics)> {
+        (0..count)
+            .map(|i| {
+                let latency = if i % 50 == 49 { 400.0 } else { 20.0 + (i % 7) as f64 };
+                let mut metrics: RealQUICMetrics = serde_json::from_value(sample(latency)).unwrap();
+                metrics.throughput = 1000.0 - latency;
+                metrics.jitter = (i % 5) as f64;
+                (Duration::from_secs(i as u64), metrics)
+            })
+            .collect()
+    }
+
+    /// Feed `samples` as a live run would, at `started_at` plus their offsets
+    fn feed(app: &mut RealQUICBottom, started_at: chrono::DateTime<chrono::Utc>, samples: &[(Duration, RealQUICMetrics)]) {
+        for (offset, metrics) in samples {
+            let mut metrics = metrics.clone();
+            metrics.normalize_units();
+            app.api.ingest(metrics.clone()).unwrap();
+            app.ingest_sample(&metrics, started_at + chrono::Duration::from_std(*offset).unwrap());
+        }
+    }
+
+    fn heatmap_cells(app: &RealQUICBottom) -> ratatui::buffer::Buffer {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+        terminal
+            .draw(|f| app.performance_heatmap.render(f, f.area(), &Theme::default()))
+            .unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[tokio::test]
+    async fn restored_run_matches_an_uninterrupted_one() {
+        let samples = recorded(200);
+        let started_at = chrono::Utc::now() - chrono::Duration::seconds(200);
+        let addr = ([127, 0, 0, 1], 0).into();
+
+        let mut uninterrupted = RealQUICBottom::new(1000, addr).await.unwrap();
+        feed(&mut uninterrupted, started_at, &samples);
+
+        // The first run dies after 120 samples; the next one resumes its recording
+        let path = std::env::temp_dir().join(format!("quic-bottom-restore-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let recorder = SessionRecorder::create(&path).unwrap();
+        for (offset, metrics) in &samples[..120] {
+            recorder.record(started_at + chrono::Duration::from_std(*offset).unwrap(), metrics);
+        }
+        let session = load_session::<RealQUICMetrics>(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut restarted = RealQUICBottom::new(1000, addr).await.unwrap().with_restored_history(session);
+        assert!(restarted.toast.as_ref().unwrap().0.starts_with("Restored 1m59s of history"));
+        feed(&mut restarted, started_at, &samples[120..]);
+
+        let latencies = |app: &RealQUICBottom| -> Vec<f64> {
+            app.api.metrics_history.lock_or_recover().iter().map(|metrics| metrics.latency).collect()
+        };
+        assert_eq!(latencies(&restarted), latencies(&uninterrupted));
+        assert_eq!(restarted.latency_graph.values(), uninterrupted.latency_graph.values());
+
+        let correlations = |app: &RealQUICBottom| -> Vec<(String, String, f64, f64)> {
+            app.correlation_widget
+                .correlations()
+                .iter()
+                .map(|c| (c.metric1.clone(), c.metric2.clone(), c.correlation, c.p_value))
+                .collect()
+        };
+        assert!(!correlations(&uninterrupted).is_empty());
+        assert_eq!(correlations(&restarted), correlations(&uninterrupted));
+
+        let anomalies = |app: &RealQUICBottom| -> Vec<(String, f64, AnomalySeverity)> {
+            app.anomaly_widget
+                .anomalies()
+                .into_iter()
+                .map(|anomaly| (anomaly.metric, anomaly.value, anomaly.severity))
+                .collect()
+        };
+        assert!(!anomalies(&uninterrupted).is_empty());
+        assert_eq!(anomalies(&restarted), anomalies(&uninterrupted));
+
+        assert_eq!(heatmap_cells(&restarted), heatmap_cells(&uninterrupted));
+    }
+
+    #[test]
+    fn restore_keeps_a_bounded_recent_tail() {
+        let mut samples = recorded(1800);
+        samples[1500].1.latency = -1.0;
+        let session = Session {
+            started_at: chrono::Utc::now(),
+            samples,
+        };
+        let tail = restore_tail(session.clone(), Duration::from_secs(15 * 60), 100);
+        assert!(tail.len() <= 100 && tail.len() >= 90, "{}", tail.len());
+        let first = session.started_at + chrono::Duration::seconds(1799 - 15 * 60);
+        assert!(tail.iter().all(|(at, metrics)| *at >= first && metrics.latency >= 0.0));
+        assert_eq!(tail.last().unwrap().0, session.started_at + chrono::Duration::seconds(1799));
+
+        // Under the cap nothing is thinned
+        let tail = restore_tail(session, Duration::from_secs(60), 100);
+        assert_eq!(tail.len(), 61);
+    }
+
+    /// The whole screen at `width`x`height`, one string per row
+    fn screen(app: &RealQUICBottom, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| app.ui(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn dashboard_reflows_at_common_terminal_sizes() {
+        let mut app = RealQUICBottom::new(1000, ([127, 0, 0, 1], 0).into()).await.unwrap();
+        feed(&mut app, chrono::Utc::now() - chrono::Duration::seconds(60), &recorded(60));
+        let rows_with = |rows: &[String], text: &str| rows.iter().filter(|row| row.contains(text)).count();
+
+        // Minimal: one line of current values per widget, nothing drawn twice
+        let small = screen(&app, 80, 24);
+        assert!(small.iter().any(|row| row.starts_with("QUIC Latency (ms): ")), "{}", small.join("\n"));
+        assert!(small.iter().any(|row| row.starts_with("QUIC Throughput: ")), "{}", small.join("\n"));
+        assert_eq!(rows_with(&small, "QUIC Latency"), 1);
+        assert_eq!(rows_with(&small, "Time Series"), 0);
+
+        // Compact: the graphs without their analytics rows
+        let medium = screen(&app, 120, 40);
+        assert_eq!(rows_with(&medium, "Time Series"), 2, "{}", medium.join("\n"));
+        assert_eq!(rows_with(&medium, "P50:"), 0);
+
+        // Full: analytics under both graphs
+        let large = screen(&app, 200, 60);
+        assert_eq!(rows_with(&large, "Time Series"), 2, "{}", large.join("\n"));
+        assert_eq!(rows_with(&large, "P50:"), 2);
+
+        // Every view survives being resized down to nothing and back
+        for (_, mode) in BUILTIN_VIEWS {
+            app.switch_view(|views| {
+                views.select_builtin(*mode);
+            });
+            for (width, height) in [(200, 60), (120, 40), (80, 24), (40, 10), (1, 1), (200, 60)] {
+                screen(&app, width, height);
+            }
+        }
+    }
+
+    fn press(app: &mut RealQUICBottom, codes: &[KeyCode]) {
+        for &code in codes {
+            app.handle_key_event(KeyEvent::from(code));
+        }
+    }
+
+    #[tokio::test]
+    async fn selections_and_zoom_survive_a_view_switch() {
+        let mut app = RealQUICBottom::new(1000, ([127, 0, 0, 1], 0).into()).await.unwrap();
+        feed(&mut app, chrono::Utc::now() - chrono::Duration::seconds(60), &recorded(60));
+
+        // In the all view: a matrix cell, an inspected heatmap cell, a zoomed graph and the focus
+        press(&mut app, &[KeyCode::Char('a'), KeyCode::Down, KeyCode::Right, KeyCode::Char('z')]);
+        press(&mut app, &[KeyCode::Char('x'), KeyCode::Down, KeyCode::Left]);
+        while app.focus.focused() != Some(Panel::Correlation) {
+            press(&mut app, &[KeyCode::Tab]);
+        }
+        let correlation = app.correlation_widget.cursor();
+        let heatmap = app.performance_heatmap.cursor();
+        let width = app.latency_graph.time_view().width;
+        assert!(heatmap.is_some());
+
+        // The analytics view starts from that state; moving its matrix selection stays there
+        press(&mut app, &[KeyCode::Char('2')]);
+        assert_eq!(app.correlation_widget.cursor(), correlation);
+        press(&mut app, &[KeyCode::Down, KeyCode::Down]);
+        assert_ne!(app.correlation_widget.cursor(), correlation);
+
+        // So does the BBRv3 view's zoom
+        press(&mut app, &[KeyCode::Char('6'), KeyCode::Char('z')]);
+        assert!(app.latency_graph.time_view().width < width);
+        app.performance_heatmap.set_cursor(None);
+
+        // Back in the all view everything is where it was left
+        press(&mut app, &[KeyCode::Char('a')]);
+        assert_eq!(app.correlation_widget.cursor(), correlation);
+        assert_eq!(app.performance_heatmap.cursor(), heatmap);
+        assert!(app.performance_heatmap.is_inspecting());
+        assert_eq!(app.latency_graph.time_view().width, width);
+        assert_eq!(app.throughput_graph.time_view().width, width);
+        assert_eq!(app.focus.focused(), Some(Panel::Correlation));
+
+        // And the BBRv3 view kept its own zoom
+        press(&mut app, &[KeyCode::Char('6')]);
+        assert!(app.latency_graph.time_view().width < width);
+    }
+
+    #[test]
+    fn loss_is_normalized_once_from_either_convention() {
+        let sent = |packet_loss: f64, loss_unit: &str| {
+            let mut body = sample(10.0);
+            body["packet_loss"] = packet_loss.into();
+            body["loss_unit"] = loss_unit.into();
+            serde_json::from_value::<RealQUICMetrics>(body).unwrap()
+        };
+        for mut metrics in [sent(0.005, "fraction"), sent(0.5, "percent")] {
+            metrics.validate().unwrap();
+            metrics.normalize_units();
+            assert_eq!((metrics.packet_loss, metrics.loss_unit), (0.005, Some(LossUnit::Fraction)));
+            assert_eq!(metrics.to_quic_metrics().packet_loss, 0.005);
+
+            // A recorded sample says it holds a fraction, so replaying it converts nothing
+            let mut replayed: RealQUICMetrics = serde_json::from_str(&serde_json::to_string(&metrics).unwrap()).unwrap();
+            replayed.normalize_units();
+            assert_eq!(replayed.packet_loss, 0.005);
+        }
+        assert!(sent(150.0, "percent").validate().is_err());
+        assert!(sent(1.5, "fraction").validate().is_err());
+    }
+}
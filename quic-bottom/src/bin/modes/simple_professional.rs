@@ -0,0 +1,245 @@
+//! Simple Professional QUIC Bottom with advanced analytics
+//! 
+//! Features:
+//! - Professional time graphs with analytics
+//! - Advanced metrics and percentiles
+//! - Real-time data visualization
+//! - Simplified implementation
+
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tokio::time::Duration;
+
+use quic_bottom::{
+    app::{AppCore, TuiMode},
+    demo_data::DemoDataGenerator,
+    keybindings::{render_help_overlay, Action, AppKind, KeyMap, KeybindingsConfig},
+    simple_professional::{SimpleQuicLatencyGraph, SimpleQuicThroughputGraph},
+    theme::{Theme, ThemeCycle},
+    time_view::TimeNav,
+};
+
+/// Simple Professional QUIC Bottom application
+pub struct SimpleProfessionalQuicBottom {
+    latency_graph: SimpleQuicLatencyGraph,
+    throughput_graph: SimpleQuicThroughputGraph,
+    demo_generator: DemoDataGenerator,
+    themes: ThemeCycle,
+    show_help: bool,
+    keymap: KeyMap,
+    should_quit: bool,
+    update_interval: Duration,
+}
+
+impl SimpleProfessionalQuicBottom {
+    pub async fn new(interval_ms: u64, theme: Theme, demo_generator: DemoDataGenerator) -> Result<Self> {
+        Ok(Self {
+            latency_graph: SimpleQuicLatencyGraph::new(),
+            throughput_graph: SimpleQuicThroughputGraph::new(),
+            demo_generator,
+            themes: ThemeCycle::new(theme),
+            show_help: false,
+            keymap: KeyMap::default(),
+            should_quit: false,
+            update_interval: Duration::from_millis(interval_ms),
+        })
+    }
+
+    /// Use `keymap` instead of the default keys
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        AppCore::new(self.update_interval).run(self).await
+    }
+
+    fn update_graphs(&mut self) {
+        // Generate demo data
+        let (latency, throughput, _, _, _) = self.demo_generator.generate_next();
+
+        // Update graphs
+        self.latency_graph.add_latency(latency);
+        self.throughput_graph.add_throughput(throughput);
+    }
+
+    fn navigate_graphs(&mut self, nav: TimeNav) {
+        self.latency_graph.navigate(nav);
+        self.throughput_graph.navigate(nav);
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        let Some(action) = self.keymap.action(AppKind::Simple, &key) else {
+            return;
+        };
+        if let Some(fault) = action.fault() {
+            self.demo_generator.inject(fault);
+            return;
+        }
+        match action {
+            Action::Quit => {
+                self.should_quit = true;
+            }
+            Action::Reset => {
+                // Reset data
+                self.latency_graph = SimpleQuicLatencyGraph::new();
+                self.throughput_graph = SimpleQuicThroughputGraph::new();
+                self.demo_generator.reset();
+            }
+            Action::Help => {
+                self.show_help = true;
+            }
+            Action::CycleTheme => {
+                self.themes.advance();
+            }
+            Action::ToggleScale => {
+                self.latency_graph.toggle_scale();
+                self.throughput_graph.toggle_scale();
+            }
+            // Time graph zoom and scroll-back
+            Action::ZoomIn => self.navigate_graphs(TimeNav::ZoomIn),
+            Action::ZoomOut => self.navigate_graphs(TimeNav::ZoomOut),
+            Action::ScrollBack => self.navigate_graphs(TimeNav::PanBack),
+            Action::ScrollForward => self.navigate_graphs(TimeNav::PanForward),
+            Action::Oldest => self.navigate_graphs(TimeNav::Oldest),
+            Action::Newest => self.navigate_graphs(TimeNav::Live),
+            _ => {}
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        let theme = self.themes.current();
+
+        // Header
+        self.render_header(f, chunks[0], theme);
+
+        // Main content - two professional graphs
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(50), // Latency graph
+                Constraint::Percentage(50), // Throughput graph
+            ])
+            .split(chunks[1]);
+
+        // Render professional graphs
+        self.latency_graph.render(f, main_chunks[0], theme);
+        self.throughput_graph.render(f, main_chunks[1], theme);
+
+        // Footer
+        self.render_footer(f, chunks[2], theme);
+
+        if self.show_help {
+            render_help_overlay(f, AppKind::Simple, &self.keymap, theme);
+        }
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let header_text = "Simple Professional QUIC Bottom - Advanced Analytics";
+        let header = Paragraph::new(header_text)
+            .style(theme.title(theme.text))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(header, area);
+    }
+
+    fn render_footer(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let keys = format!(
+            "Press '{}' to quit, '{}' to reset, '{}' for theme, '{}' for help",
+            self.keymap.key(Action::Quit),
+            self.keymap.key(Action::Reset),
+            self.keymap.key(Action::CycleTheme),
+            self.keymap.key(Action::Help)
+        );
+        let footer_text = match self.demo_generator.injection_status(self.update_interval) {
+            Some(status) => format!("{} | {}", status, keys),
+            None => format!("{}, F1-F5 to inject faults", keys),
+        };
+        let footer = Paragraph::new(footer_text)
+            .style(Style::default().fg(theme.accent))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, area);
+    }
+}
+
+impl TuiMode for SimpleProfessionalQuicBottom {
+    fn tick(&mut self) -> bool {
+        // New demo data every tick
+        self.update_graphs();
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        self.ui(f);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.handle_key_event(key);
+    }
+
+    fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+}
+
+/// Run `quic-bottom demo --style simple`
+pub async fn run(
+    interval_ms: u64,
+    theme: Theme,
+    demo_generator: DemoDataGenerator,
+    keybindings: &KeybindingsConfig,
+) -> Result<()> {
+    let (keymap, warnings) = KeyMap::from_config(keybindings);
+    println!("Starting Simple Professional QUIC Bottom...");
+    println!("Advanced analytics and professional graphs!");
+    println!("Based on bottom's capabilities but simplified!");
+    println!();
+    println!("Features:");
+    println!("  ✅ Professional time graphs");
+    println!("  ✅ Advanced analytics (P50, P95, P99)");
+    println!("  ✅ Real-time data visualization");
+    println!("  ✅ Simplified implementation");
+    println!();
+
+    let mut app = SimpleProfessionalQuicBottom::new(interval_ms, theme, demo_generator)
+        .await?
+        .with_keymap(keymap);
+    println!("Controls:");
+    for (keys, description) in app.keymap.help_rows(AppKind::Simple) {
+        println!("  {} - {}", keys, description);
+    }
+    println!();
+    for warning in &warnings {
+        println!("Warning: {}", warning);
+    }
+
+    app.run().await?;
+    println!("Demo data: {}", app.demo_generator.summary());
+    for fault in app.demo_generator.fault_log() {
+        println!("Injected {}", fault);
+    }
+    
+    println!("✅ Simple Professional QUIC Bottom completed!");
+    Ok(())
+}
@@ -0,0 +1,956 @@
+//! Ultimate Analytics QUIC Bottom
+//! 
+//! Features:
+//! - Enhanced analytics (heatmaps, correlation, anomaly detection)
+//! - Network simulation integration
+//! - Security testing integration
+//! - Cloud deployment monitoring
+//! - Advanced visualizations
+//! - Real-time parameter adjustment
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use std::collections::VecDeque;
+use std::path::Path;
+use tokio::time::Duration;
+
+use quic_bottom::{
+    app::{AppCore, TuiMode},
+    keybindings::{render_help_overlay, Action, AppKind, KeyMap},
+    config::{WidgetConfig, DEFAULT_MAX_FPS},
+    focus::{FocusState, Panel},
+    improved_layout::{build_layout, render_no_widgets, WidgetKind},
+    mouse::HitMap,
+    network_sim::{NetworkSimConfig, NetworkSimulation},
+    scenario::{Scenario, ScenarioAction, ScenarioMetric, ScenarioRunner},
+    demo_data::DemoDataGenerator,
+    metrics::QUICMetrics,
+    report::{write_report, ReportConfig, ReportPaths, SessionReport},
+    simple_professional::{SimpleQuicLatencyGraph, SimpleQuicThroughputGraph},
+    time_view::TimeNav,
+    heatmap_widget::{HeatmapConfig, QUICPerformanceHeatmap},
+    correlation_widget::{CorrelationConfig, QUICCorrelationWidget},
+    anomaly_detection::QUICAnomalyWidget,
+    theme::{Theme, ThemeCycle},
+    QuicBottomConfig,
+};
+
+/// Ultimate Analytics QUIC Bottom application
+pub struct UltimateAnalyticsQuicBottom {
+    // Basic graphs
+    latency_graph: SimpleQuicLatencyGraph,
+    throughput_graph: SimpleQuicThroughputGraph,
+    
+    // Enhanced analytics
+    performance_heatmap: QUICPerformanceHeatmap,
+    correlation_widget: QUICCorrelationWidget,
+    anomaly_widget: QUICAnomalyWidget,
+    
+    // Demo data
+    demo_generator: DemoDataGenerator,
+    /// Samples as shown, for the report on faults injected into them
+    history: VecDeque<QUICMetrics>,
+    report_config: ReportConfig,
+    
+    // App state
+    should_quit: bool,
+    paused: bool,
+    show_help: bool,
+    keymap: KeyMap,
+    /// Configured theme and the built-in ones 't' steps through
+    themes: ThemeCycle,
+    /// Where the last frame drew the widgets the mouse reaches
+    hits: HitMap<Panel>,
+    /// Focused widget of the view (Tab, clicks) and whether it is expanded ('e')
+    focus: FocusState,
+    /// Widgets turned on and off under `[widgets]`
+    widget_config: WidgetConfig,
+    update_interval: Duration,
+    /// Frame rate cap, from `max_fps`
+    max_fps: u32,
+    current_view: ViewMode,
+    
+    // Network simulation state
+    network: NetworkSimulation,
+
+    /// Steps of `--scenario`, run as scenario time passes
+    scenario: Option<ScenarioRunner>,
+    /// Values injected by the scenario into the next sample
+    injections: Vec<(ScenarioMetric, f64)>,
+    
+    // Security testing state
+    security_test_active: bool,
+    security_score: f64,
+    vulnerabilities_count: usize,
+    
+    // Cloud deployment state
+    cloud_deployment_active: bool,
+    cloud_provider: String,
+    cloud_instances: usize,
+    cloud_status: String,
+}
+
+/// Samples kept for the report, an hour at the default interval
+const REPORT_HISTORY: usize = 3600;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ViewMode {
+    Dashboard,
+    Analytics,
+    Network,
+    Security,
+    Cloud,
+    All,
+}
+
+impl UltimateAnalyticsQuicBottom {
+    pub async fn new(interval_ms: u64) -> Result<Self> {
+        Ok(Self {
+            latency_graph: SimpleQuicLatencyGraph::new(),
+            throughput_graph: SimpleQuicThroughputGraph::new(),
+            performance_heatmap: QUICPerformanceHeatmap::new(),
+            correlation_widget: QUICCorrelationWidget::new()
+                .with_sample_interval(Duration::from_millis(interval_ms)),
+            anomaly_widget: QUICAnomalyWidget::new(),
+            demo_generator: DemoDataGenerator::new(),
+            history: VecDeque::with_capacity(REPORT_HISTORY),
+            report_config: ReportConfig::default(),
+            should_quit: false,
+            paused: false,
+            show_help: false,
+            keymap: KeyMap::default(),
+            themes: ThemeCycle::default(),
+            hits: HitMap::new(),
+            focus: FocusState::default(),
+            widget_config: WidgetConfig::default(),
+            update_interval: Duration::from_millis(interval_ms),
+            max_fps: DEFAULT_MAX_FPS,
+            current_view: ViewMode::Dashboard,
+            network: NetworkSimulation::default(),
+            scenario: None,
+            injections: Vec::new(),
+            security_test_active: false,
+            security_score: 100.0,
+            vulnerabilities_count: 0,
+            cloud_deployment_active: false,
+            cloud_provider: "aws".to_string(),
+            cloud_instances: 2,
+            cloud_status: "running".to_string(),
+        })
+    }
+
+    /// Use `keymap` instead of the default keys
+    pub fn with_keymap(mut self, keymap: KeyMap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Start with `theme`; the cycle key steps through the built-in ones after it
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.themes = ThemeCycle::new(theme);
+        self
+    }
+
+    /// Draw from `generator` instead of a steady stream from a random seed
+    pub fn with_demo_data(mut self, generator: DemoDataGenerator) -> Self {
+        self.demo_generator = generator;
+        self
+    }
+
+    /// Write reports with the `[report]` settings
+    pub fn with_report(mut self, config: ReportConfig) -> Self {
+        self.report_config = config;
+        self
+    }
+
+    /// Use the `[heatmap]` settings instead of the defaults
+    pub fn with_heatmap(mut self, config: HeatmapConfig) -> Self {
+        self.performance_heatmap = QUICPerformanceHeatmap::with_config(config);
+        self
+    }
+
+    /// Leave out the widgets turned off under `[widgets]`
+    pub fn with_widgets(mut self, config: WidgetConfig) -> Self {
+        self.widget_config = config;
+        self
+    }
+
+    /// Draw at most `max_fps` frames per second
+    pub fn with_max_fps(mut self, max_fps: u32) -> Self {
+        self.max_fps = max_fps;
+        self
+    }
+
+    /// Use the `[correlation]` settings instead of the defaults
+    pub fn with_correlation(mut self, config: CorrelationConfig) -> Self {
+        self.correlation_widget = QUICCorrelationWidget::new()
+            .with_sample_interval(self.update_interval)
+            .with_config(config);
+        self
+    }
+
+    /// Use the `[network]` profiles instead of the built-in ones alone
+    pub fn with_network(mut self, config: &NetworkSimConfig) -> Self {
+        self.network = NetworkSimulation::from_config(config);
+        self
+    }
+
+    /// Run `scenario`'s steps; call after `with_network` so its presets are checked against the profiles
+    pub fn with_scenario(mut self, scenario: Scenario) -> Result<Self> {
+        scenario.check_profiles(&self.network)?;
+        self.scenario = Some(ScenarioRunner::new(scenario));
+        Ok(self)
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        AppCore::new(self.update_interval)
+            .with_max_fps(self.max_fps)
+            .run(self)
+            .await?;
+
+        // Demo data is only worth a report once faults were injected into it
+        if self.report_config.on_exit && !self.demo_generator.fault_log().is_empty() {
+            match self.write_report() {
+                Ok(paths) => println!("{}", paths),
+                Err(e) => eprintln!("Report failed: {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Summarize the samples shown with the anomalies and the faults injected
+    fn write_report(&self) -> Result<ReportPaths> {
+        let samples: Vec<QUICMetrics> = self.history.iter().cloned().collect();
+        let report = SessionReport::build(
+            &samples,
+            &self.anomaly_widget.anomalies(),
+            self.correlation_widget.correlations(),
+            chrono::Utc::now(),
+        )
+        .with_injections(self.demo_generator.fault_log(), self.update_interval);
+        write_report(&self.report_config, &report, None)
+    }
+
+    fn update_all_widgets(&mut self) {
+        if self.paused {
+            return;
+        }
+
+        // Generate demo data with network simulation effects
+        // The generator has no connection or error counts; handshake time and retransmits stand in
+        let (mut latency, mut throughput, mut connections, mut packet_loss, retransmits) =
+            self.demo_generator.generate_next();
+        let mut errors = (retransmits as u32 + self.demo_generator.injected_errors()) as f64;
+        for (metric, value) in self.injections.drain(..) {
+            match metric {
+                ScenarioMetric::Latency => latency = value,
+                ScenarioMetric::Throughput => throughput = value,
+                ScenarioMetric::PacketLoss => packet_loss = value,
+                ScenarioMetric::Connections => connections = value,
+                ScenarioMetric::Errors => errors = value,
+            }
+        }
+        
+        // Apply network simulation effects
+        let (adjusted_latency, adjusted_throughput, adjusted_loss) = self.network.apply(
+            latency, throughput, packet_loss
+        );
+        // The analytics show packet loss in percent
+        let loss_pct = adjusted_loss * 100.0;
+
+        // Update basic graphs
+        self.latency_graph.add_latency(adjusted_latency);
+        self.throughput_graph.add_throughput(adjusted_throughput);
+
+        // Update enhanced analytics
+        let now = chrono::Utc::now();
+        self.performance_heatmap.add_metric(now, "Latency", adjusted_latency);
+        self.performance_heatmap.add_metric(now, "Throughput", adjusted_throughput);
+        self.performance_heatmap.add_metric(now, "Packet Loss", loss_pct);
+        self.performance_heatmap.add_metric(now, "Connections", connections);
+        self.performance_heatmap.add_metric(now, "Errors", errors);
+
+        // Update correlation data
+        self.correlation_widget.add_metric_data("Latency".to_string(), adjusted_latency);
+        self.correlation_widget.add_metric_data("Throughput".to_string(), adjusted_throughput);
+        self.correlation_widget.add_metric_data("Packet Loss".to_string(), loss_pct);
+        self.correlation_widget.add_metric_data("Connections".to_string(), connections);
+        self.correlation_widget.add_metric_data("Errors".to_string(), errors);
+        self.correlation_widget.update_correlations();
+
+        // Update anomaly detection
+        self.anomaly_widget.add_quic_metric("Latency".to_string(), adjusted_latency);
+        self.anomaly_widget.add_quic_metric("Throughput".to_string(), adjusted_throughput);
+        self.anomaly_widget.add_quic_metric("Packet Loss".to_string(), loss_pct);
+        self.anomaly_widget.add_quic_metric("Connections".to_string(), connections);
+        self.anomaly_widget.add_quic_metric("Errors".to_string(), errors);
+
+        // The report reads retransmits and errors as cumulative counters
+        let previous = self.history.back();
+        let sample = QUICMetrics {
+            latency: adjusted_latency,
+            throughput: adjusted_throughput,
+            packet_loss: adjusted_loss,
+            retransmits: previous.map_or(0, |p| p.retransmits) + retransmits,
+            errors: previous.map_or(0, |p| p.errors) + errors as i32,
+            handshake_time: connections,
+            rtt: adjusted_latency,
+            timestamp: now,
+            ..QUICMetrics::default()
+        };
+        if self.history.len() == REPORT_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+    }
+
+
+    /// Whether the current view shows the correlation matrix
+    fn shows_correlation(&self) -> bool {
+        matches!(self.current_view, ViewMode::Analytics | ViewMode::All)
+    }
+
+    /// Whether the correlation widget has the focus, or is alone in the view
+    fn correlation_focused(&self) -> bool {
+        self.shows_correlation()
+            && (self.focus.focused() == Some(Panel::Correlation) || self.panels() == [Panel::Correlation])
+    }
+
+    /// Only count anomalies while a reset or network simulation change settles
+    fn suppress_anomalies(&mut self) {
+        let window = self.anomaly_widget.suppression();
+        self.anomaly_widget.suppress(window);
+    }
+
+    /// Whether the anomaly widget has the focus, or is alone in the view
+    fn anomaly_focused(&self) -> bool {
+        self.panels().contains(&Panel::Anomaly)
+            && (self.focus.focused() == Some(Panel::Anomaly) || self.panels() == [Panel::Anomaly])
+    }
+
+    fn navigate_graphs(&mut self, nav: TimeNav) {
+        self.latency_graph.navigate(nav);
+        self.throughput_graph.navigate(nav);
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) {
+        // Any key closes the help overlay
+        if self.show_help {
+            self.show_help = false;
+            return;
+        }
+
+        if self.handle_inspect_key(&key) || self.handle_anomaly_key(&key) || self.handle_correlation_key(&key) {
+            return;
+        }
+        let Some(action) = self.keymap.action(AppKind::Ultimate, &key) else {
+            return;
+        };
+        if let Some(fault) = action.fault() {
+            self.demo_generator.inject(fault);
+            return;
+        }
+        match action {
+            Action::Quit => {
+                self.should_quit = true;
+            }
+            Action::Reset => {
+                // Reset all data
+                self.reset_all_data();
+            }
+            Action::CorrelationMethod if self.shows_correlation() => {
+                self.correlation_widget.toggle_method();
+            }
+            Action::CorrelationLayout if self.shows_correlation() => {
+                self.correlation_widget.cycle_layout();
+            }
+            Action::Rebaseline => {
+                self.anomaly_widget.rebaseline();
+            }
+            Action::Inspect if self.panels().contains(&Panel::Heatmap) => {
+                self.performance_heatmap.toggle_inspect();
+            }
+            Action::ToggleScale => {
+                self.latency_graph.toggle_scale();
+                self.throughput_graph.toggle_scale();
+            }
+            // Time graph zoom and scroll-back
+            Action::ZoomIn => self.navigate_graphs(TimeNav::ZoomIn),
+            Action::ZoomOut => self.navigate_graphs(TimeNav::ZoomOut),
+            Action::ScrollBack => self.navigate_graphs(TimeNav::PanBack),
+            Action::ScrollForward => self.navigate_graphs(TimeNav::PanForward),
+            Action::Oldest => self.navigate_graphs(TimeNav::Oldest),
+            Action::Newest => self.navigate_graphs(TimeNav::Live),
+            Action::Pause => {
+                self.paused = !self.paused;
+            }
+            Action::Help => {
+                self.show_help = true;
+            }
+            // View switching
+            Action::ViewDashboard => {
+                self.current_view = ViewMode::Dashboard;
+            }
+            Action::ViewAnalytics => {
+                self.current_view = ViewMode::Analytics;
+            }
+            Action::ViewNetwork => {
+                self.current_view = ViewMode::Network;
+            }
+            Action::ViewSecurity => {
+                self.current_view = ViewMode::Security;
+            }
+            Action::ViewCloud => {
+                self.current_view = ViewMode::Cloud;
+            }
+            Action::ViewAll => {
+                self.current_view = ViewMode::All;
+            }
+            Action::FocusNext | Action::FocusPrev => {
+                self.focus.cycle(&self.panels(), action == Action::FocusNext);
+                self.apply_focus();
+            }
+            Action::Expand => {
+                self.focus.toggle_expanded(&self.panels());
+            }
+            Action::CycleTheme => {
+                self.themes.advance();
+            }
+            // Network simulation controls
+            Action::ToggleNetwork => {
+                self.network.toggle();
+                self.suppress_anomalies();
+            }
+            Action::NextPreset => {
+                self.network.next_preset();
+                self.suppress_anomalies();
+            }
+            Action::PrevPreset => {
+                self.network.prev_preset();
+                self.suppress_anomalies();
+            }
+            // Security testing controls
+            Action::ToggleSecurity => {
+                self.toggle_security_testing();
+            }
+            // Cloud deployment controls
+            Action::ToggleCloud => {
+                self.toggle_cloud_deployment();
+            }
+            Action::ScaleInstances => {
+                self.scale_cloud_instances();
+            }
+            _ => {}
+        }
+    }
+
+    /// Keys of the heatmap's inspect mode, which take precedence while it is on
+    fn handle_inspect_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.performance_heatmap.is_inspecting() || !self.panels().contains(&Panel::Heatmap) {
+            return false;
+        }
+        match key.code {
+            KeyCode::Esc => self.performance_heatmap.toggle_inspect(),
+            KeyCode::Up => self.performance_heatmap.move_cursor(-1, 0),
+            KeyCode::Down => self.performance_heatmap.move_cursor(1, 0),
+            KeyCode::Left => self.performance_heatmap.move_cursor(0, -1),
+            KeyCode::Right => self.performance_heatmap.move_cursor(0, 1),
+            KeyCode::Char('[') => self.performance_heatmap.jump_to_spike(false),
+            KeyCode::Char(']') => self.performance_heatmap.jump_to_spike(true),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Keys of the correlation matrix and its detail view, which take precedence there
+    /// Keys of the focused anomaly list: select, filter, acknowledge
+    fn handle_anomaly_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.anomaly_focused() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Up => self.anomaly_widget.move_selection(-1),
+            KeyCode::Down => self.anomaly_widget.move_selection(1),
+            KeyCode::PageUp => self.anomaly_widget.page_selection(-1),
+            KeyCode::PageDown => self.anomaly_widget.page_selection(1),
+            KeyCode::Char('c') => self.anomaly_widget.toggle_critical_only(),
+            KeyCode::Char('m') => self.anomaly_widget.cycle_metric_filter(),
+            KeyCode::Enter => self.anomaly_widget.acknowledge_selected(),
+            KeyCode::Delete => self.anomaly_widget.clear_acknowledged(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn handle_correlation_key(&mut self, key: &KeyEvent) -> bool {
+        if !self.shows_correlation() {
+            return false;
+        }
+        match key.code {
+            KeyCode::Esc if self.correlation_widget.is_detail_open() => self.correlation_widget.close_detail(),
+            KeyCode::Up => self.correlation_widget.move_selection(-1, 0),
+            KeyCode::Down => self.correlation_widget.move_selection(1, 0),
+            KeyCode::Left => self.correlation_widget.move_selection(0, -1),
+            KeyCode::Right => self.correlation_widget.move_selection(0, 1),
+            KeyCode::PageUp => self.correlation_widget.page_selection(-1),
+            KeyCode::PageDown => self.correlation_widget.page_selection(1),
+            KeyCode::Char('+') if self.correlation_focused() => self.correlation_widget.resize_window(1),
+            KeyCode::Char('-') if self.correlation_focused() => self.correlation_widget.resize_window(-1),
+            KeyCode::Enter => self.correlation_widget.open_detail(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Clicks focus a widget or pick a correlation pair; the wheel zooms the time graphs
+    fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        let clicked = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        // A click closes the help overlay, like any key
+        if self.show_help {
+            if clicked {
+                self.show_help = false;
+            }
+            return;
+        }
+
+        let hit = self.hits.hit(mouse.column, mouse.row);
+        let over_graph = matches!(hit, Some((Panel::LatencyGraph | Panel::ThroughputGraph, _)));
+        match mouse.kind {
+            MouseEventKind::ScrollUp if over_graph => self.navigate_graphs(TimeNav::ZoomIn),
+            MouseEventKind::ScrollDown if over_graph => self.navigate_graphs(TimeNav::ZoomOut),
+            _ if clicked => {
+                self.focus.set(hit.map(|(panel, _)| panel));
+                self.apply_focus();
+                if let Some((Panel::Correlation, area)) = hit {
+                    if self.correlation_widget.select_at(area, Position::new(mouse.column, mouse.row)) {
+                        self.correlation_widget.open_detail();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn reset_all_data(&mut self) {
+        self.latency_graph = SimpleQuicLatencyGraph::new();
+        self.throughput_graph = SimpleQuicThroughputGraph::new();
+        self.performance_heatmap.clear();
+        self.correlation_widget.clear();
+        self.anomaly_widget.clear();
+        self.suppress_anomalies();
+        self.demo_generator.reset();
+        self.history.clear();
+    }
+
+
+
+
+
+    /// Run the scenario steps that came due in this update interval
+    fn run_scenario(&mut self) {
+        if self.paused {
+            return;
+        }
+        let Some(runner) = &mut self.scenario else {
+            return;
+        };
+        for action in runner.advance(self.update_interval) {
+            match action {
+                ScenarioAction::SetPreset { preset } => {
+                    // Checked against the profiles when the scenario was loaded
+                    let _ = self.network.select(&preset);
+                    self.suppress_anomalies();
+                }
+                ScenarioAction::SetSimulation { active } => {
+                    self.network.set_active(active);
+                    self.suppress_anomalies();
+                }
+                ScenarioAction::SetSecurity { active } => {
+                    if self.security_test_active != active {
+                        self.toggle_security_testing();
+                    }
+                }
+                ScenarioAction::SetCloud { active } => {
+                    if self.cloud_deployment_active != active {
+                        self.toggle_cloud_deployment();
+                    }
+                }
+                ScenarioAction::InjectAnomaly { metric, value } => self.injections.push((metric, value)),
+            }
+        }
+    }
+
+    fn toggle_security_testing(&mut self) {
+        self.security_test_active = !self.security_test_active;
+        if self.security_test_active {
+            // Simulate security test results
+            self.security_score = 85.0 + (self.demo_generator.counter as f64 % 20.0);
+            self.vulnerabilities_count = (self.demo_generator.counter % 5) as usize;
+        }
+    }
+
+    fn toggle_cloud_deployment(&mut self) {
+        self.cloud_deployment_active = !self.cloud_deployment_active;
+        if self.cloud_deployment_active {
+            self.cloud_status = "running".to_string();
+        } else {
+            self.cloud_status = "stopped".to_string();
+        }
+    }
+
+    fn scale_cloud_instances(&mut self) {
+        if self.cloud_deployment_active {
+            self.cloud_instances = (self.cloud_instances % 5) + 1;
+        }
+    }
+
+    /// Focusable widgets of the current view, in Tab order
+    ///
+    /// Widgets turned off under `[widgets]` are left out.
+    fn panels(&self) -> Vec<Panel> {
+        let panels: &[Panel] = match self.current_view {
+            ViewMode::Dashboard => &[Panel::LatencyGraph, Panel::ThroughputGraph, Panel::Heatmap, Panel::Anomaly],
+            ViewMode::Analytics => &[Panel::Correlation, Panel::Anomaly],
+            ViewMode::All => &[
+                Panel::LatencyGraph,
+                Panel::ThroughputGraph,
+                Panel::Heatmap,
+                Panel::Correlation,
+                Panel::Anomaly,
+            ],
+            ViewMode::Network | ViewMode::Security | ViewMode::Cloud => &[],
+        };
+        panels
+            .iter()
+            .copied()
+            .filter(|panel| self.widget_config.is_enabled(panel.widget()))
+            .collect()
+    }
+
+    /// Show the focus on the widgets themselves
+    fn apply_focus(&mut self) {
+        let focused = self.focus.focused();
+        self.latency_graph.set_selected(focused == Some(Panel::LatencyGraph));
+        self.throughput_graph.set_selected(focused == Some(Panel::ThroughputGraph));
+        self.performance_heatmap.set_selected(focused == Some(Panel::Heatmap));
+        self.correlation_widget.set_selected(focused == Some(Panel::Correlation));
+        self.anomaly_widget.set_selected(focused == Some(Panel::Anomaly));
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        self.hits.clear();
+        if let Some(panel) = self.focus.expanded(&self.panels()) {
+            self.render_expanded(f, panel);
+        } else {
+            match self.current_view {
+                ViewMode::Dashboard => self.render_dashboard(f),
+                ViewMode::Analytics => self.render_analytics_view(f),
+                ViewMode::Network => self.render_network_view(f),
+                ViewMode::Security => self.render_security_view(f),
+                ViewMode::Cloud => self.render_cloud_view(f),
+                ViewMode::All => self.render_all_view(f),
+            }
+        }
+
+        if self.show_help {
+            render_help_overlay(f, AppKind::Ultimate, &self.keymap, self.themes.current());
+        }
+    }
+
+    /// Draw `panel` over `area` and remember where, for the mouse
+    fn render_panel(&self, f: &mut Frame, panel: Panel, area: Rect) {
+        self.hits.record(panel, area);
+        let theme = self.themes.current();
+        match panel {
+            Panel::LatencyGraph => self.latency_graph.render(f, area, theme),
+            Panel::ThroughputGraph => self.throughput_graph.render(f, area, theme),
+            Panel::Heatmap => self.performance_heatmap.render(f, area, theme),
+            Panel::Correlation => self.correlation_widget.render(f, area, theme),
+            Panel::Anomaly => self.anomaly_widget.render(f, area, theme),
+        }
+    }
+
+    /// Lay the view's enabled panels out over `area`, or a notice when `[widgets]` turned them all off
+    fn render_panels(&self, f: &mut Frame, area: Rect) {
+        let panels = self.panels();
+        if panels.is_empty() {
+            render_no_widgets(f, area, self.themes.current());
+            return;
+        }
+        let kinds: Vec<WidgetKind> = panels.iter().map(|panel| panel.widget()).collect();
+        for (panel, area) in panels.into_iter().zip(build_layout(&kinds, area)) {
+            self.render_panel(f, panel, area);
+        }
+    }
+
+    /// The focused widget alone between the header and footer
+    fn render_expanded(&self, f: &mut Frame, panel: Panel) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Expanded widget
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], &format!("{} (expanded)", panel.name()));
+        self.render_panel(f, panel, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_dashboard(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Ultimate Analytics Dashboard");
+        self.render_panels(f, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_analytics_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Analytics
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Advanced Analytics");
+        self.render_panels(f, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_network_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Network info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Network Simulation");
+
+        // Network simulation status
+        let network_text = self.network.status_text();
+
+        let network_paragraph = Paragraph::new(network_text)
+            .style(Style::default().fg(self.themes.current().info))
+            .block(Block::default().borders(Borders::ALL).title("Network Status"));
+        f.render_widget(network_paragraph, chunks[1]);
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_security_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Security info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Security Testing");
+
+        // Security testing status
+        let security_text = format!(
+            "Security Testing: {}\nSecurity Score: {:.1}%\nVulnerabilities: {}",
+            if self.security_test_active { "ACTIVE" } else { "INACTIVE" },
+            self.security_score,
+            self.vulnerabilities_count
+        );
+
+        let security_paragraph = Paragraph::new(security_text)
+            .style(Style::default().fg(self.themes.current().warning))
+            .block(Block::default().borders(Borders::ALL).title("Security Status"));
+        f.render_widget(security_paragraph, chunks[1]);
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_cloud_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Cloud info
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Cloud Deployment");
+
+        // Cloud deployment status
+        let cloud_text = format!(
+            "Cloud Deployment: {}\nProvider: {}\nInstances: {}\nStatus: {}",
+            if self.cloud_deployment_active { "ACTIVE" } else { "INACTIVE" },
+            self.cloud_provider,
+            self.cloud_instances,
+            self.cloud_status
+        );
+
+        let cloud_paragraph = Paragraph::new(cloud_text)
+            .style(Style::default().fg(self.themes.current().success))
+            .block(Block::default().borders(Borders::ALL).title("Cloud Status"));
+        f.render_widget(cloud_paragraph, chunks[1]);
+
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_all_view(&self, f: &mut Frame) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Min(0),    // Main content
+                Constraint::Length(3), // Footer
+            ])
+            .split(f.area());
+
+        self.render_header(f, chunks[0], "Ultimate Analytics - All Views");
+        self.render_panels(f, chunks[1]);
+        self.render_footer(f, chunks[2]);
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect, title: &str) {
+        let theme = self.themes.current();
+        let header_text = format!("Ultimate Analytics QUIC Bottom - {}", title);
+        let mut spans = vec![Span::raw(header_text)];
+        if self.paused {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                " PAUSED ",
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        let header = Paragraph::new(Line::from(spans))
+            .style(theme.title(theme.text))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(header, area);
+    }
+
+    fn render_footer(&self, f: &mut Frame, area: Rect) {
+        let scenario = self
+            .scenario
+            .as_ref()
+            .map_or(String::new(), |runner| format!("{} | ", runner.status()));
+        let injecting = self
+            .demo_generator
+            .injection_status(self.update_interval)
+            .map_or(String::new(), |status| format!("{} | ", status));
+        let footer_text = format!(
+            "{}{}Press '{}' to quit, '{}' to reset, '{}' to pause, '{}' for help, '{}-{}' for views, '{}' for all, '{}' for network, '{}' for security, '{}' for cloud",
+            injecting,
+            scenario,
+            self.keymap.key(Action::Quit),
+            self.keymap.key(Action::Reset),
+            self.keymap.key(Action::Pause),
+            self.keymap.key(Action::Help),
+            self.keymap.key(Action::ViewDashboard),
+            self.keymap.key(Action::ViewCloud),
+            self.keymap.key(Action::ViewAll),
+            self.keymap.key(Action::ToggleNetwork),
+            self.keymap.key(Action::ToggleSecurity),
+            self.keymap.key(Action::ToggleCloud)
+        );
+        let footer = Paragraph::new(footer_text)
+            .style(Style::default().fg(self.themes.current().accent))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, area);
+    }
+}
+
+impl TuiMode for UltimateAnalyticsQuicBottom {
+    fn tick(&mut self) -> bool {
+        // New demo data every tick
+        self.run_scenario();
+        self.update_all_widgets();
+        true
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        self.ui(f);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.handle_key_event(key);
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        self.handle_mouse_event(mouse);
+    }
+
+    fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+}
+
+/// Run `quic-bottom analytics`
+pub async fn run(
+    config: QuicBottomConfig,
+    interval_ms: u64,
+    demo_generator: DemoDataGenerator,
+    scenario: Option<&Path>,
+) -> Result<()> {
+    let scenario = scenario.map(Scenario::load).transpose()?;
+    let (keymap, warnings) = KeyMap::from_config(&config.keybindings);
+    println!("Starting Ultimate Analytics QUIC Bottom...");
+    println!("Ultimate analytics with network simulation, security testing, and cloud monitoring!");
+    println!("Professional visualizations with real-time parameter adjustment!");
+    println!();
+    println!("Features:");
+    println!("  ✅ Enhanced analytics (heatmaps, correlation, anomaly detection)");
+    println!("  ✅ Network simulation with presets");
+    println!("  ✅ Security testing integration");
+    println!("  ✅ Cloud deployment monitoring");
+    println!("  ✅ Real-time parameter adjustment");
+    println!("  ✅ Interactive controls");
+    println!();
+    println!("Controls:");
+    for (keys, description) in keymap.help_rows(AppKind::Ultimate) {
+        println!("  {} - {}", keys, description);
+    }
+    println!();
+    for warning in &warnings {
+        println!("Warning: {}", warning);
+    }
+    
+    let mut app = UltimateAnalyticsQuicBottom::new(interval_ms)
+        .await?
+        .with_demo_data(demo_generator)
+        .with_keymap(keymap)
+        .with_theme(config.colors.theme()?)
+        .with_heatmap(config.heatmap.clone())
+        .with_correlation(config.correlation.clone())
+        .with_widgets(config.widgets.clone())
+        .with_max_fps(config.max_fps)
+        .with_network(&config.network)
+        .with_report(config.report.clone());
+    if let Some(scenario) = scenario {
+        app = app.with_scenario(scenario)?;
+    }
+    app.run().await?;
+    println!("Demo data: {}", app.demo_generator.summary());
+    
+    println!("✅ Ultimate Analytics QUIC Bottom completed!");
+    Ok(())
+}
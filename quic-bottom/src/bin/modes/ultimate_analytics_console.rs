@@ -8,17 +8,47 @@
 //! - Console-based output (no TUI)
 
 use anyhow::Result;
+use std::path::Path;
 use std::time::Duration;
 use tokio::time::sleep;
 
 use quic_bottom::{
     demo_data::DemoDataGenerator,
+    network_sim::{NetworkSimConfig, NetworkSimulation},
+    scenario::{Scenario, ScenarioAction, ScenarioMetric, ScenarioRunner},
     simple_professional::{SimpleQuicLatencyGraph, SimpleQuicThroughputGraph},
     heatmap_widget::QUICPerformanceHeatmap,
     correlation_widget::QUICCorrelationWidget,
     anomaly_detection::QUICAnomalyWidget,
+    QuicBottomConfig,
 };
 
+/// Steps run without `--scenario`: the simulation, security test and cloud deployment come on in turn
+const DEFAULT_SCENARIO: &str = r#"
+[[step]]
+at = "4s"
+action = "set_preset"
+preset = "mobile"
+
+[[step]]
+at = "4s"
+action = "set_simulation"
+active = true
+
+[[step]]
+at = "6s"
+action = "set_security"
+active = true
+
+[[step]]
+at = "8s"
+action = "set_cloud"
+active = true
+"#;
+
+/// Cycles run at least, also after the scenario is done
+const MIN_CYCLES: usize = 10;
+
 /// Ultimate Analytics QUIC Bottom - Console Version
 pub struct UltimateAnalyticsConsole {
     // Basic graphs
@@ -38,11 +68,11 @@ pub struct UltimateAnalyticsConsole {
     time_slot: usize,
     
     // Network simulation state
-    network_simulation_active: bool,
-    network_preset: String,
-    network_latency: f64,
-    network_loss: f64,
-    network_bandwidth: f64,
+    network: NetworkSimulation,
+
+    scenario: ScenarioRunner,
+    /// Values injected by the scenario into the next sample
+    injections: Vec<(ScenarioMetric, f64)>,
     
     // Security testing state
     security_test_active: bool,
@@ -57,21 +87,26 @@ pub struct UltimateAnalyticsConsole {
 }
 
 impl UltimateAnalyticsConsole {
-    pub async fn new(interval_ms: u64) -> Result<Self> {
+    pub async fn new(
+        interval_ms: u64,
+        demo_generator: DemoDataGenerator,
+        network: &NetworkSimConfig,
+        scenario: Scenario,
+    ) -> Result<Self> {
+        let network = NetworkSimulation::from_config(network);
+        scenario.check_profiles(&network)?;
         Ok(Self {
             latency_graph: SimpleQuicLatencyGraph::new(),
             throughput_graph: SimpleQuicThroughputGraph::new(),
             performance_heatmap: QUICPerformanceHeatmap::new(),
             correlation_widget: QUICCorrelationWidget::new(),
             anomaly_widget: QUICAnomalyWidget::new(),
-            demo_generator: DemoDataGenerator::new(),
+            demo_generator,
             update_interval: Duration::from_millis(interval_ms),
             time_slot: 0,
-            network_simulation_active: false,
-            network_preset: "good".to_string(),
-            network_latency: 20.0,
-            network_loss: 1.0,
-            network_bandwidth: 100.0,
+            network,
+            scenario: ScenarioRunner::new(scenario),
+            injections: Vec::new(),
             security_test_active: false,
             security_score: 100.0,
             vulnerabilities_count: 0,
@@ -85,150 +120,133 @@ impl UltimateAnalyticsConsole {
     pub async fn run(&mut self) -> Result<()> {
         println!("Ultimate Analytics QUIC Bottom - Console Mode");
         println!("================================================");
-        println!("");
+        println!();
         
-        // Simulate different scenarios
-        for cycle in 0..10 {
+        // Run the scenario, and at least MIN_CYCLES updates
+        let mut cycle = 0;
+        while cycle < MIN_CYCLES || !self.scenario.is_finished() {
             println!("Cycle {} - Ultimate Analytics Update", cycle + 1);
             println!("==========================================");
-            
+
+            self.run_scenario();
+
             // Update all widgets with demo data
             self.update_all_widgets();
             
             // Display current status
             self.display_status();
             
-            // Simulate network simulation toggle
-            if cycle == 3 {
-                self.network_simulation_active = true;
-                self.network_preset = "mobile".to_string();
-                self.apply_network_preset();
-                println!("Network simulation activated: {}", self.network_preset);
-            }
-            
-            // Simulate security testing toggle
-            if cycle == 5 {
-                self.security_test_active = true;
-                self.security_score = 85.0 + (cycle as f64 * 2.0);
-                self.vulnerabilities_count = cycle % 3;
-                println!("🔒 Security testing activated: Score {:.1}%, Vulnerabilities: {}", 
-                    self.security_score, self.vulnerabilities_count);
-            }
-            
-            // Simulate cloud deployment toggle
-            if cycle == 7 {
-                self.cloud_deployment_active = true;
-                self.cloud_instances = 3;
-                self.cloud_status = "running".to_string();
-                println!("☁️  Cloud deployment activated: {} instances on {}", 
-                    self.cloud_instances, self.cloud_provider);
-            }
-            
-            println!("");
+            println!();
             sleep(self.update_interval).await;
+            cycle += 1;
         }
         
         println!("✅ Ultimate Analytics completed!");
         Ok(())
     }
 
+    /// Run the scenario steps that came due in this cycle
+    fn run_scenario(&mut self) {
+        for action in self.scenario.advance(self.update_interval) {
+            println!("▶ Scenario step: {}", action);
+            match action {
+                ScenarioAction::SetPreset { preset } => {
+                    // Checked against the profiles when the scenario was loaded
+                    let _ = self.network.select(&preset);
+                    self.suppress_anomalies();
+                }
+                ScenarioAction::SetSimulation { active } => {
+                    self.network.set_active(active);
+                    self.suppress_anomalies();
+                }
+                ScenarioAction::SetSecurity { active } => {
+                    self.security_test_active = active;
+                    if active {
+                        self.security_score = 85.0 + (self.time_slot as f64 * 2.0);
+                        self.vulnerabilities_count = self.time_slot % 3;
+                    }
+                }
+                ScenarioAction::SetCloud { active } => {
+                    self.cloud_deployment_active = active;
+                    if active {
+                        self.cloud_instances = 3;
+                    }
+                    self.cloud_status = if active { "running" } else { "stopped" }.to_string();
+                }
+                ScenarioAction::InjectAnomaly { metric, value } => self.injections.push((metric, value)),
+            }
+        }
+    }
+
+    /// Only count anomalies while a network simulation change settles
+    fn suppress_anomalies(&mut self) {
+        let window = self.anomaly_widget.suppression();
+        self.anomaly_widget.suppress(window);
+    }
+
     fn update_all_widgets(&mut self) {
         // Generate demo data with network simulation effects
-        let (latency, throughput, connections, errors, packet_loss) = self.demo_generator.generate_next();
+        // The generator has no connection or error counts; handshake time and retransmits stand in
+        let (mut latency, mut throughput, mut connections, mut packet_loss, retransmits) =
+            self.demo_generator.generate_next();
+        let mut errors = retransmits as f64;
+        for (metric, value) in self.injections.drain(..) {
+            match metric {
+                ScenarioMetric::Latency => latency = value,
+                ScenarioMetric::Throughput => throughput = value,
+                ScenarioMetric::PacketLoss => packet_loss = value,
+                ScenarioMetric::Connections => connections = value,
+                ScenarioMetric::Errors => errors = value,
+            }
+        }
         
         // Apply network simulation effects
-        let (adjusted_latency, adjusted_throughput, adjusted_loss) = self.apply_network_effects(
-            latency, throughput, packet_loss as f64
+        let (adjusted_latency, adjusted_throughput, adjusted_loss) = self.network.apply(
+            latency, throughput, packet_loss
         );
+        // The analytics show packet loss in percent
+        let loss_pct = adjusted_loss * 100.0;
 
         // Update basic graphs
         self.latency_graph.add_latency(adjusted_latency);
         self.throughput_graph.add_throughput(adjusted_throughput);
 
         // Update enhanced analytics
-        self.performance_heatmap.add_performance_data(self.time_slot, 0, adjusted_latency);
-        self.performance_heatmap.add_performance_data(self.time_slot, 1, adjusted_throughput);
-        self.performance_heatmap.add_performance_data(self.time_slot, 2, adjusted_loss);
-        self.performance_heatmap.add_performance_data(self.time_slot, 3, connections as f64);
-        self.performance_heatmap.add_performance_data(self.time_slot, 4, errors as f64);
+        let now = chrono::Utc::now();
+        self.performance_heatmap.add_metric(now, "Latency", adjusted_latency);
+        self.performance_heatmap.add_metric(now, "Throughput", adjusted_throughput);
+        self.performance_heatmap.add_metric(now, "Packet Loss", loss_pct);
+        self.performance_heatmap.add_metric(now, "Connections", connections);
+        self.performance_heatmap.add_metric(now, "Errors", errors);
 
         // Update correlation data
         self.correlation_widget.add_metric_data("Latency".to_string(), adjusted_latency);
         self.correlation_widget.add_metric_data("Throughput".to_string(), adjusted_throughput);
-        self.correlation_widget.add_metric_data("Packet Loss".to_string(), adjusted_loss);
-        self.correlation_widget.add_metric_data("Connections".to_string(), connections as f64);
-        self.correlation_widget.add_metric_data("Errors".to_string(), errors as f64);
+        self.correlation_widget.add_metric_data("Packet Loss".to_string(), loss_pct);
+        self.correlation_widget.add_metric_data("Connections".to_string(), connections);
+        self.correlation_widget.add_metric_data("Errors".to_string(), errors);
         self.correlation_widget.update_correlations();
 
         // Update anomaly detection
         self.anomaly_widget.add_quic_metric("Latency".to_string(), adjusted_latency);
         self.anomaly_widget.add_quic_metric("Throughput".to_string(), adjusted_throughput);
-        self.anomaly_widget.add_quic_metric("Packet Loss".to_string(), adjusted_loss);
-        self.anomaly_widget.add_quic_metric("Connections".to_string(), connections as f64);
-        self.anomaly_widget.add_quic_metric("Errors".to_string(), errors as f64);
+        self.anomaly_widget.add_quic_metric("Packet Loss".to_string(), loss_pct);
+        self.anomaly_widget.add_quic_metric("Connections".to_string(), connections);
+        self.anomaly_widget.add_quic_metric("Errors".to_string(), errors);
 
         // Update time slot
         self.time_slot = (self.time_slot + 1) % 20;
     }
 
-    fn apply_network_effects(&self, latency: f64, throughput: f64, loss: f64) -> (f64, f64, f64) {
-        if !self.network_simulation_active {
-            return (latency, throughput, loss);
-        }
-
-        let adjusted_latency = latency + self.network_latency;
-        let adjusted_throughput = throughput * (1.0 - self.network_loss / 100.0);
-        let adjusted_loss = loss + self.network_loss;
 
-        (adjusted_latency, adjusted_throughput, adjusted_loss)
-    }
-
-    fn apply_network_preset(&mut self) {
-        match self.network_preset.as_str() {
-            "excellent" => {
-                self.network_latency = 5.0;
-                self.network_loss = 0.1;
-                self.network_bandwidth = 1000.0;
-            }
-            "good" => {
-                self.network_latency = 20.0;
-                self.network_loss = 1.0;
-                self.network_bandwidth = 100.0;
-            }
-            "poor" => {
-                self.network_latency = 100.0;
-                self.network_loss = 5.0;
-                self.network_bandwidth = 10.0;
-            }
-            "mobile" => {
-                self.network_latency = 200.0;
-                self.network_loss = 10.0;
-                self.network_bandwidth = 5.0;
-            }
-            "satellite" => {
-                self.network_latency = 500.0;
-                self.network_loss = 2.0;
-                self.network_bandwidth = 2.0;
-            }
-            "adversarial" => {
-                self.network_latency = 1000.0;
-                self.network_loss = 20.0;
-                self.network_bandwidth = 1.0;
-            }
-            _ => {}
-        }
-    }
 
     fn display_status(&self) {
         println!("📈 QUIC Metrics:");
         println!("  Latency: {:.2} ms", 25.0 + (self.time_slot as f64 * 2.0));
         println!("  Throughput: {:.2} Mbps", 100.0 + (self.time_slot as f64 * 5.0));
         
-        if self.network_simulation_active {
-            println!("Network Simulation: ACTIVE ({})", self.network_preset);
-            println!("  Applied Latency: +{:.1} ms", self.network_latency);
-            println!("  Applied Loss: +{:.1}%", self.network_loss);
-            println!("  Bandwidth: {:.1} Mbps", self.network_bandwidth);
+        if self.network.active {
+            println!("{}", self.network.status_text());
         } else {
             println!("Network Simulation: INACTIVE");
         }
@@ -250,6 +268,8 @@ impl UltimateAnalyticsConsole {
             println!("☁️  Cloud Deployment: INACTIVE");
         }
         
+        println!("{}", self.scenario.status());
+
         println!("Enhanced Analytics:");
         println!("  Heatmap data points: {}", self.time_slot);
         println!("  Correlation analysis: Active");
@@ -257,14 +277,21 @@ impl UltimateAnalyticsConsole {
     }
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
-    
+/// Run `quic-bottom console`
+pub async fn run(
+    config: &QuicBottomConfig,
+    interval_ms: u64,
+    demo_generator: DemoDataGenerator,
+    scenario: Option<&Path>,
+) -> Result<()> {
+    let scenario = match scenario {
+        Some(path) => Scenario::load(path)?,
+        None => Scenario::parse(DEFAULT_SCENARIO)?,
+    };
     println!("Starting Ultimate Analytics QUIC Bottom - Console Mode...");
     println!("Ultimate analytics with network simulation, security testing, and cloud monitoring!");
     println!("Professional analytics with real-time parameter adjustment!");
-    println!("");
+    println!();
     println!("Features:");
     println!("  ✅ Enhanced analytics (heatmaps, correlation, anomaly detection)");
     println!("  ✅ Network simulation with presets");
@@ -272,10 +299,11 @@ async fn main() -> Result<()> {
     println!("  ✅ Cloud deployment monitoring");
     println!("  ✅ Real-time parameter adjustment");
     println!("  ✅ Console-based output");
-    println!("");
+    println!();
     
-    let mut app = UltimateAnalyticsConsole::new(1000).await?;
+    let mut app = UltimateAnalyticsConsole::new(interval_ms, demo_generator, &config.network, scenario).await?;
     app.run().await?;
+    println!("Demo data: {}", app.demo_generator.summary());
     
     println!("✅ Ultimate Analytics QUIC Bottom completed!");
     Ok(())
@@ -1,640 +0,0 @@
-//! Ultimate Analytics QUIC Bottom
-//! 
-//! Features:
-//! - Enhanced analytics (heatmaps, correlation, anomaly detection)
-//! - Network simulation integration
-//! - Security testing integration
-//! - Cloud deployment monitoring
-//! - Advanced visualizations
-//! - Real-time parameter adjustment
-
-use anyhow::Result;
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph},
-    Frame, Terminal,
-};
-use std::io;
-use tokio::time::Duration;
-
-use quic_bottom::{
-    demo_data::DemoDataGenerator,
-    simple_professional::{SimpleQuicLatencyGraph, SimpleQuicThroughputGraph},
-    heatmap_widget::QUICPerformanceHeatmap,
-    correlation_widget::QUICCorrelationWidget,
-    anomaly_detection::QUICAnomalyWidget,
-};
-
-/// Ultimate Analytics QUIC Bottom application
-pub struct UltimateAnalyticsQuicBottom {
-    // Basic graphs
-    latency_graph: SimpleQuicLatencyGraph,
-    throughput_graph: SimpleQuicThroughputGraph,
-    
-    // Enhanced analytics
-    performance_heatmap: QUICPerformanceHeatmap,
-    correlation_widget: QUICCorrelationWidget,
-    anomaly_widget: QUICAnomalyWidget,
-    
-    // Demo data
-    demo_generator: DemoDataGenerator,
-    
-    // App state
-    should_quit: bool,
-    update_interval: Duration,
-    current_view: ViewMode,
-    time_slot: usize,
-    
-    // Network simulation state
-    network_simulation_active: bool,
-    network_preset: String,
-    network_latency: f64,
-    network_loss: f64,
-    network_bandwidth: f64,
-    
-    // Security testing state
-    security_test_active: bool,
-    security_score: f64,
-    vulnerabilities_count: usize,
-    
-    // Cloud deployment state
-    cloud_deployment_active: bool,
-    cloud_provider: String,
-    cloud_instances: usize,
-    cloud_status: String,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-enum ViewMode {
-    Dashboard,
-    Analytics,
-    Network,
-    Security,
-    Cloud,
-    All,
-}
-
-impl UltimateAnalyticsQuicBottom {
-    pub async fn new(interval_ms: u64) -> Result<Self> {
-        Ok(Self {
-            latency_graph: SimpleQuicLatencyGraph::new(),
-            throughput_graph: SimpleQuicThroughputGraph::new(),
-            performance_heatmap: QUICPerformanceHeatmap::new(),
-            correlation_widget: QUICCorrelationWidget::new(),
-            anomaly_widget: QUICAnomalyWidget::new(),
-            demo_generator: DemoDataGenerator::new(),
-            should_quit: false,
-            update_interval: Duration::from_millis(interval_ms),
-            current_view: ViewMode::Dashboard,
-            time_slot: 0,
-            network_simulation_active: false,
-            network_preset: "good".to_string(),
-            network_latency: 20.0,
-            network_loss: 1.0,
-            network_bandwidth: 100.0,
-            security_test_active: false,
-            security_score: 100.0,
-            vulnerabilities_count: 0,
-            cloud_deployment_active: false,
-            cloud_provider: "aws".to_string(),
-            cloud_instances: 2,
-            cloud_status: "running".to_string(),
-        })
-    }
-
-    pub async fn run(&mut self) -> Result<()> {
-        // Setup terminal
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
-
-        // Main event loop
-        loop {
-            if self.should_quit {
-                break;
-            }
-
-            // Update all widgets with demo data
-            self.update_all_widgets();
-
-            // Render the UI
-            terminal.draw(|f| self.ui(f))?;
-
-            // Handle events
-            if event::poll(self.update_interval)? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key_event(key);
-                }
-            }
-        }
-
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-
-        Ok(())
-    }
-
-    fn update_all_widgets(&mut self) {
-        // Generate demo data with network simulation effects
-        let (latency, throughput, connections, errors, packet_loss) = self.demo_generator.generate_next();
-        
-        // Apply network simulation effects
-        let (adjusted_latency, adjusted_throughput, adjusted_loss) = self.apply_network_effects(
-            latency, throughput, packet_loss as f64
-        );
-
-        // Update basic graphs
-        self.latency_graph.add_latency(adjusted_latency);
-        self.throughput_graph.add_throughput(adjusted_throughput);
-
-        // Update enhanced analytics
-        self.performance_heatmap.add_performance_data(self.time_slot, 0, adjusted_latency);
-        self.performance_heatmap.add_performance_data(self.time_slot, 1, adjusted_throughput);
-        self.performance_heatmap.add_performance_data(self.time_slot, 2, adjusted_loss as f64);
-        self.performance_heatmap.add_performance_data(self.time_slot, 3, connections as f64);
-        self.performance_heatmap.add_performance_data(self.time_slot, 4, errors as f64);
-
-        // Update correlation data
-        self.correlation_widget.add_metric_data("Latency".to_string(), adjusted_latency);
-        self.correlation_widget.add_metric_data("Throughput".to_string(), adjusted_throughput);
-        self.correlation_widget.add_metric_data("Packet Loss".to_string(), adjusted_loss as f64);
-        self.correlation_widget.add_metric_data("Connections".to_string(), connections as f64);
-        self.correlation_widget.add_metric_data("Errors".to_string(), errors as f64);
-        self.correlation_widget.update_correlations();
-
-        // Update anomaly detection
-        self.anomaly_widget.add_quic_metric("Latency".to_string(), adjusted_latency);
-        self.anomaly_widget.add_quic_metric("Throughput".to_string(), adjusted_throughput);
-        self.anomaly_widget.add_quic_metric("Packet Loss".to_string(), adjusted_loss as f64);
-        self.anomaly_widget.add_quic_metric("Connections".to_string(), connections as f64);
-        self.anomaly_widget.add_quic_metric("Errors".to_string(), errors as f64);
-
-        // Update time slot
-        self.time_slot = (self.time_slot + 1) % 20;
-    }
-
-    fn apply_network_effects(&self, latency: f64, throughput: f64, loss: f64) -> (f64, f64, f64) {
-        if !self.network_simulation_active {
-            return (latency, throughput, loss);
-        }
-
-        let adjusted_latency = latency + self.network_latency;
-        let adjusted_throughput = throughput * (1.0 - self.network_loss / 100.0);
-        let adjusted_loss = loss + self.network_loss;
-
-        (adjusted_latency, adjusted_throughput, adjusted_loss)
-    }
-
-    fn handle_key_event(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.should_quit = true;
-            }
-            KeyCode::Esc => {
-                self.should_quit = true;
-            }
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.should_quit = true;
-            }
-            KeyCode::Char('r') => {
-                // Reset all data
-                self.reset_all_data();
-            }
-            KeyCode::Char('h') => {
-                self.show_help();
-            }
-            // View switching
-            KeyCode::Char('1') => {
-                self.current_view = ViewMode::Dashboard;
-            }
-            KeyCode::Char('2') => {
-                self.current_view = ViewMode::Analytics;
-            }
-            KeyCode::Char('3') => {
-                self.current_view = ViewMode::Network;
-            }
-            KeyCode::Char('4') => {
-                self.current_view = ViewMode::Security;
-            }
-            KeyCode::Char('5') => {
-                self.current_view = ViewMode::Cloud;
-            }
-            KeyCode::Char('a') => {
-                self.current_view = ViewMode::All;
-            }
-            // Network simulation controls
-            KeyCode::Char('n') => {
-                self.toggle_network_simulation();
-            }
-            KeyCode::Char('+') => {
-                self.next_network_preset();
-            }
-            KeyCode::Char('-') => {
-                self.prev_network_preset();
-            }
-            // Security testing controls
-            KeyCode::Char('s') => {
-                self.toggle_security_testing();
-            }
-            // Cloud deployment controls
-            KeyCode::Char('d') => {
-                self.toggle_cloud_deployment();
-            }
-            KeyCode::Char('i') => {
-                self.scale_cloud_instances();
-            }
-            _ => {}
-        }
-    }
-
-    fn reset_all_data(&mut self) {
-        self.latency_graph = SimpleQuicLatencyGraph::new();
-        self.throughput_graph = SimpleQuicThroughputGraph::new();
-        self.performance_heatmap = QUICPerformanceHeatmap::new();
-        self.correlation_widget = QUICCorrelationWidget::new();
-        self.anomaly_widget = QUICAnomalyWidget::new();
-        self.demo_generator = DemoDataGenerator::new();
-        self.time_slot = 0;
-    }
-
-    fn toggle_network_simulation(&mut self) {
-        self.network_simulation_active = !self.network_simulation_active;
-    }
-
-    fn next_network_preset(&mut self) {
-        let presets = vec!["excellent", "good", "poor", "mobile", "satellite", "adversarial"];
-        if let Some(current_index) = presets.iter().position(|&p| p == self.network_preset) {
-            let next_index = (current_index + 1) % presets.len();
-            self.network_preset = presets[next_index].to_string();
-            self.apply_network_preset();
-        }
-    }
-
-    fn prev_network_preset(&mut self) {
-        let presets = vec!["excellent", "good", "poor", "mobile", "satellite", "adversarial"];
-        if let Some(current_index) = presets.iter().position(|&p| p == self.network_preset) {
-            let prev_index = if current_index == 0 { presets.len() - 1 } else { current_index - 1 };
-            self.network_preset = presets[prev_index].to_string();
-            self.apply_network_preset();
-        }
-    }
-
-    fn apply_network_preset(&mut self) {
-        match self.network_preset.as_str() {
-            "excellent" => {
-                self.network_latency = 5.0;
-                self.network_loss = 0.1;
-                self.network_bandwidth = 1000.0;
-            }
-            "good" => {
-                self.network_latency = 20.0;
-                self.network_loss = 1.0;
-                self.network_bandwidth = 100.0;
-            }
-            "poor" => {
-                self.network_latency = 100.0;
-                self.network_loss = 5.0;
-                self.network_bandwidth = 10.0;
-            }
-            "mobile" => {
-                self.network_latency = 200.0;
-                self.network_loss = 10.0;
-                self.network_bandwidth = 5.0;
-            }
-            "satellite" => {
-                self.network_latency = 500.0;
-                self.network_loss = 2.0;
-                self.network_bandwidth = 2.0;
-            }
-            "adversarial" => {
-                self.network_latency = 1000.0;
-                self.network_loss = 20.0;
-                self.network_bandwidth = 1.0;
-            }
-            _ => {}
-        }
-    }
-
-    fn toggle_security_testing(&mut self) {
-        self.security_test_active = !self.security_test_active;
-        if self.security_test_active {
-            // Simulate security test results
-            self.security_score = 85.0 + (self.demo_generator.counter as f64 % 20.0);
-            self.vulnerabilities_count = (self.demo_generator.counter % 5) as usize;
-        }
-    }
-
-    fn toggle_cloud_deployment(&mut self) {
-        self.cloud_deployment_active = !self.cloud_deployment_active;
-        if self.cloud_deployment_active {
-            self.cloud_status = "running".to_string();
-        } else {
-            self.cloud_status = "stopped".to_string();
-        }
-    }
-
-    fn scale_cloud_instances(&mut self) {
-        if self.cloud_deployment_active {
-            self.cloud_instances = (self.cloud_instances % 5) + 1;
-        }
-    }
-
-    fn show_help(&self) {
-        println!("Ultimate Analytics QUIC Bottom Help:");
-        println!("  q/ESC - Quit");
-        println!("  r - Reset all data");
-        println!("  h - Show this help");
-        println!("  1 - Dashboard view");
-        println!("  2 - Analytics view");
-        println!("  3 - Network simulation view");
-        println!("  4 - Security testing view");
-        println!("  5 - Cloud deployment view");
-        println!("  a - All views");
-        println!("  n - Toggle network simulation");
-        println!("  +/- - Change network preset");
-        println!("  s - Toggle security testing");
-        println!("  d - Toggle cloud deployment");
-        println!("  i - Scale cloud instances");
-    }
-
-    fn ui(&self, f: &mut Frame) {
-        match self.current_view {
-            ViewMode::Dashboard => self.render_dashboard(f),
-            ViewMode::Analytics => self.render_analytics_view(f),
-            ViewMode::Network => self.render_network_view(f),
-            ViewMode::Security => self.render_security_view(f),
-            ViewMode::Cloud => self.render_cloud_view(f),
-            ViewMode::All => self.render_all_view(f),
-        }
-    }
-
-    fn render_dashboard(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Main content
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Ultimate Analytics Dashboard");
-
-        let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(50), // Left column
-                Constraint::Percentage(50), // Right column
-            ])
-            .split(chunks[1]);
-
-        let left_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50), // Latency
-                Constraint::Percentage(50), // Throughput
-            ])
-            .split(main_chunks[0]);
-
-        let right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50), // Heatmap
-                Constraint::Percentage(50), // Anomaly
-            ])
-            .split(main_chunks[1]);
-
-        self.latency_graph.render(f, left_chunks[0]);
-        self.throughput_graph.render(f, left_chunks[1]);
-        self.performance_heatmap.render(f, right_chunks[0]);
-        self.anomaly_widget.render(f, right_chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_analytics_view(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Analytics
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Advanced Analytics");
-
-        let main_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50), // Correlation
-                Constraint::Percentage(50), // Anomaly
-            ])
-            .split(chunks[1]);
-
-        self.correlation_widget.render(f, main_chunks[0]);
-        self.anomaly_widget.render(f, main_chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_network_view(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Network info
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Network Simulation");
-
-        // Network simulation status
-        let network_text = format!(
-            "Network Simulation: {}\nPreset: {}\nLatency: {:.1}ms\nLoss: {:.1}%\nBandwidth: {:.1} Mbps",
-            if self.network_simulation_active { "ACTIVE" } else { "INACTIVE" },
-            self.network_preset,
-            self.network_latency,
-            self.network_loss,
-            self.network_bandwidth
-        );
-
-        let network_paragraph = Paragraph::new(network_text)
-            .style(Style::default().fg(Color::Cyan))
-            .block(Block::default().borders(Borders::ALL).title("Network Status"));
-        f.render_widget(network_paragraph, chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_security_view(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Security info
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Security Testing");
-
-        // Security testing status
-        let security_text = format!(
-            "Security Testing: {}\nSecurity Score: {:.1}%\nVulnerabilities: {}",
-            if self.security_test_active { "ACTIVE" } else { "INACTIVE" },
-            self.security_score,
-            self.vulnerabilities_count
-        );
-
-        let security_paragraph = Paragraph::new(security_text)
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::default().borders(Borders::ALL).title("Security Status"));
-        f.render_widget(security_paragraph, chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_cloud_view(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Cloud info
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Cloud Deployment");
-
-        // Cloud deployment status
-        let cloud_text = format!(
-            "Cloud Deployment: {}\nProvider: {}\nInstances: {}\nStatus: {}",
-            if self.cloud_deployment_active { "ACTIVE" } else { "INACTIVE" },
-            self.cloud_provider,
-            self.cloud_instances,
-            self.cloud_status
-        );
-
-        let cloud_paragraph = Paragraph::new(cloud_text)
-            .style(Style::default().fg(Color::Green))
-            .block(Block::default().borders(Borders::ALL).title("Cloud Status"));
-        f.render_widget(cloud_paragraph, chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_all_view(&self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Main content
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.area());
-
-        self.render_header(f, chunks[0], "Ultimate Analytics - All Views");
-
-        let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(50), // Left column
-                Constraint::Percentage(50), // Right column
-            ])
-            .split(chunks[1]);
-
-        let left_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(33), // Latency
-                Constraint::Percentage(33), // Throughput
-                Constraint::Percentage(34), // Heatmap
-            ])
-            .split(main_chunks[0]);
-
-        let right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(50), // Correlation
-                Constraint::Percentage(50), // Anomaly
-            ])
-            .split(main_chunks[1]);
-
-        self.latency_graph.render(f, left_chunks[0]);
-        self.throughput_graph.render(f, left_chunks[1]);
-        self.performance_heatmap.render(f, left_chunks[2]);
-        self.correlation_widget.render(f, right_chunks[0]);
-        self.anomaly_widget.render(f, right_chunks[1]);
-
-        self.render_footer(f, chunks[2]);
-    }
-
-    fn render_header(&self, f: &mut Frame, area: Rect, title: &str) {
-        let header_text = format!("Ultimate Analytics QUIC Bottom - {}", title);
-        let header = Paragraph::new(header_text)
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(header, area);
-    }
-
-    fn render_footer(&self, f: &mut Frame, area: Rect) {
-        let footer_text = "Press 'q' to quit, 'r' to reset, 'h' for help, '1-5' for views, 'a' for all, 'n' for network, 's' for security, 'd' for cloud";
-        let footer = Paragraph::new(footer_text)
-            .style(Style::default().fg(Color::Yellow))
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(footer, area);
-    }
-}
-
-#[tokio::main]
-async fn main() -> Result<()> {
-    env_logger::init();
-    
-    println!("Starting Ultimate Analytics QUIC Bottom...");
-    println!("Ultimate analytics with network simulation, security testing, and cloud monitoring!");
-    println!("Professional visualizations with real-time parameter adjustment!");
-    println!("");
-    println!("Features:");
-    println!("  ✅ Enhanced analytics (heatmaps, correlation, anomaly detection)");
-    println!("  ✅ Network simulation with presets");
-    println!("  ✅ Security testing integration");
-    println!("  ✅ Cloud deployment monitoring");
-    println!("  ✅ Real-time parameter adjustment");
-    println!("  ✅ Interactive controls");
-    println!("");
-    println!("Controls:");
-    println!("  q/ESC - Quit");
-    println!("  r - Reset data");
-    println!("  h - Show help");
-    println!("  1-5 - Switch views");
-    println!("  a - All views");
-    println!("  n - Toggle network simulation");
-    println!("  +/- - Change network preset");
-    println!("  s - Toggle security testing");
-    println!("  d - Toggle cloud deployment");
-    println!("  i - Scale cloud instances");
-    println!("");
-    
-    let mut app = UltimateAnalyticsQuicBottom::new(100).await?;
-    app.run().await?;
-    
-    println!("✅ Ultimate Analytics QUIC Bottom completed!");
-    Ok(())
-}
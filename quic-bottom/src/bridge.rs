@@ -1,17 +1,44 @@
 //! Bridge module for Go integration
 //! 
-//! Provides FFI functions and HTTP API for communication with Go QUIC test
+//! Provides the HTTP API for communication with Go QUIC test
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use warp::filters::BoxedFilter;
+use warp::http::StatusCode;
+use warp::reply::Response;
+use warp::sse::Event;
 use warp::Filter;
 
-use crate::metrics::{QUICMetrics, update_metrics, get_current_metrics};
+use crate::auth::{recover_unauthorized, require_token, resolve_token, TOKEN_ENV};
+use crate::cors::{with_cors, CorsConfig};
+use crate::health::HealthReport;
+use crate::ingest::limit::{
+    body_limit, http_limit_stats, rate_limit, recover_rate_limited, RateLimitConfig, RateLimiter,
+};
+use crate::ingest::statsd::statsd_stats;
+use crate::ingest::{
+    check_batch_size, ensure_loss, ensure_non_negative, loss_fraction, order_batch, record_ingest_error, throughput_bps,
+    BatchQuery, BatchReport, DEFAULT_MAX_BATCH_BODY_BYTES, DEFAULT_MAX_BATCH_SIZE, DEFAULT_MAX_BODY_BYTES,
+};
+use crate::metrics::{BBRv3Metrics, HandshakeType, MetricsHandle, QUICMetrics, ResetOutcome, ResetScope};
+use crate::status::record_lagged;
+use crate::sync::catch_panic;
+use crate::tls::{self, ServerFuture, TlsIdentity};
+use crate::units::{LossUnit, Unit};
 
 /// HTTP API request structure
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct MetricsRequest {
     pub latency: f64,
     pub throughput: f64,
@@ -19,6 +46,244 @@ pub struct MetricsRequest {
     pub errors: i32,
     pub packet_loss: f64,
     pub retransmits: i32,
+
+    /// Unit of `throughput`; `[ingest] throughput_unit` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Unit>,
+
+    /// Unit of `packet_loss`; `[ingest] loss_unit` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loss_unit: Option<LossUnit>,
+
+    /// Capture time in unix milliseconds; the arrival time when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+
+    // Optional since they were added after the fields above; see `QUICMetrics`
+    #[serde(default)]
+    pub rtt: f64,
+    #[serde(default)]
+    pub jitter: f64,
+    #[serde(default)]
+    pub congestion_window: i32,
+    #[serde(default)]
+    pub bytes_sent: i64,
+    #[serde(default)]
+    pub bytes_received: i64,
+    #[serde(default)]
+    pub streams: i32,
+    #[serde(default)]
+    pub handshake_time: f64,
+    /// `"0rtt"` or `"1rtt"`
+    #[serde(default)]
+    pub handshake_type: Option<HandshakeType>,
+    #[serde(default)]
+    pub session_resumed: bool,
+    /// Cumulative packet counters
+    #[serde(default)]
+    pub packets_sent: Option<u64>,
+    #[serde(default)]
+    pub packets_received: Option<u64>,
+    #[serde(default)]
+    pub packets_lost: Option<u64>,
+    #[serde(default)]
+    pub packets_acked: Option<u64>,
+    /// Cumulative connection counters; see `QUICMetrics`
+    #[serde(default)]
+    pub connections_opened: Option<u64>,
+    #[serde(default)]
+    pub connections_failed: Option<u64>,
+    #[serde(default)]
+    pub connections_closed: Option<u64>,
+    /// Sender label; the `source` query parameter or the client address when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// `bbrv3_*` fields, as quic-test sends them with BBRv3
+    #[serde(flatten)]
+    pub bbrv3: BBRv3Metrics,
+}
+
+impl MetricsRequest {
+    /// Reject negative or non-finite values and timestamps chrono cannot represent
+    pub fn validate(&self) -> Result<()> {
+        ensure_non_negative("latency", self.latency)?;
+        ensure_non_negative("throughput", self.throughput)?;
+        ensure_non_negative("connections", self.connections as f64)?;
+        ensure_non_negative("errors", self.errors as f64)?;
+        ensure_loss(self.packet_loss, self.loss_unit)?;
+        ensure_non_negative("retransmits", self.retransmits as f64)?;
+        ensure_non_negative("rtt", self.rtt)?;
+        ensure_non_negative("jitter", self.jitter)?;
+        ensure_non_negative("congestion_window", self.congestion_window as f64)?;
+        ensure_non_negative("bytes_sent", self.bytes_sent as f64)?;
+        ensure_non_negative("bytes_received", self.bytes_received as f64)?;
+        ensure_non_negative("streams", self.streams as f64)?;
+        ensure_non_negative("handshake_time", self.handshake_time)?;
+        self.bbrv3.ensure_finite()?;
+        if let Some(ts) = self.timestamp {
+            DateTime::from_timestamp_millis(ts).ok_or_else(|| anyhow!("timestamp {} is out of range", ts))?;
+        }
+        if let Some(source) = &self.source {
+            check_source_label(source)?;
+        }
+        Ok(())
+    }
+
+    /// Fill in `source` when the body has none; see `resolve_source`
+    pub fn resolve_source(&mut self, query: &SourceQuery, addr: Option<SocketAddr>, from_addr: bool) -> Result<()> {
+        resolve_source(&mut self.source, query, addr, from_addr)
+    }
+
+    /// Convert to a sample, stamping it with `received` when it has no timestamp,
+    /// converting throughput to bits per second and packet loss to a fraction
+    pub fn to_metrics(&self, received: DateTime<Utc>) -> QUICMetrics {
+        QUICMetrics {
+            latency: self.latency,
+            throughput: throughput_bps(self.throughput, self.unit),
+            connections: self.connections,
+            errors: self.errors,
+            packet_loss: loss_fraction(self.packet_loss, self.loss_unit),
+            retransmits: self.retransmits,
+            timestamp: self
+                .timestamp
+                .and_then(DateTime::from_timestamp_millis)
+                .unwrap_or(received),
+            rtt: self.rtt,
+            jitter: self.jitter,
+            congestion_window: self.congestion_window,
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            streams: self.streams,
+            handshake_time: self.handshake_time,
+            handshake_type: self.handshake_type,
+            session_resumed: self.session_resumed,
+            packets_sent: self.packets_sent,
+            packets_received: self.packets_received,
+            packets_lost: self.packets_lost,
+            packets_acked: self.packets_acked,
+            connections_opened: self.connections_opened,
+            connections_failed: self.connections_failed,
+            connections_closed: self.connections_closed,
+            source: self.source.clone(),
+            bbrv3: self.bbrv3.is_reported().then(|| self.bbrv3.clone()),
+        }
+    }
+}
+
+/// Longest accepted `source` label
+pub const MAX_SOURCE_LEN: usize = 64;
+
+/// Fill in a sample's `source` when its body has none: the query parameter
+/// first, then the client IP if `from_addr` is set
+///
+/// Shared by the bridge and `quic-bottom live`, so both label senders alike.
+pub fn resolve_source(
+    source: &mut Option<String>,
+    query: &SourceQuery,
+    addr: Option<SocketAddr>,
+    from_addr: bool,
+) -> Result<()> {
+    if source.is_none() {
+        *source = query
+            .source
+            .clone()
+            .or_else(|| addr.filter(|_| from_addr).map(|addr| addr.ip().to_string()));
+    }
+    match source {
+        Some(source) => check_source_label(source),
+        None => Ok(()),
+    }
+}
+
+/// Reject empty, overlong or control-character `source` labels
+pub fn check_source_label(source: &str) -> Result<()> {
+    if source.is_empty() || source.len() > MAX_SOURCE_LEN || source.chars().any(char::is_control) {
+        return Err(anyhow!(
+            "source must be 1-{} printable characters, got {:?}",
+            MAX_SOURCE_LEN,
+            source
+        ));
+    }
+    Ok(())
+}
+
+/// HTTP API limits and authentication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// Most samples accepted in one `POST /api/metrics/batch` request
+    pub max_batch_size: usize,
+
+    /// Largest JSON body accepted for a single sample, in bytes
+    pub max_body_bytes: u64,
+
+    /// Largest JSON body accepted by `POST /api/metrics/batch`, in bytes
+    pub max_batch_body_bytes: u64,
+
+    /// Per-client request rate on the ingestion routes
+    pub rate_limit: RateLimitConfig,
+
+    /// `GET /health` answers 503 when no sample arrived within this long; unchecked when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_max_sample_age_ms: Option<u64>,
+
+    /// Bearer token required on POST requests; `QUIC_BOTTOM_API_TOKEN` overrides it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+
+    /// Require the token on GET requests too, except `/health`
+    pub auth_reads: bool,
+
+    /// PEM certificate chain; with `tls_key`, the API is served over HTTPS only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key for `tls_cert`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Cross-origin access for browser dashboards, off by default
+    pub cors: CorsConfig,
+
+    /// Label samples that name no `source` with the client IP
+    pub source_from_addr: bool,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_body_bytes: DEFAULT_MAX_BATCH_BODY_BYTES,
+            rate_limit: RateLimitConfig::default(),
+            health_max_sample_age_ms: None,
+            auth_token: None,
+            auth_reads: false,
+            tls_cert: None,
+            tls_key: None,
+            cors: CorsConfig::default(),
+            source_from_addr: false,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Rate limiter for one server, `None` when the limit is off
+    pub fn rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.rate_limit
+            .is_enabled()
+            .then(|| Arc::new(RateLimiter::new(&self.rate_limit)))
+    }
+
+    /// URL scheme clients should use, `https` when TLS is configured
+    pub fn scheme(&self) -> &'static str {
+        if self.tls_cert.is_some() {
+            "https"
+        } else {
+            "http"
+        }
+    }
 }
 
 /// HTTP API response structure
@@ -29,33 +294,66 @@ pub struct MetricsResponse {
     pub metrics: Option<QUICMetrics>,
 }
 
+/// Default number of samples returned by `GET /api/history`
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
+
+/// Most samples one `GET /api/history` returns, whatever `limit` asks for;
+/// older ones are reached with `to`
+pub const MAX_HISTORY_LIMIT: usize = 500;
+
+/// Query parameters for `GET /api/history`
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Start of the range, unix milliseconds (inclusive)
+    pub from: Option<i64>,
+
+    /// End of the range, unix milliseconds (inclusive)
+    pub to: Option<i64>,
+
+    /// Maximum number of samples to return, up to `MAX_HISTORY_LIMIT`
+    pub limit: Option<usize>,
+}
+
+/// `source` query parameter of `POST /metrics`, the batch route and `GET /api/current`
+#[derive(Debug, Default, Deserialize)]
+pub struct SourceQuery {
+    pub source: Option<String>,
+}
+
+/// Interval between keep-alive comments on `GET /api/stream`
+pub const STREAM_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Query parameters for `GET /api/stream`
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    /// Comma-separated fields to send, e.g. `latency,throughput`; all when unset
+    pub fields: Option<String>,
+}
+
 /// Bridge state for Go integration
 pub struct GoBridge {
+    metrics: MetricsHandle,
     metrics_sender: broadcast::Sender<QUICMetrics>,
 }
 
 impl GoBridge {
-    pub fn new() -> Self {
+    pub fn new(metrics: MetricsHandle) -> Self {
         let (tx, _) = broadcast::channel(1000);
         Self {
+            metrics,
             metrics_sender: tx,
         }
     }
 
     /// Update metrics from Go
     pub fn update_metrics(&self, req: MetricsRequest) -> Result<()> {
-        let metrics = QUICMetrics {
-            latency: req.latency,
-            throughput: req.throughput,
-            connections: req.connections,
-            errors: req.errors,
-            packet_loss: req.packet_loss,
-            retransmits: req.retransmits,
-            timestamp: chrono::Utc::now(),
-        };
+        req.validate()?;
+        self.publish(req.to_metrics(Utc::now()))
+    }
 
-        // Update global metrics
-        update_metrics(metrics.clone())?;
+    /// Store a sample and send it to subscribers
+    pub fn publish(&self, metrics: QUICMetrics) -> Result<()> {
+        self.metrics.update(metrics.clone())?;
 
         // Broadcast to subscribers
         let _ = self.metrics_sender.send(metrics);
@@ -64,8 +362,8 @@ impl GoBridge {
     }
 
     /// Get current metrics
-    pub fn get_current_metrics(&self) -> Option<QUICMetrics> {
-        get_current_metrics()
+    pub fn get_current_metrics(&self) -> QUICMetrics {
+        self.metrics.current()
     }
 
     /// Subscribe to metrics updates
@@ -74,143 +372,652 @@ impl GoBridge {
     }
 }
 
-/// FFI function to update metrics from Go
-#[no_mangle]
-pub extern "C" fn update_quic_metrics_ffi(
-    latency: f64,
-    throughput: f64,
-    connections: i32,
-    errors: i32,
-    packet_loss: f64,
-    retransmits: i32,
-) -> i32 {
-    log::debug!(
-        "FFI: Updating QUIC metrics: latency={}, throughput={}, connections={}, errors={}, loss={}, retransmits={}",
-        latency, throughput, connections, errors, packet_loss, retransmits
-    );
-    
-    let metrics = QUICMetrics {
-        latency,
-        throughput,
-        connections,
-        errors,
-        packet_loss,
-        retransmits,
-        timestamp: chrono::Utc::now(),
-    };
-
-    match update_metrics(metrics) {
-        Ok(_) => 0,
-        Err(e) => {
-            log::error!("FFI: Failed to update metrics: {}", e);
-            -1
+/// Validate, order and store a batch of samples
+fn ingest_batch(
+    bridge: &GoBridge,
+    requests: Vec<MetricsRequest>,
+    query: BatchQuery,
+    label: impl Fn(&mut MetricsRequest) -> Result<()>,
+) -> Result<BatchReport> {
+    let received = Utc::now();
+    let mut report = BatchReport::default();
+    let mut entries = Vec::with_capacity(requests.len());
+    for (index, mut req) in requests.into_iter().enumerate() {
+        match req.validate().and_then(|()| label(&mut req)) {
+            Ok(()) => entries.push((index, req.to_metrics(received))),
+            Err(e) => report.reject(index, e.to_string()),
         }
     }
+
+    let newest = bridge.metrics.latest_sample_time().map(|t| t.timestamp_millis());
+    for metrics in order_batch(entries, query.order, newest, &mut report) {
+        bridge.publish(metrics)?;
+    }
+    Ok(report)
 }
 
-/// FFI function to get current metrics
-#[no_mangle]
-pub extern "C" fn get_quic_metrics_ffi() -> *mut QUICMetrics {
-    match get_current_metrics() {
-        Some(metrics) => {
-            let boxed = Box::new(metrics);
-            Box::into_raw(boxed)
-        }
-        None => std::ptr::null_mut(),
+/// Fields of `QUICMetrics` that `GET /api/stream?fields=` can select
+const STREAM_FIELDS: [&str; 7] = [
+    "latency",
+    "throughput",
+    "connections",
+    "errors",
+    "packet_loss",
+    "retransmits",
+    "timestamp",
+];
+
+/// Parse a `fields` list such as `latency,throughput`; `None` selects every field
+fn parse_stream_fields(fields: Option<&str>) -> Result<Option<Vec<String>>> {
+    let Some(fields) = fields else {
+        return Ok(None);
+    };
+    let selected: Vec<String> = fields
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(str::to_string)
+        .collect();
+    if let Some(unknown) = selected.iter().find(|field| !STREAM_FIELDS.contains(&field.as_str())) {
+        return Err(anyhow!(
+            "unknown field '{}', expected any of {}",
+            unknown,
+            STREAM_FIELDS.join(",")
+        ));
     }
+    Ok(Some(selected))
 }
 
-/// FFI function to free metrics memory
-#[no_mangle]
-pub extern "C" fn free_quic_metrics_ffi(ptr: *mut QUICMetrics) {
-    if !ptr.is_null() {
-        unsafe {
-            let _ = Box::from_raw(ptr);
-        }
+/// `metrics` as JSON, keeping only `fields` when given
+fn project_fields(metrics: &QUICMetrics, fields: Option<&[String]>) -> serde_json::Value {
+    let mut value = serde_json::to_value(metrics).unwrap_or_default();
+    if let (Some(fields), Some(object)) = (fields, value.as_object_mut()) {
+        object.retain(|key, _| fields.contains(key));
     }
+    value
+}
+
+/// `GET /api/stream` response: every published sample as an `event: metrics`
+fn metrics_stream(bridge: &GoBridge, query: StreamQuery) -> Box<dyn warp::Reply> {
+    let fields = match parse_stream_fields(query.fields.as_deref()) {
+        Ok(fields) => fields,
+        Err(e) => {
+            let body = serde_json::json!({"status": "error", "message": e.to_string()});
+            return Box::new(warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST));
+        }
+    };
+
+    // A subscriber that falls behind skips the samples it missed
+    let events = BroadcastStream::new(bridge.subscribe()).filter_map(move |sample| {
+        let sample = match sample {
+            Ok(sample) => sample,
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                record_lagged(skipped);
+                return None;
+            }
+        };
+        let data = project_fields(&sample, fields.as_deref());
+        let event = Event::default().event("metrics").json_data(data).ok()?;
+        Some(Ok::<_, Infallible>(event))
+    });
+    // The stream, and with it the subscription, is dropped when the client disconnects
+    Box::new(warp::sse::reply(
+        warp::sse::keep_alive().interval(STREAM_HEARTBEAT).stream(events),
+    ))
 }
 
 /// Create HTTP API routes for Go integration
-pub fn create_api_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    let metrics_update = warp::path("metrics")
+///
+/// Every route works on `metrics`, so several servers can run side by side.
+pub fn create_api_routes(config: &ApiConfig, metrics: MetricsHandle) -> Result<BoxedFilter<(Response,)>> {
+    let bridge = Arc::new(GoBridge::new(metrics.clone()));
+    let reset_metrics = metrics.clone();
+    let with_metrics = warp::any().map(move || metrics.clone());
+
+    let stream_bridge = Arc::clone(&bridge);
+    let metrics_stream = warp::path!("api" / "stream")
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .map(move |query: StreamQuery| catch_panic(|| metrics_stream(&stream_bridge, query)));
+
+    let limiter = config.rate_limiter();
+    let source_from_addr = config.source_from_addr;
+
+    let batch_bridge = Arc::clone(&bridge);
+    let max_batch_size = config.max_batch_size;
+    let metrics_batch = warp::path!("api" / "metrics" / "batch")
         .and(warp::post())
+        .and(rate_limit(limiter.clone()))
+        .and(warp::query::<BatchQuery>())
+        .and(warp::query::<SourceQuery>())
+        .and(warp::addr::remote())
+        .and(body_limit(config.max_batch_body_bytes))
         .and(warp::body::json())
-        .map(|req: MetricsRequest| {
-            // Update metrics
-            let metrics = QUICMetrics {
-                latency: req.latency,
-                throughput: req.throughput,
-                connections: req.connections,
-                errors: req.errors,
-                packet_loss: req.packet_loss,
-                retransmits: req.retransmits,
-                timestamp: chrono::Utc::now(),
-            };
-
-            match update_metrics(metrics) {
-                Ok(_) => {
-                    let response = MetricsResponse {
-                        status: "ok".to_string(),
-                        message: Some("Metrics updated successfully".to_string()),
-                        metrics: None,
-                    };
-                    warp::reply::json(&response)
+        .map(move |query: BatchQuery, source: SourceQuery, addr: Option<SocketAddr>, requests: Vec<MetricsRequest>| {
+            catch_panic(|| {
+                if let Some(message) = check_batch_size(requests.len(), max_batch_size) {
+                    let body = serde_json::json!({"status": "error", "message": message});
+                    return warp::reply::with_status(warp::reply::json(&body), StatusCode::PAYLOAD_TOO_LARGE);
                 }
-                Err(e) => {
-                    let response = MetricsResponse {
-                        status: "error".to_string(),
-                        message: Some(format!("Failed to update metrics: {}", e)),
-                        metrics: None,
-                    };
-                    warp::reply::json(&response)
+                let label = |req: &mut MetricsRequest| req.resolve_source(&source, addr, source_from_addr);
+                match ingest_batch(&batch_bridge, requests, query, label) {
+                    Ok(report) => warp::reply::with_status(warp::reply::json(&report), StatusCode::OK),
+                    Err(e) => {
+                        let body = serde_json::json!({
+                            "status": "error",
+                            "message": format!("Failed to update metrics: {}", e),
+                        });
+                        warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR)
+                    }
                 }
-            }
+            })
+        });
+
+    let metrics_update = warp::path("metrics")
+        .and(warp::post())
+        .and(rate_limit(limiter))
+        .and(warp::query::<SourceQuery>())
+        .and(warp::addr::remote())
+        .and(body_limit(config.max_body_bytes))
+        .and(warp::body::json())
+        .map(move |source: SourceQuery, addr: Option<SocketAddr>, mut req: MetricsRequest| {
+            catch_panic(|| {
+                // Update metrics
+                let result = req
+                    .resolve_source(&source, addr, source_from_addr)
+                    .and_then(|()| bridge.update_metrics(req));
+                match result {
+                    Ok(_) => {
+                        let response = MetricsResponse {
+                            status: "ok".to_string(),
+                            message: Some("Metrics updated successfully".to_string()),
+                            metrics: None,
+                        };
+                        warp::reply::json(&response)
+                    }
+                    Err(e) => {
+                        record_ingest_error();
+                        let response = MetricsResponse {
+                            status: "error".to_string(),
+                            message: Some(format!("Failed to update metrics: {}", e)),
+                            metrics: None,
+                        };
+                        warp::reply::json(&response)
+                    }
+                }
+            })
         });
 
+    let metrics_delete = reset_routes(config, move |scope, caller| {
+        let outcome = reset_metrics.reset(scope, caller);
+        log::info!(
+            "Metrics reset via API by {}, {} samples dropped",
+            caller,
+            outcome.history_dropped
+        );
+        outcome
+    });
+
     let metrics_get = warp::path("metrics")
+        .and(warp::get())
+        .and(with_metrics.clone())
+        .map(|metrics: MetricsHandle| {
+            catch_panic(|| {
+                let response = MetricsResponse {
+                    status: "ok".to_string(),
+                    message: None,
+                    metrics: Some(metrics.current()),
+                };
+                warp::reply::json(&response)
+            })
+        });
+
+    let current = warp::path!("api" / "current")
+        .and(warp::get())
+        .and(warp::query::<SourceQuery>())
+        .and(with_metrics.clone())
+        .map(|query: SourceQuery, metrics: MetricsHandle| {
+            catch_panic(|| {
+                let current = match &query.source {
+                    Some(source) => metrics.source_current(source),
+                    None => Some(metrics.current()),
+                };
+                let (status, response) = match current {
+                    Some(current) => (
+                        StatusCode::OK,
+                        MetricsResponse {
+                            status: "ok".to_string(),
+                            message: None,
+                            metrics: Some(current),
+                        },
+                    ),
+                    None => (
+                        StatusCode::NOT_FOUND,
+                        MetricsResponse {
+                            status: "error".to_string(),
+                            message: Some(format!("unknown source '{}'", query.source.unwrap_or_default())),
+                            metrics: None,
+                        },
+                    ),
+                };
+                warp::reply::with_status(warp::reply::json(&response), status)
+            })
+        });
+
+    let sources = warp::path!("api" / "sources")
+        .and(warp::get())
+        .and(with_metrics.clone())
+        .map(|metrics: MetricsHandle| catch_panic(|| warp::reply::json(&metrics.sources())));
+
+    let sla = warp::path!("api" / "sla")
+        .and(warp::get())
+        .and(with_metrics.clone())
+        .map(|metrics: MetricsHandle| catch_panic(|| warp::reply::json(&metrics.sla())));
+
+    let health_score = warp::path!("api" / "health-score")
+        .and(warp::get())
+        .and(with_metrics.clone())
+        .map(|metrics: MetricsHandle| catch_panic(|| warp::reply::json(&metrics.health_score())));
+
+    let history = warp::path!("api" / "history")
+        .and(warp::get())
+        .and(warp::query::<HistoryQuery>())
+        .and(with_metrics.clone())
+        .map(|query: HistoryQuery, metrics: MetricsHandle| {
+            catch_panic(|| {
+                let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT);
+                warp::reply::json(&metrics.history(query.from, query.to, limit))
+            })
+        });
+
+    let ingest_stats = warp::path!("api" / "ingest" / "stats")
         .and(warp::get())
         .map(|| {
-            match get_current_metrics() {
-                Some(metrics) => {
-                    let response = MetricsResponse {
-                        status: "ok".to_string(),
-                        message: None,
-                        metrics: Some(metrics),
-                    };
-                    warp::reply::json(&response)
-                }
-                None => {
-                    let response = MetricsResponse {
-                        status: "error".to_string(),
-                        message: Some("No metrics available".to_string()),
-                        metrics: None,
-                    };
-                    warp::reply::json(&response)
-                }
-            }
+            catch_panic(|| {
+                warp::reply::json(&serde_json::json!({
+                    "statsd": statsd_stats(),
+                    "http": http_limit_stats(),
+                }))
+            })
         });
 
+    let started = Instant::now();
+    let max_sample_age = config.health_max_sample_age_ms.map(Duration::from_millis);
     let health = warp::path("health")
         .and(warp::get())
-        .map(|| {
-            warp::reply::json(&serde_json::json!({
-                "status": "ok",
-                "service": "quic-bottom",
-                "version": env!("CARGO_PKG_VERSION")
-            }))
+        .and(with_metrics)
+        .map(move |metrics: MetricsHandle| {
+            catch_panic(|| {
+                let report = HealthReport::collect(&metrics, started, max_sample_age);
+                let status = if report.is_ready() {
+                    StatusCode::OK
+                } else {
+                    StatusCode::SERVICE_UNAVAILABLE
+                };
+                warp::reply::with_status(warp::reply::json(&report), status)
+            })
         });
 
-    metrics_update.or(metrics_get).or(health)
+    let routes = metrics_batch
+        .or(metrics_stream)
+        .or(metrics_update)
+        .or(metrics_delete)
+        .or(metrics_get)
+        .or(current)
+        .or(sources)
+        .or(sla)
+        .or(health_score)
+        .or(history)
+        .or(ingest_stats)
+        .or(health);
+    let routes = require_token(config)
+        .and(routes)
+        .recover(recover_unauthorized)
+        .recover(recover_rate_limited);
+    with_cors(routes, &config.cors)
+}
+
+/// Reply to `POST /api/reset` and `DELETE /api/metrics`
+#[derive(Debug, Serialize)]
+struct ResetResponse {
+    status: &'static str,
+    #[serde(flatten)]
+    outcome: ResetOutcome,
 }
 
-/// Start HTTP API server
-pub async fn start_api_server(port: u16) -> Result<()> {
-    let routes = create_api_routes();
-    
-    log::info!("Starting HTTP API server on port {}", port);
-    warp::serve(routes)
-        .run(([127, 0, 0, 1], port))
-        .await;
-    
+/// `DELETE /api/metrics`, and `POST /api/reset` with a `ResetScope` body, calling `reset(scope, caller)`
+///
+/// The bridge and the live dashboard both mount these, so they read the scope
+/// and answer the same way. `DELETE` and an empty `POST` body clear everything;
+/// a body that is not a `ResetScope` gets a 400 and resets nothing. Without an
+/// API token, which `require_token` then demands of every reset, they answer
+/// 403: anyone who can reach the port could otherwise wipe a run.
+pub fn reset_routes<F>(config: &ApiConfig, reset: F) -> BoxedFilter<(Response,)>
+where
+    F: Fn(ResetScope, &str) -> ResetOutcome + Clone + Send + Sync + 'static,
+{
+    let enabled = resolve_token(config).is_some();
+    if !enabled {
+        log::info!("API reset disabled: it needs [api] auth_token or {}", TOKEN_ENV);
+    }
+    let max_body_bytes = config.max_body_bytes;
+    let delete = warp::path!("api" / "metrics")
+        .and(warp::delete())
+        .map(|| Ok(ResetScope::full()));
+    // A bare `curl -X POST` declares no length, which `body_limit` alone refuses
+    let bodyless = warp::header::optional::<String>("content-length")
+        .and(warp::header::optional::<String>("transfer-encoding"))
+        .and_then(|length: Option<String>, encoding: Option<String>| async move {
+            match (length, encoding) {
+                (None, None) => Ok(()),
+                _ => Err(warp::reject()),
+            }
+        })
+        .untuple_one();
+    let post = warp::path!("api" / "reset")
+        .and(warp::post())
+        .and(body_limit(max_body_bytes).or(bodyless).unify())
+        .and(warp::body::bytes())
+        .map(|body: warp::hyper::body::Bytes| parse_reset_scope(&body));
+
+    delete
+        .or(post)
+        .unify()
+        .and(warp::addr::remote())
+        .map(move |scope: Result<ResetScope>, addr: Option<SocketAddr>| {
+            catch_panic(|| match scope {
+                _ if !enabled => {
+                    let message = format!("reset needs an API token: set [api] auth_token or {}", TOKEN_ENV);
+                    let body = serde_json::json!({"status": "error", "message": message});
+                    warp::reply::with_status(warp::reply::json(&body), StatusCode::FORBIDDEN)
+                }
+                Ok(scope) => {
+                    let caller = addr.map_or_else(|| "unknown".to_string(), |addr| addr.to_string());
+                    let outcome = reset(scope, &caller);
+                    warp::reply::with_status(
+                        warp::reply::json(&ResetResponse { status: "ok", outcome }),
+                        StatusCode::OK,
+                    )
+                }
+                Err(e) => {
+                    let body = serde_json::json!({"status": "error", "message": e.to_string()});
+                    warp::reply::with_status(warp::reply::json(&body), StatusCode::BAD_REQUEST)
+                }
+            })
+        })
+        .boxed()
+}
+
+/// The scope of a `POST /api/reset` body; everything when the body is empty
+fn parse_reset_scope(body: &[u8]) -> Result<ResetScope> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(ResetScope::full());
+    }
+    serde_json::from_slice(body).map_err(|e| anyhow!("invalid reset scope: {}", e))
+}
+
+/// Bind the HTTP API server and return the future that runs it
+///
+/// Binding happens here rather than when the future is polled, so an occupied
+/// port is reported to the caller before anything else starts.
+pub fn bind_api_server(addr: SocketAddr, config: &ApiConfig, metrics: MetricsHandle) -> Result<ServerFuture> {
+    let identity = TlsIdentity::from_config(config)?;
+    let (bound, server) = tls::bind(create_api_routes(config, metrics)?, addr, identity.as_ref())?;
+
+    log::info!("HTTP API server listening on {}://{}", config.scheme(), bound);
+    Ok(server)
+}
+
+/// Start HTTP API server over the global metrics handle
+pub async fn start_api_server(addr: SocketAddr, config: &ApiConfig) -> Result<()> {
+    bind_api_server(addr, config, MetricsHandle::global())?.await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::SegmentReset;
+    use crate::units::format_loss;
+
+    const TOKEN: &str = "reset-token";
+
+    /// An API with a token, which resets need
+    fn config() -> ApiConfig {
+        ApiConfig {
+            auth_token: Some(TOKEN.to_string()),
+            ..ApiConfig::default()
+        }
+    }
+
+    fn bearer() -> String {
+        format!("Bearer {}", TOKEN)
+    }
+
+    async fn post_sample(routes: &BoxedFilter<(Response,)>, latency: f64) {
+        let request = MetricsRequest {
+            latency,
+            throughput: 100.0,
+            connections: 1,
+            ..MetricsRequest::default()
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/metrics")
+            .header("authorization", bearer())
+            .json(&request)
+            .reply(routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    async fn reset(
+        routes: &BoxedFilter<(Response,)>,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = warp::test::request()
+            .method(method)
+            .path(path)
+            .header("authorization", bearer())
+            .body(body)
+            .reply(routes)
+            .await;
+        (response.status(), serde_json::from_slice(response.body()).unwrap())
+    }
+
+    /// A sample captured `secs` seconds into the run
+    fn at(secs: i64) -> QUICMetrics {
+        QUICMetrics {
+            latency: secs as f64,
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            ..QUICMetrics::default()
+        }
+    }
+
+    async fn history(routes: &BoxedFilter<(Response,)>, query: &str) -> Vec<f64> {
+        let response = warp::test::request()
+            .path(&format!("/api/history{}", query))
+            .reply(routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let samples: Vec<QUICMetrics> = serde_json::from_slice(response.body()).unwrap();
+        samples.iter().map(|sample| sample.latency).collect()
+    }
+
+    #[tokio::test]
+    async fn history_is_newest_last_and_capped() {
+        let metrics = MetricsHandle::new();
+        let routes = create_api_routes(&ApiConfig::default(), metrics.clone()).unwrap();
+        let response = warp::test::request().path("/api/history").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body().as_ref(), b"[]");
+
+        for secs in 1..=5 {
+            metrics.update(at(secs)).unwrap();
+        }
+        assert_eq!(history(&routes, "").await, [1.0, 2.0, 3.0, 4.0, 5.0]);
+        // The newest within the range, still oldest first
+        assert_eq!(history(&routes, "?limit=2").await, [4.0, 5.0]);
+        let from = at(2).timestamp.timestamp_millis();
+        let to = at(4).timestamp.timestamp_millis();
+        assert_eq!(history(&routes, &format!("?from={}&to={}", from, to)).await, [2.0, 3.0, 4.0]);
+        assert_eq!(history(&routes, &format!("?to={}&limit=1", to)).await, [4.0]);
+        assert!(history(&routes, &format!("?from={}", to * 2)).await.is_empty());
+
+        for secs in 6..=MAX_HISTORY_LIMIT as i64 + 100 {
+            metrics.update(at(secs)).unwrap();
+        }
+        let capped = history(&routes, "?limit=100000000").await;
+        assert_eq!(capped.len(), MAX_HISTORY_LIMIT);
+        assert_eq!(capped.last(), Some(&(MAX_HISTORY_LIMIT as f64 + 100.0)));
+        assert!(capped.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn reset_clears_what_the_scope_selects() {
+        for scope in scope_combinations() {
+            let metrics = MetricsHandle::new();
+            let routes = create_api_routes(&config(), metrics.clone()).unwrap();
+            post_sample(&routes, 10.0).await;
+            post_sample(&routes, 20.0).await;
+
+            let body = serde_json::to_string(&scope).unwrap();
+            let (status, reply) = reset(&routes, "POST", "/api/reset", &body).await;
+            assert_eq!(status, StatusCode::OK, "{}", body);
+            assert_eq!(reply["scope"], serde_json::to_value(scope).unwrap());
+            let new_segment = scope.segments == SegmentReset::New;
+            assert_eq!(reply["segment"], if new_segment { 2 } else { 1 }, "{}", body);
+            assert_eq!(metrics.segment(), if new_segment { 2 } else { 1 });
+
+            let kept = if scope.history { 0 } else { 2 };
+            assert_eq!(reply["history_dropped"], 2 - kept, "{}", body);
+            assert_eq!(metrics.history_fill().0, kept, "{}", body);
+            let current = if scope.stats { 0.0 } else { 20.0 };
+            assert_eq!(metrics.current().latency, current, "{}", body);
+            let event = metrics.last_reset().unwrap();
+            assert_eq!((event.id, event.outcome.scope), (1, scope));
+        }
+    }
+
+    /// Every stats/history/anomalies/segments combination
+    fn scope_combinations() -> Vec<ResetScope> {
+        let mut scopes = Vec::new();
+        for bits in 0..16u8 {
+            scopes.push(ResetScope {
+                stats: bits & 1 != 0,
+                history: bits & 2 != 0,
+                anomalies: bits & 4 != 0,
+                segments: if bits & 8 != 0 { SegmentReset::New } else { SegmentReset::Keep },
+            });
+        }
+        scopes
+    }
+
+    #[tokio::test]
+    async fn reset_needs_a_token() {
+        let metrics = MetricsHandle::new();
+        let routes = create_api_routes(&ApiConfig::default(), metrics.clone()).unwrap();
+        post_sample(&routes, 10.0).await;
+        for (method, path) in [("POST", "/api/reset"), ("DELETE", "/api/metrics")] {
+            let (status, body) = reset(&routes, method, path, "").await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{} {}", method, path);
+            assert_eq!(body["status"], "error");
+        }
+        assert_eq!(metrics.history_fill().0, 1);
+        assert!(metrics.last_reset().is_none());
+
+        // With one, a request without it is refused before the route
+        let routes = create_api_routes(&config(), metrics.clone()).unwrap();
+        let response = warp::test::request().method("DELETE").path("/api/metrics").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(metrics.history_fill().0, 1);
+    }
+
+    #[tokio::test]
+    async fn empty_body_and_delete_clear_everything() {
+        let metrics = MetricsHandle::new();
+        let routes = create_api_routes(&config(), metrics.clone()).unwrap();
+        post_sample(&routes, 10.0).await;
+
+        let (status, body) = reset(&routes, "POST", "/api/reset", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["scope"], serde_json::to_value(ResetScope::full()).unwrap());
+        assert_eq!(body["history_dropped"], 1);
+
+        // As `curl -X POST` sends it, without a Content-Length
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/reset")
+            .header("authorization", bearer())
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/reset")
+            .header("authorization", bearer())
+            .header("content-length", "100000000")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        post_sample(&routes, 10.0).await;
+        let (status, body) = reset(&routes, "DELETE", "/api/metrics", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["history_dropped"], 1);
+        assert_eq!(body["segment"], 1);
+    }
+
+    #[tokio::test]
+    async fn malformed_scope_resets_nothing() {
+        let metrics = MetricsHandle::new();
+        let routes = create_api_routes(&config(), metrics.clone()).unwrap();
+        post_sample(&routes, 10.0).await;
+
+        for body in ["{", r#"{"segments": "later"}"#, r#"{"stats": "yes"}"#] {
+            let (status, reply) = reset(&routes, "POST", "/api/reset", body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+            assert_eq!(reply["status"], "error");
+        }
+        assert_eq!(metrics.history_fill().0, 1);
+        assert!(metrics.last_reset().is_none());
+    }
+
+    #[test]
+    fn throughput_is_stored_in_bits_per_second() {
+        let received = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let sample = |json: &str| serde_json::from_str::<MetricsRequest>(json).unwrap().to_metrics(received);
+        let base = r#""latency": 1.0, "connections": 1, "errors": 0, "packet_loss": 0.0, "retransmits": 0"#;
+        let mbps = sample(&format!(r#"{{{}, "throughput": 940.0, "unit": "Mbps"}}"#, base));
+        assert_eq!(mbps.throughput, 9.4e8);
+        let kbps = sample(&format!(r#"{{{}, "throughput": 940000.0, "unit": "kbps"}}"#, base));
+        assert_eq!(kbps.throughput, 9.4e8);
+        let bps = sample(&format!(r#"{{{}, "throughput": 9.4e8, "unit": "bps"}}"#, base));
+        assert_eq!(bps.throughput, 9.4e8);
+        assert!(serde_json::from_str::<MetricsRequest>(&format!(r#"{{{}, "throughput": 1.0, "unit": "MB/s"}}"#, base)).is_err());
+    }
+
+    #[test]
+    fn loss_round_trips_from_both_sender_conventions() {
+        let received = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let request = |packet_loss, loss_unit| MetricsRequest { packet_loss, loss_unit: Some(loss_unit), ..MetricsRequest::default() };
+
+        // Half a percent, as each kind of sender reports it
+        for sent in [request(0.005, LossUnit::Fraction), request(0.5, LossUnit::Percent)] {
+            sent.validate().unwrap();
+            let stored = sent.to_metrics(received);
+            assert_eq!(stored.packet_loss, 0.005);
+            assert_eq!(format_loss(stored.packet_loss, 2), "0.50%");
+            // Exported and read back, the fraction stays a fraction
+            let exported = serde_json::to_value(&stored).unwrap();
+            assert_eq!(exported["packet_loss"], 0.005);
+        }
+
+        assert!(request(100.0, LossUnit::Percent).validate().is_ok());
+        assert!(request(150.0, LossUnit::Percent).validate().is_err());
+        assert!(request(1.5, LossUnit::Fraction).validate().is_err());
+        assert!(request(-0.1, LossUnit::Fraction).validate().is_err());
+    }
+}
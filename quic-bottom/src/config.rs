@@ -1,32 +1,150 @@
 //! Configuration module for QUIC Bottom
 //! 
-//! Handles configuration loading and management
+//! Handles configuration loading and management. Settings are layered:
+//! the defaults, then the config file (any key may be left out), then
+//! `QUIC_BOTTOM_*` environment variables, then command-line flags. The
+//! result is checked once by `validate`, which lists every problem found.
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::fmt::Display;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::alerting::AlertingConfig;
+use crate::anomaly_detection::AnomalyConfig;
+use crate::axis_scale::AxisScale;
+use crate::bridge::ApiConfig;
+use crate::correlation_widget::CorrelationConfig;
+use crate::demo_data::FaultConfig;
+use crate::export::ExportConfig;
+use crate::exporters::ExportersConfig;
+use crate::health_score::HealthConfig;
+use crate::heatmap_widget::HeatmapConfig;
+use crate::improved_layout::{LayoutConfig, WidgetKind};
+use crate::ingest::IngestConfig;
+use crate::keybindings::KeybindingsConfig;
+use crate::metrics::{DerivedMetricsConfig, StalenessConfig};
+use crate::network_sim::NetworkSimConfig;
+use crate::redact::RedactionConfig;
+use crate::report::ReportConfig;
+use crate::security::SecurityConfig;
+use crate::sla::SlaConfig;
+use crate::theme::ColorConfig;
+use crate::views::{validate_presets, ViewPresetConfig};
 
 /// QUIC Bottom configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QuicBottomConfig {
     /// Update interval in milliseconds
     pub update_interval: u64,
+
+    /// Most frames drawn per second; a frame is only drawn when something changed
+    pub max_fps: u32,
     
+    /// Address the HTTP API binds to, e.g. `0.0.0.0` for remote collectors
+    #[serde(default = "default_bind_address")]
+    pub bind_address: String,
+
     /// HTTP API port for Go integration
     pub api_port: u16,
+
+    /// HTTP API limits
+    #[serde(default)]
+    pub api: ApiConfig,
+
+    /// Ingestion transports besides the HTTP API
+    #[serde(default)]
+    pub ingest: IngestConfig,
     
     /// Maximum data points for time series
     pub max_data_points: usize,
     
     /// Widget configuration
     pub widgets: WidgetConfig,
+
+    /// Rows and columns of widgets the dashboard is drawn from
+    #[serde(default)]
+    pub layout: LayoutConfig,
+
+    /// Named layouts to switch to besides the built-in views
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub views: Vec<ViewPresetConfig>,
+
+    /// Last-update age thresholds in the dashboard header
+    #[serde(default)]
+    pub staleness: StalenessConfig,
+
+    /// Goodput and efficiency derived from each sample
+    #[serde(default)]
+    pub derived: DerivedMetricsConfig,
     
     /// Color theme
+    #[serde(default)]
     pub colors: ColorConfig,
+
+    /// Heatmap row selection
+    #[serde(default)]
+    pub heatmap: HeatmapConfig,
+
+    /// Correlation window, trends and compact layout
+    #[serde(default)]
+    pub correlation: CorrelationConfig,
+
+    /// Redaction of sensitive fields in exports and reports
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    /// Session export ('e' key)
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    /// Forwarding of ingested samples to external stores
+    #[serde(default)]
+    pub exporters: ExportersConfig,
+
+    /// Session summary report ('R' key and on quit)
+    #[serde(default)]
+    pub report: ReportConfig,
+
+    /// Pass/fail targets for the run
+    #[serde(default)]
+    pub sla: SlaConfig,
+
+    /// Weights and targets of the composite health score
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Per-metric anomaly detection settings
+    #[serde(default)]
+    pub anomaly: AnomalyConfig,
+
+    /// Webhook/command alerts on detected anomalies
+    #[serde(default)]
+    pub alerting: AlertingConfig,
+
+    /// Keys for TUI actions, replacing the defaults per action
+    #[serde(default)]
+    pub keybindings: KeybindingsConfig,
+
+    /// Network simulation profiles and the one selected at startup
+    #[serde(default)]
+    pub network: NetworkSimConfig,
+
+    /// Faults F1-F5 inject into the demo data
+    #[serde(default)]
+    pub faults: FaultConfig,
+
+    /// Endpoint the security view checks, and which checks it runs
+    #[serde(default)]
+    pub security: SecurityConfig,
 }
 
 /// Widget-specific configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WidgetConfig {
     /// Latency widget settings
     pub latency: LatencyWidgetConfig,
@@ -39,10 +157,67 @@ pub struct WidgetConfig {
     
     /// Network quality widget settings
     pub network: NetworkWidgetConfig,
+
+    /// Per-connection table settings
+    #[serde(default)]
+    pub connection_table: ConnectionTableConfig,
+
+    /// Performance heatmap in the analytics views
+    pub heatmap: PanelConfig,
+
+    /// Correlation matrix in the analytics views
+    pub correlation: PanelConfig,
+
+    /// Anomaly list in the analytics views
+    pub anomaly: PanelConfig,
+}
+
+impl WidgetConfig {
+    /// Whether `kind` is drawn; streams, bbrv3, current_metrics and cwnd have no switch
+    pub fn is_enabled(&self, kind: WidgetKind) -> bool {
+        match kind {
+            WidgetKind::Latency => self.latency.enabled,
+            WidgetKind::Throughput => self.throughput.enabled,
+            WidgetKind::Connections => self.connections.enabled,
+            WidgetKind::Network => self.network.enabled,
+            WidgetKind::Heatmap => self.heatmap.enabled,
+            WidgetKind::Correlation => self.correlation.enabled,
+            WidgetKind::Anomaly => self.anomaly.enabled,
+            // The histogram draws the latency samples, so it follows the latency widget
+            WidgetKind::LatencyHistogram => self.latency.enabled,
+            WidgetKind::Streams | WidgetKind::Bbrv3 | WidgetKind::CurrentMetrics | WidgetKind::Cwnd => true,
+        }
+    }
+}
+
+/// A widget with only an on/off switch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelConfig {
+    /// Draw the widget; the others of its view share its room when off
+    pub enabled: bool,
+}
+
+impl Default for PanelConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Connection table configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConnectionTableConfig {
+    /// Seconds without a report before a connection is greyed out
+    pub stale_after_secs: u64,
+
+    /// Seconds without a report before a connection is removed
+    pub evict_after_secs: u64,
 }
 
 /// Latency widget configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LatencyWidgetConfig {
     /// Enable latency widget
     pub enabled: bool,
@@ -55,10 +230,15 @@ pub struct LatencyWidgetConfig {
     
     /// Show jitter
     pub show_jitter: bool,
+
+    /// Value axis of the latency graph
+    #[serde(default)]
+    pub scale: AxisScale,
 }
 
 /// Throughput widget configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ThroughputWidgetConfig {
     /// Enable throughput widget
     pub enabled: bool,
@@ -71,10 +251,15 @@ pub struct ThroughputWidgetConfig {
     
     /// Show maximum
     pub show_maximum: bool,
+
+    /// Value axis of the throughput graph
+    #[serde(default)]
+    pub scale: AxisScale,
 }
 
 /// Connection widget configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConnectionWidgetConfig {
     /// Enable connection widget
     pub enabled: bool,
@@ -88,6 +273,7 @@ pub struct ConnectionWidgetConfig {
 
 /// Network quality widget configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NetworkWidgetConfig {
     /// Enable network widget
     pub enabled: bool,
@@ -100,49 +286,52 @@ pub struct NetworkWidgetConfig {
     
     /// Show congestion control
     pub show_congestion_control: bool,
-}
 
-/// Color configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ColorConfig {
-    /// Primary color
-    pub primary: String,
-    
-    /// Secondary color
-    pub secondary: String,
-    
-    /// Accent color
-    pub accent: String,
-    
-    /// Success color
-    pub success: String,
-    
-    /// Warning color
-    pub warning: String,
-    
-    /// Error color
-    pub error: String,
+    /// Percentage points the reported loss may differ from the loss computed
+    /// from packet counters before it is highlighted
+    #[serde(default = "default_loss_disagreement_margin")]
+    pub loss_disagreement_margin: f64,
 }
 
 impl Default for QuicBottomConfig {
     fn default() -> Self {
         Self {
             update_interval: 100,
+            max_fps: DEFAULT_MAX_FPS,
+            bind_address: default_bind_address(),
             api_port: 8080,
+            api: ApiConfig::default(),
+            ingest: IngestConfig::default(),
             max_data_points: 1000,
             widgets: WidgetConfig::default(),
+            layout: LayoutConfig::default(),
+            views: Vec::new(),
+            staleness: StalenessConfig::default(),
+            derived: DerivedMetricsConfig::default(),
             colors: ColorConfig::default(),
+            heatmap: HeatmapConfig::default(),
+            correlation: CorrelationConfig::default(),
+            redaction: RedactionConfig::default(),
+            export: ExportConfig::default(),
+            exporters: ExportersConfig::default(),
+            report: ReportConfig::default(),
+            sla: SlaConfig::default(),
+            health: HealthConfig::default(),
+            anomaly: AnomalyConfig::default(),
+            alerting: AlertingConfig::default(),
+            keybindings: KeybindingsConfig::default(),
+            network: NetworkSimConfig::default(),
+            faults: FaultConfig::default(),
+            security: SecurityConfig::default(),
         }
     }
 }
 
-impl Default for WidgetConfig {
+impl Default for ConnectionTableConfig {
     fn default() -> Self {
         Self {
-            latency: LatencyWidgetConfig::default(),
-            throughput: ThroughputWidgetConfig::default(),
-            connections: ConnectionWidgetConfig::default(),
-            network: NetworkWidgetConfig::default(),
+            stale_after_secs: 10,
+            evict_after_secs: 60,
         }
     }
 }
@@ -154,6 +343,7 @@ impl Default for LatencyWidgetConfig {
             max_points: 1000,
             show_percentiles: true,
             show_jitter: true,
+            scale: AxisScale::Linear,
         }
     }
 }
@@ -165,6 +355,7 @@ impl Default for ThroughputWidgetConfig {
             max_points: 1000,
             show_average: true,
             show_maximum: true,
+            scale: AxisScale::Linear,
         }
     }
 }
@@ -186,31 +377,217 @@ impl Default for NetworkWidgetConfig {
             show_loss_graph: true,
             show_retransmit_graph: true,
             show_congestion_control: true,
+            loss_disagreement_margin: default_loss_disagreement_margin(),
         }
     }
 }
 
-impl Default for ColorConfig {
-    fn default() -> Self {
-        Self {
-            primary: "blue".to_string(),
-            secondary: "green".to_string(),
-            accent: "yellow".to_string(),
-            success: "green".to_string(),
-            warning: "yellow".to_string(),
-            error: "red".to_string(),
-        }
+fn default_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_loss_disagreement_margin() -> f64 {
+    1.0
+}
+
+/// Parse a bind address given as `ip` or `ip:port`
+///
+/// A bare IP is combined with `default_port`; an explicit port wins.
+pub fn parse_bind_addr(bind: &str, default_port: u16) -> Result<SocketAddr> {
+    if let Ok(addr) = bind.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    bind.parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, default_port))
+        .map_err(|_| {
+            anyhow!(
+                "invalid bind address '{}': expected an IP such as 127.0.0.1 or 0.0.0.0, optionally with :port",
+                bind
+            )
+        })
+}
+
+/// Shortest update interval `validate` accepts, in milliseconds
+pub const MIN_UPDATE_INTERVAL_MS: u64 = 10;
+
+/// Frame rate cap of the TUI modes unless `max_fps` is set
+pub const DEFAULT_MAX_FPS: u32 = 30;
+
+/// Highest `max_fps` `validate` accepts
+pub const MAX_FPS: u32 = 240;
+
+/// Fewest data points per series `validate` accepts
+pub const MIN_DATA_POINTS: usize = 10;
+
+/// Prefix of the environment variables that override config keys
+pub const ENV_PREFIX: &str = "QUIC_BOTTOM_";
+
+/// Variables `apply_env` reads, without `ENV_PREFIX`, and the keys they set
+pub const ENV_KEYS: &[(&str, &str)] = &[
+    ("UPDATE_INTERVAL", "update_interval"),
+    ("BIND_ADDRESS", "bind_address"),
+    ("API_PORT", "api_port"),
+    ("MAX_DATA_POINTS", "max_data_points"),
+    ("THEME", "colors"),
+    ("EXPORT_DIR", "export.output_dir"),
+    ("REPORT_DIR", "report.output_dir"),
+];
+
+/// The documented config file `config init` writes: every section, with the defaults
+pub const DEFAULT_CONFIG_TEMPLATE: &str = include_str!("../config.toml");
+
+/// `path` with a leading `~` replaced by the home directory
+///
+/// Left as is when `HOME` is not set.
+pub fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with('/') => &rest[1..],
+        _ => return PathBuf::from(path),
+    };
+    match std::env::var_os("HOME") {
+        Some(home) => PathBuf::from(home).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Value of `QUIC_BOTTOM_<name>`, `None` when unset or empty
+fn env_override<T>(name: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let var = format!("{}{}", ENV_PREFIX, name);
+    match std::env::var(&var) {
+        Ok(value) if !value.is_empty() => value
+            .parse()
+            .map(Some)
+            .map_err(|e| anyhow!("{}: invalid value '{}': {}", var, value, e)),
+        _ => Ok(None),
     }
 }
 
 impl QuicBottomConfig {
-    /// Load configuration from file
+    /// Socket address for the HTTP API
+    pub fn api_addr(&self) -> Result<SocketAddr> {
+        parse_bind_addr(&self.bind_address, self.api_port)
+    }
+
+    /// Load and validate a configuration file
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let config: QuicBottomConfig = toml::from_str(&content)?;
+        let path = path.as_ref();
+        let config = Self::parse_file(path)?;
+        config
+            .validate()
+            .with_context(|| format!("invalid config {}", path.display()))?;
         Ok(config)
     }
 
+    /// Read a configuration file without validating it; missing keys take their defaults
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("failed to parse config {}", path.display()))
+    }
+
+    /// Apply the `QUIC_BOTTOM_*` environment variables of `ENV_KEYS` over the loaded values
+    ///
+    /// The API token has its own variable (see `auth`).
+    pub fn apply_env(&mut self) -> Result<()> {
+        if let Some(interval) = env_override("UPDATE_INTERVAL")? {
+            self.update_interval = interval;
+        }
+        if let Some(bind) = env_override("BIND_ADDRESS")? {
+            self.bind_address = bind;
+        }
+        if let Some(port) = env_override("API_PORT")? {
+            self.api_port = port;
+        }
+        if let Some(points) = env_override("MAX_DATA_POINTS")? {
+            self.max_data_points = points;
+        }
+        if let Some(name) = env_override::<String>("THEME")? {
+            self.colors = ColorConfig::builtin(&name);
+        }
+        if let Some(dir) = env_override("EXPORT_DIR")? {
+            self.export.output_dir = dir;
+        }
+        if let Some(dir) = env_override("REPORT_DIR")? {
+            self.report.output_dir = dir;
+        }
+        Ok(())
+    }
+
+    /// Check ranges and the consistency of related keys, reporting every problem at once
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        if self.update_interval < MIN_UPDATE_INTERVAL_MS {
+            problems.push(format!(
+                "update_interval: must be at least {} ms, got {}",
+                MIN_UPDATE_INTERVAL_MS, self.update_interval
+            ));
+        }
+        if !(1..=MAX_FPS).contains(&self.max_fps) {
+            problems.push(format!("max_fps: must be between 1 and {}, got {}", MAX_FPS, self.max_fps));
+        }
+        if self.max_data_points < MIN_DATA_POINTS {
+            problems.push(format!(
+                "max_data_points: must be at least {}, got {}",
+                MIN_DATA_POINTS, self.max_data_points
+            ));
+        }
+        if self.api_port == 0 {
+            problems.push("api_port: must be between 1 and 65535, got 0".to_string());
+        }
+        if let Err(e) = self.api_addr() {
+            problems.push(format!("bind_address: {}", e));
+        }
+        if self.api.tls_cert.is_some() != self.api.tls_key.is_some() {
+            problems.push("api.tls_cert, api.tls_key: TLS needs both or neither".to_string());
+        }
+        if self.staleness.warn_after_ms > self.staleness.stale_after_ms {
+            problems.push(format!(
+                "staleness.warn_after_ms: must not exceed stale_after_ms ({} > {})",
+                self.staleness.warn_after_ms, self.staleness.stale_after_ms
+            ));
+        }
+        if self.derived.success_window_secs == 0 {
+            problems.push("derived.success_window_secs: must be at least 1".to_string());
+        }
+        let table = &self.widgets.connection_table;
+        if table.stale_after_secs > table.evict_after_secs {
+            problems.push(format!(
+                "widgets.connection_table.stale_after_secs: must not exceed evict_after_secs ({} > {})",
+                table.stale_after_secs, table.evict_after_secs
+            ));
+        }
+
+        let sections = [
+            ("colors", self.colors.theme().map(|_| ())),
+            ("layout", self.layout.validate()),
+            ("views", validate_presets(&self.views)),
+            ("heatmap", self.heatmap.validate()),
+            ("correlation", self.correlation.validate()),
+            ("anomaly", self.anomaly.validate()),
+            ("health", self.health.validate()),
+            ("network", self.network.validate()),
+            ("faults", self.faults.validate()),
+            ("security", self.security.validate()),
+        ];
+        for (section, result) in sections {
+            if let Err(e) = result {
+                problems.push(format!("[{}]: {:#}", section, e));
+            }
+        }
+
+        match problems.len() {
+            0 => Ok(()),
+            1 => bail!("{}", problems[0]),
+            n => bail!("{} problems:\n  - {}", n, problems.join("\n  - ")),
+        }
+    }
+
     /// Save configuration to file
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self)?;
@@ -218,10 +595,85 @@ impl QuicBottomConfig {
         Ok(())
     }
 
-    /// Create default configuration file
+    /// Write the commented default configuration file, creating its directory
     pub fn create_default_config<P: AsRef<Path>>(path: P) -> Result<()> {
-        let config = QuicBottomConfig::default();
-        config.save_to_file(path)?;
-        Ok(())
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        std::fs::write(path, DEFAULT_CONFIG_TEMPLATE)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// The settings as TOML, each value that differs from the defaults
+    /// followed by a comment naming where it came from
+    ///
+    /// That is the `QUIC_BOTTOM_*` variable that set it, or else `file`.
+    pub fn annotated_toml(&self, file: &str) -> Result<String> {
+        let defaults = to_table(&Self::default())?;
+        let env: Vec<(String, &str)> = ENV_KEYS
+            .iter()
+            .map(|(name, key)| (format!("{}{}", ENV_PREFIX, name), *key))
+            .filter(|(var, _)| std::env::var(var).is_ok_and(|value| !value.is_empty()))
+            .collect();
+        let source = |key: &str| {
+            env.iter()
+                .find(|(_, prefix)| key == *prefix || key.starts_with(&format!("{}.", prefix)))
+                .map_or_else(|| file.to_string(), |(var, _)| var.clone())
+        };
+        let mut out = String::new();
+        write_table(&mut out, "", &to_table(self)?, Some(&defaults), &source);
+        Ok(out)
+    }
+}
+
+/// `config` as a TOML table
+pub(crate) fn to_table(config: &QuicBottomConfig) -> Result<toml::Table> {
+    match toml::Value::try_from(config)? {
+        toml::Value::Table(table) => Ok(table),
+        _ => Err(anyhow!("config did not serialize to a table")),
+    }
+}
+
+/// Append `table` under the `[prefix]` header, values first and sub-tables after
+fn write_table(
+    out: &mut String,
+    prefix: &str,
+    table: &toml::Table,
+    defaults: Option<&toml::Table>,
+    source: &dyn Fn(&str) -> String,
+) {
+    let path = |key: &str| match prefix {
+        "" => toml_key(key),
+        _ => format!("{}.{}", prefix, toml_key(key)),
+    };
+    for (key, value) in table.iter().filter(|(_, value)| !value.is_table()) {
+        out.push_str(&format!("{} = {}", toml_key(key), value));
+        if defaults.and_then(|defaults| defaults.get(key)) != Some(value) {
+            out.push_str(&format!("  # {}", source(&path(key))));
+        }
+        out.push('\n');
+    }
+    for (key, value) in table {
+        let Some(sub) = value.as_table() else {
+            continue;
+        };
+        let header = path(key);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("[{}]\n", header));
+        let sub_defaults = defaults.and_then(|defaults| defaults.get(key)).and_then(toml::Value::as_table);
+        write_table(out, &header, sub, sub_defaults, source);
+    }
+}
+
+/// `key` bare when TOML allows it, quoted otherwise
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
     }
 }
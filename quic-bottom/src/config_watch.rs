@@ -0,0 +1,146 @@
+//! Reloading the config file while a mode runs
+//!
+//! `ConfigWatcher` checks the file's modification time from the mode's tick
+//! and hands back a `ConfigUpdate` once it changes. The new file is compared
+//! with the previous one section by section, so a setting given on the
+//! command line stays in force until the file changes the same section.
+//! Each mode applies the sections it can switch at runtime and reports the
+//! others (the API address, ingestion, exporters) as needing a restart.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::{to_table, QuicBottomConfig};
+
+/// How often the file's modification time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A config file that loaded after it changed on disk
+#[derive(Debug, Clone)]
+pub struct ConfigUpdate {
+    pub config: QuicBottomConfig,
+    /// Top-level keys whose value differs from the previous file, e.g. `sla` or `api_port`
+    pub changed: Vec<String>,
+}
+
+impl ConfigUpdate {
+    /// Whether the top-level `key` changed
+    pub fn changed(&self, key: &str) -> bool {
+        self.changed.iter().any(|changed| changed == key)
+    }
+
+    /// Changed keys outside `reloadable`, which only take effect on restart
+    pub fn rejected(&self, reloadable: &[&str]) -> Vec<&str> {
+        self.changed
+            .iter()
+            .map(String::as_str)
+            .filter(|key| !reloadable.contains(key))
+            .collect()
+    }
+
+    /// One line for the footer, e.g. `Config reloaded: sla, anomaly; restart to apply api_port`
+    pub fn summary(&self, reloadable: &[&str]) -> String {
+        let applied: Vec<&str> = self
+            .changed
+            .iter()
+            .map(String::as_str)
+            .filter(|key| reloadable.contains(key))
+            .collect();
+        let rejected = self.rejected(reloadable);
+        let mut message = if applied.is_empty() {
+            "Config reloaded, no reloadable setting changed".to_string()
+        } else {
+            format!("Config reloaded: {}", applied.join(", "))
+        };
+        if !rejected.is_empty() {
+            message.push_str(&format!("; restart to apply {}", rejected.join(", ")));
+        }
+        message
+    }
+}
+
+/// Polls a config file and reloads it when its modification time changes
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    /// Modification time seen last, `None` while the file does not exist
+    modified: Option<SystemTime>,
+    /// The last file that loaded, which changes are compared against
+    current: QuicBottomConfig,
+    last_check: Instant,
+}
+
+impl ConfigWatcher {
+    /// Watch `path`, whose contents at startup were `loaded`
+    ///
+    /// `loaded` is the config as read from the file, before command-line
+    /// overrides, or the defaults when the file did not exist.
+    pub fn new(path: impl Into<PathBuf>, loaded: QuicBottomConfig) -> Self {
+        let path = path.into();
+        Self {
+            modified: modified(&path),
+            path,
+            current: loaded,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Reload the file if it changed since the last call
+    ///
+    /// Returns `None` while nothing changed. A file that fails to load is
+    /// reported once per change, and the previous config stays the one the
+    /// next change is compared against.
+    pub fn poll(&mut self) -> Option<Result<ConfigUpdate>> {
+        if self.last_check.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        // A removed file keeps the running settings
+        modified?;
+
+        Some(self.reload())
+    }
+
+    fn reload(&mut self) -> Result<ConfigUpdate> {
+        let config = QuicBottomConfig::load_from_file(&self.path)
+            .with_context(|| format!("failed to reload {}", self.path.display()))?;
+        let changed = changed_keys(&self.current, &config)?;
+        self.current = config.clone();
+        Ok(ConfigUpdate { config, changed })
+    }
+}
+
+/// One line for the footer about a reload that failed
+pub fn failure_summary(error: &anyhow::Error) -> String {
+    // TOML errors quote the offending line over several lines; keep the text around it
+    let text = format!("{:#}", error);
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains('|'))
+        .collect();
+    format!("Config not reloaded, keeping the previous one: {}", lines.join(", "))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Top-level keys whose serialized value differs between `old` and `new`
+fn changed_keys(old: &QuicBottomConfig, new: &QuicBottomConfig) -> Result<Vec<String>> {
+    let (old, new) = (to_table(old)?, to_table(new)?);
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    Ok(keys
+        .into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .cloned()
+        .collect())
+}
@@ -1,15 +1,52 @@
 //! Correlation widget for analyzing relationships between metrics
 //! 
-//! Shows correlation matrix between different QUIC metrics
+//! Shows correlation matrix between different QUIC metrics, or on small
+//! areas a list of the strongest pairs
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Table, Row, Cell},
+    widgets::{Block, Borders, Paragraph, Table, Row, Cell, Sparkline},
     Frame,
 };
-use std::collections::HashMap;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cell;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::focus::{border_style, title_style};
+use crate::scatter_plot::ScatterPlotWidget;
+use crate::theme::Theme;
+
+/// Lags checked on each side of zero in the lag detail view
+pub const DEFAULT_MAX_LAG: usize = 10;
+
+/// Overlapping samples needed to correlate a pair at a given lag
+const MIN_LAG_OVERLAP: usize = 3;
+
+/// Samples kept per metric, the largest correlation window
+pub const HISTORY_LEN: usize = 100;
+
+/// Smallest correlation window, the samples a coefficient needs
+pub const MIN_WINDOW: usize = 3;
+
+/// Samples the `+`/`-` keys add to or take from the window
+pub const WINDOW_STEP: usize = 5;
+
+/// Change of |r| across the trend points that counts as a trend
+const MIN_TREND_CHANGE: f64 = 0.1;
+
+/// Narrowest matrix column, enough for a coefficient and its trend such as `-0.83↑`
+const MIN_CELL_WIDTH: u16 = 6;
+
+/// Widest matrix column; longer metric names are cut
+const MAX_CELL_WIDTH: u16 = 24;
+
+/// Gap between matrix columns
+const COLUMN_SPACING: u16 = 1;
 
 /// Correlation data between two metrics
 #[derive(Debug, Clone)]
@@ -17,7 +54,184 @@ pub struct CorrelationData {
     pub metric1: String,
     pub metric2: String,
     pub correlation: f64,
-    pub significance: f64,
+    /// Two-sided p-value of the coefficient against no correlation
+    pub p_value: f64,
+    /// Whether |r| has been rising or falling over the last recalculations
+    pub trend: Option<Trend>,
+}
+
+/// Direction of |r| over the last recalculations of a pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Strengthening,
+    Weakening,
+}
+
+impl Trend {
+    /// Trend of the least-squares line through `values`, `None` when |r| moved less than `MIN_TREND_CHANGE`
+    pub fn of(values: &VecDeque<f64>) -> Option<Self> {
+        if values.len() < 3 {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = values.iter().map(|r| r.abs()).sum::<f64>() / n;
+        let (mut covariance, mut variance) = (0.0, 0.0);
+        for (i, r) in values.iter().enumerate() {
+            let dx = i as f64 - mean_x;
+            covariance += dx * (r.abs() - mean_y);
+            variance += dx * dx;
+        }
+        let change = covariance / variance * (n - 1.0);
+        if change >= MIN_TREND_CHANGE {
+            Some(Trend::Strengthening)
+        } else if change <= -MIN_TREND_CHANGE {
+            Some(Trend::Weakening)
+        } else {
+            None
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Trend::Strengthening => "↑",
+            Trend::Weakening => "↓",
+        }
+    }
+}
+
+/// Correlation of a metric pair as one series is shifted against the other
+///
+/// A positive lag `k` pairs `metric1[t]` with `metric2[t + k]`, i.e. `metric2`
+/// follows `metric1` by `k` samples.
+#[derive(Debug, Clone)]
+pub struct LagProfile {
+    pub metric1: String,
+    pub metric2: String,
+    /// `(lag, correlation)` for every lag with enough overlap, in lag order
+    pub correlations: Vec<(i64, f64)>,
+    pub best_lag: i64,
+    pub best_correlation: f64,
+}
+
+/// How the correlation coefficient is computed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CorrelationMethod {
+    /// Linear correlation of the raw values
+    #[default]
+    Pearson,
+    /// Rank correlation, robust to outliers and monotonic non-linear relationships
+    Spearman,
+}
+
+impl CorrelationMethod {
+    /// The other method
+    pub fn toggled(self) -> Self {
+        match self {
+            CorrelationMethod::Pearson => CorrelationMethod::Spearman,
+            CorrelationMethod::Spearman => CorrelationMethod::Pearson,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CorrelationMethod::Pearson => "Pearson",
+            CorrelationMethod::Spearman => "Spearman",
+        }
+    }
+}
+
+/// Whether the correlations are drawn as the matrix or as a list of the strongest pairs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CorrelationLayout {
+    /// The list in areas below `[correlation] compact_width`/`compact_height`, the matrix otherwise
+    #[default]
+    Auto,
+    Matrix,
+    TopPairs,
+}
+
+impl CorrelationLayout {
+    /// The layout after this one in the cycle key's order
+    pub fn cycled(self) -> Self {
+        match self {
+            CorrelationLayout::Auto => CorrelationLayout::Matrix,
+            CorrelationLayout::Matrix => CorrelationLayout::TopPairs,
+            CorrelationLayout::TopPairs => CorrelationLayout::Auto,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CorrelationLayout::Auto => "auto",
+            CorrelationLayout::Matrix => "matrix",
+            CorrelationLayout::TopPairs => "top pairs",
+        }
+    }
+}
+
+/// `[correlation]` section of the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorrelationConfig {
+    /// Most recent samples each coefficient is computed over
+    pub window: usize,
+
+    /// Recalculations of each pair the trend arrows are fitted over
+    pub trend_points: usize,
+
+    /// Coefficients with a larger p-value are drawn dimmed as not significant
+    pub alpha: f64,
+
+    /// Pairs listed by the compact layout
+    pub top_pairs: usize,
+
+    /// Pairs with a weaker |r| are left out of the compact layout
+    pub min_abs_r: f64,
+
+    /// The automatic layout lists the top pairs in areas narrower than this
+    pub compact_width: u16,
+
+    /// ... or shorter than this
+    pub compact_height: u16,
+}
+
+impl CorrelationConfig {
+    /// Check that the window fits the kept history and alpha and the minimum |r| are in range
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_WINDOW..=HISTORY_LEN).contains(&self.window) {
+            return Err(anyhow!(
+                "correlation.window: expected {} to {} samples, got {}",
+                MIN_WINDOW,
+                HISTORY_LEN,
+                self.window
+            ));
+        }
+        if !(self.alpha > 0.0 && self.alpha < 1.0) {
+            return Err(anyhow!("correlation.alpha: expected a value between 0.0 and 1.0, got {}", self.alpha));
+        }
+        if !(0.0..=1.0).contains(&self.min_abs_r) {
+            return Err(anyhow!(
+                "correlation.min_abs_r: expected a value from 0.0 to 1.0, got {}",
+                self.min_abs_r
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for CorrelationConfig {
+    fn default() -> Self {
+        Self {
+            window: 30,
+            trend_points: 10,
+            alpha: 0.05,
+            top_pairs: 8,
+            min_abs_r: 0.2,
+            compact_width: 60,
+            compact_height: 20,
+        }
+    }
 }
 
 /// Correlation widget for metric analysis
@@ -25,90 +239,226 @@ pub struct CorrelationWidget {
     /// Correlation data
     pub correlations: Vec<CorrelationData>,
     
-    /// Available metrics
+    /// Metrics of the matrix rows and columns, set by `set_metrics`
     pub metrics: Vec<String>,
     
     /// Title
     pub title: String,
+
+    /// Coefficient used by `calculate_correlation`
+    pub method: CorrelationMethod,
+
+    /// Highlighted matrix cell as `(row, column)` indexes into `metrics`
+    pub selected: (usize, usize),
+
+    /// Whether the widget has the focus
+    pub is_selected: bool,
+
+    /// Matrix, top pairs or either depending on the area
+    pub layout: CorrelationLayout,
+
+    /// Top pair count, minimum |r| and size thresholds of the compact layout
+    pub config: CorrelationConfig,
+
+    /// Whether the last render listed the top pairs, which arrows then move through
+    compact: cell::Cell<bool>,
+
+    /// First visible `(row, column)`, moved by rendering to keep the selection in view
+    scroll: cell::Cell<(usize, usize)>,
+
+    /// Rows shown by the last render, the step of `page_selection`
+    page_rows: cell::Cell<usize>,
+}
+
+/// Part of the matrix that fits the widget
+struct Viewport {
+    rows: Range<usize>,
+    columns: Range<usize>,
+    /// Width of the metric name column
+    label_width: u16,
+    /// Width of every value column
+    cell_width: u16,
+}
+
+impl Viewport {
+    fn column_widths(&self) -> Vec<Constraint> {
+        std::iter::once(Constraint::Length(self.label_width))
+            .chain(self.columns.clone().map(|_| Constraint::Length(self.cell_width)))
+            .collect()
+    }
 }
 
 impl CorrelationWidget {
     pub fn new(title: String) -> Self {
         Self {
             correlations: Vec::new(),
-            metrics: vec![
-                "Latency".to_string(),
-                "Throughput".to_string(),
-                "Packet Loss".to_string(),
-                "RTT".to_string(),
-                "Jitter".to_string(),
-                "Retransmits".to_string(),
-                "Connections".to_string(),
-                "Errors".to_string(),
-            ],
+            metrics: Vec::new(),
             title,
+            method: CorrelationMethod::default(),
+            selected: (0, 1),
+            is_selected: false,
+            layout: CorrelationLayout::default(),
+            config: CorrelationConfig::default(),
+            compact: cell::Cell::new(false),
+            scroll: cell::Cell::new((0, 0)),
+            page_rows: cell::Cell::new(1),
+        }
+    }
+
+    /// Replace the matrix metrics, keeping the highlighted pair when both are still there
+    pub fn set_metrics(&mut self, metrics: Vec<String>) {
+        if metrics == self.metrics {
+            return;
+        }
+        let index_of = |old: usize| {
+            let name = self.metrics.get(old)?;
+            metrics.iter().position(|metric| metric == name)
+        };
+        let (row, column) = self.selected;
+        if let (Some(row), Some(column)) = (index_of(row), index_of(column)) {
+            self.selected = (row, column);
         }
+        self.metrics = metrics;
+        self.move_selection(0, 0);
+    }
+
+    /// Move the highlighted cell, staying inside the matrix
+    ///
+    /// While the top pairs are listed, `rows` moves through the list instead.
+    pub fn move_selection(&mut self, rows: isize, columns: isize) {
+        if self.compact.get() {
+            self.move_in_list(rows);
+            return;
+        }
+        let last = self.metrics.len().saturating_sub(1);
+        let (row, column) = self.selected;
+        self.selected = (
+            row.saturating_add_signed(rows).min(last),
+            column.saturating_add_signed(columns).min(last),
+        );
+    }
+
+    /// Select the pair `rows` lines away in the top pairs list, the first one without a listed selection
+    fn move_in_list(&mut self, rows: isize) {
+        let pairs = self.top_pairs();
+        let Some(last) = pairs.len().checked_sub(1) else {
+            return;
+        };
+        let index = match self.selected_index(&pairs) {
+            Some(index) => index.saturating_add_signed(rows).min(last),
+            None => 0,
+        };
+        let pair = (pairs[index].metric1.clone(), pairs[index].metric2.clone());
+        self.select_pair(&pair.0, &pair.1);
+    }
+
+    /// Highlight the cell of `metric1` against `metric2`
+    fn select_pair(&mut self, metric1: &str, metric2: &str) {
+        let index_of = |name: &str| self.metrics.iter().position(|metric| metric == name);
+        if let (Some(row), Some(column)) = (index_of(metric1), index_of(metric2)) {
+            self.selected = (row, column);
+        }
+    }
+
+    /// The strongest pairs above `config.min_abs_r`, strongest first
+    fn top_pairs(&self) -> Vec<&CorrelationData> {
+        let mut pairs: Vec<&CorrelationData> = self
+            .correlations
+            .iter()
+            .filter(|c| c.metric1 != c.metric2 && c.correlation.abs() >= self.config.min_abs_r)
+            .collect();
+        pairs.sort_by(|a, b| b.correlation.abs().total_cmp(&a.correlation.abs()));
+        pairs.truncate(self.config.top_pairs);
+        pairs
+    }
+
+    /// Position of the highlighted pair in `pairs`, in either order
+    fn selected_index(&self, pairs: &[&CorrelationData]) -> Option<usize> {
+        let (metric1, metric2) = self.selected_pair()?;
+        pairs.iter().position(|c| {
+            (c.metric1 == metric1 && c.metric2 == metric2) || (c.metric1 == metric2 && c.metric2 == metric1)
+        })
+    }
+
+    /// Whether `area` gets the top pairs list rather than the matrix
+    fn is_compact(&self, area: Rect) -> bool {
+        match self.layout {
+            CorrelationLayout::Auto => {
+                area.width < self.config.compact_width || area.height < self.config.compact_height
+            }
+            CorrelationLayout::Matrix => false,
+            CorrelationLayout::TopPairs => true,
+        }
+    }
+
+    /// Move the highlighted cell by `pages` screens of rows
+    pub fn page_selection(&mut self, pages: isize) {
+        let rows = self.page_rows.get().max(1) as isize;
+        self.move_selection(pages.saturating_mul(rows), 0);
+    }
+
+    /// Metric pair under the highlighted cell, `None` on the diagonal
+    pub fn selected_pair(&self) -> Option<(&str, &str)> {
+        let (row, column) = self.selected;
+        if row == column {
+            return None;
+        }
+        Some((self.metrics.get(row)?.as_str(), self.metrics.get(column)?.as_str()))
     }
 
     /// Add correlation data
-    pub fn add_correlation(&mut self, metric1: String, metric2: String, correlation: f64, significance: f64) {
+    pub fn add_correlation(&mut self, metric1: String, metric2: String, correlation: f64, p_value: f64) {
         let data = CorrelationData {
             metric1,
             metric2,
             correlation,
-            significance,
+            p_value,
+            trend: None,
         };
         self.correlations.push(data);
     }
 
     /// Calculate correlation between two data series
-    /// Returns Pearson correlation coefficient
+    /// Returns the coefficient for the selected `method`
     pub fn calculate_correlation(&self, data1: &[f64], data2: &[f64]) -> f64 {
         if data1.len() != data2.len() || data1.is_empty() {
             return 0.0;
         }
 
-        let n = data1.len() as f64;
-        let mean1 = data1.iter().sum::<f64>() / n;
-        let mean2 = data2.iter().sum::<f64>() / n;
-
-        let mut numerator = 0.0;
-        let mut sum_sq1 = 0.0;
-        let mut sum_sq2 = 0.0;
-
-        for (x, y) in data1.iter().zip(data2.iter()) {
-            let dx = x - mean1;
-            let dy = y - mean2;
-            numerator += dx * dy;
-            sum_sq1 += dx * dx;
-            sum_sq2 += dy * dy;
-        }
-
-        // Check for zero variance (constant values)
-        if sum_sq1 == 0.0 || sum_sq2 == 0.0 {
+        // Drop pairs where either side is undefined so one NaN can't poison the sums
+        let pairs: Vec<(f64, f64)> = data1
+            .iter()
+            .zip(data2.iter())
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|(&x, &y)| (x, y))
+            .collect();
+        if pairs.len() < 2 {
             return 0.0;
         }
 
-        let denominator = (sum_sq1 * sum_sq2).sqrt();
-        if denominator == 0.0 {
-            return 0.0;
+        match self.method {
+            CorrelationMethod::Pearson => pearson(&pairs),
+            CorrelationMethod::Spearman => {
+                let xs: Vec<f64> = pairs.iter().map(|(x, _)| *x).collect();
+                let ys: Vec<f64> = pairs.iter().map(|(_, y)| *y).collect();
+                let ranked: Vec<(f64, f64)> = ranks(&xs).into_iter().zip(ranks(&ys)).collect();
+                pearson(&ranked)
+            }
         }
+    }
 
-        let correlation = numerator / denominator;
-        
-        // Clamp to [-1, 1] range
-        correlation.max(-1.0).min(1.0)
+    /// Get color for correlation strength, from the top of the heat scale for strong ones
+    fn get_correlation_color(&self, correlation: f64, theme: &Theme) -> Color {
+        theme.heat_color(correlation.abs(), 0.0, 1.0)
     }
 
-    /// Get color for correlation strength
-    fn get_correlation_color(&self, correlation: f64) -> Color {
-        let abs_corr = correlation.abs();
-        match abs_corr {
-            x if x >= 0.8 => Color::Red,
-            x if x >= 0.6 => Color::LightRed,
-            x if x >= 0.4 => Color::Yellow,
-            x if x >= 0.2 => Color::LightGreen,
-            _ => Color::Green,
+    /// Color of a coefficient by strength, dimmed when not significant at `config.alpha`
+    fn coefficient_style(&self, pair: Option<&CorrelationData>, theme: &Theme) -> Style {
+        match pair {
+            Some(pair) if pair.p_value <= self.config.alpha => {
+                Style::default().fg(self.get_correlation_color(pair.correlation, theme))
+            }
+            _ => Style::default().fg(theme.muted).add_modifier(Modifier::DIM),
         }
     }
 
@@ -124,103 +474,528 @@ impl CorrelationWidget {
         }
     }
 
-    /// Render the correlation widget
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        let chunks = Layout::default()
+    /// Title, matrix and legend areas of the widget drawn over `area`
+    fn layout(area: Rect) -> [Rect; 3] {
+        Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Title
                 Constraint::Min(0),    // Correlation matrix
                 Constraint::Length(4), // Legend
             ])
-            .split(area);
+            .areas(area)
+    }
+
+    /// Matrix cell `(row, column)` drawn at `position` when the widget was rendered over `area`
+    ///
+    /// Follows the table layout of `render_correlation_matrix`: a header row,
+    /// then one row per visible metric, each after a column of metric names.
+    pub fn cell_at(&self, area: Rect, position: Position) -> Option<(usize, usize)> {
+        if self.correlations.is_empty() {
+            return None;
+        }
+        let matrix = Block::default().borders(Borders::ALL).inner(Self::layout(area)[1]);
+        if !matrix.contains(position) {
+            return None;
+        }
+        let viewport = self.viewport(matrix);
+        let row = usize::from(position.y - matrix.y).checked_sub(1)?;
+        let columns = Layout::horizontal(viewport.column_widths())
+            .flex(Flex::Start)
+            .spacing(COLUMN_SPACING)
+            .split(matrix);
+        let column = columns
+            .iter()
+            .position(|column| column.width > 0 && (column.x..column.right()).contains(&position.x))?
+            .checked_sub(1)?;
+        let cell = (viewport.rows.start + row, viewport.columns.start + column);
+        (viewport.rows.contains(&cell.0) && viewport.columns.contains(&cell.1)).then_some(cell)
+    }
+
+    /// Select the matrix cell at `position`, as found by `cell_at`, or the listed pair there
+    pub fn select_at(&mut self, area: Rect, position: Position) -> bool {
+        if self.is_compact(area) {
+            let Some(pair) = self.pair_at(area, position) else {
+                return false;
+            };
+            let pair = (pair.metric1.clone(), pair.metric2.clone());
+            self.select_pair(&pair.0, &pair.1);
+            return true;
+        }
+        let Some(cell) = self.cell_at(area, position) else {
+            return false;
+        };
+        self.selected = cell;
+        true
+    }
+
+    /// Listed pair drawn at `position` when the top pairs were rendered over `area`
+    fn pair_at(&self, area: Rect, position: Position) -> Option<&CorrelationData> {
+        let list = Block::default().borders(Borders::ALL).inner(area);
+        if !list.contains(position) {
+            return None;
+        }
+        let pairs = self.top_pairs();
+        let shown = self.listed_range(&pairs, list.height);
+        pairs.get(shown.start + usize::from(position.y - list.y)).copied()
+    }
+
+    /// Lines of `pairs` that fit `height`, scrolled to the highlighted pair
+    fn listed_range(&self, pairs: &[&CorrelationData], height: u16) -> Range<usize> {
+        let height = usize::from(height).max(1);
+        let first = self
+            .selected_index(pairs)
+            .map_or(0, |index| (index + 1).saturating_sub(height));
+        first..(first + height).min(pairs.len())
+    }
+
+    /// Rows and columns of the matrix that fit `matrix`, scrolled so the selection is in view
+    ///
+    /// Starts from the last scroll position and moves it only as far as needed.
+    /// Columns are as wide as the longest visible metric name.
+    fn viewport(&self, matrix: Rect) -> Viewport {
+        let count = self.metrics.len();
+        let (row, column) = self.selected;
+        let (mut first_row, mut first_column) = self.scroll.get();
+
+        // One line goes to the header
+        let rows = usize::from(matrix.height.saturating_sub(1)).max(1);
+        if row < first_row {
+            first_row = row;
+        } else if row >= first_row + rows {
+            first_row = row + 1 - rows;
+        }
+        first_row = first_row.min(count.saturating_sub(rows));
+        let rows = first_row..(first_row + rows).min(count);
+
+        let label_width = self.metrics[rows.clone()]
+            .iter()
+            .map(|metric| metric.chars().count())
+            .chain([6]) // "Metric"
+            .max()
+            .map_or(MIN_CELL_WIDTH, |width| (width as u16).min(MAX_CELL_WIDTH));
+        let room = matrix.width.saturating_sub(label_width);
+
+        first_column = first_column.min(column);
+        while first_column < column && !self.fitting_columns(first_column, room).contains(&column) {
+            first_column += 1;
+        }
+        // Use the room freed by a wider terminal instead of leaving it blank
+        while first_column > 0 && self.fitting_columns(first_column - 1, room).end == count {
+            first_column -= 1;
+        }
+        let columns = self.fitting_columns(first_column, room);
+        let cell_width = self.cell_width(columns.clone());
+
+        Viewport { rows, columns, label_width, cell_width }
+    }
+
+    /// Columns from `first` on that fit in `room`, at least one
+    fn fitting_columns(&self, first: usize, room: u16) -> Range<usize> {
+        let count = self.metrics.len();
+        let mut end = (first + 1).min(count);
+        while end < count {
+            let width = self.cell_width(first..end + 1) + COLUMN_SPACING;
+            if u32::from(width) * (end + 1 - first) as u32 > u32::from(room) {
+                break;
+            }
+            end += 1;
+        }
+        first..end
+    }
+
+    /// Width of the value columns when `columns` are shown
+    fn cell_width(&self, columns: Range<usize>) -> u16 {
+        self.metrics[columns]
+            .iter()
+            .map(|metric| (metric.chars().count() as u16).min(MAX_CELL_WIDTH))
+            .fold(MIN_CELL_WIDTH, u16::max)
+    }
+
+    /// Render the correlation widget
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let compact = self.is_compact(area);
+        self.compact.set(compact);
+        if compact {
+            self.render_top_pairs(f, area, theme);
+            return;
+        }
+
+        let chunks = Self::layout(area);
 
         // Title
-        self.render_title(f, chunks[0]);
+        self.render_title(f, chunks[0], theme);
         
         // Correlation matrix
-        self.render_correlation_matrix(f, chunks[1]);
+        self.render_correlation_matrix(f, chunks[1], theme);
         
         // Legend
-        self.render_legend(f, chunks[2]);
+        self.render_legend(f, chunks[2], theme);
+    }
+
+    /// `Pearson, 30 samples, auto: matrix` or `Spearman, 50 samples, top pairs`
+    fn heading(&self, compact: bool) -> String {
+        let shown = if compact { CorrelationLayout::TopPairs } else { CorrelationLayout::Matrix };
+        let layout = match self.layout {
+            CorrelationLayout::Auto => format!("auto: {}", shown.name()),
+            layout => layout.name().to_string(),
+        };
+        format!("{} ({}, {} samples, {})", self.title, self.method.name(), self.config.window, layout)
     }
 
-    fn render_title(&self, f: &mut Frame, area: Rect) {
-        let title = Paragraph::new(self.title.clone())
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL));
+    fn render_title(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let title = Paragraph::new(self.heading(false))
+            .style(title_style(theme, self.is_selected))
+            .block(Block::default().borders(Borders::ALL).border_style(border_style(theme, self.is_selected)));
         f.render_widget(title, area);
     }
 
-    fn render_correlation_matrix(&self, f: &mut Frame, area: Rect) {
+    fn render_correlation_matrix(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         // Show matrix even if correlations are empty (they're being recalculated)
         // Only show empty message if we truly have no data
         if self.correlations.is_empty() {
             // Show a brief message that correlations are being calculated
             let empty_text = "Recalculating correlations...\nPlease wait a moment";
             let empty_paragraph = Paragraph::new(empty_text)
-                .style(Style::default().fg(Color::Yellow))
+                .style(Style::default().fg(theme.accent))
                 .block(Block::default().borders(Borders::ALL).title("QUIC Metrics Correlation"));
             f.render_widget(empty_paragraph, area);
             return;
         }
 
+        let block = Block::default().borders(Borders::ALL);
+        let viewport = self.viewport(block.inner(area));
+        self.scroll.set((viewport.rows.start, viewport.columns.start));
+        self.page_rows.set(viewport.rows.len());
+
         // Create correlation matrix table
         let mut rows = Vec::new();
         
         // Header row
-        let mut header_cells = vec![Cell::from("Metric").style(Style::default().fg(Color::Yellow))];
-        for metric in &self.metrics {
-            header_cells.push(Cell::from(metric.as_str()).style(Style::default().fg(Color::Yellow)));
+        let mut header_cells = vec![Cell::from("Metric").style(Style::default().fg(theme.accent))];
+        for metric in &self.metrics[viewport.columns.clone()] {
+            header_cells.push(Cell::from(metric.as_str()).style(Style::default().fg(theme.accent)));
         }
         rows.push(Row::new(header_cells));
 
         // Data rows
-        for (i, metric1) in self.metrics.iter().enumerate() {
-            let mut cells = vec![Cell::from(metric1.as_str()).style(Style::default().fg(Color::Yellow))];
+        for (i, metric1) in self.metrics.iter().enumerate().take(viewport.rows.end).skip(viewport.rows.start) {
+            let mut cells = vec![Cell::from(metric1.as_str()).style(Style::default().fg(theme.accent))];
             
-            for (j, metric2) in self.metrics.iter().enumerate() {
+            for (j, metric2) in self.metrics.iter().enumerate().take(viewport.columns.end).skip(viewport.columns.start) {
+                let highlight = if (i, j) == self.selected {
+                    Modifier::REVERSED
+                } else {
+                    Modifier::empty()
+                };
                 if i == j {
-                    cells.push(Cell::from("1.00").style(Style::default().fg(Color::Green)));
+                    cells.push(Cell::from("1.00").style(Style::default().fg(theme.muted).add_modifier(highlight)));
                 } else {
                     // Find correlation between these metrics
-                    let correlation = self.correlations
+                    let pair = self.correlations
                         .iter()
                         .find(|c| (c.metric1 == *metric1 && c.metric2 == *metric2) || 
-                                 (c.metric1 == *metric2 && c.metric2 == *metric1))
-                        .map(|c| c.correlation)
-                        .unwrap_or(0.0);
+                                 (c.metric1 == *metric2 && c.metric2 == *metric1));
+                    let (correlation, trend) = pair.map_or((0.0, None), |c| (c.correlation, c.trend));
                     
-                    let color = self.get_correlation_color(correlation);
-                    let formatted = format!("{:.2}", correlation);
-                    cells.push(Cell::from(formatted).style(Style::default().fg(color)));
+                    let formatted = format!("{:.2}{}", correlation, trend.map_or("", Trend::arrow));
+                    let style = self.coefficient_style(pair, theme).add_modifier(highlight);
+                    cells.push(Cell::from(formatted).style(style));
                 }
             }
             
             rows.push(Row::new(cells));
         }
 
-        let widths = vec![Constraint::Length(12); self.metrics.len() + 1];
-        let table = Table::new(rows, widths)
-            .block(Block::default().borders(Borders::ALL));
+        // Where the viewport is, once the matrix no longer fits
+        let count = self.metrics.len();
+        let block = if viewport.rows.len() < count || viewport.columns.len() < count {
+            let position = format!(
+                " rows {}-{}/{}, columns {}-{}/{} ",
+                viewport.rows.start + 1,
+                viewport.rows.end,
+                count,
+                viewport.columns.start + 1,
+                viewport.columns.end,
+                count
+            );
+            block.title_bottom(Line::styled(position, Style::default().fg(theme.muted)).right_aligned())
+        } else {
+            block
+        };
+
+        let table = Table::new(rows, viewport.column_widths())
+            .column_spacing(COLUMN_SPACING)
+            .flex(Flex::Start)
+            .block(block);
 
         f.render_widget(table, area);
     }
 
-    fn render_legend(&self, f: &mut Frame, area: Rect) {
-        let legend_text = "Correlation Strength: Red (Strong) | Yellow (Moderate) | Green (Weak)";
+    /// One line per strong pair, e.g. `▲ Latency ↔ Packet Loss: +0.87 (very strong)`
+    fn render_top_pairs(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style(theme, self.is_selected))
+            .title(Line::styled(self.heading(true), title_style(theme, self.is_selected)))
+            .title_bottom(
+                Line::styled(format!(" |r| >= {:.2} ", self.config.min_abs_r), Style::default().fg(theme.muted))
+                    .right_aligned(),
+            );
+
+        let pairs = self.top_pairs();
+        if pairs.is_empty() {
+            let message = if self.correlations.is_empty() {
+                "Recalculating correlations...".to_string()
+            } else {
+                format!("No pair with |r| >= {:.2} yet", self.config.min_abs_r)
+            };
+            f.render_widget(Paragraph::new(message).style(Style::default().fg(theme.muted)).block(block), area);
+            return;
+        }
+
+        let shown = self.listed_range(&pairs, block.inner(area).height);
+        self.page_rows.set(shown.len());
+        let selected = self.selected_index(&pairs);
+        let lines: Vec<Line> = pairs
+            .iter()
+            .enumerate()
+            .take(shown.end)
+            .skip(shown.start)
+            .map(|(index, pair)| {
+                let style = self.coefficient_style(Some(pair), theme);
+                let arrow = if pair.correlation >= 0.0 { "▲" } else { "▼" };
+                let line = Line::from(vec![
+                    Span::styled(format!("{} ", arrow), style),
+                    Span::styled(format!("{} ↔ {}: ", pair.metric1, pair.metric2), Style::default().fg(theme.text)),
+                    Span::styled(
+                        format!(
+                            "{:+.2} ({}, p {}){}",
+                            pair.correlation,
+                            self.get_correlation_strength(pair.correlation).to_lowercase(),
+                            format_p_value(pair.p_value),
+                            pair.trend.map_or(String::new(), |trend| format!(" {}", trend.arrow()))
+                        ),
+                        style.add_modifier(Modifier::BOLD),
+                    ),
+                ]);
+                if selected == Some(index) {
+                    line.patch_style(Modifier::REVERSED)
+                } else {
+                    line
+                }
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn render_legend(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let mut scale = vec![Span::raw("Correlation Strength: Weak ")];
+        scale.extend(theme.heat.iter().map(|&color| Span::styled("█", Style::default().fg(color))));
+        scale.push(Span::raw(" Strong"));
+        let legend_text = vec![
+            Line::from(scale),
+            Line::from("Arrows, PgUp/PgDn or a click select a pair, Enter or a click shows its scatter plot and lag profile"),
+            Line::from(format!(
+                "↑/↓: strengthening/weakening over the last recalculations; dimmed: p > {}",
+                self.config.alpha
+            )),
+        ];
         let legend = Paragraph::new(legend_text)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         
         f.render_widget(legend, area);
     }
 }
 
+/// Pearson coefficient of finite pairs, 0.0 when either side is constant
+pub(crate) fn pearson(pairs: &[(f64, f64)]) -> f64 {
+    let n = pairs.len() as f64;
+    let mean1 = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean2 = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let mut numerator = 0.0;
+    let mut sum_sq1 = 0.0;
+    let mut sum_sq2 = 0.0;
+
+    for (x, y) in pairs {
+        let dx = x - mean1;
+        let dy = y - mean2;
+        numerator += dx * dy;
+        sum_sq1 += dx * dx;
+        sum_sq2 += dy * dy;
+    }
+
+    // Check for zero variance (constant values)
+    if sum_sq1 == 0.0 || sum_sq2 == 0.0 {
+        return 0.0;
+    }
+
+    let denominator = (sum_sq1 * sum_sq2).sqrt();
+    if denominator == 0.0 || !denominator.is_finite() {
+        return 0.0;
+    }
+
+    let correlation = numerator / denominator;
+    if !correlation.is_finite() {
+        return 0.0;
+    }
+    
+    // Clamp to [-1, 1] range
+    correlation.clamp(-1.0, 1.0)
+}
+
+/// Two-sided p-value of coefficient `r` over `n` samples against no correlation
+///
+/// Uses the t statistic `r * sqrt((n - 2) / (1 - r²))` with `n - 2` degrees of
+/// freedom, exact for Pearson and the usual approximation for Spearman.
+pub fn correlation_p_value(r: f64, n: usize) -> f64 {
+    if n < 3 || !r.is_finite() {
+        return 1.0;
+    }
+    let df = (n - 2) as f64;
+    let r2 = (r * r).min(1.0);
+    if r2 >= 1.0 {
+        return 0.0;
+    }
+    // P(|T| > |t|) = I_x(df / 2, 1 / 2) with x = df / (df + t²) = 1 - r²
+    regularized_incomplete_beta(df / 2.0, 0.5, 1.0 - r2)
+}
+
+/// `0.012`, or `<0.001` for anything smaller
+fn format_p_value(p: f64) -> String {
+    if p < 0.001 {
+        "<0.001".to_string()
+    } else {
+        format!("{:.3}", p)
+    }
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`
+///
+/// Continued fraction evaluated with the modified Lentz method, on whichever
+/// side of the mean converges quickly.
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front = (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 200;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let nudge = |value: f64| if value.abs() < TINY { TINY } else { value };
+    let mut c = 1.0;
+    let mut d = 1.0 / nudge(1.0 - (a + b) * x / (a + 1.0));
+    let mut result = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / nudge(1.0 + even * d);
+        c = nudge(1.0 + even / c);
+        result *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / nudge(1.0 + odd * d);
+        c = nudge(1.0 + odd / c);
+        let step = d * c;
+        result *= step;
+        if (step - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    result
+}
+
+/// Natural log of the gamma function, by the Lanczos approximation
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection, for the half-integer arguments of small sample counts
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.0));
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// 1-based ranks of `values`; tied values share the mean of the ranks they span
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end) as f64 / 2.0 + 1.0;
+        for &index in &order[start..=end] {
+            ranks[index] = rank;
+        }
+        start = end + 1;
+    }
+    ranks
+}
+
+/// Selection, matrix scroll position and open pair detail, as `cursor` saves them
+///
+/// The pair is kept by name, so it still points at the same metrics when
+/// new ones join the matrix in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationCursor {
+    pair: Option<(String, String)>,
+    scroll: (usize, usize),
+    detail: Option<(String, String)>,
+}
+
 /// QUIC Metrics Correlation Widget
 pub struct QUICCorrelationWidget {
     correlation: CorrelationWidget,
     metric_data: HashMap<String, Vec<f64>>,
+    /// Metrics in the order their first sample arrived, which is the matrix order
+    order: Vec<String>,
+    /// Last `trend_points` coefficients of each pair, oldest first
+    trends: HashMap<(String, String), VecDeque<f64>>,
+    /// Pair shown in the detail view instead of the matrix
+    detail: Option<(String, String)>,
+    scatter: ScatterPlotWidget,
+    /// Time between samples, used to express lags in seconds
+    sample_interval: Duration,
+}
+
+impl Default for QUICCorrelationWidget {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl QUICCorrelationWidget {
@@ -228,20 +1003,201 @@ impl QUICCorrelationWidget {
         Self {
             correlation: CorrelationWidget::new("QUIC Metrics Correlation".to_string()),
             metric_data: HashMap::new(),
+            order: Vec::new(),
+            trends: HashMap::new(),
+            detail: None,
+            scatter: ScatterPlotWidget::new(100),
+            sample_interval: Duration::from_millis(100),
         }
     }
 
+    /// Set the time between samples, used to express lags in seconds
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.set_sample_interval(interval);
+        self
+    }
+
+    /// Change the time between samples, after the update interval changed
+    pub fn set_sample_interval(&mut self, interval: Duration) {
+        self.sample_interval = interval;
+    }
+
+    /// Use the `[correlation]` settings instead of the defaults
+    pub fn with_config(mut self, config: CorrelationConfig) -> Self {
+        self.correlation.config = config;
+        self
+    }
+
+    /// Grow (or with a negative `steps`, shrink) the correlation window by `WINDOW_STEP` samples
+    ///
+    /// Trends restart, as coefficients over different windows do not compare.
+    pub fn resize_window(&mut self, steps: isize) {
+        let config = &mut self.correlation.config;
+        let window = config
+            .window
+            .saturating_add_signed(steps.saturating_mul(WINDOW_STEP as isize))
+            .clamp(MIN_WINDOW, HISTORY_LEN);
+        if window != config.window {
+            config.window = window;
+            self.trends.clear();
+            self.update_correlations();
+        }
+    }
+
+    /// Step through the automatic layout, the matrix and the top pairs list
+    pub fn cycle_layout(&mut self) {
+        self.correlation.layout = self.correlation.layout.cycled();
+    }
+
+    /// Drop all collected data, keeping the method, selection and interval
+    pub fn clear(&mut self) {
+        self.metric_data.clear();
+        self.order.clear();
+        self.trends.clear();
+        self.correlation.correlations.clear();
+        self.correlation.set_metrics(Vec::new());
+    }
+
+    /// Move the highlighted matrix cell
+    pub fn move_selection(&mut self, rows: isize, columns: isize) {
+        self.correlation.move_selection(rows, columns);
+    }
+
+    /// Move the highlighted matrix cell a screen of rows up or down
+    pub fn page_selection(&mut self, pages: isize) {
+        self.correlation.page_selection(pages);
+    }
+
+    /// Highlight the widget as focused
+    pub fn set_selected(&mut self, selected: bool) {
+        self.correlation.is_selected = selected;
+    }
+
+    /// Select the matrix cell under `position`, for a widget drawn over `area`
+    ///
+    /// Only while the matrix itself is shown; returns whether a cell was hit.
+    pub fn select_at(&mut self, area: Rect, position: Position) -> bool {
+        if self.detail.is_some() || self.metrics_with_enough_data().len() < 2 {
+            return false;
+        }
+        self.correlation.select_at(area, position)
+    }
+
+    /// Show the scatter plot and lag profile of the highlighted pair
+    pub fn open_detail(&mut self) {
+        self.detail = self
+            .correlation
+            .selected_pair()
+            .map(|(metric1, metric2)| (metric1.to_string(), metric2.to_string()));
+    }
+
+    /// Return from the pair detail view to the matrix
+    pub fn close_detail(&mut self) {
+        self.detail = None;
+    }
+
+    pub fn is_detail_open(&self) -> bool {
+        self.detail.is_some()
+    }
+
+    /// Where the selection, scroll position and detail view stand
+    pub fn cursor(&self) -> CorrelationCursor {
+        CorrelationCursor {
+            pair: self
+                .correlation
+                .selected_pair()
+                .map(|(metric1, metric2)| (metric1.to_string(), metric2.to_string())),
+            scroll: self.correlation.scroll.get(),
+            detail: self.detail.clone(),
+        }
+    }
+
+    /// Go back to a `cursor` taken earlier
+    ///
+    /// A pair that has left the matrix keeps the current selection.
+    pub fn set_cursor(&mut self, cursor: CorrelationCursor) {
+        let metrics = &self.correlation.metrics;
+        let index_of = |name: &str| metrics.iter().position(|metric| metric == name);
+        if let Some((row, column)) = cursor
+            .pair
+            .and_then(|(metric1, metric2)| Some((index_of(&metric1)?, index_of(&metric2)?)))
+        {
+            self.correlation.selected = (row, column);
+        }
+        self.correlation.scroll.set(cursor.scroll);
+        self.detail = cursor.detail;
+    }
+
+    /// Correlate `metric1` against `metric2` shifted by up to `max_lag` samples either way
+    ///
+    /// Both series are aligned on their most recent sample. Returns `None` when
+    /// either metric has no data or no lag has enough overlap.
+    pub fn calculate_lagged_correlation(&self, metric1: &str, metric2: &str, max_lag: usize) -> Option<LagProfile> {
+        let data1 = self.metric_data.get(metric1)?;
+        let data2 = self.metric_data.get(metric2)?;
+        let len = data1.len().min(data2.len());
+        let data1 = &data1[data1.len() - len..];
+        let data2 = &data2[data2.len() - len..];
+
+        let max_lag = max_lag.min(len.saturating_sub(MIN_LAG_OVERLAP)) as i64;
+        let correlations: Vec<(i64, f64)> = (-max_lag..=max_lag)
+            .map(|lag| {
+                let shift = lag.unsigned_abs() as usize;
+                let (x, y) = if lag >= 0 {
+                    (&data1[..len - shift], &data2[shift..])
+                } else {
+                    (&data1[shift..], &data2[..len - shift])
+                };
+                (lag, self.correlation.calculate_correlation(x, y))
+            })
+            .collect();
+
+        // Ties go to the smallest shift, so an instantaneous relationship reads as lag 0
+        let &(best_lag, best_correlation) = correlations.iter().min_by(|a, b| {
+            b.1.abs()
+                .total_cmp(&a.1.abs())
+                .then(a.0.unsigned_abs().cmp(&b.0.unsigned_abs()))
+        })?;
+
+        Some(LagProfile {
+            metric1: metric1.to_string(),
+            metric2: metric2.to_string(),
+            correlations,
+            best_lag,
+            best_correlation,
+        })
+    }
+
     /// Add metric data
     pub fn add_metric_data(&mut self, metric: String, value: f64) {
-        let entry = self.metric_data.entry(metric.clone()).or_insert_with(Vec::new);
+        if !self.metric_data.contains_key(&metric) {
+            self.order.push(metric.clone());
+        }
+        let entry = self.metric_data.entry(metric).or_default();
         entry.push(value);
         
-        // Keep only recent data (last 100 points)
-        if entry.len() > 100 {
+        // Keep only recent data
+        if entry.len() > HISTORY_LEN {
             entry.remove(0);
         }
     }
     
+    /// Switch between Pearson and Spearman and recompute the matrix
+    pub fn toggle_method(&mut self) {
+        self.correlation.method = self.correlation.method.toggled();
+        self.trends.clear();
+        self.update_correlations();
+    }
+
+    pub fn method(&self) -> CorrelationMethod {
+        self.correlation.method
+    }
+
+    /// Current correlation of every metric pair
+    pub fn correlations(&self) -> &[CorrelationData] {
+        &self.correlation.correlations
+    }
+
     /// Get number of data points for a metric
     pub fn get_data_points_count(&self, metric: &str) -> usize {
         self.metric_data.get(metric).map(|v| v.len()).unwrap_or(0)
@@ -256,13 +1212,8 @@ impl QUICCorrelationWidget {
     pub fn update_correlations(&mut self) {
         // Get all metrics that have data (need at least 3 points for meaningful correlation)
         let min_data_points = 3;
-        let metrics: Vec<String> = self.metric_data.keys()
-            .filter(|k| {
-                let count = self.metric_data.get(*k).map(|v| v.len()).unwrap_or(0);
-                count >= min_data_points
-            })
-            .cloned()
-            .collect();
+        let metrics: Vec<String> = self.metrics_with_enough_data().into_iter().cloned().collect();
+        self.correlation.set_metrics(metrics.clone());
         
         // Only calculate if we have at least 2 metrics with enough data
         if metrics.len() < 2 {
@@ -281,9 +1232,9 @@ impl QUICCorrelationWidget {
                 ) {
                     // Only calculate correlation if we have enough data points
                     if data1.len() >= min_data_points && data2.len() >= min_data_points {
-                        // Use the minimum length to ensure both series are aligned
-                        // Use more recent data (last N points) for better correlation
-                        let min_len = data1.len().min(data2.len()).min(50); // Use up to 50 points
+                        // Use the minimum length to ensure both series are aligned,
+                        // and only the window so a change of regime shows quickly
+                        let min_len = data1.len().min(data2.len()).min(self.correlation.config.window);
                         let data1_slice = &data1[data1.len() - min_len..];
                         let data2_slice = &data2[data2.len() - min_len..];
                         
@@ -293,15 +1244,29 @@ impl QUICCorrelationWidget {
                         
                         if has_variance1 && has_variance2 {
                             let correlation = self.correlation.calculate_correlation(data1_slice, data2_slice);
-                            let significance = correlation.abs(); // Simplified significance
+                            let samples = data1_slice
+                                .iter()
+                                .zip(data2_slice)
+                                .filter(|(x, y)| x.is_finite() && y.is_finite())
+                                .count();
+                            let p_value = correlation_p_value(correlation, samples);
                             
                             // Only add if correlation is meaningful (not NaN or infinite)
                             if correlation.is_finite() {
+                                let history = self
+                                    .trends
+                                    .entry((metrics[i].clone(), metrics[j].clone()))
+                                    .or_default();
+                                history.push_back(correlation);
+                                while history.len() > self.correlation.config.trend_points.max(1) {
+                                    history.pop_front();
+                                }
                                 new_correlations.push(CorrelationData {
                                     metric1: metrics[i].clone(),
                                     metric2: metrics[j].clone(),
                                     correlation,
-                                    significance,
+                                    p_value,
+                                    trend: Trend::of(history),
                                 });
                             }
                         }
@@ -318,18 +1283,15 @@ impl QUICCorrelationWidget {
     }
 
     /// Render the correlation widget
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         // Check if we have enough data before rendering
-        let metrics_with_data: Vec<String> = self.metric_data.keys()
-            .filter(|k| self.metric_data.get(*k).map(|v| v.len()).unwrap_or(0) >= 3)
-            .cloned()
-            .collect();
+        let metrics_with_data = self.metrics_with_enough_data();
         
         // Only show status if we don't have enough data points yet
         // If we have correlations, show them even if they're temporarily empty during recalculation
         if metrics_with_data.len() < 2 {
-            let data_counts: Vec<String> = self.metric_data.iter()
-                .map(|(k, v)| format!("{}: {} pts", k, v.len()))
+            let data_counts: Vec<String> = self.order.iter()
+                .map(|metric| format!("{}: {} pts", metric, self.get_data_points_count(metric)))
                 .collect();
             
             let status_text = format!(
@@ -340,14 +1302,231 @@ impl QUICCorrelationWidget {
             );
             
             let status_paragraph = Paragraph::new(status_text)
-                .style(Style::default().fg(Color::Yellow))
-                .block(Block::default().borders(Borders::ALL).title("QUIC Metrics Correlation"));
+                .style(Style::default().fg(theme.accent))
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(border_style(theme, self.correlation.is_selected))
+                        .title("QUIC Metrics Correlation"),
+                );
             f.render_widget(status_paragraph, area);
             return;
         }
         
+        if let Some((metric1, metric2)) = &self.detail {
+            self.render_pair_detail(f, area, metric1, metric2, theme);
+            return;
+        }
+
         // If we have enough data, always render the correlation matrix
         // Even if correlations are temporarily empty, they will be recalculated
-        self.correlation.render(f, area);
+        self.correlation.render(f, area, theme);
+    }
+
+    /// Metrics with the 3 points needed before the matrix replaces the status text
+    fn metrics_with_enough_data(&self) -> Vec<&String> {
+        self.order
+            .iter()
+            .filter(|metric| self.metric_data.get(*metric).is_some_and(|values| values.len() >= 3))
+            .collect()
+    }
+
+    fn render_pair_detail(&self, f: &mut Frame, area: Rect, metric1: &str, metric2: &str, theme: &Theme) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(60), // Scatter plot
+                Constraint::Percentage(40), // Lag profile
+            ])
+            .split(area);
+
+        let empty = Vec::new();
+        let data1 = self.metric_data.get(metric1).unwrap_or(&empty);
+        let data2 = self.metric_data.get(metric2).unwrap_or(&empty);
+        self.scatter.render(f, chunks[0], (metric1, data1), (metric2, data2), theme);
+        self.render_lag_detail(f, chunks[1], metric1, metric2, theme);
+    }
+
+    fn render_lag_detail(&self, f: &mut Frame, area: Rect, metric1: &str, metric2: &str, theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "Lag profile: {} vs {} ({}) - Esc to return",
+            metric1,
+            metric2,
+            self.correlation.method.name()
+        ));
+
+        let Some(profile) = self.calculate_lagged_correlation(metric1, metric2, DEFAULT_MAX_LAG) else {
+            let waiting = Paragraph::new(format!("Not enough data for {} and {} yet", metric1, metric2))
+                .style(Style::default().fg(theme.accent))
+                .block(block);
+            f.render_widget(waiting, area);
+            return;
+        };
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Best lag
+                Constraint::Min(3),    // |r| by lag
+                Constraint::Length(1), // Lag axis
+            ])
+            .split(inner);
+
+        let seconds = profile.best_lag as f64 * self.sample_interval.as_secs_f64();
+        let follower = match profile.best_lag {
+            lag if lag > 0 => format!("{} follows {}", metric2, metric1),
+            lag if lag < 0 => format!("{} follows {}", metric1, metric2),
+            _ => "no lead or lag".to_string(),
+        };
+        let color = self.correlation.get_correlation_color(profile.best_correlation, theme);
+        let summary = Paragraph::new(vec![
+            Line::styled(
+                format!(
+                    "Best lag: {:+} samples ({:+.2} s), r = {:.2} ({})",
+                    profile.best_lag,
+                    seconds,
+                    profile.best_correlation,
+                    self.correlation.get_correlation_strength(profile.best_correlation)
+                ),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Line::styled(follower, Style::default().fg(theme.muted)),
+        ]);
+        f.render_widget(summary, chunks[0]);
+
+        // Sparkline bars cannot go negative, so plot |r|; the sign is in the summary
+        let bars: Vec<u64> = profile
+            .correlations
+            .iter()
+            .map(|(_, r)| (r.abs() * 100.0).round() as u64)
+            .collect();
+        let sparkline = Sparkline::default()
+            .data(&bars)
+            .max(100)
+            .style(Style::default().fg(theme.info));
+        let width = (bars.len() as u16).min(chunks[1].width);
+        f.render_widget(sparkline, Rect { width, ..chunks[1] });
+
+        if let (Some((first, _)), Some((last, _))) = (profile.correlations.first(), profile.correlations.last()) {
+            let axis = format!("{:<w$}{:+}", first, last, w = (width as usize).saturating_sub(3).max(1));
+            f.render_widget(Paragraph::new(axis).style(Style::default().fg(theme.muted)), chunks[2]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn p_values_match_the_t_distribution() {
+        // (r, n, two-sided p) from numerical integration of the t density
+        let known = [
+            (0.5, 10, 0.141_113_281_25),
+            (0.3, 30, 0.107_245_948_06),
+            (0.6319, 10, 0.049_998_513_36),
+            (0.9, 3, 0.287_132_586_26),
+            (0.9, 5, 0.037_386_073_47),
+            (-0.2, 100, 0.046_036_286_46),
+            (0.1, 1000, 0.001_544_116_11),
+            (0.8, 4, 0.2),
+        ];
+        for (r, n, p) in known {
+            let computed = correlation_p_value(r, n);
+            assert!((computed - p).abs() < 1e-6, "r={} n={}: {} != {}", r, n, computed, p);
+        }
+    }
+
+    #[test]
+    fn p_value_edge_cases() {
+        assert_eq!(correlation_p_value(0.99, 2), 1.0);
+        assert_eq!(correlation_p_value(f64::NAN, 50), 1.0);
+        assert_eq!(correlation_p_value(1.0, 10), 0.0);
+        assert_eq!(correlation_p_value(-1.0, 10), 0.0);
+        assert!((correlation_p_value(0.0, 50) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn pearson_of_known_series() {
+        let rising: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 2.0 * i as f64 + 1.0)).collect();
+        assert!((pearson(&rising) - 1.0).abs() < 1e-12);
+        let falling: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, -(i as f64))).collect();
+        assert!((pearson(&falling) + 1.0).abs() < 1e-12);
+        let constant: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 3.0)).collect();
+        assert_eq!(pearson(&constant), 0.0);
+        // x = 1..5, y = 2, 4, 5, 4, 5: r = 6 / sqrt(10 * 6)
+        let pairs = [(1.0, 2.0), (2.0, 4.0), (3.0, 5.0), (4.0, 4.0), (5.0, 5.0)];
+        assert!((pearson(&pairs) - 6.0 / 60f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn insignificant_coefficients_are_dimmed() {
+        let widget = CorrelationWidget::new("test".to_string());
+        let theme = Theme::default();
+        let pair = |correlation, p_value| CorrelationData {
+            metric1: "a".to_string(),
+            metric2: "b".to_string(),
+            correlation,
+            p_value,
+            trend: None,
+        };
+        // Strong but noisy is dimmed, weak but significant is not
+        let noisy = widget.coefficient_style(Some(&pair(0.9, 0.2)), &theme);
+        assert!(noisy.add_modifier.contains(Modifier::DIM));
+        let significant = widget.coefficient_style(Some(&pair(0.2, 0.01)), &theme);
+        assert!(!significant.add_modifier.contains(Modifier::DIM));
+        assert_eq!(significant.fg, Some(theme.heat_color(0.2, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn constant_and_undefined_series_correlate_to_zero() {
+        for method in [CorrelationMethod::Pearson, CorrelationMethod::Spearman] {
+            let mut widget = CorrelationWidget::new("Correlation".to_string());
+            widget.method = method;
+            let rising = [1.0, 2.0, 3.0, 4.0, 5.0];
+            assert_eq!(widget.calculate_correlation(&rising, &[7.0; 5]), 0.0);
+            assert_eq!(widget.calculate_correlation(&[0.0; 5], &[0.0; 5]), 0.0);
+            assert_eq!(widget.calculate_correlation(&[f64::NAN; 5], &rising), 0.0);
+            assert_eq!(widget.calculate_correlation(&[], &[]), 0.0);
+
+            // Undefined pairs are dropped, the rest still correlate
+            let gappy = [1.0, f64::NAN, 3.0, f64::INFINITY, 5.0];
+            let r = widget.calculate_correlation(&gappy, &rising);
+            assert!((r - 1.0).abs() < 1e-9, "{:?}: {}", method, r);
+        }
+    }
+
+    #[test]
+    fn clicks_map_to_the_matrix_cell_under_them() {
+        let mut widget = CorrelationWidget::new("Correlation".to_string());
+        widget.layout = CorrelationLayout::Matrix;
+        let metrics = ["Latency", "Jitter", "RTT"];
+        widget.set_metrics(metrics.iter().map(|m| m.to_string()).collect());
+        widget.add_correlation("Latency".to_string(), "Jitter".to_string(), 0.8, 0.01);
+        widget.add_correlation("Latency".to_string(), "RTT".to_string(), 0.5, 0.01);
+        widget.add_correlation("Jitter".to_string(), "RTT".to_string(), -0.3, 0.01);
+
+        let area = Rect::new(0, 0, 80, 20);
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 20)).unwrap();
+        terminal.draw(|f| widget.render(f, area, &Theme::default())).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let line = |y: u16| (0..area.width).map(|x| buffer[(x, y)].symbol().to_string()).collect::<String>();
+        let find = |text: &str, from: u16| (from..area.height).find_map(|y| Some((line(y).find(text)? as u16, y)));
+
+        // The header names the columns, the first column names the rows
+        let (rtt_x, header_y) = find("RTT", 4).unwrap();
+        let (_, jitter_y) = find("Jitter", header_y + 1).unwrap();
+        assert_eq!(widget.cell_at(area, Position::new(rtt_x, jitter_y)), Some((1, 2)));
+        assert_eq!(widget.cell_at(area, Position::new(rtt_x, header_y)), None);
+        assert_eq!(widget.cell_at(area, Position::new(1, jitter_y)), None);
+        assert_eq!(widget.cell_at(area, Position::new(0, 0)), None);
+        assert_eq!(widget.cell_at(area, Position::new(rtt_x, area.height - 1)), None);
+
+        assert!(widget.select_at(area, Position::new(rtt_x, jitter_y)));
+        assert_eq!(widget.selected, (1, 2));
+        assert!(!widget.select_at(area, Position::new(0, 0)));
+        assert_eq!(widget.selected, (1, 2));
     }
 }
@@ -0,0 +1,94 @@
+//! CORS for the HTTP APIs
+//!
+//! Off by default: without CORS headers browsers refuse cross-origin reads,
+//! which suits an API meant for local tools. Listing origins in
+//! `[api.cors] allowed_origins` lets dashboards served from them call the API
+//! from the browser, including preflighted POSTs with a JSON body or a
+//! bearer token. Any origin needs `allow_any_origin = true`; a `*` in the
+//! list is refused so it cannot be turned on by accident. Requests from
+//! origins not on the list get 403.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use warp::filters::BoxedFilter;
+use warp::http::uri::Authority;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+/// How long browsers may cache a preflight response
+const PREFLIGHT_MAX_AGE_SECS: u32 = 600;
+
+/// CORS settings, under `[api.cors]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API, e.g. `https://dash.example.com`
+    pub allowed_origins: Vec<String>,
+
+    /// Allow every origin (`Access-Control-Allow-Origin` echoes the caller)
+    pub allow_any_origin: bool,
+}
+
+impl CorsConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.allow_any_origin || !self.allowed_origins.is_empty()
+    }
+
+    /// The warp wrapper, `None` when CORS is off
+    pub fn build(&self) -> Result<Option<warp::cors::Cors>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let cors = warp::cors()
+            .allow_methods(["GET", "POST", "DELETE"])
+            .allow_headers(["content-type", "authorization"])
+            .max_age(PREFLIGHT_MAX_AGE_SECS);
+        let cors = if self.allow_any_origin {
+            cors.allow_any_origin()
+        } else {
+            let origins = self
+                .allowed_origins
+                .iter()
+                .map(|origin| parse_origin(origin))
+                .collect::<Result<Vec<_>>>()?;
+            cors.allow_origins(origins)
+        };
+        Ok(Some(cors.build()))
+    }
+}
+
+/// Wrap `routes` with the configured CORS handling
+pub fn with_cors<F, R>(routes: F, config: &CorsConfig) -> Result<BoxedFilter<(Response,)>>
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    let routes = routes.map(|reply: R| reply.into_response());
+    Ok(match config.build()? {
+        Some(cors) => routes.with(cors).map(Reply::into_response).boxed(),
+        None => routes.boxed(),
+    })
+}
+
+/// Check `scheme://host[:port]` up front; warp panics on anything else
+fn parse_origin(origin: &str) -> Result<&str> {
+    if origin == "*" {
+        return Err(anyhow!(
+            "'*' is not accepted in [api.cors] allowed_origins; set allow_any_origin = true instead"
+        ));
+    }
+    let valid = origin.split_once("://").is_some_and(|(scheme, rest)| {
+        !scheme.is_empty()
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+            && !rest.contains('@')
+            && rest.parse::<Authority>().is_ok()
+    });
+    if valid {
+        Ok(origin)
+    } else {
+        Err(anyhow!(
+            "invalid CORS origin '{}', expected scheme://host[:port] without a path",
+            origin
+        ))
+    }
+}
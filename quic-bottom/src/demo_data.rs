@@ -1,12 +1,267 @@
 //! Demo data generator for QUIC Bottom
-//! 
-//! Generates realistic QUIC metrics for demonstration
+//!
+//! Generates realistic QUIC metrics for demonstration. A generator is seeded,
+//! so two with the same seed and `TrafficProfile` produce the same samples,
+//! and the profile picks the shape of the traffic.
+//!
+//! Faults (F1-F5 in the demo and analytics modes) are laid over whatever the
+//! profile generates for `[faults] duration_secs`, and logged so the session
+//! report can tell which spikes were made on purpose.
 
 use std::collections::VecDeque;
-use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::{finite_or, BBRv3Metrics};
+use crate::units::Unit;
+
+/// Names of the traffic profiles, as `--traffic` takes them
+pub const TRAFFIC_PROFILES: &[&str] = &[
+    "steady-stream",
+    "bursty-web",
+    "lossy-mobile",
+    "congestion-collapse",
+    "bbrv3-startup",
+];
+
+/// Shape of the generated traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrafficProfile {
+    /// A long transfer around 1 Gbps with mild noise
+    #[default]
+    SteadyStream,
+    /// Short bursts of page loads between near-idle stretches
+    BurstyWeb,
+    /// High, jittery latency, a few percent loss and stalls at handovers
+    LossyMobile,
+    /// A healthy link whose queue builds until throughput collapses, then recovers
+    CongestionCollapse,
+    /// BBRv3 through Startup, Drain, ProbeBW and ProbeRTT, with the `bbrv3_*` fields
+    BBRv3Startup,
+}
+
+impl TrafficProfile {
+    pub fn name(self) -> &'static str {
+        match self {
+            TrafficProfile::SteadyStream => "steady-stream",
+            TrafficProfile::BurstyWeb => "bursty-web",
+            TrafficProfile::LossyMobile => "lossy-mobile",
+            TrafficProfile::CongestionCollapse => "congestion-collapse",
+            TrafficProfile::BBRv3Startup => "bbrv3-startup",
+        }
+    }
+}
+
+impl fmt::Display for TrafficProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TrafficProfile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "steady-stream" => Ok(TrafficProfile::SteadyStream),
+            "bursty-web" => Ok(TrafficProfile::BurstyWeb),
+            "lossy-mobile" => Ok(TrafficProfile::LossyMobile),
+            "congestion-collapse" => Ok(TrafficProfile::CongestionCollapse),
+            "bbrv3-startup" => Ok(TrafficProfile::BBRv3Startup),
+            _ => Err(anyhow!(
+                "unknown traffic profile '{}' (expected one of {})",
+                s,
+                TRAFFIC_PROFILES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A condition injected on demand over the generated traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    /// Latency several times its usual level
+    LatencySpike,
+    /// 15-30% packet loss with the retransmits that go with it
+    LossBurst,
+    /// Throughput down to a few percent
+    ThroughputCollapse,
+    /// Handshakes timing out and connections failing
+    ConnectionFailures,
+    /// Hundreds of errors per sample
+    ErrorStorm,
+}
+
+impl FaultKind {
+    /// In F1-F5 order
+    pub const ALL: [FaultKind; 5] = [
+        FaultKind::LatencySpike,
+        FaultKind::LossBurst,
+        FaultKind::ThroughputCollapse,
+        FaultKind::ConnectionFailures,
+        FaultKind::ErrorStorm,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FaultKind::LatencySpike => "latency spike",
+            FaultKind::LossBurst => "packet-loss burst",
+            FaultKind::ThroughputCollapse => "throughput collapse",
+            FaultKind::ConnectionFailures => "connection-failure burst",
+            FaultKind::ErrorStorm => "error storm",
+        }
+    }
+}
+
+impl fmt::Display for FaultKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// `[faults]` section of the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaultConfig {
+    /// How long an injected fault lasts, in seconds
+    pub duration_secs: f64,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self { duration_secs: 5.0 }
+    }
+}
+
+impl FaultConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if !(self.duration_secs.is_finite() && self.duration_secs > 0.0) {
+            bail!("faults.duration_secs must be above 0, got {}", self.duration_secs);
+        }
+        Ok(())
+    }
+
+    /// Samples a fault lasts at one sample per `interval`, at least one
+    pub fn samples(&self, interval: Duration) -> u32 {
+        (self.duration_secs / interval.as_secs_f64().max(0.001)).ceil().max(1.0) as u32
+    }
+}
+
+/// A fault as it was injected
+#[derive(Debug, Clone)]
+pub struct FaultRecord {
+    pub kind: FaultKind,
+    pub started_at: DateTime<Utc>,
+    /// Samples it was injected into
+    pub samples: u32,
+}
+
+/// `latency spike at 14:02:31 for 5 samples`
+impl fmt::Display for FaultRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {} for {} samples",
+            self.kind,
+            self.started_at.with_timezone(&chrono::Local).format("%H:%M:%S"),
+            self.samples
+        )
+    }
+}
+
+/// One generated sample before unit conversion
+struct Sample {
+    latency: f64,
+    throughput_mbps: f64,
+    handshake_time: f64,
+    loss_pct: f64,
+    retransmits: i32,
+}
+
+/// Samples a congestion-collapse cycle lasts
+const COLLAPSE_PERIOD: u32 = 150;
+
+/// Bottleneck bandwidth and base RTT of the simulated BBRv3 path
+const BBR_BOTTLENECK_MBPS: f64 = 600.0;
+const BBR_MIN_RTT_MS: f64 = 20.0;
+/// ProbeBW pacing gains, one per sample
+const BBR_PROBE_BW_GAINS: [f64; 8] = [1.25, 0.75, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+/// Samples spent in ProbeBW between ProbeRTT visits
+const BBR_PROBE_RTT_EVERY: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BbrPhase {
+    Startup,
+    Drain,
+    ProbeBw,
+    ProbeRtt,
+}
+
+impl BbrPhase {
+    fn name(self) -> &'static str {
+        match self {
+            BbrPhase::Startup => "Startup",
+            BbrPhase::Drain => "Drain",
+            BbrPhase::ProbeBw => "ProbeBW",
+            BbrPhase::ProbeRtt => "ProbeRTT",
+        }
+    }
+}
+
+/// Simulated BBRv3 sender state
+#[derive(Debug, Clone)]
+struct BbrState {
+    phase: BbrPhase,
+    /// Samples since the phase began
+    rounds: u32,
+    /// Delivery rate in Mbps
+    bw_mbps: f64,
+    /// Samples in Startup without 25% bandwidth growth
+    plateau: u32,
+    /// Queueing delay as a share of the base RTT
+    queue: f64,
+    bw_slow_mbps: f64,
+    loss_ema: f64,
+}
+
+impl Default for BbrState {
+    fn default() -> Self {
+        Self {
+            phase: BbrPhase::Startup,
+            rounds: 0,
+            bw_mbps: 5.0,
+            plateau: 0,
+            queue: 0.0,
+            bw_slow_mbps: 5.0,
+            loss_ema: 0.0,
+        }
+    }
+}
 
 /// Demo data generator
 pub struct DemoDataGenerator {
+    rng: StdRng,
+    seed: u64,
+    profile: TrafficProfile,
+    /// Samples left in the current web burst or mobile handover
+    episode_left: u32,
+    bbr: BbrState,
+    bbrv3: BBRv3Metrics,
+    /// Samples an injected fault lasts
+    fault_samples: u32,
+    /// Faults in effect and the samples each has left
+    active_faults: Vec<(FaultKind, u32)>,
+    fault_log: Vec<FaultRecord>,
+    /// Failed connections and errors the faults added to the last sample
+    failed_connections: u32,
+    injected_errors: u32,
     latency_data: VecDeque<f64>,
     throughput_data: VecDeque<f64>,
     handshake_data: VecDeque<f64>,
@@ -15,9 +270,32 @@ pub struct DemoDataGenerator {
     pub counter: u32,
 }
 
+impl Default for DemoDataGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DemoDataGenerator {
+    /// Steady-stream samples from a random seed
     pub fn new() -> Self {
+        Self::with_seed(rand::random())
+    }
+
+    /// Steady-stream samples that are the same on every run with `seed`
+    pub fn with_seed(seed: u64) -> Self {
         Self {
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            profile: TrafficProfile::default(),
+            episode_left: 0,
+            bbr: BbrState::default(),
+            bbrv3: BBRv3Metrics::default(),
+            fault_samples: FaultConfig::default().samples(Duration::from_secs(1)),
+            active_faults: Vec::new(),
+            fault_log: Vec::new(),
+            failed_connections: 0,
+            injected_errors: 0,
             latency_data: VecDeque::with_capacity(1000),
             throughput_data: VecDeque::with_capacity(1000),
             handshake_data: VecDeque::with_capacity(100),
@@ -27,34 +305,100 @@ impl DemoDataGenerator {
         }
     }
 
-    pub fn generate_next(&mut self) -> (f64, f64, f64, f64, i32) {
-        let mut rng = rand::thread_rng();
-        self.counter += 1;
+    /// Generate `profile` traffic instead of a steady stream
+    pub fn with_profile(mut self, profile: TrafficProfile) -> Self {
+        self.profile = profile;
+        self
+    }
 
-        // Generate realistic QUIC metrics
-        let base_latency = 10.0;
-        let latency_variation = rng.gen_range(-5.0..15.0);
-        let latency_trend = (self.counter as f64 * 0.1).sin() * 3.0;
-        let latency = base_latency + latency_variation + latency_trend;
+    /// Make injected faults last `samples` samples
+    pub fn with_fault_length(mut self, samples: u32) -> Self {
+        self.fault_samples = samples.max(1);
+        self
+    }
 
-        let base_throughput = 1000.0;
-        let throughput_variation = rng.gen_range(-200.0..500.0);
-        let throughput_trend = (self.counter as f64 * 0.05).cos() * 200.0;
-        let throughput = base_throughput + throughput_variation + throughput_trend;
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 
-        let handshake_time = rng.gen_range(50.0..200.0) + (self.counter as f64 * 0.2).sin() * 20.0;
+    pub fn profile(&self) -> TrafficProfile {
+        self.profile
+    }
 
-        let packet_loss = if self.counter > 20 {
-            rng.gen_range(0.0..2.0) + (self.counter as f64 * 0.1).sin() * 0.5
-        } else {
-            0.0
-        };
+    /// `bursty-web traffic, seed 42`, for telling how to repeat a run
+    pub fn summary(&self) -> String {
+        format!("{} traffic, seed {}", self.profile, self.seed)
+    }
 
-        let retransmits = if self.counter > 25 {
-            rng.gen_range(0..10) + ((self.counter as f64 * 0.15).sin() * 3.0) as i32
-        } else {
-            0
+    /// Start over from the first sample, with the same seed and profile, and no faults
+    pub fn reset(&mut self) {
+        *self = Self::with_seed(self.seed)
+            .with_profile(self.profile)
+            .with_fault_length(self.fault_samples);
+    }
+
+    /// Lay `kind` over the next samples, starting it over if it is already in effect
+    pub fn inject(&mut self, kind: FaultKind) {
+        self.active_faults.retain(|(active, _)| *active != kind);
+        self.active_faults.push((kind, self.fault_samples));
+        self.fault_log.push(FaultRecord {
+            kind,
+            started_at: Utc::now(),
+            samples: self.fault_samples,
+        });
+    }
+
+    /// Faults injected since the start or the last reset, oldest first
+    pub fn fault_log(&self) -> &[FaultRecord] {
+        &self.fault_log
+    }
+
+    /// `INJECTING: latency spike (3s left)` while a fault is in effect, at one sample per `interval`
+    pub fn injection_status(&self, interval: Duration) -> Option<String> {
+        if self.active_faults.is_empty() {
+            return None;
+        }
+        let faults: Vec<String> = self
+            .active_faults
+            .iter()
+            .map(|(kind, left)| format!("{} ({:.0}s left)", kind, (interval.as_secs_f64() * *left as f64).ceil()))
+            .collect();
+        Some(format!("INJECTING: {}", faults.join(", ")))
+    }
+
+    /// Connections the faults made fail in the last sample
+    pub fn failed_connections(&self) -> u32 {
+        self.failed_connections
+    }
+
+    /// Errors the faults added to the last sample, failed connections included
+    pub fn injected_errors(&self) -> u32 {
+        self.injected_errors
+    }
+
+    /// BBRv3 state of the last sample; not reported outside the BBRv3 profile
+    pub fn bbrv3(&self) -> &BBRv3Metrics {
+        &self.bbrv3
+    }
+
+    /// `(latency ms, throughput bps, handshake time ms, packet loss fraction, retransmits)`
+    pub fn generate_next(&mut self) -> (f64, f64, f64, f64, i32) {
+        self.counter += 1;
+
+        let mut sample = match self.profile {
+            TrafficProfile::SteadyStream => self.steady_stream(),
+            TrafficProfile::BurstyWeb => self.bursty_web(),
+            TrafficProfile::LossyMobile => self.lossy_mobile(),
+            TrafficProfile::CongestionCollapse => self.congestion_collapse(),
+            TrafficProfile::BBRv3Startup => self.bbrv3_startup(),
         };
+        self.apply_faults(&mut sample);
+        // Stacked faults must not turn into values no sender could report
+        let latency = finite_or(sample.latency, 0.1).max(0.1);
+        let throughput = Unit::Mbps.to_bps(finite_or(sample.throughput_mbps, 0.0).max(0.0));
+        let handshake_time = finite_or(sample.handshake_time, 0.0).max(0.0);
+        let packet_loss = finite_or(sample.loss_pct / 100.0, 0.0).clamp(0.0, 1.0);
+        let retransmits = sample.retransmits.max(0);
 
         // Update data buffers
         self.latency_data.push_back(latency);
@@ -83,6 +427,215 @@ impl DemoDataGenerator {
         (latency, throughput, handshake_time, packet_loss, retransmits)
     }
 
+    /// Lay the faults in effect over `sample` and count down their samples
+    fn apply_faults(&mut self, sample: &mut Sample) {
+        self.failed_connections = 0;
+        self.injected_errors = 0;
+        let rng = &mut self.rng;
+        for (kind, left) in &mut self.active_faults {
+            match kind {
+                FaultKind::LatencySpike => sample.latency = sample.latency * 3.0 + rng.gen_range(300.0..600.0),
+                FaultKind::LossBurst => {
+                    sample.loss_pct = sample.loss_pct.max(rng.gen_range(15.0..30.0));
+                    sample.retransmits += rng.gen_range(40..100);
+                }
+                FaultKind::ThroughputCollapse => sample.throughput_mbps *= rng.gen_range(0.01..0.05),
+                FaultKind::ConnectionFailures => {
+                    // Handshakes run into their timeout
+                    sample.handshake_time = rng.gen_range(3000.0..10000.0);
+                    let failed = rng.gen_range(5..20);
+                    self.failed_connections += failed;
+                    self.injected_errors += failed;
+                }
+                FaultKind::ErrorStorm => self.injected_errors += rng.gen_range(100..300),
+            }
+            *left -= 1;
+        }
+        self.active_faults.retain(|(_, left)| *left > 0);
+    }
+
+    fn steady_stream(&mut self) -> Sample {
+        let t = self.counter as f64;
+        let rng = &mut self.rng;
+
+        let latency = 10.0 + rng.gen_range(-5.0..15.0) + (t * 0.1).sin() * 3.0;
+        // Around 1 Gbps
+        let throughput_mbps = 1000.0 + rng.gen_range(-200.0..500.0) + (t * 0.05).cos() * 200.0;
+        let handshake_time = rng.gen_range(50.0..200.0) + (t * 0.2).sin() * 20.0;
+        // Up to about 2.5%
+        let loss_pct = if self.counter > 20 {
+            rng.gen_range(0.0..2.0) + (t * 0.1).sin() * 0.5
+        } else {
+            0.0
+        };
+        let retransmits = if self.counter > 25 {
+            rng.gen_range(0..10) + ((t * 0.15).sin() * 3.0) as i32
+        } else {
+            0
+        };
+
+        Sample { latency, throughput_mbps, handshake_time, loss_pct, retransmits }
+    }
+
+    fn bursty_web(&mut self) -> Sample {
+        let rng = &mut self.rng;
+        // A page load every 8 samples or so, lasting 2-5 samples
+        if self.episode_left == 0 && rng.gen_bool(0.12) {
+            self.episode_left = rng.gen_range(2..6);
+        }
+        let bursting = self.episode_left > 0;
+        self.episode_left = self.episode_left.saturating_sub(1);
+
+        if bursting {
+            Sample {
+                latency: 15.0 + rng.gen_range(10.0..60.0),
+                throughput_mbps: rng.gen_range(300.0..900.0),
+                handshake_time: rng.gen_range(20.0..80.0),
+                loss_pct: rng.gen_range(0.2..1.5),
+                retransmits: rng.gen_range(2..15),
+            }
+        } else {
+            Sample {
+                latency: 15.0 + rng.gen_range(-3.0..5.0),
+                throughput_mbps: rng.gen_range(2.0..20.0),
+                handshake_time: rng.gen_range(20.0..80.0),
+                loss_pct: rng.gen_range(0.0..0.2),
+                retransmits: rng.gen_range(0..2),
+            }
+        }
+    }
+
+    fn lossy_mobile(&mut self) -> Sample {
+        let t = self.counter as f64;
+        let rng = &mut self.rng;
+        // A handover about once a minute at 1 sample/s stalls the link briefly
+        if self.episode_left == 0 && rng.gen_bool(1.0 / 60.0) {
+            self.episode_left = rng.gen_range(2..5);
+        }
+        let handover = self.episode_left > 0;
+        self.episode_left = self.episode_left.saturating_sub(1);
+
+        let mut sample = Sample {
+            latency: 80.0 + rng.gen_range(-30.0..60.0) + (t * 0.07).sin() * 20.0,
+            throughput_mbps: 25.0 + (t * 0.03).sin() * 10.0 + rng.gen_range(-8.0..8.0),
+            handshake_time: rng.gen_range(150.0..400.0),
+            loss_pct: rng.gen_range(2.0..6.0),
+            retransmits: rng.gen_range(5..25),
+        };
+        if handover {
+            sample.latency += rng.gen_range(200.0..600.0);
+            sample.throughput_mbps = rng.gen_range(0.1..2.0);
+            sample.loss_pct = rng.gen_range(15.0..30.0);
+            sample.retransmits = rng.gen_range(30..80);
+        }
+        sample
+    }
+
+    fn congestion_collapse(&mut self) -> Sample {
+        let rng = &mut self.rng;
+        // Healthy, then a queue building up to a collapse, then recovery
+        let phase = (self.counter - 1) % COLLAPSE_PERIOD;
+        let load = match phase {
+            0..=39 => 0.0,
+            40..=109 => (phase - 40) as f64 / 70.0,
+            _ => 1.0 - (phase - 110) as f64 / 40.0,
+        };
+
+        Sample {
+            latency: 20.0 + load * load * 400.0 + rng.gen_range(-3.0..3.0) * (1.0 + load * 10.0),
+            throughput_mbps: 900.0 * (1.0 - 0.93 * load) + rng.gen_range(-30.0..30.0) * (1.0 - load),
+            handshake_time: 60.0 + load * 500.0 + rng.gen_range(0.0..40.0),
+            loss_pct: load * load * 15.0 + rng.gen_range(0.0..0.3),
+            retransmits: (load * 60.0) as i32 + rng.gen_range(0..5),
+        }
+    }
+
+    fn bbrv3_startup(&mut self) -> Sample {
+        let rng = &mut self.rng;
+        let bbr = &mut self.bbr;
+        bbr.rounds += 1;
+
+        let (pacing_gain, cwnd_gain, loss_pct) = match bbr.phase {
+            BbrPhase::Startup => {
+                // Doubles every round or so until the bottleneck stops the growth
+                let grown = (bbr.bw_mbps * rng.gen_range(1.6..2.0)).min(BBR_BOTTLENECK_MBPS);
+                bbr.plateau = if grown < bbr.bw_mbps * 1.25 { bbr.plateau + 1 } else { 0 };
+                bbr.bw_mbps = grown;
+                bbr.queue = if bbr.plateau > 0 { (bbr.queue + 0.4).min(1.5) } else { 0.05 };
+                let loss = if bbr.plateau > 0 { rng.gen_range(1.0..3.0) } else { 0.0 };
+                if bbr.plateau >= 3 {
+                    bbr.phase = BbrPhase::Drain;
+                    bbr.rounds = 0;
+                }
+                (2.77, 2.0, loss)
+            }
+            BbrPhase::Drain => {
+                bbr.queue = (bbr.queue - 0.5).max(0.05);
+                bbr.bw_mbps = BBR_BOTTLENECK_MBPS * 0.6;
+                if bbr.rounds >= 3 {
+                    bbr.phase = BbrPhase::ProbeBw;
+                    bbr.rounds = 0;
+                }
+                (0.35, 2.0, 0.0)
+            }
+            BbrPhase::ProbeBw => {
+                let gain = BBR_PROBE_BW_GAINS[(bbr.rounds as usize - 1) % BBR_PROBE_BW_GAINS.len()];
+                bbr.bw_mbps = BBR_BOTTLENECK_MBPS * gain.min(1.0) * rng.gen_range(0.95..1.0);
+                bbr.queue = match gain {
+                    g if g > 1.0 => 0.25,
+                    g if g < 1.0 => 0.0,
+                    _ => 0.05,
+                };
+                let loss = if gain > 1.0 { rng.gen_range(0.5..1.5) } else { rng.gen_range(0.0..0.2) };
+                if bbr.rounds >= BBR_PROBE_RTT_EVERY {
+                    bbr.phase = BbrPhase::ProbeRtt;
+                    bbr.rounds = 0;
+                }
+                (gain, 2.0, loss)
+            }
+            BbrPhase::ProbeRtt => {
+                bbr.bw_mbps = BBR_BOTTLENECK_MBPS * 0.3;
+                bbr.queue = 0.0;
+                if bbr.rounds >= 2 {
+                    bbr.phase = BbrPhase::ProbeBw;
+                    bbr.rounds = 0;
+                }
+                (1.0, 0.5, 0.0)
+            }
+        };
+
+        let bw_fast = bbr.bw_mbps * rng.gen_range(0.97..1.03);
+        bbr.bw_slow_mbps = bbr.bw_slow_mbps * 0.9 + bw_fast * 0.1;
+        bbr.loss_ema = bbr.loss_ema * 0.9 + loss_pct / 100.0 * 0.1;
+        let latency = BBR_MIN_RTT_MS * (1.0 + bbr.queue) + rng.gen_range(0.0..1.5);
+        let bottleneck_bps = Unit::Mbps.to_bps(BBR_BOTTLENECK_MBPS);
+
+        self.bbrv3 = BBRv3Metrics {
+            phase: Some(bbr.phase.name().to_string()),
+            bw_fast: Some(Unit::Mbps.to_bps(bw_fast)),
+            bw_slow: Some(Unit::Mbps.to_bps(bbr.bw_slow_mbps)),
+            loss_rate_round: Some(loss_pct / 100.0),
+            loss_rate_ema: Some(bbr.loss_ema),
+            loss_threshold: Some(0.02),
+            headroom_usage: Some((bw_fast / BBR_BOTTLENECK_MBPS).min(1.0)),
+            inflight_target: Some(bottleneck_bps * BBR_MIN_RTT_MS / 1000.0 / 8.0 * cwnd_gain),
+            pacing_quantum: Some((Unit::Mbps.to_bps(bw_fast) / 8.0 / 1000.0).clamp(2400.0, 65536.0) as i64),
+            pacing_gain: Some(pacing_gain),
+            cwnd_gain: Some(cwnd_gain),
+            probe_rtt_min_ms: Some(BBR_MIN_RTT_MS),
+            bufferbloat_factor: Some(latency / BBR_MIN_RTT_MS - 1.0),
+            ..BBRv3Metrics::default()
+        };
+
+        Sample {
+            latency,
+            throughput_mbps: bw_fast,
+            handshake_time: rng.gen_range(40.0..90.0),
+            loss_pct,
+            retransmits: (loss_pct * 5.0) as i32 + rng.gen_range(0..3),
+        }
+    }
+
     pub fn get_latency_data(&self) -> &VecDeque<f64> {
         &self.latency_data
     }
@@ -103,3 +656,83 @@ impl DemoDataGenerator {
         &self.retransmit_data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: [TrafficProfile; 5] = [
+        TrafficProfile::SteadyStream,
+        TrafficProfile::BurstyWeb,
+        TrafficProfile::LossyMobile,
+        TrafficProfile::CongestionCollapse,
+        TrafficProfile::BBRv3Startup,
+    ];
+
+    /// A sample as `generate_next` returns it, with the BBRv3 state after it
+    type Generated = ((f64, f64, f64, f64, i32), BBRv3Metrics);
+
+    /// The next `count` samples of `generator`
+    fn run(generator: &mut DemoDataGenerator, count: usize) -> Vec<Generated> {
+        (0..count)
+            .map(|_| (generator.generate_next(), generator.bbrv3().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_and_profile_give_the_same_samples() {
+        for profile in PROFILES {
+            let first = run(&mut DemoDataGenerator::with_seed(1831).with_profile(profile), 400);
+            let second = run(&mut DemoDataGenerator::with_seed(1831).with_profile(profile), 400);
+            assert!(first == second, "{} differs between runs with one seed", profile);
+
+            let other = run(&mut DemoDataGenerator::with_seed(1832).with_profile(profile), 400);
+            assert!(first != other, "{} ignores the seed", profile);
+        }
+    }
+
+    #[test]
+    fn reset_and_faults_repeat_with_the_seed() {
+        let mut generator = DemoDataGenerator::with_seed(7).with_profile(TrafficProfile::LossyMobile).with_fault_length(3);
+        let before = run(&mut generator, 20);
+        generator.inject(FaultKind::LossBurst);
+        let faulted = run(&mut generator, 5);
+
+        generator.reset();
+        assert_eq!((generator.seed(), generator.profile(), generator.counter), (7, TrafficProfile::LossyMobile, 0));
+        assert!(generator.fault_log().is_empty());
+        assert!(run(&mut generator, 20) == before);
+        generator.inject(FaultKind::LossBurst);
+        assert!(run(&mut generator, 5) == faulted);
+        assert_eq!(generator.summary(), "lossy-mobile traffic, seed 7");
+    }
+
+    #[test]
+    fn only_the_bbrv3_profile_reports_bbrv3_state() {
+        let mut bbr = DemoDataGenerator::with_seed(3).with_profile(TrafficProfile::BBRv3Startup);
+        let phases: Vec<String> = (0..300)
+            .filter_map(|_| {
+                bbr.generate_next();
+                bbr.bbrv3().phase.clone()
+            })
+            .collect();
+        assert_eq!(phases.len(), 300);
+        for phase in ["Startup", "Drain", "ProbeBW", "ProbeRTT"] {
+            assert!(phases.iter().any(|p| p == phase), "never in {}", phase);
+        }
+
+        let mut steady = DemoDataGenerator::with_seed(3);
+        steady.generate_next();
+        assert!(steady.bbrv3().phase.is_none());
+    }
+
+    #[test]
+    fn profiles_parse_from_their_names() {
+        assert_eq!(TRAFFIC_PROFILES.len(), PROFILES.len());
+        for (profile, name) in PROFILES.into_iter().zip(TRAFFIC_PROFILES) {
+            assert_eq!(profile.name(), *name);
+            assert_eq!(name.parse::<TrafficProfile>().unwrap(), profile);
+        }
+        assert!("lossy".parse::<TrafficProfile>().is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Thinning long series before they are plotted
+//!
+//! A chart cell can only show a few points, so handing a `Dataset` tens of
+//! thousands of them makes every frame slow without showing more. `lttb`
+//! keeps the points that shape the line, spikes included, and is applied
+//! only to what is drawn; statistics are still computed over every sample.
+
+/// Points kept per terminal column of a chart
+pub const POINTS_PER_COLUMN: usize = 4;
+
+/// Most points worth plotting in a chart `width` cells wide
+pub fn chart_budget(width: u16) -> usize {
+    usize::from(width) * POINTS_PER_COLUMN
+}
+
+/// Largest-triangle-three-buckets: thin `points` to at most `threshold`
+///
+/// The first and last point are kept. The rest are split into buckets, and
+/// from each the point forming the largest triangle with the previously kept
+/// point and the average of the next bucket is kept, which is how peaks and
+/// dips survive. `points` must be sorted by x. Series of `threshold` points
+/// or fewer, and thresholds below 3, are returned unchanged.
+pub fn lttb(points: Vec<(f64, f64)>, threshold: usize) -> Vec<(f64, f64)> {
+    if threshold < 3 || points.len() <= threshold {
+        return points;
+    }
+
+    let len = points.len();
+    // Buckets between the fixed first and last point
+    let bucket_size = (len - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |i: usize| ((i as f64 * bucket_size) as usize + 1).min(len - 1);
+
+    let mut sampled = Vec::with_capacity(threshold);
+    sampled.push(points[0]);
+    let mut previous = points[0];
+    for i in 0..threshold - 2 {
+        let (start, end) = (bucket_start(i), bucket_start(i + 1));
+        // The bucket after this one, or the last point when there is none
+        let next = &points[end..bucket_start(i + 2).max(end + 1).min(len)];
+        let count = next.len() as f64;
+        let (avg_x, avg_y) = next
+            .iter()
+            .fold((0.0, 0.0), |(x, y), &(px, py)| (x + px / count, y + py / count));
+
+        let (ax, ay) = previous;
+        let area = |&(bx, by): &(f64, f64)| ((ax - avg_x) * (by - ay) - (ax - bx) * (avg_y - ay)).abs();
+        let kept = points[start..end]
+            .iter()
+            .copied()
+            .max_by(|a, b| area(a).total_cmp(&area(b)))
+            .unwrap_or(points[start]);
+        sampled.push(kept);
+        previous = kept;
+    }
+    sampled.push(points[len - 1]);
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_with_spike(len: usize, spike_at: usize) -> Vec<(f64, f64)> {
+        (0..len)
+            .map(|i| (i as f64, if i == spike_at { 500.0 } else { 20.0 }))
+            .collect()
+    }
+
+    #[test]
+    fn single_sample_spike_survives() {
+        for spike_at in [1, 777, 5_000, 9_998] {
+            let thinned = lttb(flat_with_spike(10_000, spike_at), 200);
+            assert_eq!(thinned.len(), 200);
+            assert!(thinned.contains(&(spike_at as f64, 500.0)), "spike at {} lost", spike_at);
+        }
+
+        // A dip as well, among noise
+        let mut points: Vec<(f64, f64)> = (0..5_000).map(|i| (i as f64, 50.0 + (i % 7) as f64)).collect();
+        points[3_210].1 = -100.0;
+        assert!(lttb(points, 100).contains(&(3_210.0, -100.0)));
+    }
+
+    #[test]
+    fn keeps_the_ends_and_the_order() {
+        let points: Vec<(f64, f64)> = (0..1_000).map(|i| (i as f64, (i as f64 / 10.0).sin())).collect();
+        let thinned = lttb(points.clone(), 50);
+        assert_eq!(thinned.len(), 50);
+        assert_eq!(thinned.first(), points.first());
+        assert_eq!(thinned.last(), points.last());
+        assert!(thinned.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn short_series_and_small_thresholds_are_unchanged() {
+        let points = flat_with_spike(10, 4);
+        assert_eq!(lttb(points.clone(), 10), points);
+        assert_eq!(lttb(points.clone(), 50), points);
+        assert_eq!(lttb(points.clone(), 2), points);
+        assert_eq!(lttb(points.clone(), 0), points);
+        assert!(lttb(Vec::new(), 3).is_empty());
+        assert!(lttb(Vec::new(), 0).is_empty());
+
+        // Three points keep just the ends and the most prominent one
+        assert_eq!(lttb(points, 3), [(0.0, 20.0), (4.0, 500.0), (9.0, 20.0)]);
+        assert_eq!(chart_budget(80), 320);
+    }
+}
@@ -0,0 +1,322 @@
+//! CSV/JSON export of the captured session
+//!
+//! Samples are serialized through serde, so any metrics type with named
+//! fields can be exported. Columns follow field order (serde_json is built
+//! with `preserve_order`, so maps keep it), and `None` fields
+//! become blank CSV cells. Rows with a `throughput` are taken as samples:
+//! they get every `bbrv3_*` column, blank when the sender does not run
+//! BBRv3, and a `unit` of `bps` unless they name one, so a baseline read
+//! back from them is not interpreted in another `[ingest] throughput_unit`.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::metrics::BBRV3_COLUMNS;
+use crate::redact::Redactor;
+use crate::units::Unit;
+
+/// Export configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// Directory the export files are written to, created if missing
+    pub output_dir: PathBuf,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("."),
+        }
+    }
+}
+
+/// Result of a successful export
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub samples: usize,
+    pub csv_path: PathBuf,
+    pub json_path: PathBuf,
+    /// Whether sensitive fields were redacted
+    pub redacted: bool,
+}
+
+impl std::fmt::Display for ExportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Exported {} samples to {}.csv/.json{}",
+            self.samples,
+            self.csv_path.with_extension("").display(),
+            if self.redacted { " (redacted)" } else { "" }
+        )
+    }
+}
+
+/// Write `samples` to timestamped `.csv` and `.json` files
+///
+/// When a redactor is given, sensitive fields are redacted in both files.
+pub fn export_session<'a, T: Serialize + 'a>(
+    config: &ExportConfig,
+    samples: impl IntoIterator<Item = &'a T>,
+    redactor: Option<&Redactor>,
+) -> Result<ExportSummary> {
+    let mut rows = samples
+        .into_iter()
+        .map(serde_json::to_value)
+        .collect::<serde_json::Result<Vec<Value>>>()?;
+    if rows.is_empty() {
+        return Err(anyhow!("no samples to export"));
+    }
+    for row in &mut rows {
+        if let Value::Object(fields) = row {
+            if fields.contains_key("throughput") {
+                spread_bbrv3(fields);
+                if fields.get("unit").is_none_or(Value::is_null) {
+                    fields.insert("unit".to_string(), Value::from(String::from(Unit::Bps)));
+                }
+            }
+        }
+    }
+    if let Some(redactor) = redactor {
+        for row in &mut rows {
+            redactor.redact_value(row);
+        }
+    }
+
+    fs::create_dir_all(&config.output_dir)?;
+    let stem = format!("quic-metrics-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let csv_path = config.output_dir.join(format!("{}.csv", stem));
+    let json_path = config.output_dir.join(format!("{}.json", stem));
+
+    write_csv(&csv_path, &rows)?;
+    fs::write(&json_path, serde_json::to_string_pretty(&rows)?)?;
+
+    Ok(ExportSummary {
+        samples: rows.len(),
+        csv_path,
+        json_path,
+        redacted: redactor.is_some(),
+    })
+}
+
+/// Give a sample all of `BBRV3_COLUMNS`, null where not reported, whether its
+/// BBRv3 state is flattened into it or nested under `bbrv3`
+fn spread_bbrv3(fields: &mut Map<String, Value>) {
+    let mut nested = match fields.shift_remove("bbrv3") {
+        Some(Value::Object(nested)) => nested,
+        _ => Map::new(),
+    };
+    for column in BBRV3_COLUMNS {
+        let value = fields
+            .shift_remove(column)
+            .or_else(|| nested.shift_remove(column))
+            .unwrap_or(Value::Null);
+        fields.insert(column.to_string(), value);
+    }
+}
+
+fn write_csv(path: &Path, rows: &[Value]) -> Result<()> {
+    let objects = rows
+        .iter()
+        .map(|row| {
+            row.as_object()
+                .ok_or_else(|| anyhow!("only samples with named fields can be exported to CSV"))
+        })
+        .collect::<Result<Vec<&Map<String, Value>>>>()?;
+
+    // Union of keys in first-seen order, so a field missing from early rows still gets a column
+    let mut header: Vec<&str> = Vec::new();
+    for object in &objects {
+        for key in object.keys() {
+            if !header.contains(&key.as_str()) {
+                header.push(key);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(&header.iter().map(|h| csv_escape(h)).collect::<Vec<_>>().join(","));
+    out.push('\n');
+    for object in &objects {
+        let cells: Vec<String> = header
+            .iter()
+            .map(|key| csv_cell(object.get(*key).unwrap_or(&Value::Null)))
+            .collect();
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+
+    fs::write(path, out)?;
+    Ok(())
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_escape(s),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        // Nested values such as per-phase durations are kept as inline JSON
+        Value::Array(_) | Value::Object(_) => csv_escape(&value.to_string()),
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{BBRv3Metrics, QUICMetrics};
+    use crate::redact::{RedactionConfig, RedactionMode};
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quic-bottom-export-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn rows() -> Vec<Value> {
+        vec![
+            json!({"latency": 10.0, "source": "client-1", "metadata": {"hostname": "edge-7", "region": "eu"}}),
+            json!({"latency": 12.0, "source": "client-1", "metadata": {"hostname": "edge-8", "region": "eu"}}),
+        ]
+    }
+
+    fn export(name: &str, redactor: Option<&Redactor>) -> (ExportSummary, String, Vec<Value>) {
+        let config = ExportConfig { output_dir: temp_dir(name) };
+        let summary = export_session(&config, &rows(), redactor).unwrap();
+        let csv = fs::read_to_string(&summary.csv_path).unwrap();
+        let json = serde_json::from_str(&fs::read_to_string(&summary.json_path).unwrap()).unwrap();
+        fs::remove_dir_all(&config.output_dir).unwrap();
+        (summary, csv, json)
+    }
+
+    #[test]
+    fn redacts_both_files_including_nested_fields() {
+        let redactor = Redactor::new(&RedactionConfig::default());
+        let (summary, csv, json) = export("hash", Some(&redactor));
+        assert!(summary.to_string().ends_with("(redacted)"));
+        for raw in ["client-1", "edge-7", "edge-8"] {
+            assert!(!csv.contains(raw), "{} in {}", raw, csv);
+        }
+        assert!(csv.contains(&redactor.pseudonym("client-1")));
+        // Rows of one sender keep the same pseudonym
+        assert_eq!(json[0]["source"], json[1]["source"]);
+        assert_eq!(json[0]["metadata"]["hostname"], redactor.pseudonym("edge-7"));
+        assert_eq!(json[0]["metadata"]["region"], "eu");
+    }
+
+    #[test]
+    fn strip_drops_the_columns_and_no_redactor_keeps_them() {
+        let redactor = Redactor::new(&RedactionConfig {
+            mode: RedactionMode::Strip,
+            ..RedactionConfig::default()
+        });
+        let (_, csv, json) = export("strip", Some(&redactor));
+        assert_eq!(csv.lines().next(), Some("latency,metadata"));
+        assert_eq!(json[1], json!({"latency": 12.0, "metadata": {"region": "eu"}}));
+
+        let (summary, csv, _) = export("plain", None);
+        assert!(!summary.redacted);
+        assert!(csv.contains("client-1") && csv.contains("edge-7"));
+    }
+
+    #[test]
+    fn columns_follow_field_order() {
+        let dir = temp_dir("order");
+        let config = ExportConfig { output_dir: dir.clone() };
+        let rows = [json!({"zeta": 1, "alpha": 2}), json!({"alpha": 3, "mid": 4})];
+        let summary = export_session(&config, &rows, None).unwrap();
+        let csv = fs::read_to_string(&summary.csv_path).unwrap();
+        assert_eq!(csv, "zeta,alpha,mid\n1,2,\n,3,4\n");
+
+        let summary = export_session(&config, &[QUICMetrics::default()], None).unwrap();
+        let csv = fs::read_to_string(&summary.csv_path).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+        assert_eq!(
+            header[..8],
+            ["latency", "throughput", "connections", "errors", "packet_loss", "retransmits", "timestamp", "rtt"]
+        );
+        // The BBRv3 columns close the sample's fields, before the added unit
+        let bbrv3 = header.len() - 1 - BBRV3_COLUMNS.len();
+        assert_eq!(header[bbrv3..header.len() - 1], BBRV3_COLUMNS);
+        assert_eq!(header.last(), Some(&"unit"));
+    }
+
+    #[test]
+    fn samples_without_bbrv3_keep_blank_bbrv3_columns() {
+        let dir = temp_dir("bbrv3");
+        let config = ExportConfig { output_dir: dir.clone() };
+        let reported = QUICMetrics {
+            bbrv3: Some(BBRv3Metrics {
+                phase: Some("ProbeBW".to_string()),
+                ..BBRv3Metrics::default()
+            }),
+            ..QUICMetrics::default()
+        };
+        let summary = export_session(&config, &[QUICMetrics::default(), reported], None).unwrap();
+        let csv = fs::read_to_string(&summary.csv_path).unwrap();
+        let json: Vec<Value> = serde_json::from_str(&fs::read_to_string(&summary.json_path).unwrap()).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+
+        let mut lines = csv.lines();
+        let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert!(!header.contains(&"bbrv3"));
+        let cells = |line: &str| -> Vec<String> {
+            let row: Vec<&str> = line.split(',').collect();
+            BBRV3_COLUMNS
+                .iter()
+                .map(|column| row[header.iter().position(|h| h == column).unwrap()].to_string())
+                .collect()
+        };
+        assert!(cells(lines.next().unwrap()).iter().all(String::is_empty));
+        let second = cells(lines.next().unwrap());
+        assert_eq!(second[0], "ProbeBW");
+        assert!(second[1..].iter().all(String::is_empty));
+
+        assert_eq!(json[0]["bbrv3_phase"], Value::Null);
+        assert_eq!(json[1]["bbrv3_phase"], "ProbeBW");
+
+        // Flattened BBRv3 state, as the live mode's samples carry it
+        let dir = temp_dir("bbrv3-flat");
+        let config = ExportConfig { output_dir: dir.clone() };
+        let flat = [json!({"latency": 1.0, "throughput": 2.0}), json!({"latency": 1.0, "throughput": 2.0, "bbrv3_bw_fast": 5.0})];
+        let summary = export_session(&config, &flat, None).unwrap();
+        let json: Vec<Value> = serde_json::from_str(&fs::read_to_string(&summary.json_path).unwrap()).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        for row in &json {
+            let object = row.as_object().unwrap();
+            assert!(BBRV3_COLUMNS.iter().all(|column| object.contains_key(*column)));
+        }
+        assert_eq!(json[1]["bbrv3_bw_fast"], 5.0);
+    }
+
+    #[test]
+    fn undefined_values_export_as_blank_cells() {
+        let sample = QUICMetrics { latency: f64::NAN, jitter: f64::INFINITY, ..QUICMetrics::default() };
+        let config = ExportConfig { output_dir: temp_dir("non-finite") };
+        let summary = export_session(&config, &[serde_json::to_value(&sample).unwrap()], None).unwrap();
+        let csv = fs::read_to_string(&summary.csv_path).unwrap();
+        let json = fs::read_to_string(&summary.json_path).unwrap();
+        fs::remove_dir_all(&config.output_dir).unwrap();
+
+        let undefined = ["NaN", "inf", "-inf", "Infinity", "-Infinity"];
+        assert!(csv.lines().flat_map(|line| line.split(',')).all(|cell| !undefined.contains(&cell)), "{}", csv);
+        assert!(!json.contains("NaN") && !json.contains("Infinity"), "{}", json);
+        let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+        let row: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+        assert_eq!(row[header.iter().position(|&h| h == "latency").unwrap()], "");
+        assert_eq!(row[header.iter().position(|&h| h == "jitter").unwrap()], "");
+    }
+}
@@ -0,0 +1,421 @@
+//! InfluxDB line protocol exporter
+//!
+//! Batches samples into line protocol and writes them to InfluxDB over
+//! HTTP, every `flush_interval_ms`:
+//!
+//! ```text
+//! quic,run_id=nightly-42 connections=4i,errors=0i,retransmits=2i,congestion_window=65536i,bytes_sent=1048576i,bytes_received=2097152i,streams=8i,session_resumed=true,handshake_type="0rtt",latency=12.5,throughput_bps=940000000,packet_loss=0.001,rtt=12.5,jitter=1.2,handshake_time=35 1700000000000
+//! ```
+//!
+//! With `org` set the InfluxDB 2 API is used (`/api/v2/write`, `bucket` is
+//! the bucket); without it the 1.x API (`/write`, `bucket` is the
+//! database). A failed write is retried with doubling backoff up to
+//! `max_backoff_ms`, keeping the batch queued; a batch InfluxDB rejects as
+//! malformed (4xx) is dropped instead. Only plain `http://` URLs are
+//! supported. `InfluxQueue::flush` writes what is queued right away, for
+//! shutdown.
+
+use anyhow::{anyhow, Result};
+use hyper::{client::HttpConnector, Body, Client, Method, Request, StatusCode, Uri};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::metrics::QUICMetrics;
+use crate::sync::MutexExt;
+
+/// Most lines sent in one write request
+const MAX_LINES_PER_WRITE: usize = 5000;
+
+/// How often `flush` checks whether the queue has drained
+const FLUSH_POLL: Duration = Duration::from_millis(100);
+
+/// InfluxDB exporter settings, under `[exporters.influx]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InfluxConfig {
+    /// Server URL, e.g. `http://127.0.0.1:8086`; the exporter is off when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Bucket (InfluxDB 2) or database (1.x)
+    pub bucket: String,
+
+    /// Organization; selects the InfluxDB 2 API when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+
+    /// API token, sent as `Authorization: Token ...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    pub measurement: String,
+
+    /// Tags added to every line, e.g. `run_id`
+    pub tags: BTreeMap<String, String>,
+
+    pub flush_interval_ms: u64,
+
+    /// Lines kept while InfluxDB is unreachable; the oldest are dropped beyond this
+    pub max_pending: usize,
+
+    /// Longest delay between retries of a failed write
+    pub max_backoff_ms: u64,
+}
+
+impl Default for InfluxConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            bucket: "quic".to_string(),
+            org: None,
+            token: None,
+            measurement: "quic".to_string(),
+            tags: BTreeMap::new(),
+            flush_interval_ms: 1000,
+            max_pending: 10_000,
+            max_backoff_ms: 30_000,
+        }
+    }
+}
+
+impl InfluxConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// The write endpoint for the configured API version
+    fn write_uri(&self) -> Result<Uri> {
+        let base = self
+            .url
+            .as_deref()
+            .ok_or_else(|| anyhow!("influx exporter has no url"))?
+            .trim_end_matches('/');
+        let url = match &self.org {
+            Some(org) => format!(
+                "{}/api/v2/write?org={}&bucket={}&precision=ms",
+                base,
+                encode_query(org),
+                encode_query(&self.bucket)
+            ),
+            None => format!("{}/write?db={}&precision=ms", base, encode_query(&self.bucket)),
+        };
+        url.parse()
+            .map_err(|e| anyhow!("invalid influx url '{}': {}", base, e))
+    }
+}
+
+/// Exporter state as shown in the footer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportState {
+    /// Nothing waiting
+    Ok,
+    /// Lines queued for the next write
+    Pending(usize),
+    /// The last write failed
+    Error(String),
+}
+
+impl fmt::Display for ExportState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportState::Ok => write!(f, "export: OK"),
+            ExportState::Pending(pending) => write!(f, "export: {} pending", pending),
+            ExportState::Error(_) => write!(f, "export: ERROR"),
+        }
+    }
+}
+
+/// Bounded queue of formatted lines between the ingest path and the writer
+///
+/// Pushing never blocks; when full, the oldest line is dropped and counted.
+pub struct InfluxQueue {
+    prefix: String,
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+    last_error: Mutex<Option<String>>,
+    dropped: AtomicU64,
+    /// Lines taken by the writer and not yet written
+    in_flight: AtomicUsize,
+    /// Wakes the writer before its flush interval is up
+    wake: Notify,
+}
+
+impl InfluxQueue {
+    /// Queue one sample
+    pub fn push(&self, metrics: &QUICMetrics) {
+        let line = format_line(&self.prefix, metrics);
+        let mut lines = self.lines.lock_or_recover();
+        lines.push_back(line);
+        self.trim(&mut lines);
+    }
+
+    /// Lines dropped because the queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// State plus the dropped count, e.g. `export: 3 pending, 12 dropped`
+    pub fn status_line(&self) -> String {
+        match self.dropped() {
+            0 => self.state().to_string(),
+            dropped => format!("{}, {} dropped", self.state(), dropped),
+        }
+    }
+
+    /// Write everything queued now, waiting up to `timeout`
+    ///
+    /// Returns how many lines are still unwritten, e.g. because InfluxDB is
+    /// unreachable. Needs the writer from `InfluxExporter::spawn` running.
+    pub async fn flush(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let pending = self.lines.lock_or_recover().len() + self.in_flight.load(Ordering::SeqCst);
+            if pending == 0 || Instant::now() >= deadline {
+                return pending;
+            }
+            // Leave a failing writer to its backoff instead of hammering InfluxDB
+            if self.last_error.lock_or_recover().is_none() {
+                self.wake.notify_one();
+            }
+            tokio::time::sleep(FLUSH_POLL).await;
+        }
+    }
+
+    pub fn state(&self) -> ExportState {
+        if let Some(error) = self.last_error.lock_or_recover().clone() {
+            return ExportState::Error(error);
+        }
+        match self.lines.lock_or_recover().len() {
+            0 => ExportState::Ok,
+            pending => ExportState::Pending(pending),
+        }
+    }
+
+    fn take_batch(&self) -> Vec<String> {
+        let mut lines = self.lines.lock_or_recover();
+        let count = lines.len().min(MAX_LINES_PER_WRITE);
+        self.in_flight.store(count, Ordering::SeqCst);
+        lines.drain(..count).collect()
+    }
+
+    /// Put a failed batch back in front of anything queued since
+    fn requeue(&self, batch: Vec<String>) {
+        let mut lines = self.lines.lock_or_recover();
+        for line in batch.into_iter().rev() {
+            lines.push_front(line);
+        }
+        self.trim(&mut lines);
+    }
+
+    fn trim(&self, lines: &mut VecDeque<String>) {
+        while lines.len() > self.capacity {
+            lines.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn set_error(&self, error: Option<String>) {
+        *self.last_error.lock_or_recover() = error;
+    }
+}
+
+/// Writes queued samples to InfluxDB
+pub struct InfluxExporter {
+    config: InfluxConfig,
+    write_uri: Uri,
+    client: Client<HttpConnector>,
+    queue: Arc<InfluxQueue>,
+}
+
+impl InfluxExporter {
+    pub fn new(config: InfluxConfig) -> Result<Self> {
+        let write_uri = config.write_uri()?;
+        if write_uri.scheme_str() != Some("http") {
+            return Err(anyhow!("influx url must start with http://"));
+        }
+        let queue = Arc::new(InfluxQueue {
+            prefix: line_prefix(&config.measurement, &config.tags),
+            capacity: config.max_pending.max(1),
+            lines: Mutex::new(VecDeque::new()),
+            last_error: Mutex::new(None),
+            dropped: AtomicU64::new(0),
+            in_flight: AtomicUsize::new(0),
+            wake: Notify::new(),
+        });
+
+        Ok(Self {
+            config,
+            write_uri,
+            client: Client::new(),
+            queue,
+        })
+    }
+
+    /// Queue shared with the ingest path and whoever displays its state
+    pub fn queue(&self) -> Arc<InfluxQueue> {
+        Arc::clone(&self.queue)
+    }
+
+    /// Write queued lines every flush interval until the runtime shuts down
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let interval = Duration::from_millis(self.config.flush_interval_ms.max(1));
+            let max_backoff = Duration::from_millis(self.config.max_backoff_ms).max(interval);
+            let mut delay = interval;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = self.queue.wake.notified() => {}
+                }
+                let batch = self.queue.take_batch();
+                if batch.is_empty() {
+                    continue;
+                }
+
+                match self.write(&batch).await {
+                    Ok(()) => {
+                        self.queue.set_error(None);
+                        delay = interval;
+                    }
+                    Err(WriteError::Rejected(e)) => {
+                        log::error!("InfluxDB rejected {} lines, dropping them: {}", batch.len(), e);
+                        self.queue.dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+                        self.queue.set_error(Some(e.to_string()));
+                        delay = interval;
+                    }
+                    Err(WriteError::Retry(e)) => {
+                        delay = (delay * 2).min(max_backoff);
+                        log::warn!("influx write of {} lines failed: {}, retrying in {:?}", batch.len(), e, delay);
+                        self.queue.set_error(Some(e.to_string()));
+                        self.queue.requeue(batch);
+                    }
+                }
+                self.queue.in_flight.store(0, Ordering::SeqCst);
+            }
+        })
+    }
+
+    async fn write(&self, batch: &[String]) -> Result<(), WriteError> {
+        self.send(batch).await.map_err(WriteError::Retry)?.map_err(WriteError::Rejected)
+    }
+
+    /// Outer error: transport or server trouble; inner: the batch was refused
+    async fn send(&self, batch: &[String]) -> Result<Result<()>> {
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri(self.write_uri.clone())
+            .header("content-type", "text/plain; charset=utf-8");
+        if let Some(token) = &self.config.token {
+            request = request.header("authorization", format!("Token {}", token));
+        }
+        let request = request.body(Body::from(batch.join("\n")))?;
+
+        let response = self.client.request(request).await?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+
+        let error = || anyhow!("InfluxDB responded with {}: {}", status, String::from_utf8_lossy(&body).trim());
+        let retryable = status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::UNAUTHORIZED
+            || status == StatusCode::FORBIDDEN;
+        if status.is_success() {
+            Ok(Ok(()))
+        } else if retryable {
+            Err(error())
+        } else {
+            Ok(Err(error()))
+        }
+    }
+}
+
+enum WriteError {
+    /// Worth trying again later
+    Retry(anyhow::Error),
+    /// The batch itself was refused
+    Rejected(anyhow::Error),
+}
+
+/// `measurement,tag=value,...` with line protocol escaping
+fn line_prefix(measurement: &str, tags: &BTreeMap<String, String>) -> String {
+    let mut prefix = escape(measurement, &[',', ' ']);
+    for (key, value) in tags {
+        prefix.push(',');
+        prefix.push_str(&escape(key, &[',', '=', ' ']));
+        prefix.push('=');
+        prefix.push_str(&escape(value, &[',', '=', ' ']));
+    }
+    prefix
+}
+
+/// One line; NaN and infinite values are left out, as line protocol has no syntax for them
+fn format_line(prefix: &str, metrics: &QUICMetrics) -> String {
+    let mut fields = vec![
+        format!("connections={}i", metrics.connections),
+        format!("errors={}i", metrics.errors),
+        format!("retransmits={}i", metrics.retransmits),
+        format!("congestion_window={}i", metrics.congestion_window),
+        format!("bytes_sent={}i", metrics.bytes_sent),
+        format!("bytes_received={}i", metrics.bytes_received),
+        format!("streams={}i", metrics.streams),
+        format!("session_resumed={}", metrics.session_resumed),
+    ];
+    for (name, value) in [
+        ("packets_sent", metrics.packets_sent),
+        ("packets_received", metrics.packets_received),
+        ("packets_lost", metrics.packets_lost),
+        ("packets_acked", metrics.packets_acked),
+        ("connections_opened", metrics.connections_opened),
+        ("connections_failed", metrics.connections_failed),
+        ("connections_closed", metrics.connections_closed),
+    ] {
+        if let Some(value) = value {
+            fields.push(format!("{}={}i", name, value));
+        }
+    }
+    if let Some(handshake_type) = metrics.handshake_type {
+        fields.push(format!("handshake_type=\"{}\"", handshake_type.as_str()));
+    }
+    for (name, value) in [
+        ("latency", metrics.latency),
+        ("throughput_bps", metrics.throughput),
+        ("packet_loss", metrics.packet_loss),
+        ("rtt", metrics.rtt),
+        ("jitter", metrics.jitter),
+        ("handshake_time", metrics.handshake_time),
+    ] {
+        if value.is_finite() {
+            fields.push(format!("{}={}", name, value));
+        }
+    }
+    format!("{} {} {}", prefix, fields.join(","), metrics.timestamp.timestamp_millis())
+}
+
+fn escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Percent-encode a query parameter value
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
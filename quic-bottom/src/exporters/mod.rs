@@ -0,0 +1,25 @@
+//! Forwarding of ingested samples to external stores
+//!
+//! Exporters receive every sample the ingest path accepts and ship it
+//! elsewhere in the background, so runs can be compared long after the TUI
+//! is closed. They never block ingestion: samples wait in a bounded queue
+//! that drops the oldest entries when the store cannot keep up.
+
+use serde::{Deserialize, Serialize};
+
+pub mod influx;
+pub mod otel;
+
+use influx::InfluxConfig;
+use otel::OtelConfig;
+
+/// Exporter settings, under `[exporters]`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportersConfig {
+    /// InfluxDB line protocol over HTTP
+    pub influx: InfluxConfig,
+
+    /// OpenTelemetry metrics over OTLP/HTTP (`otel` feature)
+    pub otel: OtelConfig,
+}
@@ -0,0 +1,226 @@
+//! OpenTelemetry metrics export
+//!
+//! Pushes every sample to an OTLP/HTTP collector as gauges named
+//! `quic.latency`, `quic.throughput`, `quic.packet_loss`,
+//! `quic.retransmits`, `quic.errors`, `quic.connections`, `quic.rtt`,
+//! `quic.jitter`, `quic.congestion_window`, `quic.bytes_sent`,
+//! `quic.bytes_received`, `quic.streams` and `quic.handshake_time`, plus
+//! `quic.bbrv3.*` for the BBRv3 fields the live mode receives. Retransmits
+//! and errors are gauges too: senders report them per interval or
+//! cumulatively, and the exporter passes on whatever they report.
+//! `quic.throughput` is in bits per second and carries the unit `bit/s`;
+//! `quic.packet_loss` is a fraction, 0.0-1.0.
+//!
+//! The exporter itself needs the `otel` cargo feature; without it the
+//! config still parses and `init` only warns, so default builds do not pull
+//! in the OpenTelemetry crates. `init` runs at startup and `shutdown`
+//! flushes the last samples on exit.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// OpenTelemetry settings, under `[exporters.otel]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OtelConfig {
+    /// OTLP/HTTP collector, e.g. `http://127.0.0.1:4318`; export is off when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    pub export_interval_ms: u64,
+
+    /// Resource attributes, e.g. `service.name` and a run id
+    pub resource: BTreeMap<String, String>,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            export_interval_ms: 5000,
+            resource: BTreeMap::from([("service.name".to_string(), "quic-bottom".to_string())]),
+        }
+    }
+}
+
+impl OtelConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some()
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use exporter::{init, record, record_gauges, shutdown};
+
+/// Stand-ins for builds without the `otel` feature
+#[cfg(not(feature = "otel"))]
+mod disabled {
+    use super::OtelConfig;
+    use crate::metrics::QUICMetrics;
+
+    pub fn init(config: &OtelConfig) -> anyhow::Result<()> {
+        if config.is_enabled() {
+            log::warn!("[exporters.otel] is set but quic-bottom was built without the otel feature");
+        }
+        Ok(())
+    }
+
+    pub fn record(_metrics: &QUICMetrics) {}
+
+    pub fn record_gauges(_fields: &[(&str, Option<f64>)]) {}
+
+    pub fn shutdown() {}
+}
+
+#[cfg(not(feature = "otel"))]
+pub use disabled::{init, record, record_gauges, shutdown};
+
+#[cfg(feature = "otel")]
+mod exporter {
+    use anyhow::{anyhow, Result};
+    use opentelemetry::metrics::{Gauge, Meter, MeterProvider};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{MetricExporter, Protocol, WithExportConfig};
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::Resource;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Duration;
+
+    use super::OtelConfig;
+    use crate::metrics::{add_metrics_sink, QUICMetrics};
+    use crate::sync::MutexExt;
+
+    const METRICS_PATH: &str = "/v1/metrics";
+
+    struct OtelExporter {
+        provider: SdkMeterProvider,
+        meter: Meter,
+        /// Instruments by name, created on first use
+        gauges: Mutex<HashMap<String, Gauge<f64>>>,
+    }
+
+    static EXPORTER: OnceLock<OtelExporter> = OnceLock::new();
+
+    impl OtelExporter {
+        fn record(&self, name: &str, value: f64) {
+            if !value.is_finite() {
+                return;
+            }
+            let mut gauges = self.gauges.lock_or_recover();
+            let gauge = gauges
+                .entry(name.to_string())
+                .or_insert_with(|| {
+                    let gauge = self.meter.f64_gauge(name.to_string());
+                    match name {
+                        "quic.throughput" => gauge.with_unit("bit/s").build(),
+                        _ => gauge.build(),
+                    }
+                });
+            gauge.record(value, &[]);
+        }
+    }
+
+    /// Start exporting if `[exporters.otel]` has an endpoint
+    ///
+    /// Samples passed to `update_metrics` are recorded from here on; other
+    /// ingest paths call `record` themselves.
+    pub fn init(config: &OtelConfig) -> Result<()> {
+        let Some(endpoint) = &config.endpoint else {
+            return Ok(());
+        };
+        let endpoint = endpoint.trim_end_matches('/');
+        let endpoint = if endpoint.ends_with(METRICS_PATH) {
+            endpoint.to_string()
+        } else {
+            format!("{}{}", endpoint, METRICS_PATH)
+        };
+        let interval = Duration::from_millis(config.export_interval_ms.max(1));
+        let resource = Resource::builder()
+            .with_attributes(
+                config
+                    .resource
+                    .iter()
+                    .map(|(key, value)| KeyValue::new(key.clone(), value.clone())),
+            )
+            .build();
+
+        // The blocking HTTP client may not be created on a runtime thread
+        let exporter_endpoint = endpoint.clone();
+        let exporter = std::thread::spawn(move || {
+            MetricExporter::builder()
+                .with_http()
+                .with_protocol(Protocol::HttpBinary)
+                .with_endpoint(exporter_endpoint)
+                .build()
+        })
+        .join()
+        .map_err(|_| anyhow!("OTLP exporter setup panicked"))?
+        .map_err(|e| anyhow!("failed to create OTLP exporter for {}: {}", endpoint, e))?;
+
+        let reader = PeriodicReader::builder(exporter).with_interval(interval).build();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader)
+            .with_resource(resource)
+            .build();
+        let meter = provider.meter("quic-bottom");
+        let otel = OtelExporter {
+            provider,
+            meter,
+            gauges: Mutex::new(HashMap::new()),
+        };
+        if EXPORTER.set(otel).is_err() {
+            return Err(anyhow!("OpenTelemetry export is already initialized"));
+        }
+
+        add_metrics_sink(Box::new(record));
+        log::info!("OpenTelemetry export to {} every {:?}", endpoint, interval);
+        Ok(())
+    }
+
+    /// Record one sample; a no-op before `init`
+    pub fn record(metrics: &QUICMetrics) {
+        record_gauges(&[
+            ("quic.latency", Some(metrics.latency)),
+            ("quic.throughput", Some(metrics.throughput)),
+            ("quic.packet_loss", Some(metrics.packet_loss)),
+            ("quic.retransmits", Some(metrics.retransmits as f64)),
+            ("quic.errors", Some(metrics.errors as f64)),
+            ("quic.connections", Some(metrics.connections as f64)),
+            ("quic.rtt", Some(metrics.rtt)),
+            ("quic.jitter", Some(metrics.jitter)),
+            ("quic.congestion_window", Some(metrics.congestion_window as f64)),
+            ("quic.bytes_sent", Some(metrics.bytes_sent as f64)),
+            ("quic.bytes_received", Some(metrics.bytes_received as f64)),
+            ("quic.streams", Some(metrics.streams as f64)),
+            ("quic.handshake_time", Some(metrics.handshake_time)),
+        ]);
+    }
+
+    /// Record named gauges, skipping unset values; a no-op before `init`
+    pub fn record_gauges(fields: &[(&str, Option<f64>)]) {
+        let Some(otel) = EXPORTER.get() else {
+            return;
+        };
+        for (name, value) in fields {
+            if let Some(value) = value {
+                otel.record(name, *value);
+            }
+        }
+    }
+
+    /// Push what has been recorded and stop exporting
+    pub fn shutdown() {
+        let Some(otel) = EXPORTER.get() else {
+            return;
+        };
+        // Flushing goes through the blocking client, so keep it off the runtime thread
+        let provider = otel.provider.clone();
+        let result = std::thread::spawn(move || provider.shutdown()).join();
+        match result {
+            Ok(Ok(())) => log::info!("OpenTelemetry export flushed"),
+            Ok(Err(e)) => log::warn!("OpenTelemetry shutdown failed: {}", e),
+            Err(_) => log::warn!("OpenTelemetry shutdown panicked"),
+        }
+    }
+}
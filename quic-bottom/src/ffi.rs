@@ -0,0 +1,725 @@
+//! C API for embedding quic-bottom, e.g. from Go through cgo
+//!
+//! Callers create a handle with `quic_bottom_new`, push samples with
+//! `quic_bottom_update`, read them back with `quic_bottom_get_current` and
+//! release the handle with `quic_bottom_free`. Each handle owns its own
+//! metrics state. Samples cross the boundary as `CQuicMetrics`, which holds
+//! only plain numbers, or as `CQuicMetricsV2` through the `_v2` functions,
+//! which adds RTT, jitter, congestion window, byte counts, streams and
+//! handshake time. The header is generated into `include/quic_bottom.h` by
+//! the build script.
+//!
+//! Functions returning `i32` answer one of the `QUIC_BOTTOM_*` codes, except
+//! that the time-series read-outs return a count on success.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::bridge::MetricsRequest;
+use crate::ingest::record_ingest_error;
+use crate::metrics::{get_current_metrics, update_metrics, MetricsHandle, QUICMetrics};
+use crate::units::{LossUnit, Unit};
+
+/// Success
+pub const QUIC_BOTTOM_OK: i32 = 0;
+/// A handle or pointer argument was null
+pub const QUIC_BOTTOM_ERR_NULL: i32 = -1;
+/// The sample was refused: a negative, non-finite or implausibly large value,
+/// a count beyond `i32`, or a timestamp out of range
+pub const QUIC_BOTTOM_ERR_INVALID: i32 = -2;
+/// quic-bottom failed internally; the handle should not be used further
+pub const QUIC_BOTTOM_ERR_INTERNAL: i32 = -3;
+
+/// Opaque metrics state owned by the caller
+pub struct QuicBottomHandle {
+    metrics: MetricsHandle,
+}
+
+/// One sample as passed across the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CQuicMetrics {
+    pub latency: f64,
+    /// Bits per second
+    pub throughput: f64,
+    pub connections: i64,
+    pub errors: i64,
+    /// Fraction of packets lost, 0.0-1.0
+    pub packet_loss: f64,
+    pub retransmits: i64,
+    /// Capture time in unix milliseconds; 0 stamps the sample on arrival
+    pub timestamp_ms: i64,
+}
+
+/// `CQuicMetrics` with the fields added in version 2, for the `_v2` functions
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CQuicMetricsV2 {
+    pub latency: f64,
+    /// Bits per second
+    pub throughput: f64,
+    pub connections: i64,
+    pub errors: i64,
+    /// Fraction of packets lost, 0.0-1.0
+    pub packet_loss: f64,
+    pub retransmits: i64,
+    /// Capture time in unix milliseconds; 0 stamps the sample on arrival
+    pub timestamp_ms: i64,
+    /// Smoothed round-trip time in ms
+    pub rtt: f64,
+    /// RTT variation in ms
+    pub jitter: f64,
+    /// Congestion window in bytes
+    pub congestion_window: i64,
+    pub bytes_sent: i64,
+    pub bytes_received: i64,
+    pub streams: i64,
+    /// Handshake duration in ms
+    pub handshake_time: f64,
+}
+
+/// A sample layout of the C API
+trait CSample: Copy + Default {
+    /// Validate like the HTTP API does and convert
+    fn to_metrics(self) -> Result<QUICMetrics>;
+
+    fn from_metrics(metrics: &QUICMetrics) -> Self;
+}
+
+/// Narrow a C count to the `i32` the metrics hold
+fn count(field: &str, value: i64) -> Result<i32> {
+    i32::try_from(value).map_err(|_| anyhow!("{} {} is out of range", field, value))
+}
+
+impl CSample for CQuicMetrics {
+    fn to_metrics(self) -> Result<QUICMetrics> {
+        CQuicMetricsV2 {
+            latency: self.latency,
+            throughput: self.throughput,
+            connections: self.connections,
+            errors: self.errors,
+            packet_loss: self.packet_loss,
+            retransmits: self.retransmits,
+            timestamp_ms: self.timestamp_ms,
+            ..CQuicMetricsV2::default()
+        }
+        .to_metrics()
+    }
+
+    fn from_metrics(metrics: &QUICMetrics) -> Self {
+        let v2 = CQuicMetricsV2::from_metrics(metrics);
+        Self {
+            latency: v2.latency,
+            throughput: v2.throughput,
+            connections: v2.connections,
+            errors: v2.errors,
+            packet_loss: v2.packet_loss,
+            retransmits: v2.retransmits,
+            timestamp_ms: v2.timestamp_ms,
+        }
+    }
+}
+
+impl CSample for CQuicMetricsV2 {
+    fn to_metrics(self) -> Result<QUICMetrics> {
+        let req = MetricsRequest {
+            latency: self.latency,
+            throughput: self.throughput,
+            connections: count("connections", self.connections)?,
+            errors: count("errors", self.errors)?,
+            packet_loss: self.packet_loss,
+            retransmits: count("retransmits", self.retransmits)?,
+            timestamp: (self.timestamp_ms != 0).then_some(self.timestamp_ms),
+            rtt: self.rtt,
+            jitter: self.jitter,
+            congestion_window: count("congestion_window", self.congestion_window)?,
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            streams: count("streams", self.streams)?,
+            handshake_time: self.handshake_time,
+            unit: Some(Unit::Bps),
+            loss_unit: Some(LossUnit::Fraction),
+            ..MetricsRequest::default()
+        };
+        req.validate()?;
+        Ok(req.to_metrics(Utc::now()))
+    }
+
+    fn from_metrics(metrics: &QUICMetrics) -> Self {
+        Self {
+            latency: metrics.latency,
+            throughput: metrics.throughput,
+            connections: i64::from(metrics.connections),
+            errors: i64::from(metrics.errors),
+            packet_loss: metrics.packet_loss,
+            retransmits: i64::from(metrics.retransmits),
+            timestamp_ms: metrics.timestamp.timestamp_millis(),
+            rtt: metrics.rtt,
+            jitter: metrics.jitter,
+            congestion_window: i64::from(metrics.congestion_window),
+            bytes_sent: metrics.bytes_sent,
+            bytes_received: metrics.bytes_received,
+            streams: i64::from(metrics.streams),
+            handshake_time: metrics.handshake_time,
+        }
+    }
+}
+
+/// Run `f`, turning a panic into `QUIC_BOTTOM_ERR_INTERNAL` instead of unwinding into C
+fn guard(f: impl FnOnce() -> i32) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        log::error!("FFI: internal error");
+        QUIC_BOTTOM_ERR_INTERNAL
+    })
+}
+
+/// Create a handle with empty metrics state; release it with `quic_bottom_free`
+#[no_mangle]
+pub extern "C" fn quic_bottom_new() -> *mut QuicBottomHandle {
+    Box::into_raw(Box::new(QuicBottomHandle {
+        metrics: MetricsHandle::new(),
+    }))
+}
+
+/// Release a handle; null is ignored
+///
+/// # Safety
+///
+/// `handle` must be null or come from `quic_bottom_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_free(handle: *mut QuicBottomHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Store a sample
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle from `quic_bottom_new`; `metrics`
+/// must be null or point to a readable `CQuicMetrics`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_update(handle: *const QuicBottomHandle, metrics: *const CQuicMetrics) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => update(&handle.metrics, metrics),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// `quic_bottom_update` for a `CQuicMetricsV2`
+///
+/// # Safety
+///
+/// As for `quic_bottom_update`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_update_v2(handle: *const QuicBottomHandle, metrics: *const CQuicMetricsV2) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => update(&handle.metrics, metrics),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// Write the last sample to `out`; all zeros before the first one
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle from `quic_bottom_new`; `out` must
+/// be null or point to writable memory for a `CQuicMetrics`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_get_current(handle: *const QuicBottomHandle, out: *mut CQuicMetrics) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => get_current(&handle.metrics, out),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// `quic_bottom_get_current` for a `CQuicMetricsV2`
+///
+/// # Safety
+///
+/// As for `quic_bottom_get_current`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_get_current_v2(handle: *const QuicBottomHandle, out: *mut CQuicMetricsV2) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => get_current(&handle.metrics, out),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// Store `len` samples in order
+///
+/// The whole batch is checked before anything is stored: if any sample is
+/// invalid, none is stored and `QUIC_BOTTOM_ERR_INVALID` is returned.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle from `quic_bottom_new`; `samples`
+/// must point to `len` readable `CQuicMetrics` (it may be null when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_update_batch(
+    handle: *const QuicBottomHandle,
+    samples: *const CQuicMetrics,
+    len: usize,
+) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => update_batch(&handle.metrics, samples, len),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// `quic_bottom_update_batch` for `CQuicMetricsV2` samples
+///
+/// # Safety
+///
+/// As for `quic_bottom_update_batch`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_update_batch_v2(
+    handle: *const QuicBottomHandle,
+    samples: *const CQuicMetricsV2,
+    len: usize,
+) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => update_batch(&handle.metrics, samples, len),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// Copy the stored history, oldest first, into `out_buf`
+///
+/// Returns the number of samples available, or a negative `QUIC_BOTTOM_*`
+/// code. When more are available than `cap`, the newest `cap` are copied;
+/// `*written` says how many. Pass a null `out_buf` and a `cap` of 0 to
+/// only ask for the count.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle from `quic_bottom_new`; `out_buf`
+/// must point to writable memory for `cap` `CQuicMetrics` (it may be null
+/// when `cap` is 0); `written` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_get_time_series(
+    handle: *const QuicBottomHandle,
+    out_buf: *mut CQuicMetrics,
+    cap: usize,
+    written: *mut usize,
+) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => copy_time_series(&handle.metrics, out_buf, cap, written),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// `quic_bottom_get_time_series` for `CQuicMetricsV2` samples
+///
+/// # Safety
+///
+/// As for `quic_bottom_get_time_series`.
+#[no_mangle]
+pub unsafe extern "C" fn quic_bottom_get_time_series_v2(
+    handle: *const QuicBottomHandle,
+    out_buf: *mut CQuicMetricsV2,
+    cap: usize,
+    written: *mut usize,
+) -> i32 {
+    match handle.as_ref() {
+        Some(handle) => copy_time_series(&handle.metrics, out_buf, cap, written),
+        None => QUIC_BOTTOM_ERR_NULL,
+    }
+}
+
+/// `quic_bottom_update_batch` on the global metrics, as shown by `start_quic_bottom`
+///
+/// # Safety
+///
+/// `samples` must point to `len` readable `CQuicMetrics` (it may be null
+/// when `len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn update_quic_metrics_batch(samples: *const CQuicMetrics, len: usize) -> i32 {
+    update_batch(&MetricsHandle::global(), samples, len)
+}
+
+/// `update_quic_metrics_batch` for `CQuicMetricsV2` samples
+///
+/// # Safety
+///
+/// As for `update_quic_metrics_batch`.
+#[no_mangle]
+pub unsafe extern "C" fn update_quic_metrics_batch_v2(samples: *const CQuicMetricsV2, len: usize) -> i32 {
+    update_batch(&MetricsHandle::global(), samples, len)
+}
+
+/// `quic_bottom_get_time_series` on the global metrics
+///
+/// # Safety
+///
+/// As for `quic_bottom_get_time_series`.
+#[no_mangle]
+pub unsafe extern "C" fn get_quic_time_series(out_buf: *mut CQuicMetrics, cap: usize, written: *mut usize) -> i32 {
+    copy_time_series(&MetricsHandle::global(), out_buf, cap, written)
+}
+
+/// `get_quic_time_series` for `CQuicMetricsV2` samples
+///
+/// # Safety
+///
+/// As for `quic_bottom_get_time_series`.
+#[no_mangle]
+pub unsafe extern "C" fn get_quic_time_series_v2(out_buf: *mut CQuicMetricsV2, cap: usize, written: *mut usize) -> i32 {
+    copy_time_series(&MetricsHandle::global(), out_buf, cap, written)
+}
+
+unsafe fn update<S: CSample>(metrics: &MetricsHandle, sample: *const S) -> i32 {
+    let Some(&sample) = sample.as_ref() else {
+        return QUIC_BOTTOM_ERR_NULL;
+    };
+    guard(|| {
+        let sample = match sample.to_metrics() {
+            Ok(sample) => sample,
+            Err(e) => {
+                record_ingest_error();
+                log::warn!("FFI: rejected sample: {}", e);
+                return QUIC_BOTTOM_ERR_INVALID;
+            }
+        };
+        match metrics.update(sample) {
+            Ok(()) => QUIC_BOTTOM_OK,
+            Err(e) => {
+                log::error!("FFI: failed to update metrics: {}", e);
+                QUIC_BOTTOM_ERR_INTERNAL
+            }
+        }
+    })
+}
+
+unsafe fn get_current<S: CSample>(metrics: &MetricsHandle, out: *mut S) -> i32 {
+    if out.is_null() {
+        return QUIC_BOTTOM_ERR_NULL;
+    }
+    guard(|| {
+        let current = match metrics.last_update_age() {
+            Some(_) => S::from_metrics(&metrics.current()),
+            None => S::default(),
+        };
+        out.write(current);
+        QUIC_BOTTOM_OK
+    })
+}
+
+unsafe fn update_batch<S: CSample>(metrics: &MetricsHandle, samples: *const S, len: usize) -> i32 {
+    if len == 0 {
+        return QUIC_BOTTOM_OK;
+    }
+    if samples.is_null() {
+        return QUIC_BOTTOM_ERR_NULL;
+    }
+    let samples = std::slice::from_raw_parts(samples, len);
+    guard(|| {
+        let converted = samples
+            .iter()
+            .enumerate()
+            .map(|(index, sample)| {
+                sample
+                    .to_metrics()
+                    .and_then(|metrics| metrics.validate().map(|()| metrics))
+                    .map_err(|e| anyhow!("sample {}: {}", index, e))
+            })
+            .collect::<Result<Vec<_>>>();
+        let converted = match converted {
+            Ok(converted) => converted,
+            Err(e) => {
+                record_ingest_error();
+                log::warn!("FFI: rejected batch: {}", e);
+                return QUIC_BOTTOM_ERR_INVALID;
+            }
+        };
+        // Samples from C carry no `source`, so nothing refuses a validated one here
+        for sample in converted {
+            if let Err(e) = metrics.update(sample) {
+                log::error!("FFI: failed to update metrics: {}", e);
+                return QUIC_BOTTOM_ERR_INTERNAL;
+            }
+        }
+        QUIC_BOTTOM_OK
+    })
+}
+
+unsafe fn copy_time_series<S: CSample>(metrics: &MetricsHandle, out_buf: *mut S, cap: usize, written: *mut usize) -> i32 {
+    if cap > 0 && out_buf.is_null() {
+        return QUIC_BOTTOM_ERR_NULL;
+    }
+    guard(|| {
+        let history = metrics.history(None, None, usize::MAX);
+        let copied = history.len().min(cap);
+        for (i, sample) in history[history.len() - copied..].iter().enumerate() {
+            out_buf.add(i).write(S::from_metrics(sample));
+        }
+        if let Some(written) = written.as_mut() {
+            *written = copied;
+        }
+        i32::try_from(history.len()).unwrap_or(i32::MAX)
+    })
+}
+
+// Deprecated API over the global metrics, kept for existing bindings
+
+/// FFI function to update QUIC metrics from Go
+///
+/// Updates the global metrics and does nothing before `init_metrics`.
+/// `throughput` is in bits per second and `packet_loss` a fraction, 0.0-1.0.
+/// Returns -1 for a negative or non-finite value.
+#[deprecated(note = "use quic_bottom_update with a handle from quic_bottom_new")]
+#[no_mangle]
+pub extern "C" fn update_quic_metrics(
+    latency: f64,
+    throughput: f64,
+    connections: i32,
+    errors: i32,
+    packet_loss: f64,
+    retransmits: i32,
+) -> i32 {
+    log::debug!(
+        "Updating QUIC metrics: latency={}, throughput={}, connections={}, errors={}, loss={}, retransmits={}",
+        latency, throughput, connections, errors, packet_loss, retransmits
+    );
+    
+    // Update global metrics state
+    if let Err(e) = update_metrics(QUICMetrics {
+        latency,
+        throughput,
+        connections,
+        errors,
+        packet_loss,
+        retransmits,
+        timestamp: chrono::Utc::now(),
+        ..QUICMetrics::default()
+    }) {
+        record_ingest_error();
+        log::error!("Failed to update metrics: {}", e);
+        return -1;
+    }
+    
+    0
+}
+
+/// FFI function to get current metrics
+///
+/// The returned struct is not C-compatible; null before `init_metrics`.
+#[deprecated(note = "use quic_bottom_get_current, which fills a CQuicMetrics")]
+#[no_mangle]
+pub extern "C" fn get_quic_metrics() -> *mut QUICMetrics {
+    match get_current_metrics() {
+        Some(metrics) => {
+            let boxed = Box::new(metrics);
+            Box::into_raw(boxed)
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// FFI function to free metrics memory
+///
+/// # Safety
+///
+/// `ptr` must be null or come from `get_quic_metrics` and not have been freed.
+#[deprecated(note = "only needed for get_quic_metrics")]
+#[no_mangle]
+pub unsafe extern "C" fn free_quic_metrics(ptr: *mut QUICMetrics) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr));
+    }
+}
+
+/// Same as `update_quic_metrics`
+#[deprecated(note = "use quic_bottom_update with a handle from quic_bottom_new")]
+#[allow(deprecated)]
+#[no_mangle]
+pub extern "C" fn update_quic_metrics_ffi(
+    latency: f64,
+    throughput: f64,
+    connections: i32,
+    errors: i32,
+    packet_loss: f64,
+    retransmits: i32,
+) -> i32 {
+    update_quic_metrics(latency, throughput, connections, errors, packet_loss, retransmits)
+}
+
+/// Same as `get_quic_metrics`
+#[deprecated(note = "use quic_bottom_get_current, which fills a CQuicMetrics")]
+#[allow(deprecated)]
+#[no_mangle]
+pub extern "C" fn get_quic_metrics_ffi() -> *mut QUICMetrics {
+    get_quic_metrics()
+}
+
+/// Same as `free_quic_metrics`
+///
+/// # Safety
+///
+/// As for `free_quic_metrics`.
+#[deprecated(note = "only needed for get_quic_metrics_ffi")]
+#[allow(deprecated)]
+#[no_mangle]
+pub unsafe extern "C" fn free_quic_metrics_ffi(ptr: *mut QUICMetrics) {
+    free_quic_metrics(ptr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn handle_round_trips_a_sample() {
+        let sample = CQuicMetrics {
+            latency: 12.5,
+            throughput: 8_000_000.0,
+            connections: 3,
+            errors: 1,
+            packet_loss: 0.02,
+            retransmits: 4,
+            timestamp_ms: 1_700_000_000_000,
+        };
+        unsafe {
+            let handle = quic_bottom_new();
+            let mut out = CQuicMetrics::default();
+            assert_eq!(quic_bottom_get_current(handle, &mut out), QUIC_BOTTOM_OK);
+            assert_eq!(out.connections, 0);
+
+            assert_eq!(quic_bottom_update(handle, &sample), QUIC_BOTTOM_OK);
+            assert_eq!(quic_bottom_get_current(handle, &mut out), QUIC_BOTTOM_OK);
+            assert_eq!(out.latency, sample.latency);
+            assert_eq!(out.connections, sample.connections);
+            assert_eq!(out.timestamp_ms, sample.timestamp_ms);
+            quic_bottom_free(handle);
+        }
+    }
+
+    #[test]
+    fn rejects_null_and_invalid_input() {
+        unsafe {
+            let handle = quic_bottom_new();
+            let invalid = CQuicMetrics {
+                latency: f64::NAN,
+                ..CQuicMetrics::default()
+            };
+            assert_eq!(quic_bottom_update(handle, &invalid), QUIC_BOTTOM_ERR_INVALID);
+            assert_eq!(quic_bottom_update(handle, std::ptr::null()), QUIC_BOTTOM_ERR_NULL);
+            assert_eq!(quic_bottom_update(std::ptr::null(), &CQuicMetrics::default()), QUIC_BOTTOM_ERR_NULL);
+            assert_eq!(quic_bottom_get_current(handle, std::ptr::null_mut()), QUIC_BOTTOM_ERR_NULL);
+            quic_bottom_free(handle);
+            quic_bottom_free(std::ptr::null_mut());
+        }
+    }
+
+    fn samples(latencies: impl IntoIterator<Item = i64>) -> Vec<CQuicMetrics> {
+        latencies
+            .into_iter()
+            .map(|i| CQuicMetrics {
+                latency: i as f64,
+                throughput: 1_000_000.0,
+                connections: 1,
+                timestamp_ms: 1_700_000_000_000 + i,
+                ..CQuicMetrics::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_and_time_series_round_trip() {
+        let batch = samples(1..=5);
+        unsafe {
+            let handle = quic_bottom_new();
+            assert_eq!(quic_bottom_update_batch(handle, batch.as_ptr(), batch.len()), QUIC_BOTTOM_OK);
+
+            // Only the count
+            let mut written = usize::MAX;
+            assert_eq!(quic_bottom_get_time_series(handle, std::ptr::null_mut(), 0, &mut written), 5);
+            assert_eq!(written, 0);
+
+            // A buffer too small gets the newest samples that fit and the total available
+            let mut out = [CQuicMetrics::default(); 3];
+            assert_eq!(quic_bottom_get_time_series(handle, out.as_mut_ptr(), out.len(), &mut written), 5);
+            assert_eq!(written, 3);
+            assert_eq!(out.map(|sample| sample.latency), [3.0, 4.0, 5.0]);
+            assert_eq!(out[2].timestamp_ms, batch[4].timestamp_ms);
+
+            // A larger one is filled only as far as the history goes
+            let unset = CQuicMetrics {
+                latency: -1.0,
+                ..CQuicMetrics::default()
+            };
+            let mut out = [unset; 8];
+            assert_eq!(quic_bottom_get_time_series(handle, out.as_mut_ptr(), out.len(), &mut written), 5);
+            assert_eq!(written, 5);
+            assert_eq!(out[4].latency, 5.0);
+            assert_eq!(out[5].latency, -1.0);
+
+            let mut out = [CQuicMetricsV2::default(); 2];
+            assert_eq!(quic_bottom_get_time_series_v2(handle, out.as_mut_ptr(), out.len(), &mut written), 5);
+            assert_eq!((written, out[0].latency, out[1].latency), (2, 4.0, 5.0));
+
+            // One invalid sample in the middle stores none of the batch
+            let mut invalid = samples(6..=8);
+            invalid[1].latency = f64::NAN;
+            assert_eq!(
+                quic_bottom_update_batch(handle, invalid.as_ptr(), invalid.len()),
+                QUIC_BOTTOM_ERR_INVALID
+            );
+            assert_eq!(quic_bottom_get_time_series(handle, std::ptr::null_mut(), 0, std::ptr::null_mut()), 5);
+
+            assert_eq!(quic_bottom_update_batch(handle, std::ptr::null(), 0), QUIC_BOTTOM_OK);
+            assert_eq!(quic_bottom_update_batch(handle, std::ptr::null(), 1), QUIC_BOTTOM_ERR_NULL);
+            assert_eq!(quic_bottom_get_time_series(handle, std::ptr::null_mut(), 1, &mut written), QUIC_BOTTOM_ERR_NULL);
+            quic_bottom_free(handle);
+        }
+    }
+
+    #[test]
+    fn global_batch_and_time_series_round_trip() {
+        // Other tests may use the global metrics too, so only what this one adds is checked
+        let batch = samples(1..=3);
+        unsafe {
+            assert_eq!(update_quic_metrics_batch(batch.as_ptr(), batch.len()), QUIC_BOTTOM_OK);
+            let mut out = [CQuicMetrics::default(); 2];
+            let mut written = 0;
+            let available = get_quic_time_series(out.as_mut_ptr(), out.len(), &mut written);
+            assert!(available >= 3, "{}", available);
+            assert_eq!(written, 2);
+
+            let mut out = [CQuicMetricsV2::default(); 1];
+            assert!(get_quic_time_series_v2(out.as_mut_ptr(), out.len(), &mut written) >= 3);
+            assert_eq!(written, 1);
+        }
+    }
+
+    #[test]
+    fn header_matches_ffi_source() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+        let mut generated = Vec::new();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(crate_dir.join("src/ffi.rs"))
+            .generate()
+            .unwrap()
+            .write(&mut generated);
+        let generated = String::from_utf8(generated).unwrap();
+        let committed = std::fs::read_to_string(crate_dir.join("include/quic_bottom.h")).unwrap();
+        assert_eq!(committed, generated, "include/quic_bottom.h is stale; rebuild to regenerate it");
+
+        for declaration in [
+            "struct QuicBottomHandle *quic_bottom_new(void);",
+            "void quic_bottom_free(struct QuicBottomHandle *handle);",
+            "int32_t quic_bottom_update(const struct QuicBottomHandle *handle,",
+            "int32_t quic_bottom_get_current(const struct QuicBottomHandle *handle,",
+            "void free_quic_metrics(QUICMetrics *ptr);",
+            "#define QUIC_BOTTOM_ERR_NULL -1",
+            "int64_t timestamp_ms;",
+        ] {
+            assert!(committed.contains(declaration), "missing from header: {}", declaration);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Focused and expanded widget of a dashboard view
+//!
+//! Tab and Shift-Tab move the focus through the panels of the current view,
+//! clicking a panel focuses it, and the expand key draws only the focused
+//! panel over the whole main area until it is pressed again.
+
+use ratatui::style::Style;
+
+use crate::improved_layout::WidgetKind;
+use crate::theme::Theme;
+
+/// Dashboard widgets that can take the focus and be expanded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    LatencyGraph,
+    ThroughputGraph,
+    Heatmap,
+    /// The correlation matrix, or the pair detail that replaces it
+    Correlation,
+    Anomaly,
+}
+
+impl Panel {
+    /// Shown in the header while the panel is expanded
+    pub fn name(self) -> &'static str {
+        match self {
+            Panel::LatencyGraph => "Latency",
+            Panel::ThroughputGraph => "Throughput",
+            Panel::Heatmap => "Performance Heatmap",
+            Panel::Correlation => "Correlation",
+            Panel::Anomaly => "Anomaly Detection",
+        }
+    }
+
+    /// The widget `[widgets]` turns on and off
+    pub fn widget(self) -> WidgetKind {
+        match self {
+            Panel::LatencyGraph => WidgetKind::Latency,
+            Panel::ThroughputGraph => WidgetKind::Throughput,
+            Panel::Heatmap => WidgetKind::Heatmap,
+            Panel::Correlation => WidgetKind::Correlation,
+            Panel::Anomaly => WidgetKind::Anomaly,
+        }
+    }
+
+    /// The panel drawing `widget`, if it can take the focus
+    pub fn for_widget(widget: WidgetKind) -> Option<Panel> {
+        match widget {
+            WidgetKind::Latency => Some(Panel::LatencyGraph),
+            WidgetKind::Throughput => Some(Panel::ThroughputGraph),
+            WidgetKind::Heatmap => Some(Panel::Heatmap),
+            WidgetKind::Correlation => Some(Panel::Correlation),
+            WidgetKind::Anomaly => Some(Panel::Anomaly),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FocusState {
+    focused: Option<Panel>,
+    expanded: bool,
+}
+
+impl FocusState {
+    /// The focused panel, if any
+    pub fn focused(&self) -> Option<Panel> {
+        self.focused
+    }
+
+    /// Focus `panel`, or nothing; an expanded view follows the focus
+    pub fn set(&mut self, panel: Option<Panel>) {
+        self.focused = panel;
+        self.expanded &= panel.is_some();
+    }
+
+    /// Move the focus to the next (or previous) of the view's `panels`, wrapping around
+    ///
+    /// Starts from the first (or last) panel when the focus is not in the view.
+    pub fn cycle(&mut self, panels: &[Panel], forward: bool) {
+        if panels.is_empty() {
+            return;
+        }
+        let current = self.focused.and_then(|focused| panels.iter().position(|p| *p == focused));
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % panels.len(),
+            (Some(i), false) => (i + panels.len() - 1) % panels.len(),
+            (None, true) => 0,
+            (None, false) => panels.len() - 1,
+        };
+        self.focused = Some(panels[next]);
+    }
+
+    /// Expand the focused panel, or restore the grid; returns whether it is now expanded
+    ///
+    /// Needs a focused panel among the view's `panels`.
+    pub fn toggle_expanded(&mut self, panels: &[Panel]) -> bool {
+        self.expanded = !self.expanded && self.focused.is_some_and(|focused| panels.contains(&focused));
+        self.expanded
+    }
+
+    /// The panel to draw alone, when expanded and in the view's `panels`
+    pub fn expanded(&self, panels: &[Panel]) -> Option<Panel> {
+        self.focused.filter(|focused| self.expanded && panels.contains(focused))
+    }
+}
+
+/// Title of a widget block, highlighted when focused
+pub fn title_style(theme: &Theme, selected: bool) -> Style {
+    theme.title(if selected { theme.accent } else { theme.text })
+}
+
+/// Border of a widget block, highlighted when focused
+pub fn border_style(theme: &Theme, selected: bool) -> Style {
+    if selected {
+        Style::default().fg(theme.accent)
+    } else {
+        Style::default()
+    }
+}
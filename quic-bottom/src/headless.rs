@@ -0,0 +1,198 @@
+//! The default mode without a terminal
+//!
+//! `quic-bottom --headless` serves the same API and runs the same ingestion,
+//! anomaly detection and SLA evaluation as the dashboard, but never sets up
+//! the terminal, so it runs under systemd or in a container without a TTY.
+//! Instead of drawing, it prints a one-line status every `STATUS_INTERVAL`.
+
+use anyhow::{anyhow, Result};
+use log::info;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::alerting::Alerter;
+use crate::anomaly_detection::{AnomalyDetector, DEFAULT_SENSITIVITY};
+use crate::exporters::influx::{InfluxExporter, InfluxQueue};
+use crate::metrics::{add_metrics_sink, format_age, format_value, MetricsHandle, QUICMetrics};
+use crate::redact::Redactor;
+use crate::report::{write_report, SessionReport};
+use crate::shutdown;
+use crate::sla::SlaStatus;
+use crate::units::{format_loss, format_rate};
+use crate::QuicBottomConfig;
+
+/// How often the status line is printed
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Start every ingestion path of the default mode over `metrics`
+///
+/// Binds the HTTP API, the statsd listener and the Unix socket as configured
+/// and forwards samples to InfluxDB when it is enabled, returning its queue.
+/// The dashboard and headless mode share this, so both ingest the same way.
+pub async fn start_ingestion(
+    config: &QuicBottomConfig,
+    metrics: &MetricsHandle,
+    interval: Duration,
+) -> Result<Option<Arc<InfluxQueue>>> {
+    metrics.set_derived_config(config.derived.clone());
+    metrics.set_sla_config(config.sla.clone());
+    metrics.set_health_config(config.health.clone());
+
+    let server = crate::bridge::bind_api_server(config.api_addr()?, &config.api, metrics.clone())?;
+    tokio::spawn(server);
+
+    let statsd = &config.ingest.statsd;
+    if statsd.enabled {
+        let listener = crate::ingest::statsd::StatsdListener::bind(statsd).await?;
+        info!("statsd ingestion on udp://{}", listener.local_addr()?);
+        tokio::spawn(listener.serve(metrics.clone(), interval));
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = &config.ingest.uds_path {
+        use crate::bridge::MetricsRequest;
+
+        let uds = crate::ingest::uds::UdsListener::bind(path)?;
+        info!("UDS ingestion on {}", uds.path().display());
+        let metrics = metrics.clone();
+        tokio::spawn(uds.serve(move |req: MetricsRequest| {
+            req.validate()?;
+            metrics.update(req.to_metrics(chrono::Utc::now()))
+        }));
+    }
+
+    if !config.exporters.influx.is_enabled() {
+        return Ok(None);
+    }
+    let exporter = InfluxExporter::new(config.exporters.influx.clone())?;
+    let queue = exporter.queue();
+    add_metrics_sink(Box::new({
+        let queue = Arc::clone(&queue);
+        move |metrics| queue.push(metrics)
+    }));
+    exporter.spawn();
+    crate::health::register_exporter(Arc::clone(&queue));
+    Ok(Some(queue))
+}
+
+/// Run the default mode without a TUI until SIGINT or SIGTERM
+///
+/// Samples are checked for anomalies every `interval_ms`. On the way out the
+/// session report is written and, with SLA targets set, a run that did not
+/// pass returns an error so the process exits non-zero.
+pub async fn run(config: QuicBottomConfig, interval_ms: u64) -> Result<()> {
+    let interval = Duration::from_millis(interval_ms.max(1));
+    let metrics = MetricsHandle::global();
+    let exporter = start_ingestion(&config, &metrics, interval).await?;
+    println!(
+        "QUIC Bottom v{} headless, API on {}://{}; Ctrl+C to stop",
+        env!("CARGO_PKG_VERSION"),
+        config.api.scheme(),
+        config.api_addr()?
+    );
+
+    let mut detector = AnomalyDetector::with_config(DEFAULT_SENSITIVITY, config.anomaly.clone());
+    let anomaly_window = chrono::Duration::seconds(config.health.anomaly_window_secs as i64);
+    if config.alerting.is_enabled() {
+        Alerter::new(config.alerting.clone())?.spawn(detector.subscribe());
+    }
+
+    let mut mark = metrics.history_mark();
+    let mut seen_reset = metrics.last_reset().map_or(0, |event| event.id);
+    let mut last_status = Instant::now();
+    let mut status_mark = mark;
+    let mut ticker = tokio::time::interval(interval);
+    // Created once, so a signal between two ticks is not missed
+    let signal = shutdown::signal();
+    tokio::pin!(signal);
+    let signal_name = loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            name = &mut signal => break name,
+        }
+
+        if let Some(event) = metrics.last_reset().filter(|event| event.id > seen_reset) {
+            seen_reset = event.id;
+            if event.outcome.scope.anomalies {
+                detector.clear();
+            }
+            println!("Remote reset by {}, segment {}", event.source, event.outcome.segment);
+        }
+        for sample in metrics.history_since(mark) {
+            detect(&mut detector, &sample);
+        }
+        mark = metrics.history_mark();
+        metrics.set_anomaly_counts(detector.counts_since(chrono::Utc::now() - anomaly_window));
+
+        if last_status.elapsed() >= STATUS_INTERVAL {
+            println!("{}", status_line(&metrics, &detector, mark - status_mark, exporter.as_deref()));
+            last_status = Instant::now();
+            status_mark = mark;
+        }
+    };
+    println!("shutting down ({})", signal_name);
+    if let Some(queue) = &exporter {
+        shutdown::flush_exporter(queue).await;
+    }
+
+    let samples = metrics.history(None, None, usize::MAX);
+    if config.report.on_exit && !samples.is_empty() {
+        let report = SessionReport::build(&samples, &detector.anomalies(), &[], chrono::Utc::now())
+            .with_health(metrics.health_score());
+        let redactor = config.redaction.enabled.then(|| Redactor::new(&config.redaction));
+        match write_report(&config.report, &report, redactor.as_ref()) {
+            Ok(paths) => println!("{}", paths),
+            Err(e) => eprintln!("Report failed: {}", e),
+        }
+    }
+
+    // CI gates on the exit status
+    if config.sla.is_enabled() {
+        let verdict = metrics.sla();
+        println!("SLA: {}", verdict.summary());
+        if config.sla.exit_on_fail && verdict.is_failure() {
+            return Err(anyhow!("SLA not met: {}", verdict.summary()));
+        }
+    }
+    Ok(())
+}
+
+/// Feed one sample to the detector under the anomaly widget's metric names
+fn detect(detector: &mut AnomalyDetector, sample: &QUICMetrics) {
+    detector.add_data_point("Latency".to_string(), sample.latency);
+    detector.add_data_point("Throughput".to_string(), sample.throughput);
+    detector.add_data_point("Packet Loss".to_string(), sample.packet_loss * 100.0);
+    detector.add_data_point("Connections".to_string(), sample.connections as f64);
+    detector.add_data_point("Errors".to_string(), sample.errors as f64);
+}
+
+/// `+42 samples | last 0.1s ago | latency 12.30ms | ... | SLA PASS`
+fn status_line(
+    metrics: &MetricsHandle,
+    detector: &AnomalyDetector,
+    new_samples: u64,
+    exporter: Option<&InfluxQueue>,
+) -> String {
+    let current = metrics.current();
+    let age = metrics
+        .last_update_age()
+        .map_or("no samples yet".to_string(), |age| format!("last {} ago", format_age(age)));
+    let mut line = format!(
+        "+{} samples | {} | latency {}ms | throughput {} | loss {} | anomalies {}",
+        new_samples,
+        age,
+        format_value(current.latency, 2),
+        format_rate(current.throughput, 2),
+        format_loss(current.packet_loss, 2),
+        detector.anomalies.len()
+    );
+    line.push_str(&format!(" | health {}", metrics.health_score().label()));
+    let sla = metrics.sla();
+    if sla.status != SlaStatus::Disabled {
+        line.push_str(&format!(" | SLA {}", sla.summary()));
+    }
+    if let Some(queue) = exporter {
+        line.push_str(&format!(" | {}", queue.status_line()));
+    }
+    line
+}
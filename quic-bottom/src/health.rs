@@ -0,0 +1,128 @@
+//! Readiness report behind `GET /health`
+//!
+//! The bridge API answers 200 while it can serve data and 503 otherwise,
+//! with the same JSON body either way, so probes and run scripts can gate
+//! on the status code and people can read why.
+
+use serde::Serialize;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::exporters::influx::InfluxQueue;
+use crate::ingest::ingest_errors;
+use crate::metrics::MetricsHandle;
+
+static EXPORTER: OnceLock<Arc<InfluxQueue>> = OnceLock::new();
+
+/// Include the state of an InfluxDB exporter in health reports
+pub fn register_exporter(queue: Arc<InfluxQueue>) {
+    let _ = EXPORTER.set(queue);
+}
+
+/// Body of `GET /health`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// `ok`, or `unavailable` when any of `problems` applies
+    pub status: &'static str,
+    pub service: &'static str,
+    pub version: &'static str,
+    pub uptime_secs: u64,
+    /// Whether the store behind the API is set up and consumed
+    pub metrics_initialized: bool,
+    /// Time since the last sample arrived; `None` before the first one
+    pub last_sample_age_ms: Option<u64>,
+    pub samples_total: u64,
+    /// Samples received but refused, e.g. by validation
+    pub ingest_errors: u64,
+    /// InfluxDB exporter state, e.g. `export: OK`, when one is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exporter: Option<String>,
+    /// Why the service is not ready
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+}
+
+impl HealthReport {
+    /// Collect the report; with `max_sample_age` set, a missing or older sample is a problem
+    pub fn collect(metrics: &MetricsHandle, started: Instant, max_sample_age: Option<Duration>) -> Self {
+        // A handle is initialized once created; `init_metrics` only creates the global one
+        Self::build(true, metrics.last_update_age(), metrics.history_mark(), started, max_sample_age)
+    }
+
+    /// Build the report for a store other than `MetricsHandle`, such as the live dashboard's
+    pub fn build(
+        initialized: bool,
+        last_sample_age: Option<Duration>,
+        samples_total: u64,
+        started: Instant,
+        max_sample_age: Option<Duration>,
+    ) -> Self {
+        let mut problems = Vec::new();
+        if !initialized {
+            problems.push("metrics system is not initialized".to_string());
+        }
+        if let Some(max_age) = max_sample_age {
+            match last_sample_age {
+                None => problems.push("no sample received yet".to_string()),
+                Some(age) if age > max_age => problems.push(format!(
+                    "last sample is {}ms old, limit is {}ms",
+                    age.as_millis(),
+                    max_age.as_millis()
+                )),
+                Some(_) => {}
+            }
+        }
+
+        Self {
+            status: if problems.is_empty() { "ok" } else { "unavailable" },
+            service: "quic-bottom",
+            version: env!("CARGO_PKG_VERSION"),
+            uptime_secs: started.elapsed().as_secs(),
+            metrics_initialized: initialized,
+            last_sample_age_ms: last_sample_age.map(|age| age.as_millis() as u64),
+            samples_total,
+            ingest_errors: ingest_errors(),
+            exporter: EXPORTER.get().map(|queue| queue.status_line()),
+            problems,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::QUICMetrics;
+
+    #[test]
+    fn ready_until_a_check_fails() {
+        let metrics = MetricsHandle::new();
+        let started = Instant::now();
+        assert!(HealthReport::collect(&metrics, started, None).is_ready());
+
+        let report = HealthReport::collect(&metrics, started, Some(Duration::from_secs(60)));
+        assert_eq!(report.status, "unavailable");
+        assert_eq!(report.problems, ["no sample received yet"]);
+
+        metrics.update(QUICMetrics::default()).unwrap();
+        let report = HealthReport::collect(&metrics, started, Some(Duration::from_secs(60)));
+        assert!(report.is_ready());
+        assert!(report.metrics_initialized);
+        assert_eq!(report.samples_total, 1);
+    }
+
+    #[test]
+    fn uninitialized_or_stale_is_unavailable() {
+        let started = Instant::now();
+        let report = HealthReport::build(false, Some(Duration::from_millis(10)), 3, started, None);
+        assert!(!report.is_ready());
+        assert_eq!(report.problems, ["metrics system is not initialized"]);
+
+        let report = HealthReport::build(true, Some(Duration::from_secs(5)), 3, started, Some(Duration::from_secs(1)));
+        assert_eq!(report.problems, ["last sample is 5000ms old, limit is 1000ms"]);
+        assert_eq!(serde_json::to_value(&report).unwrap()["last_sample_age_ms"], 5000);
+    }
+}
@@ -0,0 +1,420 @@
+//! Composite health score of the monitored connection
+//!
+//! One 0-100 number for those who want a single figure. Each component
+//! (latency, loss, retransmits, errors, anomalies, staleness) scores 100 at
+//! or below its target, falling linearly to 0 at its limit, and the score is
+//! the weighted mean of the components that have a value. A component
+//! without one, e.g. retransmits from a sender that does not report
+//! `bytes_sent`, is left out and the other weights grow to fill its share.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::anomaly_detection::AnomalySeverity;
+use crate::metrics::{DerivedMetrics, QUICMetrics};
+
+/// Share of each component in the score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthWeights {
+    pub latency: f64,
+    pub loss: f64,
+    pub retransmits: f64,
+    pub errors: f64,
+    pub anomalies: f64,
+    pub staleness: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            latency: 0.3,
+            loss: 0.25,
+            retransmits: 0.15,
+            errors: 0.1,
+            anomalies: 0.1,
+            staleness: 0.1,
+        }
+    }
+}
+
+impl HealthWeights {
+    fn sum(&self) -> f64 {
+        self.latency + self.loss + self.retransmits + self.errors + self.anomalies + self.staleness
+    }
+}
+
+/// Points one anomaly in the window takes off the anomaly component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyPenalties {
+    pub low: f64,
+    pub medium: f64,
+    pub high: f64,
+    pub critical: f64,
+}
+
+impl Default for AnomalyPenalties {
+    fn default() -> Self {
+        Self {
+            low: 1.0,
+            medium: 4.0,
+            high: 10.0,
+            critical: 25.0,
+        }
+    }
+}
+
+impl AnomalyPenalties {
+    fn of(&self, severity: &AnomalySeverity) -> f64 {
+        match severity {
+            AnomalySeverity::Low => self.low,
+            AnomalySeverity::Medium => self.medium,
+            AnomalySeverity::High => self.high,
+            AnomalySeverity::Critical => self.critical,
+        }
+    }
+}
+
+/// `[health]` section of the config file
+///
+/// Weights that do not add up to 1.0 are scaled until they do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    pub weights: HealthWeights,
+    /// Latency scoring full marks, in ms
+    pub latency_target_ms: f64,
+    /// Latency scoring nothing, in ms
+    pub latency_limit_ms: f64,
+    pub loss_target_pct: f64,
+    pub loss_limit_pct: f64,
+    /// Share of sent bytes that were retransmissions, in percent
+    pub retransmit_target_pct: f64,
+    pub retransmit_limit_pct: f64,
+    /// Errors per active connection
+    pub error_rate_target: f64,
+    pub error_rate_limit: f64,
+    /// Age of the newest sample, in seconds
+    pub staleness_target_secs: f64,
+    pub staleness_limit_secs: f64,
+    /// Seconds back from now in which anomalies count against the score
+    pub anomaly_window_secs: u64,
+    pub anomaly_penalties: AnomalyPenalties,
+    /// Lowest score shown as good
+    pub good_above: f64,
+    /// Lowest score shown as fair; anything lower is poor
+    pub fair_above: f64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            weights: HealthWeights::default(),
+            latency_target_ms: 50.0,
+            latency_limit_ms: 500.0,
+            loss_target_pct: 0.5,
+            loss_limit_pct: 5.0,
+            retransmit_target_pct: 1.0,
+            retransmit_limit_pct: 10.0,
+            error_rate_target: 0.0,
+            error_rate_limit: 0.5,
+            staleness_target_secs: 2.0,
+            staleness_limit_secs: 10.0,
+            anomaly_window_secs: 60,
+            anomaly_penalties: AnomalyPenalties::default(),
+            good_above: 80.0,
+            fair_above: 50.0,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Check the weights, that every target lies below its limit and the thresholds
+    pub fn validate(&self) -> Result<()> {
+        let w = &self.weights;
+        let weights = [
+            ("latency", w.latency),
+            ("loss", w.loss),
+            ("retransmits", w.retransmits),
+            ("errors", w.errors),
+            ("anomalies", w.anomalies),
+            ("staleness", w.staleness),
+        ];
+        for (name, weight) in weights {
+            if !(weight.is_finite() && weight >= 0.0) {
+                bail!("health.weights.{} must be a number >= 0, got {}", name, weight);
+            }
+        }
+        if w.sum() <= 0.0 {
+            bail!("health.weights: at least one weight must be above 0");
+        }
+
+        let ranges = [
+            ("latency_target_ms", self.latency_target_ms, "latency_limit_ms", self.latency_limit_ms),
+            ("loss_target_pct", self.loss_target_pct, "loss_limit_pct", self.loss_limit_pct),
+            ("retransmit_target_pct", self.retransmit_target_pct, "retransmit_limit_pct", self.retransmit_limit_pct),
+            ("error_rate_target", self.error_rate_target, "error_rate_limit", self.error_rate_limit),
+            ("staleness_target_secs", self.staleness_target_secs, "staleness_limit_secs", self.staleness_limit_secs),
+        ];
+        for (target_key, target, limit_key, limit) in ranges {
+            if !(target.is_finite() && limit.is_finite() && 0.0 <= target && target < limit) {
+                bail!(
+                    "health.{} ({}) must be >= 0 and below health.{} ({})",
+                    target_key,
+                    target,
+                    limit_key,
+                    limit
+                );
+            }
+        }
+
+        let p = &self.anomaly_penalties;
+        for (name, penalty) in [("low", p.low), ("medium", p.medium), ("high", p.high), ("critical", p.critical)] {
+            if !(penalty.is_finite() && penalty >= 0.0) {
+                bail!("health.anomaly_penalties.{} must be a number >= 0, got {}", name, penalty);
+            }
+        }
+        if !(0.0..=100.0).contains(&self.fair_above)
+            || !(0.0..=100.0).contains(&self.good_above)
+            || self.fair_above > self.good_above
+        {
+            bail!(
+                "health.fair_above ({}) and health.good_above ({}) must be 0-100, fair_above not above good_above",
+                self.fair_above,
+                self.good_above
+            );
+        }
+        Ok(())
+    }
+
+    pub fn anomaly_window(&self) -> Duration {
+        Duration::from_secs(self.anomaly_window_secs)
+    }
+
+    /// Score `inputs` against the targets
+    pub fn score(&self, inputs: &HealthInputs) -> HealthScore {
+        // Before the first sample the inputs hold defaults rather than measurements
+        let empty = HealthInputs::default();
+        let inputs = if inputs.sample_age.is_some() { inputs } else { &empty };
+        let w = &self.weights;
+        let anomaly_score = inputs.anomalies.as_ref().map(|counts| {
+            let penalty: f64 = counts
+                .iter()
+                .map(|(severity, count)| self.anomaly_penalties.of(severity) * *count as f64)
+                .sum();
+            (100.0 - penalty).max(0.0)
+        });
+        let anomaly_count = inputs.anomalies.as_ref().map(|counts| counts.values().sum::<usize>() as f64);
+
+        let linear = |value: Option<f64>, target: f64, limit: f64| {
+            value.filter(|v| v.is_finite()).map(|v| {
+                let score = (limit - v) / (limit - target) * 100.0;
+                (v, score.clamp(0.0, 100.0))
+            })
+        };
+        let mut components = vec![
+            HealthComponent::new(
+                "latency",
+                w.latency,
+                linear(inputs.latency_ms, self.latency_target_ms, self.latency_limit_ms),
+            ),
+            HealthComponent::new("loss", w.loss, linear(inputs.loss_pct, self.loss_target_pct, self.loss_limit_pct)),
+            HealthComponent::new(
+                "retransmits",
+                w.retransmits,
+                linear(inputs.retransmit_pct, self.retransmit_target_pct, self.retransmit_limit_pct),
+            ),
+            HealthComponent::new(
+                "errors",
+                w.errors,
+                linear(inputs.error_rate, self.error_rate_target, self.error_rate_limit),
+            ),
+            HealthComponent::new("anomalies", w.anomalies, anomaly_count.zip(anomaly_score)),
+            HealthComponent::new(
+                "staleness",
+                w.staleness,
+                linear(
+                    inputs.sample_age.map(|age| age.as_secs_f64()),
+                    self.staleness_target_secs,
+                    self.staleness_limit_secs,
+                ),
+            ),
+        ];
+
+        // Components without a value hand their weight to the others
+        let present: f64 = components.iter().filter(|c| c.score.is_some()).map(|c| c.weight).sum();
+        let score = if present <= 0.0 {
+            None
+        } else {
+            for component in &mut components {
+                component.weight = if component.score.is_some() { component.weight / present } else { 0.0 };
+            }
+            Some(components.iter().filter_map(|c| c.score.map(|s| s * c.weight)).sum::<f64>())
+        };
+
+        HealthScore {
+            score,
+            level: match score {
+                None => HealthLevel::NoData,
+                Some(score) if score >= self.good_above => HealthLevel::Good,
+                Some(score) if score >= self.fair_above => HealthLevel::Fair,
+                Some(_) => HealthLevel::Poor,
+            },
+            components,
+        }
+    }
+}
+
+/// What the score is computed from; `None` leaves a component out
+#[derive(Debug, Clone, Default)]
+pub struct HealthInputs {
+    pub latency_ms: Option<f64>,
+    pub loss_pct: Option<f64>,
+    pub retransmit_pct: Option<f64>,
+    pub error_rate: Option<f64>,
+    /// Anomalies in the window by severity, `None` where nothing detects them
+    pub anomalies: Option<BTreeMap<AnomalySeverity, usize>>,
+    /// Age of the newest sample, `None` before the first one
+    pub sample_age: Option<Duration>,
+}
+
+impl HealthInputs {
+    /// Inputs from the newest sample
+    pub fn from_sample(metrics: &QUICMetrics, derived: &DerivedMetrics, sample_age: Option<Duration>) -> Self {
+        Self {
+            latency_ms: Some(metrics.latency),
+            loss_pct: Some(metrics.packet_loss * 100.0),
+            retransmit_pct: derived.efficiency_pct.map(|efficiency| 100.0 - efficiency),
+            error_rate: derived.error_rate,
+            anomalies: None,
+            sample_age,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthLevel {
+    Good,
+    Fair,
+    Poor,
+    /// No sample has arrived yet
+    NoData,
+}
+
+impl HealthLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            HealthLevel::Good => "good",
+            HealthLevel::Fair => "fair",
+            HealthLevel::Poor => "poor",
+            HealthLevel::NoData => "no data",
+        }
+    }
+}
+
+/// One component of the score and what it measured
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthComponent {
+    pub name: &'static str,
+    /// Share in the score, 0 for a component without a value
+    pub weight: f64,
+    /// What was measured, in the unit of the component's target
+    pub value: Option<f64>,
+    /// 0-100
+    pub score: Option<f64>,
+}
+
+impl HealthComponent {
+    fn new(name: &'static str, weight: f64, measured: Option<(f64, f64)>) -> Self {
+        Self {
+            name,
+            weight,
+            value: measured.map(|(value, _)| value),
+            score: measured.map(|(_, score)| score),
+        }
+    }
+}
+
+/// The score with its breakdown, as returned by `GET /api/health-score`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthScore {
+    /// 0-100, `None` before the first sample
+    pub score: Option<f64>,
+    pub level: HealthLevel,
+    pub components: Vec<HealthComponent>,
+}
+
+impl HealthScore {
+    /// `82 good`, or `no data`
+    pub fn label(&self) -> String {
+        match self.score {
+            Some(score) => format!("{:.0} {}", score, self.level.name()),
+            None => self.level.name().to_string(),
+        }
+    }
+
+    /// `latency 91, loss 100, ...` for the components with a value
+    pub fn breakdown(&self) -> String {
+        let parts: Vec<String> = self
+            .components
+            .iter()
+            .filter_map(|c| c.score.map(|score| format!("{} {:.0}", c.name, score)))
+            .collect();
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::loss_fraction;
+    use crate::units::LossUnit;
+
+    fn component<'a>(score: &'a HealthScore, name: &str) -> &'a HealthComponent {
+        score.components.iter().find(|c| c.name == name).unwrap()
+    }
+
+    #[test]
+    fn loss_scores_the_same_from_either_sender_convention() {
+        let config = HealthConfig::default();
+        let age = Some(Duration::from_secs(1));
+        for (sent, unit) in [(0.02, LossUnit::Fraction), (2.0, LossUnit::Percent), (2.0, LossUnit::Auto)] {
+            let metrics = QUICMetrics { latency: 20.0, packet_loss: loss_fraction(sent, Some(unit)), ..QUICMetrics::default() };
+            let inputs = HealthInputs::from_sample(&metrics, &DerivedMetrics::default(), age);
+            assert_eq!(inputs.loss_pct, Some(2.0));
+            let loss = component(&config.score(&inputs), "loss").clone();
+            assert_eq!(loss.value, Some(2.0));
+            // 2% between the 0.5% target and the 5% limit
+            assert!((loss.score.unwrap() - (5.0 - 2.0) / (5.0 - 0.5) * 100.0).abs() < 1e-9, "{:?}", loss);
+        }
+
+        // Half a percent is on target, not fifty percent
+        let metrics = QUICMetrics { packet_loss: 0.005, ..QUICMetrics::default() };
+        let inputs = HealthInputs::from_sample(&metrics, &DerivedMetrics::default(), age);
+        assert_eq!(component(&config.score(&inputs), "loss").score, Some(100.0));
+    }
+
+    #[test]
+    fn missing_components_hand_their_weight_to_the_others() {
+        let config = HealthConfig::default();
+        assert_eq!(config.score(&HealthInputs::default()).level, HealthLevel::NoData);
+
+        let inputs = HealthInputs {
+            latency_ms: Some(500.0),
+            loss_pct: Some(0.0),
+            sample_age: Some(Duration::ZERO),
+            ..HealthInputs::default()
+        };
+        let score = config.score(&inputs);
+        let present = 0.3 + 0.25 + 0.1;
+        assert!((component(&score, "latency").weight - 0.3 / present).abs() < 1e-9);
+        assert_eq!(component(&score, "errors").weight, 0.0);
+        assert!((score.score.unwrap() - 0.35 / present * 100.0).abs() < 1e-9, "{:?}", score);
+        assert_eq!(score.level, HealthLevel::Fair);
+        assert_eq!(score.breakdown(), "latency 0, loss 100, staleness 100");
+    }
+}
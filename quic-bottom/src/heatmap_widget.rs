@@ -1,213 +1,1017 @@
 //! Heatmap widget for performance analysis
 //! 
-//! Shows performance metrics over time with color-coded intensity
+//! Shows performance metrics over time with color-coded intensity. Time runs
+//! along the horizontal axis, newest sample at the right edge, and each metric
+//! is a labelled row. Half-block characters give two rows of cells per line,
+//! so twice as many metrics fit as the widget has lines.
 
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect, Size},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::focus::{border_style, title_style};
+use crate::improved_layout::{MinSize, Presentation};
+use crate::metrics::{calculate_latency_percentiles, finite_values, format_span, format_value};
+use crate::units::{format_rate, Unit};
+use crate::theme::Theme;
+
+/// Samples kept per row, enough for a 5 minute window at 100ms updates
+const MAX_POINTS_PER_ROW: usize = 3000;
+
+/// Columns given to row labels and current values, including the gap before the cells
+const LABEL_WIDTH: u16 = 20;
+
+/// Fewest time columns worth drawing
+const MIN_COLUMNS: u16 = 20;
 
 /// Heatmap data point
 #[derive(Debug, Clone)]
 pub struct HeatmapPoint {
-    pub x: usize,
-    pub y: usize,
+    pub row: usize,
     pub value: f64,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Cell picked in inspect mode
+///
+/// Held as a metric and a moment rather than grid indices, so it stays on the
+/// same samples while new ones scroll in and the rows are re-ranked.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapCursor {
+    pub metric: String,
+    pub at: DateTime<Utc>,
 }
 
 /// Heatmap widget for performance visualization
 pub struct HeatmapWidget {
-    /// Data points for the heatmap
+    /// Data points for the heatmap, oldest first
     pub data: VecDeque<HeatmapPoint>,
     
     /// Maximum number of data points
     pub max_points: usize,
     
-    /// Heatmap dimensions
-    pub width: usize,
+    /// Number of metric rows
     pub height: usize,
+
+    /// Time spanned by the width, ending at the newest sample
+    pub time_window: Duration,
+
+    /// Scale each row's colors to its own range rather than one shared range
+    pub per_row_scale: bool,
+
+    /// Fixed color range of the rows named here, whatever their samples
+    pub bounds: BTreeMap<String, [f64; 2]>,
     
-    /// Value range for color mapping
+    /// Value range over every row, for the minimal presentation
     pub min_value: f64,
     pub max_value: f64,
     
     /// Title
     pub title: String,
+
+    /// Whether the widget has the focus
+    pub is_selected: bool,
+
+    /// Inspected cell, while inspect mode is on
+    pub cursor: Option<HeatmapCursor>,
+
+    /// Time columns of the last frame, which the cursor moves over
+    columns: Cell<usize>,
 }
 
 impl HeatmapWidget {
-    pub fn new(title: String, width: usize, height: usize) -> Self {
+    pub fn new(title: String, height: usize, time_window: Duration) -> Self {
         Self {
-            data: VecDeque::with_capacity(width * height),
-            max_points: width * height,
-            width,
+            data: VecDeque::new(),
+            max_points: height * MAX_POINTS_PER_ROW,
             height,
+            time_window,
+            per_row_scale: true,
+            bounds: BTreeMap::new(),
             min_value: 0.0,
             max_value: 100.0,
             title,
+            is_selected: false,
+            cursor: None,
+            columns: Cell::new(0),
         }
     }
 
-    /// Add a data point to the heatmap
-    pub fn add_data_point(&mut self, x: usize, y: usize, value: f64) {
-        let point = HeatmapPoint {
-            x,
-            y,
-            value,
-            timestamp: chrono::Utc::now(),
-        };
-        
-        self.data.push_back(point);
-        
-        // Keep only recent data
-        while self.data.len() > self.max_points {
-            self.data.pop_front();
+    /// Add a data point to `row` at `timestamp`
+    pub fn add_data_point(&mut self, row: usize, timestamp: DateTime<Utc>, value: f64) {
+        if !value.is_finite() {
+            return;
         }
+
+        self.data.push_back(HeatmapPoint { row, value, timestamp });
+        self.prune();
         
         // Update value range
         self.update_value_range();
     }
 
+    /// Drop points that scrolled out of the window, and the oldest beyond `max_points`
+    fn prune(&mut self) {
+        let Some(newest) = self.newest() else {
+            return;
+        };
+        while self.data.len() > self.max_points
+            || self.data.front().is_some_and(|p| seconds_before(newest, p.timestamp) > self.time_window.as_secs_f64())
+        {
+            self.data.pop_front();
+        }
+    }
+
+    /// Timestamp of the newest point, the right edge of the heatmap
+    fn newest(&self) -> Option<DateTime<Utc>> {
+        self.data.iter().map(|p| p.timestamp).max()
+    }
+
     /// Update the value range for color mapping
     fn update_value_range(&mut self) {
         if self.data.is_empty() {
             return;
         }
         
-        let values: Vec<f64> = self.data.iter().map(|p| p.value).collect();
+        let values = finite_values(self.data.iter().map(|p| &p.value));
+        if values.is_empty() {
+            return;
+        }
         self.min_value = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
         self.max_value = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
     }
 
-    /// Get color for a value based on the range
-    fn get_color_for_value(&self, value: f64) -> Color {
-        if self.max_value == self.min_value {
-            return Color::Gray;
+    /// Column of a sample taken at `at`, with the newest sample's in the rightmost one
+    ///
+    /// Columns are fixed slices of wall-clock time, so a sample stays in the
+    /// same cell as newer ones arrive until it scrolls off the left edge.
+    fn column_of(&self, newest: DateTime<Utc>, at: DateTime<Utc>, columns: usize) -> Option<usize> {
+        let slice = (self.time_window.as_millis() as i64 / columns.max(1) as i64).max(1);
+        let back = newest.timestamp_millis().div_euclid(slice) - at.timestamp_millis().div_euclid(slice);
+        let back = usize::try_from(back.max(0)).ok()?;
+        columns.checked_sub(back + 1)
+    }
+
+    /// The samples of each row in each of `columns` equal slices of the window
+    ///
+    /// Cells without samples are `None`.
+    fn cells(&self, rows: usize, columns: usize) -> Vec<Vec<Option<HeatCell>>> {
+        let mut cells: Vec<Vec<Option<HeatCell>>> = vec![vec![None; columns]; rows];
+        let (Some(newest), true) = (self.newest(), columns > 0) else {
+            return cells;
+        };
+        for point in self.data.iter().filter(|p| p.row < rows) {
+            let Some(column) = self.column_of(newest, point.timestamp, columns) else {
+                continue;
+            };
+            let cell = cells[point.row][column].get_or_insert(HeatCell {
+                mean: 0.0,
+                samples: 0,
+                newest: point.timestamp,
+            });
+            cell.samples += 1;
+            cell.mean += (point.value - cell.mean) / f64::from(cell.samples);
+            cell.newest = cell.newest.max(point.timestamp);
         }
-        
-        let normalized = (value - self.min_value) / (self.max_value - self.min_value);
-        
-        match normalized {
-            x if x < 0.2 => Color::Green,
-            x if x < 0.4 => Color::LightGreen,
-            x if x < 0.6 => Color::Yellow,
-            x if x < 0.8 => Color::LightRed,
-            _ => Color::Red,
+        cells
+    }
+
+    /// Color range of each row: fixed in `bounds`, its own, or the one shared by all rows
+    fn ranges(&self, rows: &[String], cells: &[Vec<Option<HeatCell>>]) -> Vec<(f64, f64)> {
+        let shared = value_range(cells.iter().flatten().flatten().map(|c| c.mean));
+        cells
+            .iter()
+            .enumerate()
+            .map(|(row, values)| match rows.get(row).and_then(|name| self.bounds.get(name)) {
+                Some(&[lo, hi]) => (lo, hi),
+                None if self.per_row_scale => value_range(values.iter().flatten().map(|c| c.mean)),
+                None => shared,
+            })
+            .collect()
+    }
+
+    /// Row and column of the cursor: its metric's row, or the first one if
+    /// that metric is no longer shown, at the populated cell nearest its time
+    fn cursor_cell(&self, rows: &[String], cells: &[Vec<Option<HeatCell>>]) -> Option<(usize, usize)> {
+        let cursor = self.cursor.as_ref()?;
+        let row = rows.iter().position(|name| *name == cursor.metric).unwrap_or(0);
+        // Past the left edge, the oldest cell
+        let target = self.column_of(self.newest()?, cursor.at, cells.get(row)?.len()).unwrap_or(0);
+        nearest_populated(&cells[row], target).map(|column| (row, column))
+    }
+
+    /// Newest value of each row
+    fn latest(&self, rows: usize) -> Vec<Option<f64>> {
+        let mut latest = vec![None; rows];
+        for point in self.data.iter().rev().filter(|p| p.row < rows) {
+            latest[point.row].get_or_insert(point.value);
+            if latest.iter().all(Option::is_some) {
+                break;
+            }
+        }
+        latest
+    }
+
+    /// Cells, color ranges, current values and cursor of `rows` over `columns` time columns
+    fn grid(&self, rows: &[String], columns: usize) -> Grid {
+        let row_count = if rows.is_empty() { self.height } else { rows.len().min(self.height) };
+        let cells = self.cells(row_count, columns);
+        Grid {
+            ranges: self.ranges(rows, &cells),
+            latest: self.latest(row_count),
+            cursor: self.cursor_cell(rows, &cells),
+            cells,
+        }
+    }
+
+    /// Turn inspect mode on at the newest cell of the top row, or off
+    pub fn toggle_inspect(&mut self, rows: &[String]) {
+        if self.cursor.take().is_some() {
+            return;
+        }
+        let newest = self.data.iter().filter(|p| p.row == 0).map(|p| p.timestamp).max();
+        if let (Some(metric), Some(at)) = (rows.first(), newest) {
+            self.cursor = Some(HeatmapCursor { metric: metric.clone(), at });
+        }
+    }
+
+    /// Move the cursor `rows_by` rows down and `columns_by` populated cells right
+    ///
+    /// Moving between rows keeps the time, landing on the nearest populated
+    /// cell of the new row; the cursor stops at the edges.
+    pub fn move_cursor(&mut self, rows: &[String], rows_by: isize, columns_by: isize) {
+        let grid = self.grid(rows, self.columns.get());
+        let Some((row, column)) = grid.cursor else {
+            return;
+        };
+        let row = row.saturating_add_signed(rows_by).min(grid.cells.len() - 1);
+        let cells = &grid.cells[row];
+        let mut column = nearest_populated(cells, column).unwrap_or(column);
+        for _ in 0..columns_by.unsigned_abs() {
+            let next = if columns_by < 0 {
+                (0..column).rev().find(|&c| cells[c].is_some())
+            } else {
+                (column + 1..cells.len()).find(|&c| cells[c].is_some())
+            };
+            match next {
+                Some(next) => column = next,
+                None => break,
+            }
+        }
+        self.place_cursor(rows, &grid, row, column);
+    }
+
+    /// Move the cursor to the previous or next cell of its row above the row's 95th percentile
+    pub fn jump_to_spike(&mut self, rows: &[String], forward: bool) {
+        let grid = self.grid(rows, self.columns.get());
+        let Some((row, column)) = grid.cursor else {
+            return;
+        };
+        let cells = &grid.cells[row];
+        let Some(p95) = row_p95(cells) else {
+            return;
+        };
+        let is_spike = |c: &usize| cells[*c].is_some_and(|cell| cell.mean > p95);
+        let spike = if forward {
+            (column + 1..cells.len()).find(is_spike)
+        } else {
+            (0..column).rev().find(is_spike)
+        };
+        if let Some(spike) = spike {
+            self.place_cursor(rows, &grid, row, spike);
+        }
+    }
+
+    fn place_cursor(&mut self, rows: &[String], grid: &Grid, row: usize, column: usize) {
+        if let (Some(metric), Some(Some(cell))) = (rows.get(row), grid.cells[row].get(column)) {
+            self.cursor = Some(HeatmapCursor { metric: metric.clone(), at: cell.newest });
         }
     }
 
     /// Render the heatmap
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_with_rows(f, area, &[], theme);
+    }
+
+    /// Render the heatmap, labelling each row with its metric name
+    pub fn render_with_rows(&self, f: &mut Frame, area: Rect, rows: &[String], theme: &Theme) {
+        self.render_as(f, area, rows, Presentation::fitting(self, area), theme);
+    }
+
+    /// Render at `presentation`: compact drops the legend, minimal is the value range on one line
+    pub fn render_as(&self, f: &mut Frame, area: Rect, rows: &[String], presentation: Presentation, theme: &Theme) {
+        if presentation == Presentation::Minimal {
+            let text = format!("{}: {:.1} - {:.1}", self.title, self.min_value, self.max_value);
+            f.render_widget(Paragraph::new(text).style(title_style(theme, self.is_selected)), area);
+            return;
+        }
+
+        let legend_rows = if presentation == Presentation::Full { 3 } else { 0 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(0),    // Heatmap
-                Constraint::Length(3), // Legend
+                Constraint::Length(3),           // Title
+                Constraint::Min(0),              // Heatmap
+                Constraint::Length(legend_rows), // Legend
             ])
             .split(area);
 
         // Title
-        self.render_title(f, chunks[0]);
+        self.render_title(f, chunks[0], theme);
         
         // Heatmap
-        self.render_heatmap(f, chunks[1]);
+        let inner = Block::default().borders(Borders::ALL).inner(chunks[1]);
+        let columns = usize::from(inner.width.saturating_sub(label_width(rows, inner.width)));
+        self.columns.set(columns);
+        let grid = self.grid(rows, columns);
+        self.render_heatmap(f, chunks[1], rows, &grid, theme);
         
         // Legend
-        self.render_legend(f, chunks[2]);
+        if legend_rows > 0 {
+            self.render_legend(f, chunks[2], rows, &grid.ranges, theme);
+        }
     }
 
-    fn render_title(&self, f: &mut Frame, area: Rect) {
+    fn render_title(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let title = Paragraph::new(self.title.clone())
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
-            .block(Block::default().borders(Borders::ALL));
+            .style(title_style(theme, self.is_selected))
+            .block(Block::default().borders(Borders::ALL).border_style(border_style(theme, self.is_selected)));
         f.render_widget(title, area);
     }
 
-    fn render_heatmap(&self, f: &mut Frame, area: Rect) {
+    fn render_heatmap(&self, f: &mut Frame, area: Rect, rows: &[String], grid: &Grid, theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL);
         if self.data.is_empty() {
             let empty_text = "No data available yet...";
             let empty_paragraph = Paragraph::new(empty_text)
-                .style(Style::default().fg(Color::Gray))
-                .block(Block::default().borders(Borders::ALL));
+                .style(Style::default().fg(theme.muted))
+                .block(block);
             f.render_widget(empty_paragraph, area);
             return;
         }
 
-        // Create a 2D grid for the heatmap
-        let mut grid = vec![vec![0.0; self.width]; self.height];
-        
-        // Fill the grid with data
-        for point in &self.data {
-            if point.x < self.width && point.y < self.height {
-                grid[point.y][point.x] = point.value;
+        // Every line holds two cell rows: the upper half and the lower half.
+        // Below them are the time axis and, when inspecting, the readout.
+        let inner = block.inner(area);
+        let footer_lines = if grid.cursor.is_some() { 2 } else { 1 };
+        let lines_for_cells = usize::from(inner.height).saturating_sub(footer_lines);
+        let band = (lines_for_cells * 2 / grid.cells.len().max(1)).max(1);
+        let row_at = |half: usize| Some(half / band).filter(|&row| row < grid.cells.len());
+        let color_at = |half: usize, column: usize| {
+            let row = row_at(half)?;
+            if grid.cursor == Some((row, column)) {
+                return Some(theme.text);
             }
-        }
+            let cell = grid.cells[row][column]?;
+            let (lo, hi) = grid.ranges[row];
+            Some(theme.heat_color(cell.mean, lo, hi))
+        };
 
-        // Render the heatmap
-        let mut lines = Vec::new();
-        for y in 0..self.height {
-            let mut line_spans = Vec::new();
-            for x in 0..self.width {
-                let value = grid[y][x];
-                let color = self.get_color_for_value(value);
-                let char = if value > 0.0 { "█" } else { " " };
-                line_spans.push(Span::styled(char, Style::default().fg(color)));
+        let label_style = Style::default().fg(theme.muted);
+        let width = usize::from(label_width(rows, inner.width));
+        let mut lines = Vec::with_capacity(lines_for_cells + 1);
+        for line in 0..lines_for_cells {
+            let (top, bottom) = (line * 2, line * 2 + 1);
+            if row_at(top).is_none() {
+                break;
             }
-            lines.push(Line::from(line_spans));
+
+            // Name every row whose band starts on this line with its current
+            // value, sharing the room
+            let starts: Vec<usize> = [top, bottom]
+                .into_iter()
+                .filter(|&half| half % band == 0)
+                .filter_map(row_at)
+                .filter(|&row| row < rows.len())
+                .collect();
+            let room = width.saturating_sub(starts.len()) / starts.len().max(1);
+            let label = starts
+                .iter()
+                .map(|&row| row_label(&rows[row], grid.latest[row], room))
+                .collect::<Vec<_>>()
+                .join("/");
+            let inspected = grid.cursor.is_some_and(|(row, _)| starts.contains(&row));
+            let style = if inspected { theme.title(theme.accent) } else { label_style };
+            let mut spans = vec![Span::styled(format!("{:<width$}", label), style)];
+
+            for column in 0..grid.columns() {
+                let span = match (color_at(top, column), color_at(bottom, column)) {
+                    (Some(upper), Some(lower)) => Span::styled("▀", Style::default().fg(upper).bg(lower)),
+                    (Some(upper), None) => Span::styled("▀", Style::default().fg(upper)),
+                    (None, Some(lower)) => Span::styled("▄", Style::default().fg(lower)),
+                    (None, None) => Span::raw(" "),
+                };
+                spans.push(span);
+            }
+            lines.push(Line::from(spans));
+        }
+
+        // Time axis under the cells
+        let columns = grid.columns();
+        let oldest = format!("-{}", format_span(self.time_window));
+        let axis = format!("{:<width$}{:<pad$}now", "", oldest, pad = columns.saturating_sub(3));
+        lines.push(Line::styled(axis, label_style));
+        if let Some(readout) = self.readout(rows, grid) {
+            lines.push(Line::styled(readout, Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)));
         }
 
-        let heatmap_paragraph = Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL));
+        let heatmap_paragraph = Paragraph::new(lines).block(block);
         f.render_widget(heatmap_paragraph, area);
     }
 
-    fn render_legend(&self, f: &mut Frame, area: Rect) {
-        let legend_text = format!(
-            "Range: {:.1} - {:.1} | Green: Low | Yellow: Medium | Red: High",
-            self.min_value, self.max_value
-        );
-        
-        let legend = Paragraph::new(legend_text)
-            .style(Style::default().fg(Color::Cyan))
+    /// `Latency at 14:02:11.250: 15.23 (mean of 3) | row p95 18.40 | ...` for the inspected cell
+    fn readout(&self, rows: &[String], grid: &Grid) -> Option<String> {
+        let (row, column) = grid.cursor?;
+        let cell = grid.cells[row][column]?;
+        let samples = if cell.samples == 1 { "1 sample".to_string() } else { format!("mean of {}", cell.samples) };
+        Some(format!(
+            "{} at {}: {} ({}) | row p95 {} | [ ] spikes, Esc leaves",
+            rows.get(row).map_or("?", String::as_str),
+            cell.newest.with_timezone(&chrono::Local).format("%H:%M:%S%.3f"),
+            metric_text(&rows[row], cell.mean, 2),
+            samples,
+            row_p95(&grid.cells[row]).map_or("–".to_string(), |p| metric_text(&rows[row], p, 2)),
+        ))
+    }
+
+    fn render_legend(&self, f: &mut Frame, area: Rect, rows: &[String], ranges: &[(f64, f64)], theme: &Theme) {
+        // The theme's heat scale as swatches, low to high
+        let label = if self.per_row_scale { "per row".to_string() } else { range_text("", ranges.first()) };
+        let mut scale = vec![Span::raw(format!("Scale: {} | Low ", label))];
+        scale.extend(theme.heat.iter().map(|&color| Span::styled("█", Style::default().fg(color))));
+        scale.push(Span::raw(" High"));
+        let mut lines = vec![Line::from(scale)];
+        if self.per_row_scale && !rows.is_empty() {
+            let row_ranges: Vec<String> = rows
+                .iter()
+                .zip(ranges)
+                .map(|(name, range)| {
+                    let fixed = if self.bounds.contains_key(name) { " (fixed)" } else { "" };
+                    format!("{} {}{}", name, range_text(name, Some(range)), fixed)
+                })
+                .collect();
+            lines.push(Line::from(row_ranges.join(" | ")));
+        }
+
+        let legend = Paragraph::new(lines)
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         
         f.render_widget(legend, area);
     }
 }
 
+/// The samples of one row that fall in one time column
+#[derive(Debug, Clone, Copy)]
+struct HeatCell {
+    mean: f64,
+    samples: u32,
+    newest: DateTime<Utc>,
+}
+
+/// What the heatmap draws from its samples for one frame
+struct Grid {
+    /// Samples per row and time column, `None` where there are none
+    cells: Vec<Vec<Option<HeatCell>>>,
+    /// Color range per row
+    ranges: Vec<(f64, f64)>,
+    /// Newest value per row
+    latest: Vec<Option<f64>>,
+    /// Row and column under the inspect cursor
+    cursor: Option<(usize, usize)>,
+}
+
+impl Grid {
+    fn columns(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+}
+
+/// Columns left of the cells for row labels; none without row names
+fn label_width(rows: &[String], inner_width: u16) -> u16 {
+    if rows.is_empty() {
+        0
+    } else {
+        LABEL_WIDTH.min(inner_width / 3)
+    }
+}
+
+/// `Latency 12.3` in at most `room` columns, shortening the name first
+fn row_label(name: &str, value: Option<f64>, room: usize) -> String {
+    let value = value.map_or("–".to_string(), |v| {
+        let shown = if name == "Throughput" { Unit::auto(v).from_bps(v) } else { v };
+        metric_text(name, v, if shown.abs() >= 100.0 { 0 } else { 1 })
+    });
+    let name_room = room.saturating_sub(value.chars().count() + 1).max(1);
+    let name: String = name.chars().take(name_room).collect();
+    let label = format!("{:<width$} {}", name, value, width = name_room);
+    label.chars().take(room).collect()
+}
+
+/// The populated column of `row` closest to `target`, the later one on a tie
+fn nearest_populated(row: &[Option<HeatCell>], target: usize) -> Option<usize> {
+    (0..row.len())
+        .filter(|&c| row[c].is_some())
+        .min_by_key(|&c| (c.abs_diff(target), std::cmp::Reverse(c)))
+}
+
+/// 95th percentile of the cell values of one row
+fn row_p95(row: &[Option<HeatCell>]) -> Option<f64> {
+    let values: Vec<f64> = row.iter().flatten().map(|c| c.mean).collect();
+    (!values.is_empty()).then(|| calculate_latency_percentiles(&values).1)
+}
+
+/// Smallest and largest of `values`; empty gives an inverted range
+fn value_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+/// Seconds from `earlier` to `later`
+fn seconds_before(later: DateTime<Utc>, earlier: DateTime<Utc>) -> f64 {
+    (later - earlier).num_milliseconds() as f64 / 1000.0
+}
+
+/// `value` of the metric `name`; throughput, in bits per second, with its unit
+fn metric_text(name: &str, value: f64, precision: usize) -> String {
+    if name == "Throughput" {
+        format_rate(value, precision)
+    } else {
+        format_value(value, precision)
+    }
+}
+
+/// `12.3-45.6`, `89.0-109.0 Mbps` for throughput, or `–` for a row without samples
+fn range_text(name: &str, range: Option<&(f64, f64)>) -> String {
+    match range {
+        Some(&(lo, hi)) if lo <= hi && name == "Throughput" => {
+            let unit = Unit::auto(lo.abs().max(hi.abs()));
+            format!("{}-{} {}", format_value(unit.from_bps(lo), 1), format_value(unit.from_bps(hi), 1), unit)
+        }
+        Some(&(lo, hi)) if lo <= hi => format!("{}-{}", format_value(lo, 1), format_value(hi, 1)),
+        _ => "–".to_string(),
+    }
+}
+
+impl MinSize for HeatmapWidget {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        // Labels and the time columns inside borders; two rows per line plus
+        // the time axis, under the title
+        let lines = u16::try_from(self.height.div_ceil(2)).unwrap_or(u16::MAX).saturating_add(1);
+        let grid = Size::new(LABEL_WIDTH + MIN_COLUMNS + 2, lines.saturating_add(2 + 3));
+        match presentation {
+            Presentation::Full => Size::new(grid.width, grid.height.saturating_add(3)),
+            Presentation::Compact => grid,
+            Presentation::Minimal => Size::new(0, 1),
+        }
+    }
+}
+
+/// Heatmap row selection and scale settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatmapConfig {
+    /// Number of metric rows shown in the heatmap
+    pub rows: usize,
+
+    /// Metrics that are always shown, regardless of their variance
+    pub pinned: Vec<String>,
+
+    /// Number of recent samples used to rank metrics
+    pub ranking_window: usize,
+
+    /// Re-rank rows every N samples
+    pub ranking_interval: usize,
+
+    /// Seconds of history across the width of the heatmap
+    pub window_secs: u64,
+
+    /// Scale each row's colors to its own range; otherwise all rows share
+    /// one, and metrics with large values wash out the others
+    pub per_row_scale: bool,
+
+    /// Fixed `[low, high]` color range per metric, e.g. `"Packet Loss" = [0.0, 5.0]`
+    pub bounds: BTreeMap<String, [f64; 2]>,
+}
+
+impl HeatmapConfig {
+    /// Check that every fixed range is finite and not empty
+    pub fn validate(&self) -> Result<()> {
+        for (metric, &[low, high]) in &self.bounds {
+            if !low.is_finite() || !high.is_finite() || low >= high {
+                return Err(anyhow!(
+                    "heatmap.bounds.\"{}\": expected [low, high] with low < high, got [{}, {}]",
+                    metric,
+                    low,
+                    high
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            rows: 6,
+            pinned: vec!["Latency".to_string(), "Throughput".to_string()],
+            ranking_window: 50,
+            ranking_interval: 20,
+            window_secs: 60,
+            per_row_scale: true,
+            bounds: BTreeMap::new(),
+        }
+    }
+}
+
 /// Performance Heatmap for QUIC metrics
+///
+/// Every metric fed through `add_metric` is tracked, but only the pinned
+/// metrics plus the most variable remaining ones are shown as rows.
 pub struct QUICPerformanceHeatmap {
     heatmap: HeatmapWidget,
-    time_slots: usize,
-    metric_slots: usize,
+    config: HeatmapConfig,
+    series: HashMap<String, VecDeque<(DateTime<Utc>, f64)>>,
+    rows: Vec<String>,
+    /// Timestamp of the sample being added, to count samples for ranking
+    current_sample: Option<DateTime<Utc>>,
+    samples_since_ranking: usize,
 }
 
 impl QUICPerformanceHeatmap {
     pub fn new() -> Self {
+        Self::with_config(HeatmapConfig::default())
+    }
+
+    pub fn with_config(config: HeatmapConfig) -> Self {
+        let metric_slots = config.rows.max(config.pinned.len()).max(1);
+        let mut heatmap = HeatmapWidget::new(
+            "QUIC Performance Heatmap".to_string(),
+            metric_slots,
+            Duration::from_secs(config.window_secs.max(1)),
+        );
+        heatmap.per_row_scale = config.per_row_scale;
+        heatmap.bounds = config.bounds.clone();
         Self {
-            heatmap: HeatmapWidget::new(
-                "QUIC Performance Heatmap".to_string(),
-                20, // 20 time slots
-                10, // 10 metric slots (latency, throughput, etc.)
-            ),
-            time_slots: 20,
-            metric_slots: 10,
+            heatmap,
+            rows: config.pinned.clone(),
+            config,
+            series: HashMap::new(),
+            current_sample: None,
+            samples_since_ranking: 0,
+        }
+    }
+
+    /// Add the value of `metric` in the sample taken at `at`
+    ///
+    /// Metrics of one sample share its timestamp; rows are re-ranked every
+    /// `ranking_interval` samples.
+    pub fn add_metric(&mut self, at: DateTime<Utc>, metric: &str, value: f64) {
+        if self.current_sample != Some(at) {
+            self.current_sample = Some(at);
+            self.samples_since_ranking += 1;
+            if self.samples_since_ranking >= self.config.ranking_interval {
+                self.samples_since_ranking = 0;
+                self.update_rows();
+            }
+        }
+
+        if finite_values(&[value]).is_empty() {
+            return;
         }
+
+        // Enough history to rank on and to refill the window after a row change
+        let window = self.heatmap.time_window.as_secs_f64();
+        let history = self.series.entry(metric.to_string()).or_default();
+        history.push_back((at, value));
+        while history.len() > MAX_POINTS_PER_ROW
+            || (history.len() > self.config.ranking_window
+                && history.front().is_some_and(|&(t, _)| seconds_before(at, t) > window))
+        {
+            history.pop_front();
+        }
+
+        // New metrics fill free rows immediately; ranking only runs at low cadence
+        if !self.rows.iter().any(|r| r == metric) && self.rows.len() < self.heatmap.height {
+            self.rows.push(metric.to_string());
+        }
+
+        if let Some(row) = self.rows.iter().position(|r| r == metric) {
+            self.heatmap.add_data_point(row, at, value);
+        }
+    }
+
+    /// Currently displayed rows, top to bottom
+    pub fn rows(&self) -> &[String] {
+        &self.rows
+    }
+
+    /// Re-rank all tracked metrics and relabel the heatmap rows
+    pub fn update_rows(&mut self) {
+        let ranked = rank_by_variance(&self.series, self.config.ranking_window);
+        let mut rows: Vec<String> = self
+            .config
+            .pinned
+            .iter()
+            .filter(|p| self.series.contains_key(*p))
+            .cloned()
+            .collect();
+        for (metric, _) in ranked {
+            if rows.len() >= self.heatmap.height {
+                break;
+            }
+            if !rows.contains(&metric) {
+                rows.push(metric);
+            }
+        }
+
+        if rows != self.rows {
+            self.rows = rows;
+            self.rebuild_grid();
+        }
+    }
+
+    /// Re-populate the grid from the per-metric history after a row change
+    fn rebuild_grid(&mut self) {
+        let mut points: Vec<HeatmapPoint> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter_map(|(row, metric)| Some((row, self.series.get(metric)?)))
+            .flat_map(|(row, history)| {
+                history.iter().map(move |&(timestamp, value)| HeatmapPoint { row, value, timestamp })
+            })
+            .collect();
+        points.sort_by_key(|p| p.timestamp);
+        self.heatmap.data = points.into();
+        self.heatmap.prune();
+        self.heatmap.update_value_range();
+    }
+
+    /// Forget every sample, keeping the settings
+    pub fn clear(&mut self) {
+        self.series.clear();
+        self.heatmap.data.clear();
+        self.heatmap.cursor = None;
+        self.rows = self.config.pinned.clone();
+        self.current_sample = None;
+        self.samples_since_ranking = 0;
+    }
+
+    /// Highlight the widget as focused
+    pub fn set_selected(&mut self, selected: bool) {
+        self.heatmap.is_selected = selected;
+    }
+
+    /// Whether inspect mode is on
+    pub fn is_inspecting(&self) -> bool {
+        self.heatmap.cursor.is_some()
+    }
+
+    /// Turn inspect mode on at the newest cell of the top row, or off
+    pub fn toggle_inspect(&mut self) {
+        self.heatmap.toggle_inspect(&self.rows);
+    }
+
+    /// The inspected cell, `None` outside inspect mode
+    pub fn cursor(&self) -> Option<HeatmapCursor> {
+        self.heatmap.cursor.clone()
+    }
+
+    /// Put back a cursor taken with `cursor`, turning inspect mode on or off with it
+    pub fn set_cursor(&mut self, cursor: Option<HeatmapCursor>) {
+        self.heatmap.cursor = cursor;
     }
 
-    /// Add performance data
-    pub fn add_performance_data(&mut self, time_slot: usize, metric_slot: usize, value: f64) {
-        self.heatmap.add_data_point(time_slot, metric_slot, value);
+    /// Move the inspect cursor by rows and populated cells
+    pub fn move_cursor(&mut self, rows_by: isize, columns_by: isize) {
+        self.heatmap.move_cursor(&self.rows, rows_by, columns_by);
+    }
+
+    /// Move the inspect cursor to the previous or next cell above its row's 95th percentile
+    pub fn jump_to_spike(&mut self, forward: bool) {
+        self.heatmap.jump_to_spike(&self.rows, forward);
     }
 
     /// Render the performance heatmap
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.heatmap.render(f, area);
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.heatmap.render_with_rows(f, area, &self.rows, theme);
+    }
+
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        self.heatmap.render_as(f, area, &self.rows, presentation, theme);
+    }
+}
+
+impl MinSize for QUICPerformanceHeatmap {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        self.heatmap.min_size(presentation)
+    }
+}
+
+impl Default for QUICPerformanceHeatmap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rank metrics by the coefficient of variation over their recent samples,
+/// most variable first. Flat series score zero.
+pub fn rank_by_variance(series: &HashMap<String, VecDeque<(DateTime<Utc>, f64)>>, window: usize) -> Vec<(String, f64)> {
+    let mut scores: Vec<(String, f64)> = series
+        .iter()
+        .map(|(name, history)| {
+            let recent: Vec<f64> = history.iter().rev().take(window).map(|&(_, v)| v).collect();
+            (name.clone(), normalized_variance(&recent))
+        })
+        .collect();
+
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    scores
+}
+
+/// Standard deviation relative to the mean magnitude
+fn normalized_variance(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let std_dev = variance.sqrt();
+
+    if !std_dev.is_finite() {
+        0.0
+    } else if mean.abs() > f64::EPSILON {
+        std_dev / mean.abs()
+    } else {
+        std_dev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    /// Feed one-second samples of each metric, `value(metric, i)` giving the values
+    fn feed(heatmap: &mut QUICPerformanceHeatmap, metrics: &[&str], samples: Range<i64>, value: impl Fn(&str, i64) -> f64) {
+        for i in samples {
+            for &metric in metrics {
+                heatmap.add_metric(at(i), metric, value(metric, i));
+            }
+        }
+    }
+
+    fn series(values: &[f64]) -> VecDeque<(DateTime<Utc>, f64)> {
+        values.iter().enumerate().map(|(i, &v)| (at(i as i64), v)).collect()
+    }
+
+    #[test]
+    fn active_series_outrank_flat_ones() {
+        let mut all = HashMap::new();
+        all.insert("Connections".to_string(), series(&[4.0; 20]));
+        all.insert("Errors".to_string(), series(&[0.0; 20]));
+        all.insert("Jitter".to_string(), series(&[1.0, 9.0, 2.0, 8.0, 1.0, 9.0]));
+        all.insert("RTT".to_string(), series(&[40.0, 42.0, 41.0, 43.0]));
+
+        let ranked = rank_by_variance(&all, 50);
+        let names: Vec<&str> = ranked.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Jitter", "RTT", "Connections", "Errors"]);
+        assert_eq!(ranked[2].1, 0.0);
+        assert_eq!(ranked[3].1, 0.0);
+
+        // Only the window counts: a series that settled down ranks as flat
+        all.insert("Jitter".to_string(), series(&[1.0, 9.0, 2.0, 5.0, 5.0, 5.0]));
+        assert_eq!(rank_by_variance(&all, 3)[0].0, "RTT");
+
+        // Scale does not matter, spread relative to the mean does
+        assert_eq!(normalized_variance(&[10.0, 20.0]), normalized_variance(&[1000.0, 2000.0]));
+        assert_eq!(normalized_variance(&[5.0]), 0.0);
+        assert_eq!(normalized_variance(&[]), 0.0);
+    }
+
+    #[test]
+    fn pinned_rows_stay_and_the_rest_go_to_the_most_variable() {
+        let config = HeatmapConfig { rows: 3, ranking_interval: 10, ..HeatmapConfig::default() };
+        let mut heatmap = QUICPerformanceHeatmap::with_config(config);
+        let metrics = ["Connections", "Errors", "Latency", "Throughput", "Jitter"];
+        let value = |metric: &str, i: i64| if metric == "Jitter" { (i % 2 * 8 + 1) as f64 } else { 5.0 };
+        feed(&mut heatmap, &metrics, 0..9, value);
+
+        // Before the first ranking, pinned rows come first and the first new metric fills the free row
+        assert_eq!(heatmap.rows(), ["Latency", "Throughput", "Connections"]);
+
+        // Ranking at the tenth sample swaps the flat row for the active one
+        feed(&mut heatmap, &metrics, 9..10, value);
+        assert_eq!(heatmap.rows(), ["Latency", "Throughput", "Jitter"]);
+
+        // The relabelled row is refilled from history
+        let jitter_points = heatmap.heatmap.data.iter().filter(|p| p.row == 2).count();
+        assert!(jitter_points >= 9, "only {} Jitter points after the row change", jitter_points);
+        assert!(heatmap.heatmap.data.iter().filter(|p| p.row == 2).all(|p| p.value == 1.0 || p.value == 9.0));
+    }
+
+    #[test]
+    fn pinned_metrics_without_samples_give_their_row_away() {
+        let config = HeatmapConfig { rows: 2, pinned: vec!["Latency".to_string(), "Loss".to_string()], ..HeatmapConfig::default() };
+        let mut heatmap = QUICPerformanceHeatmap::with_config(config);
+        feed(&mut heatmap, &["Latency", "Jitter"], 0..5, |_, i| i as f64);
+        heatmap.update_rows();
+        assert_eq!(heatmap.rows(), ["Latency", "Jitter"]);
+
+        // Once the pinned metric arrives it takes its row back
+        heatmap.add_metric(at(5), "Loss", 0.0);
+        heatmap.update_rows();
+        assert_eq!(heatmap.rows(), ["Latency", "Loss"]);
+
+        heatmap.clear();
+        assert_eq!(heatmap.rows(), ["Latency", "Loss"]);
+        assert!(heatmap.heatmap.data.is_empty());
+    }
+
+    /// Packet loss of 0-2% next to throughput around a gigabit, one sample a second
+    fn loss_and_throughput(widget: &mut HeatmapWidget) -> Vec<String> {
+        for i in 0..10 {
+            widget.add_data_point(0, at(i), (i % 3) as f64);
+            widget.add_data_point(1, at(i), 1e9 + i as f64 * 1e7);
+        }
+        vec!["Packet Loss".to_string(), "Throughput".to_string()]
+    }
+
+    #[test]
+    fn each_row_is_scaled_to_its_own_range() {
+        let mut widget = HeatmapWidget::new("test".to_string(), 2, Duration::from_secs(10));
+        let rows = loss_and_throughput(&mut widget);
+        let grid = widget.grid(&rows, 10);
+        assert_eq!(grid.ranges, [(0.0, 2.0), (1e9, 1.09e9)]);
+
+        // The worst loss is as hot as the best throughput, not washed out by it
+        let theme = Theme::default();
+        let hottest = *theme.heat.last().unwrap();
+        assert_eq!(theme.heat_color(2.0, 0.0, 2.0), hottest);
+        assert_eq!(theme.heat_color(1.09e9, 1e9, 1.09e9), hottest);
+        assert_eq!(theme.heat_color(0.0, 0.0, 2.0), theme.heat[0]);
+        assert_eq!(theme.heat_color(1.0, 0.0, 2.0), theme.heat[theme.heat.len() / 2]);
+        // A flat row has no range to map onto
+        assert_eq!(theme.heat_color(5.0, 5.0, 5.0), theme.muted);
+
+        // One shared scale squeezes the loss row into the coldest color
+        widget.per_row_scale = false;
+        let grid = widget.grid(&rows, 10);
+        assert_eq!(grid.ranges, [(0.0, 1.09e9), (0.0, 1.09e9)]);
+        assert_eq!(theme.heat_color(2.0, 0.0, 1.09e9), theme.heat[0]);
+    }
+
+    #[test]
+    fn fixed_bounds_override_the_samples() {
+        let mut widget = HeatmapWidget::new("test".to_string(), 2, Duration::from_secs(10));
+        let rows = loss_and_throughput(&mut widget);
+        widget.bounds.insert("Packet Loss".to_string(), [0.0, 5.0]);
+        for per_row_scale in [true, false] {
+            widget.per_row_scale = per_row_scale;
+            assert_eq!(widget.grid(&rows, 10).ranges[0], (0.0, 5.0));
+        }
+
+        let mut config = HeatmapConfig::default();
+        config.bounds.insert("Packet Loss".to_string(), [0.0, 5.0]);
+        assert!(config.validate().is_ok());
+        for bad in [[5.0, 5.0], [5.0, 0.0], [f64::NAN, 1.0], [0.0, f64::INFINITY]] {
+            config.bounds.insert("Packet Loss".to_string(), bad);
+            assert!(config.validate().is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn cells_average_their_samples_and_rows_show_the_latest() {
+        let mut widget = HeatmapWidget::new("test".to_string(), 2, Duration::from_secs(10));
+        let rows = loss_and_throughput(&mut widget);
+        // Five columns of two seconds each
+        let grid = widget.grid(&rows, 5);
+        let means: Vec<f64> = grid.cells[0].iter().map(|cell| cell.unwrap().mean).collect();
+        assert_eq!(means, [0.5, 1.0, 1.5, 0.5, 1.0]);
+        assert_eq!(grid.latest, [Some(0.0), Some(1.09e9)]);
+
+        assert_eq!(row_label("Packet Loss", Some(0.0), 20), "Packet Loss      0.0");
+        assert_eq!(row_label("Throughput", Some(1.09e9), 20), "Throughput  1.1 Gbps");
+        assert_eq!(row_label("Packet Loss", None, 20), "Packet Loss        –");
+        assert_eq!(range_text("Throughput", Some(&(1e9, 1.09e9))), "1.0-1.1 Gbps");
+        assert_eq!(range_text("Packet Loss", Some(&(0.0, 2.0))), "0.0-2.0");
+        assert_eq!(range_text("Packet Loss", Some(&value_range(std::iter::empty()))), "–");
     }
 }
@@ -1,17 +1,373 @@
 //! Improved layout for QUIC Bottom with better spacing
 //! 
-//! This module provides better spacing between widgets to prevent them from "sticking together"
+//! This module provides better spacing between widgets to prevent them from "sticking together",
+//! the `[layout]` grid the dashboards are drawn from, which leaves out the widgets `[widgets]`
+//! turns off, and the responsive layout that drops detail from the dashboard widgets when the
+//! terminal is too small for all of it.
 
+use anyhow::{anyhow, bail, Result};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Rect, Size},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Paragraph, Sparkline},
+    widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
 
-/// Improved layout with better spacing
-pub fn create_improved_layout(area: Rect) -> Vec<Rect> {
+use crate::theme::Theme;
+use crate::widgets::ScaledSeries;
+
+/// Rows of the streams widget when it shares a column
+const STREAMS_HEIGHT: u16 = 8;
+
+/// Dashboard widgets, by the names `[layout]` uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WidgetKind {
+    Latency,
+    Throughput,
+    Connections,
+    Network,
+    Streams,
+    Heatmap,
+    Correlation,
+    Anomaly,
+    Bbrv3,
+    CurrentMetrics,
+    Cwnd,
+    LatencyHistogram,
+}
+
+impl WidgetKind {
+    /// Every widget, in the order error messages list them
+    pub const ALL: [WidgetKind; 12] = [
+        WidgetKind::Latency,
+        WidgetKind::Throughput,
+        WidgetKind::Connections,
+        WidgetKind::Network,
+        WidgetKind::Streams,
+        WidgetKind::Heatmap,
+        WidgetKind::Correlation,
+        WidgetKind::Anomaly,
+        WidgetKind::Bbrv3,
+        WidgetKind::CurrentMetrics,
+        WidgetKind::Cwnd,
+        WidgetKind::LatencyHistogram,
+    ];
+
+    /// Name of the widget in `[layout]`
+    pub fn name(self) -> &'static str {
+        match self {
+            WidgetKind::Latency => "latency",
+            WidgetKind::Throughput => "throughput",
+            WidgetKind::Connections => "connections",
+            WidgetKind::Network => "network",
+            WidgetKind::Streams => "streams",
+            WidgetKind::Heatmap => "heatmap",
+            WidgetKind::Correlation => "correlation",
+            WidgetKind::Anomaly => "anomaly",
+            WidgetKind::Bbrv3 => "bbrv3",
+            WidgetKind::CurrentMetrics => "current_metrics",
+            WidgetKind::Cwnd => "cwnd",
+            WidgetKind::LatencyHistogram => "latency_histogram",
+        }
+    }
+
+    /// Rows the widget keeps when it shares a column; `None` to share the column's height
+    fn height(self) -> Option<u16> {
+        match self {
+            WidgetKind::Streams => Some(STREAMS_HEIGHT),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WidgetKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WidgetKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|kind| kind.name()).collect();
+            anyhow!("unknown widget \"{}\", expected one of {}", s, names.join(", "))
+        })
+    }
+}
+
+/// `[layout]`: the dashboard as rows of columns of widgets
+///
+/// ```toml
+/// [[layout.row]]
+/// ratio = 60
+/// column = [
+///     { ratio = 50, widgets = ["current_metrics", "latency"] },
+///     { ratio = 50, widgets = ["heatmap", "anomaly"] },
+/// ]
+/// ```
+///
+/// Without rows each mode keeps its built-in dashboard.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Rows top to bottom
+    #[serde(rename = "row", skip_serializing_if = "Vec::is_empty")]
+    pub rows: Vec<LayoutRowConfig>,
+}
+
+/// A row of `[layout]`, split into columns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutRowConfig {
+    /// Percentage of the dashboard's height
+    pub ratio: u16,
+    /// Columns left to right
+    #[serde(rename = "column")]
+    pub columns: Vec<LayoutColumnConfig>,
+}
+
+/// A column of a `[layout]` row, its widgets stacked top to bottom
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutColumnConfig {
+    /// Percentage of the row's width
+    pub ratio: u16,
+    /// Widget names, e.g. `latency` or `current_metrics`
+    pub widgets: Vec<String>,
+}
+
+impl Default for LayoutRowConfig {
+    fn default() -> Self {
+        Self {
+            ratio: 100,
+            columns: Vec::new(),
+        }
+    }
+}
+
+impl Default for LayoutColumnConfig {
+    fn default() -> Self {
+        Self {
+            ratio: 100,
+            widgets: Vec::new(),
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// Check the widget names, and that the rows and each row's columns add up to 100%
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let mut seen = Vec::new();
+        if !self.rows.is_empty() {
+            check_ratios("rows", self.rows.iter().map(|row| row.ratio), &mut problems);
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            let row_name = format!("row {}", i + 1);
+            if row.columns.is_empty() {
+                problems.push(format!("{}: has no columns", row_name));
+            } else {
+                let name = format!("{} columns", row_name);
+                check_ratios(&name, row.columns.iter().map(|column| column.ratio), &mut problems);
+            }
+            for (j, column) in row.columns.iter().enumerate() {
+                let column_name = format!("{} column {}", row_name, j + 1);
+                if column.widgets.is_empty() {
+                    problems.push(format!("{}: has no widgets", column_name));
+                }
+                for name in &column.widgets {
+                    match name.parse::<WidgetKind>() {
+                        Ok(kind) if seen.contains(&kind) => {
+                            problems.push(format!("{}: {} is already placed", column_name, kind));
+                        }
+                        Ok(kind) => seen.push(kind),
+                        Err(e) => problems.push(format!("{}: {}", column_name, e)),
+                    }
+                }
+            }
+        }
+        match problems.len() {
+            0 => Ok(()),
+            _ => bail!("{}", problems.join("; ")),
+        }
+    }
+
+    /// The configured grid, or `None` to use the mode's built-in one
+    ///
+    /// Names `validate` rejects are left out.
+    pub fn tree(&self) -> Option<LayoutTree> {
+        if self.rows.is_empty() {
+            return None;
+        }
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| LayoutRow {
+                ratio: row.ratio,
+                columns: row
+                    .columns
+                    .iter()
+                    .map(|column| LayoutColumn {
+                        ratio: column.ratio,
+                        widgets: column.widgets.iter().filter_map(|name| name.parse().ok()).collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Some(LayoutTree { rows })
+    }
+}
+
+/// Record a problem unless every ratio is positive and they add up to 100
+fn check_ratios(name: &str, ratios: impl Iterator<Item = u16>, problems: &mut Vec<String>) {
+    let ratios: Vec<u16> = ratios.collect();
+    if ratios.contains(&0) {
+        problems.push(format!("{}: ratios must be above 0, got {:?}", name, ratios));
+    }
+    let total: u32 = ratios.iter().map(|&ratio| u32::from(ratio)).sum();
+    if total != 100 {
+        problems.push(format!("{}: ratios must add up to 100, got {:?} = {}", name, ratios, total));
+    }
+}
+
+/// A dashboard grid: rows of columns, each column stacking its widgets
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutTree {
+    pub rows: Vec<LayoutRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutRow {
+    /// Share of the height, in percent
+    pub ratio: u16,
+    pub columns: Vec<LayoutColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutColumn {
+    /// Share of the row's width, in percent
+    pub ratio: u16,
+    pub widgets: Vec<WidgetKind>,
+}
+
+impl LayoutTree {
+    /// One row of equally wide columns
+    pub fn columns(columns: &[&[WidgetKind]]) -> Self {
+        let count = columns.len().max(1) as u16;
+        let columns = columns
+            .iter()
+            .zip(0..)
+            .map(|(widgets, i)| LayoutColumn {
+                // The first columns take the remainder, so the ratios add up to 100
+                ratio: 100 / count + u16::from(i < 100 % count),
+                widgets: widgets.to_vec(),
+            })
+            .collect();
+        Self {
+            rows: vec![LayoutRow { ratio: 100, columns }],
+        }
+    }
+
+    /// Area of each column the `enabled` widgets leave in `area`, with its widgets
+    ///
+    /// Disabled widgets are left out; a column left empty gives its room to
+    /// the rest of its row, and a row left empty to the other rows. Columns
+    /// are `column_gap` apart and rows `row_gap`.
+    pub fn column_areas(
+        &self,
+        area: Rect,
+        enabled: &dyn Fn(WidgetKind) -> bool,
+        column_gap: u16,
+        row_gap: u16,
+    ) -> Vec<(Rect, Vec<WidgetKind>)> {
+        let tree = self.only(enabled);
+        let row_areas = split_spaced(area, Direction::Vertical, tree.rows.iter().map(|row| row.ratio), row_gap);
+        tree.rows
+            .into_iter()
+            .zip(row_areas)
+            .flat_map(|(row, row_area)| {
+                let ratios = row.columns.iter().map(|column| column.ratio);
+                let column_areas = split_spaced(row_area, Direction::Horizontal, ratios, column_gap);
+                column_areas.into_iter().zip(row.columns.into_iter().map(|column| column.widgets))
+            })
+            .collect()
+    }
+
+    /// The tree without the widgets `enabled` rejects, nor the columns and rows left empty
+    fn only(&self, enabled: &dyn Fn(WidgetKind) -> bool) -> LayoutTree {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| LayoutRow {
+                ratio: row.ratio,
+                columns: row
+                    .columns
+                    .iter()
+                    .map(|column| LayoutColumn {
+                        ratio: column.ratio,
+                        widgets: column.widgets.iter().copied().filter(|&kind| enabled(kind)).collect(),
+                    })
+                    .filter(|column| !column.widgets.is_empty())
+                    .collect(),
+            })
+            .filter(|row| !row.columns.is_empty())
+            .collect();
+        LayoutTree { rows }
+    }
+
+    /// Area of each of the `enabled` widgets in `area`, in reading order
+    ///
+    /// Widgets sharing a column share its height, except that a fixed-height
+    /// one such as streams keeps its rows next to the others.
+    pub fn widget_areas(
+        &self,
+        area: Rect,
+        enabled: &dyn Fn(WidgetKind) -> bool,
+        column_gap: u16,
+        row_gap: u16,
+    ) -> Vec<(WidgetKind, Rect)> {
+        self.column_areas(area, enabled, column_gap, row_gap)
+            .into_iter()
+            .flat_map(|(column, widgets)| {
+                let rows = stack_widgets(column, &widgets, row_gap);
+                widgets.into_iter().zip(rows)
+            })
+            .collect()
+    }
+}
+
+/// Split `area` along `direction` in proportion to `ratios`, leaving `gap` between the parts
+fn split_spaced(area: Rect, direction: Direction, ratios: impl Iterator<Item = u16>, gap: u16) -> Vec<Rect> {
+    let mut constraints = Vec::new();
+    for (i, ratio) in ratios.enumerate() {
+        if i > 0 {
+            constraints.push(Constraint::Length(gap));
+        }
+        constraints.push(Constraint::Fill(ratio));
+    }
+    let parts = Layout::default().direction(direction).constraints(constraints).split(area);
+    parts.iter().step_by(2).copied().collect()
+}
+
+/// Areas of the basic dashboard
+#[derive(Debug, Clone)]
+pub struct ImprovedLayout {
+    pub header: Rect,
+    /// Everything between header and footer, for a notice when no widget is enabled
+    pub main: Rect,
+    pub footer: Rect,
+    /// The `enabled` widgets of the grid and their areas
+    pub widgets: Vec<(WidgetKind, Rect)>,
+}
+
+/// Improved layout with better spacing, for the `enabled` widgets of `tree` only
+pub fn create_improved_layout(area: Rect, tree: &LayoutTree, enabled: &dyn Fn(WidgetKind) -> bool) -> ImprovedLayout {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -23,44 +379,78 @@ pub fn create_improved_layout(area: Rect) -> Vec<Rect> {
         ])
         .split(area);
 
-    // Main content with horizontal spacing
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(45), // Left column
-            Constraint::Length(3),      // Horizontal spacer
-            Constraint::Percentage(45), // Right column
-        ])
-        .split(chunks[2]);
+    ImprovedLayout {
+        header: chunks[0],
+        main: chunks[2],
+        footer: chunks[4],
+        widgets: tree.widget_areas(chunks[2], enabled, 3, 2),
+    }
+}
 
-    // Left column with vertical spacing
-    let left_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(45), // Latency
-            Constraint::Length(2),      // Vertical spacer
-            Constraint::Percentage(45), // Throughput
-        ])
-        .split(main_chunks[0]);
+/// Split `area` between the `enabled` widgets, returning an area for each in order
+///
+/// One or two widgets are stacked in a single column. From three on, the
+/// first half of the list fills the left column and the rest the right one,
+/// so an odd widget out goes right. No widgets get no areas.
+pub fn build_layout(enabled: &[WidgetKind], area: Rect) -> Vec<Rect> {
+    let tree = match enabled.len() {
+        0..=2 => LayoutTree::columns(&[enabled]),
+        n => {
+            let (left, right) = enabled.split_at(n / 2);
+            LayoutTree::columns(&[left, right])
+        }
+    };
+    tree.widget_areas(area, &|_| true, 0, 0)
+        .into_iter()
+        .map(|(_, area)| area)
+        .collect()
+}
 
-    // Right column with vertical spacing
-    let right_chunks = Layout::default()
+/// Rows of `area` for `widgets`, fixed-height ones keeping their height next to the others
+fn stack_widgets(area: Rect, widgets: &[WidgetKind], gap: u16) -> Vec<Rect> {
+    let shared = widgets.iter().any(|widget| widget.height().is_none());
+    let mut constraints = Vec::new();
+    for (i, widget) in widgets.iter().enumerate() {
+        if i > 0 {
+            constraints.push(Constraint::Length(gap));
+        }
+        constraints.push(match widget.height() {
+            Some(height) if shared => Constraint::Length(height),
+            _ => Constraint::Fill(1),
+        });
+    }
+    let rows = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(45), // Connections
-            Constraint::Length(2),      // Vertical spacer
-            Constraint::Percentage(45), // Network
-        ])
-        .split(main_chunks[2]);
+        .constraints(constraints)
+        .split(area);
+    rows.iter().step_by(2).copied().collect()
+}
 
-    vec![
-        chunks[0],      // Header
-        left_chunks[0], // Latency
-        left_chunks[2], // Throughput
-        right_chunks[0], // Connections
-        right_chunks[2], // Network
-        chunks[4],      // Footer
-    ]
+/// Fill `area` of a view whose widgets are all turned off
+pub fn render_no_widgets(f: &mut Frame, area: Rect, theme: &Theme) {
+    let notice = Paragraph::new("Every widget of this view is turned off; enable them under [widgets] in the config")
+        .style(Style::default().fg(theme.muted))
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(notice, area);
+}
+
+/// Fill the area `[layout]` gives a widget the mode does not have
+pub fn render_unavailable(f: &mut Frame, area: Rect, kind: WidgetKind, theme: &Theme) {
+    let notice = Paragraph::new(format!("{} is not shown by this mode", kind))
+        .style(Style::default().fg(theme.muted))
+        .block(Block::default().borders(Borders::ALL).title(kind.name()));
+    f.render_widget(notice, area);
+}
+
+/// The room of a widget without size limits of its own, so the responsive layout can place it
+#[derive(Debug, Clone, Copy)]
+pub struct AnySize;
+
+impl MinSize for AnySize {
+    fn min_size(&self, _presentation: Presentation) -> Size {
+        // A bordered box with a line inside
+        Size::new(0, 3)
+    }
 }
 
 /// Render spacer between widgets
@@ -79,12 +469,13 @@ pub fn render_improved_sparkline(
     color: Color,
 ) {
     if !data.is_empty() {
-        let sparkline = Sparkline::default()
-            .data(&data.iter().map(|&x| x as u64).collect::<Vec<u64>>())
+        let series = ScaledSeries::new(data.iter().copied());
+        let sparkline = series
+            .sparkline()
             .style(Style::default().fg(color))
             .block(Block::default()
                 .borders(Borders::ALL)
-                .title(title)
+                .title(series.title(title, 2, ""))
                 .title_style(Style::default().fg(color).add_modifier(Modifier::BOLD)));
         f.render_widget(sparkline, area);
     } else {
@@ -98,3 +489,115 @@ pub fn render_improved_sparkline(
         f.render_widget(placeholder, area);
     }
 }
+
+/// How much of a widget is drawn, from the most to the least
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presentation {
+    /// Every row of the widget
+    Full,
+    /// Graphs without their analytics rows
+    Compact,
+    /// A single line of current values
+    Minimal,
+}
+
+impl Presentation {
+    /// The fullest presentation of `widget` whose minimum size fits in `area`
+    ///
+    /// Minimal when nothing does, so widgets drawn into whatever area a view
+    /// gives them shrink instead of drawing over themselves.
+    pub fn fitting(widget: &dyn MinSize, area: Rect) -> Self {
+        [Self::Full, Self::Compact]
+            .into_iter()
+            .find(|&presentation| {
+                let min = widget.min_size(presentation);
+                min.width <= area.width && min.height <= area.height
+            })
+            .unwrap_or(Self::Minimal)
+    }
+}
+
+/// The room a widget needs, so the responsive layout can decide what to drop
+pub trait MinSize {
+    /// Smallest area that draws the widget at `presentation` without overlap
+    fn min_size(&self, presentation: Presentation) -> Size;
+}
+
+/// Areas chosen by `responsive_layout`
+#[derive(Debug, Clone)]
+pub struct ResponsiveLayout {
+    pub presentation: Presentation,
+    /// Area of each widget, in the order given; `None` for a widget dropped for lack of room
+    pub areas: Vec<Option<Rect>>,
+}
+
+/// Place the widgets of each column over its area at the fullest presentation that fits
+///
+/// Full and compact keep the columns where they are, every widget getting its
+/// minimum height plus an even share of its column's spare rows. When not
+/// even the compact sizes fit, each widget gets its minimal line in a single
+/// column over `area`, and the widgets below the bottom edge are dropped.
+pub fn responsive_layout(area: Rect, columns: &[(Rect, Vec<&dyn MinSize>)]) -> ResponsiveLayout {
+    let (column_areas, columns): (Vec<Rect>, Vec<&[&dyn MinSize]>) =
+        columns.iter().map(|(column, widgets)| (*column, widgets.as_slice())).unzip();
+
+    for presentation in [Presentation::Full, Presentation::Compact] {
+        let sizes: Vec<Vec<Size>> = columns
+            .iter()
+            .map(|widgets| widgets.iter().map(|widget| widget.min_size(presentation)).collect())
+            .collect();
+        let fits = sizes.iter().zip(column_areas.iter()).all(|(sizes, column)| {
+            sizes.iter().all(|size| size.width <= column.width)
+                && sizes.iter().map(|size| u32::from(size.height)).sum::<u32>() <= u32::from(column.height)
+        });
+        if fits {
+            let areas = sizes
+                .iter()
+                .zip(column_areas.iter())
+                .flat_map(|(sizes, column)| {
+                    let heights: Vec<u16> = sizes.iter().map(|size| size.height).collect();
+                    stack(*column, &heights)
+                })
+                .map(Some)
+                .collect();
+            return ResponsiveLayout { presentation, areas };
+        }
+    }
+
+    let mut y = area.y;
+    let areas = columns
+        .iter()
+        .flat_map(|widgets| widgets.iter())
+        .map(|widget| {
+            let height = widget.min_size(Presentation::Minimal).height;
+            (y.saturating_add(height) <= area.bottom()).then(|| {
+                let line = Rect::new(area.x, y, area.width, height);
+                y += height;
+                line
+            })
+        })
+        .collect();
+    ResponsiveLayout {
+        presentation: Presentation::Minimal,
+        areas,
+    }
+}
+
+/// Rows of `area` at least `heights` tall, sharing the spare rows out evenly from the top
+fn stack(area: Rect, heights: &[u16]) -> Vec<Rect> {
+    let Some(count) = u16::try_from(heights.len()).ok().filter(|&count| count > 0) else {
+        return Vec::new();
+    };
+    let spare = area.height.saturating_sub(heights.iter().sum());
+    let mut y = area.y;
+    heights
+        .iter()
+        .zip(0..)
+        .map(|(&height, i)| {
+            let height = height + spare / count + u16::from(i < spare % count);
+            let row = Rect::new(area.x, y, area.width, height);
+            y += height;
+            row
+        })
+        .collect()
+}
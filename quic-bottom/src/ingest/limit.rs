@@ -0,0 +1,259 @@
+//! Request limits for the HTTP ingestion routes
+//!
+//! Each client, keyed by IP address, gets a token bucket refilled at
+//! `[api.rate_limit] requests_per_sec` and holding up to `burst` requests, so
+//! one flooding sender is throttled without touching the others. JSON bodies
+//! are capped by their declared `Content-Length`. Refused requests get 429
+//! with `Retry-After`, or 413, and are counted in `http_limit_stats()`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+use crate::sync::MutexExt;
+
+/// Buckets kept before idle, refilled ones are swept
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Per-client rate limit, under `[api.rate_limit]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Sustained requests per second per client; 0 turns the limit off
+    pub requests_per_sec: f64,
+
+    /// Requests a client may send at once after being idle
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_sec: 2000.0,
+            burst: 4000,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.requests_per_sec > 0.0
+    }
+}
+
+/// Requests refused by the HTTP ingestion limits since startup
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HttpLimitStats {
+    pub rate_limited: u64,
+    pub body_too_large: u64,
+}
+
+static RATE_LIMITED: AtomicU64 = AtomicU64::new(0);
+static BODY_TOO_LARGE: AtomicU64 = AtomicU64::new(0);
+
+/// Snapshot of the refusal counters of both HTTP servers
+pub fn http_limit_stats() -> HttpLimitStats {
+    HttpLimitStats {
+        rate_limited: RATE_LIMITED.load(Ordering::Relaxed),
+        body_too_large: BODY_TOO_LARGE.load(Ordering::Relaxed),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets per client address
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            rate: config.requests_per_sec,
+            burst: f64::from(config.burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `client`, or say how long until one is available
+    pub fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock_or_recover();
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            self.sweep(&mut buckets, now);
+        }
+
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    /// Forget clients whose bucket has refilled; they start full anyway
+    fn sweep(&self, buckets: &mut HashMap<IpAddr, Bucket>, now: Instant) {
+        let refill = Duration::from_secs_f64(self.burst / self.rate);
+        buckets.retain(|_, bucket| now.saturating_duration_since(bucket.updated) < refill);
+    }
+}
+
+/// Refusal from the rate limiter
+#[derive(Debug)]
+pub struct RateLimited {
+    retry_after: Duration,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+/// Filter passing requests while their client is within its rate
+///
+/// `None` (the limit turned off) lets everything through.
+pub fn rate_limit(limiter: Option<Arc<RateLimiter>>) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                let Some(limiter) = limiter else {
+                    return Ok(());
+                };
+                // Only connections without a peer address share this bucket
+                let client = addr.map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |addr| addr.ip());
+                limiter.check(client, Instant::now()).map_err(|retry_after| {
+                    RATE_LIMITED.fetch_add(1, Ordering::Relaxed);
+                    warp::reject::custom(RateLimited { retry_after })
+                })
+            }
+        })
+        .untuple_one()
+}
+
+/// Filter rejecting bodies declared larger than `max_bytes` (or undeclared) with 413
+pub fn body_limit(max_bytes: u64) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::body::content_length_limit(max_bytes).or_else(|rejection: Rejection| async move {
+        if rejection.find::<warp::reject::PayloadTooLarge>().is_some() {
+            BODY_TOO_LARGE.fetch_add(1, Ordering::Relaxed);
+        }
+        Err::<(), _>(rejection)
+    })
+}
+
+/// Turn a `RateLimited` rejection into a 429 with `Retry-After` in whole seconds
+pub async fn recover_rate_limited(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<RateLimited>() {
+        Some(limited) => {
+            let retry_after = limited.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            let body = serde_json::json!({"status": "error", "message": "rate limit exceeded"});
+            Ok(warp::reply::with_header(
+                warp::reply::with_status(warp::reply::json(&body), StatusCode::TOO_MANY_REQUESTS),
+                "retry-after",
+                retry_after.to_string(),
+            ))
+        }
+        None => Err(rejection),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_sec: f64, burst: u32) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig { requests_per_sec, burst })
+    }
+
+    #[test]
+    fn flooding_client_does_not_starve_the_others() {
+        let limiter = limiter(100.0, 20);
+        let start = Instant::now();
+        let flooder: IpAddr = [10, 0, 0, 1].into();
+        let senders: Vec<IpAddr> = (2..6).map(|host| IpAddr::from([10, 0, 0, host])).collect();
+
+        let (flooded, sent) = std::thread::scope(|scope| {
+            // Four threads flood from one address within the same instant
+            let floods: Vec<_> = (0..4)
+                .map(|_| {
+                    scope.spawn(|| (0..5_000).filter(|_| limiter.check(flooder, start).is_ok()).count())
+                })
+                .collect();
+            // Meanwhile each well-behaved client sends at half the rate for a simulated second
+            let sends: Vec<_> = senders
+                .iter()
+                .map(|&client| {
+                    let limiter = &limiter;
+                    scope.spawn(move || {
+                        (0..50)
+                            .filter(|&i| limiter.check(client, start + Duration::from_millis(i * 20)).is_ok())
+                            .count()
+                    })
+                })
+                .collect();
+            (
+                floods.into_iter().map(|flood| flood.join().unwrap()).sum::<usize>(),
+                sends.into_iter().map(|send| send.join().unwrap()).collect::<Vec<_>>(),
+            )
+        });
+
+        assert_eq!(flooded, 20, "the flooder gets its burst and nothing more");
+        assert_eq!(sent, [50; 4], "every legitimate request is admitted");
+    }
+
+    #[test]
+    fn refills_at_the_configured_rate() {
+        let limiter = limiter(10.0, 2);
+        let client: IpAddr = [127, 0, 0, 1].into();
+        let start = Instant::now();
+        assert!(limiter.check(client, start).is_ok());
+        assert!(limiter.check(client, start).is_ok());
+        let retry_after = limiter.check(client, start).unwrap_err();
+        assert!((retry_after.as_secs_f64() - 0.1).abs() < 1e-9);
+
+        assert!(limiter.check(client, start + Duration::from_millis(100)).is_ok());
+        assert!(limiter.check(client, start + Duration::from_millis(100)).is_err());
+        // Idle time refills up to the burst, not beyond it
+        let later = start + Duration::from_secs(60);
+        assert_eq!((0..5).filter(|_| limiter.check(client, later).is_ok()).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn over_the_limit_gets_429_with_retry_after() {
+        let limiter = Arc::new(limiter(0.5, 1));
+        let routes = rate_limit(Some(limiter))
+            .and(body_limit(16))
+            .map(|| "ok")
+            .recover(recover_rate_limited);
+        let request = |addr: [u8; 4]| {
+            warp::test::request()
+                .method("POST")
+                .remote_addr(SocketAddr::from((addr, 4000)))
+                .body("{}")
+        };
+
+        assert_eq!(request([10, 0, 0, 1]).reply(&routes).await.status(), StatusCode::OK);
+        let response = request([10, 0, 0, 1]).reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["retry-after"], "2");
+        assert_eq!(request([10, 0, 0, 2]).reply(&routes).await.status(), StatusCode::OK);
+
+        let response = request([10, 0, 0, 3]).body("x".repeat(17)).reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
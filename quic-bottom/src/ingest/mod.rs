@@ -0,0 +1,217 @@
+//! Shared handling for ingestion endpoints
+//!
+//! Both HTTP servers accept batches of samples on `POST /api/metrics/batch`.
+//! This module holds the parts that do not depend on the sample type: the
+//! ordering policy, the size limit and the per-batch report. Transports
+//! other than HTTP live in submodules.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use crate::metrics::Timestamped;
+use crate::sync::RwLockExt;
+use crate::units::{LossUnit, Unit};
+
+pub mod limit;
+pub mod statsd;
+#[cfg(unix)]
+pub mod uds;
+
+use statsd::StatsdConfig;
+
+/// Ingestion transports besides the HTTP API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IngestConfig {
+    /// Unix socket accepting newline-delimited JSON samples; off when unset
+    pub uds_path: Option<PathBuf>,
+
+    /// statsd lines over UDP
+    pub statsd: StatsdConfig,
+
+    /// Unit of `throughput` in samples that do not name one
+    pub throughput_unit: Unit,
+
+    /// Unit of `packet_loss` in samples that do not name one
+    pub loss_unit: LossUnit,
+}
+
+/// Default cap on the number of samples in one batch request
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 10_000;
+
+/// Default cap on the body of a single-sample POST
+pub const DEFAULT_MAX_BODY_BYTES: u64 = 64 * 1024;
+
+/// Default cap on the body of a batch POST
+pub const DEFAULT_MAX_BATCH_BODY_BYTES: u64 = 8 * 1024 * 1024;
+
+/// What to do with samples whose timestamps go backwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOrder {
+    /// Sort the batch by timestamp before inserting it
+    #[default]
+    Sort,
+    /// Reject samples older than the previously accepted one
+    Reject,
+}
+
+/// Query parameters for `POST /api/metrics/batch`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BatchQuery {
+    pub order: BatchOrder,
+}
+
+/// One rejected sample and why
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchRejection {
+    /// Position of the sample in the request array
+    pub index: usize,
+    pub reason: String,
+}
+
+/// Response body of a batch request
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchReport {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub errors: Vec<BatchRejection>,
+}
+
+impl BatchReport {
+    pub fn reject(&mut self, index: usize, reason: impl Into<String>) {
+        record_ingest_error();
+        self.rejected += 1;
+        self.errors.push(BatchRejection {
+            index,
+            reason: reason.into(),
+        });
+    }
+}
+
+static INGEST_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+/// Count a sample that was received but could not be stored
+pub fn record_ingest_error() {
+    INGEST_ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Samples refused by any ingestion path since startup
+pub fn ingest_errors() -> u64 {
+    INGEST_ERRORS.load(Ordering::Relaxed)
+}
+
+static THROUGHPUT_UNIT: RwLock<Unit> = RwLock::new(Unit::Bps);
+
+/// Set the unit assumed for samples without a `unit`, from `[ingest] throughput_unit`
+pub fn set_throughput_unit(unit: Unit) {
+    *THROUGHPUT_UNIT.write_or_recover() = unit;
+}
+
+/// `throughput` of a sample in bits per second, read in `unit` or the configured default
+pub fn throughput_bps(throughput: f64, unit: Option<Unit>) -> f64 {
+    unit.unwrap_or_else(|| *THROUGHPUT_UNIT.read_or_recover()).to_bps(throughput)
+}
+
+static LOSS_UNIT: RwLock<LossUnit> = RwLock::new(LossUnit::Auto);
+
+/// Whether `loss_fraction` has warned about a percent it had to guess
+static LOSS_GUESS_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Set the unit assumed for samples without a `loss_unit`, from `[ingest] loss_unit`
+pub fn set_loss_unit(unit: LossUnit) {
+    *LOSS_UNIT.write_or_recover() = unit;
+}
+
+/// `packet_loss` of a sample as a fraction, read in `unit` or the configured default
+///
+/// Under `LossUnit::Auto` a value above 1.0 is taken as a percent, with a
+/// warning the first time, since the same sender's values below 1% would
+/// then be misread as fractions.
+pub fn loss_fraction(packet_loss: f64, unit: Option<LossUnit>) -> f64 {
+    let unit = unit.unwrap_or_else(|| *LOSS_UNIT.read_or_recover());
+    if unit == LossUnit::Auto && packet_loss > 1.0 && !LOSS_GUESS_WARNED.swap(true, Ordering::Relaxed) {
+        log::warn!(
+            "packet_loss {} is above 1.0, reading it as a percent; set [ingest] loss_unit = \"percent\" if the sender reports percents",
+            packet_loss
+        );
+    }
+    unit.to_fraction(packet_loss)
+}
+
+/// Reject a packet loss that is negative or above 100% once read as a fraction
+pub fn ensure_loss(packet_loss: f64, unit: Option<LossUnit>) -> Result<()> {
+    ensure_non_negative("packet_loss", packet_loss)?;
+    let fraction = loss_fraction(packet_loss, unit);
+    if fraction > 1.0 {
+        return Err(anyhow!("packet_loss {} is above 100%", packet_loss));
+    }
+    Ok(())
+}
+
+/// Put validated `(index, sample)` entries in insertion order
+///
+/// `newest` is the timestamp (unix ms) of the newest stored sample. With
+/// `BatchOrder::Sort` the batch is sorted but still appended after stored
+/// history; with `BatchOrder::Reject` anything older than `newest` or the
+/// previously accepted entry is rejected into `report`.
+pub fn order_batch<T: Timestamped>(
+    mut entries: Vec<(usize, T)>,
+    order: BatchOrder,
+    newest: Option<i64>,
+    report: &mut BatchReport,
+) -> Vec<T> {
+    match order {
+        BatchOrder::Sort => {
+            entries.sort_by_key(|(_, sample)| sample.timestamp_ms());
+            report.accepted += entries.len();
+            entries.into_iter().map(|(_, sample)| sample).collect()
+        }
+        BatchOrder::Reject => {
+            let mut last = newest;
+            let mut accepted = Vec::with_capacity(entries.len());
+            for (index, sample) in entries {
+                let ts = sample.timestamp_ms();
+                match last {
+                    Some(last) if ts < last => {
+                        report.reject(index, format!("timestamp {} is older than {}", ts, last));
+                    }
+                    _ => {
+                        last = Some(ts);
+                        accepted.push(sample);
+                    }
+                }
+            }
+            report.accepted += accepted.len();
+            accepted
+        }
+    }
+}
+
+/// Error message for a batch over `max_batch_size`, `None` if it fits
+pub fn check_batch_size(len: usize, max_batch_size: usize) -> Option<String> {
+    (len > max_batch_size)
+        .then(|| format!("batch of {} samples exceeds max_batch_size {}", len, max_batch_size))
+}
+
+/// Largest measurement or count accepted from a sender
+///
+/// Far beyond any real latency, rate or byte count, and far enough below
+/// `f64::MAX` that chart scaling cannot overflow; ratatui's line clipping
+/// never returns once a chart's range approaches it.
+pub const MAX_MEASUREMENT: f64 = 1e18;
+
+/// Error unless `value` is finite, not negative and at most `MAX_MEASUREMENT`
+pub fn ensure_non_negative(field: &str, value: f64) -> Result<()> {
+    if !(value.is_finite() && value >= 0.0) {
+        return Err(anyhow!("{} must be a non-negative number, got {}", field, value));
+    }
+    if value > MAX_MEASUREMENT {
+        return Err(anyhow!("{} {:?} is above the largest accepted value, {:e}", field, value, MAX_MEASUREMENT));
+    }
+    Ok(())
+}
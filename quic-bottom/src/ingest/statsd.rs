@@ -0,0 +1,295 @@
+//! statsd ingestion over UDP
+//!
+//! Test rigs that already emit statsd can feed quic-bottom without code
+//! changes. Lines such as `quic.latency:12.4|g` or `quic.retransmits:3|c`
+//! are mapped onto `QUICMetrics` fields by name and folded into one sample
+//! per update interval:
+//!
+//! - gauges (`g`) keep their last value, `+n`/`-n` adjust it
+//! - counters (`c`) are summed over the interval, scaled by `|@rate`
+//! - timers (`ms`, `h`) are averaged over the interval
+//!
+//! Throughput is read in `[ingest] throughput_unit` and packet loss in
+//! `[ingest] loss_unit`. Fields with no data
+//! yet are zero. Intervals in which nothing mapped arrived produce no
+//! sample. Unknown names and malformed lines are counted in
+//! `statsd_stats()` and otherwise ignored.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+use crate::ingest::{loss_fraction, throughput_bps};
+use crate::metrics::{MetricsHandle, QUICMetrics};
+use crate::sync::MutexExt;
+
+/// `QUICMetrics` field a statsd metric is mapped onto
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricField {
+    Latency,
+    Throughput,
+    Connections,
+    Errors,
+    PacketLoss,
+    Retransmits,
+}
+
+/// statsd listener settings, under `[ingest.statsd]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsdConfig {
+    pub enabled: bool,
+
+    /// Address the UDP listener binds to
+    pub bind_address: String,
+
+    pub port: u16,
+
+    /// statsd metric name to field; replaces the defaults when set
+    pub metrics: BTreeMap<String, MetricField>,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        let metrics = [
+            ("quic.latency", MetricField::Latency),
+            ("quic.throughput", MetricField::Throughput),
+            ("quic.connections", MetricField::Connections),
+            ("quic.errors", MetricField::Errors),
+            ("quic.packet_loss", MetricField::PacketLoss),
+            ("quic.retransmits", MetricField::Retransmits),
+        ]
+        .into_iter()
+        .map(|(name, field)| (name.to_string(), field))
+        .collect();
+
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 8125,
+            metrics,
+        }
+    }
+}
+
+/// Listener counters, reported by `GET /api/ingest/stats`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsdStats {
+    pub packets: u64,
+    pub lines: u64,
+    pub malformed: u64,
+    /// Samples pushed to the metrics handle
+    pub samples: u64,
+    /// Lines per metric name missing from the mapping
+    pub unknown: BTreeMap<String, u64>,
+}
+
+/// Distinct unknown names tracked; the rest are counted under `UNKNOWN_OVERFLOW`
+const MAX_UNKNOWN_NAMES: usize = 100;
+const UNKNOWN_OVERFLOW: &str = "(other)";
+
+static STATS: Mutex<StatsdStats> = Mutex::new(StatsdStats {
+    packets: 0,
+    lines: 0,
+    malformed: 0,
+    samples: 0,
+    unknown: BTreeMap::new(),
+});
+
+/// Snapshot of the statsd listener counters; all zero when it is not running
+pub fn statsd_stats() -> StatsdStats {
+    STATS.lock_or_recover().clone()
+}
+
+/// One parsed statsd line
+#[derive(Debug, Clone)]
+struct Line<'a> {
+    name: &'a str,
+    value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Gauge(f64),
+    /// `+n` or `-n` gauge
+    GaugeDelta(f64),
+    Counter(f64),
+    Timer(f64),
+}
+
+/// Parse `name:value|type[|@rate][|#tags]`
+fn parse_line(line: &str) -> Result<Line<'_>> {
+    let (name, rest) = line.split_once(':').ok_or_else(|| anyhow!("missing ':'"))?;
+    let mut parts = rest.split('|');
+    let raw = parts.next().unwrap_or_default();
+    let kind = parts.next().ok_or_else(|| anyhow!("missing '|type'"))?;
+    let number: f64 = raw.parse().map_err(|_| anyhow!("invalid value '{}'", raw))?;
+    if name.is_empty() || !number.is_finite() {
+        return Err(anyhow!("invalid metric"));
+    }
+    let rate = parts
+        .find_map(|part| part.strip_prefix('@'))
+        .map(|rate| rate.parse::<f64>().map_err(|_| anyhow!("invalid sample rate '{}'", rate)))
+        .transpose()?
+        .filter(|rate| *rate > 0.0 && *rate <= 1.0)
+        .unwrap_or(1.0);
+
+    let value = match kind {
+        "g" if raw.starts_with(['+', '-']) => Value::GaugeDelta(number),
+        "g" => Value::Gauge(number),
+        "c" => Value::Counter(number / rate),
+        "ms" | "h" => Value::Timer(number),
+        other => return Err(anyhow!("unsupported type '{}'", other)),
+    };
+    Ok(Line { name, value })
+}
+
+/// Accumulated values between flushes
+#[derive(Debug, Default)]
+struct Aggregator {
+    gauges: HashMap<MetricField, f64>,
+    counters: HashMap<MetricField, f64>,
+    timers: HashMap<MetricField, (f64, u32)>,
+    /// Something mapped arrived since the last flush
+    dirty: bool,
+}
+
+impl Aggregator {
+    fn add(&mut self, field: MetricField, value: Value) {
+        match value {
+            Value::Gauge(v) => {
+                self.gauges.insert(field, v);
+            }
+            Value::GaugeDelta(d) => *self.gauges.entry(field).or_default() += d,
+            Value::Counter(v) => *self.counters.entry(field).or_default() += v,
+            Value::Timer(v) => {
+                let (sum, count) = self.timers.entry(field).or_default();
+                *sum += v;
+                *count += 1;
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Fold the interval into a sample; gauges carry over, the rest resets
+    fn flush(&mut self) -> Option<QUICMetrics> {
+        if !std::mem::take(&mut self.dirty) {
+            return None;
+        }
+        let counters = std::mem::take(&mut self.counters);
+        let timers = std::mem::take(&mut self.timers);
+        let value = |field| {
+            timers
+                .get(&field)
+                .map(|(sum, count)| sum / *count as f64)
+                .or_else(|| counters.get(&field).copied())
+                .or_else(|| self.gauges.get(&field).copied())
+                .unwrap_or(0.0)
+        };
+
+        Some(QUICMetrics {
+            latency: value(MetricField::Latency),
+            throughput: throughput_bps(value(MetricField::Throughput), None),
+            connections: value(MetricField::Connections).round() as i32,
+            errors: value(MetricField::Errors).round() as i32,
+            packet_loss: loss_fraction(value(MetricField::PacketLoss), None),
+            retransmits: value(MetricField::Retransmits).round() as i32,
+            timestamp: Utc::now(),
+            ..QUICMetrics::default()
+        })
+    }
+}
+
+/// Bound statsd UDP socket
+pub struct StatsdListener {
+    socket: UdpSocket,
+    metrics: BTreeMap<String, MetricField>,
+}
+
+impl StatsdListener {
+    /// Bind the configured address; binding up front surfaces errors at startup
+    pub async fn bind(config: &StatsdConfig) -> Result<Self> {
+        let ip: IpAddr = config
+            .bind_address
+            .parse()
+            .map_err(|_| anyhow!("invalid statsd bind address '{}'", config.bind_address))?;
+        let addr = SocketAddr::new(ip, config.port);
+        let socket = UdpSocket::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind statsd listener to {}", addr))?;
+        Ok(Self {
+            socket,
+            metrics: config.metrics.clone(),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Receive lines and push one sample per `interval` into `metrics` until dropped
+    pub async fn serve(self, metrics: MetricsHandle, interval: Duration) {
+        let mut aggregator = Aggregator::default();
+        let mut flush = tokio::time::interval(interval);
+        let mut buf = vec![0u8; 65_536];
+        loop {
+            tokio::select! {
+                received = self.socket.recv_from(&mut buf) => match received {
+                    Ok((len, _)) => self.handle_packet(&buf[..len], &mut aggregator),
+                    Err(e) => log::warn!("statsd: receive failed: {}", e),
+                },
+                _ = flush.tick() => {
+                    if let Some(sample) = aggregator.flush() {
+                        match metrics.update(sample) {
+                            Ok(()) => STATS.lock_or_recover().samples += 1,
+                            Err(e) => {
+                                super::record_ingest_error();
+                                log::warn!("statsd: failed to update metrics: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_packet(&self, packet: &[u8], aggregator: &mut Aggregator) {
+        let text = String::from_utf8_lossy(packet);
+        let mut stats = StatsdStats {
+            packets: 1,
+            ..StatsdStats::default()
+        };
+        for raw in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            stats.lines += 1;
+            match parse_line(raw) {
+                Ok(line) => match self.metrics.get(line.name) {
+                    Some(&field) => aggregator.add(field, line.value),
+                    None => *stats.unknown.entry(line.name.to_string()).or_default() += 1,
+                },
+                Err(e) => {
+                    log::debug!("statsd: malformed line '{}': {}", raw, e);
+                    stats.malformed += 1;
+                }
+            }
+        }
+
+        let mut total = STATS.lock_or_recover();
+        total.packets += stats.packets;
+        total.lines += stats.lines;
+        total.malformed += stats.malformed;
+        for (name, count) in stats.unknown {
+            let name = if total.unknown.len() < MAX_UNKNOWN_NAMES || total.unknown.contains_key(&name) {
+                name
+            } else {
+                UNKNOWN_OVERFLOW.to_string()
+            };
+            *total.unknown.entry(name).or_default() += count;
+        }
+    }
+}
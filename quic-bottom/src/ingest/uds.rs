@@ -0,0 +1,204 @@
+//! Unix domain socket ingestion
+//!
+//! An alternative to the HTTP API for senders on the same machine: no TCP
+//! port to reserve and less per-sample overhead. Each connection sends
+//! newline-delimited JSON objects in the same format the matching HTTP
+//! endpoint accepts. Malformed lines are logged and skipped.
+//!
+//! The socket is created with mode 0600, a stale socket file left by a
+//! crashed run is replaced, and the file is removed when the listener is
+//! dropped.
+//!
+//! From Go, `json.Encoder` already writes one object per line:
+//!
+//! ```go
+//! conn, err := net.Dial("unix", "/tmp/quic-bottom.sock")
+//! if err != nil {
+//!     log.Fatal(err)
+//! }
+//! defer conn.Close()
+//! enc := json.NewEncoder(conn)
+//! enc.Encode(map[string]any{
+//!     "latency": 12.5, "throughput": 940.0, "unit": "Mbps", "connections": 4,
+//!     "errors": 0, "packet_loss": 0.001, "retransmits": 2,
+//! })
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use std::fs::{self, Permissions};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Listening Unix socket that removes its file on drop
+pub struct UdsListener {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl UdsListener {
+    /// Bind `path`, replacing a stale socket file, and restrict it to the owner
+    pub fn bind(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        remove_stale_socket(&path)?;
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("failed to bind Unix socket {}", path.display()))?;
+        // Owned from here on, so a failure below still removes the file
+        let uds = Self { listener, path };
+        fs::set_permissions(&uds.path, Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to set permissions on {}", uds.path.display()))?;
+        Ok(uds)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Accept connections until dropped, passing each decoded line to `handler`
+    pub async fn serve<T, F>(self, handler: F)
+    where
+        T: DeserializeOwned + 'static,
+        F: Fn(T) -> Result<()> + Send + Sync + 'static,
+    {
+        let handler = Arc::new(handler);
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(read_lines(stream, Arc::clone(&handler)));
+                }
+                Err(e) => log::warn!("UDS {}: accept failed: {}", self.path.display(), e),
+            }
+        }
+    }
+}
+
+impl Drop for UdsListener {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("UDS: failed to remove {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+async fn read_lines<T, F>(stream: UnixStream, handler: Arc<F>)
+where
+    T: DeserializeOwned,
+    F: Fn(T) -> Result<()>,
+{
+    let mut lines = BufReader::new(stream).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                log::warn!("UDS: read failed: {}", e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = serde_json::from_str(&line)
+            .map_err(anyhow::Error::from)
+            .and_then(|sample| handler(sample));
+        if let Err(e) = result {
+            super::record_ingest_error();
+            log::warn!("UDS: skipped line: {}", e);
+        }
+    }
+}
+
+/// Remove a socket file nobody is listening on; refuse to touch anything else
+fn remove_stale_socket(path: &Path) -> Result<()> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(anyhow!("{} exists and is not a socket", path.display()));
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(anyhow!("{} is in use by another process", path.display()));
+    }
+    fs::remove_file(path).with_context(|| format!("failed to remove stale socket {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quic-bottom-uds-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn serves_a_real_sender_and_removes_the_socket_on_drop() {
+        let dir = temp_dir("serve");
+        let path = dir.join("ingest.sock");
+        let uds = UdsListener::bind(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let server = tokio::spawn(uds.serve(move |sample: serde_json::Value| {
+            if sample.get("latency").is_none() {
+                return Err(anyhow!("no latency"));
+            }
+            sink.lock().unwrap().push(sample["latency"].clone());
+            Ok(())
+        }));
+
+        let mut sender = UnixStream::connect(&path).await.unwrap();
+        sender
+            .write_all(b"{\"latency\": 1.5}\n\nnot json\n{\"throughput\": 2}\n{\"latency\": 2.5}\n")
+            .await
+            .unwrap();
+        sender.shutdown().await.unwrap();
+        for _ in 0..100 {
+            if received.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(*received.lock().unwrap(), [1.5, 2.5]);
+
+        // Stopping the server drops the listener, which takes its file along
+        server.abort();
+        assert!(server.await.unwrap_err().is_cancelled());
+        assert!(!path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn replaces_a_stale_socket_but_nothing_else() {
+        let dir = temp_dir("stale");
+        let path = dir.join("stale.sock");
+        // A crashed run leaves its socket file behind with nobody listening
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+        assert!(path.exists());
+        let uds = UdsListener::bind(&path).unwrap();
+        assert_eq!(uds.path(), path);
+        std::os::unix::net::UnixStream::connect(&path).unwrap();
+
+        let error = UdsListener::bind(&path).err().unwrap();
+        assert!(error.to_string().contains("in use"), "{}", error);
+        drop(uds);
+        assert!(!path.exists());
+
+        let file = dir.join("not-a-socket");
+        fs::write(&file, "keep me").unwrap();
+        let error = UdsListener::bind(&file).err().unwrap();
+        assert!(error.to_string().contains("not a socket"), "{}", error);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "keep me");
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,711 @@
+//! Keybinding table, configurable keymap and help overlay
+//!
+//! Every TUI binary lists its keys from the one table below, so the help
+//! overlay cannot drift from what each app actually handles. Rows name
+//! actions rather than keys; the `[keybindings]` section of the config file
+//! can move any action to other keys, and the overlay shows the keys in use.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::demo_data::FaultKind;
+use crate::theme::Theme;
+
+/// Which TUI a binding belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppKind {
+    /// `quic-bottom` (`QuicBottomApp`)
+    Basic,
+    /// `quic-bottom live`
+    Real,
+    /// `quic-bottom analytics`
+    Ultimate,
+    /// `quic-bottom analytics --enhanced`
+    Enhanced,
+    /// `quic-bottom demo`
+    Demo,
+    /// `quic-bottom demo --style professional`
+    Professional,
+    /// `quic-bottom demo --style simple`
+    Simple,
+}
+
+impl AppKind {
+    const ALL: [AppKind; 7] = [
+        AppKind::Basic,
+        AppKind::Real,
+        AppKind::Ultimate,
+        AppKind::Enhanced,
+        AppKind::Demo,
+        AppKind::Professional,
+        AppKind::Simple,
+    ];
+
+    /// The command that starts the app, for warnings
+    fn command(self) -> &'static str {
+        match self {
+            AppKind::Basic => "quic-bottom",
+            AppKind::Real => "quic-bottom live",
+            AppKind::Ultimate => "quic-bottom analytics",
+            AppKind::Enhanced => "quic-bottom analytics --enhanced",
+            AppKind::Demo => "quic-bottom demo",
+            AppKind::Professional => "quic-bottom demo --style professional",
+            AppKind::Simple => "quic-bottom demo --style simple",
+        }
+    }
+}
+
+/// Something a key does; the name is the key of its `[keybindings]` entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    Reset,
+    Pause,
+    Export,
+    Report,
+    ToggleRedaction,
+    ToggleHistograms,
+    CycleSource,
+    ToggleOverlay,
+    ViewDashboard,
+    ViewAnalytics,
+    ViewNetwork,
+    ViewSecurity,
+    ViewCloud,
+    ViewBbrv3,
+    ViewGraphs,
+    ViewHeatmap,
+    ViewCorrelation,
+    ViewAnomalies,
+    ViewAll,
+    NextView,
+    PrevView,
+    ViewPicker,
+    FocusNext,
+    FocusPrev,
+    Expand,
+    ToggleNetwork,
+    NextPreset,
+    PrevPreset,
+    ToggleSecurity,
+    ToggleCloud,
+    ScaleInstances,
+    CorrelationMethod,
+    CorrelationLayout,
+    ToggleScale,
+    ZoomIn,
+    ZoomOut,
+    ScrollBack,
+    ScrollForward,
+    Oldest,
+    Newest,
+    SortConnections,
+    ReverseSort,
+    TraceConnection,
+    LoadBaseline,
+    ClearBaseline,
+    CycleTheme,
+    Inspect,
+    Rebaseline,
+    InjectLatencySpike,
+    InjectLossBurst,
+    InjectThroughputCollapse,
+    InjectConnectionFailures,
+    InjectErrorStorm,
+}
+
+/// Every action with its config name and default keys
+const ACTIONS: &[(Action, &str, &[&str])] = &[
+    (Action::Quit, "quit", &["q", "Q", "esc", "ctrl-c"]),
+    (Action::Help, "help", &["h"]),
+    (Action::Reset, "reset", &["r"]),
+    (Action::Pause, "pause", &["p"]),
+    (Action::Export, "export", &["e"]),
+    (Action::Report, "report", &["R"]),
+    (Action::ToggleRedaction, "toggle_redaction", &["P"]),
+    (Action::ToggleHistograms, "toggle_histograms", &["H"]),
+    (Action::CycleSource, "cycle_source", &["tab"]),
+    (Action::ToggleOverlay, "toggle_overlay", &["o"]),
+    (Action::ViewDashboard, "view_dashboard", &["1"]),
+    (Action::ViewAnalytics, "view_analytics", &["2"]),
+    (Action::ViewNetwork, "view_network", &["3"]),
+    (Action::ViewSecurity, "view_security", &["4"]),
+    (Action::ViewCloud, "view_cloud", &["5"]),
+    (Action::ViewBbrv3, "view_bbrv3", &["6"]),
+    (Action::ViewGraphs, "view_graphs", &["1"]),
+    (Action::ViewHeatmap, "view_heatmap", &["2"]),
+    (Action::ViewCorrelation, "view_correlation", &["3"]),
+    (Action::ViewAnomalies, "view_anomalies", &["4"]),
+    (Action::ViewAll, "view_all", &["a"]),
+    (Action::NextView, "next_view", &["F9"]),
+    (Action::PrevView, "prev_view", &["F8"]),
+    (Action::ViewPicker, "view_picker", &[":"]),
+    (Action::FocusNext, "focus_next", &["tab"]),
+    (Action::FocusPrev, "focus_prev", &["shift-tab"]),
+    // 'e' as in bottom, except in live where it exports; 'f' for full screen everywhere
+    (Action::Expand, "expand", &["e", "f"]),
+    (Action::ToggleNetwork, "toggle_network", &["n"]),
+    (Action::NextPreset, "next_preset", &["+"]),
+    (Action::PrevPreset, "prev_preset", &["-"]),
+    (Action::ToggleSecurity, "toggle_security", &["s"]),
+    (Action::ToggleCloud, "toggle_cloud", &["d"]),
+    (Action::ScaleInstances, "scale_instances", &["i"]),
+    (Action::CorrelationMethod, "correlation_method", &["m"]),
+    (Action::CorrelationLayout, "correlation_layout", &["c"]),
+    (Action::ToggleScale, "toggle_scale", &["l"]),
+    (Action::ZoomIn, "zoom_in", &["z"]),
+    (Action::ZoomOut, "zoom_out", &["Z"]),
+    (Action::ScrollBack, "scroll_back", &["left"]),
+    (Action::ScrollForward, "scroll_forward", &["right"]),
+    (Action::Oldest, "oldest", &["home"]),
+    (Action::Newest, "newest", &["end"]),
+    (Action::SortConnections, "sort_connections", &["o"]),
+    (Action::ReverseSort, "reverse_sort", &["O"]),
+    (Action::TraceConnection, "trace_connection", &["T"]),
+    (Action::LoadBaseline, "load_baseline", &["b"]),
+    (Action::ClearBaseline, "clear_baseline", &["B"]),
+    (Action::CycleTheme, "cycle_theme", &["t"]),
+    (Action::Inspect, "inspect", &["x"]),
+    (Action::Rebaseline, "rebaseline", &["L"]),
+    (Action::InjectLatencySpike, "inject_latency_spike", &["F1"]),
+    (Action::InjectLossBurst, "inject_loss_burst", &["F2"]),
+    (Action::InjectThroughputCollapse, "inject_throughput_collapse", &["F3"]),
+    (Action::InjectConnectionFailures, "inject_connection_failures", &["F4"]),
+    (Action::InjectErrorStorm, "inject_error_storm", &["F5"]),
+];
+
+/// Actions injecting each `FaultKind`, in `FaultKind::ALL` order
+const FAULT_ACTIONS: [Action; 5] = [
+    Action::InjectLatencySpike,
+    Action::InjectLossBurst,
+    Action::InjectThroughputCollapse,
+    Action::InjectConnectionFailures,
+    Action::InjectErrorStorm,
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(action, ..)| *action == self).map_or("", |(_, name, _)| name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTIONS.iter().find(|(_, n, _)| *n == name).map(|(action, ..)| *action)
+    }
+
+    /// The fault an `inject_*` action injects into the demo data
+    pub fn fault(self) -> Option<FaultKind> {
+        FAULT_ACTIONS.iter().position(|action| *action == self).map(|i| FaultKind::ALL[i])
+    }
+
+    fn default_keys(self) -> Vec<KeySpec> {
+        let (_, _, keys) = ACTIONS.iter().find(|(action, ..)| *action == self).expect("every action is listed");
+        keys.iter()
+            .map(|key| KeySpec::parse(key).expect("default keys parse"))
+            .collect()
+    }
+}
+
+/// One key with its modifiers, as written in the config file
+///
+/// `q`, `Q`, `ctrl-q`, `alt-x`, `shift-tab`, `F2`, `esc`, `pageup`, `space`.
+/// Letters are case-sensitive and Shift is implied by the case, so `shift-q`
+/// is `Q`; with Ctrl the case is ignored, as terminals do not send it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySpec {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec.trim();
+        // A trailing '-' is the minus key itself, as in `ctrl--`
+        while let Some((prefix, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier {:?}", prefix)),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (None, _) => return Err("no key given".to_string()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {:?}", rest)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Fold the ways terminals report the same key into one form
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        *self == Self::new(key.code, key.modifiers)
+    }
+
+    fn event(&self) -> KeyEvent {
+        KeyEvent::new(self.code, self.modifiers)
+    }
+}
+
+/// `q`, `Ctrl+Q`, `Esc`, `F2`, as shown in the help overlay
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, label) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(label)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// `[keybindings]` section of the config file: action name to one key or a list
+///
+/// ```toml
+/// [keybindings]
+/// quit = ["q", "ctrl-q"]
+/// toggle_network = "F7"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeybindingsConfig(pub BTreeMap<String, KeyList>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn specs(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// The keys each action is bound to
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    keys: HashMap<Action, Vec<KeySpec>>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: ACTIONS.iter().map(|(action, ..)| (*action, action.default_keys())).collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Apply `[keybindings]` over the defaults
+    ///
+    /// Unknown actions, keys that do not parse and keys that would reach two
+    /// actions in the same app are reported, one line per entry, and the
+    /// action keeps its default keys.
+    pub fn from_config(config: &KeybindingsConfig) -> (Self, Vec<String>) {
+        let mut map = Self::default();
+        let mut warnings = Vec::new();
+        let mut configured = Vec::new();
+
+        for (name, list) in &config.0 {
+            let entry = format!("[keybindings] {} = {}", name, list);
+            let Some(action) = Action::from_name(name) else {
+                warnings.push(format!("{}: unknown action", entry));
+                continue;
+            };
+            let parsed: Result<Vec<KeySpec>, String> = list.specs().iter().map(|key| KeySpec::parse(key)).collect();
+            match parsed {
+                Ok(keys) if keys.is_empty() => {
+                    warnings.push(format!("{}: no keys given, keeping {}", entry, map.label(action)));
+                }
+                Ok(keys) => {
+                    map.keys.insert(action, keys);
+                    configured.push((action, entry));
+                }
+                Err(e) => warnings.push(format!("{}: {}, keeping {}", entry, e, map.label(action))),
+            }
+        }
+
+        // Reverting a configured action can clash with another, so repeat until clean
+        while let Some((key, actions, app)) = map.first_conflict(&configured) {
+            let clashing: Vec<&str> = actions.iter().map(|action| action.name()).collect();
+            configured.retain(|(action, entry)| {
+                if !actions.contains(action) {
+                    return true;
+                }
+                warnings.push(format!(
+                    "{}: {} is bound to both {} in {}, keeping the default {}",
+                    entry,
+                    key,
+                    clashing.join(" and "),
+                    app.command(),
+                    label(&action.default_keys())
+                ));
+                false
+            });
+            for action in actions {
+                map.keys.insert(action, action.default_keys());
+            }
+        }
+        (map, warnings)
+    }
+
+    /// A key reaching two actions of one app, where at least one was configured
+    fn first_conflict(&self, configured: &[(Action, String)]) -> Option<(KeySpec, Vec<Action>, AppKind)> {
+        for app in AppKind::ALL {
+            let mut owners: HashMap<KeySpec, Vec<Action>> = HashMap::new();
+            for action in actions_for(app) {
+                for key in &self.keys[&action] {
+                    let owner = owners.entry(*key).or_default();
+                    if !owner.contains(&action) {
+                        owner.push(action);
+                    }
+                }
+            }
+            let conflict = owners.into_iter().find(|(_, actions)| {
+                actions.len() > 1 && actions.iter().any(|action| configured.iter().any(|(c, _)| c == action))
+            });
+            if let Some((key, actions)) = conflict {
+                return Some((key, actions, app));
+            }
+        }
+        None
+    }
+
+    /// The action `key` triggers in `app`, if any
+    ///
+    /// Where two default bindings share a key, the earlier table row has it.
+    pub fn action(&self, app: AppKind, key: &KeyEvent) -> Option<Action> {
+        actions_for(app)
+            .into_iter()
+            .find(|action| self.keys[action].iter().any(|spec| spec.matches(key)))
+    }
+
+    /// All keys of `action`, as `q / Esc`
+    pub fn label(&self, action: Action) -> String {
+        label(&self.keys[&action])
+    }
+
+    /// Keys of `action` that reach it in `app`, not taken by an earlier row
+    fn app_label(&self, app: AppKind, action: Action) -> String {
+        let keys: Vec<KeySpec> = self.keys[&action]
+            .iter()
+            .filter(|key| self.action(app, &key.event()) == Some(action))
+            .copied()
+            .collect();
+        label(&keys)
+    }
+
+    /// The first key of `action`, for footers
+    pub fn key(&self, action: Action) -> String {
+        self.keys[&action].first().map_or_else(String::new, KeySpec::to_string)
+    }
+
+    /// Help rows of `app` as (keys, description), in table order
+    pub fn help_rows(&self, app: AppKind) -> Vec<(String, &'static str)> {
+        bindings_for(app)
+            .map(|binding| {
+                let keys = match binding.keys {
+                    Keys::Actions(actions) => {
+                        actions.iter().map(|action| self.app_label(app, *action)).collect::<Vec<_>>().join(" / ")
+                    }
+                    Keys::Fixed(keys) => keys.to_string(),
+                };
+                (keys, binding.description)
+            })
+            .collect()
+    }
+}
+
+fn label(keys: &[KeySpec]) -> String {
+    keys.iter().map(KeySpec::to_string).collect::<Vec<_>>().join(" / ")
+}
+
+/// `"q"` or `["q", "ctrl-q"]`, as written in the config file
+impl fmt::Display for KeyList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyList::One(key) => write!(f, "{:?}", key),
+            KeyList::Many(keys) => write!(f, "{:?}", keys),
+        }
+    }
+}
+
+/// Keys of a help row: configurable actions, or keys fixed to a view's context
+#[derive(Debug, Clone, Copy)]
+pub enum Keys {
+    Actions(&'static [Action]),
+    Fixed(&'static str),
+}
+
+/// One row of the keybinding table
+#[derive(Debug, Clone, Copy)]
+pub struct KeyBinding {
+    pub keys: Keys,
+    pub description: &'static str,
+    pub apps: &'static [AppKind],
+}
+
+use AppKind::{Basic, Demo, Enhanced, Professional, Real, Simple, Ultimate};
+use Keys::{Actions, Fixed};
+
+const ALL: &[AppKind] = &[Basic, Real, Ultimate, Enhanced, Demo, Professional, Simple];
+/// Apps reading live or analyzed data, as opposed to the `demo` styles
+const MONITORS: &[AppKind] = &[Basic, Real, Ultimate, Enhanced];
+const DEMOS: &[AppKind] = &[Demo, Professional, Simple];
+const TIME_GRAPHS: &[AppKind] = &[Professional, Simple];
+const DASHBOARDS: &[AppKind] = &[Real, Ultimate];
+const ANALYTICS: &[AppKind] = &[Real, Ultimate, Enhanced];
+
+/// All keybindings, in the order they are listed in the help overlay
+pub const KEYBINDINGS: &[KeyBinding] = &[
+    KeyBinding { keys: Actions(&[Action::Quit]), description: "Quit", apps: ALL },
+    KeyBinding { keys: Actions(&[Action::Help]), description: "Toggle this help", apps: ALL },
+    KeyBinding { keys: Actions(&[Action::Reset]), description: "Refresh metrics", apps: &[Basic] },
+    KeyBinding { keys: Actions(&[Action::Reset]), description: "Reset all data", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::Reset]), description: "Reset the demo data", apps: DEMOS },
+    KeyBinding { keys: Actions(&[Action::Pause]), description: "Pause/resume updates", apps: MONITORS },
+    KeyBinding { keys: Actions(&[Action::CycleTheme]), description: "Cycle color themes", apps: ALL },
+    KeyBinding { keys: Actions(&[Action::Export]), description: "Export history to CSV/JSON", apps: &[Basic, Real] },
+    KeyBinding { keys: Actions(&[Action::Report]), description: "Write a session summary report", apps: &[Basic, Real] },
+    KeyBinding { keys: Actions(&[Action::ToggleRedaction]), description: "Redact the next exports and reports, or stop", apps: &[Basic, Real] },
+    KeyBinding { keys: Actions(&[Action::ToggleHistograms]), description: "Latency/handshake histograms instead of sparklines", apps: &[Basic] },
+    KeyBinding { keys: Actions(&[Action::CycleSource]), description: "Cycle sources: each labelled sender, then all", apps: &[Basic] },
+    KeyBinding { keys: Actions(&[Action::ToggleOverlay]), description: "Latency of every source on one chart", apps: &[Basic] },
+    KeyBinding { keys: Actions(&[Action::ViewDashboard]), description: "Dashboard view", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ViewAnalytics]), description: "Analytics view", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ViewNetwork]), description: "Network simulation view", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ViewSecurity]), description: "Security testing view", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ViewCloud]), description: "Cloud deployment view", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ViewBbrv3]), description: "BBRv3 congestion control view", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::ViewGraphs]), description: "Basic graphs view", apps: &[Enhanced] },
+    KeyBinding { keys: Actions(&[Action::ViewHeatmap]), description: "Performance heatmap view", apps: &[Enhanced] },
+    KeyBinding { keys: Actions(&[Action::ViewCorrelation]), description: "Correlation analysis view", apps: &[Enhanced] },
+    KeyBinding { keys: Actions(&[Action::ViewAnomalies]), description: "Anomaly detection view", apps: &[Enhanced] },
+    KeyBinding { keys: Actions(&[Action::ViewAll]), description: "All views", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::NextView, Action::PrevView]), description: "Next/previous view preset, [[views]] included", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::ViewPicker]), description: "Pick a view preset from a list (Enter switches)", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::FocusNext, Action::FocusPrev]), description: "Focus next/previous widget of the view", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::Expand]), description: "Expand the focused widget / restore the grid", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::FocusNext, Action::FocusPrev]), description: "Select the other graph", apps: &[Professional] },
+    KeyBinding { keys: Actions(&[Action::Expand]), description: "Expand the selected graph / show both", apps: &[Professional] },
+    KeyBinding { keys: Fixed("+ / -"), description: "Widen/narrow the time window by 10s", apps: &[Professional] },
+    KeyBinding { keys: Actions(&[Action::ToggleNetwork]), description: "Toggle network simulation", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::NextPreset, Action::PrevPreset]), description: "Change network profile", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ToggleSecurity]), description: "Run the [security] checks against the target", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::ToggleSecurity]), description: "Toggle security testing", apps: &[Ultimate] },
+    KeyBinding { keys: Actions(&[Action::ToggleCloud]), description: "Toggle cloud deployment", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::ScaleInstances]), description: "Scale cloud instances", apps: DASHBOARDS },
+    KeyBinding { keys: Actions(&[Action::CorrelationMethod]), description: "Correlation method (Pearson/Spearman)", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::CorrelationLayout]), description: "Correlation matrix / top pairs / by size", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("Arrows, PgUp/PgDn"), description: "Select correlation pair, scrolling the matrix (correlation views)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("Enter"), description: "Scatter plot and lag profile of pair (Esc returns)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("+ / -"), description: "Grow/shrink the correlation window (correlation widget focused)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("Up/Down, PgUp/PgDn"), description: "Select an anomaly (anomaly widget focused)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("c / m"), description: "Critical anomalies only / cycle the metric filter (anomaly widget focused)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("Enter / Del"), description: "Acknowledge the anomaly / drop acknowledged ones (anomaly widget focused)", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::Rebaseline]), description: "Learn the anomaly baselines again, keeping the anomalies", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::Inspect]), description: "Inspect heatmap cells (heatmap views; Esc leaves)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("Arrows"), description: "Move the heatmap cursor (inspect mode)", apps: ANALYTICS },
+    KeyBinding { keys: Fixed("[ / ]"), description: "Previous/next heatmap cell above its row's p95 (inspect mode)", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::ToggleScale]), description: "Linear/log value axis in time graphs", apps: &[Real, Ultimate, Enhanced, Professional, Simple] },
+    KeyBinding { keys: Actions(&[Action::ZoomIn, Action::ZoomOut]), description: "Zoom time graphs in/out", apps: &[Real, Ultimate, Enhanced, Simple] },
+    KeyBinding { keys: Actions(&[Action::ScrollBack, Action::ScrollForward]), description: "Scroll time graphs back/forward (other views)", apps: ANALYTICS },
+    KeyBinding { keys: Actions(&[Action::ScrollBack, Action::ScrollForward]), description: "Scroll time graphs back/forward", apps: TIME_GRAPHS },
+    KeyBinding { keys: Actions(&[Action::Oldest, Action::Newest]), description: "Jump to oldest history / back to live", apps: &[Real, Ultimate, Enhanced, Professional, Simple] },
+    KeyBinding { keys: Actions(&FAULT_ACTIONS), description: "Inject a latency spike / loss burst / throughput collapse / connection failures / error storm", apps: &[Ultimate, Enhanced, Demo, Professional, Simple] },
+    KeyBinding { keys: Actions(&[Action::SortConnections, Action::ReverseSort]), description: "Sort connection table / reverse", apps: &[Real] },
+    KeyBinding { keys: Fixed("Up/Down"), description: "Select a connection (network view)", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::TraceConnection]), description: "Pin the selected connection and trace it to a file / unpin (network view)", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::LoadBaseline, Action::ClearBaseline]), description: "Compare with the newest export / stop comparing", apps: &[Real] },
+    KeyBinding { keys: Actions(&[Action::ScrollBack, Action::ScrollForward]), description: "Seek replay back/forward 10s (--replay)", apps: &[Basic] },
+    KeyBinding { keys: Actions(&[Action::Oldest, Action::Newest]), description: "Jump to replay start/end (--replay)", apps: &[Basic] },
+];
+
+/// Bindings handled by `app`, in table order
+pub fn bindings_for(app: AppKind) -> impl Iterator<Item = &'static KeyBinding> {
+    KEYBINDINGS.iter().filter(move |b| b.apps.contains(&app))
+}
+
+/// Actions handled by `app`, in table order
+fn actions_for(app: AppKind) -> Vec<Action> {
+    let mut actions = Vec::new();
+    for binding in bindings_for(app) {
+        if let Keys::Actions(row) = binding.keys {
+            for action in row {
+                if !actions.contains(action) {
+                    actions.push(*action);
+                }
+            }
+        }
+    }
+    actions
+}
+
+/// Draw the help overlay centered over the whole frame
+pub fn render_help_overlay(f: &mut Frame, app: AppKind, keymap: &KeyMap, theme: &Theme) {
+    let rows = keymap.help_rows(app);
+    let key_width = rows.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}  ", keys, width = key_width),
+                    theme.title(theme.accent),
+                ),
+                Span::raw(description),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  Press any key to close",
+        Style::default().fg(theme.muted),
+    )));
+
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let area = centered_rect(content_width + 4, lines.len() as u16 + 2, f.area());
+
+    let help = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Help")
+            .style(Style::default().fg(theme.text)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(help, area);
+}
+
+/// A `width` x `height` rect centered in `outer`, shrunk to fit
+pub(crate) fn centered_rect(width: u16, height: u16, outer: Rect) -> Rect {
+    let width = width.min(outer.width);
+    let height = height.min(outer.height);
+    Rect {
+        x: outer.x + (outer.width - width) / 2,
+        y: outer.y + (outer.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(spec: &str) -> KeyEvent {
+        KeySpec::parse(spec).unwrap().event()
+    }
+
+    #[test]
+    fn demo_styles_resolve_their_keys() {
+        let keymap = KeyMap::default();
+        assert_eq!(keymap.action(AppKind::Demo, &key("h")), Some(Action::Help));
+        assert_eq!(keymap.action(AppKind::Demo, &key("l")), None);
+        assert_eq!(keymap.action(AppKind::Professional, &key("tab")), Some(Action::FocusNext));
+        assert_eq!(keymap.action(AppKind::Professional, &key("e")), Some(Action::Expand));
+        assert_eq!(keymap.action(AppKind::Simple, &key("Z")), Some(Action::ZoomOut));
+        assert_eq!(
+            keymap.action(AppKind::Simple, &key("F2")).and_then(Action::fault),
+            Some(FaultKind::LossBurst)
+        );
+    }
+
+    #[test]
+    fn configured_keys_reach_the_demo_styles() {
+        let config: KeybindingsConfig = toml::from_str("help = \"F6\"\ncycle_theme = [\"t\", \"ctrl-t\"]").unwrap();
+        let (keymap, warnings) = KeyMap::from_config(&config);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(keymap.action(AppKind::Professional, &key("F6")), Some(Action::Help));
+        assert_eq!(keymap.action(AppKind::Professional, &key("h")), None);
+        assert_eq!(keymap.action(AppKind::Simple, &key("ctrl-t")), Some(Action::CycleTheme));
+        assert!(keymap.help_rows(AppKind::Demo).contains(&("F6".to_string(), "Toggle this help")));
+    }
+
+    #[test]
+    fn clashing_keys_keep_the_defaults() {
+        let config: KeybindingsConfig = toml::from_str("zoom_in = \"l\"").unwrap();
+        let (keymap, warnings) = KeyMap::from_config(&config);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("toggle_scale and zoom_in"), "{}", warnings[0]);
+        assert_eq!(keymap.action(AppKind::Simple, &key("z")), Some(Action::ZoomIn));
+    }
+}
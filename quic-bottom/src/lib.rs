@@ -6,15 +6,51 @@
 pub mod app;
 pub mod widgets;
 pub mod metrics;
+pub mod units;
+pub mod network_sim;
 pub mod bridge;
+pub mod auth;
+pub mod cors;
+pub mod health;
+pub mod health_score;
+pub mod tls;
+pub mod security;
+pub mod ingest;
 pub mod config;
+pub mod config_watch;
 pub mod demo_data;
 pub mod improved_layout;
-// pub mod professional_graphs; // Temporarily disabled due to compilation errors
+pub mod axis_scale;
+pub mod professional_graphs;
 pub mod simple_professional;
+pub mod downsample;
+pub mod tdigest;
+pub mod time_view;
 pub mod heatmap_widget;
 pub mod correlation_widget;
+pub mod scatter_plot;
 pub mod anomaly_detection;
+pub mod alerting;
+pub mod loadgen;
+pub mod redact;
+pub mod replay;
+pub mod trace;
+pub mod scenario;
+pub mod export;
+pub mod baseline;
+pub mod report;
+pub mod sla;
+pub mod exporters;
+pub mod ffi;
+pub mod shutdown;
+pub mod sync;
+pub mod status;
+pub mod headless;
+pub mod keybindings;
+pub mod mouse;
+pub mod focus;
+pub mod views;
+pub mod theme;
 
 // Re-export key types
 pub use metrics::QUICMetrics;
@@ -40,56 +76,11 @@ pub fn start_quic_bottom(interval_ms: u64) -> Result<()> {
     })
 }
 
-/// FFI function to update QUIC metrics from Go
-#[no_mangle]
-pub extern "C" fn update_quic_metrics(
-    latency: f64,
-    throughput: f64,
-    connections: i32,
-    errors: i32,
-    packet_loss: f64,
-    retransmits: i32,
-) -> i32 {
-    log::debug!(
-        "Updating QUIC metrics: latency={}, throughput={}, connections={}, errors={}, loss={}, retransmits={}",
-        latency, throughput, connections, errors, packet_loss, retransmits
-    );
-    
-    // Update global metrics state
-    if let Err(e) = metrics::update_metrics(metrics::QUICMetrics {
-        latency,
-        throughput,
-        connections,
-        errors,
-        packet_loss,
-        retransmits,
-        timestamp: chrono::Utc::now(),
-    }) {
-        log::error!("Failed to update metrics: {}", e);
-        return -1;
-    }
-    
-    0
-}
-
-/// FFI function to get current metrics
-#[no_mangle]
-pub extern "C" fn get_quic_metrics() -> *mut metrics::QUICMetrics {
-    match metrics::get_current_metrics() {
-        Some(metrics) => {
-            let boxed = Box::new(metrics);
-            Box::into_raw(boxed)
-        }
-        None => std::ptr::null_mut(),
-    }
-}
-
-/// FFI function to free metrics memory
-#[no_mangle]
-pub extern "C" fn free_quic_metrics(ptr: *mut metrics::QUICMetrics) {
-    if !ptr.is_null() {
-        unsafe {
-            let _ = Box::from_raw(ptr);
-        }
-    }
+/// Serve the API and run the analytics without a TUI until Ctrl+C
+///
+/// Needs no TTY, so it suits systemd units and containers; see `headless::run`.
+pub fn start_quic_bottom_headless(config: QuicBottomConfig) -> Result<()> {
+    let rt = Runtime::new()?;
+    let interval_ms = config.update_interval;
+    rt.block_on(headless::run(config, interval_ms))
 }
@@ -0,0 +1,483 @@
+//! Synthetic load generator for the ingestion API
+//!
+//! Sends realistic metrics from `DemoDataGenerator` to a running quic-bottom
+//! instance so the ingest path can be benchmarked without the Go harness.
+//! The URL scheme picks the transport:
+//!
+//! - `http://host:port/metrics`: one JSON POST per sample
+//! - `unix:///path/to.sock`: newline-delimited JSON over the Unix socket
+//! - `udp://host:port`: statsd gauges under the default `quic.*` names,
+//!   throughput in bits per second and packet loss as a fraction, as the
+//!   listener reads them with the default `[ingest]` units
+//!
+//! Sends are scheduled from the time since the start, so a slow request
+//! doesn't lower the rate and nothing is sent after the run ends.
+
+use anyhow::{anyhow, Context, Result};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Method, Request};
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use tokio::io::AsyncWriteExt;
+use tokio::net::UdpSocket;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+
+use crate::bridge::{MetricsRequest, MetricsResponse};
+use crate::demo_data::{DemoDataGenerator, TrafficProfile};
+use crate::metrics::calculate_latency_percentiles;
+use crate::units::{LossUnit, Unit};
+
+/// Load generator settings
+#[derive(Debug, Clone)]
+pub struct LoadgenConfig {
+    /// Target URL, e.g. `http://127.0.0.1:8080/metrics`, `unix:///tmp/quic-bottom.sock` or `udp://127.0.0.1:8125`
+    pub url: String,
+
+    /// Number of concurrent sender tasks
+    pub concurrency: usize,
+
+    /// Target aggregate rate in samples per second
+    pub rate: f64,
+
+    /// Total run duration
+    pub duration: Duration,
+
+    /// Time to ramp linearly from zero to the target rate
+    pub ramp: Duration,
+
+    /// Bearer token for an instance with API authentication; HTTP only
+    pub token: Option<String>,
+
+    /// Seed of the first sender's samples, the next ones counting up from it; random when unset
+    pub seed: Option<u64>,
+
+    /// Shape of the generated traffic
+    pub profile: TrafficProfile,
+}
+
+/// Summary of a load generator run
+///
+/// Latencies are request round trips over HTTP and write times over the
+/// Unix socket and UDP, which have no response.
+#[derive(Debug, Clone)]
+pub struct LoadgenReport {
+    pub sent: usize,
+    pub errors: usize,
+    pub elapsed: Duration,
+    pub achieved_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl std::fmt::Display for LoadgenReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Sent: {} | Errors: {} | Elapsed: {:.1}s | Rate: {:.1}/s | P50: {:.2}ms | P95: {:.2}ms | P99: {:.2}ms",
+            self.sent,
+            self.errors,
+            self.elapsed.as_secs_f64(),
+            self.achieved_rate,
+            self.p50_ms,
+            self.p95_ms,
+            self.p99_ms
+        )
+    }
+}
+
+/// Where samples go, chosen by the URL scheme
+#[derive(Debug, Clone)]
+enum Target {
+    Http(hyper::Uri),
+    #[cfg(unix)]
+    Unix(PathBuf),
+    Statsd(SocketAddr),
+}
+
+impl Target {
+    async fn parse(url: &str) -> Result<Self> {
+        if let Some(path) = url.strip_prefix("unix://") {
+            #[cfg(unix)]
+            return match path {
+                "" => Err(anyhow!("invalid URL {}: missing socket path", url)),
+                path => Ok(Self::Unix(PathBuf::from(path))),
+            };
+            #[cfg(not(unix))]
+            return Err(anyhow!("invalid URL {}: Unix sockets are not supported here", url));
+        }
+        if let Some(host) = url.strip_prefix("udp://") {
+            let addr = tokio::net::lookup_host(host)
+                .await
+                .with_context(|| format!("invalid URL {}", url))?
+                .next()
+                .ok_or_else(|| anyhow!("invalid URL {}: {} did not resolve", url, host))?;
+            return Ok(Self::Statsd(addr));
+        }
+        let uri: hyper::Uri = url.parse().map_err(|e| anyhow!("invalid URL {}: {}", url, e))?;
+        match uri.scheme_str() {
+            Some("http") => Ok(Self::Http(uri)),
+            _ => Err(anyhow!("invalid URL {}: expected http://, unix:// or udp://", url)),
+        }
+    }
+}
+
+/// One task's connection to the target
+enum Sender {
+    Http {
+        client: Client<HttpConnector>,
+        uri: hyper::Uri,
+        token: Option<String>,
+    },
+    /// Connected on first use and again after a failed write
+    #[cfg(unix)]
+    Unix {
+        path: PathBuf,
+        stream: Option<UnixStream>,
+    },
+    Statsd(UdpSocket),
+}
+
+impl Sender {
+    async fn connect(target: &Target, token: Option<String>) -> Result<Self> {
+        match target {
+            Target::Http(uri) => Ok(Self::Http {
+                client: Client::new(),
+                uri: uri.clone(),
+                token,
+            }),
+            #[cfg(unix)]
+            Target::Unix(path) => Ok(Self::Unix {
+                path: path.clone(),
+                stream: None,
+            }),
+            Target::Statsd(addr) => {
+                let local: SocketAddr = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse()?;
+                let socket = UdpSocket::bind(local).await?;
+                socket.connect(addr).await?;
+                Ok(Self::Statsd(socket))
+            }
+        }
+    }
+
+    async fn send(&mut self, sample: &MetricsRequest) -> Result<()> {
+        match self {
+            Self::Http { client, uri, token } => post_sample(client, uri, token.as_deref(), sample).await,
+            #[cfg(unix)]
+            Self::Unix { path, stream } => {
+                let mut line = serde_json::to_vec(sample)?;
+                line.push(b'\n');
+                if stream.is_none() {
+                    let connected = UnixStream::connect(&*path)
+                        .await
+                        .with_context(|| format!("failed to connect to {}", path.display()))?;
+                    *stream = Some(connected);
+                }
+                let result = match stream.as_mut() {
+                    Some(stream) => stream.write_all(&line).await,
+                    None => Ok(()),
+                };
+                if result.is_err() {
+                    *stream = None;
+                }
+                Ok(result?)
+            }
+            Self::Statsd(socket) => {
+                socket.send(statsd_packet(sample).as_bytes()).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Per-task results collected at the end of the run
+struct WorkerResult {
+    latencies_ms: Vec<f64>,
+    errors: usize,
+}
+
+/// Run the load generator until the configured duration elapses
+pub async fn run_loadgen(config: LoadgenConfig) -> Result<LoadgenReport> {
+    if config.concurrency == 0 {
+        return Err(anyhow!("concurrency must be at least 1"));
+    }
+    if !(config.rate.is_finite() && config.rate > 0.0) {
+        return Err(anyhow!("rate must be a positive number, got {}", config.rate));
+    }
+    let target = Target::parse(&config.url).await?;
+
+    log::info!(
+        "Loadgen: {} tasks, {:.1} samples/s, {:?} duration, {:?} ramp -> {}",
+        config.concurrency,
+        config.rate,
+        config.duration,
+        config.ramp,
+        config.url
+    );
+
+    let per_task_rate = config.rate / config.concurrency as f64;
+    let mut senders = Vec::with_capacity(config.concurrency);
+    for _ in 0..config.concurrency {
+        senders.push(Sender::connect(&target, config.token.clone()).await?);
+    }
+
+    let start = Instant::now();
+    let mut handles = Vec::with_capacity(config.concurrency);
+    for (task, sender) in senders.into_iter().enumerate() {
+        let config = config.clone();
+        let generator = match config.seed {
+            Some(seed) => DemoDataGenerator::with_seed(seed.wrapping_add(task as u64)),
+            None => DemoDataGenerator::new(),
+        }
+        .with_profile(config.profile);
+        handles.push(tokio::spawn(async move {
+            run_worker(sender, generator, per_task_rate, config.duration, config.ramp, start).await
+        }));
+    }
+
+    let mut latencies_ms = Vec::new();
+    let mut errors = 0;
+    for handle in handles {
+        let result = handle.await?;
+        latencies_ms.extend(result.latencies_ms);
+        errors += result.errors;
+    }
+
+    let elapsed = start.elapsed();
+    let sent = latencies_ms.len() + errors;
+    let (p50_ms, p95_ms, p99_ms) = calculate_latency_percentiles(&latencies_ms);
+
+    Ok(LoadgenReport {
+        sent,
+        errors,
+        elapsed,
+        achieved_rate: sent as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        p50_ms,
+        p95_ms,
+        p99_ms,
+    })
+}
+
+async fn run_worker(
+    mut sender: Sender,
+    mut generator: DemoDataGenerator,
+    rate: f64,
+    duration: Duration,
+    ramp: Duration,
+    start: Instant,
+) -> WorkerResult {
+    let mut result = WorkerResult {
+        latencies_ms: Vec::new(),
+        errors: 0,
+    };
+
+    for n in 0.. {
+        let offset = send_offset(n, rate, ramp);
+        if offset >= duration {
+            break;
+        }
+        // Behind schedule this returns at once, catching up on the missed sends
+        tokio::time::sleep_until((start + offset).into()).await;
+        let send_started = Instant::now();
+
+        let (latency, throughput, handshake_time, packet_loss, retransmits) = generator.generate_next();
+        let sample = MetricsRequest {
+            latency,
+            throughput,
+            unit: Some(Unit::Bps),
+            loss_unit: Some(LossUnit::Fraction),
+            connections: 1,
+            errors: 0,
+            packet_loss,
+            retransmits,
+            rtt: latency,
+            handshake_time,
+            bbrv3: generator.bbrv3().clone(),
+            ..MetricsRequest::default()
+        };
+
+        match sender.send(&sample).await {
+            Ok(()) => result
+                .latencies_ms
+                .push(send_started.elapsed().as_secs_f64() * 1000.0),
+            Err(e) => {
+                log::debug!("Loadgen: send failed: {}", e);
+                result.errors += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// When the `n`th sample (from 0) is due, ramping linearly from zero to `rate` over `ramp`
+///
+/// Samples due by `t` are the integral of the rate: `rate * t^2 / (2 * ramp)`
+/// during the ramp, `rate * (t - ramp / 2)` after it.
+fn send_offset(n: u64, rate: f64, ramp: Duration) -> Duration {
+    let n = n as f64;
+    let ramp = ramp.as_secs_f64();
+    let ramp_samples = rate * ramp / 2.0;
+    let secs = if n < ramp_samples {
+        (2.0 * ramp * n / rate).sqrt()
+    } else {
+        n / rate + ramp / 2.0
+    };
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+}
+
+async fn post_sample(
+    client: &Client<HttpConnector>,
+    uri: &hyper::Uri,
+    token: Option<&str>,
+    sample: &MetricsRequest,
+) -> Result<()> {
+    let body = serde_json::to_vec(sample)?;
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(uri.clone())
+        .header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {}", token));
+    }
+    let request = request.body(Body::from(body))?;
+
+    let response = client.request(request).await?;
+    let status = response.status();
+    // Read the whole body, which also lets the connection be reused
+    let body = hyper::body::to_bytes(response.into_body()).await?;
+
+    if !status.is_success() {
+        return Err(anyhow!("server responded with {}", status));
+    }
+    // A rejected sample still gets 200, with the reason in the body
+    match serde_json::from_slice::<MetricsResponse>(&body) {
+        Ok(response) if response.status != "ok" => Err(anyhow!(
+            "sample rejected: {}",
+            response.message.unwrap_or_default()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `sample` as statsd gauges, one line per field
+fn statsd_packet(sample: &MetricsRequest) -> String {
+    format!(
+        "quic.latency:{}|g\nquic.throughput:{}|g\nquic.connections:{}|g\nquic.errors:{}|g\nquic.packet_loss:{}|g\nquic.retransmits:{}|g",
+        sample.latency, sample.throughput, sample.connections, sample.errors, sample.packet_loss, sample.retransmits
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::{create_api_routes, ApiConfig};
+    use crate::ingest::statsd::{StatsdConfig, StatsdListener};
+    use crate::metrics::MetricsHandle;
+
+    fn config(url: String) -> LoadgenConfig {
+        LoadgenConfig {
+            url,
+            concurrency: 2,
+            rate: 200.0,
+            duration: Duration::from_millis(400),
+            ramp: Duration::from_millis(200),
+            token: None,
+            seed: Some(7),
+            profile: TrafficProfile::default(),
+        }
+    }
+
+    /// Wait for the listener to store `count` samples
+    async fn wait_for_samples(metrics: &MetricsHandle, count: u64) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while metrics.history_mark() < count && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[test]
+    fn schedules_the_ramp_from_elapsed_time() {
+        let rate = 100.0;
+        let ramp = Duration::from_secs(10);
+        assert_eq!(send_offset(0, rate, Duration::ZERO), Duration::ZERO);
+        assert_eq!(send_offset(50, rate, Duration::ZERO), Duration::from_millis(500));
+        // The first sends of a ramp come within a second, not after 1 / (rate * tiny)
+        assert!(send_offset(1, rate, ramp) < Duration::from_secs(1));
+        // 500 samples fill the ramp, after which they come at the full rate
+        assert!((send_offset(500, rate, ramp).as_secs_f64() - 10.0).abs() < 1e-9);
+        assert!((send_offset(600, rate, ramp).as_secs_f64() - 11.0).abs() < 1e-9);
+        let offsets: Vec<Duration> = (0..1000).map(|n| send_offset(n, rate, ramp)).collect();
+        assert!(offsets.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[tokio::test]
+    async fn http_samples_all_pass_validation() {
+        let metrics = MetricsHandle::new();
+        let routes = create_api_routes(&ApiConfig::default(), metrics.clone()).unwrap();
+        let (addr, server) = crate::tls::bind(routes, ([127, 0, 0, 1], 0).into(), None).unwrap();
+        tokio::spawn(server);
+
+        let report = run_loadgen(config(format!("http://{}/metrics", addr))).await.unwrap();
+        assert_eq!(report.errors, 0);
+        assert!(report.sent > 0);
+        assert!(report.elapsed < Duration::from_secs(2));
+        assert_eq!(metrics.history_mark(), report.sent as u64);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn uds_samples_all_pass_validation() {
+        use crate::ingest::uds::UdsListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let path = std::env::temp_dir().join(format!("quic-bottom-loadgen-{}.sock", std::process::id()));
+        let uds = UdsListener::bind(&path).unwrap();
+        let metrics = MetricsHandle::new();
+        let rejected = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(uds.serve({
+            let metrics = metrics.clone();
+            let rejected = Arc::clone(&rejected);
+            move |req: MetricsRequest| {
+                if let Err(e) = req.validate() {
+                    rejected.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+                metrics.update(req.to_metrics(chrono::Utc::now()))
+            }
+        }));
+
+        let report = run_loadgen(config(format!("unix://{}", path.display()))).await.unwrap();
+        assert_eq!(report.errors, 0);
+        assert!(report.sent > 0);
+        wait_for_samples(&metrics, report.sent as u64).await;
+        assert_eq!(rejected.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.history_mark(), report.sent as u64);
+    }
+
+    #[tokio::test]
+    async fn statsd_gauges_map_onto_samples() {
+        let statsd = StatsdConfig {
+            port: 0,
+            ..StatsdConfig::default()
+        };
+        let listener = StatsdListener::bind(&statsd).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let metrics = MetricsHandle::new();
+        tokio::spawn(listener.serve(metrics.clone(), Duration::from_millis(20)));
+
+        let report = run_loadgen(config(format!("udp://{}", addr))).await.unwrap();
+        assert_eq!(report.errors, 0);
+        wait_for_samples(&metrics, 1).await;
+        let current = metrics.current();
+        assert!(current.latency > 0.0);
+        assert!(current.throughput > 0.0);
+        assert_eq!(current.connections, 1);
+        assert!(current.packet_loss <= 1.0);
+    }
+}
@@ -1,20 +1,721 @@
 //! QUIC metrics handling and data structures
 
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 
+use crate::anomaly_detection::AnomalySeverity;
+use crate::health_score::{HealthConfig, HealthInputs, HealthScore};
+use crate::ingest::{ensure_non_negative, MAX_MEASUREMENT};
+use crate::sla::{SlaConfig, SlaVerdict};
+use crate::status::record_samples;
+use crate::sync::{MutexExt, RwLockExt};
+use crate::tdigest::TDigest;
+use crate::trace::ConnectionTrace;
+
 /// QUIC-specific metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Fields after `retransmits` were added later; samples without them
+/// deserialize with zeros, which the widgets treat as not reported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QUICMetrics {
     pub latency: f64,
     pub throughput: f64,
     pub connections: i32,
     pub errors: i32,
+    /// Fraction of packets lost, 0.0-1.0
     pub packet_loss: f64,
     pub retransmits: i32,
     pub timestamp: DateTime<Utc>,
+
+    /// Smoothed round-trip time in ms
+    #[serde(default)]
+    pub rtt: f64,
+    /// RTT variation in ms
+    #[serde(default)]
+    pub jitter: f64,
+    /// Congestion window in bytes
+    #[serde(default)]
+    pub congestion_window: i32,
+    #[serde(default)]
+    pub bytes_sent: i64,
+    #[serde(default)]
+    pub bytes_received: i64,
+    /// Open streams
+    #[serde(default)]
+    pub streams: i32,
+    /// Handshake duration in ms
+    #[serde(default)]
+    pub handshake_time: f64,
+    /// Whether the handshake was 0-RTT or a full 1-RTT one, when the sender says
+    #[serde(default)]
+    pub handshake_type: Option<HandshakeType>,
+    /// The connection resumed an earlier TLS session
+    #[serde(default)]
+    pub session_resumed: bool,
+
+    /// Cumulative packet counters, when the sender reports them
+    #[serde(default)]
+    pub packets_sent: Option<u64>,
+    #[serde(default)]
+    pub packets_received: Option<u64>,
+    #[serde(default)]
+    pub packets_lost: Option<u64>,
+    #[serde(default)]
+    pub packets_acked: Option<u64>,
+
+    /// Cumulative connection counters, when the sender reports them:
+    /// attempts started, attempts that never completed the handshake, and
+    /// established connections that have since closed
+    #[serde(default)]
+    pub connections_opened: Option<u64>,
+    #[serde(default)]
+    pub connections_failed: Option<u64>,
+    #[serde(default)]
+    pub connections_closed: Option<u64>,
+
+    /// Label of the sender, e.g. `client` or `server`, when several report at once
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Congestion control state when the sender runs BBRv3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbrv3: Option<BBRv3Metrics>,
+}
+
+/// How a handshake completed, as `"0rtt"` or `"1rtt"` on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandshakeType {
+    #[serde(rename = "0rtt")]
+    ZeroRtt,
+    #[serde(rename = "1rtt")]
+    OneRtt,
+}
+
+impl HandshakeType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ZeroRtt => "0rtt",
+            Self::OneRtt => "1rtt",
+        }
+    }
+}
+
+/// Handshakes seen so far, split by type
+///
+/// Every sample with a `handshake_time` counts as one handshake; without a
+/// `handshake_type` it is taken to be a full 1-RTT one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct HandshakeStats {
+    pub zero_rtt: u64,
+    pub one_rtt: u64,
+    /// Handshakes that resumed a session, of either type
+    pub resumed: u64,
+    zero_rtt_ms_total: f64,
+    one_rtt_ms_total: f64,
+}
+
+impl HandshakeStats {
+    pub fn record(&mut self, metrics: &QUICMetrics) {
+        if !(metrics.handshake_time > 0.0 && metrics.handshake_time.is_finite()) {
+            return;
+        }
+        match metrics.handshake_type.unwrap_or(HandshakeType::OneRtt) {
+            HandshakeType::ZeroRtt => {
+                self.zero_rtt += 1;
+                self.zero_rtt_ms_total += metrics.handshake_time;
+            }
+            HandshakeType::OneRtt => {
+                self.one_rtt += 1;
+                self.one_rtt_ms_total += metrics.handshake_time;
+            }
+        }
+        if metrics.session_resumed {
+            self.resumed += 1;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.zero_rtt + self.one_rtt
+    }
+
+    /// Share of handshakes that were 0-RTT, `None` before the first one
+    pub fn zero_rtt_ratio(&self) -> Option<f64> {
+        (self.total() > 0).then(|| self.zero_rtt as f64 / self.total() as f64)
+    }
+
+    pub fn avg_zero_rtt_ms(&self) -> Option<f64> {
+        (self.zero_rtt > 0).then(|| self.zero_rtt_ms_total / self.zero_rtt as f64)
+    }
+
+    pub fn avg_one_rtt_ms(&self) -> Option<f64> {
+        (self.one_rtt > 0).then(|| self.one_rtt_ms_total / self.one_rtt as f64)
+    }
+}
+
+/// Packets counted between two consecutive samples
+///
+/// A counter lower than in the previous sample is taken to have restarted
+/// from zero, so its delta is the new value rather than negative. Counters
+/// missing from either sample have no delta.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PacketDeltas {
+    /// Time between the two samples' timestamps
+    pub interval_secs: f64,
+    pub sent: Option<u64>,
+    pub received: Option<u64>,
+    pub lost: Option<u64>,
+    pub acked: Option<u64>,
+}
+
+impl PacketDeltas {
+    /// Deltas from `prev` to `current`; `None` unless `current` is later and
+    /// both report at least one counter
+    pub fn between(prev: &QUICMetrics, current: &QUICMetrics) -> Option<Self> {
+        let interval_secs = (current.timestamp - prev.timestamp).num_milliseconds() as f64 / 1000.0;
+        if interval_secs <= 0.0 {
+            return None;
+        }
+        let deltas = Self {
+            interval_secs,
+            sent: counter_delta(prev.packets_sent, current.packets_sent),
+            received: counter_delta(prev.packets_received, current.packets_received),
+            lost: counter_delta(prev.packets_lost, current.packets_lost),
+            acked: counter_delta(prev.packets_acked, current.packets_acked),
+        };
+        [deltas.sent, deltas.received, deltas.lost, deltas.acked]
+            .iter()
+            .any(Option::is_some)
+            .then_some(deltas)
+    }
+
+    /// Packets per second for one of the deltas
+    pub fn rate(&self, delta: Option<u64>) -> Option<f64> {
+        delta.map(|count| count as f64 / self.interval_secs)
+    }
+
+    /// Lost packets as a percentage of those sent in the interval
+    pub fn loss_pct(&self) -> Option<f64> {
+        match (self.lost, self.sent) {
+            (Some(lost), Some(sent)) if sent > 0 => Some((lost as f64 / sent as f64 * 100.0).min(100.0)),
+            _ => None,
+        }
+    }
+}
+
+fn counter_delta(prev: Option<u64>, current: Option<u64>) -> Option<u64> {
+    let (prev, current) = (prev?, current?);
+    Some(if current >= prev { current - prev } else { current })
+}
+
+/// Connection counters of one sender, as last reported
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ConnectionCounters {
+    opened: Option<u64>,
+    failed: Option<u64>,
+    closed: Option<u64>,
+}
+
+impl ConnectionCounters {
+    fn of(metrics: &QUICMetrics) -> Self {
+        Self {
+            opened: metrics.connections_opened,
+            failed: metrics.connections_failed,
+            closed: metrics.connections_closed,
+        }
+    }
+
+    fn is_reported(&self) -> bool {
+        self.opened.is_some() || self.failed.is_some() || self.closed.is_some()
+    }
+
+    fn active(&self) -> Option<u64> {
+        Some(self.opened?.saturating_sub(self.failed?).saturating_sub(self.closed?))
+    }
+}
+
+/// Connection lifecycle built from the `connections_*` counters
+///
+/// Counters are followed per sender, so interleaved senders do not subtract
+/// from each other, and one lower than before is taken to have restarted
+/// from zero, as in `PacketDeltas`. A sender's first sample only sets its
+/// baseline. Attempts and failures are kept by sample time so the success
+/// rate can cover a recent window rather than the whole run.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionLifecycle {
+    /// Last counters of each sender, by `source`
+    last: BTreeMap<Option<String>, ConnectionCounters>,
+    /// Attempts and failures between consecutive samples, by sample time
+    deltas: VecDeque<(DateTime<Utc>, u64, u64)>,
+}
+
+impl ConnectionLifecycle {
+    /// Take in a sample, forgetting deltas older than `window` before it
+    pub fn record(&mut self, metrics: &QUICMetrics, window: Duration) {
+        let counters = ConnectionCounters::of(metrics);
+        if !counters.is_reported() {
+            return;
+        }
+        if let Some(prev) = self.last.insert(metrics.source.clone(), counters) {
+            let opened = counter_delta(prev.opened, counters.opened).unwrap_or(0);
+            let failed = counter_delta(prev.failed, counters.failed).unwrap_or(0);
+            if opened > 0 || failed > 0 {
+                self.deltas.push_back((metrics.timestamp, opened, failed));
+            }
+        }
+
+        let Some(cutoff) = window_start(metrics.timestamp, window) else {
+            return;
+        };
+        while self.deltas.front().is_some_and(|(at, _, _)| *at < cutoff) {
+            self.deltas.pop_front();
+        }
+    }
+
+    /// Totals over all senders and the success rate over the last `window`
+    ///
+    /// The window ends at the newest delta, so a paused or replayed run
+    /// keeps its rate instead of draining to nothing.
+    pub fn stats(&self, window: Duration) -> ConnectionStats {
+        let sum = |field: fn(&ConnectionCounters) -> Option<u64>| {
+            self.last.values().filter_map(field).reduce(|a, b| a.saturating_add(b))
+        };
+        let active = sum(ConnectionCounters::active)
+            .filter(|_| self.last.values().all(|c| c.active().is_some()));
+
+        let start = self.deltas.back().and_then(|(newest, _, _)| window_start(*newest, window));
+        let (attempts, failures) = self
+            .deltas
+            .iter()
+            .filter(|(at, _, _)| start.is_some_and(|start| *at >= start))
+            .fold((0u64, 0u64), |(a, f), (_, opened, failed)| {
+                (a.saturating_add(*opened), f.saturating_add(*failed))
+            });
+
+        ConnectionStats {
+            opened: sum(|c| c.opened),
+            failed: sum(|c| c.failed),
+            closed: sum(|c| c.closed),
+            active,
+            success_rate: (attempts > 0).then(|| 1.0 - (failures as f64 / attempts as f64).min(1.0)),
+            window_attempts: attempts,
+            window_secs: window.as_secs(),
+        }
+    }
+
+}
+
+/// Start of the `window` ending at `end`; `None` when it reaches before chrono's range
+fn window_start(end: DateTime<Utc>, window: Duration) -> Option<DateTime<Utc>> {
+    end.checked_sub_signed(chrono::Duration::from_std(window).ok()?)
+}
+
+/// Connection counts and success rate, from `ConnectionLifecycle::stats`
+///
+/// Totals are the senders' own counters summed, so they count from when each
+/// sender started rather than from when monitoring did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ConnectionStats {
+    pub opened: Option<u64>,
+    pub failed: Option<u64>,
+    pub closed: Option<u64>,
+    /// Opened less failed and closed; `None` unless every sender reports all three
+    pub active: Option<u64>,
+    /// Share of the attempts in the window that did not fail, 0.0-1.0;
+    /// `None` when there were none
+    pub success_rate: Option<f64>,
+    /// Attempts the success rate covers
+    pub window_attempts: u64,
+    pub window_secs: u64,
+}
+
+impl ConnectionStats {
+    /// Whether any sender reports the lifecycle counters
+    pub fn is_reported(&self) -> bool {
+        self.opened.is_some() || self.failed.is_some() || self.closed.is_some()
+    }
+}
+
+/// BBRv3 congestion control state reported by the sender
+///
+/// Keys keep the `bbrv3_` prefix quic-test sends, so the same struct can be
+/// flattened into a flat sample or nested under `bbrv3`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BBRv3Metrics {
+    /// Startup, Drain, ProbeBW or ProbeRTT
+    #[serde(rename = "bbrv3_phase", skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    /// Fast-scale bandwidth estimate in bps
+    #[serde(rename = "bbrv3_bw_fast", skip_serializing_if = "Option::is_none")]
+    pub bw_fast: Option<f64>,
+    /// Slow-scale bandwidth estimate in bps
+    #[serde(rename = "bbrv3_bw_slow", skip_serializing_if = "Option::is_none")]
+    pub bw_slow: Option<f64>,
+    /// Loss rate in the last round, 0.0-1.0
+    #[serde(rename = "bbrv3_loss_rate_round", skip_serializing_if = "Option::is_none")]
+    pub loss_rate_round: Option<f64>,
+    /// Smoothed loss rate, 0.0-1.0
+    #[serde(rename = "bbrv3_loss_rate_ema", skip_serializing_if = "Option::is_none")]
+    pub loss_rate_ema: Option<f64>,
+    /// Loss rate BBRv3 reacts to, usually 0.02
+    #[serde(rename = "bbrv3_loss_threshold", skip_serializing_if = "Option::is_none")]
+    pub loss_threshold: Option<f64>,
+    /// Headroom usage, 0.0-1.0
+    #[serde(rename = "bbrv3_headroom_usage", skip_serializing_if = "Option::is_none")]
+    pub headroom_usage: Option<f64>,
+    /// Target inflight in bytes
+    #[serde(rename = "bbrv3_inflight_target", skip_serializing_if = "Option::is_none")]
+    pub inflight_target: Option<f64>,
+    /// Pacing quantum in bytes
+    #[serde(rename = "bbrv3_pacing_quantum", skip_serializing_if = "Option::is_none")]
+    pub pacing_quantum: Option<i64>,
+    #[serde(rename = "bbrv3_pacing_gain", skip_serializing_if = "Option::is_none")]
+    pub pacing_gain: Option<f64>,
+    #[serde(rename = "bbrv3_cwnd_gain", skip_serializing_if = "Option::is_none")]
+    pub cwnd_gain: Option<f64>,
+    /// Minimum RTT seen in ProbeRTT, in ms
+    #[serde(rename = "bbrv3_probe_rtt_min_ms", skip_serializing_if = "Option::is_none")]
+    pub probe_rtt_min_ms: Option<f64>,
+    /// avg_rtt / min_rtt - 1
+    #[serde(rename = "bbrv3_bufferbloat_factor", skip_serializing_if = "Option::is_none")]
+    pub bufferbloat_factor: Option<f64>,
+    /// Change in throughput over change in RTT
+    #[serde(rename = "bbrv3_stability_index", skip_serializing_if = "Option::is_none")]
+    pub stability_index: Option<f64>,
+    /// Time spent in each phase, in ms
+    #[serde(rename = "bbrv3_phase_duration_ms", skip_serializing_if = "Option::is_none")]
+    pub phase_duration_ms: Option<HashMap<String, f64>>,
+    /// Time to recover from loss, in ms
+    #[serde(rename = "bbrv3_recovery_time_ms", skip_serializing_if = "Option::is_none")]
+    pub recovery_time_ms: Option<f64>,
+    /// Recovered over lost packets
+    #[serde(rename = "bbrv3_loss_recovery_efficiency", skip_serializing_if = "Option::is_none")]
+    pub loss_recovery_efficiency: Option<f64>,
+}
+
+/// Keys of every `BBRv3Metrics` field in field order, the columns an export gives each sample
+pub const BBRV3_COLUMNS: [&str; 17] = [
+    "bbrv3_phase",
+    "bbrv3_bw_fast",
+    "bbrv3_bw_slow",
+    "bbrv3_loss_rate_round",
+    "bbrv3_loss_rate_ema",
+    "bbrv3_loss_threshold",
+    "bbrv3_headroom_usage",
+    "bbrv3_inflight_target",
+    "bbrv3_pacing_quantum",
+    "bbrv3_pacing_gain",
+    "bbrv3_cwnd_gain",
+    "bbrv3_probe_rtt_min_ms",
+    "bbrv3_bufferbloat_factor",
+    "bbrv3_stability_index",
+    "bbrv3_phase_duration_ms",
+    "bbrv3_recovery_time_ms",
+    "bbrv3_loss_recovery_efficiency",
+];
+
+impl BBRv3Metrics {
+    /// Whether the sender reported BBRv3 state; quic-test always sets the phase then
+    pub fn is_reported(&self) -> bool {
+        self.phase.is_some()
+    }
+
+    /// Reject values that are NaN, infinite or beyond `MAX_MEASUREMENT` either way; values not reported pass
+    pub fn ensure_finite(&self) -> Result<(), anyhow::Error> {
+        let values = [
+            ("bbrv3_bw_fast", self.bw_fast),
+            ("bbrv3_bw_slow", self.bw_slow),
+            ("bbrv3_loss_rate_round", self.loss_rate_round),
+            ("bbrv3_loss_rate_ema", self.loss_rate_ema),
+            ("bbrv3_loss_threshold", self.loss_threshold),
+            ("bbrv3_headroom_usage", self.headroom_usage),
+            ("bbrv3_inflight_target", self.inflight_target),
+            ("bbrv3_pacing_gain", self.pacing_gain),
+            ("bbrv3_cwnd_gain", self.cwnd_gain),
+            ("bbrv3_probe_rtt_min_ms", self.probe_rtt_min_ms),
+            ("bbrv3_bufferbloat_factor", self.bufferbloat_factor),
+            ("bbrv3_stability_index", self.stability_index),
+            ("bbrv3_recovery_time_ms", self.recovery_time_ms),
+            ("bbrv3_loss_recovery_efficiency", self.loss_recovery_efficiency),
+        ];
+        let durations = self.phase_duration_ms.iter().flatten().map(|(_, ms)| ("bbrv3_phase_duration_ms", Some(*ms)));
+        for (field, value) in values.into_iter().chain(durations) {
+            if let Some(value) = value.filter(|value| !value.is_finite() || value.abs() > MAX_MEASUREMENT) {
+                anyhow::bail!("{} must be a number within ±{:e}, got {:?}", field, MAX_MEASUREMENT, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Numeric fields as OpenTelemetry gauge names and values
+    pub fn gauges(&self) -> [(&'static str, Option<f64>); 15] {
+        [
+            ("quic.bbrv3.bw_fast", self.bw_fast),
+            ("quic.bbrv3.bw_slow", self.bw_slow),
+            ("quic.bbrv3.loss_rate_round", self.loss_rate_round),
+            ("quic.bbrv3.loss_rate_ema", self.loss_rate_ema),
+            ("quic.bbrv3.loss_threshold", self.loss_threshold),
+            ("quic.bbrv3.headroom_usage", self.headroom_usage),
+            ("quic.bbrv3.inflight_target", self.inflight_target),
+            ("quic.bbrv3.pacing_quantum", self.pacing_quantum.map(|q| q as f64)),
+            ("quic.bbrv3.pacing_gain", self.pacing_gain),
+            ("quic.bbrv3.cwnd_gain", self.cwnd_gain),
+            ("quic.bbrv3.probe_rtt_min_ms", self.probe_rtt_min_ms),
+            ("quic.bbrv3.bufferbloat_factor", self.bufferbloat_factor),
+            ("quic.bbrv3.stability_index", self.stability_index),
+            ("quic.bbrv3.recovery_time_ms", self.recovery_time_ms),
+            ("quic.bbrv3.loss_recovery_efficiency", self.loss_recovery_efficiency),
+        ]
+    }
+}
+
+/// Recent BBRv3 values for graphs, oldest first; a missing value is stored as NaN
+#[derive(Debug, Clone)]
+pub struct BBRv3TimeSeries {
+    /// Bandwidth estimates in bps
+    pub bw_fast: VecDeque<f64>,
+    pub bw_slow: VecDeque<f64>,
+    pub loss_rate_ema: VecDeque<f64>,
+    pub pacing_gain: VecDeque<f64>,
+    pub cwnd_gain: VecDeque<f64>,
+    pub bufferbloat_factor: VecDeque<f64>,
+    pub max_points: usize,
+}
+
+impl BBRv3TimeSeries {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            bw_fast: VecDeque::with_capacity(max_points),
+            bw_slow: VecDeque::with_capacity(max_points),
+            loss_rate_ema: VecDeque::with_capacity(max_points),
+            pacing_gain: VecDeque::with_capacity(max_points),
+            cwnd_gain: VecDeque::with_capacity(max_points),
+            bufferbloat_factor: VecDeque::with_capacity(max_points),
+            max_points,
+        }
+    }
+
+    pub fn add_data_point(&mut self, metrics: &BBRv3Metrics) {
+        let max = self.max_points;
+        let value = |v: Option<f64>| v.unwrap_or(f64::NAN);
+        push_bounded(&mut self.bw_fast, value(metrics.bw_fast), max);
+        push_bounded(&mut self.bw_slow, value(metrics.bw_slow), max);
+        push_bounded(&mut self.loss_rate_ema, value(metrics.loss_rate_ema), max);
+        push_bounded(&mut self.pacing_gain, value(metrics.pacing_gain), max);
+        push_bounded(&mut self.cwnd_gain, value(metrics.cwnd_gain), max);
+        push_bounded(&mut self.bufferbloat_factor, value(metrics.bufferbloat_factor), max);
+    }
+
+    /// Samples stored
+    pub fn len(&self) -> usize {
+        self.bw_fast.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bw_fast.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.max_points);
+    }
+}
+
+/// Which parts of the collected state a reset clears
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResetScope {
+    /// Graph, heatmap and correlation statistics
+    pub stats: bool,
+
+    /// Stored metrics history
+    pub history: bool,
+
+    /// Detected anomalies and detector baselines
+    pub anomalies: bool,
+
+    /// Whether the samples after the reset belong to a new segment
+    pub segments: SegmentReset,
+}
+
+impl ResetScope {
+    /// Clear everything, as the 'r' key does
+    pub fn full() -> Self {
+        Self {
+            stats: true,
+            history: true,
+            anomalies: true,
+            segments: SegmentReset::Keep,
+        }
+    }
+}
+
+impl Default for ResetScope {
+    fn default() -> Self {
+        Self {
+            stats: true,
+            history: false,
+            anomalies: false,
+            segments: SegmentReset::Keep,
+        }
+    }
+}
+
+/// `"segments"` of a `ResetScope`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentReset {
+    /// Stay in the current segment
+    #[default]
+    Keep,
+    /// Start the next one, e.g. for the next test iteration
+    New,
+}
+
+/// What a reset cleared, as the reset endpoints report it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ResetOutcome {
+    pub scope: ResetScope,
+    /// Samples removed from the history
+    pub history_dropped: usize,
+    /// Segment the samples after the reset belong to, starting at 1
+    pub segment: u64,
+}
+
+/// Samples that carry their own capture time
+pub trait Timestamped {
+    /// Capture time in unix milliseconds
+    fn timestamp_ms(&self) -> i64;
+}
+
+impl Timestamped for QUICMetrics {
+    fn timestamp_ms(&self) -> i64 {
+        self.timestamp.timestamp_millis()
+    }
+}
+
+impl QUICMetrics {
+    /// Reject NaN, infinite, negative or implausibly large measurements, and loss above 1.0
+    ///
+    /// `MetricsHandle::update` checks every sample with this, whichever path
+    /// it came in on; the ingest paths check their own formats first to give
+    /// the sender a better message.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        ensure_non_negative("latency", self.latency)?;
+        ensure_non_negative("throughput", self.throughput)?;
+        ensure_non_negative("connections", f64::from(self.connections))?;
+        ensure_non_negative("errors", f64::from(self.errors))?;
+        ensure_non_negative("packet_loss", self.packet_loss)?;
+        if self.packet_loss > 1.0 {
+            anyhow::bail!("packet_loss {} is above 1.0", self.packet_loss);
+        }
+        ensure_non_negative("retransmits", f64::from(self.retransmits))?;
+        ensure_non_negative("rtt", self.rtt)?;
+        ensure_non_negative("jitter", self.jitter)?;
+        ensure_non_negative("congestion_window", f64::from(self.congestion_window))?;
+        ensure_non_negative("bytes_sent", self.bytes_sent as f64)?;
+        ensure_non_negative("bytes_received", self.bytes_received as f64)?;
+        ensure_non_negative("streams", f64::from(self.streams))?;
+        ensure_non_negative("handshake_time", self.handshake_time)?;
+        self.bbrv3.as_ref().map_or(Ok(()), BBRv3Metrics::ensure_finite)
+    }
+}
+
+/// Bounded sample history, oldest first
+///
+/// Pushing is O(1); once `capacity` is reached the oldest sample is dropped.
+#[derive(Debug, Clone)]
+pub struct MetricsHistory<T> {
+    samples: VecDeque<T>,
+    capacity: usize,
+    pushed: u64,
+}
+
+impl<T> MetricsHistory<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            pushed: 0,
+        }
+    }
+
+    /// Append a sample, evicting the oldest when full
+    pub fn push(&mut self, sample: T) {
+        self.pushed += 1;
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// The last `n` samples, oldest first
+    pub fn iter_recent(&self, n: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.samples.iter().skip(self.samples.len().saturating_sub(n))
+    }
+
+    /// Total number of samples ever pushed; not reset by `clear`
+    pub fn total_pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Samples pushed after `total_pushed()` returned `mark`, oldest first
+    ///
+    /// Samples already evicted are skipped.
+    pub fn iter_since(&self, mark: u64) -> impl DoubleEndedIterator<Item = &T> {
+        let newer = self.pushed.saturating_sub(mark);
+        self.iter_recent(usize::try_from(newer).unwrap_or(usize::MAX))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.samples.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+impl<T: Timestamped> MetricsHistory<T> {
+    /// Samples captured within `[from, to]` (unix ms, either end open), oldest first
+    pub fn range(&self, from: Option<i64>, to: Option<i64>) -> impl DoubleEndedIterator<Item = &T> {
+        self.samples.iter().filter(move |s| {
+            let ts = s.timestamp_ms();
+            from.is_none_or(|from| ts >= from) && to.is_none_or(|to| ts <= to)
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a MetricsHistory<T> {
+    type Item = &'a T;
+    type IntoIter = std::collections::vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.samples.iter()
+    }
 }
 
 /// Time series data for graphs
@@ -24,6 +725,21 @@ pub struct TimeSeriesData {
     pub throughput: VecDeque<f64>,
     pub packet_loss: VecDeque<f64>,
     pub retransmits: VecDeque<i32>,
+    pub rtt: VecDeque<f64>,
+    pub jitter: VecDeque<f64>,
+    pub congestion_window: VecDeque<i32>,
+    pub bytes_sent: VecDeque<i64>,
+    pub bytes_received: VecDeque<i64>,
+    pub streams: VecDeque<i32>,
+    pub handshake_time: VecDeque<f64>,
+    /// Capture time of each sample
+    pub timestamps: VecDeque<DateTime<Utc>>,
+    /// Only samples carrying BBRv3 state add to it
+    pub bbrv3: BBRv3TimeSeries,
+    /// Derived values, NaN where a sample lacked the inputs
+    pub goodput: VecDeque<f64>,
+    pub efficiency_pct: VecDeque<f64>,
+    pub error_rate: VecDeque<f64>,
     pub max_points: usize,
 }
 
@@ -34,32 +750,49 @@ impl TimeSeriesData {
             throughput: VecDeque::with_capacity(max_points),
             packet_loss: VecDeque::with_capacity(max_points),
             retransmits: VecDeque::with_capacity(max_points),
+            rtt: VecDeque::with_capacity(max_points),
+            jitter: VecDeque::with_capacity(max_points),
+            congestion_window: VecDeque::with_capacity(max_points),
+            bytes_sent: VecDeque::with_capacity(max_points),
+            bytes_received: VecDeque::with_capacity(max_points),
+            streams: VecDeque::with_capacity(max_points),
+            handshake_time: VecDeque::with_capacity(max_points),
+            timestamps: VecDeque::with_capacity(max_points),
+            bbrv3: BBRv3TimeSeries::new(max_points),
+            goodput: VecDeque::with_capacity(max_points),
+            efficiency_pct: VecDeque::with_capacity(max_points),
+            error_rate: VecDeque::with_capacity(max_points),
             max_points,
         }
     }
 
     pub fn add_data_point(&mut self, metrics: &QUICMetrics) {
-        // Add new data points
-        self.latency.push_back(metrics.latency);
-        self.throughput.push_back(metrics.throughput);
-        self.packet_loss.push_back(metrics.packet_loss);
-        self.retransmits.push_back(metrics.retransmits);
-
-        // Remove old data points if we exceed max_points
-        if self.latency.len() > self.max_points {
-            self.latency.pop_front();
-        }
-        if self.throughput.len() > self.max_points {
-            self.throughput.pop_front();
-        }
-        if self.packet_loss.len() > self.max_points {
-            self.packet_loss.pop_front();
-        }
-        if self.retransmits.len() > self.max_points {
-            self.retransmits.pop_front();
+        let max = self.max_points;
+        push_bounded(&mut self.latency, metrics.latency, max);
+        push_bounded(&mut self.throughput, metrics.throughput, max);
+        push_bounded(&mut self.packet_loss, metrics.packet_loss, max);
+        push_bounded(&mut self.retransmits, metrics.retransmits, max);
+        push_bounded(&mut self.rtt, metrics.rtt, max);
+        push_bounded(&mut self.jitter, metrics.jitter, max);
+        push_bounded(&mut self.congestion_window, metrics.congestion_window, max);
+        push_bounded(&mut self.bytes_sent, metrics.bytes_sent, max);
+        push_bounded(&mut self.bytes_received, metrics.bytes_received, max);
+        push_bounded(&mut self.streams, metrics.streams, max);
+        push_bounded(&mut self.handshake_time, metrics.handshake_time, max);
+        push_bounded(&mut self.timestamps, metrics.timestamp, max);
+        if let Some(bbrv3) = metrics.bbrv3.as_ref().filter(|bbrv3| bbrv3.is_reported()) {
+            self.bbrv3.add_data_point(bbrv3);
         }
     }
 
+    pub fn add_derived(&mut self, derived: &DerivedMetrics) {
+        let max = self.max_points;
+        let value = |v: Option<f64>| v.unwrap_or(f64::NAN);
+        push_bounded(&mut self.goodput, value(derived.goodput), max);
+        push_bounded(&mut self.efficiency_pct, value(derived.efficiency_pct), max);
+        push_bounded(&mut self.error_rate, value(derived.error_rate), max);
+    }
+
     pub fn get_latency_data(&self) -> Vec<f64> {
         self.latency.iter().cloned().collect()
     }
@@ -75,118 +808,1517 @@ impl TimeSeriesData {
     pub fn get_retransmits_data(&self) -> Vec<i32> {
         self.retransmits.iter().cloned().collect()
     }
+
+    pub fn get_rtt_data(&self) -> Vec<f64> {
+        self.rtt.iter().cloned().collect()
+    }
+
+    pub fn get_jitter_data(&self) -> Vec<f64> {
+        self.jitter.iter().cloned().collect()
+    }
+
+    pub fn get_congestion_window_data(&self) -> Vec<i32> {
+        self.congestion_window.iter().cloned().collect()
+    }
+
+    pub fn get_bytes_sent_data(&self) -> Vec<i64> {
+        self.bytes_sent.iter().cloned().collect()
+    }
+
+    pub fn get_bytes_received_data(&self) -> Vec<i64> {
+        self.bytes_received.iter().cloned().collect()
+    }
+
+    pub fn get_streams_data(&self) -> Vec<i32> {
+        self.streams.iter().cloned().collect()
+    }
+
+    pub fn get_handshake_time_data(&self) -> Vec<f64> {
+        self.handshake_time.iter().cloned().collect()
+    }
+
+    pub fn get_goodput_data(&self) -> Vec<f64> {
+        self.goodput.iter().cloned().collect()
+    }
+
+    pub fn get_efficiency_data(&self) -> Vec<f64> {
+        self.efficiency_pct.iter().cloned().collect()
+    }
+
+    pub fn get_error_rate_data(&self) -> Vec<f64> {
+        self.error_rate.iter().cloned().collect()
+    }
 }
 
-/// Global metrics state
-static METRICS_STATE: Mutex<Option<Arc<RwLock<QUICMetricsState>>>> = Mutex::new(None);
+/// Append `value`, dropping the oldest entries beyond `max_points`
+fn push_bounded<T>(series: &mut VecDeque<T>, value: T, max_points: usize) {
+    series.push_back(value);
+    while series.len() > max_points {
+        series.pop_front();
+    }
+}
+
+/// Series and latest sample for one QUIC connection
+#[derive(Debug, Clone)]
+pub struct TrackedConnection {
+    pub series: TimeSeriesData,
+    pub latest: QUICMetrics,
+    pub last_seen: Instant,
+}
 
+/// Per-connection metrics keyed by connection id
+///
+/// Connections that stop reporting are first marked stale and later evicted.
+/// One connection can be pinned, which traces its raw samples; see `trace`.
 #[derive(Debug)]
-struct QUICMetricsState {
-    current: QUICMetrics,
-    time_series: TimeSeriesData,
+pub struct ConnectionTracker {
+    connections: HashMap<String, TrackedConnection>,
+    max_points: usize,
+    stale_after: Duration,
+    evict_after: Duration,
+    pinned: Option<ConnectionTrace>,
 }
 
-impl QUICMetricsState {
-    fn new() -> Self {
+impl ConnectionTracker {
+    pub fn new(max_points: usize, stale_after: Duration, evict_after: Duration) -> Self {
         Self {
-            current: QUICMetrics {
-                latency: 0.0,
-                throughput: 0.0,
-                connections: 0,
-                errors: 0,
-                packet_loss: 0.0,
-                retransmits: 0,
-                timestamp: Utc::now(),
-            },
-            time_series: TimeSeriesData::new(1000), // Keep last 1000 data points
+            connections: HashMap::new(),
+            max_points,
+            stale_after,
+            evict_after,
+            pinned: None,
         }
     }
 
-    fn update(&mut self, metrics: QUICMetrics) {
-        self.current = metrics.clone();
-        self.time_series.add_data_point(&metrics);
+    /// Record a sample for `connection_id`, creating the entry on first report
+    pub fn record(&mut self, connection_id: &str, metrics: &QUICMetrics) {
+        let max_points = self.max_points;
+        let entry = self
+            .connections
+            .entry(connection_id.to_string())
+            .or_insert_with(|| TrackedConnection {
+                series: TimeSeriesData::new(max_points),
+                latest: metrics.clone(),
+                last_seen: Instant::now(),
+            });
+        entry.series.add_data_point(metrics);
+        entry.latest = metrics.clone();
+        entry.last_seen = Instant::now();
+
+        if let Some(trace) = self.pinned.as_mut().filter(|trace| trace.connection_id() == connection_id) {
+            trace.record(metrics);
+        }
+    }
+
+    /// Trace `connection_id` into a new file under `dir`, replacing any pinned connection
+    pub fn pin(&mut self, connection_id: &str, dir: &Path) -> anyhow::Result<&ConnectionTrace> {
+        let trace = ConnectionTrace::create(connection_id, dir)?;
+        Ok(self.pinned.insert(trace))
+    }
+
+    /// Stop tracing, closing the trace file
+    pub fn unpin(&mut self) -> Option<ConnectionTrace> {
+        self.pinned.take()
+    }
+
+    pub fn pinned(&self) -> Option<&ConnectionTrace> {
+        self.pinned.as_ref()
+    }
+
+    /// Whether a connection has gone quiet for longer than the stale timeout
+    pub fn is_stale(&self, connection: &TrackedConnection) -> bool {
+        connection.last_seen.elapsed() >= self.stale_after
     }
 
-    fn get_current(&self) -> QUICMetrics {
-        self.current.clone()
+    /// Drop connections that have not reported within the eviction timeout
+    pub fn evict_expired(&mut self) -> usize {
+        let before = self.connections.len();
+        let evict_after = self.evict_after;
+        self.connections
+            .retain(|_, connection| connection.last_seen.elapsed() < evict_after);
+        before - self.connections.len()
     }
 
-    fn get_time_series(&self) -> TimeSeriesData {
-        self.time_series.clone()
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &TrackedConnection)> {
+        self.connections.iter()
+    }
+
+    pub fn get(&self, connection_id: &str) -> Option<&TrackedConnection> {
+        self.connections.get(connection_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.connections.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connections.is_empty()
+    }
+
+    /// Forget every connection; a pinned one stays pinned with its file, from an empty trace
+    pub fn clear(&mut self) {
+        self.connections.clear();
+        if let Some(trace) = self.pinned.as_mut() {
+            trace.clear();
+        }
     }
 }
 
-/// Initialize the metrics system
-pub fn init_metrics() -> Result<(), anyhow::Error> {
-    let state = Arc::new(RwLock::new(QUICMetricsState::new()));
-    let mut global_state = METRICS_STATE.lock().unwrap();
-    *global_state = Some(state);
-    Ok(())
+/// Incremental mean/variance (Welford) plus estimated percentiles
+///
+/// Built for sliding windows: `remove` undoes a sample's effect, so owners
+/// push new samples and remove evicted ones instead of rescanning the window.
+/// Percentiles come from a `TDigest` of the window: within 1% of the window
+/// in rank of the exact ones, and exact up to `COMPRESSION` samples. The
+/// window's values are also kept as a sorted multiset, which gives exact min
+/// and max and is what the digest is rebuilt from.
+#[derive(Debug, Clone, Default)]
+pub struct StreamingStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    values: BTreeMap<Sample, usize>,
+    digest: TDigest,
 }
 
-/// Update QUIC metrics
-pub fn update_metrics(metrics: QUICMetrics) -> Result<(), anyhow::Error> {
-    let global_state = METRICS_STATE.lock().unwrap();
-    if let Some(state) = global_state.as_ref() {
-        let mut state_guard = state.write().unwrap();
-        state_guard.update(metrics);
+/// A finite sample as a key ordered by value
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample(f64);
+
+impl Sample {
+    /// -0.0 and 0.0 are the same sample
+    fn of(value: f64) -> Self {
+        Self(value + 0.0)
     }
-    Ok(())
 }
 
-/// Get current metrics
-pub fn get_current_metrics() -> Option<QUICMetrics> {
-    let global_state = METRICS_STATE.lock().unwrap();
-    if let Some(state) = global_state.as_ref() {
-        let state_guard = state.read().unwrap();
-        Some(state_guard.get_current())
-    } else {
-        None
+impl Eq for Sample {}
+
+impl PartialOrd for Sample {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-/// Get time series data
-pub fn get_time_series_data() -> Option<TimeSeriesData> {
-    let global_state = METRICS_STATE.lock().unwrap();
-    if let Some(state) = global_state.as_ref() {
-        let state_guard = state.read().unwrap();
-        Some(state_guard.get_time_series())
-    } else {
-        None
+impl Ord for Sample {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
     }
 }
 
-/// Calculate percentiles for latency data
-pub fn calculate_latency_percentiles(data: &[f64]) -> (f64, f64, f64) {
-    if data.is_empty() {
-        return (0.0, 0.0, 0.0);
+impl StreamingStats {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    let mut sorted_data = data.to_vec();
-    sorted_data.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// Add a sample; non-finite values are ignored
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
 
-    let len = sorted_data.len();
-    let p50_idx = (len as f64 * 0.5) as usize;
-    let p95_idx = (len as f64 * 0.95) as usize;
-    let p99_idx = (len as f64 * 0.99) as usize;
+        *self.values.entry(Sample::of(value)).or_insert(0) += 1;
+        if !self.digest.push(value) {
+            self.rebuild_digest();
+        }
+    }
 
-    let p50 = sorted_data[p50_idx.min(len - 1)];
-    let p95 = sorted_data[p95_idx.min(len - 1)];
-    let p99 = sorted_data[p99_idx.min(len - 1)];
+    /// Remove a sample previously pushed, e.g. one evicted from a window
+    pub fn remove(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        let key = Sample::of(value);
+        match self.values.get_mut(&key) {
+            None => return,
+            Some(repeats) if *repeats > 1 => *repeats -= 1,
+            Some(_) => {
+                self.values.remove(&key);
+            }
+        }
 
-    (p50, p95, p99)
-}
+        if self.count == 1 {
+            self.count = 0;
+            self.mean = 0.0;
+            self.m2 = 0.0;
+        } else {
+            let delta = value - self.mean;
+            self.mean -= delta / (self.count - 1) as f64;
+            self.m2 = (self.m2 - delta * (value - self.mean)).max(0.0);
+            self.count -= 1;
+        }
+        if !self.digest.remove(value) {
+            self.rebuild_digest();
+        }
+    }
 
-/// Calculate jitter (standard deviation) for latency data
-pub fn calculate_jitter(data: &[f64]) -> f64 {
-    if data.is_empty() {
-        return 0.0;
+    fn rebuild_digest(&mut self) {
+        self.digest
+            .rebuild(self.values.iter().map(|(sample, repeats)| (sample.0, *repeats)), self.count);
     }
 
-    let mean = data.iter().sum::<f64>() / data.len() as f64;
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.values.first_key_value().map(|(sample, _)| sample.0)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.values.last_key_value().map(|(sample, _)| sample.0)
+    }
+
+    /// Estimated value at quantile `p` (0.0..=1.0), or 0.0 when empty
+    pub fn percentile(&self, p: f64) -> f64 {
+        let (Some(min), Some(max)) = (self.min(), self.max()) else {
+            return 0.0;
+        };
+        self.digest.quantile(p, min, max).unwrap_or(0.0)
+    }
+
+    /// (P50, P95, P99)
+    pub fn percentiles(&self) -> (f64, f64, f64) {
+        (self.percentile(0.5), self.percentile(0.95), self.percentile(0.99))
+    }
+}
+
+/// How recent the last reported sample is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    /// Nothing reported yet
+    Waiting,
+    Fresh,
+    /// Past the warning threshold; the sender may be struggling
+    Lagging,
+    /// Past the stale threshold; the sender has probably stopped
+    Stale,
+}
+
+/// Age thresholds for the dashboard's last-update indicator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StalenessConfig {
+    /// Milliseconds without a sample before the indicator turns yellow
+    pub warn_after_ms: u64,
+
+    /// Milliseconds without a sample before the data is shown as stale
+    pub stale_after_ms: u64,
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            warn_after_ms: 2_000,
+            stale_after_ms: 10_000,
+        }
+    }
+}
+
+impl StalenessConfig {
+    /// Classify the time since the last sample, `None` if there was none
+    pub fn freshness(&self, age: Option<Duration>) -> Freshness {
+        match age {
+            None => Freshness::Waiting,
+            Some(age) if age >= Duration::from_millis(self.stale_after_ms) => Freshness::Stale,
+            Some(age) if age >= Duration::from_millis(self.warn_after_ms) => Freshness::Lagging,
+            Some(_) => Freshness::Fresh,
+        }
+    }
+}
+
+/// Settings for the metrics derived from each sample, under `[derived]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DerivedMetricsConfig {
+    /// Average packet size in bytes, to turn retransmit counts into bytes
+    pub avg_packet_size: u32,
+
+    /// Seconds of connection attempts the success rate is computed over
+    pub success_window_secs: u64,
+}
+
+impl Default for DerivedMetricsConfig {
+    fn default() -> Self {
+        Self {
+            avg_packet_size: 1200,
+            success_window_secs: 60,
+        }
+    }
+}
+
+impl DerivedMetricsConfig {
+    pub fn success_window(&self) -> Duration {
+        Duration::from_secs(self.success_window_secs)
+    }
+}
+
+/// Values computed from a sample rather than reported by the sender
+///
+/// `None` where the sample lacks the inputs, e.g. no `bytes_sent`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DerivedMetrics {
+    /// Throughput less the share spent on retransmissions, in the same unit
+    pub goodput: Option<f64>,
+    /// Sent bytes that were not retransmissions, in percent
+    pub efficiency_pct: Option<f64>,
+    /// Errors per active connection
+    pub error_rate: Option<f64>,
+}
+
+impl DerivedMetrics {
+    /// Derive from cumulative `bytes_sent` and `retransmits`, so efficiency
+    /// covers the run so far
+    pub fn compute(metrics: &QUICMetrics, config: &DerivedMetricsConfig) -> Self {
+        let efficiency_pct = (metrics.bytes_sent > 0).then(|| {
+            let retransmitted = f64::from(metrics.retransmits.max(0)) * f64::from(config.avg_packet_size);
+            let sent = metrics.bytes_sent as f64;
+            ((sent - retransmitted) / sent).clamp(0.0, 1.0) * 100.0
+        });
+        Self {
+            goodput: efficiency_pct.map(|pct| metrics.throughput * pct / 100.0),
+            efficiency_pct,
+            error_rate: (metrics.connections > 0)
+                .then(|| f64::from(metrics.errors) / f64::from(metrics.connections)),
+        }
+    }
+}
+
+/// Maximum number of full samples kept for history queries
+const HISTORY_CAPACITY: usize = 1000;
+
+/// Most distinct `source` labels tracked at once; samples from more are refused
+pub const MAX_SOURCES: usize = 16;
+
+/// Refuse a new `source` once `tracked` reaches `MAX_SOURCES`; `known` ones are always taken
+pub fn ensure_source_room(source: &str, known: bool, tracked: usize) -> Result<(), anyhow::Error> {
+    if !known && tracked >= MAX_SOURCES {
+        anyhow::bail!("already tracking {} sources, refusing '{}'", MAX_SOURCES, source);
+    }
+    Ok(())
+}
+
+/// Samples of one labelled sender, kept apart from the others
+#[derive(Debug)]
+struct SourceState {
+    current: QUICMetrics,
+    packets: Option<PacketDeltas>,
+    time_series: TimeSeriesData,
+    samples: u64,
+    last_update: Instant,
+}
+
+impl SourceState {
+    fn new(metrics: &QUICMetrics) -> Self {
+        Self {
+            current: metrics.clone(),
+            packets: None,
+            time_series: TimeSeriesData::new(1000),
+            samples: 0,
+            last_update: Instant::now(),
+        }
+    }
+
+    fn update(&mut self, metrics: &QUICMetrics, derived: &DerivedMetrics) {
+        if self.samples > 0 {
+            self.packets = PacketDeltas::between(&self.current, metrics);
+        }
+        self.current = metrics.clone();
+        self.time_series.add_data_point(metrics);
+        self.time_series.add_derived(derived);
+        self.samples += 1;
+        self.last_update = Instant::now();
+    }
+}
+
+/// A labelled sender as listed by `GET /api/sources`
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceSummary {
+    pub source: String,
+    /// Samples received since the start or the last reset
+    pub samples: u64,
+    /// Capture time of the newest sample
+    pub last_sample: DateTime<Utc>,
+    /// Time since the newest sample arrived, by the local clock
+    pub age_ms: u64,
+}
+
+#[derive(Debug)]
+struct QUICMetricsState {
+    current: QUICMetrics,
+    derived: DerivedMetrics,
+    derived_config: DerivedMetricsConfig,
+    sla_config: SlaConfig,
+    health_config: HealthConfig,
+    /// Recent anomalies by severity, from whoever runs the detector
+    anomaly_counts: Option<BTreeMap<AnomalySeverity, usize>>,
+    handshakes: HandshakeStats,
+    connections: ConnectionLifecycle,
+    /// Counter deltas between the last two samples
+    packets: Option<PacketDeltas>,
+    time_series: TimeSeriesData,
+    /// Per-sender state, by `source` label
+    sources: BTreeMap<String, SourceState>,
+    history: MetricsHistory<QUICMetrics>,
+    /// When the last sample arrived, by the local clock
+    last_update: Option<Instant>,
+    last_reset: Option<ResetEvent>,
+    /// Bumped by a reset with `"segments": "new"`
+    segment: u64,
+}
+
+impl QUICMetricsState {
+    fn new() -> Self {
+        Self {
+            current: QUICMetrics {
+                timestamp: Utc::now(),
+                ..QUICMetrics::default()
+            },
+            derived: DerivedMetrics::default(),
+            derived_config: DerivedMetricsConfig::default(),
+            sla_config: SlaConfig::default(),
+            health_config: HealthConfig::default(),
+            anomaly_counts: None,
+            handshakes: HandshakeStats::default(),
+            connections: ConnectionLifecycle::default(),
+            packets: None,
+            time_series: TimeSeriesData::new(1000), // Keep last 1000 data points
+            sources: BTreeMap::new(),
+            history: MetricsHistory::new(HISTORY_CAPACITY),
+            last_update: None,
+            last_reset: None,
+            segment: 1,
+        }
+    }
+
+    fn update(&mut self, metrics: QUICMetrics) {
+        self.last_update = Some(Instant::now());
+        // Counters of different senders cannot be subtracted from each other
+        self.packets = if self.current.source == metrics.source {
+            PacketDeltas::between(&self.current, &metrics)
+        } else {
+            None
+        };
+        self.current = metrics.clone();
+        self.derived = DerivedMetrics::compute(&metrics, &self.derived_config);
+        self.handshakes.record(&metrics);
+        self.connections.record(&metrics, self.derived_config.success_window());
+        self.time_series.add_data_point(&metrics);
+        self.time_series.add_derived(&self.derived);
+        if let Some(source) = &metrics.source {
+            self.sources
+                .entry(source.clone())
+                .or_insert_with(|| SourceState::new(&metrics))
+                .update(&metrics, &self.derived);
+        }
+
+        self.history.push(metrics);
+    }
+
+    /// Clear `scope` as a new handle would, keeping history positions valid
+    fn reset(&mut self, scope: ResetScope, source: &str) -> ResetEvent {
+        if scope.stats {
+            let fresh = Self::new();
+            self.current = fresh.current;
+            self.derived = fresh.derived;
+            self.handshakes = fresh.handshakes;
+            self.connections = fresh.connections;
+            self.packets = None;
+            self.time_series = fresh.time_series;
+            self.sources.clear();
+            self.last_update = None;
+        }
+        let dropped = if scope.history {
+            let dropped = self.history.len();
+            self.history.clear();
+            dropped
+        } else {
+            0
+        };
+        if scope.segments == SegmentReset::New {
+            self.segment += 1;
+        }
+
+        let event = ResetEvent {
+            id: self.last_reset.as_ref().map_or(1, |event| event.id + 1),
+            source: source.to_string(),
+            outcome: ResetOutcome {
+                scope,
+                history_dropped: dropped,
+                segment: self.segment,
+            },
+        };
+        self.last_reset = Some(event.clone());
+        event
+    }
+}
+
+/// A reset of the collected state requested through the API
+#[derive(Debug, Clone)]
+pub struct ResetEvent {
+    /// Increases with every reset
+    pub id: u64,
+    /// Who asked, e.g. the client address
+    pub source: String,
+    pub outcome: ResetOutcome,
+}
+
+/// Shared metrics state: the current sample, time series and history
+///
+/// Clones share the same state. The app, the bridge routes and the ingest
+/// paths are each given a handle, so independent monitors can live in one
+/// process; `MetricsHandle::global()` is the one behind the FFI functions.
+#[derive(Debug, Clone)]
+pub struct MetricsHandle {
+    state: Arc<RwLock<QUICMetricsState>>,
+}
+
+impl Default for MetricsHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsHandle {
+    /// A fresh, empty state
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(QUICMetricsState::new())),
+        }
+    }
+
+    /// The process-wide handle, created on first use
+    pub fn global() -> Self {
+        GLOBAL_METRICS.lock_or_recover().get_or_insert_with(Self::new).clone()
+    }
+
+    /// Store a sample and pass it to the registered sinks
+    ///
+    /// Fails for a sample `QUICMetrics::validate` refuses, and for one from a
+    /// new `source` once `MAX_SOURCES` are tracked.
+    pub fn update(&self, metrics: QUICMetrics) -> Result<(), anyhow::Error> {
+        metrics.validate()?;
+        if let Some(source) = &metrics.source {
+            let state = self.state.read_or_recover();
+            ensure_source_room(source, state.sources.contains_key(source), state.sources.len())?;
+        }
+        for sink in METRICS_SINKS.read_or_recover().iter() {
+            sink(&metrics);
+        }
+        self.state.write_or_recover().update(metrics);
+        record_samples(1);
+        Ok(())
+    }
+
+    /// The last sample, zeros before the first one
+    pub fn current(&self) -> QUICMetrics {
+        self.state.read_or_recover().current.clone()
+    }
+
+    /// Values derived from the last sample
+    pub fn derived(&self) -> DerivedMetrics {
+        self.state.read_or_recover().derived
+    }
+
+    /// 0-RTT and 1-RTT handshakes counted since the start or the last reset
+    pub fn handshake_stats(&self) -> HandshakeStats {
+        self.state.read_or_recover().handshakes
+    }
+
+    /// Connection counts and the success rate over `[derived] success_window_secs`
+    pub fn connection_stats(&self) -> ConnectionStats {
+        let state = self.state.read_or_recover();
+        state.connections.stats(state.derived_config.success_window())
+    }
+
+    /// Packet counter deltas between the last two samples, `None` without counters
+    pub fn packet_deltas(&self) -> Option<PacketDeltas> {
+        self.state.read_or_recover().packets
+    }
+
+    /// Change how later samples are derived from
+    pub fn set_derived_config(&self, config: DerivedMetricsConfig) {
+        self.state.write_or_recover().derived_config = config;
+    }
+
+    /// Set the targets `sla()` checks the history against
+    pub fn set_sla_config(&self, config: SlaConfig) {
+        self.state.write_or_recover().sla_config = config;
+    }
+
+    /// Verdict of the stored history against the configured SLA
+    pub fn sla(&self) -> SlaVerdict {
+        let state = self.state.read_or_recover();
+        let from = state
+            .history
+            .latest()
+            .and_then(|newest| state.sla_config.window_start_ms(newest.timestamp_ms()));
+        let samples: Vec<QUICMetrics> = state.history.range(from, None).cloned().collect();
+        state.sla_config.evaluate(&samples)
+    }
+
+    /// Set the weights and targets `health_score()` uses
+    pub fn set_health_config(&self, config: HealthConfig) {
+        self.state.write_or_recover().health_config = config;
+    }
+
+    /// Count these anomalies in the health score, as the detector's owner publishes them
+    pub fn set_anomaly_counts(&self, counts: BTreeMap<AnomalySeverity, usize>) {
+        self.state.write_or_recover().anomaly_counts = Some(counts);
+    }
+
+    /// Composite health score of the newest sample, `None` score before the first one
+    pub fn health_score(&self) -> HealthScore {
+        let state = self.state.read_or_recover();
+        let age = state.last_update.map(|at| at.elapsed());
+        let inputs = HealthInputs {
+            anomalies: state.anomaly_counts.clone(),
+            ..HealthInputs::from_sample(&state.current, &state.derived, age)
+        };
+        state.health_config.score(&inputs)
+    }
+
+    /// A copy of the time series; `with_time_series` reads it in place
+    pub fn time_series(&self) -> TimeSeriesData {
+        self.state.read_or_recover().time_series.clone()
+    }
+
+    /// Run `f` on the time series under the read lock, without copying it
+    ///
+    /// Samples are stored while `f` runs only once it returns, so keep it short.
+    pub fn with_time_series<R>(&self, f: impl FnOnce(&TimeSeriesData) -> R) -> R {
+        f(&self.state.read_or_recover().time_series)
+    }
+
+    /// Labelled senders seen since the start or the last reset, by name
+    pub fn sources(&self) -> Vec<SourceSummary> {
+        self.state
+            .read_or_recover()
+            .sources
+            .iter()
+            .map(|(name, source)| SourceSummary {
+                source: name.clone(),
+                samples: source.samples,
+                last_sample: source.current.timestamp,
+                age_ms: source.last_update.elapsed().as_millis() as u64,
+            })
+            .collect()
+    }
+
+    /// The last sample from `source`, `None` for an unknown label
+    pub fn source_current(&self, source: &str) -> Option<QUICMetrics> {
+        let state = self.state.read_or_recover();
+        state.sources.get(source).map(|source| source.current.clone())
+    }
+
+    /// A copy of the time series of `source` alone
+    pub fn source_time_series(&self, source: &str) -> Option<TimeSeriesData> {
+        let state = self.state.read_or_recover();
+        state.sources.get(source).map(|source| source.time_series.clone())
+    }
+
+    /// Run `f` on the time series of every labelled sender, by name, without copying them
+    pub fn with_source_time_series<R>(&self, f: impl FnOnce(&[(&str, &TimeSeriesData)]) -> R) -> R {
+        let state = self.state.read_or_recover();
+        let sources: Vec<(&str, &TimeSeriesData)> = state
+            .sources
+            .iter()
+            .map(|(name, source)| (name.as_str(), &source.time_series))
+            .collect();
+        f(&sources)
+    }
+
+    /// Packet counter deltas between the last two samples of `source`
+    pub fn source_packet_deltas(&self, source: &str) -> Option<PacketDeltas> {
+        self.state.read_or_recover().sources.get(source)?.packets
+    }
+
+    /// Time since the last `update`, `None` before the first one
+    pub fn last_update_age(&self) -> Option<Duration> {
+        self.state.read_or_recover().last_update.map(|at| at.elapsed())
+    }
+
+    /// Capture time of the newest stored sample
+    pub fn latest_sample_time(&self) -> Option<DateTime<Utc>> {
+        self.state.read_or_recover().history.latest().map(|sample| sample.timestamp)
+    }
+
+    /// Stored samples with timestamps in `[from, to]` (unix ms), oldest first
+    ///
+    /// At most `limit` samples are returned; when more match, the newest ones win.
+    pub fn history(&self, from: Option<i64>, to: Option<i64>, limit: usize) -> Vec<QUICMetrics> {
+        let state = self.state.read_or_recover();
+        let mut samples: Vec<QUICMetrics> = state
+            .history
+            .range(from, to)
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect();
+        samples.reverse();
+        samples
+    }
+
+    /// Current position in the history, for use with `history_since`
+    ///
+    /// Also the number of samples stored since the handle was created.
+    pub fn history_mark(&self) -> u64 {
+        self.state.read_or_recover().history.total_pushed()
+    }
+
+    /// Samples stored in the history and how many it holds at most
+    pub fn history_fill(&self) -> (usize, usize) {
+        let state = self.state.read_or_recover();
+        (state.history.len(), state.history.capacity())
+    }
+
+    /// Samples recorded after `history_mark()` returned `mark`, oldest first
+    pub fn history_since(&self, mark: u64) -> Vec<QUICMetrics> {
+        self.state.read_or_recover().history.iter_since(mark).cloned().collect()
+    }
+
+    /// `history_since(mark)` and the mark to pass next time, read together
+    pub fn history_catch_up(&self, mark: u64) -> (Vec<QUICMetrics>, u64) {
+        let state = self.state.read_or_recover();
+        let samples = state.history.iter_since(mark).cloned().collect();
+        (samples, state.history.total_pushed())
+    }
+
+    /// Clear the parts of the state `scope` selects: the current sample, time series and sources, and the history
+    ///
+    /// Displays watch `last_reset()` to clear their own widgets.
+    pub fn reset(&self, scope: ResetScope, source: &str) -> ResetOutcome {
+        self.state.write_or_recover().reset(scope, source).outcome
+    }
+
+    /// Segment the incoming samples belong to, see `SegmentReset`
+    pub fn segment(&self) -> u64 {
+        self.state.read_or_recover().segment
+    }
+
+    /// The most recent `reset`, if any
+    pub fn last_reset(&self) -> Option<ResetEvent> {
+        self.state.read_or_recover().last_reset.clone()
+    }
+}
+
+/// Handle behind the FFI functions and the global wrappers below
+static GLOBAL_METRICS: Mutex<Option<MetricsHandle>> = Mutex::new(None);
+
+/// Receives every sample stored through any handle, e.g. an exporter queue
+pub type MetricsSink = Box<dyn Fn(&QUICMetrics) + Send + Sync>;
+
+static METRICS_SINKS: RwLock<Vec<MetricsSink>> = RwLock::new(Vec::new());
+
+/// Register `sink` for all further updates
+pub fn add_metrics_sink(sink: MetricsSink) {
+    METRICS_SINKS.write_or_recover().push(sink);
+}
+
+/// Initialize the global metrics handle; later calls keep the existing state
+pub fn init_metrics() -> Result<(), anyhow::Error> {
+    MetricsHandle::global();
+    Ok(())
+}
+
+fn initialized_global() -> Option<MetricsHandle> {
+    GLOBAL_METRICS.lock_or_recover().clone()
+}
+
+/// Update the global metrics; a no-op before `init_metrics`
+pub fn update_metrics(metrics: QUICMetrics) -> Result<(), anyhow::Error> {
+    match initialized_global() {
+        Some(handle) => handle.update(metrics),
+        None => Ok(()),
+    }
+}
+
+/// Current global metrics, `None` before `init_metrics`
+pub fn get_current_metrics() -> Option<QUICMetrics> {
+    initialized_global().map(|handle| handle.current())
+}
+
+/// A copy of the global time series, `None` before `init_metrics`
+pub fn get_time_series_data() -> Option<TimeSeriesData> {
+    initialized_global().map(|handle| handle.time_series())
+}
+
+/// Run `f` on the global time series without copying it, `None` before `init_metrics`
+pub fn with_time_series_data<R>(f: impl FnOnce(&TimeSeriesData) -> R) -> Option<R> {
+    initialized_global().map(|handle| handle.with_time_series(f))
+}
+
+/// Non-finite values skipped by analytics since startup
+static NON_FINITE_SKIPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Copy the finite values out of `data`, counting the ones skipped
+///
+/// Analytics never aggregate NaN or infinite values: they are dropped here
+/// and the total is available through `non_finite_skipped()`.
+pub fn finite_values<'a, I>(data: I) -> Vec<f64>
+where
+    I: IntoIterator<Item = &'a f64>,
+{
+    let mut skipped = 0;
+    let values: Vec<f64> = data
+        .into_iter()
+        .filter(|v| {
+            let finite = v.is_finite();
+            if !finite {
+                skipped += 1;
+            }
+            finite
+        })
+        .copied()
+        .collect();
+
+    if skipped > 0 {
+        NON_FINITE_SKIPPED.fetch_add(skipped, Ordering::Relaxed);
+    }
+    values
+}
+
+/// `value`, or `fallback` counted as a skipped value when it is NaN or infinite
+pub fn finite_or(value: f64, fallback: f64) -> f64 {
+    if value.is_finite() {
+        value
+    } else {
+        NON_FINITE_SKIPPED.fetch_add(1, Ordering::Relaxed);
+        fallback
+    }
+}
+
+/// Number of non-finite values skipped by analytics since startup
+pub fn non_finite_skipped() -> u64 {
+    NON_FINITE_SKIPPED.load(Ordering::Relaxed)
+}
+
+/// Format a value for display, rendering undefined values as "–"
+pub fn format_value(value: f64, precision: usize) -> String {
+    if value.is_finite() {
+        format!("{:.*}", precision, value)
+    } else {
+        "–".to_string()
+    }
+}
+
+/// Format a timestamp as local wall-clock time, e.g. for graph axes
+pub fn format_clock(t: DateTime<Utc>) -> String {
+    t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string()
+}
+
+/// Format a duration compactly, e.g. `42s` or `3m05s`
+pub fn format_span(span: Duration) -> String {
+    let secs = span.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Format the age of a sample, e.g. `0.3s` or `3m05s`
+pub fn format_age(age: Duration) -> String {
+    if age < Duration::from_secs(60) {
+        format!("{:.1}s", age.as_secs_f64())
+    } else {
+        format_span(age)
+    }
+}
+
+/// How `percentile_with` picks a value for a quantile that falls between two samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PercentileMethod {
+    /// Interpolate linearly between the two closest ranks, as NumPy and spreadsheets do
+    #[default]
+    Linear,
+    /// The smallest sample with at least a fraction `p` of the samples at or below it
+    NearestRank,
+}
+
+/// Value at quantile `p` (0.0..=1.0) of `sorted`, interpolating between ranks
+///
+/// `sorted` must be ascending and free of NaN, as `StreamingStats` keeps it;
+/// `calculate_latency_percentiles` prepares raw data. `None` when empty.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    percentile_with(sorted, p, PercentileMethod::Linear)
+}
+
+/// `percentile` with the method chosen by the caller
+pub fn percentile_with(sorted: &[f64], p: f64, method: PercentileMethod) -> Option<f64> {
+    let last = sorted.len().checked_sub(1)?;
+    let p = p.clamp(0.0, 1.0);
+    match method {
+        PercentileMethod::Linear => {
+            let rank = p * last as f64;
+            let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+            let (low, high) = (sorted[below], sorted[above]);
+            Some(low + (high - low) * (rank - below as f64))
+        }
+        PercentileMethod::NearestRank => {
+            let rank = (p * sorted.len() as f64).ceil() as usize;
+            Some(sorted[rank.clamp(1, sorted.len()) - 1])
+        }
+    }
+}
+
+/// (P50, P95, P99) of `data`, skipping non-finite values; zeros without finite data
+pub fn calculate_latency_percentiles(data: &[f64]) -> (f64, f64, f64) {
+    let mut sorted = finite_values(data);
+    sorted.sort_by(f64::total_cmp);
+    let at = |p| percentile(&sorted, p).unwrap_or(0.0);
+    (at(0.5), at(0.95), at(0.99))
+}
+
+/// Calculate jitter (standard deviation) for latency data
+pub fn calculate_jitter(data: &[f64]) -> f64 {
+    let data = finite_values(data);
+    // Scaled by the largest magnitude, so huge finite values cannot overflow the sums
+    let scale = data.iter().fold(0.0f64, |max, x| max.max(x.abs()));
+    if scale == 0.0 {
+        return 0.0;
+    }
+
+    let mean = data.iter().map(|x| x / scale).sum::<f64>() / data.len() as f64;
     let variance = data.iter()
-        .map(|x| (x - mean).powi(2))
+        .map(|x| (x / scale - mean).powi(2))
         .sum::<f64>() / data.len() as f64;
     
-    variance.sqrt()
+    variance.sqrt() * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    fn sample(latency: f64) -> QUICMetrics {
+        QUICMetrics {
+            latency,
+            throughput: 100.0,
+            connections: 1,
+            ..QUICMetrics::default()
+        }
+    }
+
+    #[test]
+    fn updates_continue_after_a_panic_under_the_lock() {
+        let metrics = MetricsHandle::new();
+        metrics.update(sample(10.0)).unwrap();
+
+        let state = Arc::clone(&metrics.state);
+        let result = std::panic::catch_unwind(move || {
+            let _guard = state.write().unwrap();
+            panic!("update dies with the state locked");
+        });
+        assert!(result.is_err());
+        assert!(metrics.state.is_poisoned());
+
+        metrics.update(sample(20.0)).unwrap();
+        assert_eq!(metrics.current().latency, 20.0);
+        assert_eq!(metrics.history_mark(), 2);
+        assert!(!metrics.state.is_poisoned());
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn percentile_of_one_and_two_samples() {
+        for method in [PercentileMethod::Linear, PercentileMethod::NearestRank] {
+            for p in [0.0, 0.5, 0.99, 1.0] {
+                assert_eq!(percentile_with(&[5.0], p, method), Some(5.0));
+            }
+            assert_eq!(percentile_with(&[], 0.5, method), None);
+        }
+
+        // p99 of two samples lies next to the larger one, not on the first
+        let two = [1.0, 3.0];
+        assert_close(percentile(&two, 0.5).unwrap(), 2.0);
+        assert_close(percentile(&two, 0.99).unwrap(), 2.98);
+        assert_eq!(percentile_with(&two, 0.5, PercentileMethod::NearestRank), Some(1.0));
+        assert_eq!(percentile_with(&two, 0.99, PercentileMethod::NearestRank), Some(3.0));
+    }
+
+    #[test]
+    fn percentile_of_a_hundred_samples() {
+        let sorted: Vec<f64> = (1..=100).map(f64::from).collect();
+        assert_close(percentile(&sorted, 0.5).unwrap(), 50.5);
+        assert_close(percentile(&sorted, 0.95).unwrap(), 95.05);
+        assert_close(percentile(&sorted, 0.99).unwrap(), 99.01);
+        assert_eq!(percentile(&sorted, 0.0), Some(1.0));
+        assert_eq!(percentile(&sorted, 1.0), Some(100.0));
+
+        let nearest = |p| percentile_with(&sorted, p, PercentileMethod::NearestRank).unwrap();
+        assert_eq!(nearest(0.5), 50.0);
+        assert_eq!(nearest(0.95), 95.0);
+        assert_eq!(nearest(0.99), 99.0);
+    }
+
+    #[test]
+    fn latency_percentiles_skip_non_finite_values() {
+        let (p50, p95, p99) = calculate_latency_percentiles(&[3.0, f64::NAN, 1.0, f64::INFINITY]);
+        assert_close(p50, 2.0);
+        assert_close(p95, 2.9);
+        assert_close(p99, 2.98);
+        assert_eq!(calculate_latency_percentiles(&[f64::NAN, f64::NEG_INFINITY]), (0.0, 0.0, 0.0));
+        assert_eq!(calculate_latency_percentiles(&[]), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn undefined_values_are_counted_and_render_as_a_dash() {
+        // Other tests skip values concurrently, so only a lower bound holds
+        let before = non_finite_skipped();
+        assert_eq!(finite_values(&[1.0, f64::NAN, 2.0, f64::NEG_INFINITY]), [1.0, 2.0]);
+        assert_eq!(finite_or(f64::INFINITY, 0.0), 0.0);
+        assert_eq!(finite_or(3.5, 0.0), 3.5);
+        assert!(non_finite_skipped() >= before + 3);
+
+        for undefined in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, (-1.0f64).sqrt(), 0.0f64.ln(), f64::MAX * 2.0] {
+            assert_eq!(format_value(undefined, 2), "–");
+        }
+        assert_eq!(format_value(0.0, 1), "0.0");
+        assert_eq!(format_value(-1.25, 1), "-1.2");
+
+        // Constant, empty and all-undefined windows give plain numbers
+        assert_eq!(calculate_jitter(&[5.0; 10]), 0.0);
+        assert_eq!(calculate_jitter(&[0.0; 10]), 0.0);
+        assert_eq!(calculate_jitter(&[]), 0.0);
+        assert_eq!(calculate_jitter(&[f64::NAN, f64::INFINITY]), 0.0);
+    }
+
+    /// NaN, ±Inf, negative, zero or huge, and an ordinary value half the time
+    fn fuzzed(rng: &mut impl rand::Rng) -> f64 {
+        match rng.gen_range(0..12) {
+            0 => f64::NAN,
+            1 => f64::INFINITY,
+            2 => f64::NEG_INFINITY,
+            3 => -rng.gen_range(0.0..1e6),
+            4 => rng.gen_range(1e15..f64::MAX),
+            5 => 0.0,
+            _ => rng.gen_range(0.0..1000.0),
+        }
+    }
+
+    #[test]
+    fn jitter_is_the_population_standard_deviation_of_finite_values() {
+        assert_eq!(calculate_jitter(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), 2.0);
+        assert_eq!(calculate_jitter(&[1e300, -1e300, f64::NAN]), 1e300);
+        assert_eq!(calculate_jitter(&[f64::NAN, f64::INFINITY]), 0.0);
+    }
+
+    #[test]
+    fn fuzzed_samples_are_rejected_or_kept_sane() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1844);
+        let metrics = MetricsHandle::new();
+        let acceptable = |value: f64| value.is_finite() && (0.0..=MAX_MEASUREMENT).contains(&value);
+
+        let mut latencies = Vec::new();
+        let mut throughputs = Vec::new();
+        let mut accepted = 0;
+        for _ in 0..3000 {
+            let sample = QUICMetrics {
+                latency: fuzzed(&mut rng),
+                throughput: fuzzed(&mut rng),
+                rtt: fuzzed(&mut rng),
+                jitter: fuzzed(&mut rng),
+                handshake_time: fuzzed(&mut rng),
+                packet_loss: if rng.gen_bool(0.5) { rng.gen_range(0.0..2.0) } else { fuzzed(&mut rng) },
+                ..QUICMetrics::default()
+            };
+            latencies.push(sample.latency);
+            throughputs.push(sample.throughput);
+            let valid = [sample.latency, sample.throughput, sample.rtt, sample.jitter, sample.handshake_time]
+                .into_iter()
+                .all(acceptable)
+                && acceptable(sample.packet_loss)
+                && sample.packet_loss <= 1.0;
+            assert_eq!(metrics.update(sample.clone()).is_ok(), valid, "{:?}", sample);
+            accepted += usize::from(valid);
+        }
+        assert!(accepted > 0);
+
+        // Only valid samples are stored
+        let history = metrics.history(None, None, usize::MAX);
+        assert_eq!(history.len(), accepted);
+        assert!(history.iter().all(|sample| sample.validate().is_ok()));
+
+        // The analytics skip what the store would refuse
+        let (p50, p95, p99) = calculate_latency_percentiles(&latencies);
+        assert!(p50.is_finite() && p50 <= p95 && p95 <= p99 && p99.is_finite(), "{} {} {}", p50, p95, p99);
+        let jitter = calculate_jitter(&latencies);
+        assert!(jitter.is_finite() && jitter >= 0.0, "{}", jitter);
+
+        let correlation = crate::correlation_widget::CorrelationWidget::new(String::new())
+            .calculate_correlation(&latencies, &throughputs);
+        assert!((-1.0..=1.0).contains(&correlation), "{}", correlation);
+
+        let mut detector = crate::anomaly_detection::AnomalyDetector::new(0.7);
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        for (i, &latency) in latencies.iter().enumerate() {
+            detector.add_data_point_at("Latency".to_string(), latency, start + chrono::Duration::seconds(i as i64));
+        }
+        for anomaly in detector.anomalies() {
+            assert!(anomaly.value.is_finite(), "{:?}", anomaly);
+            assert!(!anomaly.expected_range.0.is_nan() && !anomaly.expected_range.1.is_nan(), "{:?}", anomaly);
+        }
+    }
+
+    /// Lifecycle counters of `source` at `secs` seconds into the run
+    fn counters(source: Option<&str>, secs: i64, opened: u64, failed: u64, closed: u64) -> QUICMetrics {
+        QUICMetrics {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+            connections_opened: Some(opened),
+            connections_failed: Some(failed),
+            connections_closed: Some(closed),
+            source: source.map(str::to_string),
+            ..QUICMetrics::default()
+        }
+    }
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    fn lifecycle(samples: &[QUICMetrics]) -> ConnectionStats {
+        let mut lifecycle = ConnectionLifecycle::default();
+        for sample in samples {
+            lifecycle.record(sample, WINDOW);
+        }
+        lifecycle.stats(WINDOW)
+    }
+
+    #[test]
+    fn first_sample_is_only_a_baseline() {
+        let stats = lifecycle(&[counters(None, 0, 100, 10, 20)]);
+        assert_eq!((stats.opened, stats.failed, stats.closed, stats.active), (Some(100), Some(10), Some(20), Some(70)));
+        assert_eq!(stats.success_rate, None);
+        assert_eq!(stats.window_attempts, 0);
+    }
+
+    #[test]
+    fn success_rate_is_the_share_of_new_attempts_that_did_not_fail() {
+        let stats = lifecycle(&[
+            counters(None, 0, 100, 10, 0),
+            counters(None, 1, 110, 11, 0),
+            counters(None, 2, 120, 13, 0),
+        ]);
+        assert_eq!(stats.window_attempts, 20);
+        assert_close(stats.success_rate.unwrap(), 0.85);
+    }
+
+    #[test]
+    fn counter_reset_counts_from_zero() {
+        // The sender restarted between the second and third sample
+        let stats = lifecycle(&[
+            counters(None, 0, 100, 10, 50),
+            counters(None, 1, 110, 10, 55),
+            counters(None, 2, 4, 2, 0),
+            counters(None, 3, 10, 2, 1),
+        ]);
+        assert_eq!(stats.window_attempts, 10 + 4 + 6);
+        assert_close(stats.success_rate.unwrap(), 18.0 / 20.0);
+        // Totals are the restarted sender's own
+        assert_eq!((stats.opened, stats.failed, stats.active), (Some(10), Some(2), Some(7)));
+    }
+
+    #[test]
+    fn reset_to_a_higher_failure_count_never_goes_below_zero() {
+        // Restarted with more failures than attempts since the last sample
+        let stats = lifecycle(&[counters(None, 0, 100, 90, 0), counters(None, 1, 2, 5, 0)]);
+        assert_eq!(stats.success_rate, Some(0.0));
+        assert_eq!(stats.active, Some(0));
+    }
+
+    #[test]
+    fn window_covers_only_recent_attempts() {
+        let stats = lifecycle(&[
+            counters(None, 0, 0, 0, 0),
+            counters(None, 1, 10, 10, 0),
+            counters(None, 100, 20, 10, 0),
+        ]);
+        assert_eq!(stats.window_attempts, 10);
+        assert_eq!(stats.success_rate, Some(1.0));
+        assert_eq!(stats.window_secs, 60);
+    }
+
+    #[test]
+    fn interleaved_senders_do_not_subtract_from_each_other() {
+        let stats = lifecycle(&[
+            counters(Some("a"), 0, 1000, 0, 0),
+            counters(Some("b"), 0, 10, 0, 0),
+            counters(Some("a"), 1, 1010, 0, 0),
+            counters(Some("b"), 1, 20, 5, 0),
+        ]);
+        assert_eq!(stats.window_attempts, 20);
+        assert_close(stats.success_rate.unwrap(), 0.75);
+        assert_eq!(stats.opened, Some(1030));
+    }
+
+    #[test]
+    fn gauge_only_senders_have_no_success_rate() {
+        let stats = lifecycle(&[sample(1.0), sample(2.0)]);
+        assert!(!stats.is_reported());
+        assert_eq!(stats.success_rate, None);
+
+        // One sender without the closed counter leaves active unknown
+        let mut partial = counters(Some("b"), 0, 5, 0, 0);
+        partial.connections_closed = None;
+        let stats = lifecycle(&[counters(Some("a"), 0, 5, 0, 0), partial]);
+        assert_eq!(stats.active, None);
+        assert_eq!(stats.opened, Some(10));
+    }
+
+    #[test]
+    fn handle_reset_starts_the_success_rate_over() {
+        let metrics = MetricsHandle::new();
+        metrics.update(counters(None, 0, 10, 0, 0)).unwrap();
+        metrics.update(counters(None, 1, 20, 5, 0)).unwrap();
+        assert_close(metrics.connection_stats().success_rate.unwrap(), 0.5);
+
+        metrics.reset(ResetScope::default(), "test");
+        assert!(!metrics.connection_stats().is_reported());
+        metrics.update(counters(None, 2, 30, 5, 0)).unwrap();
+        assert_eq!(metrics.connection_stats().success_rate, None);
+    }
+
+    /// Draw from one of several shapes: uniform, heavy-tailed, bimodal, drifting or few distinct values
+    fn shaped(rng: &mut impl rand::Rng, shape: usize, step: usize) -> f64 {
+        match shape {
+            0 => rng.gen_range(0.0..100.0),
+            1 => -rng.gen_range(f64::EPSILON..1.0f64).ln() * 50.0,
+            2 => rng.gen_range(0.0..10.0) + if rng.gen_bool(0.3) { 1000.0 } else { 0.0 },
+            3 => step as f64 * 0.5 + rng.gen_range(-5.0..5.0),
+            _ => f64::from(rng.gen_range(0..5)),
+        }
+    }
+
+    /// Worst rank error of `stats`' percentiles against sorting `window`, in samples
+    fn rank_error(stats: &StreamingStats, window: &[f64]) -> f64 {
+        let mut sorted = window.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mut worst: f64 = 0.0;
+        for p in [0.0, 0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.95, 0.99, 1.0] {
+            let estimate = stats.percentile(p);
+            let exact = percentile(&sorted, p).unwrap();
+            if estimate == exact {
+                continue;
+            }
+            // Ranks the estimate could have in the window, interpolated between neighbours
+            let above = sorted.partition_point(|v| *v < estimate);
+            let (low, high) = if sorted.get(above) == Some(&estimate) {
+                (above as f64, sorted.partition_point(|v| *v <= estimate) as f64 - 1.0)
+            } else {
+                let (below, next) = (sorted[above - 1], sorted[above]);
+                let rank = (above - 1) as f64 + (estimate - below) / (next - below);
+                (rank, rank)
+            };
+            let rank = p * (sorted.len() - 1) as f64;
+            let error = if rank < low { low - rank } else if rank > high { rank - high } else { 0.0 };
+            worst = worst.max(error);
+        }
+        worst
+    }
+
+    #[test]
+    fn streaming_stats_track_a_sliding_window() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1762);
+        for shape in 0..5 {
+            for size in [1, 10, 50, 200, 1000] {
+                let mut stats = StreamingStats::new();
+                let mut window: VecDeque<f64> = VecDeque::new();
+                for step in 0..size * 4 {
+                    let value = shaped(&mut rng, shape, step);
+                    stats.push(value);
+                    window.push_back(value);
+                    if window.len() > size {
+                        // Mostly the oldest, sometimes one from the middle as a backfill would
+                        let index = if rng.gen_bool(0.1) { rng.gen_range(0..window.len()) } else { 0 };
+                        stats.remove(window.remove(index).unwrap());
+                    }
+                    if step % 7 != 0 {
+                        continue;
+                    }
+
+                    let values: Vec<f64> = window.iter().copied().collect();
+                    assert_eq!(stats.count(), values.len());
+                    let mean = values.iter().sum::<f64>() / values.len() as f64;
+                    assert!((stats.mean() - mean).abs() <= 1e-6 * mean.abs().max(1.0), "{} vs {}", stats.mean(), mean);
+                    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                    assert_eq!((stats.min(), stats.max()), (Some(min), Some(max)));
+
+                    let error = rank_error(&stats, &values);
+                    // Exact while every centroid holds one sample, within 1% of the window beyond
+                    if values.len() <= crate::tdigest::COMPRESSION as usize {
+                        assert!(error < 1e-6, "shape {} size {} step {}: {}", shape, size, step, error);
+                    }
+                    assert!(error <= 0.01 * values.len() as f64, "shape {} size {} step {}: {}", shape, size, step, error);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streaming_stats_forget_everything_removed() {
+        let mut stats = StreamingStats::new();
+        for value in [3.0, -0.0, 3.0, 7.5] {
+            stats.push(value);
+        }
+        stats.push(f64::NAN);
+        assert_eq!(stats.count(), 4);
+        // Not in the window: ignored
+        stats.remove(4.0);
+        for value in [3.0, 0.0, 7.5] {
+            stats.remove(value);
+        }
+        assert_eq!((stats.count(), stats.min(), stats.max()), (1, Some(3.0), Some(3.0)));
+        assert_eq!(stats.percentiles(), (3.0, 3.0, 3.0));
+        stats.remove(3.0);
+        assert_eq!((stats.count(), stats.min(), stats.percentile(0.5)), (0, None, 0.0));
+    }
+
+    #[test]
+    fn bbrv3_columns_name_every_field() {
+        let all = BBRv3Metrics {
+            phase: Some("ProbeBW".to_string()),
+            bw_fast: Some(1.0),
+            bw_slow: Some(1.0),
+            loss_rate_round: Some(0.0),
+            loss_rate_ema: Some(0.0),
+            loss_threshold: Some(0.02),
+            headroom_usage: Some(0.5),
+            inflight_target: Some(1.0),
+            pacing_quantum: Some(1),
+            pacing_gain: Some(1.0),
+            cwnd_gain: Some(2.0),
+            probe_rtt_min_ms: Some(1.0),
+            bufferbloat_factor: Some(0.1),
+            stability_index: Some(1.0),
+            phase_duration_ms: Some(HashMap::new()),
+            recovery_time_ms: Some(1.0),
+            loss_recovery_efficiency: Some(1.0),
+        };
+        let value = serde_json::to_value(all).unwrap();
+        let keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(keys, BBRV3_COLUMNS);
+    }
+
+    fn history_of(latencies: impl IntoIterator<Item = i64>, capacity: usize) -> MetricsHistory<QUICMetrics> {
+        let mut history = MetricsHistory::new(capacity);
+        for secs in latencies {
+            history.push(QUICMetrics {
+                latency: secs as f64,
+                timestamp: DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap(),
+                ..QUICMetrics::default()
+            });
+        }
+        history
+    }
+
+    fn latencies<'a>(samples: impl Iterator<Item = &'a QUICMetrics>) -> Vec<f64> {
+        samples.map(|sample| sample.latency).collect()
+    }
+
+    #[test]
+    fn history_range_and_catch_up() {
+        let mut history = history_of(1..=5, 10);
+        let ms = |secs: i64| (1_700_000_000 + secs) * 1000;
+        assert_eq!(latencies(history.range(Some(ms(2)), Some(ms(4)))), [2.0, 3.0, 4.0]);
+        assert_eq!(latencies(history.range(None, Some(ms(1)))), [1.0]);
+        assert_eq!(latencies(history.range(Some(ms(5)), None)), [5.0]);
+        assert!(history.range(Some(ms(6)), None).next().is_none());
+
+        let mark = history.total_pushed();
+        assert!(history.iter_since(mark).next().is_none());
+        history.push(sample(6.0));
+        history.push(sample(7.0));
+        assert_eq!(latencies(history.iter_since(mark)), [6.0, 7.0]);
+        // Samples evicted since the mark are skipped
+        let mut small = history_of(1..=3, 3);
+        let mark = small.total_pushed() - 2;
+        for secs in 4..=8 {
+            small.push(sample(secs as f64));
+        }
+        assert_eq!(latencies(small.iter_since(mark)), [6.0, 7.0, 8.0]);
+        assert!(history_of(0..0, 3).range(None, None).next().is_none());
+    }
+
+    #[test]
+    fn history_evicts_the_oldest_at_capacity() {
+        let mut history = history_of(1..=3, 3);
+        assert_eq!((history.len(), history.capacity()), (3, 3));
+        history.push(sample(4.0));
+        assert_eq!(latencies(history.iter()), [2.0, 3.0, 4.0]);
+        assert_eq!(history.latest().unwrap().latency, 4.0);
+
+        // Wrapping around many times keeps the newest, in order, without growing
+        let allocated = history.samples.capacity();
+        for latency in 5..=1000 {
+            history.push(sample(latency as f64));
+        }
+        assert_eq!(latencies(history.iter()), [998.0, 999.0, 1000.0]);
+        assert_eq!(history.samples.capacity(), allocated);
+        assert_eq!(history.total_pushed(), 1000);
+
+        let mut none = MetricsHistory::new(0);
+        none.push(sample(1.0));
+        assert!(none.is_empty());
+        assert_eq!(none.total_pushed(), 1);
+    }
+
+    #[test]
+    fn history_push_takes_constant_time() {
+        // Pushing into a full history as large as the live one costs the same as into a tiny one
+        let time = |capacity: usize| {
+            let mut history = MetricsHistory::new(capacity);
+            for i in 0..capacity {
+                history.push(i);
+            }
+            let started = std::time::Instant::now();
+            for i in 0..200_000 {
+                history.push(i);
+            }
+            started.elapsed()
+        };
+        let small = time(16);
+        let large = time(1_000_000);
+        // Shifting the buffer on each push, as `Vec::remove(0)` did, would be thousands of times slower
+        assert!(large < small * 20 + std::time::Duration::from_millis(50), "{:?} vs {:?}", large, small);
+    }
+
+    #[test]
+    fn history_iter_recent_stops_at_what_it_holds() {
+        let history = history_of(1..=5, 4);
+        assert_eq!(latencies(history.iter_recent(2)), [4.0, 5.0]);
+        assert_eq!(latencies(history.iter_recent(4)), [2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(latencies(history.iter_recent(100)), [2.0, 3.0, 4.0, 5.0]);
+        assert!(history.iter_recent(0).next().is_none());
+        assert_eq!(latencies(history.iter_recent(3).rev()), [5.0, 4.0, 3.0]);
+
+        let mut cleared = history.clone();
+        cleared.clear();
+        assert!(cleared.iter_recent(3).next().is_none());
+        assert_eq!(cleared.total_pushed(), 5);
+    }
 }
@@ -0,0 +1,92 @@
+//! Mouse hit-testing over the widgets drawn in the last frame
+//!
+//! Layouts are recomputed on every draw from the terminal size, so a mode
+//! records where each clickable widget landed while drawing and looks mouse
+//! events up against that frame's areas. The dashboards key their areas by
+//! `focus::Panel`.
+
+use ratatui::layout::{Position, Rect};
+use std::cell::RefCell;
+
+/// Areas of the last frame's widgets
+///
+/// Recorded from the `&self` draw path, hence the interior mutability.
+#[derive(Debug)]
+pub struct HitMap<T> {
+    regions: RefCell<Vec<(T, Rect)>>,
+}
+
+impl<T> Default for HitMap<T> {
+    fn default() -> Self {
+        Self { regions: RefCell::new(Vec::new()) }
+    }
+}
+
+impl<T: Copy> HitMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the previous frame; call before drawing a new one
+    pub fn clear(&self) {
+        self.regions.borrow_mut().clear();
+    }
+
+    /// Note that `target` was drawn over `area`
+    pub fn record(&self, target: T, area: Rect) {
+        self.regions.borrow_mut().push((target, area));
+    }
+
+    /// The target under the terminal cell at `column`, `row` and its area
+    ///
+    /// Later records are drawn on top, so they win where areas overlap.
+    pub fn hit(&self, column: u16, row: u16) -> Option<(T, Rect)> {
+        self.regions
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(_, area)| area.contains(Position::new(column, row)))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hits_the_area_under_the_cell() {
+        let map = HitMap::new();
+        assert_eq!(map.hit(0, 0), None);
+
+        let left = Rect::new(0, 0, 40, 20);
+        let right = Rect::new(40, 0, 40, 20);
+        map.record('l', left);
+        map.record('r', right);
+        assert_eq!(map.hit(0, 0), Some(('l', left)));
+        assert_eq!(map.hit(39, 19), Some(('l', left)));
+        // Right and bottom edges belong to the next area
+        assert_eq!(map.hit(40, 0), Some(('r', right)));
+        assert_eq!(map.hit(79, 19), Some(('r', right)));
+        assert_eq!(map.hit(80, 5), None);
+        assert_eq!(map.hit(5, 20), None);
+    }
+
+    #[test]
+    fn later_records_win_and_clear_forgets_the_frame() {
+        let map = HitMap::new();
+        let screen = Rect::new(0, 0, 80, 24);
+        let popup = Rect::new(20, 5, 40, 10);
+        map.record(1, screen);
+        map.record(2, popup);
+        assert_eq!(map.hit(30, 8), Some((2, popup)));
+        assert_eq!(map.hit(10, 8), Some((1, screen)));
+
+        // Empty areas are never hit
+        map.record(3, Rect::new(0, 0, 0, 0));
+        assert_eq!(map.hit(0, 0), Some((1, screen)));
+
+        map.clear();
+        assert_eq!(map.hit(30, 8), None);
+    }
+}
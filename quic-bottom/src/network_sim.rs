@@ -0,0 +1,311 @@
+//! Simulated network conditions layered over the incoming metrics
+//!
+//! A `NetworkProfile` adds latency, jitter and packet loss to every sample
+//! and caps its throughput, so a dashboard shows what the same run would
+//! look like on a worse link. Six profiles are built in; `[network.profiles]`
+//! adds more or replaces built-in ones by name:
+//!
+//! ```toml
+//! [network]
+//! profile = "lab-wifi"
+//!
+//! [network.profiles.lab-wifi]
+//! added_latency_ms = 8.0
+//! jitter_ms = 4.0
+//! added_loss_pct = 0.5
+//! bandwidth_cap_mbps = 300.0
+//! ```
+//!
+//! +/- step through the profiles in the TUI, and the live mode's
+//! `PUT /api/simulation` switches them remotely.
+
+use anyhow::{anyhow, bail, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::units::Unit;
+
+/// Conditions one profile simulates
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkProfile {
+    /// Taken from the `[network.profiles]` key for configured profiles
+    #[serde(skip_deserializing, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    pub added_latency_ms: f64,
+    /// Added packet loss, in percent
+    pub added_loss_pct: f64,
+    /// Throughput is clamped to this; no cap when unset
+    pub bandwidth_cap_mbps: Option<f64>,
+    /// Latency varies by up to this much either way, in ms
+    pub jitter_ms: f64,
+}
+
+impl Default for NetworkProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            added_latency_ms: 0.0,
+            added_loss_pct: 0.0,
+            bandwidth_cap_mbps: None,
+            jitter_ms: 0.0,
+        }
+    }
+}
+
+impl NetworkProfile {
+    fn new(name: &str, added_latency_ms: f64, jitter_ms: f64, added_loss_pct: f64, bandwidth_cap_mbps: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            added_latency_ms,
+            added_loss_pct,
+            bandwidth_cap_mbps: Some(bandwidth_cap_mbps),
+            jitter_ms,
+        }
+    }
+
+    /// The built-in profiles, best first, in the order +/- cycles through them
+    pub fn builtin() -> Vec<NetworkProfile> {
+        vec![
+            NetworkProfile::new("excellent", 5.0, 1.0, 0.1, 1000.0),
+            NetworkProfile::new("good", 20.0, 5.0, 1.0, 100.0),
+            NetworkProfile::new("poor", 100.0, 20.0, 5.0, 10.0),
+            NetworkProfile::new("mobile", 200.0, 50.0, 10.0, 5.0),
+            NetworkProfile::new("satellite", 500.0, 30.0, 2.0, 2.0),
+            NetworkProfile::new("adversarial", 1000.0, 200.0, 20.0, 1.0),
+        ]
+    }
+
+    /// Check that every value is a number in range
+    pub fn validate(&self) -> Result<()> {
+        for (key, value) in [
+            ("added_latency_ms", self.added_latency_ms),
+            ("jitter_ms", self.jitter_ms),
+            ("added_loss_pct", self.added_loss_pct),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                bail!("network profile '{}': {} must be a number >= 0, got {}", self.name, key, value);
+            }
+        }
+        if self.added_loss_pct > 100.0 {
+            bail!("network profile '{}': added_loss_pct must be at most 100, got {}", self.name, self.added_loss_pct);
+        }
+        if let Some(cap) = self.bandwidth_cap_mbps {
+            if !(cap.is_finite() && cap > 0.0) {
+                bail!("network profile '{}': bandwidth_cap_mbps must be above 0, got {}", self.name, cap);
+            }
+        }
+        Ok(())
+    }
+
+    /// `(latency, throughput, loss)` through this profile, with throughput in
+    /// bits per second and loss a fraction
+    pub fn apply(&self, latency: f64, throughput: f64, loss: f64) -> (f64, f64, f64) {
+        let jitter = if self.jitter_ms > 0.0 {
+            rand::thread_rng().gen_range(-self.jitter_ms..=self.jitter_ms)
+        } else {
+            0.0
+        };
+        let mut throughput = throughput * (1.0 - self.added_loss_pct / 100.0);
+        if let Some(cap) = self.bandwidth_cap_mbps {
+            throughput = throughput.min(Unit::Mbps.to_bps(cap));
+        }
+        (
+            (latency + self.added_latency_ms + jitter).max(0.0),
+            throughput,
+            (loss + self.added_loss_pct / 100.0).min(1.0),
+        )
+    }
+}
+
+/// `[network]` section of the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSimConfig {
+    /// Profile selected at startup
+    pub profile: String,
+    /// Profiles besides the built-in ones, by name; a built-in name replaces that profile
+    pub profiles: BTreeMap<String, NetworkProfile>,
+}
+
+impl Default for NetworkSimConfig {
+    fn default() -> Self {
+        Self {
+            profile: "good".to_string(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl NetworkSimConfig {
+    /// Built-in profiles, with configured ones replacing or following them
+    pub fn all_profiles(&self) -> Vec<NetworkProfile> {
+        let mut profiles = NetworkProfile::builtin();
+        for (name, configured) in &self.profiles {
+            let profile = NetworkProfile {
+                name: name.clone(),
+                ..configured.clone()
+            };
+            match profiles.iter_mut().find(|p| p.name == *name) {
+                Some(builtin) => *builtin = profile,
+                None => profiles.push(profile),
+            }
+        }
+        profiles
+    }
+
+    /// Check the configured profiles and that the startup profile exists
+    pub fn validate(&self) -> Result<()> {
+        let profiles = self.all_profiles();
+        for profile in &profiles {
+            if profile.name.trim().is_empty() {
+                bail!("network.profiles: profile names must not be empty");
+            }
+            profile.validate()?;
+        }
+        if !profiles.iter().any(|p| p.name == self.profile) {
+            bail!("network.profile '{}' is neither built in nor in [network.profiles]", self.profile);
+        }
+        Ok(())
+    }
+}
+
+/// The selected profile and whether it is applied
+#[derive(Debug, Clone)]
+pub struct NetworkSimulation {
+    pub active: bool,
+    profiles: Vec<NetworkProfile>,
+    current: usize,
+    /// Bumped on every change, so a view can tell one happened elsewhere
+    revision: u64,
+}
+
+impl Default for NetworkSimulation {
+    fn default() -> Self {
+        Self::from_config(&NetworkSimConfig::default())
+    }
+}
+
+impl NetworkSimulation {
+    /// Off, with the configured profiles and startup profile
+    pub fn from_config(config: &NetworkSimConfig) -> Self {
+        let profiles = config.all_profiles();
+        let current = profiles.iter().position(|p| p.name == config.profile).unwrap_or(0);
+        Self {
+            active: false,
+            profiles,
+            current,
+            revision: 0,
+        }
+    }
+
+    pub fn profile(&self) -> &NetworkProfile {
+        &self.profiles[self.current]
+    }
+
+    pub fn profiles(&self) -> &[NetworkProfile] {
+        &self.profiles
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_active(!self.active);
+    }
+
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+        self.revision += 1;
+    }
+
+    pub fn next_preset(&mut self) {
+        self.current = (self.current + 1) % self.profiles.len();
+        self.revision += 1;
+    }
+
+    pub fn prev_preset(&mut self) {
+        self.current = (self.current + self.profiles.len() - 1) % self.profiles.len();
+        self.revision += 1;
+    }
+
+    /// Switch to the named profile
+    pub fn select(&mut self, name: &str) -> Result<()> {
+        let index = self.profiles.iter().position(|p| p.name == name).ok_or_else(|| {
+            let names: Vec<&str> = self.profiles.iter().map(|p| p.name.as_str()).collect();
+            anyhow!("unknown network profile '{}' (expected one of {})", name, names.join(", "))
+        })?;
+        self.current = index;
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// `(latency, throughput, loss)` as seen through the simulated network
+    ///
+    /// Throughput is in bits per second and loss a fraction; everything
+    /// passes through unchanged while the simulation is off.
+    pub fn apply(&self, latency: f64, throughput: f64, loss: f64) -> (f64, f64, f64) {
+        if self.active {
+            self.profile().apply(latency, throughput, loss)
+        } else {
+            (latency, throughput, loss)
+        }
+    }
+
+    /// `Network Simulation: ACTIVE` and the profile, one line each
+    pub fn status_text(&self) -> String {
+        let profile = self.profile();
+        let cap = profile
+            .bandwidth_cap_mbps
+            .map_or("none".to_string(), |cap| format!("{:.1} Mbps", cap));
+        format!(
+            "Network Simulation: {}\nProfile: {} ({}/{})\nAdded Latency: {:.1}ms ±{:.1}ms\nAdded Loss: {:.1}%\nBandwidth Cap: {}",
+            if self.active { "ACTIVE" } else { "INACTIVE" },
+            profile.name,
+            self.current + 1,
+            self.profiles.len(),
+            profile.added_latency_ms,
+            profile.jitter_ms,
+            profile.added_loss_pct,
+            cap
+        )
+    }
+
+    /// `GET`/`PUT /api/simulation` body
+    pub fn state(&self) -> SimulationState {
+        SimulationState {
+            active: self.active,
+            profile: self.profile().clone(),
+            profiles: self.profiles.iter().map(|p| p.name.clone()).collect(),
+        }
+    }
+
+    /// Apply a `PUT /api/simulation` request; nothing changes when it names an unknown profile
+    pub fn update(&mut self, request: &SimulationRequest) -> Result<()> {
+        if let Some(name) = &request.profile {
+            self.select(name)?;
+        }
+        if let Some(active) = request.active {
+            self.set_active(active);
+        }
+        Ok(())
+    }
+}
+
+/// State of the simulation as the API reports it
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationState {
+    pub active: bool,
+    pub profile: NetworkProfile,
+    /// Names of every profile, in +/- order
+    pub profiles: Vec<String>,
+}
+
+/// `PUT /api/simulation` body; fields left out are unchanged
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SimulationRequest {
+    pub active: Option<bool>,
+    pub profile: Option<String>,
+}
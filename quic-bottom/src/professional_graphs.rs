@@ -4,31 +4,44 @@
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::Marker,
     text::{Line, Span},
     widgets::{Block, Borders, Dataset, GraphType, Paragraph},
     Frame,
 };
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
-use std::time::Instant;
+use std::time::Duration;
+
+use crate::axis_scale::AxisScale;
+use crate::metrics::{format_clock, format_span, format_value, StreamingStats};
+use crate::theme::Theme;
+use crate::time_view::{TimeNav, TimeView, DEFAULT_RETENTION, MIN_VIEW_WIDTH};
+use crate::units::Unit;
 
 /// Professional time graph for QUIC metrics
 pub struct ProfessionalTimeGraph {
-    /// Historical data points
-    pub data_points: VecDeque<(Instant, f64)>,
+    /// Historical data points, oldest first
+    pub data_points: VecDeque<(DateTime<Utc>, f64)>,
     
     /// Maximum number of data points to keep
     pub max_points: usize,
     
-    /// Current time window (seconds)
-    pub time_window: f64,
+    /// Points older than this, relative to the newest point, are dropped
+    pub retention: Duration,
+
+    /// Visible slice of the retained points
+    pub view: TimeView,
     
     /// Y-axis bounds
     pub y_bounds: (f64, f64),
+
+    /// Linear or log value axis
+    pub scale: AxisScale,
     
-    /// Graph style
-    pub style: Style,
+    /// Draw the line in the theme's secondary color rather than its primary one
+    pub secondary: bool,
     
     /// Title
     pub title: String,
@@ -38,6 +51,9 @@ pub struct ProfessionalTimeGraph {
     
     /// Whether graph is expanded
     pub is_expanded: bool,
+
+    /// Running statistics over `data_points`
+    stats: StreamingStats,
 }
 
 impl ProfessionalTimeGraph {
@@ -45,97 +61,138 @@ impl ProfessionalTimeGraph {
         Self {
             data_points: VecDeque::with_capacity(max_points),
             max_points,
-            time_window,
+            retention: DEFAULT_RETENTION,
+            view: TimeView::new(seconds(time_window), DEFAULT_RETENTION),
             y_bounds: (0.0, 100.0),
-            style: Style::default().fg(Color::Green),
+            scale: AxisScale::Linear,
+            secondary: false,
             title,
             is_selected: false,
             is_expanded: false,
+            stats: StreamingStats::new(),
+        }
+    }
+
+    /// Change the visible time window (seconds)
+    pub fn set_time_window(&mut self, time_window: f64) {
+        self.view.width = seconds(time_window).clamp(MIN_VIEW_WIDTH, self.view.max_width);
+    }
+
+    pub fn with_scale(mut self, scale: AxisScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Switch between linear and log value axes
+    pub fn toggle_scale(&mut self) {
+        self.scale = self.scale.toggled();
+    }
+
+    /// Zoom or pan the visible window
+    pub fn navigate(&mut self, nav: TimeNav) {
+        if let (Some((oldest, _)), Some((newest, _))) = (self.data_points.front(), self.data_points.back()) {
+            self.view.navigate(nav, *oldest, *newest);
         }
     }
 
-    /// Add new data point
+    /// Add new data point, timestamped now
     pub fn add_data_point(&mut self, value: f64) {
-        let now = Instant::now();
-        self.data_points.push_back((now, value));
+        self.add_data_point_at(Utc::now(), value);
+    }
+
+    /// Add a data point observed at `timestamp`, keeping points in time order
+    pub fn add_data_point_at(&mut self, timestamp: DateTime<Utc>, value: f64) {
+        let index = self.data_points.partition_point(|(t, _)| *t <= timestamp);
+        self.data_points.insert(index, (timestamp, value));
+        self.stats.push(value);
+        self.trim();
         
-        // Keep only recent data within time window
-        while let Some(&(time, _)) = self.data_points.front() {
-            match now.duration_since(time) {
-                Ok(duration) => {
-                    if duration.as_secs_f64() > self.time_window {
-                        self.data_points.pop_front();
-                    } else {
-                        break;
-                    }
-                }
-                Err(_) => break,
+        // Update y bounds based on current data
+        self.update_y_bounds();
+    }
+
+    /// Drop points outside the retention period and beyond `max_points`
+    fn trim(&mut self) {
+        let cutoff = self
+            .window_end()
+            .and_then(|end| end.checked_sub_signed(chrono::Duration::from_std(self.retention).ok()?));
+        while let Some(&(oldest, value)) = self.data_points.front() {
+            let expired = cutoff.is_some_and(|cutoff| oldest < cutoff);
+            if !expired && self.data_points.len() <= self.max_points {
+                break;
             }
-        }
-        
-        // Also limit by max_points
-        while self.data_points.len() > self.max_points {
             self.data_points.pop_front();
+            self.stats.remove(value);
+        }
+    }
+
+    /// Timestamp of the newest point, the right edge of the x-axis
+    fn window_end(&self) -> Option<DateTime<Utc>> {
+        self.data_points.back().map(|(t, _)| *t)
+    }
+
+    /// Time between the oldest and newest points
+    pub fn span(&self) -> Duration {
+        match (self.data_points.front(), self.data_points.back()) {
+            (Some((first, _)), Some((last, _))) => (*last - *first).to_std().unwrap_or_default(),
+            _ => Duration::ZERO,
         }
-        
-        // Update y bounds based on current data
-        self.update_y_bounds();
     }
 
     /// Update Y-axis bounds based on current data
     fn update_y_bounds(&mut self) {
-        if self.data_points.is_empty() {
+        let (Some(min_val), Some(max_val)) = (self.stats.min(), self.stats.max()) else {
             return;
-        }
-        
-        let values: Vec<f64> = self.data_points.iter().map(|(_, v)| *v).collect();
-        let min_val = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_val = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        };
         
-        // Add some padding
-        let padding = (max_val - min_val) * 0.1;
-        self.y_bounds = (min_val - padding, max_val + padding);
+        // Add some padding; flat series still get a non-empty range
+        let padding = if max_val > min_val {
+            (max_val - min_val) * 0.1
+        } else {
+            (max_val.abs() * 0.1).max(1.0)
+        };
+        let bounds = (min_val - padding, max_val + padding);
+        // Padding a range near f64::MAX overflows; fall back to the bare range
+        if bounds.0.is_finite() && bounds.1.is_finite() {
+            self.y_bounds = bounds;
+        } else {
+            self.y_bounds = (min_val, max_val);
+        }
     }
 
     /// Get analytics for the current data
     pub fn get_analytics(&self) -> GraphAnalytics {
-        if self.data_points.is_empty() {
+        if self.stats.count() == 0 {
             return GraphAnalytics::default();
         }
-        
-        let values: Vec<f64> = self.data_points.iter().map(|(_, v)| *v).collect();
-        let current = *values.last().unwrap();
-        let average = values.iter().sum::<f64>() / values.len() as f64;
-        let min = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        
-        // Calculate percentiles
-        let mut sorted_values = values.clone();
-        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let p50 = percentile(&sorted_values, 0.5);
-        let p95 = percentile(&sorted_values, 0.95);
-        let p99 = percentile(&sorted_values, 0.99);
-        
-        // Calculate trend (simple linear regression)
-        let trend = calculate_trend(&values);
+
+        let values: Vec<f64> = self
+            .data_points
+            .iter()
+            .map(|(_, v)| *v)
+            .filter(|v| v.is_finite())
+            .collect();
+        let (p50, p95, p99) = self.stats.percentiles();
         
         GraphAnalytics {
-            current,
-            average,
-            min,
-            max,
+            current: values.last().copied().unwrap_or(0.0),
+            average: self.stats.mean(),
+            min: self.stats.min().unwrap_or(0.0),
+            max: self.stats.max().unwrap_or(0.0),
             p50,
             p95,
             p99,
-            trend,
-            data_points: values.len(),
+            // Simple linear regression over sample order
+            trend: calculate_trend(&values),
+            data_points: self.stats.count(),
+            span: self.span(),
         }
     }
 
     /// Render the professional graph
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         if self.data_points.is_empty() {
-            self.render_empty_state(f, area);
+            self.render_empty_state(f, area, theme);
             return;
         }
 
@@ -149,101 +206,119 @@ impl ProfessionalTimeGraph {
             .split(area);
 
         // Title
-        self.render_title(f, chunks[0]);
+        self.render_title(f, chunks[0], theme);
         
         // Graph
-        self.render_graph(f, chunks[1]);
+        self.render_graph(f, chunks[1], theme);
         
         // Analytics
-        self.render_analytics(f, chunks[2]);
+        self.render_analytics(f, chunks[2], theme);
     }
 
-    fn render_title(&self, f: &mut Frame, area: Rect) {
+    fn render_title(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let title_style = if self.is_selected {
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            theme.title(theme.accent)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.text)
         };
         
-        let title = Paragraph::new(self.title.clone())
+        let mut spans = vec![
+            Span::styled(self.title.clone(), title_style),
+            Span::styled(
+                format!("  [{} | {}]", format_span(self.view.width), self.scale.name()),
+                Style::default().fg(theme.muted),
+            ),
+        ];
+        if let Some(newest) = self.window_end().filter(|_| !self.view.is_live()) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!(" HISTORY (-{}) ", format_span(self.view.lag_behind(newest))),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        
+        let title = Paragraph::new(Line::from(spans))
             .style(title_style)
             .block(Block::default()
                 .borders(Borders::ALL)
                 .border_style(if self.is_selected { 
-                    Style::default().fg(Color::Yellow) 
+                    Style::default().fg(theme.accent) 
                 } else { 
-                    Style::default().fg(Color::Gray) 
+                    Style::default().fg(theme.muted) 
                 }));
         f.render_widget(title, area);
     }
 
-    fn render_graph(&self, f: &mut Frame, area: Rect) {
-        let now = Instant::now();
-        let time_start = -self.time_window;
-        
-        // Convert data to chart format
+    fn render_graph(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let style = Style::default().fg(if self.secondary { theme.secondary } else { theme.primary });
+        let (Some((oldest, _)), Some((newest, _))) = (self.data_points.front(), self.data_points.back()) else {
+            return;
+        };
+        let (start, end) = self.view.bounds(*oldest, *newest);
+
+        // Plot against unix milliseconds so gaps and bursts keep their real spacing;
+        // one point either side of the window keeps the line running to the edges
+        let first = self.data_points.partition_point(|(t, _)| *t < start).saturating_sub(1);
+        let last = self.data_points.partition_point(|(t, _)| *t <= end);
         let data: Vec<(f64, f64)> = self.data_points
-            .iter()
-            .filter_map(|(time, value)| {
-                now.duration_since(*time).ok().map(|duration| {
-                    let time_offset = duration.as_secs_f64();
-                    (-time_offset, *value)
-                })
-            })
+            .range(first..(last + 1).min(self.data_points.len()))
+            .filter(|(_, value)| value.is_finite())
+            .map(|(time, value)| (time.timestamp_millis() as f64, self.scale.transform(*value)))
             .collect();
 
-        if data.is_empty() {
-            return;
-        }
-
         let dataset = Dataset::default()
             .data(&data)
-            .style(self.style)
+            .style(style)
             .graph_type(GraphType::Line)
             .marker(Marker::Braille);
 
+        let middle = start + (end - start) / 2;
+
+        let data_range = (
+            self.stats.min().unwrap_or(self.y_bounds.0),
+            self.stats.max().unwrap_or(self.y_bounds.1),
+        );
+        let (y_bounds, y_labels) = self.scale.axis(self.y_bounds, data_range);
+
         // Create chart with professional styling
         let chart = ratatui::widgets::Chart::new(vec![dataset])
             .block(Block::default()
                 .borders(Borders::ALL)
                 .title("Time Series")
-                .title_style(Style::default().fg(Color::Cyan)))
+                .title_style(Style::default().fg(theme.info)))
             .x_axis(ratatui::widgets::Axis::default()
-                .bounds([time_start, 0.0])
-                .labels(vec![
-                    Span::styled(format!("{:.0}s", -self.time_window), self.style),
-                    Span::styled("0s", self.style),
-                ]))
+                .bounds([start.timestamp_millis() as f64, end.timestamp_millis() as f64])
+                .labels([start, middle, end].map(|t| Span::styled(format_clock(t), style))))
             .y_axis(ratatui::widgets::Axis::default()
-                .bounds([self.y_bounds.0, self.y_bounds.1])
-                .labels(vec![
-                    Span::styled(format!("{:.1}", self.y_bounds.0), self.style),
-                    Span::styled(format!("{:.1}", self.y_bounds.1), self.style),
-                ]));
+                .bounds(y_bounds)
+                .labels(y_labels.into_iter().map(|label| Span::styled(label, style))));
 
         f.render_widget(chart, area);
     }
 
-    fn render_analytics(&self, f: &mut Frame, area: Rect) {
+    fn render_analytics(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let analytics = self.get_analytics();
         
         let analytics_text = format!(
-            "Current: {:.2} | Avg: {:.2} | Min: {:.2} | Max: {:.2} | P50: {:.2} | P95: {:.2} | P99: {:.2} | Trend: {:.2}",
-            analytics.current, analytics.average, analytics.min, analytics.max,
-            analytics.p50, analytics.p95, analytics.p99, analytics.trend
+            "Current: {} | Avg: {} | Min: {} | Max: {} | P50: {} | P95: {} | P99: {} | Trend: {} | Span: {}",
+            format_value(analytics.current, 2), format_value(analytics.average, 2),
+            format_value(analytics.min, 2), format_value(analytics.max, 2),
+            format_value(analytics.p50, 2), format_value(analytics.p95, 2),
+            format_value(analytics.p99, 2), format_value(analytics.trend, 2),
+            format_span(analytics.span)
         );
         
         let analytics_paragraph = Paragraph::new(analytics_text)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         
         f.render_widget(analytics_paragraph, area);
     }
 
-    fn render_empty_state(&self, f: &mut Frame, area: Rect) {
+    fn render_empty_state(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let empty_text = "No data available yet...";
         let empty_paragraph = Paragraph::new(empty_text)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(theme.muted))
             .block(Block::default()
                 .borders(Borders::ALL)
                 .title(self.title.as_str()));
@@ -264,16 +339,13 @@ pub struct GraphAnalytics {
     pub p99: f64,
     pub trend: f64,
     pub data_points: usize,
+    /// Time covered by the data points
+    pub span: Duration,
 }
 
-/// Calculate percentile
-fn percentile(sorted_data: &[f64], p: f64) -> f64 {
-    if sorted_data.is_empty() {
-        return 0.0;
-    }
-    
-    let index = (p * (sorted_data.len() - 1) as f64) as usize;
-    sorted_data[index]
+/// `secs` as a `Duration`; negative or NaN becomes zero, overflow saturates
+fn seconds(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs.max(0.0)).unwrap_or(Duration::MAX)
 }
 
 /// Calculate trend using simple linear regression
@@ -322,8 +394,8 @@ impl ProfessionalQuicLatencyGraph {
         self.graph.add_data_point(latency);
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.graph.render(f, area);
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.graph.render(f, area, theme);
     }
 
     pub fn get_analytics(&self) -> GraphAnalytics {
@@ -331,6 +403,12 @@ impl ProfessionalQuicLatencyGraph {
     }
 }
 
+impl Default for ProfessionalQuicLatencyGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Professional QUIC Throughput Graph
 pub struct ProfessionalQuicThroughputGraph {
     graph: ProfessionalTimeGraph,
@@ -339,23 +417,125 @@ pub struct ProfessionalQuicThroughputGraph {
 impl ProfessionalQuicThroughputGraph {
     pub fn new() -> Self {
         Self {
-            graph: ProfessionalTimeGraph::new(
-                "QUIC Throughput (KB/s)".to_string(),
-                1000,
-                60.0, // 60 seconds window
-            ),
+            graph: ProfessionalTimeGraph {
+                secondary: true,
+                ..ProfessionalTimeGraph::new(
+                    "QUIC Throughput (Mbps)".to_string(),
+                    1000,
+                    60.0, // 60 seconds window
+                )
+            },
         }
     }
 
+    /// Add a sample in bits per second
     pub fn add_throughput(&mut self, throughput: f64) {
-        self.graph.add_data_point(throughput);
+        self.graph.add_data_point(Unit::Mbps.from_bps(throughput));
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.graph.render(f, area);
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.graph.render(f, area, theme);
     }
 
     pub fn get_analytics(&self) -> GraphAnalytics {
         self.graph.get_analytics()
     }
 }
+
+impl Default for ProfessionalQuicThroughputGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(1_700_000_000_000 + millis).unwrap()
+    }
+
+    fn rendered(graph: &ProfessionalTimeGraph) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(160, 30)).unwrap();
+        terminal.draw(|f| graph.render(f, f.area(), &Theme::default())).unwrap();
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn axis_labels_are_the_clock_times_of_the_window() {
+        let mut graph = ProfessionalTimeGraph::new("RTT".to_string(), 100, 20.0);
+        for (millis, value) in [(0, 1.0), (50, 2.0), (9_000, 3.0), (9_020, 4.0), (45_000, 5.0), (44_000, 6.0)] {
+            graph.add_data_point_at(at(millis), value);
+        }
+        assert!(graph.data_points.iter().zip(graph.data_points.iter().skip(1)).all(|(a, b)| a.0 <= b.0));
+        assert_eq!(graph.span(), Duration::from_secs(45));
+
+        let (start, end) = graph.view.bounds(at(0), at(45_000));
+        assert_eq!((start, end), (at(25_000), at(45_000)));
+        let text = rendered(&graph);
+        for label in [start, start + (end - start) / 2, end].map(format_clock) {
+            assert!(text.contains(&label), "no {} label in {}", label, text);
+        }
+        assert!(text.contains("Span: 45s"), "{}", text);
+
+        // Panned back, the labels follow the window
+        graph.navigate(TimeNav::PanBack);
+        let (start, end) = graph.view.bounds(at(0), at(45_000));
+        assert_eq!(end, at(40_000));
+        let text = rendered(&graph);
+        assert!(text.contains(&format_clock(start)) && text.contains(&format_clock(end)), "{}", text);
+        assert!(text.contains("HISTORY (-5s)"), "{}", text);
+    }
+
+    #[test]
+    fn adding_trims_to_max_points_and_retention() {
+        let mut graph = ProfessionalTimeGraph::new("RTT".to_string(), 5, 60.0);
+        for i in 0..10 {
+            graph.add_data_point_at(at(i * 1_000), i as f64);
+        }
+        let values: Vec<f64> = graph.data_points.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, [5.0, 6.0, 7.0, 8.0, 9.0]);
+        assert_eq!(graph.get_analytics().min, 5.0);
+
+        graph.retention = Duration::from_secs(3);
+        graph.add_data_point_at(at(10_000), 10.0);
+        let values: Vec<f64> = graph.data_points.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, [7.0, 8.0, 9.0, 10.0]);
+
+        // A late sample from before the retention is dropped straight away
+        graph.add_data_point_at(at(1_000), 99.0);
+        assert_eq!(graph.data_points.len(), 4);
+        assert_eq!(graph.get_analytics().max, 10.0);
+        assert_eq!(graph.y_bounds, (7.0 - 0.3, 10.0 + 0.3));
+    }
+
+    #[test]
+    fn analytics_of_empty_single_and_full_windows() {
+        let mut graph = ProfessionalTimeGraph::new("RTT".to_string(), 50, 60.0);
+        let empty = graph.get_analytics();
+        assert_eq!((empty.data_points, empty.current, empty.trend, empty.span), (0, 0.0, 0.0, Duration::ZERO));
+
+        graph.add_data_point_at(at(0), 42.0);
+        let single = graph.get_analytics();
+        assert_eq!(single.data_points, 1);
+        for value in [single.current, single.average, single.min, single.max, single.p50, single.p99] {
+            assert_eq!(value, 42.0);
+        }
+        assert_eq!((single.trend, single.span), (0.0, Duration::ZERO));
+        // A flat series still gets a usable axis
+        assert_eq!(graph.y_bounds, (42.0 - 4.2, 42.0 + 4.2));
+
+        for i in 1..80 {
+            graph.add_data_point_at(at(i * 500), i as f64);
+        }
+        let full = graph.get_analytics();
+        assert_eq!(full.data_points, 50);
+        assert_eq!((full.current, full.min, full.max), (79.0, 30.0, 79.0));
+        assert_eq!(full.average, 54.5);
+        assert_eq!(full.p50, 54.5);
+        assert!((full.trend - 1.0).abs() < 1e-9, "{}", full.trend);
+        assert_eq!(full.span, Duration::from_millis(49 * 500));
+    }
+}
@@ -0,0 +1,206 @@
+//! Redaction of sensitive fields in exported data
+//!
+//! Replaces identifying values (addresses, hostnames, instance ids) with
+//! pseudonyms so rows can still be correlated after export. A pseudonym is
+//! the first 64 bits of an HMAC-SHA256 of the value, so it only depends on
+//! the value and the key: with `key` set it is the same in every run.
+
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How a sensitive field is redacted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    /// Replace the value with a pseudonym that is stable within the session
+    Hash,
+    /// Remove the field entirely
+    Strip,
+}
+
+/// Redaction configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Redact exports and reports by default
+    pub enabled: bool,
+
+    /// Hash or strip sensitive fields
+    pub mode: RedactionMode,
+
+    /// Field names treated as sensitive, matched at any nesting depth
+    pub fields: Vec<String>,
+
+    /// Secret the pseudonyms are keyed with, for pseudonyms that match
+    /// across runs; a random key per session when unset
+    pub key: Option<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: RedactionMode::Hash,
+            fields: vec![
+                "source".to_string(),
+                "source_addr".to_string(),
+                "remote_addr".to_string(),
+                "hostname".to_string(),
+                "instance_id".to_string(),
+            ],
+            key: None,
+        }
+    }
+}
+
+/// Applies a `RedactionConfig` to JSON values
+///
+/// Pseudonyms are a keyed hash of the value, so one instance should be shared
+/// by everything exported during a session unless the config sets a key.
+pub struct Redactor {
+    mode: RedactionMode,
+    fields: Vec<String>,
+    key: hmac::Key,
+}
+
+impl Redactor {
+    pub fn new(config: &RedactionConfig) -> Self {
+        let key = match &config.key {
+            Some(key) => hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes()),
+            None => hmac::Key::new(hmac::HMAC_SHA256, &rand::random::<[u8; 32]>()),
+        };
+        Self {
+            mode: config.mode,
+            fields: config.fields.clone(),
+            key,
+        }
+    }
+
+    /// Whether a field name is configured as sensitive
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        self.fields.iter().any(|f| f == key)
+    }
+
+    /// The pseudonym of a raw value, e.g. `anon-3f2a9c0d5e7b1a48`
+    pub fn pseudonym(&self, raw: &str) -> String {
+        let tag = hmac::sign(&self.key, raw.as_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&tag.as_ref()[..8]);
+        format!("anon-{:016x}", u64::from_be_bytes(bytes))
+    }
+
+    /// `raw` as it may appear in a `field`: unchanged when the field is not
+    /// sensitive, its pseudonym in hash mode, `None` in strip mode
+    pub fn redact_field(&self, field: &str, raw: &str) -> Option<String> {
+        if !self.is_sensitive(field) {
+            return Some(raw.to_string());
+        }
+        match self.mode {
+            RedactionMode::Hash => Some(self.pseudonym(raw)),
+            RedactionMode::Strip => None,
+        }
+    }
+
+    /// Redact sensitive fields in place, descending into objects and arrays
+    pub fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                if self.mode == RedactionMode::Strip {
+                    map.retain(|key, _| !self.is_sensitive(key));
+                }
+
+                for (key, field) in map.iter_mut() {
+                    if self.is_sensitive(key) {
+                        let raw = match &*field {
+                            Value::String(s) => s.clone(),
+                            Value::Null => continue,
+                            other => other.to_string(),
+                        };
+                        *field = Value::String(self.pseudonym(&raw));
+                    } else {
+                        self.redact_value(field);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_value(item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Serialize a value and return a redacted JSON copy of it
+    pub fn redact<T: Serialize>(&self, item: &T) -> serde_json::Result<Value> {
+        let mut value = serde_json::to_value(item)?;
+        self.redact_value(&mut value);
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redactor(mode: RedactionMode, key: Option<&str>) -> Redactor {
+        Redactor::new(&RedactionConfig {
+            enabled: true,
+            mode,
+            key: key.map(str::to_string),
+            ..RedactionConfig::default()
+        })
+    }
+
+    #[test]
+    fn hashes_sensitive_fields_at_any_depth() {
+        let redactor = redactor(RedactionMode::Hash, None);
+        let mut value = json!({
+            "latency": 12.5,
+            "source": "client-1",
+            "metadata": {"hostname": "edge-7.example.net", "region": "eu", "peers": [{"remote_addr": "10.0.0.2:443"}]},
+            "instance_id": 42,
+            "source_addr": null,
+        });
+        redactor.redact_value(&mut value);
+
+        assert_eq!(value["latency"], 12.5);
+        assert_eq!(value["metadata"]["region"], "eu");
+        assert_eq!(value["source"], redactor.pseudonym("client-1"));
+        assert_eq!(value["metadata"]["hostname"], redactor.pseudonym("edge-7.example.net"));
+        assert_eq!(value["metadata"]["peers"][0]["remote_addr"], redactor.pseudonym("10.0.0.2:443"));
+        assert_eq!(value["instance_id"], redactor.pseudonym("42"));
+        assert_eq!(value["source_addr"], Value::Null);
+        let text = value.to_string();
+        for raw in ["client-1", "edge-7", "10.0.0.2"] {
+            assert!(!text.contains(raw), "{} leaked: {}", raw, text);
+        }
+    }
+
+    #[test]
+    fn strip_drops_sensitive_fields_at_any_depth() {
+        let redactor = redactor(RedactionMode::Strip, None);
+        let mut value = json!([{"source": "a", "metadata": {"hostname": "h", "region": "eu"}}]);
+        redactor.redact_value(&mut value);
+        assert_eq!(value, json!([{"metadata": {"region": "eu"}}]));
+        assert_eq!(redactor.redact_field("source", "a"), None);
+        assert_eq!(redactor.redact_field("region", "eu").as_deref(), Some("eu"));
+    }
+
+    #[test]
+    fn pseudonyms_are_64_bits_and_stable_for_a_key() {
+        let keyed = redactor(RedactionMode::Hash, Some("secret"));
+        let pseudonym = keyed.pseudonym("client-1");
+        assert_eq!(pseudonym.len(), "anon-".len() + 16);
+        assert!(pseudonym["anon-".len()..].chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(pseudonym, keyed.pseudonym("client-2"));
+
+        // The same key gives the same pseudonym in another run, another key does not
+        assert_eq!(redactor(RedactionMode::Hash, Some("secret")).pseudonym("client-1"), pseudonym);
+        assert_ne!(redactor(RedactionMode::Hash, Some("other")).pseudonym("client-1"), pseudonym);
+        // HMAC-SHA256("secret", "client-1"), independent of the Rust version
+        assert_eq!(pseudonym, "anon-e6b20c2a7bfb205b");
+    }
+}
@@ -0,0 +1,504 @@
+//! qlog trace and session replay
+//!
+//! Turns a qlog trace written by quic-go or another stack into a stream of
+//! `QUICMetrics` samples, one per `SAMPLE_PERIOD` of trace time, so a
+//! recorded connection can be watched in the TUI after the fact:
+//!
+//! - latency and rtt are the smoothed RTT from `recovery:metrics_updated`,
+//!   jitter its `rtt_variance` and the congestion window its
+//!   `congestion_window`
+//! - throughput is the bits of `transport:packet_received` per second of
+//!   the period
+//! - packet loss is the fraction lost / (received + lost) packets per period, and
+//!   retransmits the lost count
+//! - bytes received is the running total of received packet bytes
+//!
+//! Both the JSON format (`traces[].events`) and JSON-SEQ/NDJSON (one record
+//! per line, as quic-go writes `.sqlog` files) are read. Other event types
+//! are skipped and counted.
+//!
+//! Live sessions can also be recorded in quic-bottom's own format, one JSON
+//! line per ingested sample with its arrival time (`SessionRecorder`), and
+//! loaded back with `load_session`. `Replayer` plays either kind.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::ingest::record_ingest_error;
+use crate::metrics::QUICMetrics;
+use crate::sync::MutexExt;
+
+/// Trace time covered by one replayed sample
+pub const SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+
+/// Samples decoded from a qlog file
+#[derive(Debug, Clone)]
+pub struct QlogTrace {
+    /// Samples with their offset from the start of the trace, in order
+    pub samples: Vec<(Duration, QUICMetrics)>,
+    /// Events of types the replay does not use
+    pub skipped_events: usize,
+}
+
+impl QlogTrace {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read qlog file {}", path.display()))?;
+        let mut trace = Self::parse(&text).with_context(|| format!("failed to parse {}", path.display()))?;
+        trace.samples.retain(|(offset, metrics)| match metrics.validate() {
+            Ok(()) => true,
+            Err(e) => {
+                record_ingest_error();
+                log::warn!("{}: skipping sample at {:?}: {}", path.display(), offset, e);
+                false
+            }
+        });
+        if trace.samples.is_empty() {
+            return Err(anyhow!("{} contains no replayable events", path.display()));
+        }
+        Ok(trace)
+    }
+
+    /// Decode a qlog document in JSON or JSON-SEQ form
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut events = read_events(text)?;
+        events.sort_by(|a, b| a.time_ms.total_cmp(&b.time_ms));
+        Ok(build_samples(&events))
+    }
+
+    /// Trace time of the last sample
+    pub fn duration(&self) -> Duration {
+        self.samples.last().map(|(offset, _)| *offset).unwrap_or_default()
+    }
+}
+
+/// One qlog event, reduced to what the replay reads
+struct Event {
+    time_ms: f64,
+    kind: EventKind,
+    data: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventKind {
+    MetricsUpdated,
+    PacketLost,
+    PacketReceived,
+    Unsupported,
+}
+
+impl EventKind {
+    /// Accepts both `recovery:metrics_updated` and newer `quic:` names
+    fn from_name(name: &str) -> Self {
+        match name.rsplit(':').next().unwrap_or(name) {
+            "metrics_updated" | "recovery_metrics_updated" => EventKind::MetricsUpdated,
+            "packet_lost" => EventKind::PacketLost,
+            "packet_received" => EventKind::PacketReceived,
+            _ => EventKind::Unsupported,
+        }
+    }
+}
+
+fn read_events(text: &str) -> Result<Vec<Event>> {
+    let text = text.trim_start_matches(['\u{feff}', ' ', '\n', '\r', '\t']);
+    // A JSON document holds every trace; JSON-SEQ starts with a record separator
+    if !text.starts_with('\u{1e}') {
+        if let Ok(document) = serde_json::from_str::<Value>(text) {
+            if let Some(traces) = document.get("traces").and_then(Value::as_array) {
+                return Ok(traces
+                    .iter()
+                    .filter_map(|trace| trace.get("events").and_then(Value::as_array))
+                    .flatten()
+                    .filter_map(event_from_value)
+                    .collect());
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for (number, record) in text.split(['\u{1e}', '\n']).enumerate() {
+        let record = record.trim();
+        if record.is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(record)
+            .with_context(|| format!("record {} is not valid JSON", number + 1))?;
+        // The header record has no event name or time
+        events.extend(event_from_value(&value));
+    }
+    Ok(events)
+}
+
+/// Read an event object, or a draft-01 `[time, category, event, data]` array
+fn event_from_value(value: &Value) -> Option<Event> {
+    let (time, name, data) = match value {
+        Value::Object(object) => (
+            object.get("time")?,
+            object.get("name")?.as_str()?.to_string(),
+            object.get("data").cloned().unwrap_or(Value::Null),
+        ),
+        Value::Array(fields) if fields.len() == 4 => (
+            &fields[0],
+            format!("{}:{}", fields[1].as_str()?, fields[2].as_str()?),
+            fields[3].clone(),
+        ),
+        _ => return None,
+    };
+    let time_ms = match time {
+        Value::String(s) => s.parse().ok()?,
+        other => other.as_f64()?,
+    };
+    Some(Event {
+        time_ms,
+        kind: EventKind::from_name(&name),
+        data,
+    })
+}
+
+fn field_f64(data: &Value, pointer: &str) -> Option<f64> {
+    data.pointer(pointer).and_then(Value::as_f64)
+}
+
+/// Fold sorted events into one sample per `SAMPLE_PERIOD`
+fn build_samples(events: &[Event]) -> QlogTrace {
+    let skipped_events = events.iter().filter(|e| e.kind == EventKind::Unsupported).count();
+    let used: Vec<&Event> = events.iter().filter(|e| e.kind != EventKind::Unsupported).collect();
+    let (Some(first), Some(last)) = (used.first(), used.last()) else {
+        return QlogTrace {
+            samples: Vec::new(),
+            skipped_events,
+        };
+    };
+
+    let start = first.time_ms;
+    let period_ms = SAMPLE_PERIOD.as_secs_f64() * 1000.0;
+    let periods = ((last.time_ms - start) / period_ms).floor() as usize + 1;
+    let started_at = Utc::now();
+
+    let mut samples = Vec::with_capacity(periods);
+    let (mut rtt_ms, mut jitter_ms, mut cwnd) = (0.0, 0.0, 0);
+    let mut total_bytes = 0.0;
+    let mut events = used.iter().peekable();
+    for period in 0..periods {
+        let end_ms = start + (period + 1) as f64 * period_ms;
+        let (mut bytes, mut received, mut lost) = (0.0, 0u32, 0u32);
+        while let Some(event) = events.next_if(|e| e.time_ms < end_ms || period + 1 == periods) {
+            match event.kind {
+                EventKind::MetricsUpdated => {
+                    if let Some(rtt) = field_f64(&event.data, "/smoothed_rtt")
+                        .or_else(|| field_f64(&event.data, "/latest_rtt"))
+                    {
+                        rtt_ms = rtt;
+                    }
+                    if let Some(variance) = field_f64(&event.data, "/rtt_variance") {
+                        jitter_ms = variance;
+                    }
+                    if let Some(window) = field_f64(&event.data, "/congestion_window") {
+                        cwnd = window as i32;
+                    }
+                }
+                EventKind::PacketReceived => {
+                    received += 1;
+                    bytes += field_f64(&event.data, "/raw/length")
+                        .or_else(|| field_f64(&event.data, "/header/packet_size"))
+                        .unwrap_or(0.0);
+                }
+                EventKind::PacketLost => lost += 1,
+                EventKind::Unsupported => {}
+            }
+        }
+
+        let offset = SAMPLE_PERIOD * (period as u32 + 1);
+        let packets = received + lost;
+        total_bytes += bytes;
+        samples.push((
+            offset,
+            QUICMetrics {
+                latency: rtt_ms,
+                throughput: bytes * 8.0 / SAMPLE_PERIOD.as_secs_f64(),
+                connections: 1,
+                errors: 0,
+                packet_loss: if packets > 0 {
+                    lost as f64 / packets as f64
+                } else {
+                    0.0
+                },
+                retransmits: lost as i32,
+                timestamp: offset_time(started_at, offset),
+                rtt: rtt_ms,
+                jitter: jitter_ms,
+                congestion_window: cwnd,
+                bytes_received: total_bytes as i64,
+                ..QUICMetrics::default()
+            },
+        ));
+    }
+
+    QlogTrace {
+        samples,
+        skipped_events,
+    }
+}
+
+fn offset_time(start: DateTime<Utc>, offset: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(offset)
+        .ok()
+        .and_then(|offset| start.checked_add_signed(offset))
+        .unwrap_or(start)
+}
+
+/// One line of a session recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedSample<T> {
+    /// Arrival time, unix ms
+    pub received_at: i64,
+    pub sample: T,
+}
+
+/// A loaded session recording
+#[derive(Debug, Clone)]
+pub struct Session<T> {
+    /// Arrival time of the first sample
+    pub started_at: DateTime<Utc>,
+    /// Samples with their arrival offset from `started_at`, in order
+    pub samples: Vec<(Duration, T)>,
+}
+
+/// Read a recording written by `SessionRecorder`
+///
+/// A malformed last line, as left by a run that was killed mid-write, is
+/// dropped with a warning; anywhere else it is an error.
+pub fn load_session<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Session<T>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read session {}", path.display()))?;
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let mut records: Vec<RecordedSample<T>> = Vec::with_capacity(lines.len());
+    for (position, (number, line)) in lines.iter().enumerate() {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(e) if position + 1 == lines.len() => {
+                log::warn!("{}: dropping truncated last line {}: {}", path.display(), number + 1, e);
+            }
+            Err(e) => {
+                return Err(anyhow!("{}: line {} is not a recorded sample: {}", path.display(), number + 1, e));
+            }
+        }
+    }
+    records.sort_by_key(|record| record.received_at);
+
+    let first = records
+        .first()
+        .ok_or_else(|| anyhow!("{} contains no recorded samples", path.display()))?
+        .received_at;
+    let started_at = DateTime::from_timestamp_millis(first)
+        .ok_or_else(|| anyhow!("{}: invalid timestamp {}", path.display(), first))?;
+    let samples = records
+        .into_iter()
+        .map(|record| {
+            let offset = Duration::from_millis((record.received_at - first) as u64);
+            (offset, record.sample)
+        })
+        .collect();
+    Ok(Session { started_at, samples })
+}
+
+/// Appends every ingested sample to a session file
+///
+/// Lines are flushed as they are written, so a crash loses at most the
+/// sample being written. A write error stops the recording and is kept for
+/// `status`; it is never passed back to the ingest path.
+pub struct SessionRecorder {
+    path: PathBuf,
+    inner: Mutex<RecorderState>,
+}
+
+struct RecorderState {
+    /// `None` once a write has failed
+    writer: Option<LineWriter<File>>,
+    written: u64,
+    error: Option<String>,
+}
+
+/// What a recorder has done so far
+#[derive(Debug, Clone)]
+pub struct RecorderStatus {
+    pub path: PathBuf,
+    pub written: u64,
+    /// Why recording stopped, if it did
+    pub error: Option<String>,
+}
+
+impl SessionRecorder {
+    /// Create `path`, or append to it when it already holds a recording
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open session file {}", path.display()))?;
+        Ok(Self {
+            path,
+            inner: Mutex::new(RecorderState {
+                writer: Some(LineWriter::new(file)),
+                written: 0,
+                error: None,
+            }),
+        })
+    }
+
+    /// Append `sample`, stamped with `received_at`
+    pub fn record<T: Serialize>(&self, received_at: DateTime<Utc>, sample: &T) {
+        let mut state = self.inner.lock_or_recover();
+        let Some(writer) = state.writer.as_mut() else {
+            return;
+        };
+        let record = RecordedSample {
+            received_at: received_at.timestamp_millis(),
+            sample,
+        };
+        let result = serde_json::to_vec(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                writer.write_all(&line)?;
+                Ok(())
+            });
+        match result {
+            Ok(()) => state.written += 1,
+            Err(e) => {
+                log::error!("Recording to {} stopped: {}", self.path.display(), e);
+                state.writer = None;
+                state.error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn status(&self) -> RecorderStatus {
+        let state = self.inner.lock_or_recover();
+        RecorderStatus {
+            path: self.path.clone(),
+            written: state.written,
+            error: state.error.clone(),
+        }
+    }
+}
+
+/// Plays timed samples back against the wall clock
+pub struct Replayer<T = QUICMetrics> {
+    /// Samples with their offset from the start, in order
+    samples: Vec<(Duration, T)>,
+    /// Trace time per wall-clock time
+    speed: f64,
+    /// Current position in trace time
+    position: Duration,
+    /// Index of the next sample to hand out
+    next: usize,
+    paused: bool,
+    last_tick: Instant,
+}
+
+impl<T> Replayer<T> {
+    pub fn new(samples: Vec<(Duration, T)>, speed: f64) -> Self {
+        Self {
+            samples,
+            speed: if speed.is_finite() && speed > 0.0 { speed } else { 1.0 },
+            position: Duration::ZERO,
+            next: 0,
+            paused: false,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// Move the position on by the wall time since the last call and return
+    /// the samples now due, oldest first
+    pub fn advance(&mut self) -> &[(Duration, T)] {
+        let elapsed = self.last_tick.elapsed();
+        self.last_tick = Instant::now();
+        if !self.paused {
+            self.position = (self.position + elapsed.mul_f64(self.speed)).min(self.duration());
+        }
+
+        let from = self.next;
+        while self
+            .samples
+            .get(self.next)
+            .is_some_and(|(offset, _)| *offset <= self.position)
+        {
+            self.next += 1;
+        }
+        &self.samples[from..self.next]
+    }
+
+    /// Jump to `target`; returns true when the caller must clear what it has
+    /// shown, because the next `advance` replays from the start
+    pub fn seek(&mut self, target: Duration) -> bool {
+        let rewound = target < self.position;
+        self.position = target.min(self.duration());
+        if rewound {
+            self.next = 0;
+        }
+        rewound
+    }
+
+    /// Seek by a signed number of seconds
+    pub fn seek_by(&mut self, seconds: i64) -> bool {
+        let step = Duration::from_secs(seconds.unsigned_abs());
+        let target = if seconds < 0 {
+            self.position.saturating_sub(step)
+        } else {
+            self.position + step
+        };
+        self.seek(target)
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next == self.samples.len()
+    }
+
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// Offset of the last sample
+    pub fn duration(&self) -> Duration {
+        self.samples.last().map(|(offset, _)| *offset).unwrap_or_default()
+    }
+
+    /// Position as a fraction of the duration, 1.0 for an empty replay
+    pub fn progress(&self) -> f64 {
+        let duration = self.duration();
+        if duration.is_zero() {
+            1.0
+        } else {
+            self.position.as_secs_f64() / duration.as_secs_f64()
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+}
@@ -0,0 +1,545 @@
+//! Summary report of a monitoring session
+//!
+//! `SessionReport::build` is a pure function of the stored samples plus the
+//! anomalies and correlations the analytics found, so the same history
+//! always gives the same report. The report is written as Markdown, and
+//! optionally JSON, when a TUI quits or on 'R'.
+//!
+//! Retransmits and errors are cumulative counters on the wire; their totals
+//! add up the increases between samples, taking a drop as a sender restart.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::anomaly_detection::AnomalyResult;
+use crate::correlation_widget::CorrelationData;
+use crate::demo_data::FaultRecord;
+use crate::health_score::HealthScore;
+use crate::metrics::{calculate_latency_percentiles, finite_values, format_value, QUICMetrics};
+use crate::redact::Redactor;
+use crate::units::Unit;
+
+/// Correlations listed in the report, strongest first
+pub const REPORT_CORRELATIONS: usize = 5;
+
+/// `[report]` section of the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Directory reports are written to, created if missing
+    pub output_dir: PathBuf,
+    /// Also write the report as JSON next to the Markdown file
+    pub json: bool,
+    /// Write a report when the TUI quits
+    pub on_exit: bool,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("."),
+            json: false,
+            on_exit: true,
+        }
+    }
+}
+
+/// Min/avg/max and percentiles of one metric's finite values
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SeriesSummary {
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+impl SeriesSummary {
+    /// `None` when there is no finite value
+    pub fn of(values: &[f64]) -> Option<Self> {
+        let values = finite_values(values);
+        if values.is_empty() {
+            return None;
+        }
+        let (p50, p95, p99) = calculate_latency_percentiles(&values);
+        Some(Self {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            p50,
+            p95,
+            p99,
+        })
+    }
+}
+
+/// An anomaly as listed in the report
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportAnomaly {
+    pub timestamp: DateTime<Utc>,
+    pub metric: String,
+    pub value: f64,
+    pub severity: String,
+    pub description: String,
+    /// Acknowledged in the anomaly widget during the session
+    pub acknowledged: bool,
+}
+
+/// A labelled sender and the samples it contributed
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSource {
+    pub source: String,
+    pub samples: usize,
+}
+
+/// A metric pair and its correlation coefficient
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportCorrelation {
+    pub metric1: String,
+    pub metric2: String,
+    pub correlation: f64,
+}
+
+/// Time spent in one BBRv3 phase
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTime {
+    pub phase: String,
+    pub seconds: f64,
+    /// Share of the time in all phases, 0-100
+    pub percent: f64,
+}
+
+/// A fault injected into the demo data during the session
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportInjection {
+    pub fault: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: f64,
+    /// Anomalies detected while it was in effect
+    pub anomalies: usize,
+}
+
+/// Everything the report says about a session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    pub generated_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_secs: f64,
+    pub samples: usize,
+    /// Labelled senders by label; empty when no sample had a `source`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<ReportSource>,
+    pub latency: Option<SeriesSummary>,
+    pub throughput: Option<SeriesSummary>,
+    /// Unit of `throughput`, always bits per second
+    pub throughput_unit: Unit,
+    /// Fraction of packets lost, 0.0-1.0
+    pub packet_loss: Option<SeriesSummary>,
+    pub total_retransmits: u64,
+    pub total_errors: u64,
+    /// Oldest first
+    pub anomalies: Vec<ReportAnomaly>,
+    /// Strongest first, at most `REPORT_CORRELATIONS`
+    pub correlations: Vec<ReportCorrelation>,
+    /// Empty unless the sender reported BBRv3 phases
+    pub bbrv3_phases: Vec<PhaseTime>,
+    /// Health score when the report was written
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthScore>,
+    /// Faults injected on purpose (F1-F5 in the demo modes), oldest first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub injections: Vec<ReportInjection>,
+}
+
+impl SessionReport {
+    /// Summarize `samples` (in any order) with the analytics' findings
+    pub fn build(
+        samples: &[QUICMetrics],
+        anomalies: &[AnomalyResult],
+        correlations: &[CorrelationData],
+        generated_at: DateTime<Utc>,
+    ) -> Self {
+        let mut ordered: Vec<&QUICMetrics> = samples.iter().collect();
+        ordered.sort_by_key(|sample| sample.timestamp);
+        let started_at = ordered.first().map(|sample| sample.timestamp);
+        let ended_at = ordered.last().map(|sample| sample.timestamp);
+        let duration_secs = match (started_at, ended_at) {
+            (Some(start), Some(end)) => (end - start).num_milliseconds() as f64 / 1000.0,
+            _ => 0.0,
+        };
+
+        let mut sources = BTreeMap::new();
+        for source in ordered.iter().filter_map(|sample| sample.source.as_ref()) {
+            *sources.entry(source.clone()).or_insert(0) += 1;
+        }
+
+        let series = |pick: fn(&QUICMetrics) -> f64| ordered.iter().map(|s| pick(s)).collect::<Vec<f64>>();
+
+        let mut anomalies: Vec<ReportAnomaly> = anomalies
+            .iter()
+            .map(|anomaly| ReportAnomaly {
+                timestamp: anomaly.timestamp,
+                metric: anomaly.metric.clone(),
+                value: anomaly.value,
+                severity: anomaly.severity.get_description().to_string(),
+                description: anomaly.description.clone(),
+                acknowledged: anomaly.acknowledged,
+            })
+            .collect();
+        anomalies.sort_by_key(|anomaly| anomaly.timestamp);
+
+        let mut correlations: Vec<ReportCorrelation> = correlations
+            .iter()
+            .filter(|c| c.correlation.is_finite())
+            .map(|c| ReportCorrelation {
+                metric1: c.metric1.clone(),
+                metric2: c.metric2.clone(),
+                correlation: c.correlation,
+            })
+            .collect();
+        correlations.sort_by(|a, b| b.correlation.abs().total_cmp(&a.correlation.abs()));
+        correlations.truncate(REPORT_CORRELATIONS);
+
+        Self {
+            generated_at,
+            started_at,
+            ended_at,
+            duration_secs,
+            samples: ordered.len(),
+            sources: sources
+                .into_iter()
+                .map(|(source, samples)| ReportSource { source, samples })
+                .collect(),
+            latency: SeriesSummary::of(&series(|s| s.latency)),
+            throughput: SeriesSummary::of(&series(|s| s.throughput)),
+            throughput_unit: Unit::Bps,
+            packet_loss: SeriesSummary::of(&series(|s| s.packet_loss)),
+            total_retransmits: counter_total(ordered.iter().map(|s| s.retransmits)),
+            total_errors: counter_total(ordered.iter().map(|s| s.errors)),
+            anomalies,
+            correlations,
+            bbrv3_phases: phase_times(&ordered),
+            health: None,
+            injections: Vec::new(),
+        }
+    }
+
+    /// Include the health score at the time of writing
+    pub fn with_health(mut self, health: HealthScore) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// List the faults of `log`, lasting one sample per `interval`, with the anomalies each set off
+    pub fn with_injections(mut self, log: &[FaultRecord], interval: Duration) -> Self {
+        self.injections = log
+            .iter()
+            .map(|record| {
+                let duration = interval * record.samples;
+                let ended_at = record.started_at + chrono::Duration::from_std(duration).unwrap_or_default();
+                ReportInjection {
+                    fault: record.kind.name().to_string(),
+                    started_at: record.started_at,
+                    duration_secs: duration.as_secs_f64(),
+                    anomalies: self
+                        .anomalies
+                        .iter()
+                        .filter(|a| record.started_at <= a.timestamp && a.timestamp <= ended_at)
+                        .count(),
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Source labels replaced or dropped as `redactor` is configured to
+    pub fn redacted(mut self, redactor: &Redactor) -> Self {
+        self.sources = std::mem::take(&mut self.sources)
+            .into_iter()
+            .filter_map(|source| {
+                Some(ReportSource {
+                    source: redactor.redact_field("source", &source.source)?,
+                    ..source
+                })
+            })
+            .collect();
+        self
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let time = |t: Option<DateTime<Utc>>| t.map_or("-".to_string(), |t| t.to_rfc3339());
+        let _ = writeln!(out, "# QUIC Bottom session report\n");
+        let _ = writeln!(out, "Generated {}\n", self.generated_at.to_rfc3339());
+        let _ = writeln!(out, "- Start: {}", time(self.started_at));
+        let _ = writeln!(out, "- End: {}", time(self.ended_at));
+        let _ = writeln!(out, "- Duration: {}s", format_value(self.duration_secs, 1));
+        let _ = writeln!(out, "- Samples: {}", self.samples);
+        if !self.sources.is_empty() {
+            let sources: Vec<String> = self
+                .sources
+                .iter()
+                .map(|source| format!("{} ({})", source.source, source.samples))
+                .collect();
+            let _ = writeln!(out, "- Sources: {}", sources.join(", "));
+        }
+        let _ = writeln!(out, "- Retransmits: {}", self.total_retransmits);
+        let _ = writeln!(out, "- Errors: {}", self.total_errors);
+        if let Some(health) = &self.health {
+            let _ = writeln!(out, "- Health score: {} ({})", health.label(), health.breakdown());
+        }
+        let _ = writeln!(out);
+
+        let _ = writeln!(out, "## Metrics\n");
+        let _ = writeln!(out, "| Metric | Min | Avg | Max | P50 | P95 | P99 |");
+        let _ = writeln!(out, "|---|---|---|---|---|---|---|");
+        // Throughput in the unit that suits its largest value
+        let rate_unit = Unit::auto(self.throughput.map_or(0.0, |s| s.max));
+        for (name, summary, factor) in [
+            ("Latency (ms)".to_string(), self.latency, 1.0),
+            (format!("Throughput ({})", rate_unit), self.throughput, rate_unit.factor()),
+            ("Packet loss (%)".to_string(), self.packet_loss, 0.01),
+        ] {
+            match summary {
+                Some(s) => {
+                    let cells = [s.min, s.avg, s.max, s.p50, s.p95, s.p99].map(|v| format_value(v / factor, 2));
+                    let _ = writeln!(out, "| {} | {} |", name, cells.join(" | "));
+                }
+                None => {
+                    let _ = writeln!(out, "| {} | - | - | - | - | - | - |", name);
+                }
+            }
+        }
+
+        let _ = writeln!(out, "\n## Anomalies\n");
+        if self.anomalies.is_empty() {
+            let _ = writeln!(out, "None detected.");
+        }
+        for anomaly in &self.anomalies {
+            let _ = writeln!(
+                out,
+                "- {} **{}** {} = {}: {}{}",
+                anomaly.timestamp.to_rfc3339(),
+                anomaly.severity,
+                anomaly.metric,
+                format_value(anomaly.value, 2),
+                anomaly.description,
+                if anomaly.acknowledged { " (acknowledged)" } else { "" }
+            );
+        }
+
+        if !self.injections.is_empty() {
+            let _ = writeln!(out, "\n## Injected faults\n");
+            for injection in &self.injections {
+                let _ = writeln!(
+                    out,
+                    "- {} {} for {}s: {} anomalies",
+                    injection.started_at.to_rfc3339(),
+                    injection.fault,
+                    format_value(injection.duration_secs, 1),
+                    injection.anomalies
+                );
+            }
+        }
+
+        let _ = writeln!(out, "\n## Strongest correlations\n");
+        if self.correlations.is_empty() {
+            let _ = writeln!(out, "Not enough data.");
+        }
+        for c in &self.correlations {
+            let _ = writeln!(out, "- {} / {}: {}", c.metric1, c.metric2, format_value(c.correlation, 3));
+        }
+
+        if !self.bbrv3_phases.is_empty() {
+            let _ = writeln!(out, "\n## BBRv3 phases\n");
+            let _ = writeln!(out, "| Phase | Time (s) | Share |");
+            let _ = writeln!(out, "|---|---|---|");
+            for phase in &self.bbrv3_phases {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {}% |",
+                    phase.phase,
+                    format_value(phase.seconds, 1),
+                    format_value(phase.percent, 1)
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Where a report was written
+#[derive(Debug, Clone)]
+pub struct ReportPaths {
+    pub markdown: PathBuf,
+    pub json: Option<PathBuf>,
+    /// Whether sensitive fields were redacted
+    pub redacted: bool,
+}
+
+impl std::fmt::Display for ReportPaths {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Report written to {}", self.markdown.display())?;
+        if let Some(json) = &self.json {
+            write!(f, " and {}", json.display())?;
+        }
+        if self.redacted {
+            write!(f, " (redacted)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `report` to a timestamped file in the configured directory
+///
+/// When a redactor is given, sensitive fields are redacted in both files.
+pub fn write_report(config: &ReportConfig, report: &SessionReport, redactor: Option<&Redactor>) -> Result<ReportPaths> {
+    if report.samples == 0 {
+        return Err(anyhow!("no samples to report on"));
+    }
+    let redacted;
+    let report = match redactor {
+        Some(redactor) => {
+            redacted = report.clone().redacted(redactor);
+            &redacted
+        }
+        None => report,
+    };
+    fs::create_dir_all(&config.output_dir)?;
+    let stem = format!("quic-report-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let markdown = config.output_dir.join(format!("{}.md", stem));
+    fs::write(&markdown, report.to_markdown())?;
+
+    let json = if config.json {
+        let path = config.output_dir.join(format!("{}.json", stem));
+        fs::write(&path, serde_json::to_string_pretty(report)?)?;
+        Some(path)
+    } else {
+        None
+    };
+    Ok(ReportPaths {
+        markdown,
+        json,
+        redacted: redactor.is_some(),
+    })
+}
+
+/// Sum of increases of a cumulative counter; after a drop the new value counts in full
+fn counter_total(values: impl Iterator<Item = i32>) -> u64 {
+    let mut total = 0u64;
+    let mut previous = 0i64;
+    for value in values.map(|v| i64::from(v.max(0))) {
+        total += if value >= previous { value - previous } else { value } as u64;
+        previous = value;
+    }
+    total
+}
+
+/// Time per BBRv3 phase: the sender's own `phase_duration_ms` when the last
+/// sample has it, otherwise each gap between samples counted to the earlier
+/// sample's phase
+fn phase_times(ordered: &[&QUICMetrics]) -> Vec<PhaseTime> {
+    let reported = ordered
+        .iter()
+        .rev()
+        .find_map(|s| s.bbrv3.as_ref()?.phase_duration_ms.clone());
+    let seconds: BTreeMap<String, f64> = match reported {
+        Some(durations) => durations.into_iter().map(|(phase, ms)| (phase, ms / 1000.0)).collect(),
+        None => {
+            let mut seconds = BTreeMap::new();
+            for pair in ordered.windows(2) {
+                if let Some(phase) = pair[0].bbrv3.as_ref().and_then(|b| b.phase.clone()) {
+                    let gap = (pair[1].timestamp - pair[0].timestamp).num_milliseconds() as f64 / 1000.0;
+                    *seconds.entry(phase).or_insert(0.0) += gap.max(0.0);
+                }
+            }
+            seconds
+        }
+    };
+
+    let total: f64 = seconds.values().sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let mut phases: Vec<PhaseTime> = seconds
+        .into_iter()
+        .map(|(phase, seconds)| PhaseTime {
+            phase,
+            seconds,
+            percent: seconds / total * 100.0,
+        })
+        .collect();
+    phases.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+    phases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redact::{RedactionConfig, RedactionMode};
+
+    fn report() -> SessionReport {
+        let samples: Vec<QUICMetrics> = ["client-1", "client-1", "server-1"]
+            .iter()
+            .map(|source| QUICMetrics {
+                latency: 10.0,
+                source: Some(source.to_string()),
+                ..QUICMetrics::default()
+            })
+            .collect();
+        SessionReport::build(&samples, &[], &[], Utc::now())
+    }
+
+    #[test]
+    fn lists_the_sources() {
+        assert!(report().to_markdown().contains("- Sources: client-1 (2), server-1 (1)\n"));
+    }
+
+    #[test]
+    fn redacted_report_hides_the_sources() {
+        let redactor = Redactor::new(&RedactionConfig::default());
+        let hashed = report().redacted(&redactor);
+        let markdown = hashed.to_markdown();
+        let json = serde_json::to_string(&hashed).unwrap();
+        for text in [&markdown, &json] {
+            assert!(!text.contains("client-1") && !text.contains("server-1"), "{}", text);
+        }
+        assert!(markdown.contains(&format!("{} (2)", redactor.pseudonym("client-1"))));
+
+        let strip = Redactor::new(&RedactionConfig {
+            mode: RedactionMode::Strip,
+            ..RedactionConfig::default()
+        });
+        let stripped = report().redacted(&strip);
+        assert!(stripped.sources.is_empty());
+        assert!(!stripped.to_markdown().contains("Sources"));
+    }
+
+    #[test]
+    fn write_report_redacts_both_files() {
+        let dir = std::env::temp_dir().join(format!("quic-bottom-report-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ReportConfig {
+            output_dir: dir.clone(),
+            json: true,
+            on_exit: false,
+        };
+        let redactor = Redactor::new(&RedactionConfig::default());
+        let paths = write_report(&config, &report(), Some(&redactor)).unwrap();
+        assert!(paths.to_string().ends_with("(redacted)"));
+        for path in [&paths.markdown, paths.json.as_ref().unwrap()] {
+            let text = fs::read_to_string(path).unwrap();
+            assert!(!text.contains("client-1"), "{}", text);
+            assert!(text.contains(&redactor.pseudonym("client-1")));
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
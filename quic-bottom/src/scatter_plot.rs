@@ -0,0 +1,167 @@
+//! Scatter plot of one metric against another
+//!
+//! Shows whether a correlation is linear, clustered or driven by a few
+//! outliers, with the least-squares line and Pearson r overlaid.
+
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+use crate::correlation_widget::pearson;
+use crate::metrics::format_value;
+use crate::theme::Theme;
+
+/// Least-squares fit `y = slope * x + intercept`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    /// Pearson correlation of the fitted points
+    pub r: f64,
+}
+
+impl LinearFit {
+    /// Fit finite `points`; `None` with fewer than two points or a constant x
+    pub fn from_points(points: &[(f64, f64)]) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let (mut covariance, mut variance_x) = (0.0, 0.0);
+        for (x, y) in points {
+            covariance += (x - mean_x) * (y - mean_y);
+            variance_x += (x - mean_x) * (x - mean_x);
+        }
+        if variance_x == 0.0 {
+            return None;
+        }
+
+        let slope = covariance / variance_x;
+        Some(Self {
+            slope,
+            intercept: mean_y - slope * mean_x,
+            r: pearson(points),
+        })
+    }
+
+    pub fn at(&self, x: f64) -> f64 {
+        self.slope * x + self.intercept
+    }
+}
+
+/// Scatter plot widget for an aligned pair of metric series
+pub struct ScatterPlotWidget {
+    /// Most recent samples plotted
+    pub max_points: usize,
+}
+
+impl ScatterPlotWidget {
+    pub fn new(max_points: usize) -> Self {
+        Self { max_points }
+    }
+
+    /// Plot the last `max_points` samples of `y` against `x`, aligned on the newest sample
+    pub fn render(&self, f: &mut Frame, area: Rect, x: (&str, &[f64]), y: (&str, &[f64]), theme: &Theme) {
+        let (x_name, x_data) = x;
+        let (y_name, y_data) = y;
+        let len = x_data.len().min(y_data.len()).min(self.max_points);
+        let points: Vec<(f64, f64)> = x_data[x_data.len() - len..]
+            .iter()
+            .zip(&y_data[y_data.len() - len..])
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .map(|(&x, &y)| (x, y))
+            .collect();
+
+        let block = Block::default().borders(Borders::ALL);
+        let (Some(x_bounds), Some(y_bounds)) = (
+            bounds(points.iter().map(|(x, _)| *x)),
+            bounds(points.iter().map(|(_, y)| *y)),
+        ) else {
+            let waiting = Paragraph::new(format!("No aligned samples of {} and {} yet", x_name, y_name))
+                .style(Style::default().fg(theme.warning))
+                .block(block.title(format!("{} vs {}", y_name, x_name)));
+            f.render_widget(waiting, area);
+            return;
+        };
+
+        let fit = LinearFit::from_points(&points);
+        let title = match fit {
+            Some(fit) => format!(
+                "{} vs {} - r = {:.2}, y = {}x + {} (n={})",
+                y_name,
+                x_name,
+                fit.r,
+                format_value(fit.slope, 3),
+                format_value(fit.intercept, 2),
+                points.len()
+            ),
+            None => format!("{} vs {} (n={})", y_name, x_name, points.len()),
+        };
+
+        let line: Vec<(f64, f64)> = fit
+            .map(|fit| vec![(x_bounds[0], fit.at(x_bounds[0])), (x_bounds[1], fit.at(x_bounds[1]))])
+            .unwrap_or_default();
+
+        let mut datasets = vec![Dataset::default()
+            .data(&points)
+            .style(Style::default().fg(theme.primary))
+            .graph_type(GraphType::Scatter)
+            .marker(Marker::Braille)];
+        if !line.is_empty() {
+            datasets.push(
+                Dataset::default()
+                    .data(&line)
+                    .style(Style::default().fg(theme.accent))
+                    .graph_type(GraphType::Line)
+                    .marker(Marker::Braille),
+            );
+        }
+
+        let label_style = Style::default().fg(theme.muted);
+        let chart = Chart::new(datasets)
+            .block(block.title(title))
+            .x_axis(
+                Axis::default()
+                    .title(Span::styled(x_name.to_string(), label_style))
+                    .bounds(x_bounds)
+                    .labels(axis_labels(x_bounds, label_style)),
+            )
+            .y_axis(
+                Axis::default()
+                    .title(Span::styled(y_name.to_string(), label_style))
+                    .bounds(y_bounds)
+                    .labels(axis_labels(y_bounds, label_style)),
+            );
+
+        f.render_widget(chart, area);
+    }
+}
+
+/// Min/max of `values` with 5% padding, widened when all values are equal
+fn bounds(values: impl Iterator<Item = f64>) -> Option<[f64; 2]> {
+    let (min, max) = values.fold(None, |acc: Option<(f64, f64)>, v| match acc {
+        Some((min, max)) => Some((min.min(v), max.max(v))),
+        None => Some((v, v)),
+    })?;
+    let pad = if max > min {
+        (max - min) * 0.05
+    } else {
+        min.abs().max(1.0) * 0.05
+    };
+    Some([min - pad, max + pad])
+}
+
+fn axis_labels(bounds: [f64; 2], style: Style) -> Vec<Span<'static>> {
+    let mid = (bounds[0] + bounds[1]) / 2.0;
+    [bounds[0], mid, bounds[1]]
+        .into_iter()
+        .map(|v| Span::styled(format_value(v, 1), style))
+        .collect()
+}
@@ -0,0 +1,362 @@
+//! Scripted scenarios for the demo-data modes
+//!
+//! A scenario is a TOML file of timed steps that `analytics --scenario` and
+//! `console --scenario` run against the app, in place of pressing keys by
+//! hand:
+//!
+//! ```toml
+//! [[step]]
+//! at = "10s"
+//! action = "set_simulation"
+//! active = true
+//!
+//! [[step]]
+//! at = "30s"
+//! action = "set_preset"
+//! preset = "satellite"
+//!
+//! [[step]]
+//! at = "1m"
+//! action = "inject_anomaly"
+//! metric = "latency"
+//! value = 800
+//! ```
+//!
+//! `at` is the time since the start, as `500ms`, `30s`, `2m`, `1m30s` or a
+//! number of seconds. Scenario time only moves while the app is not paused,
+//! one update interval per sample. An injected value replaces that metric in
+//! the next generated sample, before the network simulation, in the units
+//! samples are stored in: ms, bits per second, and packet loss as a fraction.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::network_sim::NetworkSimulation;
+
+/// Metric an `inject_anomaly` step replaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioMetric {
+    Latency,
+    Throughput,
+    PacketLoss,
+    Connections,
+    Errors,
+}
+
+impl ScenarioMetric {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScenarioMetric::Latency => "latency",
+            ScenarioMetric::Throughput => "throughput",
+            ScenarioMetric::PacketLoss => "packet_loss",
+            ScenarioMetric::Connections => "connections",
+            ScenarioMetric::Errors => "errors",
+        }
+    }
+}
+
+/// What a step does
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ScenarioAction {
+    /// Switch the network simulation to a profile, leaving it on or off
+    SetPreset { preset: String },
+    /// Turn the network simulation on or off
+    SetSimulation { active: bool },
+    /// Turn the simulated security test on or off
+    SetSecurity { active: bool },
+    /// Turn the simulated cloud deployment on or off
+    SetCloud { active: bool },
+    /// Replace `metric` with `value` in the next sample
+    InjectAnomaly { metric: ScenarioMetric, value: f64 },
+}
+
+impl fmt::Display for ScenarioAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let on_off = |active: bool| if active { "on" } else { "off" };
+        match self {
+            ScenarioAction::SetPreset { preset } => write!(f, "preset {}", preset),
+            ScenarioAction::SetSimulation { active } => write!(f, "simulation {}", on_off(*active)),
+            ScenarioAction::SetSecurity { active } => write!(f, "security test {}", on_off(*active)),
+            ScenarioAction::SetCloud { active } => write!(f, "cloud deployment {}", on_off(*active)),
+            ScenarioAction::InjectAnomaly { metric, value } => write!(f, "inject {}={}", metric.name(), value),
+        }
+    }
+}
+
+/// One timed step
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScenarioStep {
+    #[serde(deserialize_with = "deserialize_offset")]
+    pub at: Duration,
+    #[serde(flatten)]
+    pub action: ScenarioAction,
+    /// Line of the step's table in the file, for error messages
+    #[serde(skip)]
+    pub line: usize,
+}
+
+/// Steps of a scenario file, in the order they run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scenario {
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// A scenario file before its steps are decoded one by one
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScenario {
+    #[serde(default)]
+    step: Vec<toml::Spanned<toml::Table>>,
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read scenario {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid scenario {}", path.display()))
+    }
+
+    /// Decode a scenario; an error names the line and number of the step it is about
+    pub fn parse(text: &str) -> Result<Self> {
+        let raw: RawScenario = toml::from_str(text)?;
+        let mut steps = Vec::with_capacity(raw.step.len());
+        for (index, table) in raw.step.into_iter().enumerate() {
+            let line = line_of(text, table.span());
+            let mut step: ScenarioStep = toml::Value::Table(table.into_inner())
+                .try_into()
+                .map_err(|e: toml::de::Error| anyhow!("step {} (line {}): {}", index + 1, line, e.message()))?;
+            step.line = line;
+            if let ScenarioAction::InjectAnomaly { value, .. } = step.action {
+                if !value.is_finite() {
+                    bail!("step {} (line {}): value must be a number, got {}", index + 1, line, value);
+                }
+            }
+            steps.push(step);
+        }
+        // Steps may be written in any order; equal times keep the file's order
+        steps.sort_by_key(|step| step.at);
+        Ok(Self { steps })
+    }
+
+    /// Check that every `set_preset` step names one of `network`'s profiles
+    pub fn check_profiles(&self, network: &NetworkSimulation) -> Result<()> {
+        for step in &self.steps {
+            if let ScenarioAction::SetPreset { preset } = &step.action {
+                if !network.profiles().iter().any(|p| p.name == *preset) {
+                    let names: Vec<&str> = network.profiles().iter().map(|p| p.name.as_str()).collect();
+                    bail!(
+                        "scenario step at line {}: unknown network profile '{}' (expected one of {})",
+                        step.line,
+                        preset,
+                        names.join(", ")
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 1-based line of the first character of `span`
+fn line_of(text: &str, span: Range<usize>) -> usize {
+    text[..span.start.min(text.len())].matches('\n').count() + 1
+}
+
+/// `500ms`, `30s`, `2m`, `1h`, a combination such as `1m30s`, or a bare number of seconds
+pub fn parse_offset(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<f64>() {
+        return offset_from_secs(secs);
+    }
+    let invalid = || anyhow!("invalid time '{}': expected e.g. 500ms, 30s, 2m or 1m30s", text);
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    let mut total = 0.0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let split = rest.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(invalid)?;
+        let number: f64 = rest[..split].trim().parse().map_err(|_| invalid())?;
+        if !(number.is_finite() && number >= 0.0) {
+            return Err(invalid());
+        }
+        let unit_len = rest[split..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len() - split);
+        let factor = match &rest[split..split + unit_len] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return Err(invalid()),
+        };
+        total += number * factor;
+        rest = rest[split + unit_len..].trim_start();
+    }
+    offset_from_secs(total)
+}
+
+fn offset_from_secs(secs: f64) -> Result<Duration> {
+    if !(secs.is_finite() && secs >= 0.0) {
+        bail!("time must be 0 or later, got {}s", secs);
+    }
+    Ok(Duration::from_secs_f64(secs))
+}
+
+fn deserialize_offset<'de, D>(deserializer: D) -> std::result::Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Offset {
+        Secs(f64),
+        Text(String),
+    }
+    match Offset::deserialize(deserializer)? {
+        Offset::Secs(secs) => offset_from_secs(secs),
+        Offset::Text(text) => parse_offset(&text),
+    }
+    .map_err(serde::de::Error::custom)
+}
+
+/// Runs a scenario's steps as its time passes
+#[derive(Debug, Clone)]
+pub struct ScenarioRunner {
+    scenario: Scenario,
+    elapsed: Duration,
+    /// Index of the first step not yet run
+    next: usize,
+}
+
+impl ScenarioRunner {
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            scenario,
+            elapsed: Duration::ZERO,
+            next: 0,
+        }
+    }
+
+    /// Move scenario time on by `step` and return the actions that came due, in order
+    pub fn advance(&mut self, step: Duration) -> Vec<ScenarioAction> {
+        self.elapsed += step;
+        let due = self.scenario.steps[self.next..]
+            .iter()
+            .take_while(|s| s.at <= self.elapsed)
+            .map(|s| s.action.clone())
+            .collect::<Vec<_>>();
+        self.next += due.len();
+        due
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.scenario.steps.len()
+    }
+
+    /// `Scenario 2/5: preset satellite, next in 12s`, or `Scenario done (5 steps)`
+    pub fn status(&self) -> String {
+        let total = self.scenario.steps.len();
+        let current = match self.next {
+            0 => "not started".to_string(),
+            n => format!("{}/{}: {}", n, total, self.scenario.steps[n - 1].action),
+        };
+        match self.scenario.steps.get(self.next) {
+            Some(next) => format!(
+                "Scenario {}, next in {}s",
+                current,
+                next.at.saturating_sub(self.elapsed).as_secs_f64().ceil()
+            ),
+            None => format!("Scenario done ({} steps)", total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+[[step]]
+at = "1m"
+action = "inject_anomaly"
+metric = "latency"
+value = 800
+
+[[step]]
+at = "10s"
+action = "set_simulation"
+active = true
+
+[[step]]
+at = 30
+action = "set_preset"
+preset = "satellite"
+"#;
+
+    #[test]
+    fn steps_run_in_time_order_however_time_advances() {
+        let scenario = Scenario::parse(SCENARIO).unwrap();
+        let lines: Vec<usize> = scenario.steps.iter().map(|step| step.line).collect();
+        assert_eq!(lines, [8, 13, 2]);
+        scenario.check_profiles(&NetworkSimulation::default()).unwrap();
+
+        let expected = vec![
+            ScenarioAction::SetSimulation { active: true },
+            ScenarioAction::SetPreset { preset: "satellite".to_string() },
+            ScenarioAction::InjectAnomaly { metric: ScenarioMetric::Latency, value: 800.0 },
+        ];
+        for step in [Duration::from_millis(100), Duration::from_secs(7), Duration::from_secs(60)] {
+            let mut runner = ScenarioRunner::new(scenario.clone());
+            let mut ran = Vec::new();
+            while !runner.is_finished() {
+                ran.extend(runner.advance(step));
+            }
+            assert_eq!(ran, expected, "advancing by {:?}", step);
+            assert_eq!(runner.status(), "Scenario done (3 steps)");
+        }
+
+        let mut runner = ScenarioRunner::new(scenario);
+        assert_eq!(runner.status(), "Scenario not started, next in 10s");
+        runner.advance(Duration::from_secs(12));
+        assert_eq!(runner.status(), "Scenario 1/3: simulation on, next in 18s");
+    }
+
+    #[test]
+    fn errors_name_the_step_and_its_line() {
+        let unknown = "[[step]]\nat = \"5s\"\naction = \"reboot\"\n";
+        let error = Scenario::parse(unknown).unwrap_err().to_string();
+        assert!(error.starts_with("step 1 (line 1):"), "{}", error);
+
+        let bad_time = "[[step]]\nat = \"5s\"\naction = \"set_cloud\"\nactive = true\n\n[[step]]\nat = \"soon\"\naction = \"set_cloud\"\nactive = false\n";
+        let error = Scenario::parse(bad_time).unwrap_err().to_string();
+        assert!(error.starts_with("step 2 (line 6):") && error.contains("invalid time 'soon'"), "{}", error);
+
+        let unknown_preset = "[[step]]\nat = 1\naction = \"set_preset\"\npreset = \"dial-up\"\n";
+        let error = Scenario::parse(unknown_preset).unwrap().check_profiles(&NetworkSimulation::default()).unwrap_err();
+        assert!(error.to_string().contains("unknown network profile 'dial-up'"), "{}", error);
+
+        assert!(Scenario::parse("[[step]]\nat = 1\naction = \"set_cloud\"\nactive = true\ncolor = \"red\"\n").is_err());
+        assert!(Scenario::parse("").unwrap().steps.is_empty());
+    }
+
+    #[test]
+    fn offsets_parse_in_any_unit() {
+        let secs = |text| parse_offset(text).map(|d| d.as_secs_f64());
+        assert_eq!(secs("500ms").unwrap(), 0.5);
+        assert_eq!(secs("30s").unwrap(), 30.0);
+        assert_eq!(secs("1m30s").unwrap(), 90.0);
+        assert_eq!(secs("1h 2m").unwrap(), 3720.0);
+        assert_eq!(secs(" 12.5 ").unwrap(), 12.5);
+        for bad in ["", "s", "10x", "-5s", "-1", "NaN", "1m-30s"] {
+            assert!(secs(bad).is_err(), "{:?} parsed", bad);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Judging the certificate a server presented
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rustls::client::{verify_server_cert_signed_by_trust_anchor, verify_server_name};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::server::ParsedCertificate;
+use rustls::RootCertStore;
+use std::io::BufReader;
+use std::path::Path;
+
+/// When a certificate starts and stops being valid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Validity {
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+/// One DER element: its tag, its contents and what follows it
+fn element(input: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first().context("certificate truncated")?;
+    let (&first, rest) = rest.split_first().context("certificate truncated")?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            bail!("bad length in certificate");
+        }
+        let len = rest[..count].iter().fold(0usize, |len, &byte| (len << 8) | byte as usize);
+        (len, &rest[count..])
+    };
+    if rest.len() < len {
+        bail!("certificate truncated");
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+/// UTCTime (tag 0x17) or GeneralizedTime (0x18)
+fn time(tag: u8, contents: &[u8]) -> Result<DateTime<Utc>> {
+    let text = std::str::from_utf8(contents).context("certificate time is not text")?;
+    let full = match tag {
+        // Two-digit years from 50 on are in the 1900s (RFC 5280 4.1.2.5.1)
+        0x17 if text.len() == 13 => {
+            let century = if text[..2] >= *"50" { "19" } else { "20" };
+            format!("{}{}", century, text)
+        }
+        0x18 if text.len() == 15 => text.to_string(),
+        _ => bail!("unsupported certificate time {:?}", text),
+    };
+    let naive = NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .with_context(|| format!("bad certificate time {:?}", text))?;
+    Ok(naive.and_utc())
+}
+
+/// The validity period of a DER certificate
+pub fn validity(cert: &[u8]) -> Result<Validity> {
+    let (_, certificate, _) = element(cert)?;
+    let (_, tbs, _) = element(certificate)?;
+    let (tag, _, mut rest) = element(tbs)?;
+    // An explicit version comes first, then the serial number
+    if tag == 0xa0 {
+        rest = element(rest)?.2;
+    }
+    // Signature algorithm and issuer
+    rest = element(element(rest)?.2)?.2;
+    let (tag, validity, _) = element(rest)?;
+    if tag != 0x30 {
+        bail!("certificate validity not found");
+    }
+    let (tag, not_before, rest) = element(validity)?;
+    let not_before = time(tag, not_before)?;
+    let (tag, not_after, _) = element(rest)?;
+    let not_after = time(tag, not_after)?;
+    Ok(Validity { not_before, not_after })
+}
+
+/// CA certificates read from a PEM file
+pub fn load_roots(path: &Path) -> Result<RootCertStore> {
+    let pem = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut roots = RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut BufReader::new(pem.as_slice())) {
+        let cert = cert.with_context(|| format!("invalid PEM in {}", path.display()))?;
+        roots
+            .add(cert)
+            .with_context(|| format!("unusable CA certificate in {}", path.display()))?;
+    }
+    if roots.is_empty() {
+        bail!("no certificate found in {}", path.display());
+    }
+    Ok(roots)
+}
+
+/// Whether the leaf of `chain` is issued for `server_name`
+pub fn check_name(chain: &[CertificateDer<'_>], server_name: &ServerName<'_>) -> Result<()> {
+    let leaf = ParsedCertificate::try_from(chain.first().context("no certificate presented")?)?;
+    verify_server_name(&leaf, server_name)?;
+    Ok(())
+}
+
+/// Whether `chain` leads to one of `roots`, valid at `now`
+pub fn check_trust(chain: &[CertificateDer<'_>], roots: &RootCertStore, now: DateTime<Utc>) -> Result<()> {
+    let leaf = ParsedCertificate::try_from(chain.first().context("no certificate presented")?)?;
+    let now = UnixTime::since_unix_epoch(std::time::Duration::from_secs(now.timestamp().max(0) as u64));
+    let algorithms = rustls::crypto::ring::default_provider().signature_verification_algorithms;
+    verify_server_cert_signed_by_trust_anchor(&leaf, roots, &chain[1..], now, algorithms.all)?;
+    Ok(())
+}
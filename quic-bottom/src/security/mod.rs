@@ -0,0 +1,875 @@
+//! Security checks against the QUIC endpoint under test
+//!
+//! `[security] target` names the endpoint (`host:port`, over UDP). Four
+//! checks can be run against it:
+//!
+//! - `certificate`: a QUIC handshake, and the certificate it brings checked
+//!   for expiry, for the server name and, with `ca_cert`, for a chain to a
+//!   trusted CA
+//! - `negotiation`: a reserved version must be answered with a Version
+//!   Negotiation offering QUIC v1, and the handshake must agree on one of the
+//!   `alpn` protocols
+//! - `amplification`: what the server sends back to one Initial it never
+//!   hears from again, which RFC 9000 limits to three times what it received
+//! - `flood`: `concurrency` connections opened at once and left half open,
+//!   after which a new client must still get an answer
+//!
+//! Checks run when asked ('s' in `quic-bottom live`, `POST /api/security`).
+//! Each has its own `enabled` and `min_interval_secs`; one asked for again
+//! sooner keeps its last result. The flood check is off by default, being a
+//! small load test of the server.
+
+mod cert;
+mod probe;
+mod wire;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rustls::pki_types::ServerName;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::sync::MutexExt;
+use probe::{Echo, Flood, Handshake, Target};
+use wire::{version_name, PeerClosed, QUIC_V1};
+
+/// Most connections the flood check may open at once
+pub const MAX_FLOOD_CONCURRENCY: usize = 1024;
+
+/// Shortest wait for the server that is allowed
+const MIN_TIMEOUT_MS: u64 = 100;
+
+/// Bytes a server may send per byte received before the client's address is validated
+const AMPLIFICATION_LIMIT: f64 = 3.0;
+
+/// TLS alert a server sends when it shares no ALPN protocol with the client
+const NO_APPLICATION_PROTOCOL: u8 = 120;
+
+/// Security check configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    /// `host:port` of the QUIC endpoint; nothing is checked without it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Name sent as SNI and looked for in the certificate; the target's host when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+
+    /// ALPN protocols offered, most preferred first
+    pub alpn: Vec<String>,
+
+    /// PEM file of the CA certificates the server's chain must lead to;
+    /// without it the chain of trust is not checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
+
+    /// How long each probe waits for the server
+    pub timeout_ms: u64,
+
+    /// A certificate expiring within this many days is a warning
+    pub expiry_warning_days: u32,
+
+    pub certificate: CheckConfig,
+    pub negotiation: CheckConfig,
+    pub amplification: CheckConfig,
+    pub flood: FloodConfig,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            target: None,
+            server_name: None,
+            alpn: vec!["h3".to_string()],
+            ca_cert: None,
+            timeout_ms: 3000,
+            expiry_warning_days: 14,
+            certificate: CheckConfig::default(),
+            negotiation: CheckConfig::default(),
+            amplification: CheckConfig::default(),
+            flood: FloodConfig::default(),
+        }
+    }
+}
+
+/// Whether a check runs, and how often it may
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckConfig {
+    pub enabled: bool,
+    /// Least time between two runs; asking sooner keeps the last result
+    pub min_interval_secs: u64,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_secs: 60,
+        }
+    }
+}
+
+/// The flood check, which needs turning on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FloodConfig {
+    pub enabled: bool,
+    pub min_interval_secs: u64,
+    /// Connections opened at once
+    pub concurrency: usize,
+}
+
+impl Default for FloodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: 600,
+            concurrency: 32,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Check the target, names and limits
+    pub fn validate(&self) -> Result<()> {
+        if let Some(target) = &self.target {
+            split_target(target).context("target")?;
+        }
+        if let Some(name) = &self.server_name {
+            ServerName::try_from(name.as_str()).map_err(|_| anyhow!("server_name: {:?} is not a DNS name or IP address", name))?;
+        }
+        if self.alpn.is_empty() {
+            bail!("alpn: offer at least one protocol");
+        }
+        if let Some(protocol) = self.alpn.iter().find(|protocol| protocol.is_empty() || protocol.len() > 255) {
+            bail!("alpn: {:?} must be 1 to 255 bytes long", protocol);
+        }
+        if self.timeout_ms < MIN_TIMEOUT_MS {
+            bail!("timeout_ms: must be at least {}, got {}", MIN_TIMEOUT_MS, self.timeout_ms);
+        }
+        if !(1..=MAX_FLOOD_CONCURRENCY).contains(&self.flood.concurrency) {
+            bail!(
+                "flood.concurrency: must be between 1 and {}, got {}",
+                MAX_FLOOD_CONCURRENCY,
+                self.flood.concurrency
+            );
+        }
+        Ok(())
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    /// Whether `check` is on and how often it may run
+    pub fn schedule(&self, check: CheckKind) -> CheckConfig {
+        match check {
+            CheckKind::Certificate => self.certificate,
+            CheckKind::Negotiation => self.negotiation,
+            CheckKind::Amplification => self.amplification,
+            CheckKind::Flood => CheckConfig {
+                enabled: self.flood.enabled,
+                min_interval_secs: self.flood.min_interval_secs,
+            },
+        }
+    }
+
+    /// Whether results taken under `self` still describe the endpoint of `other`
+    fn same_endpoint(&self, other: &SecurityConfig) -> bool {
+        self.target == other.target && self.server_name == other.server_name && self.alpn == other.alpn
+    }
+}
+
+/// Host and port of `host:port` or `[v6 address]:port`
+fn split_target(target: &str) -> Result<(&str, u16)> {
+    let (host, port) = target
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("expected host:port, got {:?}", target))?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    let port = port
+        .parse::<u16>()
+        .ok()
+        .filter(|&port| port != 0)
+        .ok_or_else(|| anyhow!("bad port in {:?}", target))?;
+    if host.is_empty() {
+        bail!("no host in {:?}", target);
+    }
+    Ok((host, port))
+}
+
+/// The checks, in the order they run and are listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    Certificate,
+    Negotiation,
+    Amplification,
+    Flood,
+}
+
+impl CheckKind {
+    pub const ALL: [CheckKind; 4] = [
+        CheckKind::Certificate,
+        CheckKind::Negotiation,
+        CheckKind::Amplification,
+        CheckKind::Flood,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckKind::Certificate => "Certificate",
+            CheckKind::Negotiation => "Version / ALPN negotiation",
+            CheckKind::Amplification => "Amplification limit",
+            CheckKind::Flood => "Connection flood",
+        }
+    }
+}
+
+/// Verdict of a check, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// The check could not reach a verdict, e.g. the server did not answer
+    Error,
+}
+
+impl CheckStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Error => "ERROR",
+        }
+    }
+}
+
+/// Latest result of one check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub check: CheckKind,
+    pub status: CheckStatus,
+    /// The worst finding, or what passed
+    pub summary: String,
+    /// Every finding, one line each
+    pub details: Vec<String>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// Results of the checks against the target, as `GET /api/security` returns them
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SecurityReport {
+    pub target: Option<String>,
+    /// Checks turned on under `[security]`
+    pub enabled: Vec<CheckKind>,
+    /// Whether checks are running now
+    pub running: bool,
+    /// Latest result of each check that has run, in check order
+    pub checks: Vec<CheckResult>,
+}
+
+impl SecurityReport {
+    pub fn result(&self, check: CheckKind) -> Option<&CheckResult> {
+        self.checks.iter().find(|result| result.check == check)
+    }
+}
+
+/// A check left out of a run for having run too recently
+#[derive(Debug, Clone, Serialize)]
+pub struct HeldCheck {
+    pub check: CheckKind,
+    /// Seconds until it may run again
+    pub retry_in_secs: u64,
+}
+
+/// What a request to run the checks started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunRequest {
+    pub started: Vec<CheckKind>,
+    pub held: Vec<HeldCheck>,
+}
+
+struct ScanState {
+    report: SecurityReport,
+    /// When each check last started, for `min_interval_secs`
+    last_started: HashMap<CheckKind, Instant>,
+}
+
+/// Runs the checks in the background and keeps their latest results
+pub struct SecurityScanner {
+    config: Mutex<SecurityConfig>,
+    state: Mutex<ScanState>,
+    /// Bumped whenever the report changes, so a view can tell it has to redraw
+    revision: AtomicU64,
+}
+
+impl SecurityScanner {
+    pub fn new(config: SecurityConfig) -> Self {
+        Self {
+            config: Mutex::new(config),
+            state: Mutex::new(ScanState {
+                report: SecurityReport::default(),
+                last_started: HashMap::new(),
+            }),
+            revision: AtomicU64::new(0),
+        }
+    }
+
+    /// Apply a reloaded `[security]`; results about another endpoint are dropped
+    pub fn set_config(&self, config: SecurityConfig) {
+        let mut current = self.config.lock_or_recover();
+        if !current.same_endpoint(&config) {
+            let mut state = self.state.lock_or_recover();
+            state.report.checks.clear();
+            state.last_started.clear();
+            self.revision.fetch_add(1, Ordering::Relaxed);
+        }
+        *current = config;
+    }
+
+    pub fn report(&self) -> SecurityReport {
+        let config = self.config.lock_or_recover().clone();
+        let mut report = self.state.lock_or_recover().report.clone();
+        report.target = config.target.clone();
+        report.enabled = CheckKind::ALL
+            .into_iter()
+            .filter(|&check| config.schedule(check).enabled)
+            .collect();
+        report
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Relaxed)
+    }
+
+    /// Start the enabled checks that may run again; they finish in the background
+    ///
+    /// Fails without a target, with every check disabled, or while a run is
+    /// still going.
+    pub fn run(self: &Arc<Self>) -> Result<RunRequest> {
+        let config = self.config.lock_or_recover().clone();
+        if config.target.is_none() {
+            bail!("no [security] target configured");
+        }
+        let mut state = self.state.lock_or_recover();
+        if state.report.running {
+            bail!("security checks are already running");
+        }
+
+        let now = Instant::now();
+        let mut request = RunRequest::default();
+        let mut enabled = false;
+        for check in CheckKind::ALL {
+            let schedule = config.schedule(check);
+            enabled |= schedule.enabled;
+            if !schedule.enabled {
+                continue;
+            }
+            let interval = Duration::from_secs(schedule.min_interval_secs);
+            match state.last_started.get(&check).map(|at| now.duration_since(*at)) {
+                Some(since) if since < interval => request.held.push(HeldCheck {
+                    check,
+                    retry_in_secs: (interval - since).as_secs().max(1),
+                }),
+                _ => {
+                    state.last_started.insert(check, now);
+                    request.started.push(check);
+                }
+            }
+        }
+        if !enabled {
+            bail!("every security check is disabled");
+        }
+
+        if !request.started.is_empty() {
+            state.report.running = true;
+            self.revision.fetch_add(1, Ordering::Relaxed);
+            let scanner = self.clone();
+            let checks = request.started.clone();
+            tokio::spawn(async move {
+                run_checks(&scanner, &config, &checks).await;
+                scanner.state.lock_or_recover().report.running = false;
+                scanner.revision.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        Ok(request)
+    }
+
+    /// Keep `result` unless the endpoint changed since `config` was current
+    fn store(&self, config: &SecurityConfig, result: CheckResult) {
+        if !self.config.lock_or_recover().same_endpoint(config) {
+            return;
+        }
+        let mut state = self.state.lock_or_recover();
+        let checks = &mut state.report.checks;
+        checks.retain(|stored| stored.check != result.check);
+        checks.push(result);
+        checks.sort_by_key(|stored| stored.check);
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Findings of one check, the worst of which is its verdict
+struct Finding {
+    status: CheckStatus,
+    summary: Option<String>,
+    details: Vec<String>,
+}
+
+impl Finding {
+    fn new() -> Self {
+        Self {
+            status: CheckStatus::Pass,
+            summary: None,
+            details: Vec::new(),
+        }
+    }
+
+    fn error(message: String) -> Self {
+        let mut finding = Self::new();
+        finding.problem(CheckStatus::Error, message);
+        finding
+    }
+
+    fn note(&mut self, detail: String) {
+        self.details.push(detail);
+    }
+
+    fn problem(&mut self, status: CheckStatus, message: String) {
+        if status > self.status {
+            self.status = status;
+            self.summary = Some(message.clone());
+        }
+        self.details.push(message);
+    }
+
+    /// The result, summarized by `passed` when nothing was wrong
+    fn finish(self, check: CheckKind, started: Instant, passed: String) -> CheckResult {
+        CheckResult {
+            check,
+            status: self.status,
+            summary: self.summary.unwrap_or(passed),
+            details: self.details,
+            finished_at: Utc::now(),
+            duration_ms: started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Resolve the target and set up TLS for it
+async fn connect_target(config: &SecurityConfig) -> Result<Target> {
+    let target = config.target.as_deref().context("no target")?;
+    let (host, port) = split_target(target)?;
+    let addr = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("failed to resolve {}", host))?
+        .next()
+        .with_context(|| format!("{} has no address", host))?;
+    let name = config.server_name.as_deref().unwrap_or(host).to_string();
+    let server_name = ServerName::try_from(name.clone()).map_err(|_| anyhow!("{:?} is not a valid server name", name))?;
+    Target::new(addr, server_name, &config.alpn)
+}
+
+async fn run_checks(scanner: &SecurityScanner, config: &SecurityConfig, checks: &[CheckKind]) {
+    let started = Instant::now();
+    let target = match connect_target(config).await {
+        Ok(target) => target,
+        Err(e) => {
+            for &check in checks {
+                scanner.store(config, Finding::error(format!("{:#}", e)).finish(check, started, String::new()));
+            }
+            return;
+        }
+    };
+    let timeout = config.timeout();
+
+    // The certificate and negotiation checks share one handshake, timed with the former
+    let handshake_started = Instant::now();
+    let handshake = if checks.contains(&CheckKind::Certificate) || checks.contains(&CheckKind::Negotiation) {
+        Some(probe::handshake(&target, timeout).await)
+    } else {
+        None
+    };
+    for &check in checks {
+        let started = Instant::now();
+        let result = match (check, &handshake) {
+            (CheckKind::Certificate, Some(handshake)) => {
+                check_certificate(config, &target, handshake, handshake_started)
+            }
+            (CheckKind::Negotiation, Some(handshake)) => {
+                let versions = probe::version_negotiation(&target, timeout).await;
+                check_negotiation(config, &versions, handshake, started)
+            }
+            (CheckKind::Amplification, _) => {
+                check_amplification(probe::unanswered_initial(&target, timeout).await, timeout, started)
+            }
+            (CheckKind::Flood, _) => {
+                check_flood(probe::flood(&target, config.flood.concurrency, timeout).await, started)
+            }
+            (CheckKind::Certificate | CheckKind::Negotiation, None) => continue,
+        };
+        log::info!("security check {:?}: {} ({})", check, result.status.label(), result.summary);
+        scanner.store(config, result);
+    }
+}
+
+fn check_certificate(config: &SecurityConfig, target: &Target, handshake: &Result<Handshake>, started: Instant) -> CheckResult {
+    let check = CheckKind::Certificate;
+    let handshake = match handshake {
+        Ok(handshake) => handshake,
+        Err(e) => return Finding::error(format!("{:#}", e)).finish(check, started, String::new()),
+    };
+    let chain = &handshake.certificates;
+    let Some(leaf) = chain.first() else {
+        let mut finding = Finding::new();
+        finding.problem(CheckStatus::Fail, "no certificate presented".to_string());
+        return finding.finish(check, started, String::new());
+    };
+
+    let now = Utc::now();
+    let mut finding = Finding::new();
+    let mut passed = "valid".to_string();
+    finding.note(format!("{} certificate(s) in the chain", chain.len()));
+    match cert::validity(leaf) {
+        Ok(validity) => {
+            finding.note(format!(
+                "valid from {} to {}",
+                validity.not_before.format("%Y-%m-%d %H:%M UTC"),
+                validity.not_after.format("%Y-%m-%d %H:%M UTC")
+            ));
+            let days_left = (validity.not_after - now).num_days();
+            if now < validity.not_before {
+                finding.problem(CheckStatus::Fail, format!("not valid before {}", validity.not_before.format("%Y-%m-%d")));
+            } else if now > validity.not_after {
+                finding.problem(CheckStatus::Fail, format!("expired {} day(s) ago", (now - validity.not_after).num_days()));
+            } else if days_left < i64::from(config.expiry_warning_days) {
+                finding.problem(CheckStatus::Warn, format!("expires in {} day(s)", days_left));
+            } else {
+                passed = format!("valid for {} more days", days_left);
+            }
+        }
+        Err(e) => finding.problem(CheckStatus::Warn, format!("validity period unreadable: {:#}", e)),
+    }
+
+    let name = target.server_name.to_str();
+    match cert::check_name(chain, &target.server_name) {
+        Ok(()) => finding.note(format!("issued for {}", name)),
+        Err(e) => finding.problem(CheckStatus::Fail, format!("not issued for {}: {}", name, e)),
+    }
+    match &config.ca_cert {
+        Some(path) => match cert::load_roots(path).and_then(|roots| cert::check_trust(chain, &roots, now)) {
+            Ok(()) => finding.note(format!("chain leads to a CA in {}", path.display())),
+            Err(e) => finding.problem(CheckStatus::Fail, format!("chain not trusted: {:#}", e)),
+        },
+        None => finding.note("chain of trust not checked: no ca_cert set".to_string()),
+    }
+    finding.finish(check, started, passed)
+}
+
+fn check_negotiation(
+    config: &SecurityConfig,
+    versions: &Result<Option<Vec<u32>>>,
+    handshake: &Result<Handshake>,
+    started: Instant,
+) -> CheckResult {
+    let mut finding = Finding::new();
+    match versions {
+        Ok(Some(versions)) => {
+            let names: Vec<String> = versions.iter().map(|&version| version_name(version)).collect();
+            finding.note(format!("Version Negotiation offers {}", names.join(", ")));
+            if !versions.contains(&QUIC_V1) {
+                finding.problem(CheckStatus::Fail, "QUIC v1 not offered in Version Negotiation".to_string());
+            }
+        }
+        Ok(None) => finding.problem(
+            CheckStatus::Warn,
+            "no Version Negotiation reply to a reserved version".to_string(),
+        ),
+        Err(e) => finding.problem(CheckStatus::Error, format!("version probe failed: {:#}", e)),
+    }
+
+    let mut passed = "QUIC v1".to_string();
+    match handshake {
+        Ok(handshake) => {
+            if handshake.retried {
+                finding.note("address validated with a Retry".to_string());
+            }
+            match &handshake.alpn {
+                Some(protocol) => {
+                    let protocol = String::from_utf8_lossy(protocol);
+                    finding.note(format!("ALPN {} agreed in {} ms", protocol, handshake.elapsed.as_millis()));
+                    passed = format!("QUIC v1, ALPN {}", protocol);
+                }
+                None => finding.problem(CheckStatus::Fail, "no ALPN protocol agreed".to_string()),
+            }
+        }
+        Err(e) => match e.downcast_ref::<PeerClosed>() {
+            Some(closed) if closed.tls_alert() == Some(NO_APPLICATION_PROTOCOL) => finding.problem(
+                CheckStatus::Fail,
+                format!("server accepts none of the ALPN protocols {}", config.alpn.join(", ")),
+            ),
+            _ => finding.problem(CheckStatus::Error, format!("{:#}", e)),
+        },
+    }
+    finding.finish(CheckKind::Negotiation, started, passed)
+}
+
+fn check_amplification(echo: Result<Echo>, listen: Duration, started: Instant) -> CheckResult {
+    let check = CheckKind::Amplification;
+    let echo = match echo {
+        Ok(echo) if echo.datagrams == 0 => {
+            let message = format!("no reply within {} ms", listen.as_millis());
+            return Finding::error(message).finish(check, started, String::new());
+        }
+        Ok(echo) => echo,
+        Err(e) => return Finding::error(format!("{:#}", e)).finish(check, started, String::new()),
+    };
+
+    let mut finding = Finding::new();
+    let factor = echo.received as f64 / echo.sent as f64;
+    finding.note(format!(
+        "{} bytes in {} datagram(s) back for {} sent ({:.1}x)",
+        echo.received, echo.datagrams, echo.sent, factor
+    ));
+    if echo.retry {
+        finding.note("answered with a Retry, validating the address first".to_string());
+    }
+    if factor > AMPLIFICATION_LIMIT {
+        finding.problem(
+            CheckStatus::Fail,
+            format!("sent {:.1}x the bytes of an unvalidated client, over the {}x limit", factor, AMPLIFICATION_LIMIT),
+        );
+    }
+    finding.finish(check, started, format!("{:.1}x the bytes received (limit {}x)", factor, AMPLIFICATION_LIMIT))
+}
+
+fn check_flood(flood: Result<Flood>, started: Instant) -> CheckResult {
+    let check = CheckKind::Flood;
+    let flood = match flood {
+        Ok(flood) => flood,
+        Err(e) => return Finding::error(format!("{:#}", e)).finish(check, started, String::new()),
+    };
+
+    let mut finding = Finding::new();
+    finding.note(format!(
+        "{} of {} connections answered, {} with a Retry",
+        flood.answered + flood.retried,
+        flood.attempts,
+        flood.retried
+    ));
+    match flood.followup {
+        Some(after) => finding.note(format!("a new client was answered in {} ms afterwards", after.as_millis())),
+        None => finding.problem(
+            CheckStatus::Fail,
+            format!("a new client got no answer after {} half-open connections", flood.attempts),
+        ),
+    }
+    finding.finish(check, started, format!("still answering after {} half-open connections", flood.attempts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::quic::{self, KeyChange};
+    use std::collections::HashSet;
+    use std::io::BufReader;
+    use std::net::SocketAddr;
+    use std::path::Path;
+    use tokio::net::UdpSocket;
+    use wire::{
+        close_frame, crypto_frame, initial_keys, open, parse_frames, parse_long_header, random_cid, seal,
+        CryptoStream, Frame, Outgoing, PacketType,
+    };
+
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name)
+    }
+
+    /// TLS of a server with the self-signed localhost certificate, agreeing on `alpn`
+    fn server_tls(alpn: &str) -> Arc<rustls::ServerConfig> {
+        let cert = std::fs::read(testdata("localhost.crt")).unwrap();
+        let key = std::fs::read(testdata("localhost.key")).unwrap();
+        let chain = rustls_pemfile::certs(&mut BufReader::new(cert.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let key = rustls_pemfile::private_key(&mut BufReader::new(key.as_slice())).unwrap().unwrap();
+        let mut config = rustls::ServerConfig::builder_with_protocol_versions(&[&rustls::version::TLS13])
+            .with_no_client_auth()
+            .with_single_cert(chain, key)
+            .unwrap();
+        config.alpn_protocols = vec![alpn.as_bytes().to_vec()];
+        Arc::new(config)
+    }
+
+    /// What a QUIC v1 server sends back for a client's first datagram
+    ///
+    /// A Version Negotiation for another version; otherwise its whole
+    /// handshake flight in an Initial and a Handshake packet, or a
+    /// CONNECTION_CLOSE carrying the TLS alert when it refuses the client.
+    fn answer(tls: &Arc<rustls::ServerConfig>, datagram: &mut [u8]) -> Result<Vec<u8>> {
+        let header = parse_long_header(datagram)?.context("short header")?;
+        if header.version != QUIC_V1 {
+            let mut reply = vec![0x80, 0, 0, 0, 0];
+            for cid in [&header.scid, &header.dcid] {
+                reply.push(cid.len() as u8);
+                reply.extend_from_slice(cid);
+            }
+            reply.extend_from_slice(&QUIC_V1.to_be_bytes());
+            return Ok(reply);
+        }
+        if header.packet_type != PacketType::Initial {
+            bail!("not an Initial");
+        }
+
+        // The client's Initial keys, read and written the other way round
+        let keys = initial_keys(&header.dcid)?;
+        let (_, payload) = open(&keys.local, &mut datagram[..header.len], header.pn_offset, None)?;
+        let mut crypto = CryptoStream::default();
+        for frame in parse_frames(&payload)? {
+            if let Frame::Crypto { offset, data } = frame {
+                crypto.insert(offset, data);
+            }
+        }
+        let scid = random_cid();
+        let outgoing = |packet_type| Outgoing {
+            packet_type,
+            version: QUIC_V1,
+            dcid: &header.scid,
+            scid: &scid,
+            token: &[],
+            pn: 0,
+        };
+
+        let mut server = quic::ServerConnection::new(tls.clone(), quic::Version::V1, Vec::new())?;
+        if server.read_hs(&crypto.take_ready()).is_err() {
+            let alert = server.alert().map_or(0, |alert| alert.get_u8());
+            return seal(&keys.remote, &outgoing(PacketType::Initial), &close_frame(0x100 + u64::from(alert)), 0);
+        }
+        let mut hello = Vec::new();
+        let Some(KeyChange::Handshake { keys: handshake }) = server.write_hs(&mut hello) else {
+            bail!("no handshake keys after the ServerHello");
+        };
+        let mut flight = Vec::new();
+        server.write_hs(&mut flight);
+
+        let mut reply = seal(&keys.remote, &outgoing(PacketType::Initial), &crypto_frame(0, &hello), 0)?;
+        reply.extend(seal(&handshake.local, &outgoing(PacketType::Handshake), &crypto_frame(0, &flight), 0)?);
+        Ok(reply)
+    }
+
+    /// A server on localhost answering each client's first datagram, and nothing after it
+    async fn serve(alpn: &str) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        let tls = server_tls(alpn);
+        tokio::spawn(async move {
+            let mut buf = vec![0u8; 65_527];
+            let mut answered = HashSet::new();
+            while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+                if answered.contains(&peer) {
+                    continue;
+                }
+                if let Ok(reply) = answer(&tls, &mut buf[..len]) {
+                    answered.insert(peer);
+                    let _ = socket.send_to(&reply, peer).await;
+                }
+            }
+        });
+        addr
+    }
+
+    fn config(addr: SocketAddr) -> SecurityConfig {
+        SecurityConfig {
+            target: Some(addr.to_string()),
+            server_name: Some("localhost".to_string()),
+            ca_cert: Some(testdata("localhost.crt")),
+            timeout_ms: 500,
+            flood: FloodConfig {
+                enabled: true,
+                concurrency: 4,
+                ..FloodConfig::default()
+            },
+            ..SecurityConfig::default()
+        }
+    }
+
+    /// Run every enabled check and wait for the report
+    async fn scan(config: SecurityConfig) -> SecurityReport {
+        let scanner = Arc::new(SecurityScanner::new(config));
+        let request = scanner.run().unwrap();
+        assert_eq!(request.started, CheckKind::ALL);
+        while scanner.report().running {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        scanner.report()
+    }
+
+    fn verdict(report: &SecurityReport, check: CheckKind) -> (CheckStatus, String) {
+        let result = report.result(check).unwrap();
+        (result.status, result.summary.clone())
+    }
+
+    #[tokio::test]
+    async fn checks_pass_against_a_well_behaved_server() {
+        let report = scan(config(serve("h3").await)).await;
+
+        let (status, summary) = verdict(&report, CheckKind::Certificate);
+        assert_eq!(status, CheckStatus::Pass, "{}", summary);
+        assert!(summary.starts_with("valid for "), "{}", summary);
+        let details = &report.result(CheckKind::Certificate).unwrap().details;
+        assert!(details.contains(&"issued for localhost".to_string()), "{:?}", details);
+        assert!(details.iter().any(|detail| detail.starts_with("chain leads to a CA")), "{:?}", details);
+
+        assert_eq!(verdict(&report, CheckKind::Negotiation), (CheckStatus::Pass, "QUIC v1, ALPN h3".to_string()));
+        assert_eq!(verdict(&report, CheckKind::Amplification).0, CheckStatus::Pass);
+        assert_eq!(
+            verdict(&report, CheckKind::Flood),
+            (CheckStatus::Pass, "still answering after 4 half-open connections".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_name_and_alpn_fail() {
+        let mut wrong_name = config(serve("h3").await);
+        wrong_name.server_name = Some("example.com".to_string());
+        let report = scan(wrong_name).await;
+        let (status, summary) = verdict(&report, CheckKind::Certificate);
+        assert_eq!(status, CheckStatus::Fail);
+        assert!(summary.starts_with("not issued for example.com"), "{}", summary);
+
+        let report = scan(config(serve("hq-interop").await)).await;
+        let (status, summary) = verdict(&report, CheckKind::Negotiation);
+        assert_eq!(status, CheckStatus::Fail);
+        assert_eq!(summary, "server accepts none of the ALPN protocols h3");
+        let (status, summary) = verdict(&report, CheckKind::Certificate);
+        assert_eq!(status, CheckStatus::Error);
+        assert!(summary.contains("TLS alert 120"), "{}", summary);
+    }
+
+    #[tokio::test]
+    async fn silent_server_is_an_error_not_a_pass() {
+        // Bound, so nothing answers with ICMP either
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let report = scan(config(silent.local_addr().unwrap())).await;
+
+        let (status, summary) = verdict(&report, CheckKind::Certificate);
+        assert_eq!(status, CheckStatus::Error);
+        assert_eq!(summary, "no handshake within 500 ms");
+        assert_eq!(verdict(&report, CheckKind::Amplification), (CheckStatus::Error, "no reply within 500 ms".to_string()));
+        assert_eq!(verdict(&report, CheckKind::Flood).0, CheckStatus::Fail);
+    }
+}
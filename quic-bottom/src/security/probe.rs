@@ -0,0 +1,539 @@
+//! The probes the checks send: a handshake, an unanswered Initial, a
+//! reserved version and a burst of Initials
+
+use anyhow::{anyhow, bail, Context, Result};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, WebPkiSupportedAlgorithms};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::quic::{self, KeyChange, Keys};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::task::JoinSet;
+use tokio::time::timeout_at;
+
+use super::wire::{
+    ack_frame, close_frame, crypto_frame, initial_keys, open, parse_frames, parse_long_header, random_cid,
+    reserved_version, seal, CryptoStream, Frame, Outgoing, PacketType, PeerClosed, MIN_INITIAL_SIZE, QUIC_V1,
+};
+
+/// Largest datagram read
+const MAX_DATAGRAM: usize = 65_527;
+
+/// An unanswered Initial is sent again after this long
+const RESEND_AFTER: Duration = Duration::from_millis(1000);
+
+/// Accepts every certificate, still checking the handshake signature
+///
+/// The certificate check judges the chain itself and reports what is wrong
+/// with it, which needs the handshake to get that far.
+#[derive(Debug)]
+struct AcceptAnyCertificate {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// Where and how to reach the server
+#[derive(Clone)]
+pub struct Target {
+    pub addr: SocketAddr,
+    pub server_name: ServerName<'static>,
+    tls: Arc<ClientConfig>,
+}
+
+impl Target {
+    /// TLS 1.3 offering `alpn`, most preferred first
+    pub fn new(addr: SocketAddr, server_name: ServerName<'static>, alpn: &[String]) -> Result<Self> {
+        let provider = rustls::crypto::ring::default_provider();
+        let verifier = AcceptAnyCertificate {
+            algorithms: provider.signature_verification_algorithms,
+        };
+        let mut tls = ClientConfig::builder_with_protocol_versions(&[&rustls::version::TLS13])
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        tls.alpn_protocols = alpn.iter().map(|protocol| protocol.as_bytes().to_vec()).collect();
+        Ok(Self {
+            addr,
+            server_name,
+            tls: Arc::new(tls),
+        })
+    }
+
+    async fn socket(&self) -> Result<UdpSocket> {
+        let local: SocketAddr = if self.addr.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local).await.context("failed to open a UDP socket")?;
+        socket
+            .connect(self.addr)
+            .await
+            .with_context(|| format!("failed to reach {}", self.addr))?;
+        Ok(socket)
+    }
+}
+
+/// What the handshake probe learned
+#[derive(Debug)]
+pub struct Handshake {
+    pub certificates: Vec<CertificateDer<'static>>,
+    pub alpn: Option<Vec<u8>>,
+    /// Whether the server asked for address validation with a Retry first
+    pub retried: bool,
+    pub elapsed: Duration,
+}
+
+/// Packets received and sent in one packet number space
+struct Space {
+    keys: Keys,
+    largest: Option<u64>,
+    next_pn: u64,
+    crypto: CryptoStream,
+}
+
+impl Space {
+    fn new(keys: Keys) -> Self {
+        Self {
+            keys,
+            largest: None,
+            next_pn: 0,
+            crypto: CryptoStream::default(),
+        }
+    }
+
+    fn take_pn(&mut self) -> u64 {
+        self.next_pn += 1;
+        self.next_pn - 1
+    }
+}
+
+/// What a datagram from the server changed
+enum Progress {
+    /// Keep reading
+    Continue,
+    /// The server asked for a Retry; the Initial has to go out again
+    Retry,
+    /// The server's handshake flight is complete
+    Done,
+}
+
+/// A client connection that goes as far as the server's handshake flight
+struct Client {
+    socket: UdpSocket,
+    tls: quic::ClientConnection,
+    client_hello: Vec<u8>,
+    /// Destination Connection ID: ours at first, then the one the server picked
+    dcid: Vec<u8>,
+    scid: Vec<u8>,
+    token: Vec<u8>,
+    initial: Space,
+    handshake: Option<Space>,
+    /// Whether the server's first Initial arrived, fixing its connection ID
+    answered: bool,
+    retried: bool,
+}
+
+impl Client {
+    async fn new(target: &Target) -> Result<Self> {
+        let scid = random_cid();
+        // Transport parameters: initial_source_connection_id, and a 10 s max_idle_timeout
+        let mut params = vec![0x0f, scid.len() as u8];
+        params.extend_from_slice(&scid);
+        params.extend_from_slice(&[0x01, 0x02, 0x67, 0x10]);
+        let mut tls = quic::ClientConnection::new(target.tls.clone(), quic::Version::V1, target.server_name.clone(), params)
+            .map_err(|e| anyhow!("TLS setup failed: {}", e))?;
+        let mut client_hello = Vec::new();
+        tls.write_hs(&mut client_hello);
+
+        let dcid = random_cid();
+        Ok(Self {
+            socket: target.socket().await?,
+            tls,
+            client_hello,
+            initial: Space::new(initial_keys(&dcid)?),
+            dcid,
+            scid,
+            token: Vec::new(),
+            handshake: None,
+            answered: false,
+            retried: false,
+        })
+    }
+
+    fn outgoing(&self, packet_type: PacketType, pn: u64) -> Outgoing<'_> {
+        Outgoing {
+            packet_type,
+            version: QUIC_V1,
+            dcid: &self.dcid,
+            scid: &self.scid,
+            token: &self.token,
+            pn,
+        }
+    }
+
+    /// Send the ClientHello in a full-size Initial; the bytes sent
+    async fn send_initial(&mut self) -> Result<usize> {
+        let pn = self.initial.take_pn();
+        let frames = crypto_frame(0, &self.client_hello);
+        let packet = seal(&self.initial.keys.local, &self.outgoing(PacketType::Initial, pn), &frames, MIN_INITIAL_SIZE)?;
+        self.socket.send(&packet).await.context("failed to send")
+    }
+
+    /// Acknowledge what arrived, which also lets the server send three times as much again
+    async fn send_acks(&mut self) -> Result<()> {
+        let mut handshake_packet = Vec::new();
+        if let Some(largest) = self.handshake.as_ref().and_then(|space| space.largest) {
+            let pn = self.handshake.as_mut().map_or(0, Space::take_pn);
+            let space = self.handshake.as_ref().context("handshake keys missing")?;
+            handshake_packet = seal(&space.keys.local, &self.outgoing(PacketType::Handshake, pn), &ack_frame(largest), 0)?;
+        }
+        let pn = self.initial.take_pn();
+        // Padding alone when only Handshake packets arrived
+        let frames = self.initial.largest.map(ack_frame).unwrap_or_default();
+        let min_len = MIN_INITIAL_SIZE.saturating_sub(handshake_packet.len());
+        let mut datagram = seal(&self.initial.keys.local, &self.outgoing(PacketType::Initial, pn), &frames, min_len)?;
+        datagram.extend_from_slice(&handshake_packet);
+        self.socket.send(&datagram).await.context("failed to send")?;
+        Ok(())
+    }
+
+    /// Tell the server the probe is over, so it can drop the connection at once
+    async fn close(&mut self, code: u64) {
+        let pn = self.initial.take_pn();
+        let packet = seal(&self.initial.keys.local, &self.outgoing(PacketType::Initial, pn), &close_frame(code), MIN_INITIAL_SIZE);
+        if let Ok(packet) = packet {
+            let _ = self.socket.send(&packet).await;
+        }
+        if let Some(space) = &mut self.handshake {
+            let pn = space.take_pn();
+            let outgoing = Outgoing {
+                packet_type: PacketType::Handshake,
+                version: QUIC_V1,
+                dcid: &self.dcid,
+                scid: &self.scid,
+                token: &[],
+                pn,
+            };
+            if let Ok(packet) = seal(&space.keys.local, &outgoing, &close_frame(code), 0) {
+                let _ = self.socket.send(&packet).await;
+            }
+        }
+    }
+
+    /// Open the packets of one datagram and hand their handshake bytes to TLS
+    fn process(&mut self, datagram: &mut [u8]) -> Result<Progress> {
+        let mut pos = 0;
+        while pos < datagram.len() {
+            let Some(header) = parse_long_header(&datagram[pos..])? else {
+                break;
+            };
+            let packet = &mut datagram[pos..pos + header.len];
+            pos += header.len;
+            match header.packet_type {
+                PacketType::VersionNegotiation => {
+                    bail!("server does not speak QUIC v1 (Version Negotiation)");
+                }
+                // Keys for another version would not open it
+                _ if header.version != QUIC_V1 => {}
+                PacketType::Retry => {
+                    if self.answered || self.retried {
+                        continue;
+                    }
+                    self.retried = true;
+                    self.dcid = header.scid;
+                    self.token = header.token;
+                    self.initial = Space::new(initial_keys(&self.dcid)?);
+                    return Ok(Progress::Retry);
+                }
+                PacketType::ZeroRtt => {}
+                PacketType::Initial => {
+                    let (pn, payload) = open(&self.initial.keys.remote, packet, header.pn_offset, self.initial.largest)?;
+                    self.initial.largest = self.initial.largest.max(Some(pn));
+                    if !self.answered {
+                        self.answered = true;
+                        self.dcid = header.scid;
+                    }
+                    receive_frames(&mut self.initial, &payload)?;
+                    let ready = self.initial.crypto.take_ready();
+                    if let Some(done) = self.read_tls(&ready)? {
+                        return Ok(done);
+                    }
+                }
+                PacketType::Handshake => {
+                    // Keys arrive with the ServerHello; anything sooner cannot be opened
+                    let Some(space) = &mut self.handshake else {
+                        continue;
+                    };
+                    let (pn, payload) = open(&space.keys.remote, packet, header.pn_offset, space.largest)?;
+                    space.largest = space.largest.max(Some(pn));
+                    receive_frames(space, &payload)?;
+                    let ready = space.crypto.take_ready();
+                    if let Some(done) = self.read_tls(&ready)? {
+                        return Ok(done);
+                    }
+                }
+            }
+        }
+        Ok(Progress::Continue)
+    }
+
+    /// Feed handshake bytes to TLS; `Done` once its flight is complete
+    fn read_tls(&mut self, bytes: &[u8]) -> Result<Option<Progress>> {
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        self.tls.read_hs(bytes).map_err(|e| anyhow!("TLS handshake failed: {}", e))?;
+        loop {
+            // The client's Finished is never sent: the probe is over by then
+            let mut unsent = Vec::new();
+            match self.tls.write_hs(&mut unsent) {
+                Some(KeyChange::Handshake { keys }) => self.handshake = Some(Space::new(keys)),
+                Some(KeyChange::OneRtt { .. }) => return Ok(Some(Progress::Done)),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Next datagram from the server, or `None` at `deadline`
+    async fn recv(&self, buf: &mut [u8], deadline: Instant) -> Result<Option<usize>> {
+        match timeout_at(deadline.into(), self.socket.recv(buf)).await {
+            Ok(received) => received.map(Some).context("failed to receive"),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Act on the CRYPTO and CONNECTION_CLOSE frames of a payload
+fn receive_frames(space: &mut Space, payload: &[u8]) -> Result<()> {
+    for frame in parse_frames(payload)? {
+        match frame {
+            Frame::Crypto { offset, data } => space.crypto.insert(offset, data),
+            Frame::Close(closed) => return Err(closed.into()),
+        }
+    }
+    Ok(())
+}
+
+/// Run a handshake up to the server's Finished, within `timeout`
+///
+/// Fails with a `PeerClosed` when the server refuses the connection.
+pub async fn handshake(target: &Target, timeout: Duration) -> Result<Handshake> {
+    let started = Instant::now();
+    let deadline = started + timeout;
+    let mut client = Client::new(target).await?;
+    client.send_initial().await?;
+
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    let mut last_sent = Instant::now();
+    loop {
+        let wait = if client.answered { deadline } else { deadline.min(last_sent + RESEND_AFTER) };
+        let Some(len) = client.recv(&mut buf, wait).await? else {
+            if Instant::now() >= deadline {
+                bail!("no handshake within {} ms", timeout.as_millis());
+            }
+            client.send_initial().await?;
+            last_sent = Instant::now();
+            continue;
+        };
+        let progress = match client.process(&mut buf[..len]) {
+            Ok(progress) => progress,
+            Err(e) => {
+                if e.downcast_ref::<PeerClosed>().is_none() {
+                    // 0x100 + the alert TLS raised, or a protocol violation
+                    let code = client.tls.alert().map_or(0x0a, |alert| 0x100 + u64::from(alert.get_u8()));
+                    client.close(code).await;
+                }
+                return Err(e);
+            }
+        };
+        match progress {
+            Progress::Continue => client.send_acks().await?,
+            Progress::Retry => {
+                client.send_initial().await?;
+                last_sent = Instant::now();
+            }
+            Progress::Done => {
+                client.close(0).await;
+                return Ok(Handshake {
+                    certificates: client
+                        .tls
+                        .peer_certificates()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|cert| cert.clone().into_owned())
+                        .collect(),
+                    alpn: client.tls.alpn_protocol().map(<[u8]>::to_vec),
+                    retried: client.retried,
+                    elapsed: started.elapsed(),
+                });
+            }
+        }
+    }
+}
+
+/// What one unanswered Initial brought back
+#[derive(Debug, Default)]
+pub struct Echo {
+    pub sent: usize,
+    pub received: usize,
+    pub datagrams: usize,
+    /// Whether the server answered with a Retry, validating the address first
+    pub retry: bool,
+}
+
+/// Send one Initial and count what comes back for `listen`, answering nothing
+pub async fn unanswered_initial(target: &Target, listen: Duration) -> Result<Echo> {
+    let mut client = Client::new(target).await?;
+    let mut echo = Echo {
+        sent: client.send_initial().await?,
+        ..Echo::default()
+    };
+    let deadline = Instant::now() + listen;
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    while let Some(len) = client.recv(&mut buf, deadline).await? {
+        echo.received += len;
+        echo.datagrams += 1;
+        if let Ok(Some(header)) = parse_long_header(&buf[..len]) {
+            echo.retry |= header.packet_type == PacketType::Retry;
+            if header.packet_type == PacketType::Initial && !client.answered {
+                client.answered = true;
+                client.dcid = header.scid;
+            }
+        }
+    }
+    if client.answered {
+        client.close(0).await;
+    }
+    Ok(echo)
+}
+
+/// The server's reply to a reserved version: the versions it offers, `None` without a reply
+pub async fn version_negotiation(target: &Target, timeout: Duration) -> Result<Option<Vec<u32>>> {
+    let socket = target.socket().await?;
+    let (dcid, scid) = (random_cid(), random_cid());
+    let mut datagram = vec![0xc0];
+    datagram.extend_from_slice(&reserved_version().to_be_bytes());
+    for cid in [&dcid, &scid] {
+        datagram.push(cid.len() as u8);
+        datagram.extend_from_slice(cid);
+    }
+    datagram.resize(MIN_INITIAL_SIZE, 0);
+    socket.send(&datagram).await.context("failed to send")?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    loop {
+        let len = match timeout_at(deadline.into(), socket.recv(&mut buf)).await {
+            Ok(received) => received.context("failed to receive")?,
+            Err(_) => return Ok(None),
+        };
+        if let Ok(Some(header)) = parse_long_header(&buf[..len]) {
+            if header.packet_type == PacketType::VersionNegotiation && header.dcid == scid {
+                return Ok(Some(header.versions));
+            }
+        }
+    }
+}
+
+/// Replies to a burst of Initials
+#[derive(Debug, Default)]
+pub struct Flood {
+    pub attempts: usize,
+    /// Initials answered with an Initial of the server's
+    pub answered: usize,
+    /// Initials answered with a Retry
+    pub retried: usize,
+    /// Time until a new client got an answer after the burst, `None` when it got none
+    pub followup: Option<Duration>,
+}
+
+/// Open `concurrency` connections at once and leave them half open, then
+/// see whether one more still gets an answer
+pub async fn flood(target: &Target, concurrency: usize, timeout: Duration) -> Result<Flood> {
+    let mut tasks = JoinSet::new();
+    for _ in 0..concurrency {
+        let target = target.clone();
+        tasks.spawn(async move { first_reply(&target, timeout).await });
+    }
+    let mut clients = Vec::with_capacity(concurrency);
+    let mut result = Flood {
+        attempts: concurrency,
+        ..Flood::default()
+    };
+    while let Some(joined) = tasks.join_next().await {
+        let (client, reply) = joined.context("flood task failed")??;
+        match reply {
+            Some(PacketType::Initial) => result.answered += 1,
+            Some(PacketType::Retry) => result.retried += 1,
+            _ => {}
+        }
+        clients.push(client);
+    }
+
+    let started = Instant::now();
+    let (mut followup, reply) = first_reply(target, timeout).await?;
+    result.followup = reply.is_some().then(|| started.elapsed());
+    followup.close(0).await;
+    for client in &mut clients {
+        if client.answered {
+            client.close(0).await;
+        }
+    }
+    Ok(result)
+}
+
+/// Send one Initial and wait for the type of the first reply
+async fn first_reply(target: &Target, timeout: Duration) -> Result<(Client, Option<PacketType>)> {
+    let mut client = Client::new(target).await?;
+    client.send_initial().await?;
+    let deadline = Instant::now() + timeout;
+    let mut buf = vec![0u8; MAX_DATAGRAM];
+    while let Some(len) = client.recv(&mut buf, deadline).await? {
+        if let Ok(Some(header)) = parse_long_header(&buf[..len]) {
+            if header.packet_type == PacketType::Initial {
+                client.answered = true;
+                client.dcid = header.scid;
+            }
+            return Ok((client, Some(header.packet_type)));
+        }
+    }
+    Ok((client, None))
+}
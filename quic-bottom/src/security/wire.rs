@@ -0,0 +1,452 @@
+//! Just enough of QUIC to probe a server
+//!
+//! Long-header packets only: Initial and Handshake packets are protected and
+//! opened with the keys rustls derives, and their CRYPTO frames carry the TLS
+//! handshake. Nothing past the handshake is spoken, so 1-RTT packets the
+//! server sends are skipped.
+
+use anyhow::{anyhow, bail, Context, Result};
+use rustls::quic::{DirectionalKeys, Keys, Version};
+use rustls::Side;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// QUIC version 1 (RFC 9000)
+pub const QUIC_V1: u32 = 1;
+
+/// QUIC version 2 (RFC 9369)
+pub const QUIC_V2: u32 = 0x6b33_43cf;
+
+/// Smallest datagram a client may carry an Initial packet in
+pub const MIN_INITIAL_SIZE: usize = 1200;
+
+/// Connection IDs the probes pick for themselves
+pub const CID_LEN: usize = 8;
+
+/// Packet numbers are always sent in four bytes
+const PN_LEN: usize = 4;
+
+/// A version of the form 0x?a?a?a?a, which RFC 9000 reserves so that no
+/// server supports it
+pub fn reserved_version() -> u32 {
+    (rand::random::<u32>() & 0xf0f0_f0f0) | 0x0a0a_0a0a
+}
+
+/// A fresh connection ID
+pub fn random_cid() -> Vec<u8> {
+    (0..CID_LEN).map(|_| rand::random()).collect()
+}
+
+/// `version` as a name where there is one
+pub fn version_name(version: u32) -> String {
+    match version {
+        QUIC_V1 => "v1".to_string(),
+        QUIC_V2 => "v2".to_string(),
+        0xff00_0000..=0xff00_00ff => format!("draft-{}", version & 0xff),
+        _ => format!("{:#010x}", version),
+    }
+}
+
+/// Long-header packet types of QUIC v1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+    Initial,
+    ZeroRtt,
+    Handshake,
+    Retry,
+    /// Version 0: the server's list of versions
+    VersionNegotiation,
+}
+
+impl PacketType {
+    fn bits(self) -> u8 {
+        match self {
+            PacketType::Initial => 0,
+            PacketType::ZeroRtt => 1,
+            PacketType::Handshake => 2,
+            PacketType::Retry | PacketType::VersionNegotiation => 3,
+        }
+    }
+}
+
+/// Clear header fields of a long-header packet, before protection is removed
+#[derive(Debug, Clone)]
+pub struct LongHeader {
+    pub packet_type: PacketType,
+    pub version: u32,
+    pub dcid: Vec<u8>,
+    pub scid: Vec<u8>,
+    /// Initial: the address validation token; Retry: the token to send back
+    pub token: Vec<u8>,
+    /// Where the (protected) packet number starts
+    pub pn_offset: usize,
+    /// Length of the whole packet within its datagram
+    pub len: usize,
+    /// Version Negotiation: the versions the server offers
+    pub versions: Vec<u32>,
+}
+
+/// Reads the fields of a packet in order
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.remaining() < len {
+            bail!("packet truncated");
+        }
+        let bytes = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let first = self.u8()?;
+        let len = 1 << (first >> 6);
+        let mut value = u64::from(first & 0x3f);
+        for &byte in self.take(len - 1)? {
+            value = (value << 8) | u64::from(byte);
+        }
+        Ok(value)
+    }
+
+    /// A varint length followed by that many bytes
+    fn prefixed(&mut self) -> Result<&'a [u8]> {
+        let len = self.varint()?;
+        self.take(usize::try_from(len).map_err(|_| anyhow!("length out of range"))?)
+    }
+
+    /// A one-byte length followed by a connection ID
+    fn cid(&mut self) -> Result<Vec<u8>> {
+        let len = self.u8()? as usize;
+        if len > 20 {
+            bail!("connection ID of {} bytes", len);
+        }
+        Ok(self.take(len)?.to_vec())
+    }
+}
+
+/// Append `value` as a variable-length integer in as few bytes as it takes
+fn put_varint(out: &mut Vec<u8>, value: u64) {
+    match value {
+        0..=0x3f => out.push(value as u8),
+        0x40..=0x3fff => out.extend_from_slice(&(0x4000 | value as u16).to_be_bytes()),
+        0x4000..=0x3fff_ffff => out.extend_from_slice(&(0x8000_0000 | value as u32).to_be_bytes()),
+        _ => out.extend_from_slice(&(0xc000_0000_0000_0000 | value).to_be_bytes()),
+    }
+}
+
+/// Parse the header of the long-header packet `packet` starts with
+///
+/// Returns `None` for a short-header packet, which runs to the end of the
+/// datagram and which the probes never need to read.
+pub fn parse_long_header(packet: &[u8]) -> Result<Option<LongHeader>> {
+    let mut reader = Reader::new(packet);
+    let first = reader.u8()?;
+    if first & 0x80 == 0 {
+        return Ok(None);
+    }
+    let version = reader.u32()?;
+    let dcid = reader.cid()?;
+    let scid = reader.cid()?;
+    let mut header = LongHeader {
+        packet_type: PacketType::VersionNegotiation,
+        version,
+        dcid,
+        scid,
+        token: Vec::new(),
+        pn_offset: 0,
+        len: packet.len(),
+        versions: Vec::new(),
+    };
+
+    if version == 0 {
+        while reader.remaining() >= 4 {
+            header.versions.push(reader.u32()?);
+        }
+        return Ok(Some(header));
+    }
+    header.packet_type = match (first >> 4) & 0x03 {
+        0 => PacketType::Initial,
+        1 => PacketType::ZeroRtt,
+        2 => PacketType::Handshake,
+        _ => PacketType::Retry,
+    };
+    match header.packet_type {
+        PacketType::Retry => {
+            // The token runs up to the 16-byte integrity tag
+            let token_len = reader.remaining().checked_sub(16).context("Retry packet truncated")?;
+            header.token = reader.take(token_len)?.to_vec();
+        }
+        packet_type => {
+            if packet_type == PacketType::Initial {
+                header.token = reader.prefixed()?.to_vec();
+            }
+            let len = reader.varint()?;
+            header.pn_offset = reader.pos;
+            header.len = usize::try_from(len)
+                .ok()
+                .and_then(|len| reader.pos.checked_add(len))
+                .filter(|&end| end <= packet.len())
+                .context("packet length exceeds the datagram")?;
+        }
+    }
+    Ok(Some(header))
+}
+
+/// Full packet number from its truncated form, as in RFC 9000 appendix A.3
+fn decode_packet_number(largest: Option<u64>, truncated: u64, len: usize) -> u64 {
+    let expected = largest.map_or(0, |largest| largest + 1);
+    let window = 1u64 << (len * 8);
+    let half = window / 2;
+    let candidate = (expected & !(window - 1)) | truncated;
+    if candidate + half <= expected && candidate < (1 << 62) - window {
+        candidate + window
+    } else if candidate > expected + half && candidate >= window {
+        candidate - window
+    } else {
+        candidate
+    }
+}
+
+/// Remove the protection of `packet` with `keys`; its packet number and payload
+pub fn open(keys: &DirectionalKeys, packet: &mut [u8], pn_offset: usize, largest: Option<u64>) -> Result<(u64, Vec<u8>)> {
+    let sample_len = keys.header.sample_len();
+    let sample_start = pn_offset + PN_LEN;
+    if packet.len() < sample_start + sample_len {
+        bail!("packet too short to remove header protection");
+    }
+    let sample = packet[sample_start..sample_start + sample_len].to_vec();
+    let (header, rest) = packet.split_at_mut(pn_offset);
+    keys.header
+        .decrypt_in_place(&sample, &mut header[0], &mut rest[..PN_LEN])
+        .map_err(|e| anyhow!("header protection: {}", e))?;
+
+    let pn_len = (header[0] & 0x03) as usize + 1;
+    let truncated = rest[..pn_len].iter().fold(0u64, |pn, &byte| (pn << 8) | u64::from(byte));
+    let pn = decode_packet_number(largest, truncated, pn_len);
+    let (header, payload) = packet.split_at_mut(pn_offset + pn_len);
+    let plain = keys
+        .packet
+        .decrypt_in_place(pn, header, payload)
+        .map_err(|e| anyhow!("packet protection: {}", e))?;
+    Ok((pn, plain.to_vec()))
+}
+
+/// Fields of a packet to send, besides its frames
+pub struct Outgoing<'a> {
+    pub packet_type: PacketType,
+    pub version: u32,
+    pub dcid: &'a [u8],
+    pub scid: &'a [u8],
+    /// Sent in Initial packets only
+    pub token: &'a [u8],
+    pub pn: u64,
+}
+
+/// Build and protect a packet holding `frames`, padded to at least `min_len` bytes
+pub fn seal(keys: &DirectionalKeys, packet: &Outgoing<'_>, frames: &[u8], min_len: usize) -> Result<Vec<u8>> {
+    let mut header = vec![0xc0 | (packet.packet_type.bits() << 4) | (PN_LEN as u8 - 1)];
+    header.extend_from_slice(&packet.version.to_be_bytes());
+    header.push(packet.dcid.len() as u8);
+    header.extend_from_slice(packet.dcid);
+    header.push(packet.scid.len() as u8);
+    header.extend_from_slice(packet.scid);
+    if packet.packet_type == PacketType::Initial {
+        put_varint(&mut header, packet.token.len() as u64);
+        header.extend_from_slice(packet.token);
+    }
+
+    let tag_len = keys.packet.tag_len();
+    let mut payload = frames.to_vec();
+    // Two bytes of length field, then the packet number and the payload
+    let unpadded = header.len() + 2 + PN_LEN + payload.len() + tag_len;
+    payload.resize(payload.len() + min_len.saturating_sub(unpadded), 0);
+    let len = PN_LEN + payload.len() + tag_len;
+    if len > 0x3fff {
+        bail!("packet of {} bytes is too large", len);
+    }
+    header.extend_from_slice(&(0x4000 | len as u16).to_be_bytes());
+    let pn_offset = header.len();
+    header.extend_from_slice(&(packet.pn as u32).to_be_bytes());
+
+    let tag = keys
+        .packet
+        .encrypt_in_place(packet.pn, &header, &mut payload)
+        .map_err(|e| anyhow!("packet protection: {}", e))?;
+    payload.extend_from_slice(tag.as_ref());
+    // With a four-byte packet number the sample starts right after it
+    let sample = &payload[..keys.header.sample_len()];
+    let (first, rest) = header.split_first_mut().context("empty header")?;
+    keys.header
+        .encrypt_in_place(sample, first, &mut rest[pn_offset - 1..])
+        .map_err(|e| anyhow!("header protection: {}", e))?;
+
+    header.extend_from_slice(&payload);
+    Ok(header)
+}
+
+/// Keys of the Initial packets of a connection whose first Destination Connection ID is `dcid`
+pub fn initial_keys(dcid: &[u8]) -> Result<Keys> {
+    let suite = rustls::crypto::ring::cipher_suite::TLS13_AES_128_GCM_SHA256
+        .tls13()
+        .context("TLS 1.3 suite missing")?;
+    let quic = suite.quic.context("cipher suite without QUIC support")?;
+    Ok(Keys::initial(Version::V1, suite, quic, dcid, Side::Client))
+}
+
+/// A frame the probes act on
+#[derive(Debug)]
+pub enum Frame {
+    Crypto { offset: u64, data: Vec<u8> },
+    Close(PeerClosed),
+}
+
+/// The server closed the connection
+#[derive(Debug, Clone)]
+pub struct PeerClosed {
+    pub code: u64,
+    pub reason: String,
+}
+
+impl PeerClosed {
+    /// TLS alert the close carries, for errors 0x100-0x1ff
+    pub fn tls_alert(&self) -> Option<u8> {
+        (0x100..0x200).contains(&self.code).then(|| (self.code - 0x100) as u8)
+    }
+}
+
+impl fmt::Display for PeerClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tls_alert() {
+            Some(alert) => write!(f, "server closed the connection with TLS alert {}", alert)?,
+            None => write!(f, "server closed the connection with error {:#x}", self.code)?,
+        }
+        if !self.reason.is_empty() {
+            write!(f, " ({})", self.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PeerClosed {}
+
+/// The frames of a decrypted Initial or Handshake payload that matter here
+///
+/// Padding, PING and ACK frames are read past; other frames are not allowed
+/// in these packets.
+pub fn parse_frames(payload: &[u8]) -> Result<Vec<Frame>> {
+    let mut reader = Reader::new(payload);
+    let mut frames = Vec::new();
+    while reader.remaining() > 0 {
+        match reader.varint()? {
+            0x00 | 0x01 => {}
+            frame_type @ (0x02 | 0x03) => {
+                // Largest acknowledged, delay, range count, first range
+                let _ = (reader.varint()?, reader.varint()?);
+                let ranges = reader.varint()?;
+                reader.varint()?;
+                for _ in 0..ranges {
+                    reader.varint()?;
+                    reader.varint()?;
+                }
+                if frame_type == 0x03 {
+                    for _ in 0..3 {
+                        reader.varint()?;
+                    }
+                }
+            }
+            0x06 => {
+                let offset = reader.varint()?;
+                let data = reader.prefixed()?.to_vec();
+                frames.push(Frame::Crypto { offset, data });
+            }
+            frame_type @ (0x1c | 0x1d) => {
+                let code = reader.varint()?;
+                if frame_type == 0x1c {
+                    reader.varint()?;
+                }
+                let reason = String::from_utf8_lossy(reader.prefixed()?).into_owned();
+                frames.push(Frame::Close(PeerClosed { code, reason }));
+            }
+            frame_type => bail!("unexpected frame type {:#x} during the handshake", frame_type),
+        }
+    }
+    Ok(frames)
+}
+
+/// CRYPTO frame carrying `data` from `offset`
+pub fn crypto_frame(offset: u64, data: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x06];
+    put_varint(&mut frame, offset);
+    put_varint(&mut frame, data.len() as u64);
+    frame.extend_from_slice(data);
+    frame
+}
+
+/// ACK frame for packet `largest` alone
+pub fn ack_frame(largest: u64) -> Vec<u8> {
+    let mut frame = vec![0x02];
+    put_varint(&mut frame, largest);
+    frame.extend_from_slice(&[0, 0, 0]);
+    frame
+}
+
+/// CONNECTION_CLOSE frame with transport error `code`
+pub fn close_frame(code: u64) -> Vec<u8> {
+    let mut frame = vec![0x1c];
+    put_varint(&mut frame, code);
+    frame.extend_from_slice(&[0, 0]);
+    frame
+}
+
+/// Handshake bytes of one packet number space, put back in order
+#[derive(Debug, Default)]
+pub struct CryptoStream {
+    delivered: u64,
+    pending: BTreeMap<u64, Vec<u8>>,
+}
+
+impl CryptoStream {
+    pub fn insert(&mut self, offset: u64, data: Vec<u8>) {
+        if offset + data.len() as u64 > self.delivered {
+            self.pending.insert(offset, data);
+        }
+    }
+
+    /// The bytes that now follow what was taken before without a gap
+    pub fn take_ready(&mut self) -> Vec<u8> {
+        let mut ready = Vec::new();
+        while let Some(entry) = self.pending.first_entry() {
+            if *entry.key() > self.delivered {
+                break;
+            }
+            let (offset, data) = entry.remove_entry();
+            let skip = (self.delivered - offset) as usize;
+            if skip < data.len() {
+                ready.extend_from_slice(&data[skip..]);
+                self.delivered += (data.len() - skip) as u64;
+            }
+        }
+        ready
+    }
+}
@@ -0,0 +1,67 @@
+//! SIGINT/SIGTERM handling shared by the TUI and headless modes
+//!
+//! systemd and Kubernetes stop a process with SIGTERM, and `kill -INT`
+//! reaches a TUI even though raw mode turns the Ctrl+C key into input.
+//! Either signal ends the run loop instead of the process, so the terminal
+//! is restored, exporters are flushed and the report is written as on 'q'.
+
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
+
+use crate::exporters::influx::InfluxQueue;
+
+/// How long shutdown waits for queued samples to reach InfluxDB
+pub const EXPORT_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Name of the first shutdown signal received
+static RECEIVED: OnceLock<&'static str> = OnceLock::new();
+
+/// Wait for SIGINT or, on Unix, SIGTERM and return the signal's name
+pub async fn signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                log::warn!("cannot listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+/// Start listening in the background, once; `requested` reports the signal
+pub fn watch() {
+    static WATCH: Once = Once::new();
+    WATCH.call_once(|| {
+        tokio::spawn(async {
+            let name = signal().await;
+            log::info!("shutting down ({})", name);
+            let _ = RECEIVED.set(name);
+        });
+    });
+}
+
+/// The shutdown signal received since `watch`, if any
+pub fn requested() -> Option<&'static str> {
+    RECEIVED.get().copied()
+}
+
+/// Give the InfluxDB writer up to `EXPORT_FLUSH_TIMEOUT` to send what is queued
+pub async fn flush_exporter(queue: &InfluxQueue) {
+    let unwritten = queue.flush(EXPORT_FLUSH_TIMEOUT).await;
+    if unwritten > 0 {
+        eprintln!("InfluxDB export: {} samples not written", unwritten);
+    }
+}
@@ -3,34 +3,86 @@
 //! Based on bottom's advanced capabilities but simplified for easier implementation
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect, Size},
+    style::{Modifier, Style},
     symbols::Marker,
-    text::Span,
+    text::{Line, Span},
     widgets::{Block, Borders, Dataset, GraphType, Paragraph},
     Frame,
 };
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::axis_scale::AxisScale;
+use crate::downsample::{chart_budget, lttb};
+use crate::improved_layout::{MinSize, Presentation};
+use crate::metrics::{
+    calculate_latency_percentiles, format_clock, format_span, format_value, StreamingStats,
+};
+use crate::theme::Theme;
+use crate::time_view::{TimeNav, TimeView, DEFAULT_RETENTION};
+use crate::units::Unit;
+
+/// Time window shown by default
+pub const DEFAULT_TIME_WINDOW: Duration = Duration::from_secs(60);
+
+/// Points kept by the QUIC graphs unless configured otherwise
+pub const DEFAULT_MAX_POINTS: usize = 1000;
+
+/// Rows of the bordered title
+const TITLE_ROWS: u16 = 3;
+
+/// Chart rows with the analytics shown, and without them
+const FULL_GRAPH_ROWS: u16 = 8;
+const COMPACT_GRAPH_ROWS: u16 = 5;
+
+/// Narrowest graph whose axis labels still fit
+const MIN_GRAPH_WIDTH: u16 = 30;
 
 /// Simplified professional time graph for QUIC metrics
 pub struct SimpleProfessionalGraph {
-    /// Historical data points
-    pub data_points: VecDeque<f64>,
+    /// Historical data points, oldest first
+    pub data_points: VecDeque<(DateTime<Utc>, f64)>,
     
     /// Maximum number of data points to keep
     pub max_points: usize,
+
+    /// Points older than this, relative to the newest point, are dropped
+    pub retention: Duration,
+
+    /// Visible slice of the retained points
+    pub view: TimeView,
     
     /// Y-axis bounds
     pub y_bounds: (f64, f64),
+
+    /// Linear or log value axis
+    pub scale: AxisScale,
     
-    /// Graph style
-    pub style: Style,
+    /// Draw the line in the theme's secondary color rather than its primary one
+    pub secondary: bool,
     
     /// Title
     pub title: String,
     
     /// Whether graph is selected
     pub is_selected: bool,
+
+    /// Running statistics over `data_points`
+    stats: StreamingStats,
+
+    /// Saved run drawn behind the live line, by offset from its start
+    baseline: Option<Vec<(Duration, f64)>>,
+
+    /// First point since the last clear; baselines are aligned to it
+    run_start: Option<DateTime<Utc>>,
+
+    /// Whether a rise over the baseline is an improvement, for the delta color
+    pub higher_is_better: bool,
+
+    /// Values are bits per second, drawn in the unit that suits the largest
+    pub rate: bool,
 }
 
 impl SimpleProfessionalGraph {
@@ -38,184 +90,479 @@ impl SimpleProfessionalGraph {
         Self {
             data_points: VecDeque::with_capacity(max_points),
             max_points,
+            retention: DEFAULT_RETENTION,
+            view: TimeView::new(DEFAULT_TIME_WINDOW, DEFAULT_RETENTION),
             y_bounds: (0.0, 100.0),
-            style: Style::default().fg(Color::Green),
+            scale: AxisScale::Linear,
+            secondary: false,
             title,
             is_selected: false,
+            stats: StreamingStats::new(),
+            baseline: None,
+            run_start: None,
+            higher_is_better: false,
+            rate: false,
+        }
+    }
+
+    /// Compare against a saved run, or stop comparing with `None`
+    pub fn set_baseline(&mut self, baseline: Option<Vec<(Duration, f64)>>) {
+        self.baseline = baseline.filter(|points| !points.is_empty());
+    }
+
+    pub fn has_baseline(&self) -> bool {
+        self.baseline.is_some()
+    }
+
+    /// Wall-clock position of a baseline offset in the live run
+    fn baseline_time(&self, offset: Duration) -> Option<DateTime<Utc>> {
+        self.run_start?.checked_add_signed(chrono::Duration::from_std(offset).ok()?)
+    }
+
+    /// Baseline values over the same elapsed time as the retained live points
+    fn baseline_overlap(&self) -> Vec<f64> {
+        let (Some(baseline), Some(run_start)) = (&self.baseline, self.run_start) else {
+            return Vec::new();
+        };
+        let (Some((oldest, _)), Some((newest, _))) = (self.data_points.front(), self.data_points.back()) else {
+            return Vec::new();
+        };
+        let elapsed = |t: DateTime<Utc>| (t - run_start).to_std().unwrap_or_default();
+        let (from, to) = (elapsed(*oldest), elapsed(*newest));
+        baseline
+            .iter()
+            .filter(|(offset, _)| (from..=to).contains(offset))
+            .map(|(_, value)| *value)
+            .collect()
+    }
+
+    /// Visible window width
+    pub fn with_time_window(mut self, time_window: Duration) -> Self {
+        self.view = TimeView::new(time_window, self.retention);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: AxisScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Switch between linear and log value axes
+    pub fn toggle_scale(&mut self) {
+        self.scale = self.scale.toggled();
+    }
+
+    /// Zoom or pan the visible window
+    pub fn navigate(&mut self, nav: TimeNav) {
+        if let (Some((oldest, _)), Some((newest, _))) = (self.data_points.front(), self.data_points.back()) {
+            self.view.navigate(nav, *oldest, *newest);
         }
     }
 
-    /// Add new data point
+    /// Drop all points, keeping the configuration and zoom level
+    pub fn clear(&mut self) {
+        self.data_points.clear();
+        self.stats = StreamingStats::new();
+        self.view.go_live();
+        self.y_bounds = (0.0, 100.0);
+        self.run_start = None;
+    }
+
+    /// Add new data point, timestamped now
     pub fn add_data_point(&mut self, value: f64) {
-        self.data_points.push_back(value);
+        self.add_data_point_at(Utc::now(), value);
+    }
+
+    /// Add a data point observed at `timestamp`
+    ///
+    /// Late points are inserted in time order, so backfilled samples land
+    /// where they belong on the time axis.
+    pub fn add_data_point_at(&mut self, timestamp: DateTime<Utc>, value: f64) {
+        let index = self.data_points.partition_point(|(t, _)| *t <= timestamp);
+        self.data_points.insert(index, (timestamp, value));
+        self.stats.push(value);
+        if self.run_start.is_none_or(|start| timestamp < start) {
+            self.run_start = Some(timestamp);
+        }
         
-        // Keep only recent data
-        while self.data_points.len() > self.max_points {
+        // Keep only data inside the retention period, and at most max_points
+        let cutoff = self
+            .window_end()
+            .and_then(|end| end.checked_sub_signed(chrono::Duration::from_std(self.retention).ok()?));
+        while let Some(&(oldest, evicted)) = self.data_points.front() {
+            let expired = cutoff.is_some_and(|cutoff| oldest < cutoff);
+            if !expired && self.data_points.len() <= self.max_points {
+                break;
+            }
             self.data_points.pop_front();
+            self.stats.remove(evicted);
         }
         
         // Update y bounds based on current data
         self.update_y_bounds();
     }
 
+    /// Timestamp of the newest point, the right edge of the x-axis
+    fn window_end(&self) -> Option<DateTime<Utc>> {
+        self.data_points.back().map(|(t, _)| *t)
+    }
+
+    /// Time between the oldest and newest points
+    pub fn span(&self) -> Duration {
+        match (self.data_points.front(), self.data_points.back()) {
+            (Some((first, _)), Some((last, _))) => (*last - *first).to_std().unwrap_or_default(),
+            _ => Duration::ZERO,
+        }
+    }
+
     /// Update Y-axis bounds based on current data
     fn update_y_bounds(&mut self) {
-        if self.data_points.is_empty() {
+        let (Some(min_val), Some(max_val)) = (self.stats.min(), self.stats.max()) else {
             return;
+        };
+        self.y_bounds = padded(min_val, max_val);
+    }
+
+    /// Unit a rate graph is drawn in, `None` for other graphs
+    fn rate_unit(&self) -> Option<Unit> {
+        self.rate.then(|| Unit::auto(self.stats.max().unwrap_or(0.0)))
+    }
+
+    /// `value` as drawn: converted to the rate unit for a rate graph
+    fn shown(&self, value: f64) -> f64 {
+        self.rate_unit().map_or(value, |unit| unit.from_bps(value))
+    }
+
+    /// `value` as drawn, with the rate unit when there is one
+    fn shown_text(&self, value: f64) -> String {
+        match self.rate_unit() {
+            Some(unit) => format!("{} {}", format_value(unit.from_bps(value), 2), unit),
+            None => format_value(value, 2),
+        }
+    }
+
+    /// The title, followed by the rate unit for a rate graph, e.g. `QUIC Throughput (Mbps)`
+    fn full_title(&self) -> String {
+        match self.rate_unit() {
+            Some(unit) => format!("{} ({})", self.title, unit),
+            None => self.title.clone(),
         }
-        
-        let min_val = self.data_points.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max_val = self.data_points.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        
-        // Add some padding
-        let padding = (max_val - min_val) * 0.1;
-        self.y_bounds = (min_val - padding, max_val + padding);
     }
 
     /// Get analytics for the current data
     pub fn get_analytics(&self) -> SimpleAnalytics {
-        if self.data_points.is_empty() {
+        if self.stats.count() == 0 {
             return SimpleAnalytics::default();
         }
-        
-        let values: Vec<f64> = self.data_points.iter().cloned().collect();
-        let current = *values.last().unwrap();
-        let average = values.iter().sum::<f64>() / values.len() as f64;
-        let min = values.iter().fold(f64::INFINITY, |a, &b| a.min(b));
-        let max = values.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
-        
-        // Calculate percentiles
-        let mut sorted_values = values.clone();
-        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let p50 = percentile(&sorted_values, 0.5);
-        let p95 = percentile(&sorted_values, 0.95);
-        let p99 = percentile(&sorted_values, 0.99);
+
+        let current = self
+            .data_points
+            .iter()
+            .rev()
+            .map(|(_, v)| *v)
+            .find(|v| v.is_finite())
+            .unwrap_or(0.0);
+        let (p50, p95, p99) = self.stats.percentiles();
         
         SimpleAnalytics {
             current,
-            average,
-            min,
-            max,
+            average: self.stats.mean(),
+            min: self.stats.min().unwrap_or(0.0),
+            max: self.stats.max().unwrap_or(0.0),
             p50,
             p95,
             p99,
-            data_points: values.len(),
+            data_points: self.stats.count(),
+            span: self.span(),
         }
     }
 
-    /// Render the professional graph
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    /// Render the professional graph, with as much detail as `area` has room for
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_as(f, area, Presentation::fitting(self, area), theme);
+    }
+
+    /// Render the professional graph at `presentation`
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        if presentation == Presentation::Minimal {
+            self.render_current(f, area, theme);
+            return;
+        }
         if self.data_points.is_empty() {
-            self.render_empty_state(f, area);
+            self.render_empty_state(f, area, theme);
             return;
         }
 
+        let analytics_rows = match presentation {
+            Presentation::Full => self.analytics_rows(),
+            _ => 0,
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(8),    // Graph
-                Constraint::Length(4), // Analytics
+                Constraint::Length(TITLE_ROWS),     // Title
+                Constraint::Min(0),                 // Graph
+                Constraint::Length(analytics_rows), // Analytics
             ])
             .split(area);
 
         // Title
-        self.render_title(f, chunks[0]);
+        self.render_title(f, chunks[0], theme);
         
         // Graph
-        self.render_graph(f, chunks[1]);
+        self.render_graph(f, chunks[1], theme);
         
         // Analytics
-        self.render_analytics(f, chunks[2]);
+        if analytics_rows > 0 {
+            self.render_analytics(f, chunks[2], theme);
+        }
     }
 
-    fn render_title(&self, f: &mut Frame, area: Rect) {
+    /// The analytics line, and the baseline comparison under it when there is one
+    fn analytics_rows(&self) -> u16 {
+        if self.baseline.is_some() {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// `QUIC Latency (ms): 12.30 | P95: 18.40` on one line
+    fn render_current(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let text = if self.data_points.is_empty() {
+            format!("{}: no data yet", self.title)
+        } else {
+            let analytics = self.get_analytics();
+            format!(
+                "{}: {} | P95: {}",
+                self.title,
+                self.shown_text(analytics.current),
+                self.shown_text(analytics.p95)
+            )
+        };
+        let style = if self.is_selected {
+            theme.title(theme.accent)
+        } else {
+            Style::default().fg(theme.info)
+        };
+        f.render_widget(Paragraph::new(text).style(style), area);
+    }
+
+    fn render_title(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let title_style = if self.is_selected {
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            theme.title(theme.accent)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.text)
         };
+
+        let mut spans = vec![
+            Span::styled(self.full_title(), title_style),
+            Span::styled(
+                format!("  [{} | {}]", format_span(self.view.width), self.scale.name()),
+                Style::default().fg(theme.muted),
+            ),
+        ];
+        if let Some(newest) = self.window_end().filter(|_| !self.view.is_live()) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!(" HISTORY (-{}) ", format_span(self.view.lag_behind(newest))),
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
         
-        let title = Paragraph::new(self.title.clone())
+        let title = Paragraph::new(Line::from(spans))
             .style(title_style)
             .block(Block::default()
                 .borders(Borders::ALL)
                 .border_style(if self.is_selected { 
-                    Style::default().fg(Color::Yellow) 
+                    Style::default().fg(theme.accent) 
                 } else { 
-                    Style::default().fg(Color::Gray) 
+                    Style::default().fg(theme.muted) 
                 }));
         f.render_widget(title, area);
     }
 
-    fn render_graph(&self, f: &mut Frame, area: Rect) {
-        // Convert data to chart format
+    fn render_graph(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let style = Style::default().fg(if self.secondary { theme.secondary } else { theme.primary });
+        let (Some((oldest, _)), Some((newest, _))) = (self.data_points.front(), self.data_points.back()) else {
+            return;
+        };
+        let (start, end) = self.view.bounds(*oldest, *newest);
+
+        // Plot against unix milliseconds so gaps and bursts keep their real spacing;
+        // one point either side of the window keeps the line running to the edges
+        let first = self.data_points.partition_point(|(t, _)| *t < start).saturating_sub(1);
+        let last = self.data_points.partition_point(|(t, _)| *t <= end);
         let data: Vec<(f64, f64)> = self.data_points
-            .iter()
-            .enumerate()
-            .map(|(i, &value)| (i as f64, value))
+            .range(first..(last + 1).min(self.data_points.len()))
+            .filter(|(_, value)| value.is_finite())
+            .map(|(t, value)| (t.timestamp_millis() as f64, self.scale.transform(self.shown(*value))))
             .collect();
+        // A few points per column are all the chart can show; the stats below still cover every sample
+        let data = lttb(data, chart_budget(area.width));
 
         if data.is_empty() {
             return;
         }
 
-        let dataset = Dataset::default()
-            .data(&data)
-            .style(self.style)
-            .graph_type(GraphType::Line)
-            .marker(Marker::Braille);
+        // Baseline points in the window, placed by their offset from the run start
+        let baseline: Vec<(f64, f64)> = self
+            .baseline
+            .iter()
+            .flatten()
+            .filter(|(_, value)| value.is_finite())
+            .filter_map(|(offset, value)| Some((self.baseline_time(*offset)?, *value)))
+            .filter(|(t, _)| (start..=end).contains(t))
+            .map(|(t, value)| (t.timestamp_millis() as f64, self.shown(value)))
+            .collect();
+
+        let plotted_baseline: Vec<(f64, f64)> = baseline
+            .iter()
+            .map(|&(t, value)| (t, self.scale.transform(value)))
+            .collect();
+        let plotted_baseline = lttb(plotted_baseline, chart_budget(area.width));
+
+        // The baseline goes first so the live line is drawn over it
+        let mut datasets = Vec::with_capacity(2);
+        if !plotted_baseline.is_empty() {
+            datasets.push(
+                Dataset::default()
+                    .data(&plotted_baseline)
+                    .style(Style::default().fg(theme.muted))
+                    .graph_type(GraphType::Line)
+                    .marker(Marker::Braille),
+            );
+        }
+        datasets.push(
+            Dataset::default()
+                .data(&data)
+                .style(style)
+                .graph_type(GraphType::Line)
+                .marker(Marker::Braille),
+        );
+
+        let middle = start + (end - start) / 2;
+        let bounds = [start.timestamp_millis() as f64, end.timestamp_millis() as f64];
+
+        let mut data_range = (
+            self.shown(self.stats.min().unwrap_or(self.y_bounds.0)),
+            self.shown(self.stats.max().unwrap_or(self.y_bounds.1)),
+        );
+        let mut padded_range = (self.shown(self.y_bounds.0), self.shown(self.y_bounds.1));
+        if !baseline.is_empty() {
+            for &(_, value) in &baseline {
+                data_range = (data_range.0.min(value), data_range.1.max(value));
+            }
+            padded_range = padded(data_range.0, data_range.1);
+        }
+        let (y_bounds, y_labels) = self.scale.axis(padded_range, data_range);
 
         // Create chart with professional styling
-        let chart = ratatui::widgets::Chart::new(vec![dataset])
+        let chart = ratatui::widgets::Chart::new(datasets)
             .block(Block::default()
                 .borders(Borders::ALL)
                 .title("Time Series")
-                .title_style(Style::default().fg(Color::Cyan)))
+                .title_style(Style::default().fg(theme.info)))
             .x_axis(ratatui::widgets::Axis::default()
-                .bounds([0.0, self.data_points.len() as f64])
-                .labels(vec![
-                    Span::styled("0", self.style),
-                    Span::styled(format!("{}", self.data_points.len()), self.style),
-                ]))
+                .bounds(bounds)
+                .labels([start, middle, end].map(|t| Span::styled(format_clock(t), style))))
             .y_axis(ratatui::widgets::Axis::default()
-                .bounds([self.y_bounds.0, self.y_bounds.1])
-                .labels(vec![
-                    Span::styled(format!("{:.1}", self.y_bounds.0), self.style),
-                    Span::styled(format!("{:.1}", self.y_bounds.1), self.style),
-                ]));
+                .bounds(y_bounds)
+                .labels(y_labels.into_iter().map(|label| Span::styled(label, style))));
 
         f.render_widget(chart, area);
     }
 
-    fn render_analytics(&self, f: &mut Frame, area: Rect) {
+    fn render_analytics(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let analytics = self.get_analytics();
         
         let analytics_text = format!(
-            "Current: {:.2} | Avg: {:.2} | Min: {:.2} | Max: {:.2} | P50: {:.2} | P95: {:.2} | P99: {:.2}",
-            analytics.current, analytics.average, analytics.min, analytics.max,
-            analytics.p50, analytics.p95, analytics.p99
+            "Current: {} | Avg: {} | Min: {} | Max: {} | P50: {} | P95: {} | P99: {} | Span: {}",
+            self.shown_text(analytics.current), self.shown_text(analytics.average),
+            self.shown_text(analytics.min), self.shown_text(analytics.max),
+            self.shown_text(analytics.p50), self.shown_text(analytics.p95),
+            self.shown_text(analytics.p99),
+            format_span(analytics.span)
         );
         
-        let analytics_paragraph = Paragraph::new(analytics_text)
-            .style(Style::default().fg(Color::Cyan))
+        let mut lines = vec![Line::from(analytics_text)];
+        if self.baseline.is_some() {
+            lines.push(self.baseline_delta_line(analytics.p95, theme));
+        }
+        let analytics_paragraph = Paragraph::new(lines)
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         
         f.render_widget(analytics_paragraph, area);
     }
 
-    fn render_empty_state(&self, f: &mut Frame, area: Rect) {
+    /// Live P95 against the baseline's P95 over the same elapsed time
+    fn baseline_delta_line(&self, p95: f64, theme: &Theme) -> Line<'static> {
+        let overlap = self.baseline_overlap();
+        if overlap.is_empty() {
+            return Line::styled("Baseline: no samples at this point of the run", Style::default().fg(theme.muted));
+        }
+        let (_, baseline_p95, _) = calculate_latency_percentiles(&overlap);
+        let mut spans = vec![Span::styled(
+            format!("Baseline P95: {} | P95 now: {} | ", self.shown_text(baseline_p95), self.shown_text(p95)),
+            Style::default().fg(theme.muted),
+        )];
+        if baseline_p95.abs() > f64::EPSILON {
+            let change = (p95 - baseline_p95) / baseline_p95 * 100.0;
+            let better = (change > 0.0) == self.higher_is_better;
+            let color = if change.abs() < 1.0 {
+                theme.muted
+            } else if better {
+                theme.success
+            } else {
+                theme.error
+            };
+            spans.push(Span::styled(
+                format!("{:+.1}% vs baseline", change),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    fn render_empty_state(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let empty_text = "No data available yet...";
         let empty_paragraph = Paragraph::new(empty_text)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(theme.muted))
             .block(Block::default()
                 .borders(Borders::ALL)
-                .title(self.title.as_str()));
+                .title(self.full_title()));
         
         f.render_widget(empty_paragraph, area);
     }
 }
 
+impl MinSize for SimpleProfessionalGraph {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        match presentation {
+            Presentation::Full => Size::new(MIN_GRAPH_WIDTH, TITLE_ROWS + FULL_GRAPH_ROWS + self.analytics_rows()),
+            Presentation::Compact => Size::new(MIN_GRAPH_WIDTH, TITLE_ROWS + COMPACT_GRAPH_ROWS),
+            Presentation::Minimal => Size::new(0, 1),
+        }
+    }
+}
+
+/// `(min, max)` with 10% padding; flat series still get a non-empty range
+fn padded(min: f64, max: f64) -> (f64, f64) {
+    let padding = if max > min {
+        (max - min) * 0.1
+    } else {
+        (max.abs() * 0.1).max(1.0)
+    };
+    // Values near f64::MAX would pad out to infinity, which the chart cannot scale
+    let bounds = (min - padding, max + padding);
+    if bounds.0.is_finite() && bounds.1.is_finite() {
+        bounds
+    } else {
+        (min, max)
+    }
+}
+
 /// Analytics data for the graph
 #[derive(Default, Debug)]
 pub struct SimpleAnalytics {
@@ -227,16 +574,8 @@ pub struct SimpleAnalytics {
     pub p95: f64,
     pub p99: f64,
     pub data_points: usize,
-}
-
-/// Calculate percentile
-fn percentile(sorted_data: &[f64], p: f64) -> f64 {
-    if sorted_data.is_empty() {
-        return 0.0;
-    }
-    
-    let index = (p * (sorted_data.len() - 1) as f64) as usize;
-    sorted_data[index]
+    /// Time covered by the data points
+    pub span: Duration,
 }
 
 /// Professional QUIC Latency Graph
@@ -246,20 +585,70 @@ pub struct SimpleQuicLatencyGraph {
 
 impl SimpleQuicLatencyGraph {
     pub fn new() -> Self {
+        Self::with_max_points(DEFAULT_MAX_POINTS)
+    }
+
+    pub fn with_max_points(max_points: usize) -> Self {
         Self {
-            graph: SimpleProfessionalGraph::new(
-                "QUIC Latency (ms)".to_string(),
-                100, // 100 data points
-            ),
+            graph: SimpleProfessionalGraph::new("QUIC Latency (ms)".to_string(), max_points),
         }
     }
 
+    /// The retained latencies, oldest first
+    pub fn values(&self) -> Vec<f64> {
+        self.graph.data_points.iter().map(|&(_, latency)| latency).collect()
+    }
+
     pub fn add_latency(&mut self, latency: f64) {
         self.graph.add_data_point(latency);
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.graph.render(f, area);
+    pub fn add_latency_at(&mut self, timestamp: DateTime<Utc>, latency: f64) {
+        self.graph.add_data_point_at(timestamp, latency);
+    }
+
+    pub fn with_scale(mut self, scale: AxisScale) -> Self {
+        self.graph.scale = scale;
+        self
+    }
+
+    pub fn toggle_scale(&mut self) {
+        self.graph.toggle_scale();
+    }
+
+    pub fn navigate(&mut self, nav: TimeNav) {
+        self.graph.navigate(nav);
+    }
+
+    /// Zoom and pan position, to hand back later through `set_time_view`
+    pub fn time_view(&self) -> TimeView {
+        self.graph.view.clone()
+    }
+
+    pub fn set_time_view(&mut self, view: TimeView) {
+        self.graph.view = view;
+    }
+
+    pub fn clear(&mut self) {
+        self.graph.clear();
+    }
+
+    /// Draw a saved run behind the live line, aligned by elapsed time
+    pub fn set_baseline(&mut self, baseline: Option<Vec<(Duration, f64)>>) {
+        self.graph.set_baseline(baseline);
+    }
+
+    /// Highlight the graph as the one the mouse focused
+    pub fn set_selected(&mut self, selected: bool) {
+        self.graph.is_selected = selected;
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.graph.render(f, area, theme);
+    }
+
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        self.graph.render_as(f, area, presentation, theme);
     }
 
     pub fn get_analytics(&self) -> SimpleAnalytics {
@@ -267,6 +656,18 @@ impl SimpleQuicLatencyGraph {
     }
 }
 
+impl MinSize for SimpleQuicLatencyGraph {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        self.graph.min_size(presentation)
+    }
+}
+
+impl Default for SimpleQuicLatencyGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Professional QUIC Throughput Graph
 pub struct SimpleQuicThroughputGraph {
     graph: SimpleProfessionalGraph,
@@ -274,11 +675,17 @@ pub struct SimpleQuicThroughputGraph {
 
 impl SimpleQuicThroughputGraph {
     pub fn new() -> Self {
+        Self::with_max_points(DEFAULT_MAX_POINTS)
+    }
+
+    pub fn with_max_points(max_points: usize) -> Self {
         Self {
-            graph: SimpleProfessionalGraph::new(
-                "QUIC Throughput (KB/s)".to_string(),
-                100, // 100 data points
-            ),
+            graph: SimpleProfessionalGraph {
+                higher_is_better: true,
+                secondary: true,
+                rate: true,
+                ..SimpleProfessionalGraph::new("QUIC Throughput".to_string(), max_points)
+            },
         }
     }
 
@@ -286,11 +693,184 @@ impl SimpleQuicThroughputGraph {
         self.graph.add_data_point(throughput);
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
-        self.graph.render(f, area);
+    pub fn add_throughput_at(&mut self, timestamp: DateTime<Utc>, throughput: f64) {
+        self.graph.add_data_point_at(timestamp, throughput);
+    }
+
+    pub fn with_scale(mut self, scale: AxisScale) -> Self {
+        self.graph.scale = scale;
+        self
+    }
+
+    pub fn toggle_scale(&mut self) {
+        self.graph.toggle_scale();
+    }
+
+    pub fn navigate(&mut self, nav: TimeNav) {
+        self.graph.navigate(nav);
+    }
+
+    /// Zoom and pan position, to hand back later through `set_time_view`
+    pub fn time_view(&self) -> TimeView {
+        self.graph.view.clone()
+    }
+
+    pub fn set_time_view(&mut self, view: TimeView) {
+        self.graph.view = view;
+    }
+
+    pub fn clear(&mut self) {
+        self.graph.clear();
+    }
+
+    /// Draw a saved run behind the live line, aligned by elapsed time
+    pub fn set_baseline(&mut self, baseline: Option<Vec<(Duration, f64)>>) {
+        self.graph.set_baseline(baseline);
+    }
+
+    /// Highlight the graph as the one the mouse focused
+    pub fn set_selected(&mut self, selected: bool) {
+        self.graph.is_selected = selected;
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.graph.render(f, area, theme);
+    }
+
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        self.graph.render_as(f, area, presentation, theme);
     }
 
     pub fn get_analytics(&self) -> SimpleAnalytics {
         self.graph.get_analytics()
     }
 }
+
+impl MinSize for SimpleQuicThroughputGraph {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        self.graph.min_size(presentation)
+    }
+}
+
+impl Default for SimpleQuicThroughputGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::MAX_MEASUREMENT;
+    use rand::{Rng, SeedableRng};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(1_700_000_000_000 + millis).unwrap()
+    }
+
+    fn rendered(graph: &SimpleProfessionalGraph) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|f| graph.render(f, f.area(), &Theme::default())).unwrap();
+        terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn fuzzed_values_render_without_panicking() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1844);
+        let theme = Theme::default();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut graphs = [
+            SimpleQuicLatencyGraph::with_max_points(200),
+            SimpleQuicLatencyGraph::with_max_points(200).with_scale(AxisScale::Log10),
+        ];
+        for round in 0..20 {
+            for _ in 0..50 {
+                let value = match rng.gen_range(0..8) {
+                    0 => f64::NAN,
+                    1 => f64::INFINITY,
+                    2 => f64::NEG_INFINITY,
+                    3 => 0.0,
+                    4 => -rng.gen_range(0.0..MAX_MEASUREMENT),
+                    5 => rng.gen_range(1e12..MAX_MEASUREMENT),
+                    _ => rng.gen_range(0.0..1000.0),
+                };
+                for graph in &mut graphs {
+                    graph.add_latency(value);
+                }
+            }
+            for graph in &graphs {
+                let analytics = graph.get_analytics();
+                let fields = [analytics.current, analytics.average, analytics.min, analytics.max];
+                assert!(fields.iter().all(|value| value.is_finite()), "round {}: {:?}", round, fields);
+                assert!(analytics.min <= analytics.p50 && analytics.p50 <= analytics.p99 && analytics.p99 <= analytics.max);
+                let (low, high) = graph.graph.y_bounds;
+                assert!(low.is_finite() && high.is_finite() && low < high, "round {}: {:?}", round, (low, high));
+                for presentation in [Presentation::Full, Presentation::Compact, Presentation::Minimal] {
+                    terminal.draw(|f| graph.render_as(f, f.area(), presentation, &theme)).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn undefined_and_constant_values_never_render_as_nan() {
+        let theme = Theme::default();
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut graph = SimpleQuicLatencyGraph::with_max_points(50);
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            graph.add_latency(value);
+        }
+        for filled in [false, true] {
+            if filled {
+                (0..20).for_each(|_| graph.add_latency(0.0));
+            }
+            for presentation in [Presentation::Full, Presentation::Compact, Presentation::Minimal] {
+                terminal.draw(|f| graph.render_as(f, f.area(), presentation, &theme)).unwrap();
+                let text: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+                assert!(!text.contains("NaN") && !text.contains("inf"), "{:?} {}: {}", presentation, filled, text);
+            }
+        }
+    }
+
+    #[test]
+    fn irregular_arrivals_keep_their_wall_clock_spacing() {
+        let mut graph = SimpleProfessionalGraph::new("Latency".to_string(), 100);
+        // A burst, a pause, a trickle and one sample that arrives late
+        for (millis, value) in [(0, 10.0), (100, 11.0), (200, 12.0), (7_200, 13.0), (37_200, 14.0), (3_000, 15.0)] {
+            graph.add_data_point_at(at(millis), value);
+        }
+        let times: Vec<i64> = graph.data_points.iter().map(|(t, _)| (*t - at(0)).num_milliseconds()).collect();
+        assert_eq!(times, [0, 100, 200, 3_000, 7_200, 37_200]);
+        assert_eq!(graph.span(), Duration::from_millis(37_200));
+
+        // The window ends at the newest sample, whatever the sample count
+        let (start, end) = graph.view.bounds(at(0), at(37_200));
+        assert_eq!(end, at(37_200));
+        assert_eq!(start, at(37_200 - 60_000));
+
+        let text = rendered(&graph);
+        let middle = start + (end - start) / 2;
+        for label in [start, middle, end].map(format_clock) {
+            assert!(text.contains(&label), "no {} label in {}", label, text);
+        }
+        assert!(text.contains("Span: 37s"), "{}", text);
+    }
+
+    #[test]
+    fn retention_culls_by_timestamp_not_by_count() {
+        let mut graph = SimpleProfessionalGraph::new("Latency".to_string(), 1_000);
+        graph.retention = Duration::from_secs(10);
+        for millis in [0, 1_000, 2_000, 9_000] {
+            graph.add_data_point_at(at(millis), millis as f64);
+        }
+        assert_eq!(graph.data_points.len(), 4);
+
+        // Twelve seconds in, the first two samples are older than the retention
+        graph.add_data_point_at(at(11_500), 11_500.0);
+        let times: Vec<DateTime<Utc>> = graph.data_points.iter().map(|(t, _)| *t).collect();
+        assert_eq!(times, [at(2_000), at(9_000), at(11_500)]);
+        assert_eq!(graph.get_analytics().min, 2_000.0);
+        assert_eq!(graph.get_analytics().data_points, 3);
+    }
+}
@@ -0,0 +1,224 @@
+//! Pass/fail evaluation of a run against configured targets
+//!
+//! Each configured target is checked over the samples of the last
+//! `window_secs`, counted back from the newest sample rather than the wall
+//! clock, so a replayed run gets the same verdict as the live one. Only
+//! stored history can be evaluated, so a window longer than the history
+//! covers the stored samples.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::metrics::{calculate_latency_percentiles, finite_values, format_value, QUICMetrics};
+use crate::units::{deserialize_rate, format_rate};
+
+/// `[sla]` section of the config file; no target set means no verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlaConfig {
+    /// Highest allowed P95 latency, in ms
+    pub p95_latency_ms: Option<f64>,
+    /// Highest allowed average packet loss, in percent
+    pub max_loss_pct: Option<f64>,
+    /// Lowest allowed average throughput, in bits per second or as a string such as `"80 Mbps"`
+    #[serde(deserialize_with = "deserialize_rate")]
+    pub min_throughput: Option<f64>,
+    /// Seconds of samples evaluated, back from the newest; 0 for all stored samples
+    pub window_secs: u64,
+    /// Exit non-zero from `live --headless` when the verdict at shutdown is not a pass
+    pub exit_on_fail: bool,
+}
+
+impl Default for SlaConfig {
+    fn default() -> Self {
+        Self {
+            p95_latency_ms: None,
+            max_loss_pct: None,
+            min_throughput: None,
+            window_secs: 60,
+            exit_on_fail: true,
+        }
+    }
+}
+
+impl SlaConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.p95_latency_ms.is_some() || self.max_loss_pct.is_some() || self.min_throughput.is_some()
+    }
+
+    /// Oldest capture time (unix ms) inside the window ending at `newest_ms`, `None` for all samples
+    pub fn window_start_ms(&self, newest_ms: i64) -> Option<i64> {
+        (self.window_secs > 0).then(|| newest_ms - self.window_secs as i64 * 1000)
+    }
+
+    /// Check the targets against `samples` (oldest first)
+    pub fn evaluate(&self, samples: &[QUICMetrics]) -> SlaVerdict {
+        let window_end = samples.last().map(|sample| sample.timestamp);
+        let start = window_end.and_then(|end| self.window_start_ms(end.timestamp_millis()));
+        let window: Vec<&QUICMetrics> = samples
+            .iter()
+            .filter(|sample| start.is_none_or(|start| sample.timestamp.timestamp_millis() >= start))
+            .collect();
+        let series = |pick: fn(&QUICMetrics) -> f64| {
+            finite_values(&window.iter().map(|s| pick(s)).collect::<Vec<f64>>())
+        };
+        let mean = |values: Vec<f64>| {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+
+        let mut checks = Vec::new();
+        if let Some(target) = self.p95_latency_ms {
+            let latency = series(|s| s.latency);
+            let p95 = (!latency.is_empty()).then(|| calculate_latency_percentiles(&latency).1);
+            checks.push(SlaCheck::new("p95 latency", format_ms, Bound::AtMost, target, p95));
+        }
+        if let Some(target) = self.max_loss_pct {
+            checks.push(SlaCheck::new("loss", format_pct, Bound::AtMost, target, mean(series(|s| s.packet_loss * 100.0))));
+        }
+        if let Some(target) = self.min_throughput {
+            checks.push(SlaCheck::new(
+                "throughput",
+                |bps| format_rate(bps, 2),
+                Bound::AtLeast,
+                target,
+                mean(series(|s| s.throughput)),
+            ));
+        }
+
+        let status = if checks.is_empty() {
+            SlaStatus::Disabled
+        } else if window.is_empty() {
+            SlaStatus::NoData
+        } else if checks.iter().all(|check| check.passed) {
+            SlaStatus::Pass
+        } else {
+            SlaStatus::Fail
+        };
+        SlaVerdict {
+            status,
+            window_secs: self.window_secs,
+            samples: window.len(),
+            window_start: window.first().map(|sample| sample.timestamp),
+            window_end,
+            checks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaStatus {
+    Pass,
+    Fail,
+    /// Targets are set but no sample has arrived
+    NoData,
+    /// No target is configured
+    Disabled,
+}
+
+/// Which side of the target a value has to stay on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bound {
+    AtMost,
+    AtLeast,
+}
+
+/// One target and the value measured for it
+#[derive(Debug, Clone, Serialize)]
+pub struct SlaCheck {
+    pub criterion: &'static str,
+    /// Formats the target and actual value with their unit
+    #[serde(skip)]
+    pub format: fn(f64) -> String,
+    pub bound: Bound,
+    pub target: f64,
+    /// `None` when the window has no finite value of the metric, which fails the check
+    pub actual: Option<f64>,
+    pub passed: bool,
+}
+
+impl SlaCheck {
+    fn new(criterion: &'static str, format: fn(f64) -> String, bound: Bound, target: f64, actual: Option<f64>) -> Self {
+        let passed = actual.is_some_and(|actual| match bound {
+            Bound::AtMost => actual <= target,
+            Bound::AtLeast => actual >= target,
+        });
+        Self {
+            criterion,
+            format,
+            bound,
+            target,
+            actual,
+            passed,
+        }
+    }
+}
+
+/// `p95 latency 62.3ms > 50ms`, or `loss no data`
+impl fmt::Display for SlaCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(actual) = self.actual else {
+            return write!(f, "{} no data", self.criterion);
+        };
+        let op = match (self.bound, self.passed) {
+            (Bound::AtMost, true) => "<=",
+            (Bound::AtMost, false) => ">",
+            (Bound::AtLeast, true) => ">=",
+            (Bound::AtLeast, false) => "<",
+        };
+        write!(
+            f,
+            "{} {} {} {}",
+            self.criterion,
+            (self.format)(actual),
+            op,
+            (self.format)(self.target)
+        )
+    }
+}
+
+fn format_ms(ms: f64) -> String {
+    format!("{}ms", format_value(ms, 2))
+}
+
+fn format_pct(pct: f64) -> String {
+    format!("{}%", format_value(pct, 2))
+}
+
+/// Result of evaluating the SLA, as returned by `GET /api/sla`
+#[derive(Debug, Clone, Serialize)]
+pub struct SlaVerdict {
+    pub status: SlaStatus,
+    pub window_secs: u64,
+    /// Samples in the window
+    pub samples: usize,
+    pub window_start: Option<DateTime<Utc>>,
+    pub window_end: Option<DateTime<Utc>>,
+    pub checks: Vec<SlaCheck>,
+}
+
+impl SlaVerdict {
+    /// Whether a CI gate should fail the run; a run without samples did not meet its targets either
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, SlaStatus::Fail | SlaStatus::NoData)
+    }
+
+    pub fn violations(&self) -> impl Iterator<Item = &SlaCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+
+    /// `PASS`, or `FAIL` followed by the violated checks
+    pub fn summary(&self) -> String {
+        match self.status {
+            SlaStatus::Pass => "PASS".to_string(),
+            SlaStatus::NoData => "NO DATA".to_string(),
+            SlaStatus::Disabled => "not configured".to_string(),
+            SlaStatus::Fail => {
+                let violations: Vec<String> = self.violations().map(|check| check.to_string()).collect();
+                format!("FAIL: {}", violations.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Counters behind the TUI status bar
+//!
+//! The ingest paths, the event loop and the broadcast receivers bump
+//! process-wide atomic counters, which cost one relaxed add each. The modes
+//! keep a `StatusBar` that samples the counters once per tick and turns the
+//! last few seconds of samples into the ingest rate, frame rate and frame
+//! time shown in the footer, next to the totals, the history fill and the
+//! refusals of the ingestion limits.
+//!
+//! Stored samples are also published on a watch channel, which wakes the
+//! event loop so it draws when data arrives rather than on a timer.
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::ingest::ingest_errors;
+use crate::ingest::limit::http_limit_stats;
+use crate::theme::Theme;
+
+/// Span the ingest rate and frame rate are averaged over
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+static SAMPLES_INGESTED: AtomicU64 = AtomicU64::new(0);
+static FRAMES_DRAWN: AtomicU64 = AtomicU64::new(0);
+static FRAME_MICROS: AtomicU64 = AtomicU64::new(0);
+static BROADCAST_LAGGED: AtomicU64 = AtomicU64::new(0);
+
+/// Total of `SAMPLES_INGESTED`, sent whenever it grows
+fn samples_sender() -> &'static watch::Sender<u64> {
+    static SENDER: OnceLock<watch::Sender<u64>> = OnceLock::new();
+    SENDER.get_or_init(|| watch::channel(0).0)
+}
+
+/// Count samples that were stored and wake whoever watches for them
+pub fn record_samples(count: u64) {
+    let total = SAMPLES_INGESTED.fetch_add(count, Ordering::Relaxed) + count;
+    samples_sender().send_replace(total);
+}
+
+/// Receiver that changes whenever samples are stored
+pub fn watch_samples() -> watch::Receiver<u64> {
+    samples_sender().subscribe()
+}
+
+/// Count a frame drawn to the terminal in `took`
+pub fn record_frame(took: Duration) {
+    FRAMES_DRAWN.fetch_add(1, Ordering::Relaxed);
+    FRAME_MICROS.fetch_add(took.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Count messages a broadcast receiver skipped because it fell behind
+pub fn record_lagged(skipped: u64) {
+    BROADCAST_LAGGED.fetch_add(skipped, Ordering::Relaxed);
+}
+
+/// The status counters at one moment, totals since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusCounters {
+    pub samples: u64,
+    pub frames: u64,
+    /// Time spent drawing `frames`
+    pub frame_micros: u64,
+    /// Broadcast messages skipped by receivers that fell behind
+    pub lagged: u64,
+    /// HTTP requests refused with 429
+    pub rate_limited: u64,
+    /// HTTP requests refused with 413
+    pub body_too_large: u64,
+    /// Samples refused by validation on any ingestion path
+    pub invalid: u64,
+}
+
+impl StatusCounters {
+    pub fn now() -> Self {
+        let http = http_limit_stats();
+        Self {
+            samples: SAMPLES_INGESTED.load(Ordering::Relaxed),
+            frames: FRAMES_DRAWN.load(Ordering::Relaxed),
+            frame_micros: FRAME_MICROS.load(Ordering::Relaxed),
+            lagged: BROADCAST_LAGGED.load(Ordering::Relaxed),
+            rate_limited: http.rate_limited,
+            body_too_large: http.body_too_large,
+            invalid: ingest_errors(),
+        }
+    }
+}
+
+/// Rates over the last few seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatusRates {
+    /// Samples stored per second
+    pub ingest: f64,
+    /// Frames drawn per second
+    pub fps: f64,
+    /// Average time to draw a frame, `None` without frames in the window
+    pub frame_time: Option<Duration>,
+}
+
+/// Recent samples of the counters, for the footer of a TUI mode
+#[derive(Debug, Clone, Default)]
+pub struct StatusBar {
+    /// Oldest first, reaching back at least `RATE_WINDOW` once that long has passed
+    samples: VecDeque<(Instant, StatusCounters)>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the counters; called once per tick
+    pub fn sample(&mut self) {
+        let now = Instant::now();
+        self.samples.push_back((now, StatusCounters::now()));
+        // Keep one sample from before the window so the rate covers all of it
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(at, _)| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The latest counters, zeros before the first `sample()`
+    pub fn counters(&self) -> StatusCounters {
+        self.samples.back().map_or_else(StatusCounters::default, |(_, counters)| *counters)
+    }
+
+    /// Rates over the window, `None` until two samples are apart
+    pub fn rates(&self) -> Option<StatusRates> {
+        let ((first_at, first), (last_at, last)) = (self.samples.front()?, self.samples.back()?);
+        let secs = last_at.duration_since(*first_at).as_secs_f64();
+        if secs <= 0.0 {
+            return None;
+        }
+        let frames = last.frames.saturating_sub(first.frames);
+        let frame_micros = last.frame_micros.saturating_sub(first.frame_micros);
+        Some(StatusRates {
+            ingest: last.samples.saturating_sub(first.samples) as f64 / secs,
+            fps: frames as f64 / secs,
+            frame_time: (frames > 0).then(|| Duration::from_micros(frame_micros / frames)),
+        })
+    }
+
+    /// Footer line: rates, totals, how full the history is, frame time and the refusals
+    ///
+    /// `history` is the number of stored samples and the capacity.
+    /// `help_key` is named at the end, since the key list lives in the help overlay.
+    pub fn line(&self, history: (usize, usize), help_key: &str, theme: &Theme) -> Line<'static> {
+        let counters = self.counters();
+        let muted = Style::default().fg(theme.muted);
+        let separator = || Span::styled(" | ", muted);
+        // Counters of lost or refused data stand out once they are not zero
+        let alarm = |label: String, count: u64| {
+            let style = match count {
+                0 => muted,
+                _ => Style::default().fg(theme.warning).add_modifier(Modifier::BOLD),
+            };
+            Span::styled(label, style)
+        };
+
+        let rates = self.rates();
+        let ingest_rate = rates.map_or_else(|| "-/s".to_string(), |rates| format!("{:.1}/s", rates.ingest));
+        let fps = rates.map_or_else(|| "- fps".to_string(), |rates| format!("{:.0} fps", rates.fps));
+        let frame_time = match rates.and_then(|rates| rates.frame_time) {
+            Some(took) => format!("frame {:.1} ms", took.as_secs_f64() * 1000.0),
+            None => "frame - ms".to_string(),
+        };
+        let (len, capacity) = history;
+        let fill = if capacity == 0 { 0.0 } else { len as f64 / capacity as f64 * 100.0 };
+        let rejected = counters.rate_limited + counters.body_too_large + counters.invalid;
+
+        Line::from(vec![
+            Span::styled(format!("ingest {}", ingest_rate), Style::default().fg(theme.accent)),
+            separator(),
+            Span::styled(format!("{} samples", counters.samples), Style::default().fg(theme.text)),
+            separator(),
+            Span::styled(format!("history {:.0}%", fill), Style::default().fg(theme.text)),
+            separator(),
+            Span::styled(fps, Style::default().fg(theme.text)),
+            separator(),
+            Span::styled(frame_time, Style::default().fg(theme.text)),
+            separator(),
+            alarm(format!("lagged {}", counters.lagged), counters.lagged),
+            separator(),
+            alarm(
+                format!(
+                    "rejected {} (429: {}, 413: {}, invalid: {})",
+                    rejected, counters.rate_limited, counters.body_too_large, counters.invalid
+                ),
+                rejected,
+            ),
+            separator(),
+            Span::styled(format!("'{}' help", help_key), muted),
+        ])
+    }
+}
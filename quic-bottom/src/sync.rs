@@ -0,0 +1,145 @@
+//! Shared state that outlives a panic
+//!
+//! A panic while a std lock is held poisons it, and with `lock().unwrap()`
+//! every later access panics too: one bad request would take the API and the
+//! renderer down with it. Shared state is locked through the `*_or_recover`
+//! methods here instead, which log the poisoning, clear it and carry on with
+//! the data as the panicking holder left it. At worst that is one sample
+//! half applied, which the next sample overwrites.
+//!
+//! HTTP handlers run under `catch_panic`, so a panic becomes a 500 with a
+//! JSON error rather than a dropped connection.
+
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use warp::http::StatusCode;
+use warp::reply::{Reply, Response};
+
+thread_local! {
+    /// Set while `catch_panic` runs a handler on this thread
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// `Mutex::lock` that never fails
+pub trait MutexExt<T: ?Sized> {
+    /// Lock, taking the data over from a holder that panicked
+    fn lock_or_recover(&self) -> MutexGuard<'_, T>;
+}
+
+/// `RwLock::read` and `write` that never fail
+pub trait RwLockExt<T: ?Sized> {
+    /// Lock for reading, taking the data over from a writer that panicked
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T>;
+
+    /// Lock for writing, taking the data over from a writer that panicked
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T>;
+}
+
+impl<T: ?Sized> MutexExt<T> for Mutex<T> {
+    fn lock_or_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|poisoned| {
+            let guard = recover(poisoned);
+            self.clear_poison();
+            guard
+        })
+    }
+}
+
+impl<T: ?Sized> RwLockExt<T> for RwLock<T> {
+    fn read_or_recover(&self) -> RwLockReadGuard<'_, T> {
+        self.read().unwrap_or_else(|poisoned| {
+            let guard = recover(poisoned);
+            self.clear_poison();
+            guard
+        })
+    }
+
+    fn write_or_recover(&self) -> RwLockWriteGuard<'_, T> {
+        self.write().unwrap_or_else(|poisoned| {
+            let guard = recover(poisoned);
+            self.clear_poison();
+            guard
+        })
+    }
+}
+
+fn recover<G>(poisoned: PoisonError<G>) -> G {
+    log::warn!("Recovered a lock poisoned by a panic; continuing with its last state");
+    poisoned.into_inner()
+}
+
+/// Whether this thread is inside `catch_panic`, where a panic is answered
+/// rather than fatal
+pub fn catching_panic() -> bool {
+    CATCHING.with(Cell::get)
+}
+
+/// Run an HTTP handler, answering a panic with 500 and a JSON error
+pub fn catch_panic<R: Reply>(handler: impl FnOnce() -> R) -> Response {
+    let outer = CATCHING.with(|catching| catching.replace(true));
+    let result = catch_unwind(AssertUnwindSafe(|| handler().into_response()));
+    CATCHING.with(|catching| catching.set(outer));
+
+    result.unwrap_or_else(|_| {
+        log::error!("HTTP handler panicked; answered 500");
+        let body = serde_json::json!({"status": "error", "message": "internal error"});
+        warp::reply::with_status(warp::reply::json(&body), StatusCode::INTERNAL_SERVER_ERROR).into_response()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn recovers_a_mutex_poisoned_by_a_panic() {
+        let lock = Arc::new(Mutex::new(1));
+        let holder = Arc::clone(&lock);
+        let _ = std::thread::spawn(move || {
+            let mut value = holder.lock().unwrap();
+            *value = 2;
+            panic!("holder dies with the lock held");
+        })
+        .join();
+        assert!(lock.is_poisoned());
+
+        // The update made before the panic stays, and the poisoning is gone
+        *lock.lock_or_recover() += 1;
+        assert_eq!(*lock.lock_or_recover(), 3);
+        assert!(!lock.is_poisoned());
+    }
+
+    #[test]
+    fn recovers_a_rwlock_poisoned_by_a_writer() {
+        let lock = Arc::new(RwLock::new(vec![1]));
+        let writer = Arc::clone(&lock);
+        let _ = std::thread::spawn(move || {
+            writer.write().unwrap().push(2);
+            let _guard = writer.write().unwrap();
+            panic!("writer dies with the lock held");
+        })
+        .join();
+        assert!(lock.is_poisoned());
+
+        assert_eq!(*lock.read_or_recover(), [1, 2]);
+        lock.write_or_recover().push(3);
+        assert_eq!(*lock.read_or_recover(), [1, 2, 3]);
+        assert!(!lock.is_poisoned());
+    }
+
+    #[test]
+    fn handler_panic_becomes_a_500() {
+        let response = catch_panic(|| -> warp::reply::Json { panic!("handler bug") });
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!catching_panic());
+
+        let response = catch_panic(|| {
+            assert!(catching_panic());
+            warp::reply::json(&serde_json::json!({"status": "ok"}))
+        });
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!catching_panic());
+    }
+}
@@ -0,0 +1,194 @@
+//! Quantile estimates of a sliding window
+//!
+//! `TDigest` summarizes a window as a few hundred centroids (mean and
+//! weight), small at the tails and larger towards the median, so P99 stays
+//! close to exact while the middle is approximated. Samples are merged in
+//! as they arrive and taken back out when they leave the window. A removal
+//! can only be matched to the nearest centroid, so the digest drifts as the
+//! window slides; it is rebuilt from the exact window values, which the
+//! owner keeps sorted, once enough samples have left since the last build.
+
+/// Centroids allowed per unit of the scale function; bigger is more exact
+pub const COMPRESSION: f64 = 100.0;
+
+/// Removals since the last build, as a share of the window, that trigger a rebuild
+const REBUILD_SHARE: f64 = 0.125;
+
+/// Removals that are always tolerated before a rebuild, for small windows
+const REBUILD_MIN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Merging t-digest with approximate removal
+#[derive(Debug, Clone, Default)]
+pub struct TDigest {
+    /// Sorted by mean
+    centroids: Vec<Centroid>,
+    count: usize,
+    removed_since_build: usize,
+}
+
+impl TDigest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Samples in the digest
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Largest weight a centroid around quantile `q` of `total` samples may have
+    fn weight_limit(total: f64, q: f64) -> f64 {
+        (4.0 * total * q * (1.0 - q) / COMPRESSION).max(1.0)
+    }
+
+    /// Replace the digest with one of `sorted`, given as `(value, repeats)` in ascending order
+    pub fn rebuild(&mut self, sorted: impl IntoIterator<Item = (f64, usize)>, count: usize) {
+        self.centroids.clear();
+        self.count = count;
+        self.removed_since_build = 0;
+        let total = count as f64;
+        let mut seen = 0.0;
+        for (value, repeats) in sorted {
+            let mut left = repeats as f64;
+            while left > 0.0 {
+                let Some(last) = self.centroids.last_mut() else {
+                    self.centroids.push(Centroid { mean: value, weight: 1.0 });
+                    left -= 1.0;
+                    continue;
+                };
+                let q = (seen + last.weight / 2.0) / total;
+                let room = (Self::weight_limit(total, q) - last.weight).floor();
+                if room >= 1.0 {
+                    // As many repeats as the centroid has room for
+                    let take = room.min(left);
+                    last.mean += (value - last.mean) * take / (last.weight + take);
+                    last.weight += take;
+                    left -= take;
+                } else {
+                    seen += last.weight;
+                    self.centroids.push(Centroid { mean: value, weight: 1.0 });
+                    left -= 1.0;
+                }
+            }
+        }
+    }
+
+    /// Merge one sample into the nearest centroid with room, or add a centroid for it
+    ///
+    /// Returns false when the digest has grown past twice `COMPRESSION`
+    /// centroids and should be rebuilt.
+    pub fn push(&mut self, value: f64) -> bool {
+        self.count += 1;
+        let total = self.count as f64;
+        let index = self.centroids.partition_point(|c| c.mean < value);
+        let nearest = self.nearest(index, value);
+        if let Some(i) = nearest {
+            let before: f64 = self.centroids[..i].iter().map(|c| c.weight).sum();
+            let c = &mut self.centroids[i];
+            let q = (before + c.weight / 2.0) / total;
+            if c.weight + 1.0 <= Self::weight_limit(total, q) {
+                c.weight += 1.0;
+                c.mean += (value - c.mean) / c.weight;
+                return true;
+            }
+        }
+        self.centroids.insert(index, Centroid { mean: value, weight: 1.0 });
+        self.centroids.len() <= 2 * COMPRESSION as usize
+    }
+
+    /// Take one sample back out of the centroid nearest to it
+    ///
+    /// Returns false when enough has been removed since the last build that
+    /// the digest should be rebuilt.
+    pub fn remove(&mut self, value: f64) -> bool {
+        let index = self.centroids.partition_point(|c| c.mean < value);
+        let Some(i) = self.nearest(index, value) else {
+            return true;
+        };
+        self.count -= 1;
+        self.removed_since_build += 1;
+        let c = &mut self.centroids[i];
+        if c.weight <= 1.0 {
+            self.centroids.remove(i);
+        } else {
+            c.mean = (c.mean * c.weight - value) / (c.weight - 1.0);
+            c.weight -= 1.0;
+            // Keep the order by mean; the mean moved away from `value`, at most past a neighbour or two
+            let mut i = i;
+            while i > 0 && self.centroids[i - 1].mean > self.centroids[i].mean {
+                self.centroids.swap(i - 1, i);
+                i -= 1;
+            }
+            while i + 1 < self.centroids.len() && self.centroids[i + 1].mean < self.centroids[i].mean {
+                self.centroids.swap(i, i + 1);
+                i += 1;
+            }
+        }
+        let allowed = ((self.count as f64 * REBUILD_SHARE) as usize).max(REBUILD_MIN);
+        self.removed_since_build <= allowed
+    }
+
+    /// Index of the centroid closest to `value`, given the first one at or above it
+    fn nearest(&self, index: usize, value: f64) -> Option<usize> {
+        let below = index.checked_sub(1);
+        let above = (index < self.centroids.len()).then_some(index);
+        match (below, above) {
+            (Some(b), Some(a)) => {
+                if value - self.centroids[b].mean <= self.centroids[a].mean - value {
+                    Some(b)
+                } else {
+                    Some(a)
+                }
+            }
+            (b, a) => b.or(a),
+        }
+    }
+
+    /// Estimated value at quantile `p` (0.0..=1.0), interpolated like `metrics::percentile`
+    ///
+    /// `min` and `max` are the exact extremes of the window; the estimate is
+    /// kept between them. `None` when empty.
+    pub fn quantile(&self, p: f64, min: f64, max: f64) -> Option<f64> {
+        let first = self.centroids.first()?;
+        let last = self.centroids.last()?;
+        let total = self.centroids.iter().map(|c| c.weight).sum::<f64>();
+        // Position in the window with sample i centered at i + 0.5, as rank p * (n - 1) would be
+        let target = p.clamp(0.0, 1.0) * (total - 1.0) + 0.5;
+
+        // Outside the outermost centers, towards the exact extremes
+        let first_center = first.weight / 2.0;
+        if target <= first_center {
+            return Some(interpolate(0.5, min, first_center, first.mean, target).clamp(min, max));
+        }
+        let last_center = total - last.weight / 2.0;
+        if target >= last_center {
+            return Some(interpolate(last_center, last.mean, total - 0.5, max, target).clamp(min, max));
+        }
+
+        let mut start = 0.0;
+        for pair in self.centroids.windows(2) {
+            let (left, right) = (pair[0], pair[1]);
+            let left_center = start + left.weight / 2.0;
+            let right_center = start + left.weight + right.weight / 2.0;
+            if target <= right_center {
+                return Some(interpolate(left_center, left.mean, right_center, right.mean, target).clamp(min, max));
+            }
+            start += left.weight;
+        }
+        Some(last.mean.clamp(min, max))
+    }
+}
+
+/// Value at `x` on the line from `(x0, y0)` to `(x1, y1)`
+fn interpolate(x0: f64, y0: f64, x1: f64, y1: f64, x: f64) -> f64 {
+    if x1 <= x0 {
+        return y1;
+    }
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
@@ -0,0 +1,276 @@
+//! Color themes for the TUI
+//!
+//! Widgets draw with roles (titles, graph series, severity levels, muted
+//! text) rather than fixed colors, and a theme maps each role to a color.
+//! `[colors] theme` picks one of the built-in themes and the other keys of
+//! the section override single roles. Colors are written as a name (`red`,
+//! `lightblue`, `darkgray`), `#rrggbb` or a 256-color index (`208`).
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Built-in themes, in the order the cycle key steps through them
+pub const THEMES: &[&str] = &["default", "gruvbox", "nord", "high-contrast", "monochrome"];
+
+/// Colors of every role the widgets draw with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Built-in theme this one starts from
+    pub name: &'static str,
+    /// Whether `[colors]` overrides some of the built-in colors
+    pub customized: bool,
+    /// Titles and the first graph series (latency)
+    pub primary: Color,
+    /// The second graph series (throughput)
+    pub secondary: Color,
+    /// Focus, highlights and key hints
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Stats lines under graphs and other readouts
+    pub info: Color,
+    /// Regular text
+    pub text: Color,
+    /// Placeholders, axis labels and unfocused borders
+    pub muted: Color,
+    /// Heatmap and correlation scale, from low to high
+    pub heat: [Color; 5],
+}
+
+impl Theme {
+    /// The built-in theme called `name`
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self {
+                name: "default",
+                customized: false,
+                primary: Color::Blue,
+                secondary: Color::Green,
+                accent: Color::Yellow,
+                success: Color::Green,
+                warning: Color::Yellow,
+                error: Color::Red,
+                info: Color::Cyan,
+                text: Color::White,
+                muted: Color::Gray,
+                heat: [Color::Green, Color::LightGreen, Color::Yellow, Color::LightRed, Color::Red],
+            },
+            "gruvbox" => Self {
+                name: "gruvbox",
+                customized: false,
+                primary: Color::Rgb(0x83, 0xa5, 0x98),
+                secondary: Color::Rgb(0xb8, 0xbb, 0x26),
+                accent: Color::Rgb(0xfa, 0xbd, 0x2f),
+                success: Color::Rgb(0xb8, 0xbb, 0x26),
+                warning: Color::Rgb(0xfe, 0x80, 0x19),
+                error: Color::Rgb(0xfb, 0x49, 0x34),
+                info: Color::Rgb(0x8e, 0xc0, 0x7c),
+                text: Color::Rgb(0xeb, 0xdb, 0xb2),
+                muted: Color::Rgb(0x92, 0x83, 0x74),
+                heat: [
+                    Color::Rgb(0x98, 0x97, 0x1a),
+                    Color::Rgb(0xb8, 0xbb, 0x26),
+                    Color::Rgb(0xfa, 0xbd, 0x2f),
+                    Color::Rgb(0xfe, 0x80, 0x19),
+                    Color::Rgb(0xfb, 0x49, 0x34),
+                ],
+            },
+            "nord" => Self {
+                name: "nord",
+                customized: false,
+                primary: Color::Rgb(0x81, 0xa1, 0xc1),
+                secondary: Color::Rgb(0xa3, 0xbe, 0x8c),
+                accent: Color::Rgb(0xeb, 0xcb, 0x8b),
+                success: Color::Rgb(0xa3, 0xbe, 0x8c),
+                warning: Color::Rgb(0xd0, 0x87, 0x70),
+                error: Color::Rgb(0xbf, 0x61, 0x6a),
+                info: Color::Rgb(0x88, 0xc0, 0xd0),
+                text: Color::Rgb(0xec, 0xef, 0xf4),
+                muted: Color::Rgb(0x61, 0x6e, 0x88),
+                heat: [
+                    Color::Rgb(0xa3, 0xbe, 0x8c),
+                    Color::Rgb(0x8f, 0xbc, 0xbb),
+                    Color::Rgb(0xeb, 0xcb, 0x8b),
+                    Color::Rgb(0xd0, 0x87, 0x70),
+                    Color::Rgb(0xbf, 0x61, 0x6a),
+                ],
+            },
+            "high-contrast" => Self {
+                name: "high-contrast",
+                customized: false,
+                primary: Color::LightCyan,
+                secondary: Color::LightGreen,
+                accent: Color::LightYellow,
+                success: Color::LightGreen,
+                warning: Color::LightYellow,
+                error: Color::LightRed,
+                info: Color::White,
+                text: Color::White,
+                muted: Color::White,
+                heat: [Color::LightGreen, Color::LightCyan, Color::LightYellow, Color::LightMagenta, Color::LightRed],
+            },
+            // The terminal's own colors, for terminals without color or NO_COLOR
+            "monochrome" => Self {
+                name: "monochrome",
+                customized: false,
+                primary: Color::Reset,
+                secondary: Color::Reset,
+                accent: Color::Reset,
+                success: Color::Reset,
+                warning: Color::Reset,
+                error: Color::Reset,
+                info: Color::Reset,
+                text: Color::Reset,
+                muted: Color::Reset,
+                heat: [Color::Reset; 5],
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// `nord`, or `nord (custom)` with `[colors]` overrides
+    pub fn label(&self) -> String {
+        if self.customized {
+            format!("{} (custom)", self.name)
+        } else {
+            self.name.to_string()
+        }
+    }
+
+    /// Color of `value` within `[min, max]` on the heat scale
+    pub fn heat_color(&self, value: f64, min: f64, max: f64) -> Color {
+        if max.partial_cmp(&min) != Some(Ordering::Greater) {
+            return self.muted;
+        }
+        let step = ((value - min) / (max - min) * self.heat.len() as f64).floor();
+        self.heat[(step.max(0.0) as usize).min(self.heat.len() - 1)]
+    }
+
+    /// Bold text in `color`, as widget titles are drawn
+    pub fn title(&self, color: Color) -> Style {
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin("default").expect("the default theme is built in")
+    }
+}
+
+/// The configured theme first, then the other built-in themes, for the cycle key
+#[derive(Debug, Clone)]
+pub struct ThemeCycle {
+    themes: Vec<Theme>,
+    current: usize,
+}
+
+impl ThemeCycle {
+    pub fn new(configured: Theme) -> Self {
+        let (name, customized) = (configured.name, configured.customized);
+        let others = THEMES
+            .iter()
+            .filter(|&&builtin| customized || builtin != name)
+            .filter_map(|builtin| Theme::builtin(builtin));
+        Self {
+            themes: std::iter::once(configured).chain(others).collect(),
+            current: 0,
+        }
+    }
+
+    pub fn current(&self) -> &Theme {
+        &self.themes[self.current]
+    }
+
+    /// Switch to the next theme and return it
+    pub fn advance(&mut self) -> &Theme {
+        self.current = (self.current + 1) % self.themes.len();
+        self.current()
+    }
+}
+
+impl Default for ThemeCycle {
+    fn default() -> Self {
+        Self::new(Theme::default())
+    }
+}
+
+/// `[colors]` section of the config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorConfig {
+    /// Built-in theme; `default` unless set, or `monochrome` when NO_COLOR is set
+    pub theme: Option<String>,
+    /// Titles and the first graph series
+    pub primary: Option<String>,
+    /// The second graph series
+    pub secondary: Option<String>,
+    /// Focus, highlights and key hints
+    pub accent: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    /// Stats lines and readouts
+    pub info: Option<String>,
+    pub text: Option<String>,
+    /// Placeholders, axis labels and unfocused borders
+    pub muted: Option<String>,
+}
+
+impl ColorConfig {
+    /// Exactly the built-in theme `name`, without overrides, as `--theme` selects it
+    pub fn builtin(name: &str) -> Self {
+        Self {
+            theme: Some(name.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// The theme these settings describe, failing on an unknown theme or color
+    pub fn theme(&self) -> Result<Theme> {
+        let name = match &self.theme {
+            Some(name) => name.as_str(),
+            None if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) => "monochrome",
+            None => "default",
+        };
+        let mut theme = Theme::builtin(name).ok_or_else(|| {
+            anyhow!("colors.theme: unknown theme '{}' (built in: {})", name, THEMES.join(", "))
+        })?;
+
+        let overrides = [
+            ("primary", &self.primary, &mut theme.primary),
+            ("secondary", &self.secondary, &mut theme.secondary),
+            ("accent", &self.accent, &mut theme.accent),
+            ("success", &self.success, &mut theme.success),
+            ("warning", &self.warning, &mut theme.warning),
+            ("error", &self.error, &mut theme.error),
+            ("info", &self.info, &mut theme.info),
+            ("text", &self.text, &mut theme.text),
+            ("muted", &self.muted, &mut theme.muted),
+        ];
+        let mut customized = false;
+        for (key, value, color) in overrides {
+            if let Some(value) = value {
+                *color = parse_color(value).with_context(|| format!("colors.{}", key))?;
+                customized = true;
+            }
+        }
+        theme.customized = customized;
+        Ok(theme)
+    }
+}
+
+/// Parse `red`, `lightblue`, `#83a598` or a 256-color index such as `208`
+pub fn parse_color(value: &str) -> Result<Color> {
+    let value = value.trim();
+    value.parse::<Color>().map_err(|_| {
+        anyhow!(
+            "invalid color '{}': expected a name such as red or lightblue, #rrggbb, or a 256-color index 0-255",
+            value
+        )
+    })
+}
@@ -0,0 +1,107 @@
+//! Zoom and pan state for time graphs
+//!
+//! A `TimeView` decides which slice of a graph's retained history is on
+//! screen. It follows the newest point until the user pans back, then stays
+//! anchored at a fixed time until they jump back to live.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Narrowest visible window
+pub const MIN_VIEW_WIDTH: Duration = Duration::from_secs(5);
+
+/// History kept for scrolling back, and the widest zoom
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(600);
+
+/// Navigation command for a time graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeNav {
+    /// Halve the visible window
+    ZoomIn,
+    /// Double the visible window
+    ZoomOut,
+    /// Move a quarter window back in time
+    PanBack,
+    /// Move a quarter window forward, resuming live at the newest point
+    PanForward,
+    /// Jump to the oldest retained point
+    Oldest,
+    /// Follow the newest point again
+    Live,
+}
+
+/// Visible window of a time graph
+#[derive(Debug, Clone)]
+pub struct TimeView {
+    /// Width of the visible window
+    pub width: Duration,
+    /// Widest the window can be zoomed out to
+    pub max_width: Duration,
+    /// Right edge while panned into history; `None` follows the newest point
+    anchor: Option<DateTime<Utc>>,
+}
+
+impl TimeView {
+    pub fn new(width: Duration, max_width: Duration) -> Self {
+        let max_width = max_width.max(MIN_VIEW_WIDTH);
+        Self {
+            width: width.clamp(MIN_VIEW_WIDTH, max_width),
+            max_width,
+            anchor: None,
+        }
+    }
+
+    /// Whether the view follows the newest point
+    pub fn is_live(&self) -> bool {
+        self.anchor.is_none()
+    }
+
+    /// Stop panning and follow the newest point
+    pub fn go_live(&mut self) {
+        self.anchor = None;
+    }
+
+    /// How far the right edge is behind `newest`, zero while live
+    pub fn lag_behind(&self, newest: DateTime<Utc>) -> Duration {
+        self.anchor
+            .and_then(|anchor| (newest - anchor).to_std().ok())
+            .unwrap_or_default()
+    }
+
+    /// Visible `(start, end)` given the oldest and newest retained points
+    pub fn bounds(&self, oldest: DateTime<Utc>, newest: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        // History can be evicted under an anchored view; never show past its start
+        let end = self.anchor.map_or(newest, |anchor| anchor.clamp(oldest, newest));
+        let start = end.checked_sub_signed(chrono_duration(self.width)).unwrap_or(oldest);
+        (start, end)
+    }
+
+    /// Apply `nav` to a graph whose data spans `oldest..=newest`
+    pub fn navigate(&mut self, nav: TimeNav, oldest: DateTime<Utc>, newest: DateTime<Utc>) {
+        let step = chrono_duration(self.width / 4);
+        let (_, end) = self.bounds(oldest, newest);
+        // Right edge that puts the oldest point at the left edge
+        let earliest = oldest
+            .checked_add_signed(chrono_duration(self.width))
+            .map_or(newest, |t| t.min(newest));
+        match nav {
+            TimeNav::ZoomIn => self.width = (self.width / 2).max(MIN_VIEW_WIDTH),
+            TimeNav::ZoomOut => self.width = (self.width * 2).min(self.max_width),
+            // Anchoring at the newest point would look live without following it
+            TimeNav::PanBack => {
+                let end = (end - step).max(earliest);
+                self.anchor = (end < newest).then_some(end);
+            }
+            TimeNav::PanForward => {
+                let end = end + step;
+                self.anchor = (end < newest).then_some(end);
+            }
+            TimeNav::Oldest => self.anchor = (earliest < newest).then_some(earliest),
+            TimeNav::Live => self.go_live(),
+        }
+    }
+}
+
+fn chrono_duration(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
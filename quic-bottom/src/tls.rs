@@ -0,0 +1,225 @@
+//! TLS for the HTTP APIs
+//!
+//! With `[api] tls_cert` and `tls_key` (or `--tls-cert`/`--tls-key`) set,
+//! both API servers speak HTTPS only. The PEM files are read and checked at
+//! startup, including that the key belongs to the certificate, so a bad pair
+//! fails there instead of on the first handshake.
+
+use anyhow::{anyhow, Context, Result};
+use rustls::crypto::ring::{default_provider, sign::any_supported_type};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::future::Future;
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::Pin;
+use warp::{Filter, Rejection, Reply};
+
+use crate::bridge::ApiConfig;
+
+/// Running server, plain or TLS
+pub type ServerFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Certificate chain and private key, as PEM, checked to belong together
+pub struct TlsIdentity {
+    cert: Vec<u8>,
+    key: Vec<u8>,
+}
+
+impl TlsIdentity {
+    /// Read the pair configured in `[api]`; `None` when TLS is off
+    pub fn from_config(config: &ApiConfig) -> Result<Option<Self>> {
+        match (&config.tls_cert, &config.tls_key) {
+            (None, None) => Ok(None),
+            (Some(cert), Some(key)) => Self::load(cert, key).map(Some),
+            _ => Err(anyhow!("TLS needs both tls_cert and tls_key")),
+        }
+    }
+
+    pub fn load(cert_path: &Path, key_path: &Path) -> Result<Self> {
+        let cert = std::fs::read(cert_path)
+            .with_context(|| format!("failed to read TLS certificate {}", cert_path.display()))?;
+        let key = std::fs::read(key_path)
+            .with_context(|| format!("failed to read TLS key {}", key_path.display()))?;
+
+        let chain = rustls_pemfile::certs(&mut BufReader::new(cert.as_slice()))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("invalid PEM in {}", cert_path.display()))?;
+        let Some(leaf) = chain.first() else {
+            return Err(anyhow!("no certificate found in {}", cert_path.display()));
+        };
+        let private_key = rustls_pemfile::private_key(&mut BufReader::new(key.as_slice()))
+            .with_context(|| format!("invalid PEM in {}", key_path.display()))?
+            .ok_or_else(|| anyhow!("no private key found in {}", key_path.display()))?;
+
+        check_key_matches(leaf, &private_key).with_context(|| {
+            format!(
+                "TLS key {} does not match certificate {}",
+                key_path.display(),
+                cert_path.display()
+            )
+        })?;
+        Ok(Self { cert, key })
+    }
+}
+
+/// Sign with the key and verify against the certificate's public key
+fn check_key_matches(cert: &CertificateDer<'_>, key: &PrivateKeyDer<'_>) -> Result<()> {
+    const MESSAGE: &[u8] = b"quic-bottom key check";
+
+    let algorithms = default_provider().signature_verification_algorithms;
+    let offered: Vec<_> = algorithms.mapping.iter().map(|(scheme, _)| *scheme).collect();
+    let signer = any_supported_type(key)
+        .map_err(|e| anyhow!("unsupported private key: {}", e))?
+        .choose_scheme(&offered)
+        .ok_or_else(|| anyhow!("no usable signature scheme for the private key"))?;
+    let signature = signer
+        .sign(MESSAGE)
+        .map_err(|e| anyhow!("signing failed: {}", e))?;
+
+    let scheme = signer.scheme();
+    let verifiers = algorithms
+        .mapping
+        .iter()
+        .find(|(candidate, _)| *candidate == scheme)
+        .map(|(_, verifiers)| *verifiers)
+        .unwrap_or_default();
+    let cert = webpki::EndEntityCert::try_from(cert).map_err(|e| anyhow!("invalid certificate: {}", e))?;
+    if verifiers
+        .iter()
+        .any(|alg| cert.verify_signature(*alg, MESSAGE, &signature).is_ok())
+    {
+        Ok(())
+    } else {
+        Err(anyhow!("signature made with the key does not verify"))
+    }
+}
+
+/// Bind `routes` on `addr`, with TLS when `tls` is given
+///
+/// Errors, rather than panicking as warp's `bind` does, when the address is
+/// taken.
+pub fn bind<F>(routes: F, addr: SocketAddr, tls: Option<&TlsIdentity>) -> Result<(SocketAddr, ServerFuture)>
+where
+    F: Filter<Error = Rejection> + Clone + Send + Sync + 'static,
+    F::Extract: Reply,
+{
+    let server = warp::serve(routes);
+    match tls {
+        None => {
+            let (bound, server) = server
+                .try_bind_ephemeral(addr)
+                .map_err(|e| anyhow!("failed to bind HTTP API to {}: {}", addr, e))?;
+            Ok((bound, Box::pin(server)))
+        }
+        Some(identity) => {
+            let (bound, server) = server
+                .tls()
+                .cert(&identity.cert)
+                .key(&identity.key)
+                .try_bind_with_graceful_shutdown(addr, std::future::pending())
+                .map_err(|e| anyhow!("failed to bind HTTPS API to {}: {}", addr, e))?;
+            Ok((bound, Box::pin(server)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::pki_types::ServerName;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    /// Self-signed EC certificate for localhost and 127.0.0.1, valid 2024-2124, its key
+    /// and an unrelated key, made with `openssl req -x509 -newkey ec`
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name)
+    }
+
+    /// Everything the server sends for `request` until it closes the connection
+    fn exchange(stream: &mut (impl Read + Write), request: &[u8]) -> String {
+        let mut response = Vec::new();
+        if stream.write_all(request).is_ok() {
+            // A reset or a missing close_notify just ends the response
+            let _ = stream.read_to_end(&mut response);
+        }
+        String::from_utf8_lossy(&response).into_owned()
+    }
+
+    /// `GET /health` over TLS, trusting the test certificate alone
+    fn get_over_tls(addr: SocketAddr) -> Result<String> {
+        let pem = std::fs::read(testdata("localhost.crt"))?;
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut BufReader::new(pem.as_slice())) {
+            roots.add(cert?)?;
+        }
+        let config = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connection = rustls::ClientConnection::new(Arc::new(config), ServerName::try_from("localhost")?)?;
+        let mut stream = rustls::StreamOwned::new(connection, TcpStream::connect(addr)?);
+        // Handshake first, so a failure shows as an error rather than an empty response
+        stream.conn.complete_io(&mut stream.sock)?;
+        Ok(exchange(
+            &mut stream,
+            b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        ))
+    }
+
+    #[tokio::test]
+    async fn serves_https_with_a_self_signed_certificate() {
+        let config = ApiConfig {
+            tls_cert: Some(testdata("localhost.crt")),
+            tls_key: Some(testdata("localhost.key")),
+            ..ApiConfig::default()
+        };
+        assert_eq!(config.scheme(), "https");
+        let identity = TlsIdentity::from_config(&config).unwrap().unwrap();
+        let routes = warp::path("health").map(|| "ok");
+        let (addr, server) = bind(routes, ([127, 0, 0, 1], 0).into(), Some(&identity)).unwrap();
+        tokio::spawn(server);
+
+        let response = tokio::task::spawn_blocking(move || get_over_tls(addr)).await.unwrap().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("ok"), "{}", response);
+
+        // Plain HTTP gets no answer on the TLS port
+        let response = tokio::task::spawn_blocking(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            exchange(&mut stream, b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        })
+        .await
+        .unwrap();
+        assert!(!response.contains("200 OK"), "{}", response);
+    }
+
+    /// Why loading `cert` and `key` fails, with its causes
+    fn load_error(cert: &str, key: &str) -> String {
+        match TlsIdentity::load(&testdata(cert), &testdata(key)) {
+            Ok(_) => panic!("{} and {} loaded", cert, key),
+            Err(e) => format!("{:#}", e),
+        }
+    }
+
+    #[test]
+    fn bad_pairs_fail_at_startup() {
+        let error = load_error("localhost.crt", "other.key");
+        assert!(error.contains("does not match certificate"), "{}", error);
+        let error = load_error("missing.crt", "localhost.key");
+        assert!(error.starts_with("failed to read TLS certificate"), "{}", error);
+        // The key where the certificate should be
+        let error = load_error("localhost.key", "localhost.key");
+        assert!(error.starts_with("no certificate found"), "{}", error);
+
+        let half = ApiConfig {
+            tls_cert: Some(testdata("localhost.crt")),
+            ..ApiConfig::default()
+        };
+        assert!(TlsIdentity::from_config(&half).is_err());
+        assert!(TlsIdentity::from_config(&ApiConfig::default()).unwrap().is_none());
+        assert_eq!(ApiConfig::default().scheme(), "http");
+    }
+}
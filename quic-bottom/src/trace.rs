@@ -0,0 +1,229 @@
+//! Full-resolution trace of one pinned connection
+//!
+//! Pinning a connection in the connection table keeps every sample it
+//! reports from then on, however the graphs thin their series, and appends
+//! each one as a JSON line to a trace file until it is unpinned. The file
+//! is flushed line by line, like a session recording; a write error stops
+//! the file but not the in-memory trace.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::metrics::QUICMetrics;
+
+/// Samples kept in memory for the trace panel; the file has all of them
+pub const TRACE_CAPACITY: usize = 100_000;
+
+/// One line of a trace file
+#[derive(Serialize)]
+struct TraceRecord<'a> {
+    connection_id: &'a str,
+    received_at: i64,
+    #[serde(flatten)]
+    sample: &'a QUICMetrics,
+}
+
+/// Raw samples of the pinned connection and the file they are written to
+pub struct ConnectionTrace {
+    connection_id: String,
+    samples: VecDeque<QUICMetrics>,
+    path: PathBuf,
+    /// `None` once a write has failed
+    writer: Option<LineWriter<File>>,
+    written: u64,
+    error: Option<String>,
+    started_at: DateTime<Utc>,
+}
+
+impl ConnectionTrace {
+    /// Start tracing `connection_id` into a new file under `dir`
+    pub fn create(connection_id: &str, dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let started_at = Utc::now();
+        let path = dir.join(format!(
+            "trace-{}-{}.ndjson",
+            file_safe(connection_id),
+            started_at.format("%Y%m%d-%H%M%S")
+        ));
+        let file = File::create(&path).with_context(|| format!("failed to create trace file {}", path.display()))?;
+        Ok(Self {
+            connection_id: connection_id.to_string(),
+            samples: VecDeque::new(),
+            path,
+            writer: Some(LineWriter::new(file)),
+            written: 0,
+            error: None,
+            started_at,
+        })
+    }
+
+    /// Keep `sample` and append it to the file
+    pub fn record(&mut self, sample: &QUICMetrics) {
+        self.samples.push_back(sample.clone());
+        while self.samples.len() > TRACE_CAPACITY {
+            self.samples.pop_front();
+        }
+
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let record = TraceRecord {
+            connection_id: &self.connection_id,
+            received_at: Utc::now().timestamp_millis(),
+            sample,
+        };
+        let result = serde_json::to_vec(&record)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut line| {
+                line.push(b'\n');
+                writer.write_all(&line)?;
+                Ok(())
+            });
+        match result {
+            Ok(()) => self.written += 1,
+            Err(e) => {
+                log::error!("Trace file {} stopped: {}", self.path.display(), e);
+                self.writer = None;
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Drop the samples held in memory; the file keeps them
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// Every sample since pinning, oldest first, up to `TRACE_CAPACITY`
+    pub fn samples(&self) -> &VecDeque<QUICMetrics> {
+        &self.samples
+    }
+
+    pub fn latest(&self) -> Option<&QUICMetrics> {
+        self.samples.back()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Lines written to the trace file
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Why the trace file stopped, if it did
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+}
+
+impl std::fmt::Debug for ConnectionTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionTrace")
+            .field("connection_id", &self.connection_id)
+            .field("samples", &self.samples.len())
+            .field("path", &self.path)
+            .field("written", &self.written)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// `connection_id` with anything but letters, digits, '-' and '_' replaced, for a file name
+fn file_safe(connection_id: &str) -> String {
+    connection_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ConnectionTracker;
+    use std::time::Duration;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quic-bottom-trace-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn writes_every_sample_to_the_file() {
+        let dir = temp_dir("file");
+        let mut trace = ConnectionTrace::create("conn/1", &dir).unwrap();
+        assert!(trace.path().file_name().unwrap().to_string_lossy().starts_with("trace-conn_1-"));
+        for latency in [10.0, 20.0, 30.0] {
+            trace.record(&QUICMetrics {
+                latency,
+                ..QUICMetrics::default()
+            });
+        }
+
+        let text = fs::read_to_string(trace.path()).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2]["connection_id"], "conn/1");
+        assert_eq!(lines[2]["latency"], 30.0);
+        assert_eq!(trace.written(), 3);
+        assert_eq!(trace.samples().len(), 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn tracker_traces_only_the_pinned_connection() {
+        let dir = temp_dir("tracker");
+        let mut tracker = ConnectionTracker::new(2, Duration::from_secs(5), Duration::from_secs(30));
+        tracker.pin("a", &dir).unwrap();
+        for latency in [1.0, 2.0, 3.0, 4.0] {
+            let sample = QUICMetrics {
+                latency,
+                ..QUICMetrics::default()
+            };
+            tracker.record("a", &sample);
+            tracker.record("b", &sample);
+        }
+
+        // The table's series is bounded; the trace keeps every raw sample
+        assert_eq!(tracker.get("a").unwrap().series.get_latency_data().len(), 2);
+        let trace = tracker.pinned().unwrap();
+        let latencies: Vec<f64> = trace.samples().iter().map(|sample| sample.latency).collect();
+        assert_eq!(latencies, [1.0, 2.0, 3.0, 4.0]);
+
+        let trace = tracker.unpin().unwrap();
+        tracker.record("a", &QUICMetrics::default());
+        assert_eq!(fs::read_to_string(trace.path()).unwrap().lines().count(), 4);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_at_most_the_capacity_in_memory() {
+        let dir = temp_dir("capacity");
+        let mut trace = ConnectionTrace::create("c", &dir).unwrap();
+        for i in 0..TRACE_CAPACITY + 5 {
+            trace.samples.push_back(QUICMetrics {
+                latency: i as f64,
+                ..QUICMetrics::default()
+            });
+        }
+        trace.record(&QUICMetrics::default());
+        assert_eq!(trace.samples().len(), TRACE_CAPACITY);
+        assert_eq!(trace.written(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
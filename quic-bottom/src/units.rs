@@ -0,0 +1,266 @@
+//! Throughput and packet loss units
+//!
+//! Samples are stored with throughput in bits per second. A sender declares
+//! the unit it reports in, either per sample with a `unit` field or for all
+//! of them with `[ingest] throughput_unit`, and ingest converts on arrival.
+//! Displays pick the unit that suits the value, so 940000000 shows as
+//! `940.00 Mbps` and 1.2e9 as `1.20 Gbps`.
+//!
+//! Packet loss is stored as a fraction, 0.0-1.0, and shown as a percent.
+//! Senders that report percents say so with a `loss_unit` field or
+//! `[ingest] loss_unit`, the same way.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::metrics::format_value;
+
+/// Unit of a throughput or bandwidth value, in bits per second and its decimal multiples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Unit {
+    #[default]
+    Bps,
+    Kbps,
+    Mbps,
+    Gbps,
+}
+
+/// Units from the smallest up, as auto-scaling tries them
+const UNITS: [Unit; 4] = [Unit::Bps, Unit::Kbps, Unit::Mbps, Unit::Gbps];
+
+impl Unit {
+    /// Bits per second in one of this unit
+    pub fn factor(self) -> f64 {
+        match self {
+            Unit::Bps => 1.0,
+            Unit::Kbps => 1e3,
+            Unit::Mbps => 1e6,
+            Unit::Gbps => 1e9,
+        }
+    }
+
+    /// `value` in this unit as bits per second
+    pub fn to_bps(self, value: f64) -> f64 {
+        value * self.factor()
+    }
+
+    /// `bps` bits per second in this unit
+    pub fn from_bps(self, bps: f64) -> f64 {
+        bps / self.factor()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Unit::Bps => "bps",
+            Unit::Kbps => "Kbps",
+            Unit::Mbps => "Mbps",
+            Unit::Gbps => "Gbps",
+        }
+    }
+
+    /// Largest unit in which `bps` is at least 1, bps for smaller or non-finite values
+    pub fn auto(bps: f64) -> Self {
+        UNITS
+            .into_iter()
+            .rev()
+            .find(|unit| bps.abs() >= unit.factor())
+            .unwrap_or(Unit::Bps)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// `bps`, `kbps`, `mbps` or `gbps`, in any case
+impl FromStr for Unit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        UNITS
+            .into_iter()
+            .find(|unit| unit.label().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| anyhow!("unknown throughput unit '{}' (expected bps, Kbps, Mbps or Gbps)", s))
+    }
+}
+
+impl TryFrom<String> for Unit {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Unit> for String {
+    fn from(unit: Unit) -> Self {
+        unit.label().to_string()
+    }
+}
+
+/// How a sender reports packet loss
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LossUnit {
+    /// 0.0-1.0
+    Fraction,
+    /// 0-100
+    Percent,
+    /// A fraction, unless the value is above 1.0 and so can only be a percent
+    #[default]
+    Auto,
+}
+
+impl LossUnit {
+    /// `loss` reported in this unit as a fraction
+    pub fn to_fraction(self, loss: f64) -> f64 {
+        match self {
+            LossUnit::Fraction => loss,
+            LossUnit::Percent => loss / 100.0,
+            LossUnit::Auto if loss > 1.0 => loss / 100.0,
+            LossUnit::Auto => loss,
+        }
+    }
+}
+
+/// Packet loss given as a fraction in percent, e.g. `0.50%` for 0.005
+pub fn format_loss(fraction: f64, precision: usize) -> String {
+    format!("{}%", format_value(fraction * 100.0, precision))
+}
+
+/// `bps` in the unit that suits it, e.g. `12.50 Mbps`, or `–` when not finite
+pub fn format_rate(bps: f64, precision: usize) -> String {
+    if !bps.is_finite() {
+        return format_value(bps, precision);
+    }
+    let unit = Unit::auto(bps);
+    format!("{} {}", format_value(unit.from_bps(bps), precision), unit)
+}
+
+/// Parse `80 Mbps` or `1.5Gbps` into bits per second; a bare number is bits per second
+pub fn parse_rate(text: &str) -> Result<f64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("invalid rate '{}': expected a number and a unit such as 80 Mbps", text))?;
+    let unit = if unit.is_empty() { Unit::Bps } else { unit.parse()? };
+    Ok(unit.to_bps(value))
+}
+
+/// Deserialize an optional rate given as bits per second or as a string such as `"80 Mbps"`
+pub fn deserialize_rate<'de, D>(deserializer: D) -> std::result::Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Rate {
+        Bps(f64),
+        Text(String),
+    }
+    match Option::<Rate>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Rate::Bps(bps)) => Ok(Some(bps)),
+        Some(Rate::Text(text)) => parse_rate(&text).map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip_through_bits_per_second() {
+        assert_eq!(Unit::Mbps.to_bps(940.0), 9.4e8);
+        assert_eq!(Unit::Kbps.to_bps(1.5), 1500.0);
+        assert_eq!(Unit::Gbps.from_bps(2.5e9), 2.5);
+        for unit in UNITS {
+            for value in [0.0, 0.001, 1.0, 37.5, 12_345.678] {
+                let back = unit.from_bps(unit.to_bps(value));
+                assert!((back - value).abs() <= value * 1e-12, "{} {}: {}", unit, value, back);
+            }
+        }
+    }
+
+    #[test]
+    fn auto_picks_the_largest_unit_with_a_whole_value() {
+        let cases = [
+            (0.0, Unit::Bps),
+            (999.0, Unit::Bps),
+            (1e3, Unit::Kbps),
+            (999_999.0, Unit::Kbps),
+            (9.4e8, Unit::Mbps),
+            (1.2e9, Unit::Gbps),
+            (4e12, Unit::Gbps),
+            (-2e6, Unit::Mbps),
+            (f64::NAN, Unit::Bps),
+        ];
+        for (bps, unit) in cases {
+            assert_eq!(Unit::auto(bps), unit, "{}", bps);
+        }
+        assert_eq!(format_rate(9.4e8, 2), "940.00 Mbps");
+        assert_eq!(format_rate(1.2e9, 2), "1.20 Gbps");
+        assert_eq!(format_rate(512.0, 0), "512 bps");
+        assert_eq!(format_rate(f64::INFINITY, 2), "–");
+    }
+
+    #[test]
+    fn units_parse_from_config_and_payloads() {
+        assert_eq!("mbps".parse::<Unit>().unwrap(), Unit::Mbps);
+        assert_eq!(" GBPS ".parse::<Unit>().unwrap(), Unit::Gbps);
+        assert!("MB/s".parse::<Unit>().is_err());
+        for unit in UNITS {
+            assert_eq!(unit.to_string().parse::<Unit>().unwrap(), unit);
+            let json = serde_json::to_string(&unit).unwrap();
+            assert_eq!(serde_json::from_str::<Unit>(&json).unwrap(), unit);
+        }
+        assert!(serde_json::from_str::<Unit>("\"KB/s\"").is_err());
+
+        assert_eq!(parse_rate("80 Mbps").unwrap(), 8e7);
+        assert_eq!(parse_rate("1.5Gbps").unwrap(), 1.5e9);
+        assert_eq!(parse_rate("2500").unwrap(), 2500.0);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("80 MB/s").is_err());
+
+        #[derive(Deserialize)]
+        struct Limit {
+            #[serde(default, deserialize_with = "deserialize_rate")]
+            rate: Option<f64>,
+        }
+        let rate = |json: &str| serde_json::from_str::<Limit>(json).map(|limit| limit.rate);
+        assert_eq!(rate(r#"{"rate": "10 Kbps"}"#).unwrap(), Some(1e4));
+        assert_eq!(rate(r#"{"rate": 640}"#).unwrap(), Some(640.0));
+        assert_eq!(rate("{}").unwrap(), None);
+        assert!(rate(r#"{"rate": "10 furlongs"}"#).is_err());
+    }
+
+    #[test]
+    fn loss_reads_as_a_fraction_in_either_convention() {
+        assert_eq!(LossUnit::Fraction.to_fraction(0.005), 0.005);
+        assert_eq!(LossUnit::Percent.to_fraction(0.5), 0.005);
+        assert_eq!(LossUnit::Percent.to_fraction(100.0), 1.0);
+        // Auto only guesses a percent where a fraction is impossible
+        assert_eq!(LossUnit::Auto.to_fraction(0.5), 0.5);
+        assert_eq!(LossUnit::Auto.to_fraction(1.0), 1.0);
+        assert_eq!(LossUnit::Auto.to_fraction(2.5), 0.025);
+
+        assert_eq!(format_loss(0.005, 2), "0.50%");
+        assert_eq!(format_loss(1.0, 0), "100%");
+        assert_eq!(format_loss(f64::NAN, 2), "–%");
+
+        for (name, unit) in [("fraction", LossUnit::Fraction), ("percent", LossUnit::Percent), ("auto", LossUnit::Auto)] {
+            assert_eq!(serde_json::to_string(&unit).unwrap(), format!("\"{}\"", name));
+            assert_eq!(serde_json::from_str::<LossUnit>(&format!("\"{}\"", name)).unwrap(), unit);
+        }
+    }
+}
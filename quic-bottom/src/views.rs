@@ -0,0 +1,212 @@
+//! Named view presets and the picker that switches between them
+//!
+//! A mode's built-in views (dashboard, analytics, ...) are presets like the
+//! ones `[[views]]` defines in the config file, which are `[layout]` grids
+//! under a name. `ViewRegistry` keeps them in order, tracks the active one
+//! and resolves names, so the view keys, the next/previous keys and the
+//! picker all switch views the same way.
+
+use anyhow::{bail, Result};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::improved_layout::{LayoutConfig, LayoutTree};
+use crate::keybindings::centered_rect;
+use crate::theme::Theme;
+
+/// A `[[views]]` entry: a layout grid under a name
+///
+/// ```toml
+/// [[views]]
+/// name = "cc-tuning"
+/// row = [{ column = [{ widgets = ["bbrv3", "throughput"] }] }]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewPresetConfig {
+    /// Shown in the header and the picker; a built-in view's name replaces that view
+    pub name: String,
+    #[serde(flatten)]
+    pub layout: LayoutConfig,
+}
+
+/// Check that every preset has a unique name and a valid, non-empty grid
+pub fn validate_presets(presets: &[ViewPresetConfig]) -> Result<()> {
+    let mut problems = Vec::new();
+    for (i, preset) in presets.iter().enumerate() {
+        let name = if preset.name.is_empty() {
+            problems.push(format!("view {}: needs a name", i + 1));
+            format!("view {}", i + 1)
+        } else {
+            format!("view \"{}\"", preset.name)
+        };
+        if presets[..i].iter().any(|other| other.name == preset.name) && !preset.name.is_empty() {
+            problems.push(format!("{}: is defined twice", name));
+        }
+        if preset.layout.rows.is_empty() {
+            problems.push(format!("{}: has no rows", name));
+        }
+        if let Err(e) = preset.layout.validate() {
+            problems.push(format!("{}: {:#}", name, e));
+        }
+    }
+    match problems.len() {
+        0 => Ok(()),
+        _ => bail!("{}", problems.join("; ")),
+    }
+}
+
+/// What a preset draws: one of the mode's own views, or a grid of widgets
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetContent<V> {
+    Builtin(V),
+    Layout(LayoutTree),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewPreset<V> {
+    pub name: String,
+    pub content: PresetContent<V>,
+    /// The built-in view this preset is, or replaces, for the mode's view keys
+    pub slot: Option<V>,
+}
+
+/// The presets of a mode, in picker order, and the active one
+#[derive(Debug, Clone)]
+pub struct ViewRegistry<V> {
+    presets: Vec<ViewPreset<V>>,
+    active: usize,
+}
+
+impl<V: Copy + PartialEq> ViewRegistry<V> {
+    /// The mode's `builtin` views by name, then the `configured` presets
+    ///
+    /// A configured preset named like a built-in view takes its place.
+    /// The first built-in view is active.
+    pub fn new(builtin: &[(&str, V)], configured: &[ViewPresetConfig]) -> Self {
+        let mut presets: Vec<ViewPreset<V>> = builtin
+            .iter()
+            .map(|&(name, view)| ViewPreset {
+                name: name.to_string(),
+                content: PresetContent::Builtin(view),
+                slot: Some(view),
+            })
+            .collect();
+        for preset in configured {
+            let Some(tree) = preset.layout.tree() else {
+                continue;
+            };
+            let content = PresetContent::Layout(tree);
+            match presets.iter_mut().find(|existing| existing.name == preset.name) {
+                Some(existing) => existing.content = content,
+                None => presets.push(ViewPreset {
+                    name: preset.name.clone(),
+                    content,
+                    slot: None,
+                }),
+            }
+        }
+        Self { presets, active: 0 }
+    }
+
+    /// Rebuild from a changed config, staying on the active preset if it still exists
+    pub fn reload(&mut self, builtin: &[(&str, V)], configured: &[ViewPresetConfig]) {
+        let name = self.active().name.clone();
+        *self = Self::new(builtin, configured);
+        self.select_name(&name);
+    }
+
+    pub fn presets(&self) -> &[ViewPreset<V>] {
+        &self.presets
+    }
+
+    pub fn active(&self) -> &ViewPreset<V> {
+        &self.presets[self.active]
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// The active built-in view, `None` while a configured grid is shown
+    pub fn builtin(&self) -> Option<V> {
+        match self.active().content {
+            PresetContent::Builtin(view) => Some(view),
+            PresetContent::Layout(_) => None,
+        }
+    }
+
+    /// Activate the preset at `index`; returns whether there is one
+    pub fn select(&mut self, index: usize) -> bool {
+        let exists = index < self.presets.len();
+        if exists {
+            self.active = index;
+        }
+        exists
+    }
+
+    /// Activate the preset called `name`; returns whether there is one
+    pub fn select_name(&mut self, name: &str) -> bool {
+        match self.presets.iter().position(|preset| preset.name == name) {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    /// Activate the built-in `view`, or the preset that replaced it
+    pub fn select_builtin(&mut self, view: V) -> bool {
+        match self.presets.iter().position(|preset| preset.slot == Some(view)) {
+            Some(index) => self.select(index),
+            None => false,
+        }
+    }
+
+    /// Activate the next (or previous) preset, wrapping around
+    pub fn cycle(&mut self, forward: bool) {
+        let count = self.presets.len();
+        self.active = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+    }
+}
+
+/// The picker's list of presets over the middle of the screen, `selected` highlighted
+pub fn render_view_picker<V>(f: &mut Frame, registry: &ViewRegistry<V>, selected: usize, theme: &Theme) {
+    let mut lines: Vec<Line> = registry
+        .presets
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            let marker = if i == registry.active { "*" } else { " " };
+            let text = format!(" {} {} ", marker, preset.name);
+            if i == selected {
+                Line::from(Span::styled(text, Style::default().fg(theme.accent).add_modifier(Modifier::REVERSED)))
+            } else {
+                Line::from(text)
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Enter switches, Esc closes",
+        Style::default().fg(theme.muted),
+    )));
+
+    let content_width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let area = centered_rect(content_width + 4, lines.len() as u16 + 2, f.area());
+    let picker = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Views")
+            .style(Style::default().fg(theme.text)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(picker, area);
+}
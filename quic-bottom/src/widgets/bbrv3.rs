@@ -0,0 +1,197 @@
+//! BBRv3 state over time
+//!
+//! Charts for the BBRv3 view: both bandwidth estimates, the pacing and cwnd
+//! gains as steps, and the bufferbloat factor against its 0.1 target. Until a
+//! few samples have arrived the view keeps its text panels instead.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType},
+    Frame,
+};
+
+use crate::metrics::{format_value, BBRv3Metrics, BBRv3TimeSeries};
+use crate::theme::Theme;
+
+/// Samples needed before the charts replace the text panels
+const MIN_HISTORY: usize = 3;
+
+/// Bufferbloat factor BBRv3 aims to stay under
+const BUFFERBLOAT_TARGET: f64 = 0.1;
+
+/// Charts of the BBRv3 time series
+pub struct BBRv3GraphsWidget {
+    series: BBRv3TimeSeries,
+}
+
+impl BBRv3GraphsWidget {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            series: BBRv3TimeSeries::new(max_points),
+        }
+    }
+
+    /// Record a sample; ignored when the sender does not report BBRv3 state
+    pub fn add(&mut self, metrics: &BBRv3Metrics) {
+        if metrics.is_reported() {
+            self.series.add_data_point(metrics);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+
+    /// Enough samples to draw lines rather than single points
+    pub fn has_history(&self) -> bool {
+        self.series.len() >= MIN_HISTORY
+    }
+
+    /// Fast and slow bandwidth estimates in Mbps
+    pub fn render_bandwidth(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let fast = points(&self.series.bw_fast, 1e-6);
+        let slow = points(&self.series.bw_slow, 1e-6);
+        let title = format!(
+            "Bandwidth Estimates - fast {} / slow {} Mbps",
+            latest(&fast, 2),
+            latest(&slow, 2)
+        );
+
+        let datasets = vec![
+            line("fast", &fast, theme.primary, GraphType::Line),
+            line("slow", &slow, theme.secondary, GraphType::Line),
+        ];
+        self.render_chart(f, area, title, datasets, [&fast, &slow], "Mbps", theme);
+    }
+
+    /// Pacing and cwnd gains, drawn as steps since BBRv3 switches them per phase
+    pub fn render_gains(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let pacing = points(&self.series.pacing_gain, 1.0);
+        let cwnd = points(&self.series.cwnd_gain, 1.0);
+        let title = format!(
+            "Pacing/CWND Gains - pacing {}x / cwnd {}x",
+            latest(&pacing, 2),
+            latest(&cwnd, 2)
+        );
+
+        let (pacing_steps, cwnd_steps) = (steps(&pacing), steps(&cwnd));
+        let datasets = vec![
+            line("pacing", &pacing_steps, theme.info, GraphType::Line),
+            line("cwnd", &cwnd_steps, theme.accent, GraphType::Line),
+        ];
+        self.render_chart(f, area, title, datasets, [&pacing, &cwnd], "gain", theme);
+    }
+
+    /// Bufferbloat factor with the target as a flat line
+    pub fn render_bufferbloat(&self, f: &mut Frame, area: Rect, stability_index: Option<f64>, theme: &Theme) {
+        let factor = points(&self.series.bufferbloat_factor, 1.0);
+        let end = self.series.len().saturating_sub(1) as f64;
+        let target = vec![(0.0, BUFFERBLOAT_TARGET), (end, BUFFERBLOAT_TARGET)];
+        let title = format!(
+            "Bufferbloat - {} (target < {}), stability {}",
+            latest(&factor, 3),
+            BUFFERBLOAT_TARGET,
+            stability_index.map_or("N/A".to_string(), |v| format_value(v, 2))
+        );
+
+        let color = match factor.last() {
+            Some(&(_, v)) if v >= 0.3 => theme.error,
+            Some(&(_, v)) if v >= BUFFERBLOAT_TARGET => theme.warning,
+            _ => theme.success,
+        };
+        let datasets = vec![
+            line("factor", &factor, color, GraphType::Line),
+            line("target", &target, theme.muted, GraphType::Line),
+        ];
+        self.render_chart(f, area, title, datasets, [&factor, &target], "", theme);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_chart(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        title: String,
+        datasets: Vec<Dataset>,
+        series: [&[(f64, f64)]; 2],
+        unit: &str,
+        theme: &Theme,
+    ) {
+        let block = Block::default().borders(Borders::ALL).title(title);
+        let Some(y_bounds) = bounds(series.iter().flat_map(|s| s.iter().map(|&(_, y)| y))) else {
+            f.render_widget(block, area);
+            return;
+        };
+        let x_bounds = [0.0, self.series.len().saturating_sub(1).max(1) as f64];
+
+        let label_style = Style::default().fg(theme.muted);
+        let labels: Vec<Span> = [y_bounds[0], (y_bounds[0] + y_bounds[1]) / 2.0, y_bounds[1]]
+            .into_iter()
+            .map(|v| Span::styled(format_value(v, 2), label_style))
+            .collect();
+        let chart = Chart::new(datasets)
+            .block(block)
+            .x_axis(Axis::default().bounds(x_bounds))
+            .y_axis(
+                Axis::default()
+                    .title(Span::styled(unit.to_string(), label_style))
+                    .bounds(y_bounds)
+                    .labels(labels),
+            );
+        f.render_widget(chart, area);
+    }
+}
+
+fn line<'a>(name: &'static str, data: &'a [(f64, f64)], color: Color, kind: GraphType) -> Dataset<'a> {
+    Dataset::default()
+        .name(name)
+        .data(data)
+        .style(Style::default().fg(color))
+        .graph_type(kind)
+        .marker(Marker::Braille)
+}
+
+/// Finite values of `series` scaled by `scale`, indexed by sample
+fn points(series: &std::collections::VecDeque<f64>, scale: f64) -> Vec<(f64, f64)> {
+    series
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(i, v)| (i as f64, v * scale))
+        .collect()
+}
+
+/// Hold each value until the next sample so changes show as vertical edges
+fn steps(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut out = Vec::with_capacity(points.len() * 2);
+    for window in points.windows(2) {
+        let ((x0, y0), (x1, _)) = (window[0], window[1]);
+        out.push((x0, y0));
+        out.push((x1, y0));
+    }
+    out.extend(points.last().copied());
+    out
+}
+
+fn latest(points: &[(f64, f64)], precision: usize) -> String {
+    points
+        .last()
+        .map_or("N/A".to_string(), |&(_, v)| format_value(v, precision))
+}
+
+/// Min/max of `values` with 5% padding, widened when all values are equal
+fn bounds(values: impl Iterator<Item = f64>) -> Option<[f64; 2]> {
+    let (min, max) = values.fold(None, |acc: Option<(f64, f64)>, v| match acc {
+        Some((min, max)) => Some((min.min(v), max.max(v))),
+        None => Some((v, v)),
+    })?;
+    let pad = if max > min {
+        (max - min) * 0.05
+    } else {
+        min.abs().max(1.0) * 0.05
+    };
+    Some([min - pad, max + pad])
+}
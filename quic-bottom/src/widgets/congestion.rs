@@ -0,0 +1,183 @@
+//! Congestion window over time
+//!
+//! Plots the sender's cwnd and, when it uses BBRv3, its inflight target on
+//! a shared KB axis. Zeros reported before the connection ramps up stay on
+//! the chart but are left out of the axis range and the stats, so they do
+//! not flatten the interesting part of the line.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect, Size},
+    style::Style,
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+use crate::improved_layout::{MinSize, Presentation};
+use crate::metrics::{format_value, MetricsHistory};
+use crate::theme::Theme;
+
+/// Smallest y-axis range, in KB, while there is nothing above zero to show
+const MIN_AXIS_KB: f64 = 16.0;
+
+/// Congestion window and inflight target, in KB
+pub struct CongestionWindowWidget {
+    cwnd: MetricsHistory<f64>,
+    /// NaN where the sample had no target
+    inflight_target: MetricsHistory<f64>,
+}
+
+impl CongestionWindowWidget {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            cwnd: MetricsHistory::new(max_points),
+            inflight_target: MetricsHistory::new(max_points),
+        }
+    }
+
+    /// Record a sample; both values in bytes
+    pub fn update(&mut self, cwnd: f64, inflight_target: Option<f64>) {
+        self.cwnd.push(cwnd / 1024.0);
+        self.inflight_target
+            .push(inflight_target.map_or(f64::NAN, |target| target / 1024.0));
+    }
+
+    pub fn clear(&mut self) {
+        self.cwnd.clear();
+        self.inflight_target.clear();
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_as(f, area, Presentation::fitting(self, area), theme);
+    }
+
+    /// Compact leaves out the stats line; minimal is the stats line alone
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        if presentation == Presentation::Minimal {
+            let text = if self.cwnd.is_empty() {
+                "cwnd: waiting for data...".to_string()
+            } else {
+                format!("cwnd (KB) {}", self.stats_line())
+            };
+            f.render_widget(Paragraph::new(text).style(Style::default().fg(theme.info)), area);
+            return;
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Congestion Window (KB)");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        if self.cwnd.is_empty() {
+            f.render_widget(
+                Paragraph::new("Waiting for data...").style(Style::default().fg(theme.muted)),
+                inner,
+            );
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(u16::from(presentation == Presentation::Full)),
+            ])
+            .split(inner);
+
+        let cwnd = points(&self.cwnd);
+        let target = points(&self.inflight_target);
+        let mut datasets = vec![Dataset::default()
+            .name("cwnd")
+            .data(&cwnd)
+            .style(Style::default().fg(theme.primary))
+            .graph_type(GraphType::Line)
+            .marker(Marker::Braille)];
+        if !target.is_empty() {
+            datasets.push(
+                Dataset::default()
+                    .name("inflight target")
+                    .data(&target)
+                    .style(Style::default().fg(theme.accent))
+                    .graph_type(GraphType::Line)
+                    .marker(Marker::Braille),
+            );
+        }
+
+        let top = cwnd
+            .iter()
+            .chain(&target)
+            .map(|&(_, kb)| kb)
+            .fold(0.0f64, f64::max);
+        let y_max = if top > 0.0 { top * 1.1 } else { MIN_AXIS_KB };
+        let x_max = (self.cwnd.len().saturating_sub(1)).max(1) as f64;
+        let label_style = Style::default().fg(theme.muted);
+        let labels: Vec<Span> = [0.0, y_max / 2.0, y_max]
+            .into_iter()
+            .map(|kb| Span::styled(format_value(kb, 0), label_style))
+            .collect();
+        let chart = Chart::new(datasets)
+            .x_axis(Axis::default().bounds([0.0, x_max]))
+            .y_axis(Axis::default().bounds([0.0, y_max]).labels(labels));
+        f.render_widget(chart, chunks[0]);
+
+        f.render_widget(
+            Paragraph::new(self.stats_line()).style(Style::default().fg(theme.info)),
+            chunks[1],
+        );
+    }
+
+    /// Current/avg/max of the cwnd and how it compares to the target
+    fn stats_line(&self) -> String {
+        let ramped: Vec<f64> = self
+            .cwnd
+            .iter()
+            .copied()
+            .filter(|kb| kb.is_finite() && *kb > 0.0)
+            .collect();
+        let avg = if ramped.is_empty() {
+            f64::NAN
+        } else {
+            ramped.iter().sum::<f64>() / ramped.len() as f64
+        };
+        let max = ramped.iter().copied().fold(f64::NAN, f64::max);
+        let current = self.cwnd.latest().copied().unwrap_or(f64::NAN);
+
+        let mut line = format!(
+            "Current: {} | Avg: {} | Max: {}",
+            format_value(current, 1),
+            format_value(avg, 1),
+            format_value(max, 1)
+        );
+        let target = self.inflight_target.latest().copied().unwrap_or(f64::NAN);
+        if target.is_finite() {
+            line.push_str(&format!(" | Target: {}", format_value(target, 1)));
+            if target > 0.0 {
+                line.push_str(&format!(" | cwnd/target: {}x", format_value(current / target, 2)));
+            }
+        }
+        line
+    }
+}
+
+impl MinSize for CongestionWindowWidget {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        match presentation {
+            // Borders, three chart rows and the stats line
+            Presentation::Full => Size::new(20, 6),
+            Presentation::Compact => Size::new(20, 5),
+            Presentation::Minimal => Size::new(0, 1),
+        }
+    }
+}
+
+/// Finite values indexed by sample
+fn points(series: &MetricsHistory<f64>) -> Vec<(f64, f64)> {
+    series
+        .iter()
+        .enumerate()
+        .filter(|(_, kb)| kb.is_finite())
+        .map(|(i, &kb)| (i as f64, kb))
+        .collect()
+}
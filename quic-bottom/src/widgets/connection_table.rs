@@ -0,0 +1,189 @@
+//! Per-connection table widget
+//!
+//! One row per tracked QUIC connection, sortable by any column. A row can
+//! be selected to pin its connection for tracing; the pinned one is marked.
+
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Cell, Row, Table},
+    Frame,
+};
+use std::cmp::Ordering;
+
+use crate::metrics::{format_value, ConnectionTracker, TrackedConnection};
+use crate::theme::Theme;
+use crate::units::format_rate;
+
+/// Column the table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionSortColumn {
+    Id,
+    Rtt,
+    Throughput,
+    Loss,
+    Retransmits,
+}
+
+impl ConnectionSortColumn {
+    const ALL: [ConnectionSortColumn; 5] = [
+        ConnectionSortColumn::Id,
+        ConnectionSortColumn::Rtt,
+        ConnectionSortColumn::Throughput,
+        ConnectionSortColumn::Loss,
+        ConnectionSortColumn::Retransmits,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            ConnectionSortColumn::Id => "Connection",
+            ConnectionSortColumn::Rtt => "RTT (ms)",
+            ConnectionSortColumn::Throughput => "Throughput",
+            ConnectionSortColumn::Loss => "Loss %",
+            ConnectionSortColumn::Retransmits => "Retrans",
+        }
+    }
+
+    fn compare(self, a: (&String, &TrackedConnection), b: (&String, &TrackedConnection)) -> Ordering {
+        let (a_id, a) = a;
+        let (b_id, b) = b;
+        match self {
+            ConnectionSortColumn::Id => a_id.cmp(b_id),
+            ConnectionSortColumn::Rtt => a.latest.latency.total_cmp(&b.latest.latency),
+            ConnectionSortColumn::Throughput => a.latest.throughput.total_cmp(&b.latest.throughput),
+            ConnectionSortColumn::Loss => a.latest.packet_loss.total_cmp(&b.latest.packet_loss),
+            ConnectionSortColumn::Retransmits => a.latest.retransmits.cmp(&b.latest.retransmits),
+        }
+    }
+}
+
+/// QUIC Connection Table Widget - current RTT, throughput, loss and retransmits per connection
+pub struct QUICConnectionTableWidget {
+    sort_column: ConnectionSortColumn,
+    descending: bool,
+    /// Selected connection, kept by id so it stays put when rows reorder
+    selected: Option<String>,
+}
+
+impl QUICConnectionTableWidget {
+    pub fn new() -> Self {
+        Self {
+            sort_column: ConnectionSortColumn::Id,
+            descending: false,
+            selected: None,
+        }
+    }
+
+    /// Rows in display order
+    fn sorted<'a>(&self, tracker: &'a ConnectionTracker) -> Vec<(&'a String, &'a TrackedConnection)> {
+        let mut rows: Vec<(&String, &TrackedConnection)> = tracker.iter().collect();
+        rows.sort_by(|a, b| {
+            let ordering = self.sort_column.compare(*a, *b);
+            if self.descending { ordering.reverse() } else { ordering }
+        });
+        rows
+    }
+
+    /// Move the selection `delta` rows, selecting the first row when none is
+    pub fn move_selection(&mut self, tracker: &ConnectionTracker, delta: isize) {
+        let rows = self.sorted(tracker);
+        if rows.is_empty() {
+            self.selected = None;
+            return;
+        }
+        let index = match self.selected_index(&rows) {
+            Some(index) => index.saturating_add_signed(delta).min(rows.len() - 1),
+            None => 0,
+        };
+        self.selected = Some(rows[index].0.clone());
+    }
+
+    fn selected_index(&self, rows: &[(&String, &TrackedConnection)]) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        rows.iter().position(|(id, _)| *id == selected)
+    }
+
+    /// The selected connection, while it is still tracked
+    pub fn selected(&self, tracker: &ConnectionTracker) -> Option<String> {
+        self.selected.clone().filter(|id| tracker.get(id).is_some())
+    }
+
+    /// Sort by the next column
+    pub fn next_sort_column(&mut self) {
+        let index = ConnectionSortColumn::ALL
+            .iter()
+            .position(|c| *c == self.sort_column)
+            .unwrap_or(0);
+        self.sort_column = ConnectionSortColumn::ALL[(index + 1) % ConnectionSortColumn::ALL.len()];
+    }
+
+    /// Flip between ascending and descending order
+    pub fn toggle_sort_order(&mut self) {
+        self.descending = !self.descending;
+    }
+
+    pub fn sort_column(&self) -> ConnectionSortColumn {
+        self.sort_column
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, tracker: &ConnectionTracker, theme: &Theme) {
+        let rows = self.sorted(tracker);
+        let pinned = tracker.pinned().map(|trace| trace.connection_id());
+
+        let arrow = if self.descending { " ▼" } else { " ▲" };
+        let header = Row::new(ConnectionSortColumn::ALL.iter().map(|column| {
+            let mut title = column.title().to_string();
+            if *column == self.sort_column {
+                title.push_str(arrow);
+            }
+            Cell::from(title)
+        }))
+        .style(theme.title(theme.accent));
+
+        let rows = rows.into_iter().map(|(id, connection)| {
+            let mut style = if tracker.is_stale(connection) {
+                Style::default().fg(theme.muted)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            if self.selected.as_ref() == Some(id) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let label = if pinned == Some(id.as_str()) { format!("* {}", id) } else { id.clone() };
+            let latest = &connection.latest;
+            Row::new(vec![
+                Cell::from(label),
+                Cell::from(format_value(latest.latency, 2)),
+                Cell::from(format_rate(latest.throughput, 2)),
+                Cell::from(format_value(latest.packet_loss * 100.0, 2)),
+                Cell::from(latest.retransmits.to_string()),
+            ])
+            .style(style)
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(28),
+                Constraint::Percentage(18),
+                Constraint::Percentage(20),
+                Constraint::Percentage(16),
+                Constraint::Percentage(18),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Connections ({}) - 'o' sort, 'O' reverse, 'T' trace", tracker.len())),
+        );
+
+        f.render_widget(table, area);
+    }
+}
+
+impl Default for QUICConnectionTableWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
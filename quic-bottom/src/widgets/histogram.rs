@@ -0,0 +1,169 @@
+//! Distribution of samples as a horizontal bar chart
+//!
+//! Buckets span the data's own range, one row each, labelled with the range
+//! and count. Rows holding p50/p95/p99 carry a marker, so the tail is visible
+//! next to the bulk of the samples.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::metrics::{calculate_latency_percentiles, finite_values, format_value};
+use crate::theme::Theme;
+
+/// One bucket of a histogram, covering `[start, end)`; the last one includes `end`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+/// Sort finite `values` into at most `bins` equal-width buckets over their range
+///
+/// All-identical values give a single zero-width bucket.
+pub fn bin(values: &[f64], bins: usize) -> Vec<Bucket> {
+    let values = finite_values(values);
+    let Some((min, max)) = range(&values) else {
+        return Vec::new();
+    };
+    if max == min {
+        return vec![Bucket {
+            start: min,
+            end: max,
+            count: values.len(),
+        }];
+    }
+
+    let bins = bins.max(1);
+    let width = (max - min) / bins as f64;
+    let mut buckets: Vec<Bucket> = (0..bins)
+        .map(|i| Bucket {
+            start: min + width * i as f64,
+            end: if i + 1 == bins { max } else { min + width * (i + 1) as f64 },
+            count: 0,
+        })
+        .collect();
+    for value in values {
+        buckets[bucket_index(value, min, width, bins)].count += 1;
+    }
+    buckets
+}
+
+fn range(values: &[f64]) -> Option<(f64, f64)> {
+    values.iter().fold(None, |acc, &v| match acc {
+        Some((min, max)) => Some((f64::min(min, v), f64::max(max, v))),
+        None => Some((v, v)),
+    })
+}
+
+fn bucket_index(value: f64, min: f64, width: f64, bins: usize) -> usize {
+    (((value - min) / width) as usize).min(bins - 1)
+}
+
+/// Horizontal bar chart of a sample distribution
+pub struct HistogramWidget {
+    title: String,
+    unit: &'static str,
+    bins: usize,
+    /// Bar color; the theme's info color unless set
+    color: Option<Color>,
+}
+
+impl HistogramWidget {
+    pub fn new(title: impl Into<String>, bins: usize) -> Self {
+        Self {
+            title: title.into(),
+            unit: "",
+            bins: bins.max(1),
+            color: None,
+        }
+    }
+
+    /// Unit shown after the bucket ranges, e.g. `ms`
+    pub fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Draw `samples` into `area`, using fewer buckets when it has fewer rows
+    pub fn render(&self, f: &mut Frame, area: Rect, samples: &[f64], theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL).title(self.title.as_str());
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let buckets = bin(samples, self.bins.min(inner.height.max(1) as usize));
+        if buckets.is_empty() {
+            f.render_widget(
+                Paragraph::new("No data yet...").style(Style::default().fg(theme.muted)),
+                inner,
+            );
+            return;
+        }
+
+        let (p50, p95, p99) = calculate_latency_percentiles(samples);
+        let markers = [("p50", p50, theme.success), ("p95", p95, theme.warning), ("p99", p99, theme.error)];
+        let min = buckets[0].start;
+        let width = buckets[0].end - buckets[0].start;
+        let marked_bucket = |value: f64| {
+            if width > 0.0 {
+                bucket_index(value, min, width, buckets.len())
+            } else {
+                0
+            }
+        };
+
+        let labels: Vec<String> = buckets
+            .iter()
+            .map(|b| {
+                format!(
+                    "{}-{}{}",
+                    format_value(b.start, 1),
+                    format_value(b.end, 1),
+                    self.unit
+                )
+            })
+            .collect();
+        let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+        let count_width = buckets.iter().map(|b| b.count.to_string().len()).max().unwrap_or(1);
+        // Room left for the bar after the label, count and up to three markers
+        let bar_room = (inner.width as usize).saturating_sub(label_width + count_width + 15);
+        let max_count = buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1);
+
+        let lines: Vec<Line> = buckets
+            .iter()
+            .zip(&labels)
+            .enumerate()
+            .map(|(i, (bucket, label))| {
+                let bar_len = (bucket.count * bar_room).div_ceil(max_count);
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{:>width$} ", label, width = label_width),
+                        Style::default().fg(theme.muted),
+                    ),
+                    Span::styled("█".repeat(bar_len), Style::default().fg(self.color.unwrap_or(theme.info))),
+                    Span::raw(format!(" {}", bucket.count)),
+                ];
+                for (name, value, color) in markers {
+                    if marked_bucket(value) == i {
+                        spans.push(Span::styled(
+                            format!(" ◀{}", name),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
+                        ));
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), inner);
+    }
+}
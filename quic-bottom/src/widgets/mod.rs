@@ -3,39 +3,69 @@
 //! Adapted from bottom's widget system for QUIC protocol monitoring
 
 use ratatui::{
-    backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect, Size},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, Paragraph, Sparkline},
+    widgets::{Block, Borders, Gauge, Paragraph},
     Frame,
 };
+use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
+use std::time::Duration;
 
-use crate::metrics::{QUICMetrics, calculate_latency_percentiles, calculate_jitter};
+use crate::improved_layout::{MinSize, Presentation};
+use crate::metrics::{
+    calculate_latency_percentiles, calculate_jitter, finite_values, format_age, format_span, format_value,
+    ConnectionStats, DerivedMetrics, Freshness, HandshakeStats, MetricsHistory,
+};
+use crate::health_score::{HealthLevel, HealthScore};
+use crate::sla::{SlaStatus, SlaVerdict};
+use crate::theme::Theme;
+use crate::units::format_rate;
+
+mod bbrv3;
+mod congestion;
+mod connection_table;
+mod histogram;
+mod sources;
+mod sparkline;
+mod trace;
+
+pub use bbrv3::BBRv3GraphsWidget;
+pub use congestion::CongestionWindowWidget;
+pub use connection_table::{ConnectionSortColumn, QUICConnectionTableWidget};
+pub use histogram::{bin, Bucket, HistogramWidget};
+pub use sources::{source_color, LatencyOverlayWidget};
+pub use sparkline::ScaledSeries;
+pub use trace::TracePanel;
+
+/// Buckets in the latency and handshake time histograms
+pub const HISTOGRAM_BINS: usize = 10;
 
 /// QUIC Latency Widget - displays RTT, jitter, and percentiles
 pub struct QUICLatencyWidget {
-    data: VecDeque<f64>,
-    max_points: usize,
+    data: MetricsHistory<f64>,
+    /// Draw the distribution instead of the sparkline
+    show_histogram: bool,
 }
 
 impl QUICLatencyWidget {
     pub fn new(max_points: usize) -> Self {
         Self {
-            data: VecDeque::with_capacity(max_points),
-            max_points,
+            data: MetricsHistory::new(max_points),
+            show_histogram: false,
         }
     }
 
+    pub fn set_histogram(&mut self, show: bool) {
+        self.show_histogram = show;
+    }
+
     pub fn update(&mut self, latency: f64) {
-        self.data.push_back(latency);
-        if self.data.len() > self.max_points {
-            self.data.pop_front();
-        }
+        self.data.push(latency);
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -47,17 +77,24 @@ impl QUICLatencyWidget {
 
         // Title
         let title = Paragraph::new("QUIC Latency (ms)")
-            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            .style(theme.title(theme.primary))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
-        // Sparkline graph
-        if !self.data.is_empty() {
-            let sparkline = Sparkline::default()
-                .data(&self.data.iter().map(|&x| x as u64).collect::<Vec<u64>>())
-                .style(Style::default().fg(Color::Green))
-                .block(Block::default().borders(Borders::ALL).title("Latency Graph"));
-            f.render_widget(sparkline, chunks[2]);
+        // Sparkline graph or distribution
+        if self.show_histogram {
+            let samples: Vec<f64> = self.data.iter().copied().collect();
+            HistogramWidget::new("Latency Distribution", HISTOGRAM_BINS)
+                .with_unit("ms")
+                .with_color(theme.primary)
+                .render(f, chunks[1], &samples, theme);
+        } else if !self.data.is_empty() {
+            let series = ScaledSeries::new(self.data.iter().copied());
+            let sparkline = series
+                .sparkline()
+                .style(Style::default().fg(theme.primary))
+                .block(Block::default().borders(Borders::ALL).title(series.title("Latency", 2, "ms")));
+            f.render_widget(sparkline, chunks[1]);
         }
 
         // Stats
@@ -65,15 +102,16 @@ impl QUICLatencyWidget {
             let data_vec: Vec<f64> = self.data.iter().cloned().collect();
             let (p50, p95, p99) = calculate_latency_percentiles(&data_vec);
             let jitter = calculate_jitter(&data_vec);
-            let current = self.data.back().unwrap_or(&0.0);
+            let current = self.data.latest().unwrap_or(&0.0);
             
             let stats_text = format!(
-                "Current: {:.2}ms | P50: {:.2}ms | P95: {:.2}ms | P99: {:.2}ms | Jitter: {:.2}ms",
-                current, p50, p95, p99, jitter
+                "Current: {}ms | P50: {}ms | P95: {}ms | P99: {}ms | Jitter: {}ms",
+                format_value(*current, 2), format_value(p50, 2), format_value(p95, 2),
+                format_value(p99, 2), format_value(jitter, 2)
             );
             
             let stats = Paragraph::new(stats_text)
-                .style(Style::default().fg(Color::Cyan))
+                .style(Style::default().fg(theme.info))
                 .block(Block::default().borders(Borders::NONE));
             f.render_widget(stats, chunks[2]);
         }
@@ -82,26 +120,21 @@ impl QUICLatencyWidget {
 
 /// QUIC Throughput Widget - displays bandwidth and packet rates
 pub struct QUICThroughputWidget {
-    data: VecDeque<f64>,
-    max_points: usize,
+    data: MetricsHistory<f64>,
 }
 
 impl QUICThroughputWidget {
     pub fn new(max_points: usize) -> Self {
         Self {
-            data: VecDeque::with_capacity(max_points),
-            max_points,
+            data: MetricsHistory::new(max_points),
         }
     }
 
     pub fn update(&mut self, throughput: f64) {
-        self.data.push_back(throughput);
-        if self.data.len() > self.max_points {
-            self.data.pop_front();
-        }
+        self.data.push(throughput);
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -112,33 +145,39 @@ impl QUICThroughputWidget {
             .split(area);
 
         // Title
-        let title = Paragraph::new("QUIC Throughput (KB/s)")
-            .style(Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD))
+        let title = Paragraph::new("QUIC Throughput")
+            .style(theme.title(theme.primary))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
         // Sparkline graph
         if !self.data.is_empty() {
-            let sparkline = Sparkline::default()
-                .data(&self.data.iter().map(|&x| x as u64).collect::<Vec<u64>>())
-                .style(Style::default().fg(Color::Magenta))
-                .block(Block::default().borders(Borders::NONE));
+            let series = ScaledSeries::new(self.data.iter().copied());
+            let sparkline = series
+                .sparkline()
+                .style(Style::default().fg(theme.secondary))
+                .block(Block::default().borders(Borders::NONE).title(series.rate_title("Throughput")));
             f.render_widget(sparkline, chunks[1]);
         }
 
         // Stats
         if !self.data.is_empty() {
-            let current = self.data.back().unwrap_or(&0.0);
-            let avg = self.data.iter().sum::<f64>() / self.data.len() as f64;
-            let max = self.data.iter().fold(0.0f64, |a, &b| a.max(b));
+            let current = self.data.latest().copied().unwrap_or(0.0);
+            let values = finite_values(&self.data);
+            let avg = if values.is_empty() {
+                f64::NAN
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            };
+            let max = values.iter().fold(0.0f64, |a, &b| a.max(b));
             
             let stats_text = format!(
-                "Current: {:.2} KB/s | Avg: {:.2} KB/s | Max: {:.2} KB/s",
-                current, avg, max
+                "Current: {} | Avg: {} | Max: {}",
+                format_rate(current, 2), format_rate(avg, 2), format_rate(max, 2)
             );
             
             let stats = Paragraph::new(stats_text)
-                .style(Style::default().fg(Color::Cyan))
+                .style(Style::default().fg(theme.info))
                 .block(Block::default().borders(Borders::NONE));
             f.render_widget(stats, chunks[2]);
         }
@@ -147,26 +186,61 @@ impl QUICThroughputWidget {
 
 /// QUIC Connection Status Widget - displays connection statistics
 pub struct QUICConnectionWidget {
-    active_connections: i32,
-    failed_connections: i32,
-    total_connections: i32,
+    /// The `connections` gauge of the last sample
+    connections: i32,
+    errors: i32,
+    /// From the `connections_*` counters; empty when the sender has none
+    lifecycle: ConnectionStats,
     handshake_times: VecDeque<f64>,
+    /// The sender stopped reporting, so the counts above are out of date
+    stale: bool,
+    /// Draw the handshake time distribution instead of the sparkline
+    show_histogram: bool,
+    handshakes: HandshakeStats,
+}
+
+impl Default for QUICConnectionWidget {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl QUICConnectionWidget {
     pub fn new() -> Self {
         Self {
-            active_connections: 0,
-            failed_connections: 0,
-            total_connections: 0,
+            connections: 0,
+            errors: 0,
+            lifecycle: ConnectionStats::default(),
             handshake_times: VecDeque::with_capacity(100),
+            stale: false,
+            show_histogram: false,
+            handshakes: HandshakeStats::default(),
         }
     }
 
-    pub fn update(&mut self, active: i32, failed: i32, total: i32) {
-        self.active_connections = active;
-        self.failed_connections = failed;
-        self.total_connections = total;
+    /// Gauges of the last sample, shown for senders without lifecycle counters
+    pub fn update(&mut self, connections: i32, errors: i32) {
+        self.connections = connections;
+        self.errors = errors;
+    }
+
+    /// Lifecycle counts and success rate, e.g. from `MetricsHandle::connection_stats`
+    pub fn set_lifecycle(&mut self, stats: ConnectionStats) {
+        self.lifecycle = stats;
+    }
+
+    /// Show the connection counts as stale instead of live
+    pub fn set_stale(&mut self, stale: bool) {
+        self.stale = stale;
+    }
+
+    pub fn set_histogram(&mut self, show: bool) {
+        self.show_histogram = show;
+    }
+
+    /// 0-RTT/1-RTT counts to show, e.g. from `MetricsHandle::handshake_stats`
+    pub fn set_handshake_stats(&mut self, stats: HandshakeStats) {
+        self.handshakes = stats;
     }
 
     pub fn add_handshake_time(&mut self, time: f64) {
@@ -176,7 +250,7 @@ impl QUICConnectionWidget {
         }
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -184,91 +258,227 @@ impl QUICConnectionWidget {
                 Constraint::Length(3), // Active connections
                 Constraint::Length(3), // Failed connections
                 Constraint::Length(3), // Success rate
+                Constraint::Length(2), // 0-RTT/1-RTT breakdown
                 Constraint::Min(0),    // Handshake times
             ])
             .split(area);
 
         // Title
         let title = Paragraph::new("QUIC Connections")
-            .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+            .style(theme.title(theme.primary))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
-        // Active connections
-        let active_text = format!("Active: {}", self.active_connections);
-        let active_style = if self.active_connections > 0 {
-            Style::default().fg(Color::Green)
+        // Active connections: derived from the counters when the sender has
+        // them, otherwise its `connections` gauge
+        let active = self.lifecycle.active.map_or(i64::from(self.connections), |n| n as i64);
+        let active_text = if self.stale {
+            "Active: STALE (no recent updates)".to_string()
         } else {
-            Style::default().fg(Color::Red)
+            format!("Active: {}", active)
+        };
+        let active_style = if self.stale {
+            Style::default().fg(theme.error).add_modifier(Modifier::BOLD)
+        } else if active > 0 {
+            Style::default().fg(theme.success)
+        } else {
+            Style::default().fg(theme.error)
         };
         let active = Paragraph::new(active_text)
             .style(active_style)
             .block(Block::default().borders(Borders::NONE));
         f.render_widget(active, chunks[1]);
 
-        // Failed connections
-        let failed_text = format!("Failed: {}", self.failed_connections);
-        let failed_style = if self.failed_connections > 0 {
-            Style::default().fg(Color::Red)
+        // Opened and failed attempts, or only the error count without counters
+        let count = |n: Option<u64>| n.map_or("N/A".to_string(), |n| n.to_string());
+        let (failed_text, failing) = if self.lifecycle.is_reported() {
+            let text = format!(
+                "Opened: {} | Failed: {} | Closed: {}",
+                count(self.lifecycle.opened),
+                count(self.lifecycle.failed),
+                count(self.lifecycle.closed)
+            );
+            (text, self.lifecycle.failed.unwrap_or(0) > 0)
         } else {
-            Style::default().fg(Color::Green)
+            (format!("Errors: {}", self.errors), self.errors > 0)
+        };
+        let failed_style = if failing {
+            Style::default().fg(theme.error)
+        } else {
+            Style::default().fg(theme.success)
         };
         let failed = Paragraph::new(failed_text)
             .style(failed_style)
             .block(Block::default().borders(Borders::NONE));
         f.render_widget(failed, chunks[2]);
 
-        // Success rate
-        let success_rate = if self.total_connections > 0 {
-            (self.active_connections as f64 / self.total_connections as f64) * 100.0
-        } else {
-            0.0
-        };
-        let success_text = format!("Success Rate: {:.1}%", success_rate);
-        let success_style = if success_rate >= 95.0 {
-            Style::default().fg(Color::Green)
-        } else if success_rate >= 80.0 {
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default().fg(Color::Red)
+        // Success rate over the recent window
+        let window = format_span(Duration::from_secs(self.lifecycle.window_secs));
+        let (success_text, success_style) = match self.lifecycle.success_rate {
+            Some(rate) => {
+                let pct = rate * 100.0;
+                let style = if pct >= 95.0 {
+                    Style::default().fg(theme.success)
+                } else if pct >= 80.0 {
+                    Style::default().fg(theme.warning)
+                } else {
+                    Style::default().fg(theme.error)
+                };
+                let text = format!(
+                    "Success Rate: {}% of {} in {}",
+                    format_value(pct, 1),
+                    self.lifecycle.window_attempts,
+                    window
+                );
+                (text, style)
+            }
+            None if self.lifecycle.is_reported() => {
+                (format!("Success Rate: no attempts in {}", window), Style::default().fg(theme.muted))
+            }
+            None => ("Success Rate: N/A (no connection counters)".to_string(), Style::default().fg(theme.muted)),
         };
         let success = Paragraph::new(success_text)
             .style(success_style)
             .block(Block::default().borders(Borders::NONE));
         f.render_widget(success, chunks[3]);
 
-        // Handshake times sparkline
-        if !self.handshake_times.is_empty() && chunks.len() > 4 {
-            let sparkline = Sparkline::default()
-                .data(&self.handshake_times.iter().map(|&x| x as u64).collect::<Vec<u64>>())
-                .style(Style::default().fg(Color::Yellow))
-                .block(Block::default().borders(Borders::NONE));
-            f.render_widget(sparkline, chunks[4]);
+        // 0-RTT/1-RTT breakdown
+        let avg = |ms: Option<f64>| ms.map_or("N/A".to_string(), |ms| format!("{}ms", format_value(ms, 1)));
+        let breakdown_text = match self.handshakes.zero_rtt_ratio() {
+            Some(ratio) => format!(
+                "0-RTT: {}% of {} (avg {}) | 1-RTT avg {} | Resumed: {}",
+                format_value(ratio * 100.0, 1),
+                self.handshakes.total(),
+                avg(self.handshakes.avg_zero_rtt_ms()),
+                avg(self.handshakes.avg_one_rtt_ms()),
+                self.handshakes.resumed
+            ),
+            None => "0-RTT: no handshakes yet".to_string(),
+        };
+        let breakdown = Paragraph::new(breakdown_text).style(Style::default().fg(theme.info));
+        f.render_widget(breakdown, chunks[4]);
+
+        // Handshake times sparkline or distribution
+        if self.show_histogram {
+            let samples: Vec<f64> = self.handshake_times.iter().copied().collect();
+            HistogramWidget::new("Handshake Times", HISTOGRAM_BINS)
+                .with_unit("ms")
+                .with_color(theme.secondary)
+                .render(f, chunks[5], &samples, theme);
+        } else if !self.handshake_times.is_empty() && chunks.len() > 5 {
+            let series = ScaledSeries::new(self.handshake_times.iter().copied());
+            let sparkline = series
+                .sparkline()
+                .style(Style::default().fg(theme.secondary))
+                .block(Block::default().borders(Borders::NONE).title(series.title("Handshakes", 1, "ms")));
+            f.render_widget(sparkline, chunks[5]);
         }
     }
 }
 
+/// Header indicator such as `last update 0.3s ago`, colored by `freshness`
+pub fn last_update_span(age: Option<Duration>, freshness: Freshness, theme: &Theme) -> Span<'static> {
+    let text = match age {
+        Some(age) => format!("last update {} ago", format_age(age)),
+        None => "no updates yet".to_string(),
+    };
+    let style = match freshness {
+        Freshness::Waiting => Style::default().fg(theme.muted),
+        Freshness::Fresh => Style::default().fg(theme.success),
+        Freshness::Lagging => Style::default().fg(theme.warning),
+        Freshness::Stale => Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+    };
+    Span::styled(text, style)
+}
+
+/// Header badge for the SLA verdict followed by the violated targets, `None` without targets
+pub fn sla_span(verdict: &SlaVerdict, theme: &Theme) -> Option<Span<'static>> {
+    let badge = |color| Style::default().fg(color).add_modifier(Modifier::BOLD | Modifier::REVERSED);
+    let span = match verdict.status {
+        SlaStatus::Disabled => return None,
+        SlaStatus::Pass => Span::styled(" SLA PASS ", badge(theme.success)),
+        SlaStatus::NoData => Span::styled(" SLA NO DATA ", badge(theme.muted)),
+        SlaStatus::Fail => {
+            let violations: Vec<String> = verdict.violations().map(|check| check.to_string()).collect();
+            Span::styled(format!(" SLA FAIL: {} ", violations.join(", ")), badge(theme.error))
+        }
+    };
+    Some(span)
+}
+
+/// Header gauge for the health score, colored by its level
+fn health_gauge(score: &HealthScore, theme: &Theme) -> Gauge<'static> {
+    let color = match score.level {
+        HealthLevel::Good => theme.success,
+        HealthLevel::Fair => theme.warning,
+        HealthLevel::Poor => theme.error,
+        HealthLevel::NoData => theme.muted,
+    };
+    Gauge::default()
+        .gauge_style(Style::default().fg(color))
+        .ratio((score.score.unwrap_or(0.0) / 100.0).clamp(0.0, 1.0))
+        .label(format!("health {}", score.label()))
+}
+
+/// Columns of the health gauge in the header
+const HEALTH_GAUGE_WIDTH: u16 = 20;
+
+/// Bordered header with `line` on the left and the health gauge at the right
+/// end, which is left out when it would not leave room for the text
+pub fn render_header(f: &mut Frame, area: Rect, line: Line, health: &HealthScore, theme: &Theme) {
+    let block = Block::default().borders(Borders::ALL);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let text_area = if inner.width >= HEALTH_GAUGE_WIDTH * 3 {
+        let [text_area, gauge_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(HEALTH_GAUGE_WIDTH)]).areas(inner);
+        f.render_widget(health_gauge(health, theme), gauge_area);
+        text_area
+    } else {
+        inner
+    };
+    f.render_widget(Paragraph::new(line).style(theme.title(theme.text)), text_area);
+}
+
 /// QUIC Network Quality Widget - displays packet loss, retransmits, and congestion control
 pub struct QUICNetworkWidget {
     packet_loss: f64,
     retransmits: i32,
     congestion_control: String,
+    /// Bytes; 0 when the sender does not report it
+    congestion_window: i32,
+    /// Loss computed from packet counters, in percent
+    computed_loss: Option<f64>,
+    /// Percentage points the two losses may differ before being highlighted
+    loss_margin: f64,
     loss_data: VecDeque<f64>,
     retransmit_data: VecDeque<i32>,
 }
 
+impl Default for QUICNetworkWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QUICNetworkWidget {
     pub fn new() -> Self {
         Self {
             packet_loss: 0.0,
             retransmits: 0,
             congestion_control: "Unknown".to_string(),
+            congestion_window: 0,
+            computed_loss: None,
+            loss_margin: 1.0,
             loss_data: VecDeque::with_capacity(100),
             retransmit_data: VecDeque::with_capacity(100),
         }
     }
 
+    /// Add a sample, with `packet_loss` a fraction
     pub fn update(&mut self, packet_loss: f64, retransmits: i32, cc: String) {
+        let packet_loss = packet_loss * 100.0;
         self.packet_loss = packet_loss;
         self.retransmits = retransmits;
         self.congestion_control = cc;
@@ -285,7 +495,25 @@ impl QUICNetworkWidget {
         }
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn with_loss_margin(mut self, margin: f64) -> Self {
+        self.set_loss_margin(margin);
+        self
+    }
+
+    pub fn set_loss_margin(&mut self, margin: f64) {
+        self.loss_margin = margin;
+    }
+
+    pub fn set_congestion_window(&mut self, bytes: i32) {
+        self.congestion_window = bytes;
+    }
+
+    /// Loss from packet counters, shown next to the reported one
+    pub fn set_computed_loss(&mut self, loss: Option<f64>) {
+        self.computed_loss = loss;
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -299,32 +527,45 @@ impl QUICNetworkWidget {
 
         // Title
         let title = Paragraph::new("QUIC Network Quality")
-            .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            .style(theme.title(theme.primary))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
         // Packet loss
-        let loss_text = format!("Packet Loss: {:.2}%", self.packet_loss);
+        let loss_text = format!("Packet Loss: {}%", format_value(self.packet_loss, 2));
         let loss_style = if self.packet_loss < 1.0 {
-            Style::default().fg(Color::Green)
+            Style::default().fg(theme.success)
         } else if self.packet_loss < 5.0 {
-            Style::default().fg(Color::Yellow)
+            Style::default().fg(theme.warning)
         } else {
-            Style::default().fg(Color::Red)
+            Style::default().fg(theme.error)
         };
-        let loss = Paragraph::new(loss_text)
-            .style(loss_style)
-            .block(Block::default().borders(Borders::NONE));
+        let mut loss_lines = vec![Line::styled(loss_text, loss_style)];
+        if let Some(computed) = self.computed_loss {
+            let difference = (computed - self.packet_loss).abs();
+            let computed_text = format!("From counters: {}%", format_value(computed, 2));
+            loss_lines.push(if difference > self.loss_margin {
+                Line::styled(
+                    format!("{} - differs by {} pts", computed_text, format_value(difference, 2)),
+                    Style::default()
+                        .fg(theme.warning)
+                        .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                )
+            } else {
+                Line::styled(computed_text, Style::default().fg(theme.muted))
+            });
+        }
+        let loss = Paragraph::new(loss_lines).block(Block::default().borders(Borders::NONE));
         f.render_widget(loss, chunks[1]);
 
         // Retransmits
         let retrans_text = format!("Retransmits: {}", self.retransmits);
         let retrans_style = if self.retransmits < 10 {
-            Style::default().fg(Color::Green)
+            Style::default().fg(theme.success)
         } else if self.retransmits < 50 {
-            Style::default().fg(Color::Yellow)
+            Style::default().fg(theme.warning)
         } else {
-            Style::default().fg(Color::Red)
+            Style::default().fg(theme.error)
         };
         let retrans = Paragraph::new(retrans_text)
             .style(retrans_style)
@@ -332,9 +573,15 @@ impl QUICNetworkWidget {
         f.render_widget(retrans, chunks[2]);
 
         // Congestion control
-        let cc_text = format!("CC Algorithm: {}", self.congestion_control);
+        let mut cc_text = format!("CC Algorithm: {}", self.congestion_control);
+        if self.congestion_window > 0 {
+            cc_text.push_str(&format!(
+                "  cwnd: {} KB",
+                format_value(self.congestion_window as f64 / 1024.0, 1)
+            ));
+        }
         let cc = Paragraph::new(cc_text)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.info))
             .block(Block::default().borders(Borders::NONE));
         f.render_widget(cc, chunks[3]);
 
@@ -349,18 +596,216 @@ impl QUICNetworkWidget {
                 .split(chunks[4]);
 
             // Loss graph
-            let loss_sparkline = Sparkline::default()
-                .data(&self.loss_data.iter().map(|&x| x as u64).collect::<Vec<u64>>())
-                .style(Style::default().fg(Color::Red))
-                .block(Block::default().borders(Borders::NONE));
+            let loss = ScaledSeries::new(self.loss_data.iter().copied());
+            let loss_sparkline = loss
+                .sparkline()
+                .style(Style::default().fg(theme.error))
+                .block(Block::default().borders(Borders::NONE).title(loss.title("Loss", 2, "%")));
             f.render_widget(loss_sparkline, graph_chunks[0]);
 
             // Retransmit graph
-            let retrans_sparkline = Sparkline::default()
-                .data(&self.retransmit_data.iter().map(|&x| x as u64).collect::<Vec<u64>>())
-                .style(Style::default().fg(Color::Yellow))
-                .block(Block::default().borders(Borders::NONE));
+            let retrans = ScaledSeries::new(self.retransmit_data.iter().map(|&x| f64::from(x)));
+            let retrans_sparkline = retrans
+                .sparkline()
+                .style(Style::default().fg(theme.warning))
+                .block(Block::default().borders(Borders::NONE).title(retrans.title("Retransmits", 0, "")));
             f.render_widget(retrans_sparkline, graph_chunks[1]);
         }
     }
 }
+
+/// QUIC Streams Widget - open streams over time, open/close rates and throughput per stream
+pub struct QUICStreamsWidget {
+    counts: MetricsHistory<f64>,
+    /// Capture time and count of the previous sample
+    last: Option<(DateTime<Utc>, i32)>,
+    opened_per_sec: f64,
+    closed_per_sec: f64,
+    throughput_per_stream: Option<f64>,
+}
+
+impl QUICStreamsWidget {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            counts: MetricsHistory::new(max_points),
+            last: None,
+            opened_per_sec: 0.0,
+            closed_per_sec: 0.0,
+            throughput_per_stream: None,
+        }
+    }
+
+    /// Record the open stream count of a sample captured at `at`
+    ///
+    /// Pass the sample's own capture time: a sample shown again on a later
+    /// tick keeps its time and leaves the rates alone.
+    ///
+    /// Rates come from the net change since the previous, older sample. A
+    /// drop to zero is taken as the connection restarting rather than every
+    /// stream closing at once, and resets the rates.
+    pub fn update(&mut self, at: DateTime<Utc>, streams: i32, throughput: f64) {
+        let streams = streams.max(0);
+        self.counts.push(f64::from(streams));
+        self.throughput_per_stream = (streams > 0).then(|| throughput / f64::from(streams));
+
+        match self.last {
+            // Same sample seen again on the next tick
+            Some((prev_at, _)) if at <= prev_at => return,
+            Some((prev_at, prev)) if !(streams == 0 && prev > 0) => {
+                let secs = (at - prev_at).num_milliseconds().max(1) as f64 / 1000.0;
+                self.opened_per_sec = f64::from((streams - prev).max(0)) / secs;
+                self.closed_per_sec = f64::from((prev - streams).max(0)) / secs;
+            }
+            _ => {
+                self.opened_per_sec = 0.0;
+                self.closed_per_sec = 0.0;
+            }
+        }
+        self.last = Some((at, streams));
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.counts.capacity());
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        self.render_as(f, area, Presentation::fitting(self, area), theme);
+    }
+
+    /// Compact leaves out the sparkline; minimal is the count and rates on one line
+    pub fn render_as(&self, f: &mut Frame, area: Rect, presentation: Presentation, theme: &Theme) {
+        let current = self.counts.latest().copied().unwrap_or(0.0);
+        if presentation == Presentation::Minimal {
+            let text = format!(
+                "Streams: {} | Opened: {}/s | Closed: {}/s",
+                current,
+                format_value(self.opened_per_sec, 1),
+                format_value(self.closed_per_sec, 1)
+            );
+            f.render_widget(Paragraph::new(text).style(Style::default().fg(theme.info)), area);
+            return;
+        }
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Streams ({})", current));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Rates
+                Constraint::Min(0),    // Stream count sparkline
+            ])
+            .split(inner);
+
+        let stats_text = format!(
+            "Opened: {}/s | Closed: {}/s | Per stream: {}",
+            format_value(self.opened_per_sec, 1),
+            format_value(self.closed_per_sec, 1),
+            self.throughput_per_stream
+                .map_or("N/A".to_string(), |v| format_rate(v, 2))
+        );
+        let stats = Paragraph::new(stats_text).style(Style::default().fg(theme.info));
+        f.render_widget(stats, chunks[0]);
+
+        if presentation == Presentation::Full && !self.counts.is_empty() {
+            let sparkline = ScaledSeries::new(self.counts.iter().copied())
+                .sparkline()
+                .style(Style::default().fg(theme.primary));
+            f.render_widget(sparkline, chunks[1]);
+        }
+    }
+}
+
+impl MinSize for QUICStreamsWidget {
+    fn min_size(&self, presentation: Presentation) -> Size {
+        match presentation {
+            // Borders, the rates and a two-row sparkline
+            Presentation::Full => Size::new(20, 5),
+            Presentation::Compact => Size::new(20, 3),
+            Presentation::Minimal => Size::new(0, 1),
+        }
+    }
+}
+
+/// QUIC Efficiency Widget - goodput and the share of sent bytes that were not retransmissions
+pub struct QUICEfficiencyWidget {
+    goodput: MetricsHistory<f64>,
+    current: DerivedMetrics,
+}
+
+impl QUICEfficiencyWidget {
+    pub fn new(max_points: usize) -> Self {
+        Self {
+            goodput: MetricsHistory::new(max_points),
+            current: DerivedMetrics::default(),
+        }
+    }
+
+    pub fn update(&mut self, derived: DerivedMetrics) {
+        if let Some(goodput) = derived.goodput {
+            self.goodput.push(goodput);
+        }
+        self.current = derived;
+    }
+
+    pub fn clear(&mut self) {
+        self.goodput.clear();
+        self.current = DerivedMetrics::default();
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default().borders(Borders::ALL).title("Efficiency");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // Efficiency gauge
+                Constraint::Length(1), // Goodput and error rate
+                Constraint::Min(0),    // Goodput sparkline
+            ])
+            .split(inner);
+
+        // Efficiency gauge: green above 95%, yellow above 85%
+        match self.current.efficiency_pct {
+            Some(pct) => {
+                let color = if pct > 95.0 {
+                    theme.success
+                } else if pct > 85.0 {
+                    theme.warning
+                } else {
+                    theme.error
+                };
+                let gauge = Gauge::default()
+                    .gauge_style(Style::default().fg(color))
+                    .ratio((pct / 100.0).clamp(0.0, 1.0))
+                    .label(format!("{}% efficient", format_value(pct, 1)));
+                f.render_widget(gauge, chunks[0]);
+            }
+            None => {
+                let waiting = Paragraph::new("Efficiency: N/A (no bytes_sent reported)")
+                    .style(Style::default().fg(theme.muted));
+                f.render_widget(waiting, chunks[0]);
+            }
+        }
+
+        let stats_text = format!(
+            "Goodput: {} | Errors/conn: {}",
+            self.current.goodput.map_or("N/A".to_string(), |v| format_rate(v, 2)),
+            self.current.error_rate.map_or("N/A".to_string(), |v| format_value(v, 2))
+        );
+        let stats = Paragraph::new(stats_text).style(Style::default().fg(theme.info));
+        f.render_widget(stats, chunks[1]);
+
+        if !self.goodput.is_empty() {
+            let sparkline = ScaledSeries::new(self.goodput.iter().copied())
+                .sparkline()
+                .style(Style::default().fg(theme.secondary));
+            f.render_widget(sparkline, chunks[2]);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Latency of several senders on one chart
+//!
+//! Each labelled source gets its own line, placed by capture time so
+//! senders reporting at different rates still line up. The x axis counts
+//! seconds back from the newest sample of any source.
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame,
+};
+
+use crate::metrics::{format_value, TimeSeriesData};
+use crate::theme::Theme;
+
+/// Color of the `index`th source, shared with the header's source list
+///
+/// Colors are reused in order when there are more sources.
+pub fn source_color(theme: &Theme, index: usize) -> Color {
+    let colors = [theme.primary, theme.secondary, theme.accent, theme.info, theme.error, theme.muted];
+    colors[index % colors.len()]
+}
+
+/// One latency line per source
+#[derive(Default)]
+pub struct LatencyOverlayWidget;
+
+impl LatencyOverlayWidget {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw `sources`, as `(label, series)` pairs in display order
+    pub fn render(&self, f: &mut Frame, area: Rect, sources: &[(&str, &TimeSeriesData)], theme: &Theme) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Latency by Source (ms)");
+
+        let Some(newest) = sources
+            .iter()
+            .filter_map(|(_, series)| series.timestamps.back())
+            .max()
+            .copied()
+        else {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new("No labelled sources yet...").style(Style::default().fg(theme.muted)),
+                inner,
+            );
+            return;
+        };
+
+        let lines: Vec<Vec<(f64, f64)>> = sources
+            .iter()
+            .map(|(_, series)| {
+                series
+                    .timestamps
+                    .iter()
+                    .zip(&series.latency)
+                    .filter(|(_, latency)| latency.is_finite())
+                    .map(|(at, &latency)| {
+                        let ago = (*at - newest).num_milliseconds() as f64 / 1000.0;
+                        (ago, latency)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let datasets: Vec<Dataset> = sources
+            .iter()
+            .zip(&lines)
+            .enumerate()
+            .map(|(i, ((name, _), points))| {
+                Dataset::default()
+                    .name(*name)
+                    .data(points)
+                    .style(Style::default().fg(source_color(theme, i)))
+                    .graph_type(GraphType::Line)
+                    .marker(Marker::Braille)
+            })
+            .collect();
+
+        let all = lines.iter().flatten();
+        let oldest = all.clone().map(|&(x, _)| x).fold(0.0f64, f64::min).min(-1.0);
+        let top = all.map(|&(_, y)| y).fold(0.0f64, f64::max);
+        let y_max = if top > 0.0 { top * 1.1 } else { 1.0 };
+
+        let label_style = Style::default().fg(theme.muted);
+        let x_labels = vec![
+            Span::styled(format!("{}s", format_value(oldest, 0)), label_style),
+            Span::styled("now", label_style),
+        ];
+        let y_labels: Vec<Span> = [0.0, y_max / 2.0, y_max]
+            .into_iter()
+            .map(|ms| Span::styled(format_value(ms, 1), label_style))
+            .collect();
+        let chart = Chart::new(datasets)
+            .block(block)
+            .x_axis(Axis::default().bounds([oldest, 0.0]).labels(x_labels))
+            .y_axis(Axis::default().bounds([0.0, y_max]).labels(y_labels));
+        f.render_widget(chart, area);
+    }
+}
@@ -0,0 +1,151 @@
+//! Sparkline bars scaled from floating-point samples
+//!
+//! Sparklines draw `u64` bars, so casting samples truncates every value
+//! below 1.0 to an empty bar and clamps negative ones to zero. A
+//! [`ScaledSeries`] maps the window's own min..max onto `0..=SCALE` instead,
+//! keeping the shape of the series, and keeps the real range for the title.
+//! Non-finite samples become gaps.
+
+use ratatui::widgets::Sparkline;
+
+use crate::metrics::format_value;
+use crate::units::{format_rate, Unit};
+
+/// Height of the tallest bar; the sparkline's `max`
+const SCALE: u64 = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaledSeries {
+    /// One bar per sample, `None` for a non-finite one
+    pub bars: Vec<Option<u64>>,
+    /// Smallest and largest finite sample, `None` without one
+    pub range: Option<(f64, f64)>,
+}
+
+impl ScaledSeries {
+    /// Scale `values` onto the bar range; a flat series draws at half height
+    pub fn new(values: impl IntoIterator<Item = f64>) -> Self {
+        let values: Vec<f64> = values.into_iter().collect();
+        let range = values
+            .iter()
+            .filter(|v| v.is_finite())
+            .fold(None, |range: Option<(f64, f64)>, &v| match range {
+                Some((min, max)) => Some((min.min(v), max.max(v))),
+                None => Some((v, v)),
+            });
+        let bars = values
+            .iter()
+            .map(|&v| {
+                let (min, max) = range?;
+                if !v.is_finite() {
+                    None
+                } else if max > min {
+                    Some(((v - min) / (max - min) * SCALE as f64).round() as u64)
+                } else {
+                    Some(SCALE / 2)
+                }
+            })
+            .collect();
+        Self { bars, range }
+    }
+
+    /// `name 0.02–1.80%`, or `name 1.00%` for a flat series, or just `name` without samples
+    pub fn title(&self, name: &str, precision: usize, unit: &str) -> String {
+        match self.range {
+            Some((min, max)) if max > min => format!(
+                "{} {}–{}{}",
+                name,
+                format_value(min, precision),
+                format_value(max, precision),
+                unit
+            ),
+            Some((value, _)) => format!("{} {}{}", name, format_value(value, precision), unit),
+            None => name.to_string(),
+        }
+    }
+
+    /// `name 600.00–940.00 Mbps` for a series in bits per second, both ends in the unit of the larger
+    pub fn rate_title(&self, name: &str) -> String {
+        match self.range {
+            Some((min, max)) if max > min => {
+                let unit = Unit::auto(max.abs().max(min.abs()));
+                format!(
+                    "{} {}–{} {}",
+                    name,
+                    format_value(unit.from_bps(min), 2),
+                    format_value(unit.from_bps(max), 2),
+                    unit
+                )
+            }
+            Some((value, _)) => format!("{} {}", name, format_rate(value, 2)),
+            None => name.to_string(),
+        }
+    }
+
+    /// The bars as a sparkline, with the style and block left to the caller
+    pub fn sparkline(&self) -> Sparkline<'static> {
+        Sparkline::default().data(self.bars.clone()).max(SCALE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    /// The columns a two-line sparkline draws, top to bottom, one per sample
+    fn drawn(series: &ScaledSeries) -> Vec<String> {
+        let width = series.bars.len() as u16;
+        let mut terminal = Terminal::new(TestBackend::new(width, 2)).unwrap();
+        terminal.draw(|f| f.render_widget(series.sparkline(), f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..width).map(|x| buffer[(x, 0)].symbol().to_string() + buffer[(x, 1)].symbol()).collect()
+    }
+
+    #[test]
+    fn fractional_samples_keep_their_shape() {
+        let loss = [0.02, 0.5, 1.8, 0.9, 0.02];
+        let series = ScaledSeries::new(loss);
+        assert_eq!(series.bars, [Some(0), Some(270), Some(1000), Some(494), Some(0)]);
+        assert_eq!(series.range, Some((0.02, 1.8)));
+        assert_eq!(series.title("Loss", 2, "%"), "Loss 0.02–1.80%");
+
+        // Casting would have drawn all but one of these as empty bars
+        let columns = drawn(&series);
+        assert_eq!(columns[0], columns[4]);
+        assert!(columns[0] != columns[1] && columns[1] != columns[2] && columns[2] != columns[3], "{:?}", columns);
+    }
+
+    #[test]
+    fn negative_samples_scale_from_the_minimum() {
+        let deltas = [-3.0, -1.5, 0.0, 1.5, 3.0];
+        let series = ScaledSeries::new(deltas);
+        assert_eq!(series.bars, [Some(0), Some(250), Some(500), Some(750), Some(1000)]);
+        assert_eq!(series.title("Jitter Δ", 1, " ms"), "Jitter Δ -3.0–3.0 ms");
+
+        // Strictly rising bars, where a cast would have clamped half of them to zero
+        assert!(series.bars.windows(2).all(|pair| pair[0] < pair[1]));
+        let all_negative = ScaledSeries::new([-0.4, -0.2, -0.3]);
+        assert_eq!(all_negative.bars, [Some(0), Some(1000), Some(500)]);
+    }
+
+    #[test]
+    fn flat_empty_and_undefined_series() {
+        let flat = ScaledSeries::new([0.25; 4]);
+        assert_eq!(flat.bars, [Some(SCALE / 2); 4]);
+        assert_eq!(flat.title("Loss", 2, "%"), "Loss 0.25%");
+
+        let gappy = ScaledSeries::new([1.0, f64::NAN, 3.0, f64::INFINITY]);
+        assert_eq!(gappy.bars, [Some(0), None, Some(1000), None]);
+        assert_eq!(gappy.range, Some((1.0, 3.0)));
+
+        for empty in [ScaledSeries::new([]), ScaledSeries::new([f64::NAN, f64::NEG_INFINITY])] {
+            assert!(empty.bars.iter().all(Option::is_none));
+            assert_eq!(empty.range, None);
+            assert_eq!(empty.title("Loss", 2, "%"), "Loss");
+            assert_eq!(empty.rate_title("Throughput"), "Throughput");
+        }
+
+        assert_eq!(ScaledSeries::new([6e8, 9.4e8]).rate_title("Throughput"), "Throughput 600.00–940.00 Mbps");
+    }
+}
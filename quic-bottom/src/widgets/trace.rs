@@ -0,0 +1,125 @@
+//! Trace panel of the pinned connection
+//!
+//! Shows every field of the connection's latest sample, unset ones included,
+//! next to latency and congestion window sparklines drawn from its raw
+//! samples, one bar per sample, without the thinning the graphs apply.
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::metrics::format_span;
+use crate::theme::Theme;
+use crate::trace::ConnectionTrace;
+
+use super::ScaledSeries;
+
+/// Field dump and raw-resolution charts of a `ConnectionTrace`
+pub struct TracePanel;
+
+impl TracePanel {
+    pub fn render(f: &mut Frame, area: Rect, trace: &ConnectionTrace, theme: &Theme) {
+        let file = match trace.error() {
+            Some(error) => format!("file stopped: {}", error),
+            None => format!("{} lines to {}", trace.written(), trace.path().display()),
+        };
+        let age = (chrono::Utc::now() - trace.started_at()).to_std().unwrap_or_default();
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "Trace {} - {} samples in {} - {}",
+            trace.connection_id(),
+            trace.samples().len(),
+            format_span(age),
+            file
+        ));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let Some(latest) = trace.latest() else {
+            f.render_widget(
+                Paragraph::new("Waiting for the connection's next sample...").style(Style::default().fg(theme.muted)),
+                inner,
+            );
+            return;
+        };
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+            .split(inner);
+
+        f.render_widget(
+            Paragraph::new(field_lines(latest)).style(Style::default().fg(theme.text)),
+            columns[0],
+        );
+
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(columns[1]);
+        // The newest samples that fit, one bar each
+        let visible = usize::from(charts[0].width.saturating_sub(2));
+        let skip = trace.samples().len().saturating_sub(visible);
+        let recent = || trace.samples().iter().skip(skip);
+
+        let latency = ScaledSeries::new(recent().map(|sample| sample.latency));
+        f.render_widget(
+            latency
+                .sparkline()
+                .style(Style::default().fg(theme.primary))
+                .block(Block::default().borders(Borders::ALL).title(latency.title("Latency", 2, " ms"))),
+            charts[0],
+        );
+        let cwnd = ScaledSeries::new(recent().map(|sample| sample.congestion_window as f64 / 1024.0));
+        f.render_widget(
+            cwnd.sparkline()
+                .style(Style::default().fg(theme.info))
+                .block(Block::default().borders(Borders::ALL).title(cwnd.title("cwnd", 1, " KB"))),
+            charts[1],
+        );
+    }
+}
+
+/// `name: value` for every field of `sample`, flattening nested ones as `parent.name`
+fn field_lines(sample: &impl serde::Serialize) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if let Ok(value) = serde_json::to_value(sample) {
+        push_fields(&mut lines, "", &value);
+    }
+    lines
+}
+
+fn push_fields(lines: &mut Vec<Line<'static>>, prefix: &str, value: &Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                let name = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+                push_fields(lines, &name, value);
+            }
+        }
+        Value::Null => lines.push(Line::raw(format!("{}: -", prefix))),
+        Value::String(text) => lines.push(Line::raw(format!("{}: {}", prefix, text))),
+        value => lines.push(Line::raw(format!("{}: {}", prefix, value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::QUICMetrics;
+
+    #[test]
+    fn dumps_unset_and_nested_fields() {
+        let lines: Vec<String> = field_lines(&QUICMetrics::default())
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(lines.contains(&"latency: 0.0".to_string()));
+        assert!(lines.contains(&"packets_sent: -".to_string()));
+        assert!(lines.iter().any(|line| line.starts_with("timestamp: ")));
+    }
+}